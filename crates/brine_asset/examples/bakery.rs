@@ -1,9 +1,8 @@
 use std::path::{Path, PathBuf};
 
-use minecraft_assets::api::AssetPack;
 use tracing::*;
 
-use brine_asset::bakery::{self, models::ModelBakery};
+use brine_asset::bakery::{self, models::ModelBakery, AssetPacks};
 use brine_data::MinecraftData;
 
 fn cargo_workspace_relative_path(relative: impl AsRef<Path>) -> PathBuf {
@@ -21,14 +20,16 @@ fn main() {
         .init();
 
     let mc_data = MinecraftData::for_version("1.14.4");
-    let asset_pack = AssetPack::at_path(cargo_workspace_relative_path("../../assets/1.14.4"));
+    let asset_pack = AssetPacks::at_path(cargo_workspace_relative_path("../../assets/1.14.4"));
 
-    let baked_assets = bakery::bake_all(&mc_data, &asset_pack);
+    let (baked_assets, warnings) = bakery::bake_all(&mc_data, &asset_pack).unwrap();
+
+    info!("{} warnings", warnings.len());
 
     // println!("{:#?}", baked_assets);
 }
 
-fn print_a_few(mc_data: &MinecraftData, asset_pack: &AssetPack) {
+fn print_a_few(mc_data: &MinecraftData, asset_pack: &AssetPacks) {
     info!("Loading textures");
     let texture_table = bakery::textures::load_texture_table(&asset_pack).unwrap();
 
@@ -5,23 +5,29 @@ use std::{
     sync::Arc,
 };
 
-use minecraft_assets::api::{AssetPack, ResourcePath};
+use bevy_tasks::{Task, TaskPool};
+use minecraft_assets::api::ResourcePath;
 use tracing::*;
 
 pub use minecraft_assets::{api::Result, schemas::models::BlockFace};
 
 pub use brine_data::{
+    biomes::BiomeId,
     blocks::{BlockId, BlockStateId},
+    items::ItemId,
     MinecraftData, Version,
 };
 
 use crate::bakery::{
     self,
     block_states::BakedBlockStateTable,
-    models::BakedModelTable,
+    items::BakedItemTable,
+    models::{BakedModelTable, BakedQuad},
     textures::{TextureKey, TextureTable},
-    BakedAssets,
+    AssetPacks, AssetRoots, BakedAssets,
 };
+use crate::colormap::{Colormaps, TintTable};
+use crate::font::Font;
 
 /// Provides access to Minecraft assets for a given assets directory.
 ///
@@ -34,17 +40,67 @@ pub struct MinecraftAssets {
 }
 
 impl MinecraftAssets {
-    pub fn new(path: impl AsRef<Path>, data: &MinecraftData) -> Result<Self> {
-        let inner = MinecraftAssetsInner::build(path.as_ref(), data)?;
+    /// Loads the assets at `roots`, using previously-baked assets from the
+    /// [default cache directory][bakery::cache::default_cache_dir] if they
+    /// are still valid, and baking (and caching) them otherwise.
+    ///
+    /// `roots` is either the path of the base assets, or an [`AssetRoots`]
+    /// that layers resource packs on top of them.
+    pub fn new(roots: impl Into<AssetRoots>, data: &MinecraftData) -> Result<Self> {
+        Self::new_cached(roots, data, bakery::cache::default_cache_dir())
+    }
+
+    /// Like [`new`][Self::new], but keeps the baked assets in `cache_dir`.
+    pub fn new_cached(
+        roots: impl Into<AssetRoots>,
+        data: &MinecraftData,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let inner = MinecraftAssetsInner::build(roots.into(), data, Some(cache_dir.as_ref()))?;
 
         Ok(Self {
             inner: Arc::new(inner),
         })
     }
 
+    /// Like [`new`][Self::new], but loads the assets on `task_pool` instead of
+    /// blocking the calling thread.
+    ///
+    /// Pass Bevy's `ComputeTaskPool` (or `AsyncComputeTaskPool`) resource so
+    /// that the app can keep running, e.g. to show a loading screen, while the
+    /// assets are baked. The returned [`Task`] is a future that resolves to the
+    /// loaded assets; dropping it cancels the load.
+    pub fn new_async(
+        roots: impl Into<AssetRoots>,
+        data: &MinecraftData,
+        task_pool: &TaskPool,
+    ) -> Task<Result<Self>> {
+        let roots = roots.into();
+        let data = data.clone();
+
+        task_pool.spawn(async move { Self::new(roots, &data) })
+    }
+
+    /// Loads and bakes the assets at `roots` without touching the cache.
+    pub fn new_uncached(roots: impl Into<AssetRoots>, data: &MinecraftData) -> Result<Self> {
+        let inner = MinecraftAssetsInner::build(roots.into(), data, None)?;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Returns the root of the base assets.
     #[inline]
     pub fn root(&self) -> &Path {
-        &self.inner.root
+        self.inner.roots.base()
+    }
+
+    /// Returns the roots of the base assets and of every resource pack on top
+    /// of them.
+    #[inline]
+    pub fn roots(&self) -> &AssetRoots {
+        &self.inner.roots
     }
 
     #[inline]
@@ -52,6 +108,63 @@ impl MinecraftAssets {
         &self.inner.block_state_table
     }
 
+    /// Returns whether the block state glows, i.e., should be drawn at full
+    /// brightness regardless of the light around it.
+    #[inline]
+    pub fn is_emissive(&self, block_state_id: BlockStateId) -> bool {
+        self.block_states()
+            .get_by_key(block_state_id)
+            .map(|block_state| block_state.emissive)
+            .unwrap_or(false)
+    }
+
+    /// Iterates over the id of every baked block state.
+    #[inline]
+    pub fn all_block_state_ids(&self) -> impl Iterator<Item = BlockStateId> {
+        self.block_states().iter_keys()
+    }
+
+    /// Returns the model of every item, keyed by [`ItemId`].
+    ///
+    /// Items that are placed as blocks share the models of the block.
+    #[inline]
+    pub fn items(&self) -> &BakedItemTable {
+        &self.inner.item_table
+    }
+
+    /// Returns the default font, which is empty if the asset pack has none.
+    #[inline]
+    pub fn font(&self) -> &Font {
+        &self.inner.font
+    }
+
+    /// Returns the color that the block state's quads with the given
+    /// [`tint_index`][BakedQuad::tint_index] are multiplied by in the biome,
+    /// as sRGB from `0.0` to `1.0`, or `None` if they aren't tinted.
+    ///
+    /// Unknown biomes are treated like plains. See [`colormap`][crate::colormap]
+    /// for which blocks are tinted.
+    #[inline]
+    pub fn tint_for(
+        &self,
+        block_state_id: BlockStateId,
+        tint_index: u8,
+        biome_id: BiomeId,
+    ) -> Option<[f32; 3]> {
+        if tint_index != 0 {
+            return None;
+        }
+
+        let color = self.inner.tint_table.get(block_state_id, biome_id)?;
+
+        Some(color.map(|channel| channel as f32 / 255.0))
+    }
+
+    #[inline]
+    pub fn tints(&self) -> &TintTable {
+        &self.inner.tint_table
+    }
+
     #[inline]
     pub fn models(&self) -> &BakedModelTable {
         &self.inner.model_table
@@ -62,85 +175,596 @@ impl MinecraftAssets {
         &self.inner.texture_table
     }
 
-    #[inline]
+    /// Returns the path of a texture relative to the `assets` directory, for
+    /// loading with Bevy's `AssetServer`.
+    ///
+    /// The path is in whichever asset root the texture comes from (see
+    /// [`TextureTable::get_pack`]), so resource packs must be in the `assets`
+    /// directory, just like the base assets.
     pub fn get_texture_path(&self, texture_key: TextureKey) -> Option<PathBuf> {
         let texture_id = self.textures().get_by_key(texture_key)?;
+        let root = self.roots().get(self.textures().get_pack(texture_key)?)?;
 
-        let texture_path = ResourcePath::for_resource(&self.root(), texture_id);
+        let texture_path = ResourcePath::for_resource(root, texture_id);
 
         Some(texture_path.strip_prefix("assets").unwrap().into())
     }
 
-    // TODO: deprecate
-    pub fn get_texture_path_for_block_state_and_face(
+    /// Returns the quads of a block state's models that face the given
+    /// direction (see [`BakedQuad::facing`]).
+    ///
+    /// Quads whose `cull_face` is also `face`, i.e., those that lie on the
+    /// boundary of the block, come first.
+    pub fn get_quads_for_face(
+        &self,
+        block_state_id: BlockStateId,
+        face: BlockFace,
+    ) -> impl Iterator<Item = &BakedQuad> + '_ {
+        let quads = move || {
+            self.block_states()
+                .get_by_key(block_state_id)
+                .into_iter()
+                .flat_map(|baked_block_state| baked_block_state.iter_first_models())
+                .filter_map(move |model_key| self.models().get_by_key(model_key))
+                .flat_map(|model| model.quads.iter())
+                .filter(move |quad| quad.facing() == face)
+        };
+
+        let on_boundary = quads().filter(move |quad| quad.cull_face == Some(face));
+        let inside = quads().filter(move |quad| quad.cull_face != Some(face));
+
+        on_boundary.chain(inside)
+    }
+
+    /// Returns the path of the texture on the given face of a block state, as
+    /// determined by the first quad from [`get_quads_for_face`].
+    ///
+    /// [`get_quads_for_face`]: Self::get_quads_for_face
+    pub fn get_texture_path_for_face(
         &self,
         block_state_id: BlockStateId,
         face: BlockFace,
     ) -> Option<PathBuf> {
         trace!("Querying texture for {:?}:{:?}", block_state_id, face);
 
-        let baked_block_state = self.block_states().get_by_key(block_state_id).or_else(|| {
-            warn!("No block for {:?}", block_state_id);
-            None
-        })?;
+        let quad = self
+            .get_quads_for_face(block_state_id, face)
+            .next()
+            .or_else(|| {
+                debug!("{:?} has no quads facing {:?}", block_state_id, face);
+                None
+            })?;
 
-        if baked_block_state.models.len() > 1 {
-            debug!(
-                "{:?} is composed of multiple models, using the first one",
-                block_state_id
-            );
-        }
-
-        // TODO: pick random model from grab bag.
-        let model_key = baked_block_state.get_first_model().or_else(|| {
-            warn!("{:?} has no models!", block_state_id);
-            None
-        })?;
+        self.get_texture_path(quad.texture)
+    }
 
-        let model = self.models().get_by_key(model_key).or_else(|| {
-            warn!("No model with key {:?}", model_key);
-            None
-        })?;
+    /// Returns the texture of every quad in a block state's models, along with
+    /// the direction that the quad faces (see [`BakedQuad::facing`]).
+    ///
+    /// Unlike [`get_texture_path_for_face`], this doesn't stop at the first
+    /// quad of each face: a face made of several quads (e.g., the top of
+    /// stairs) yields the texture of each. Each `(face, texture)` pair is only
+    /// returned once, in the order that the quads appear in the models.
+    ///
+    /// [`get_texture_path_for_face`]: Self::get_texture_path_for_face
+    pub fn textures_for_block_state(
+        &self,
+        block_state_id: BlockStateId,
+    ) -> impl Iterator<Item = (BlockFace, TextureKey)> + '_ {
+        let mut textures: Vec<(BlockFace, TextureKey)> = Vec::new();
 
-        let quad = model.quads.iter().find(|quad| {
-            quad.cull_face
-                .map(|cull_face| cull_face == face)
-                .unwrap_or(false)
-        })?;
+        let quads = self
+            .block_states()
+            .get_by_key(block_state_id)
+            .into_iter()
+            .flat_map(|baked_block_state| baked_block_state.iter_first_models())
+            .filter_map(|model_key| self.models().get_by_key(model_key))
+            .flat_map(|model| model.quads.iter());
 
-        let texture_key = quad.texture;
+        for quad in quads {
+            let entry = (quad.facing(), quad.texture);
+            if !textures.contains(&entry) {
+                textures.push(entry);
+            }
+        }
 
-        let texture_path = self.get_texture_path(texture_key).unwrap();
+        textures.into_iter()
+    }
 
-        Some(texture_path)
+    #[deprecated(note = "use `get_texture_path_for_face` or `textures_for_block_state` instead")]
+    pub fn get_texture_path_for_block_state_and_face(
+        &self,
+        block_state_id: BlockStateId,
+        face: BlockFace,
+    ) -> Option<PathBuf> {
+        self.get_texture_path_for_face(block_state_id, face)
     }
 }
 
 #[derive(Debug)]
 pub(crate) struct MinecraftAssetsInner {
-    pub(crate) root: PathBuf,
+    pub(crate) roots: AssetRoots,
     pub(crate) block_state_table: BakedBlockStateTable,
+    pub(crate) item_table: BakedItemTable,
+    pub(crate) font: Font,
     pub(crate) model_table: BakedModelTable,
     pub(crate) texture_table: TextureTable,
+    pub(crate) tint_table: TintTable,
 }
 
 impl MinecraftAssetsInner {
-    fn build(root: &Path, data: &MinecraftData) -> Result<Self> {
-        let assets = AssetPack::at_path(root);
+    fn build(roots: AssetRoots, data: &MinecraftData, cache_dir: Option<&Path>) -> Result<Self> {
+        let cached = cache_dir.and_then(|cache_dir| bakery::cache::load(cache_dir, &roots, data));
+
+        let baked = match cached {
+            Some(baked) => baked,
+            None => {
+                let start = std::time::Instant::now();
+
+                let assets = AssetPacks::new(&roots);
+                let (baked, warnings) = bakery::bake_all(data, &assets)?;
+
+                info!(
+                    "Baked assets in {:?} with {} warnings",
+                    start.elapsed(),
+                    warnings.len()
+                );
 
+                if let Some(cache_dir) = cache_dir {
+                    if let Err(e) = bakery::cache::store(cache_dir, &roots, data, &baked) {
+                        warn!("Failed to save baked assets to cache: {}", e);
+                    }
+                }
+
+                baked
+            }
+        };
+
+        Ok(Self::from_baked(roots, data, baked))
+    }
+
+    pub(crate) fn from_baked(roots: AssetRoots, data: &MinecraftData, baked: BakedAssets) -> Self {
         let BakedAssets {
             block_states,
+            items,
             models,
             textures,
-        } = bakery::bake_all(data, &assets)?;
+        } = baked;
 
-        let new = Self {
-            root: PathBuf::from(root),
+        let font = Font::load(roots.base(), &textures).unwrap_or_else(|e| {
+            debug!("No font loaded: {}", e);
+            Font::default()
+        });
+
+        let tint_table = TintTable::new(&Colormaps::load(&roots), data);
+
+        Self {
+            roots,
             block_state_table: block_states,
+            item_table: items,
+            font,
             model_table: models,
             texture_table: textures,
+            tint_table,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use minecraft_assets::api::ResourceIdentifier;
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::bakery::{
+        block_states::{BakedBlockState, BlockStateGrabBag},
+        models::BakedModel,
+    };
+
+    const ALL_FACES: [BlockFace; 6] = [
+        BlockFace::Down,
+        BlockFace::Up,
+        BlockFace::North,
+        BlockFace::South,
+        BlockFace::West,
+        BlockFace::East,
+    ];
+
+    fn normal(face: BlockFace) -> [f32; 3] {
+        match face {
+            BlockFace::Down => [0.0, -1.0, 0.0],
+            BlockFace::Up => [0.0, 1.0, 0.0],
+            BlockFace::North => [0.0, 0.0, -1.0],
+            BlockFace::South => [0.0, 0.0, 1.0],
+            BlockFace::West => [-1.0, 0.0, 0.0],
+            BlockFace::East => [1.0, 0.0, 0.0],
+        }
+    }
+
+    fn quad(facing: BlockFace, cull_face: Option<BlockFace>, texture: TextureKey) -> BakedQuad {
+        BakedQuad {
+            positions: Default::default(),
+            normal: normal(facing),
+            tex_coords: Default::default(),
+            texture,
+            face: facing,
+            cull_face,
+            tint_index: None,
+            shade: true,
+        }
+    }
+
+    struct TestAssets {
+        assets: MinecraftAssets,
+        textures: Vec<TextureKey>,
+    }
+
+    /// Builds assets where block state `n` consists of the `n`th model.
+    fn assets(texture_names: &[&'static str], models: Vec<BakedModel>) -> TestAssets {
+        let mut baked = BakedAssets::default();
+
+        let textures = texture_names
+            .iter()
+            .map(|name| baked.textures.insert(ResourceIdentifier::texture(name)))
+            .collect();
+
+        for model in models {
+            let is_full_cube = model.is_full_cube;
+            let model_key = baked.models.insert(model);
+            baked.block_states.insert(BakedBlockState {
+                is_full_cube,
+                models: smallvec![BlockStateGrabBag {
+                    choices: smallvec![model_key],
+                }],
+                fluid_overlay: None,
+                emissive: false,
+            });
+        }
+
+        TestAssets {
+            assets: MinecraftAssets {
+                inner: Arc::new(MinecraftAssetsInner::from_baked(
+                    AssetRoots::new("assets"),
+                    &MinecraftData::for_version("1.14.4"),
+                    baked,
+                )),
+            },
+            textures,
+        }
+    }
+
+    fn textures_for_face(
+        assets: &MinecraftAssets,
+        block_state_id: BlockStateId,
+        face: BlockFace,
+    ) -> Vec<TextureKey> {
+        assets
+            .get_quads_for_face(block_state_id, face)
+            .map(|quad| quad.texture)
+            .collect()
+    }
+
+    #[test]
+    fn grass_block() {
+        let TestAssets { assets, textures } = assets(
+            &["block/grass_block_top", "block/grass_block_side", "block/dirt"],
+            vec![BakedModel {
+                is_full_cube: true,
+                quads: ALL_FACES
+                    .into_iter()
+                    .map(|face| {
+                        let texture = match face {
+                            BlockFace::Up => 0,
+                            BlockFace::Down => 2,
+                            _ => 1,
+                        };
+                        quad(face, Some(face), TextureKey(texture))
+                    })
+                    .collect(),
+            }],
+        );
+        let grass_block = BlockStateId(0);
+
+        assert_eq!(
+            textures_for_face(&assets, grass_block, BlockFace::Up),
+            vec![textures[0]]
+        );
+        assert_eq!(
+            textures_for_face(&assets, grass_block, BlockFace::Down),
+            vec![textures[2]]
+        );
+        for side in [
+            BlockFace::North,
+            BlockFace::South,
+            BlockFace::West,
+            BlockFace::East,
+        ] {
+            assert_eq!(
+                textures_for_face(&assets, grass_block, side),
+                vec![textures[1]]
+            );
+        }
+    }
+
+    #[test]
+    fn grass_block_textures_for_block_state() {
+        let TestAssets { assets, textures } = assets(
+            &[
+                "block/grass_block_top",
+                "block/grass_block_side",
+                "block/dirt",
+            ],
+            vec![BakedModel {
+                is_full_cube: true,
+                quads: ALL_FACES
+                    .into_iter()
+                    .map(|face| {
+                        let texture = match face {
+                            BlockFace::Up => 0,
+                            BlockFace::Down => 2,
+                            _ => 1,
+                        };
+                        quad(face, Some(face), TextureKey(texture))
+                    })
+                    .collect(),
+            }],
+        );
+
+        assert_eq!(
+            assets
+                .textures_for_block_state(BlockStateId(0))
+                .collect::<Vec<_>>(),
+            vec![
+                (BlockFace::Down, textures[2]),
+                (BlockFace::Up, textures[0]),
+                (BlockFace::North, textures[1]),
+                (BlockFace::South, textures[1]),
+                (BlockFace::West, textures[1]),
+                (BlockFace::East, textures[1]),
+            ]
+        );
+        assert_eq!(
+            assets.textures_for_block_state(BlockStateId(1)).next(),
+            None
+        );
+    }
+
+    #[test]
+    fn torch_has_no_cull_faces() {
+        let TestAssets { assets, textures } = assets(
+            &["block/torch"],
+            vec![BakedModel {
+                is_full_cube: false,
+                quads: ALL_FACES
+                    .into_iter()
+                    .map(|face| quad(face, None, TextureKey(0)))
+                    .collect(),
+            }],
+        );
+        let torch = BlockStateId(0);
+
+        for face in ALL_FACES {
+            assert_eq!(textures_for_face(&assets, torch, face), vec![textures[0]]);
+            assert_eq!(
+                assets.get_texture_path_for_face(torch, face),
+                assets.get_texture_path(textures[0])
+            );
+            assert!(assets.get_texture_path_for_face(torch, face).is_some());
+        }
+    }
+
+    #[test]
+    fn stairs() {
+        // A bottom slab plus a step on its north half. The top of the slab and
+        // the south side of the step are inside the block, so they have no
+        // cull face.
+        let mut quads: smallvec::SmallVec<[BakedQuad; 6]> = ALL_FACES
+            .into_iter()
+            .map(|face| {
+                let cull_face = (face != BlockFace::Up).then(|| face);
+                quad(face, cull_face, TextureKey(0))
+            })
+            .collect();
+        quads.extend([
+            quad(BlockFace::Up, Some(BlockFace::Up), TextureKey(1)),
+            quad(BlockFace::North, Some(BlockFace::North), TextureKey(1)),
+            quad(BlockFace::South, None, TextureKey(1)),
+            quad(BlockFace::West, Some(BlockFace::West), TextureKey(1)),
+            quad(BlockFace::East, Some(BlockFace::East), TextureKey(1)),
+        ]);
+
+        let TestAssets { assets, textures } = assets(
+            &["block/oak_planks", "block/stripped_oak_log"],
+            vec![BakedModel {
+                is_full_cube: false,
+                quads,
+            }],
+        );
+        let stairs = BlockStateId(0);
+
+        // Quads on the boundary of the block come first.
+        assert_eq!(
+            textures_for_face(&assets, stairs, BlockFace::Up),
+            vec![textures[1], textures[0]]
+        );
+        assert_eq!(
+            textures_for_face(&assets, stairs, BlockFace::South),
+            vec![textures[0], textures[1]]
+        );
+        assert_eq!(
+            textures_for_face(&assets, stairs, BlockFace::Down),
+            vec![textures[0]]
+        );
+        assert_eq!(
+            assets.get_texture_path_for_face(stairs, BlockFace::Up),
+            assets.get_texture_path(textures[1])
+        );
+
+        // Both textures on the top and south side are included.
+        let all: Vec<_> = assets.textures_for_block_state(stairs).collect();
+        assert_eq!(all.len(), 6 + 5);
+        assert!(all.contains(&(BlockFace::Up, textures[0])));
+        assert!(all.contains(&(BlockFace::Up, textures[1])));
+        assert!(all.contains(&(BlockFace::South, textures[0])));
+        assert!(all.contains(&(BlockFace::South, textures[1])));
+    }
+
+    /// Writes a tiny asset pack containing only stone to a fresh directory.
+    fn write_stone_asset_pack(dir: &Path) {
+        let minecraft = dir.join("assets/minecraft");
+        let files = [
+            (
+                "blockstates/stone.json",
+                r#"{ "variants": { "": { "model": "block/stone" } } }"#,
+            ),
+            (
+                "models/block/stone.json",
+                r##"{
+                    "textures": { "all": "block/stone" },
+                    "elements": [{
+                        "from": [0, 0, 0],
+                        "to": [16, 16, 16],
+                        "faces": {
+                            "down":  { "texture": "#all", "cullface": "down" },
+                            "up":    { "texture": "#all", "cullface": "up" },
+                            "north": { "texture": "#all", "cullface": "north" },
+                            "south": { "texture": "#all", "cullface": "south" },
+                            "west":  { "texture": "#all", "cullface": "west" },
+                            "east":  { "texture": "#all", "cullface": "east" }
+                        }
+                    }]
+                }"##,
+            ),
+            ("textures/block/stone.png", ""),
+        ];
+
+        for (path, contents) in files {
+            let path = minecraft.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn cached_assets_match_fresh_bake() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_cache_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let assets_dir = dir.join("assets_root");
+        let cache_dir = dir.join("cache");
+        write_stone_asset_pack(&assets_dir);
+
+        let data = MinecraftData::for_version("1.14.4");
+
+        let fresh = MinecraftAssets::new_uncached(&assets_dir, &data).unwrap();
+        let first = MinecraftAssets::new_cached(&assets_dir, &data, &cache_dir).unwrap();
+
+        let key = bakery::cache::cache_key(&AssetRoots::new(&assets_dir), &data).unwrap();
+        assert!(bakery::cache::cache_file_path(&cache_dir, &data, key).is_file());
+
+        let second = MinecraftAssets::new_cached(&assets_dir, &data, &cache_dir).unwrap();
+
+        for assets in [&first, &second] {
+            assert_eq!(assets.block_states(), fresh.block_states());
+            assert_eq!(assets.items(), fresh.items());
+            assert_eq!(assets.models(), fresh.models());
+            assert_eq!(assets.textures(), fresh.textures());
+        }
+        assert!(!fresh.models().models.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn async_assets_match_sync_assets() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_async_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let assets_dir = dir.join("assets_root");
+        write_stone_asset_pack(&assets_dir);
+
+        let data = MinecraftData::for_version("1.14.4");
+        let task_pool = TaskPool::new();
+
+        let sync = MinecraftAssets::new_uncached(&assets_dir, &data).unwrap();
+        let task = MinecraftAssets::new_async(&assets_dir, &data, &task_pool);
+        let async_ = futures_lite::future::block_on(task).unwrap();
+
+        assert_eq!(async_.root(), sync.root());
+        assert_eq!(async_.block_states(), sync.block_states());
+        assert_eq!(async_.items(), sync.items());
+        assert_eq!(async_.models(), sync.models());
+        assert_eq!(async_.textures(), sync.textures());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_block_state_has_no_quads() {
+        let TestAssets { assets, .. } = assets(&[], vec![]);
+
+        assert_eq!(
+            assets.get_quads_for_face(BlockStateId(7), BlockFace::Up).next(),
+            None
+        );
+        assert_eq!(assets.get_texture_path_for_face(BlockStateId(7), BlockFace::Up), None);
+    }
+
+    #[test]
+    fn texture_path_is_in_its_pack() {
+        let mut baked = BakedAssets::default();
+        let dirt = baked
+            .textures
+            .insert(ResourceIdentifier::texture("block/dirt"));
+        let stone = baked
+            .textures
+            .insert_from_pack(ResourceIdentifier::texture("block/stone"), 1);
+
+        let roots = AssetRoots::new("assets/1.14.4").with_pack("assets/packs/hd");
+        let assets = MinecraftAssets {
+            inner: Arc::new(MinecraftAssetsInner::from_baked(
+                roots,
+                &MinecraftData::for_version("1.14.4"),
+                baked,
+            )),
         };
 
-        Ok(new)
+        assert!(assets.get_texture_path(dirt).unwrap().starts_with("1.14.4"));
+        assert!(assets
+            .get_texture_path(stone)
+            .unwrap()
+            .starts_with("packs/hd"));
+        assert!(assets
+            .get_texture_path(stone)
+            .unwrap()
+            .ends_with("textures/block/stone.png"));
+    }
+
+    #[test]
+    fn tint_for() {
+        let TestAssets { assets, .. } = assets(&[], vec![]);
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+        let biomes = data.biomes();
+
+        let grass_block = blocks.get_state_id("grass_block", &[]).unwrap();
+        let water = blocks.get_state_id("water", &[]).unwrap();
+        let stone = blocks.get_state_id("stone", &[]).unwrap();
+        let plains = biomes.get_by_name("plains").unwrap().id;
+        let swamp = biomes.get_by_name("swamp").unwrap().id;
+
+        let rgb = |[r, g, b]: [u8; 3]| Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]);
+
+        // There are no colormaps, so the default grass color is used.
+        assert_eq!(
+            assets.tint_for(grass_block, 0, plains),
+            rgb(crate::colormap::DEFAULT_GRASS_COLOR)
+        );
+        assert_eq!(assets.tint_for(grass_block, 1, plains), None);
+
+        assert_eq!(assets.tint_for(water, 0, plains), rgb([0x3f, 0x76, 0xe4]));
+        assert_eq!(assets.tint_for(water, 0, swamp), rgb([0x61, 0x7b, 0x64]));
+
+        assert_eq!(assets.tint_for(stone, 0, plains), None);
     }
 }
@@ -1,128 +1,637 @@
-use minecraft_assets::api::{AssetPack, Result};
+use std::time::Instant;
+
+use minecraft_assets::api::{ResourceIdentifier, Result};
 
 use rayon::prelude::*;
-use smallvec::SmallVec;
+use serde::{Deserialize, Serialize};
 use tracing::*;
 
 use brine_data::{BlockStateId, MinecraftData};
 
 use crate::bakery::{
     self,
-    block_states::{
-        BakedBlockState, BakedBlockStateTable, BlockStateGrabBag, BlockStatesBakery,
-        HalfBakedBlockState, HalfBakedGrabBagChoice,
-    },
-    models::{BakedModelTable, ModelBakery},
+    block_states::{BakedBlockState, BakedBlockStateTable, BlockStatesBakery},
+    items::{BakedItemTable, ItemsBakery},
+    models::{bake_water_overlay, BakedModelTable, ModelBakery, WATER_TEXTURE},
+    packs::AssetPacks,
     textures::TextureTable,
+    warnings::{BakeWarning, BakeWarnings},
 };
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BakedAssets {
     pub block_states: BakedBlockStateTable,
+    pub items: BakedItemTable,
     pub models: BakedModelTable,
     pub textures: TextureTable,
 }
 
-pub fn bake_all(mc_data: &MinecraftData, asset_pack: &AssetPack) -> Result<BakedAssets> {
+/// Bakes every block state and item in the asset packs, along with the models
+/// and textures they use.
+///
+/// Each resource comes from the highest-priority pack that has it (see
+/// [`AssetPacks`]).
+///
+/// Anything that can't be baked is left out. Each problem is logged as it is
+/// found, and also returned, sorted, alongside the baked assets.
+pub fn bake_all(
+    mc_data: &MinecraftData,
+    asset_pack: &AssetPacks,
+) -> Result<(BakedAssets, Vec<BakeWarning>)> {
+    let warnings = BakeWarnings::new();
+
     let texture_table = bakery::textures::load_texture_table(asset_pack)?;
 
     let unbaked_models = bakery::models::load_unbaked_block_models(asset_pack)?;
-    let model_bakery = ModelBakery::new(&unbaked_models, &texture_table);
+    let model_bakery = ModelBakery::new(&unbaked_models, &texture_table).with_warnings(&warnings);
 
     let unbaked_block_states = bakery::block_states::load_unbaked_block_states(asset_pack)?;
-    let block_states_bakery = BlockStatesBakery::new(mc_data, &unbaked_block_states, model_bakery);
 
-    // (Half-)Bake block states in parallel.
-    let half_baked_block_states: Vec<(BlockStateId, HalfBakedBlockState)> = unbaked_block_states
-        .par_iter()
-        .map(|(key, _)| key)
+    // Bake every model referenced by a block state in parallel. Keys are
+    // assigned in order of model name afterwards, so they are deterministic.
+    let start = Instant::now();
+    let model_references = bakery::block_states::collect_model_references(&unbaked_block_states);
+    let (mut baked_models, model_keys) = model_bakery.bake_models(&model_references);
+
+    // Waterlogged block states get an extra model for the water in them.
+    let water_overlay = texture_table
+        .get_key(&ResourceIdentifier::texture(WATER_TEXTURE))
+        .map(|texture| baked_models.insert(bake_water_overlay(texture)));
+    if water_overlay.is_none() {
+        warnings.push(BakeWarning::MissingFluidTexture(WATER_TEXTURE.to_string()));
+    }
+
+    debug!(
+        "Baked {} models in {:?}",
+        baked_models.models.len(),
+        start.elapsed()
+    );
+
+    // Bake block states in parallel. Every block state is written to the slot
+    // for its id, so the order in which they finish does not matter.
+    let start = Instant::now();
+    let mut block_states_bakery =
+        BlockStatesBakery::new(mc_data, &unbaked_block_states, &model_keys, &baked_models)
+            .with_warnings(&warnings);
+    if let Some(water_overlay) = water_overlay {
+        block_states_bakery = block_states_bakery.with_water_overlay(water_overlay);
+    }
+
+    let mut block_names: Vec<&String> = unbaked_block_states.keys().collect();
+    block_names.sort();
+
+    let baked_block_states_by_id: Vec<(BlockStateId, BakedBlockState)> = block_names
+        .into_par_iter()
         .flat_map(|block_name| block_states_bakery.bake_block_states_for_block(block_name))
         .collect();
 
-    debug!("Finished half-baking block states");
-    // trace!(
-    //     "Half-baked block states: {:#?}",
-    //     &half_baked_block_states[0..100]
-    // );
-
-    let max_block_state_id = half_baked_block_states
+    let max_block_state_id = baked_block_states_by_id
         .iter()
         .map(|(block_state_id, _)| *block_state_id)
         .max()
         .unwrap();
 
-    let num_models: usize = half_baked_block_states
-        .iter()
-        .flat_map(|(_id, block_state)| {
-            block_state
-                .models
-                .iter()
-                .map(|grab_bag| grab_bag.choices.len())
-        })
-        .sum();
-
     let mut baked_block_states =
         vec![BakedBlockState::default(); max_block_state_id.0 as usize + 1];
 
-    let mut baked_models = BakedModelTable {
-        models: Vec::with_capacity(num_models),
+    for (block_state_id, baked_block_state) in baked_block_states_by_id.into_iter() {
+        baked_block_states[block_state_id.0 as usize] = baked_block_state;
+    }
+
+    debug!(
+        "Baked {} block states in {:?}",
+        baked_block_states.len(),
+        start.elapsed()
+    );
+
+    // Bake items after block states, so that items can reuse the models baked
+    // for blocks.
+    let start = Instant::now();
+    let unbaked_item_models = bakery::models::load_unbaked_item_models(asset_pack)?;
+    let baked_items = ItemsBakery::new(&unbaked_item_models, &texture_table)
+        .with_warnings(&warnings)
+        .bake_items(mc_data, &model_bakery, &model_keys, &mut baked_models);
+
+    debug!(
+        "Baked {} item models in {:?}",
+        baked_items.len(),
+        start.elapsed()
+    );
+
+    let baked_assets = BakedAssets {
+        block_states: BakedBlockStateTable {
+            block_states: baked_block_states,
+        },
+        items: baked_items,
+        models: baked_models,
+        textures: texture_table,
     };
 
-    // Turn half-baked block states into fully-baked block states.
-    for (block_state_id, half_baked_block_state) in half_baked_block_states.into_iter() {
-        trace!("{:?}", block_state_id);
+    Ok((baked_assets, warnings.into_vec()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
 
-        // The block state is a full cube if all of its models are full cubes.
-        let is_full_cube = half_baked_block_state.models.iter().all(|grab_bag| {
-            grab_bag
-                .choices
-                .iter()
-                .all(|choice| choice.model.is_full_cube)
-        });
+    use minecraft_assets::schemas::models::BlockFace;
+
+    use super::*;
+    use crate::bakery::AssetRoots;
+
+    /// Writes each of `files` to an asset pack in `dir`, relative to its
+    /// `assets/minecraft` directory.
+    fn write_files(dir: &Path, files: &[(&str, &str)]) {
+        for (path, contents) in files {
+            let path = dir.join("assets/minecraft").join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+    }
+
+    /// Writes an asset pack containing only stone, whose top face refers to a
+    /// texture variable that isn't defined.
+    fn write_asset_pack(dir: &Path) {
+        write_files(
+            dir,
+            &[
+                (
+                    "blockstates/stone.json",
+                    r#"{ "variants": { "": { "model": "block/stone" } } }"#,
+                ),
+                (
+                    "models/block/stone.json",
+                    r##"{
+                        "textures": { "all": "block/stone" },
+                        "elements": [{
+                            "from": [0, 0, 0],
+                            "to": [16, 16, 16],
+                            "faces": {
+                                "down":  { "texture": "#all", "cullface": "down" },
+                                "up":    { "texture": "#top", "cullface": "up" },
+                                "north": { "texture": "#all", "cullface": "north" },
+                                "south": { "texture": "#all", "cullface": "south" },
+                                "west":  { "texture": "#all", "cullface": "west" },
+                                "east":  { "texture": "#all", "cullface": "east" }
+                            }
+                        }]
+                    }"##,
+                ),
+                ("textures/block/stone.png", ""),
+                ("textures/block/water_still.png", ""),
+            ],
+        );
+    }
+
+    #[test]
+    fn dangling_texture_reference_is_collected() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_warnings_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_asset_pack(&dir);
 
-        let baked_grab_bags = half_baked_block_state
+        let mc_data = MinecraftData::for_version("1.14.4");
+        let (baked, warnings) = bake_all(&mc_data, &AssetPacks::at_path(&dir)).unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![BakeWarning::UnresolvedTexture {
+                model: "block/stone".to_string(),
+                var: "top".to_string(),
+            }]
+        );
+
+        // The rest of the model is still baked.
+        assert!(baked
             .models
-            .into_iter()
-            .map(|half_baked_grab_bag| {
-                let mut choices = SmallVec::new();
-
-                for HalfBakedGrabBagChoice { model, weight } in
-                    half_baked_grab_bag.choices.into_iter()
-                {
-                    let model_key = baked_models.insert(model);
-                    for _ in 0..weight {
-                        choices.push(model_key);
-                    }
-                }
+            .models
+            .iter()
+            .any(|model| model.quads.len() == 5));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn every_block_state_id_is_baked() {
+        let dir = std::env::temp_dir().join(format!("brine_asset_ids_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Baking the block with the highest state ids fills in the table up to
+        // the last block state of the version.
+        let mc_data = MinecraftData::for_version("1.14.4");
+        let last_block = mc_data.blocks().iter_blocks().last().unwrap();
+        let (max_state_id, _) = mc_data
+            .blocks()
+            .iter_states_for_block(brine_data::BlockId(last_block.id))
+            .unwrap()
+            .last()
+            .unwrap();
+
+        let block_states_path = format!("blockstates/{}.json", last_block.name);
+        write_files(
+            &dir,
+            &[
+                (
+                    block_states_path.as_str(),
+                    r#"{ "variants": { "": { "model": "block/cube" } } }"#,
+                ),
+                ("models/block/cube.json", "{}"),
+                ("textures/block/stone.png", ""),
+            ],
+        );
+
+        let (baked, _) = bake_all(&mc_data, &AssetPacks::at_path(&dir)).unwrap();
+        let table = &baked.block_states;
+
+        assert_eq!(table.len(), max_state_id.0 as usize + 1);
+        assert_eq!(table.iter_keys().count(), table.len());
+        assert!(table
+            .iter_keys()
+            .zip(0..)
+            .all(|(key, index)| key == BlockStateId(index)));
 
-                BlockStateGrabBag { choices }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn baking_is_deterministic() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_rebake_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Every model is a different height, so models baked under the wrong
+        // key can be told apart.
+        let models = [
+            "glowstone",
+            "oak_fence_post",
+            "oak_fence_side",
+            "redstone_lamp",
+            "redstone_lamp_on",
+            "stone",
+        ];
+        let model_files: Vec<(String, String)> = models
+            .iter()
+            .enumerate()
+            .map(|(i, model)| {
+                let contents = format!(
+                    r##"{{
+                        "textures": {{ "all": "block/stone" }},
+                        "elements": [{{
+                            "from": [0, 0, 0],
+                            "to": [8, {}, 16],
+                            "faces": {{ "up": {{ "texture": "#all" }} }}
+                        }}]
+                    }}"##,
+                    i + 1
+                );
+                (format!("models/block/{}.json", model), contents)
             })
             .collect();
 
-        let baked_block_state = BakedBlockState {
-            models: baked_grab_bags,
-            is_full_cube,
+        let mut files = vec![
+            (
+                "blockstates/glowstone.json",
+                r#"{ "variants": { "": { "model": "block/glowstone" } } }"#,
+            ),
+            (
+                "blockstates/oak_fence.json",
+                r#"{
+                    "multipart": [
+                        { "apply": { "model": "block/oak_fence_post" } },
+                        {
+                            "when": { "north": "true" },
+                            "apply": { "model": "block/oak_fence_side", "uvlock": true }
+                        },
+                        {
+                            "when": { "east": "true" },
+                            "apply": { "model": "block/oak_fence_side", "y": 90, "uvlock": true }
+                        }
+                    ]
+                }"#,
+            ),
+            (
+                "blockstates/redstone_lamp.json",
+                r#"{
+                    "variants": {
+                        "lit=false": { "model": "block/redstone_lamp" },
+                        "lit=true": { "model": "block/redstone_lamp_on" }
+                    }
+                }"#,
+            ),
+            (
+                "blockstates/stone.json",
+                r#"{ "variants": { "": { "model": "block/stone" } } }"#,
+            ),
+            ("textures/block/stone.png", ""),
+            ("textures/block/water_still.png", ""),
+        ];
+        files.extend(
+            model_files
+                .iter()
+                .map(|(path, contents)| (path.as_str(), contents.as_str())),
+        );
+        write_files(&dir, &files);
+
+        // Bake once on a single thread, where models finish in order, and once
+        // on several threads, where they finish in whatever order they like.
+        let mc_data = MinecraftData::for_version("1.14.4");
+        let bake_with_threads = |num_threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap()
+                .install(|| bake_all(&mc_data, &AssetPacks::at_path(&dir)).unwrap())
         };
 
-        baked_block_states[block_state_id.0 as usize] = baked_block_state;
+        let (sequential, warnings) = bake_with_threads(1);
+        assert_eq!(warnings, vec![]);
+
+        let (parallel, _) = bake_with_threads(4);
+        assert_eq!(sequential, parallel);
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    debug!("Finished fully baking block states");
+    /// Writes an asset pack containing stone and a few item models that
+    /// derive from `builtin/generated` in different ways.
+    fn write_item_asset_pack(dir: &Path) {
+        write_files(
+            dir,
+            &[
+                (
+                    "blockstates/stone.json",
+                    r#"{ "variants": { "": { "model": "block/stone" } } }"#,
+                ),
+                (
+                    "models/block/stone.json",
+                    r##"{
+                        "textures": { "all": "block/stone" },
+                        "elements": [{
+                            "from": [0, 0, 0],
+                            "to": [16, 16, 16],
+                            "faces": {
+                                "down":  { "texture": "#all", "cullface": "down" },
+                                "up":    { "texture": "#all", "cullface": "up" },
+                                "north": { "texture": "#all", "cullface": "north" },
+                                "south": { "texture": "#all", "cullface": "south" },
+                                "west":  { "texture": "#all", "cullface": "west" },
+                                "east":  { "texture": "#all", "cullface": "east" }
+                            }
+                        }]
+                    }"##,
+                ),
+                (
+                    "models/item/generated.json",
+                    r#"{ "parent": "builtin/generated" }"#,
+                ),
+                (
+                    "models/item/handheld.json",
+                    r#"{ "parent": "item/generated" }"#,
+                ),
+                (
+                    "models/item/stick.json",
+                    r#"{ "parent": "item/handheld", "textures": { "layer0": "item/stick" } }"#,
+                ),
+                (
+                    "models/item/template_spawn_egg.json",
+                    r#"{
+                        "parent": "item/generated",
+                        "textures": {
+                            "layer0": "item/spawn_egg",
+                            "layer1": "item/spawn_egg_overlay"
+                        }
+                    }"#,
+                ),
+                (
+                    "models/item/bat_spawn_egg.json",
+                    r#"{ "parent": "item/template_spawn_egg" }"#,
+                ),
+                ("models/item/stone.json", r#"{ "parent": "block/stone" }"#),
+                ("textures/block/stone.png", ""),
+                ("textures/block/water_still.png", ""),
+                ("textures/item/stick.png", ""),
+                ("textures/item/spawn_egg.png", ""),
+                ("textures/item/spawn_egg_overlay.png", ""),
+            ],
+        );
+    }
 
-    // trace!(
-    //     "Fully baked: {:#?}",
-    //     baked_block_states
-    //         .iter()
-    //         .enumerate()
-    //         .filter(|(_index, baked_block_state)| !baked_block_state.models.is_empty())
-    //         .collect::<Vec<_>>()
-    // );
+    #[test]
+    fn item_models() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_items_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_item_asset_pack(&dir);
 
-    Ok(BakedAssets {
-        block_states: BakedBlockStateTable {
-            block_states: baked_block_states,
-        },
-        models: baked_models,
-        textures: texture_table,
-    })
+        let mc_data = MinecraftData::for_version("1.14.4");
+        let (baked, warnings) = bake_all(&mc_data, &AssetPacks::at_path(&dir)).unwrap();
+        assert_eq!(warnings, vec![]);
+
+        let item_model = |name: &str| {
+            let item = mc_data.items().get_by_name(name).unwrap();
+            let key = baked.items.get_model_for_item(item.id).unwrap();
+            baked.models.get_by_key(key).unwrap()
+        };
+        let texture = |name: &str| {
+            baked
+                .textures
+                .get_key(&ResourceIdentifier::texture(name))
+                .unwrap()
+        };
+
+        // A simple item is one quad with its texture.
+        let stick = item_model("stick");
+        assert_eq!(stick.quads.len(), 1);
+        assert_eq!(stick.quads[0].texture, texture("item/stick"));
+
+        // A layered item is one quad per layer, in order.
+        let spawn_egg = item_model("bat_spawn_egg");
+        assert_eq!(
+            spawn_egg
+                .quads
+                .iter()
+                .map(|quad| quad.texture)
+                .collect::<Vec<_>>(),
+            vec![texture("item/spawn_egg"), texture("item/spawn_egg_overlay")]
+        );
+
+        // A block item uses the model baked for the block.
+        let stone = mc_data.items().get_by_name("stone").unwrap();
+        let stone_state = mc_data.blocks().get_state_id("stone", &[]).unwrap();
+        assert_eq!(
+            baked.items.get_model_for_item(stone.id),
+            baked
+                .block_states
+                .get_by_key(stone_state)
+                .unwrap()
+                .get_first_model()
+        );
+
+        // Items without models in the asset pack have none.
+        let dirt = mc_data.items().get_by_name("dirt").unwrap();
+        assert_eq!(baked.items.get_model_for_item(dirt.id), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn grass_top_is_tinted() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_tint_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_files(
+            &dir,
+            &[
+                (
+                    "blockstates/grass_block.json",
+                    r#"{ "variants": { "": { "model": "block/grass_block" } } }"#,
+                ),
+                (
+                    "models/block/grass_block.json",
+                    r##"{
+                        "textures": {
+                            "top": "block/grass_block_top",
+                            "bottom": "block/dirt"
+                        },
+                        "elements": [{
+                            "from": [0, 0, 0],
+                            "to": [16, 16, 16],
+                            "faces": {
+                                "down": { "texture": "#bottom", "cullface": "down" },
+                                "up":   { "texture": "#top", "cullface": "up", "tintindex": 0 }
+                            }
+                        }]
+                    }"##,
+                ),
+                ("textures/block/grass_block_top.png", ""),
+                ("textures/block/dirt.png", ""),
+                ("textures/block/water_still.png", ""),
+            ],
+        );
+
+        let mc_data = MinecraftData::for_version("1.14.4");
+        let (baked, _) = bake_all(&mc_data, &AssetPacks::at_path(&dir)).unwrap();
+
+        let grass_block = mc_data.blocks().get_state_id("grass_block", &[]).unwrap();
+        let model = baked
+            .block_states
+            .get_by_key(grass_block)
+            .and_then(|block_state| block_state.get_first_model())
+            .and_then(|key| baked.models.get_by_key(key))
+            .unwrap();
+        let tint_index = |face: BlockFace| {
+            model
+                .quads
+                .iter()
+                .find(|quad| quad.face == face)
+                .map(|quad| quad.tint_index)
+                .unwrap()
+        };
+
+        assert_eq!(tint_index(BlockFace::Up), Some(0));
+        assert_eq!(tint_index(BlockFace::Down), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resource_pack_overrides_base_assets() {
+        let dir =
+            std::env::temp_dir().join(format!("brine_asset_packs_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let base = dir.join("base");
+        let pack = dir.join("pack");
+
+        write_files(
+            &base,
+            &[
+                (
+                    "blockstates/stone.json",
+                    r#"{ "variants": { "": { "model": "block/stone" } } }"#,
+                ),
+                (
+                    "models/block/stone.json",
+                    r##"{
+                        "textures": { "all": "block/stone" },
+                        "elements": [{
+                            "from": [0, 0, 0],
+                            "to": [16, 16, 16],
+                            "faces": {
+                                "down":  { "texture": "#all", "cullface": "down" },
+                                "up":    { "texture": "#all", "cullface": "up" },
+                                "north": { "texture": "#all", "cullface": "north" },
+                                "south": { "texture": "#all", "cullface": "south" },
+                                "west":  { "texture": "#all", "cullface": "west" },
+                                "east":  { "texture": "#all", "cullface": "east" }
+                            }
+                        }]
+                    }"##,
+                ),
+                ("textures/block/stone.png", "base"),
+                ("textures/block/water_still.png", ""),
+            ],
+        );
+
+        // The pack turns stone into a slab with its own top texture, and
+        // replaces the stone texture.
+        write_files(
+            &pack,
+            &[
+                (
+                    "models/block/stone.json",
+                    r##"{
+                        "textures": { "all": "block/stone", "top": "block/stone_top" },
+                        "elements": [{
+                            "from": [0, 0, 0],
+                            "to": [16, 8, 16],
+                            "faces": {
+                                "down":  { "texture": "#all", "cullface": "down" },
+                                "up":    { "texture": "#top" },
+                                "north": { "texture": "#all", "cullface": "north" },
+                                "south": { "texture": "#all", "cullface": "south" },
+                                "west":  { "texture": "#all", "cullface": "west" },
+                                "east":  { "texture": "#all", "cullface": "east" }
+                            }
+                        }]
+                    }"##,
+                ),
+                ("textures/block/stone.png", "pack"),
+                ("textures/block/stone_top.png", "pack"),
+            ],
+        );
+
+        let mc_data = MinecraftData::for_version("1.14.4");
+        let roots = AssetRoots::new(&base).with_pack(&pack);
+        let (baked, warnings) = bake_all(&mc_data, &AssetPacks::new(&roots)).unwrap();
+        assert_eq!(warnings, vec![]);
+
+        let texture = |name: &str| {
+            baked
+                .textures
+                .get_key(&ResourceIdentifier::texture(name))
+                .unwrap()
+        };
+        assert_eq!(baked.textures.get_pack(texture("block/stone")), Some(1));
+        assert_eq!(baked.textures.get_pack(texture("block/stone_top")), Some(1));
+        assert_eq!(
+            baked.textures.get_pack(texture("block/water_still")),
+            Some(0)
+        );
+
+        let stone = mc_data.blocks().get_state_id("stone", &[]).unwrap();
+        let model = baked
+            .block_states
+            .get_by_key(stone)
+            .and_then(|block_state| block_state.get_first_model())
+            .and_then(|key| baked.models.get_by_key(key))
+            .unwrap();
+        let quad = |face: BlockFace| model.quads.iter().find(|quad| quad.face == face).unwrap();
+
+        assert!(!model.is_full_cube);
+        assert_eq!(quad(BlockFace::Up).texture, texture("block/stone_top"));
+        assert_eq!(quad(BlockFace::Up).cull_face, None);
+        assert_eq!(quad(BlockFace::Down).texture, texture("block/stone"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
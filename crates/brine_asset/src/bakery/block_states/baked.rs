@@ -1,13 +1,26 @@
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
 use brine_data::BlockStateId;
 
 use crate::bakery::models::BakedModelKey;
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BakedBlockState {
     pub is_full_cube: bool,
     pub models: SmallVec<[BlockStateGrabBag; 1]>,
+
+    /// A fluid model to draw along with the block's own models, e.g., water
+    /// for a waterlogged block.
+    ///
+    /// This doesn't affect `is_full_cube`.
+    #[serde(default)]
+    pub fluid_overlay: Option<BakedModelKey>,
+
+    /// Whether the block glows, i.e., is drawn at full brightness regardless
+    /// of the light around it.
+    #[serde(default)]
+    pub emissive: bool,
 }
 
 impl BakedBlockState {
@@ -16,14 +29,32 @@ impl BakedBlockState {
             .first()
             .map(|grab_bag| *grab_bag.choices.first().unwrap())
     }
+
+    /// Returns the first model of each of the block state's grab bags.
+    ///
+    /// Block states defined with multipart definitions (fences, redstone wire,
+    /// etc.) have one grab bag per case that applies to them, and all of those
+    /// models need to be rendered together to draw the block.
+    // TODO: pick random model from each grab bag.
+    pub fn iter_first_models(&self) -> impl Iterator<Item = BakedModelKey> + '_ {
+        self.models
+            .iter()
+            .filter_map(|grab_bag| grab_bag.choices.first().copied())
+    }
+
+    /// Like [`iter_first_models`][Self::iter_first_models], followed by the
+    /// [`fluid_overlay`][Self::fluid_overlay] if there is one.
+    pub fn iter_first_models_with_overlay(&self) -> impl Iterator<Item = BakedModelKey> + '_ {
+        self.iter_first_models().chain(self.fluid_overlay)
+    }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BlockStateGrabBag {
     pub choices: SmallVec<[BakedModelKey; 1]>,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BakedBlockStateTable {
     /// Indexed by [`BlockStateId`].
     pub block_states: Vec<BakedBlockState>,
@@ -41,4 +72,20 @@ impl BakedBlockStateTable {
     pub fn get_by_key(&self, key: BlockStateId) -> Option<&BakedBlockState> {
         self.block_states.get(key.0 as usize)
     }
+
+    /// Returns the number of block states in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.block_states.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.block_states.is_empty()
+    }
+
+    /// Iterates over the id of every block state in the table, in order.
+    pub fn iter_keys(&self) -> impl Iterator<Item = BlockStateId> {
+        (0..self.block_states.len()).map(|index| BlockStateId(index as u16))
+    }
 }
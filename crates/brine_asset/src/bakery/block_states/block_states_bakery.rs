@@ -1,45 +1,125 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use minecraft_assets::schemas::blockstates::{
     multipart::{Case, StateValue as McStateValue},
     Variant,
 };
+use smallvec::SmallVec;
 use tracing::*;
 
 use brine_data::{blocks::StateValue, BlockId, BlockState, BlockStateId, MinecraftData};
 
 use crate::bakery::{
-    block_states::{
-        half_baked::{HalfBakedBlockStateGrabBag, HalfBakedGrabBagChoice},
-        model_cache::BakedModelCache,
-        HalfBakedBlockState, UnbakedBlockStatesTable,
-    },
-    models::ModelBakery,
+    block_states::{BakedBlockState, BlockStateGrabBag, UnbakedBlockStatesTable},
+    models::{BakedModelKey, BakedModelKeys, BakedModelTable, ModelReference},
+    warnings::{self, BakeWarning, BakeWarnings},
 };
 
+/// Blocks that are always drawn at full brightness.
+///
+/// Vanilla models don't say whether they glow, so this has to be decided by
+/// block name.
+const EMISSIVE_BLOCKS: &[&str] = &[
+    "beacon",
+    "end_gateway",
+    "end_portal",
+    "end_rod",
+    "fire",
+    "glowstone",
+    "jack_o_lantern",
+    "lantern",
+    "lava",
+    "magma_block",
+    "nether_portal",
+    "sea_lantern",
+    "torch",
+    "wall_torch",
+];
+
+/// Blocks that are drawn at full brightness only while they are `lit`.
+const LIT_EMISSIVE_BLOCKS: &[&str] = &[
+    "blast_furnace",
+    "campfire",
+    "furnace",
+    "redstone_lamp",
+    "redstone_ore",
+    "redstone_torch",
+    "redstone_wall_torch",
+    "smoker",
+];
+
+/// Returns whether the given state of the given block is
+/// [`emissive`][BakedBlockState::emissive].
+pub fn is_emissive(block_name: &str, block_state_properties: &BlockState) -> bool {
+    EMISSIVE_BLOCKS.contains(&block_name)
+        || (LIT_EMISSIVE_BLOCKS.contains(&block_name)
+            && block_state_properties.get("lit") == Some(&StateValue::Bool(true)))
+}
+
+/// Returns every model referenced by any of the given block states
+/// definitions, in sorted order.
+pub fn collect_model_references(
+    unbaked_block_states: &UnbakedBlockStatesTable,
+) -> BTreeSet<ModelReference> {
+    unbaked_block_states
+        .values()
+        .flat_map(|block_states_definition| block_states_definition.clone().into_multipart())
+        .flat_map(|case| {
+            case.apply
+                .models()
+                .iter()
+                .map(ModelReference::from)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Bakes block states out of models that have already been baked by
+/// [`ModelBakery::bake_models`][crate::bakery::models::ModelBakery::bake_models].
 pub struct BlockStatesBakery<'a> {
     mc_data: &'a MinecraftData,
     unbaked_block_states: &'a UnbakedBlockStatesTable,
-    model_bakery: ModelBakery<'a>,
+    model_keys: &'a BakedModelKeys,
+    baked_models: &'a BakedModelTable,
+    water_overlay: Option<BakedModelKey>,
+    warnings: Option<&'a BakeWarnings>,
 }
 
 impl<'a> BlockStatesBakery<'a> {
     pub fn new(
         mc_data: &'a MinecraftData,
         unbaked_block_states: &'a UnbakedBlockStatesTable,
-        model_bakery: ModelBakery<'a>,
+        model_keys: &'a BakedModelKeys,
+        baked_models: &'a BakedModelTable,
     ) -> Self {
         Self {
             mc_data,
             unbaked_block_states,
-            model_bakery,
+            model_keys,
+            baked_models,
+            water_overlay: None,
+            warnings: None,
         }
     }
 
+    /// Sets the model that is added to every waterlogged block state as its
+    /// [`fluid_overlay`][BakedBlockState::fluid_overlay].
+    pub fn with_water_overlay(mut self, water_overlay: BakedModelKey) -> Self {
+        self.water_overlay = Some(water_overlay);
+        self
+    }
+
+    /// Records the problems found while baking in `warnings`, in addition to
+    /// logging them.
+    pub fn with_warnings(mut self, warnings: &'a BakeWarnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
     pub fn bake_block_states_for_block(
         &self,
         block_name: &str,
-    ) -> Vec<(BlockStateId, HalfBakedBlockState)> {
+    ) -> Vec<(BlockStateId, BakedBlockState)> {
         debug!("Baking block states for block: {}", block_name);
 
         self.bake_block_states_for_block_inner(block_name)
@@ -49,18 +129,22 @@ impl<'a> BlockStatesBakery<'a> {
     fn bake_block_states_for_block_inner(
         &self,
         block_name: &str,
-    ) -> Option<Vec<(BlockStateId, HalfBakedBlockState)>> {
-        let mut model_cache = BakedModelCache::new(&self.model_bakery);
-
+    ) -> Option<Vec<(BlockStateId, BakedBlockState)>> {
         let block_states_definition = self.unbaked_block_states.get(block_name).or_else(|| {
-            warn!("No blockstates definition found for block {}", block_name);
+            warnings::report(
+                self.warnings,
+                BakeWarning::MissingBlockStates(block_name.to_string()),
+            );
             None
         })?;
 
         let multipart_cases = block_states_definition.clone().into_multipart();
 
         let block = self.mc_data.blocks().get_by_name(block_name).or_else(|| {
-            warn!("No block data for block {}", block_name);
+            warnings::report(
+                self.warnings,
+                BakeWarning::MissingBlockData(block_name.to_string()),
+            );
             None
         })?;
         trace!("Block: {:?}", block);
@@ -81,8 +165,11 @@ impl<'a> BlockStatesBakery<'a> {
                 .unwrap()
                 .map(|(block_state_id, block_with_state)| {
                     let block_state = block_with_state.state;
-                    let baked =
-                        self.bake_block_state(&multipart_cases[..], block_state, &mut model_cache);
+                    let emissive = is_emissive(block_name, &block_state);
+                    let baked = BakedBlockState {
+                        emissive,
+                        ..self.bake_block_state(&multipart_cases[..], block_state)
+                    };
                     (block_state_id, baked)
                 })
                 .collect(),
@@ -93,8 +180,7 @@ impl<'a> BlockStatesBakery<'a> {
         &self,
         multipart_cases: &'a [Case],
         block_state_properties: BlockState<'a>,
-        model_cache: &mut BakedModelCache<'_, 'a>,
-    ) -> HalfBakedBlockState {
+    ) -> BakedBlockState {
         // Convert to `minecraft_assets` types.
         let block_state_properties: HashMap<&str, McStateValue> = block_state_properties
             .iter()
@@ -120,32 +206,338 @@ impl<'a> BlockStatesBakery<'a> {
             })
             .map(|case| &case.apply);
 
-        let grab_bags = variants_that_apply
-            .map(|variant| self.bake_grab_bag_for_block_variant(variant, model_cache))
+        let grab_bags: SmallVec<[BlockStateGrabBag; 1]> = variants_that_apply
+            .map(|variant| self.bake_grab_bag_for_block_variant(variant))
             .collect();
 
-        HalfBakedBlockState { models: grab_bags }
+        // The block state is a full cube if all of its models are full cubes.
+        let is_full_cube = grab_bags.iter().all(|grab_bag| {
+            grab_bag.choices.iter().all(|model_key| {
+                self.baked_models
+                    .get_by_key(*model_key)
+                    .map(|model| model.is_full_cube)
+                    .unwrap_or(false)
+            })
+        });
+
+        let fluid_overlay = match block_state_properties.get("waterlogged") {
+            Some(McStateValue::Bool(true)) => self.water_overlay,
+            _ => None,
+        };
+
+        BakedBlockState {
+            models: grab_bags,
+            is_full_cube,
+            fluid_overlay,
+            emissive: false,
+        }
     }
 
-    pub fn bake_grab_bag_for_block_variant(
-        &self,
-        variant: &'a Variant,
-        model_cache: &mut BakedModelCache<'_, 'a>,
-    ) -> HalfBakedBlockStateGrabBag {
-        let choices = variant
-            .models()
+    pub fn bake_grab_bag_for_block_variant(&self, variant: &'a Variant) -> BlockStateGrabBag {
+        let mut choices = SmallVec::new();
+
+        for model_properties in variant.models().iter() {
+            if let Some(model_key) = self.model_keys.get_by_properties(model_properties) {
+                // Models are chosen with a probability proportional to their weight.
+                for _ in 0..model_properties.weight {
+                    choices.push(model_key);
+                }
+            }
+        }
+
+        BlockStateGrabBag { choices }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::bakery::{
+        models::{assign_model_keys, bake_water_overlay, BakedModel},
+        textures::TextureKey,
+    };
+
+    const OAK_FENCE: &str = r#"{
+        "multipart": [
+            { "apply": { "model": "block/oak_fence_post" } },
+            {
+                "when": { "north": "true" },
+                "apply": { "model": "block/oak_fence_side", "uvlock": true }
+            },
+            {
+                "when": { "east": "true" },
+                "apply": { "model": "block/oak_fence_side", "y": 90, "uvlock": true }
+            },
+            {
+                "when": { "south": "true" },
+                "apply": { "model": "block/oak_fence_side", "y": 180, "uvlock": true }
+            },
+            {
+                "when": { "west": "true" },
+                "apply": { "model": "block/oak_fence_side", "y": 270, "uvlock": true }
+            }
+        ]
+    }"#;
+
+    const REDSTONE_WIRE: &str = r#"{
+        "multipart": [
+            {
+                "when": { "OR": [
+                    { "north": "none", "east": "none", "south": "none", "west": "none" },
+                    { "north": "side|up", "east": "side|up" },
+                    { "east": "side|up", "south": "side|up" },
+                    { "south": "side|up", "west": "side|up" },
+                    { "west": "side|up", "north": "side|up" }
+                ]},
+                "apply": { "model": "block/redstone_dust_dot" }
+            },
+            {
+                "when": { "north": "side|up" },
+                "apply": { "model": "block/redstone_dust_side0" }
+            },
+            {
+                "when": { "south": "side|up" },
+                "apply": { "model": "block/redstone_dust_side_alt0" }
+            },
+            {
+                "when": { "east": "side|up" },
+                "apply": { "model": "block/redstone_dust_side_alt1", "y": 270 }
+            },
+            {
+                "when": { "west": "side|up" },
+                "apply": { "model": "block/redstone_dust_side1", "y": 270 }
+            },
+            {
+                "when": { "north": "up" },
+                "apply": { "model": "block/redstone_dust_up" }
+            },
+            {
+                "when": { "east": "up" },
+                "apply": { "model": "block/redstone_dust_up", "y": 90 }
+            },
+            {
+                "when": { "south": "up" },
+                "apply": { "model": "block/redstone_dust_up", "y": 180 }
+            },
+            {
+                "when": { "west": "up" },
+                "apply": { "model": "block/redstone_dust_up", "y": 270 }
+            }
+        ]
+    }"#;
+
+    const GLOWSTONE: &str = r#"{ "variants": { "": { "model": "block/glowstone" } } }"#;
+
+    const STONE: &str = r#"{ "variants": { "": { "model": "block/stone" } } }"#;
+
+    const REDSTONE_LAMP: &str = r#"{
+        "variants": {
+            "lit=false": { "model": "block/redstone_lamp" },
+            "lit=true": { "model": "block/redstone_lamp_on" }
+        }
+    }"#;
+
+    fn reference(model: &str, y: u32, uv_lock: bool) -> ModelReference {
+        ModelReference {
+            model: model.to_string(),
+            x: 0,
+            y,
+            uv_lock,
+        }
+    }
+
+    /// Bakes the given block state of a single block, using empty models for
+    /// everything the block's definition references.
+    fn bake(
+        block_name: &str,
+        definition: &str,
+        props: &[(&str, &str)],
+    ) -> (BakedBlockState, BakedModelKeys) {
+        let (baked, model_keys, _) = bake_with_water(block_name, definition, props);
+        (baked, model_keys)
+    }
+
+    /// Like [`bake`], but also gives the bakery a water overlay model.
+    fn bake_with_water(
+        block_name: &str,
+        definition: &str,
+        props: &[(&str, &str)],
+    ) -> (BakedBlockState, BakedModelKeys, BakedModelTable) {
+        let mc_data = MinecraftData::for_version("1.14.4");
+
+        let mut unbaked_block_states = UnbakedBlockStatesTable::default();
+        unbaked_block_states.insert(
+            block_name.to_string(),
+            serde_json::from_str(definition).unwrap(),
+        );
+
+        let references = collect_model_references(&unbaked_block_states);
+        let (mut baked_models, model_keys) = assign_model_keys(
+            references
+                .into_iter()
+                .map(|reference| (reference, BakedModel::default())),
+        );
+        let water_overlay = baked_models.insert(bake_water_overlay(TextureKey(0)));
+
+        let block_state_id = mc_data.blocks().get_state_id(block_name, props).unwrap();
+
+        let bakery =
+            BlockStatesBakery::new(&mc_data, &unbaked_block_states, &model_keys, &baked_models)
+                .with_water_overlay(water_overlay);
+        let (_, baked) = bakery
+            .bake_block_states_for_block(block_name)
+            .into_iter()
+            .find(|(id, _)| *id == block_state_id)
+            .unwrap();
+
+        (baked, model_keys, baked_models)
+    }
+
+    fn keys(model_keys: &BakedModelKeys, references: &[ModelReference]) -> Vec<BakedModelKey> {
+        references
             .iter()
-            .filter_map(|model_properties| {
-                let baked_model = model_cache.get_or_bake_model(model_properties)?;
-                let weight = model_properties.weight;
+            .map(|reference| model_keys.get(reference).unwrap())
+            .collect()
+    }
 
-                Some(HalfBakedGrabBagChoice {
-                    model: baked_model.clone(),
-                    weight,
-                })
-            })
-            .collect();
+    #[test]
+    fn fence_post_with_two_arms() {
+        let (baked, model_keys) = bake(
+            "oak_fence",
+            OAK_FENCE,
+            &[
+                ("north", "true"),
+                ("east", "true"),
+                ("south", "false"),
+                ("west", "false"),
+            ],
+        );
+
+        let expected = keys(
+            &model_keys,
+            &[
+                reference("block/oak_fence_post", 0, false),
+                reference("block/oak_fence_side", 0, true),
+                reference("block/oak_fence_side", 90, true),
+            ],
+        );
+
+        assert_eq!(baked.iter_first_models().collect::<Vec<_>>(), expected);
+        assert!(baked.models.iter().all(|grab_bag| grab_bag.choices.len() == 1));
+    }
+
+    #[test]
+    fn waterlogged_fence_has_water_overlay() {
+        let props = [
+            ("north", "true"),
+            ("east", "false"),
+            ("south", "false"),
+            ("west", "false"),
+        ];
+
+        let (baked, model_keys, baked_models) = bake_with_water(
+            "oak_fence",
+            OAK_FENCE,
+            &[&props[..], &[("waterlogged", "true")]].concat(),
+        );
+
+        let fence = keys(
+            &model_keys,
+            &[
+                reference("block/oak_fence_post", 0, false),
+                reference("block/oak_fence_side", 0, true),
+            ],
+        );
+        assert_eq!(baked.iter_first_models().collect::<Vec<_>>(), fence);
+
+        let water = baked.fluid_overlay.unwrap();
+        assert_eq!(
+            baked.iter_first_models_with_overlay().collect::<Vec<_>>(),
+            [&fence[..], &[water]].concat()
+        );
+        assert_eq!(baked_models.get_by_key(water).unwrap().quads.len(), 6);
+
+        // The same fence without water only has the fence models.
+        let (baked, _, _) = bake_with_water(
+            "oak_fence",
+            OAK_FENCE,
+            &[&props[..], &[("waterlogged", "false")]].concat(),
+        );
+        assert_eq!(baked.fluid_overlay, None);
+        assert_eq!(
+            baked.iter_first_models_with_overlay().collect::<Vec<_>>(),
+            fence
+        );
+    }
+
+    #[test]
+    fn glowstone_is_emissive() {
+        let (glowstone, _) = bake("glowstone", GLOWSTONE, &[]);
+        assert!(glowstone.emissive);
+
+        let (stone, _) = bake("stone", STONE, &[]);
+        assert!(!stone.emissive);
+    }
+
+    #[test]
+    fn redstone_lamp_is_emissive_while_lit() {
+        let (lit, _) = bake("redstone_lamp", REDSTONE_LAMP, &[("lit", "true")]);
+        assert!(lit.emissive);
+
+        let (unlit, _) = bake("redstone_lamp", REDSTONE_LAMP, &[("lit", "false")]);
+        assert!(!unlit.emissive);
+    }
+
+    #[test]
+    fn redstone_wire_corner() {
+        let (baked, model_keys) = bake(
+            "redstone_wire",
+            REDSTONE_WIRE,
+            &[
+                ("north", "side"),
+                ("east", "up"),
+                ("south", "none"),
+                ("west", "none"),
+            ],
+        );
+
+        // "side|up" matches both the north side and the east side running up
+        // the wall, and the OR condition puts a dot in the corner.
+        let expected = keys(
+            &model_keys,
+            &[
+                reference("block/redstone_dust_dot", 0, false),
+                reference("block/redstone_dust_side0", 0, false),
+                reference("block/redstone_dust_side_alt1", 270, false),
+                reference("block/redstone_dust_up", 90, false),
+            ],
+        );
+
+        assert_eq!(baked.iter_first_models().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn redstone_wire_straight_line_has_no_dot() {
+        let (baked, model_keys) = bake(
+            "redstone_wire",
+            REDSTONE_WIRE,
+            &[
+                ("north", "side"),
+                ("east", "none"),
+                ("south", "side"),
+                ("west", "none"),
+            ],
+        );
+
+        let expected = keys(
+            &model_keys,
+            &[
+                reference("block/redstone_dust_side0", 0, false),
+                reference("block/redstone_dust_side_alt0", 0, false),
+            ],
+        );
 
-        HalfBakedBlockStateGrabBag { choices }
+        assert_eq!(baked.iter_first_models().collect::<Vec<_>>(), expected);
     }
 }
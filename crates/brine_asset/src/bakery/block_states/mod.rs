@@ -1,10 +1,7 @@
 mod baked;
 mod block_states_bakery;
-mod half_baked;
-pub(crate) mod model_cache;
 mod unbaked;
 
 pub use baked::{BakedBlockState, BakedBlockStateTable, BlockStateGrabBag};
-pub use block_states_bakery::BlockStatesBakery;
-pub use half_baked::{HalfBakedBlockState, HalfBakedGrabBagChoice};
+pub use block_states_bakery::{collect_model_references, BlockStatesBakery};
 pub use unbaked::{load_unbaked_block_states, UnbakedBlockStatesTable};
@@ -1,18 +1,20 @@
 use std::collections::HashMap;
 
-use minecraft_assets::api::{AssetPack, ResourceKind, Result};
+use minecraft_assets::api::{ResourceKind, Result};
+
+use crate::bakery::packs::AssetPacks;
 
 pub type UnbakedBlockStates = minecraft_assets::schemas::blockstates::BlockStates;
 
 pub type UnbakedBlockStatesTable = HashMap<String, UnbakedBlockStates>;
 
-pub fn load_unbaked_block_states(mc_assets: &AssetPack) -> Result<UnbakedBlockStatesTable> {
+pub fn load_unbaked_block_states(mc_assets: &AssetPacks) -> Result<UnbakedBlockStatesTable> {
     let block_ids = mc_assets.enumerate_resources("minecraft", ResourceKind::BlockStates)?;
 
     let unbaked_block_states = block_ids
         .into_iter()
-        .map(|block_id| {
-            let model = mc_assets.load_blockstates(block_id.as_str())?;
+        .map(|(block_id, pack)| {
+            let model = mc_assets.pack(pack).load_blockstates(block_id.as_str())?;
             Ok((block_id.as_str().to_string(), model))
         })
         .collect::<Result<_>>()?;
@@ -0,0 +1,396 @@
+//! On-disk cache for [`BakedAssets`].
+//!
+//! Baking walks and resolves every block state and model in the asset pack,
+//! which takes a few seconds. The result only depends on the contents of the
+//! asset directories, the Minecraft version, and the way this crate bakes
+//! things, so it can be saved to disk and loaded back on subsequent runs.
+//!
+//! Cache files are named after a [`cache_key`] computed from all three of those
+//! inputs, so a change to any of them automatically results in a cache miss.
+//! Bump [`BAKE_FORMAT_VERSION`] whenever a change is made to the baked types or
+//! to the baking process itself.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use tracing::*;
+
+use brine_data::MinecraftData;
+
+use crate::bakery::{AssetRoots, BakedAssets};
+
+/// Version of the baked asset format.
+///
+/// Must be incremented whenever the output of [`bake_all`][super::bake_all]
+/// could change for the same input assets.
+pub const BAKE_FORMAT_VERSION: u32 = 10;
+
+const MAGIC: &[u8; 8] = b"BRINEBAK";
+
+/// Returns the directory that cache files are stored in by default.
+///
+/// This is `$BRINE_ASSET_CACHE_DIR` if that is set, or `target/brine_asset_cache`
+/// relative to the current directory otherwise.
+pub fn default_cache_dir() -> PathBuf {
+    std::env::var_os("BRINE_ASSET_CACHE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target/brine_asset_cache"))
+}
+
+/// Computes a key that uniquely identifies the baked output of the asset packs
+/// at `roots` for the given Minecraft data.
+///
+/// The key covers the order of the roots, the relative path and contents of
+/// every file under each of them, the Minecraft version, and
+/// [`BAKE_FORMAT_VERSION`].
+pub fn cache_key(roots: &AssetRoots, data: &MinecraftData) -> io::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+
+    BAKE_FORMAT_VERSION.hash(&mut hasher);
+    data.version().name().hash(&mut hasher);
+
+    for (index, root) in roots.iter().enumerate() {
+        index.hash(&mut hasher);
+
+        let mut files = Vec::new();
+        collect_files(root, &mut files)?;
+        files.sort();
+
+        for file in files.iter() {
+            file.strip_prefix(root).unwrap_or(file).hash(&mut hasher);
+            fs::read(file)?.hash(&mut hasher);
+        }
+    }
+
+    Ok(hasher.finish())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path of the cache file for the given key.
+pub fn cache_file_path(cache_dir: &Path, data: &MinecraftData, key: u64) -> PathBuf {
+    let mut path = PathBuf::from(cache_dir);
+    path.push(format!(
+        "baked_{}_{:016x}.bin",
+        data.version().name(),
+        key
+    ));
+    path
+}
+
+/// Writes baked assets to `writer`, tagged with the given cache key.
+pub fn write_to(writer: impl Write, baked: &BakedAssets, key: u64) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&BAKE_FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&key.to_le_bytes())?;
+
+    bincode::serialize_into(&mut writer, baked)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+    writer.flush()
+}
+
+/// Reads baked assets previously written by [`write_to`].
+///
+/// Returns `Ok(None)` if the data was written by a different format version or
+/// with a different cache key.
+pub fn read_from(reader: impl Read, key: u64) -> io::Result<Option<BakedAssets>> {
+    let mut reader = BufReader::new(reader);
+
+    let mut magic = [0; 8];
+    let mut format_version = [0; 4];
+    let mut stored_key = [0; 8];
+    reader.read_exact(&mut magic)?;
+    reader.read_exact(&mut format_version)?;
+    reader.read_exact(&mut stored_key)?;
+
+    if &magic != MAGIC
+        || u32::from_le_bytes(format_version) != BAKE_FORMAT_VERSION
+        || u64::from_le_bytes(stored_key) != key
+    {
+        return Ok(None);
+    }
+
+    let baked = bincode::deserialize_from(&mut reader)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(Some(baked))
+}
+
+/// Attempts to load baked assets for `roots` from the cache.
+///
+/// Returns `None` on a cache miss or if the cache file could not be read.
+pub fn load(cache_dir: &Path, roots: &AssetRoots, data: &MinecraftData) -> Option<BakedAssets> {
+    let start = std::time::Instant::now();
+
+    let key = cache_key(roots, data)
+        .map_err(|e| warn!("Failed to compute asset cache key: {}", e))
+        .ok()?;
+    let path = cache_file_path(cache_dir, data, key);
+
+    let file = fs::File::open(&path)
+        .map_err(|_| debug!("Asset cache miss: {}", path.to_string_lossy()))
+        .ok()?;
+
+    match read_from(file, key) {
+        Ok(Some(baked)) => {
+            info!(
+                "Loaded baked assets from cache {} in {:?}",
+                path.to_string_lossy(),
+                start.elapsed()
+            );
+            Some(baked)
+        }
+        Ok(None) => {
+            debug!("Asset cache is stale: {}", path.to_string_lossy());
+            None
+        }
+        Err(e) => {
+            warn!(
+                "Failed to read asset cache {}: {}",
+                path.to_string_lossy(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Saves baked assets for `roots` to the cache.
+pub fn store(
+    cache_dir: &Path,
+    roots: &AssetRoots,
+    data: &MinecraftData,
+    baked: &BakedAssets,
+) -> io::Result<PathBuf> {
+    let start = std::time::Instant::now();
+
+    let key = cache_key(roots, data)?;
+    let path = cache_file_path(cache_dir, data, key);
+
+    fs::create_dir_all(cache_dir)?;
+
+    // Write to a temporary file first so that a partially-written cache file
+    // is never mistaken for a valid one.
+    let temp_path = path.with_extension("tmp");
+    write_to(fs::File::create(&temp_path)?, baked, key)?;
+    fs::rename(&temp_path, &path)?;
+
+    info!(
+        "Saved baked assets to cache {} in {:?}",
+        path.to_string_lossy(),
+        start.elapsed()
+    );
+
+    Ok(path)
+}
+
+/// Serde helpers that store a [`BlockFace`] as a single byte, so that the cache
+/// format does not depend on how `minecraft-assets` chooses to represent it.
+///
+/// [`BlockFace`]: minecraft_assets::schemas::models::BlockFace
+pub(crate) mod block_face {
+    use minecraft_assets::schemas::models::BlockFace;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    fn to_u8(face: BlockFace) -> u8 {
+        match face {
+            BlockFace::Down => 0,
+            BlockFace::Up => 1,
+            BlockFace::North => 2,
+            BlockFace::South => 3,
+            BlockFace::West => 4,
+            BlockFace::East => 5,
+        }
+    }
+
+    fn from_u8(index: u8) -> Option<BlockFace> {
+        Some(match index {
+            0 => BlockFace::Down,
+            1 => BlockFace::Up,
+            2 => BlockFace::North,
+            3 => BlockFace::South,
+            4 => BlockFace::West,
+            5 => BlockFace::East,
+            _ => return None,
+        })
+    }
+
+    pub fn serialize<S: Serializer>(face: &BlockFace, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(to_u8(*face))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BlockFace, D::Error> {
+        let index = u8::deserialize(deserializer)?;
+        from_u8(index).ok_or_else(|| D::Error::custom(format!("invalid block face {}", index)))
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            face: &Option<BlockFace>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match face {
+                Some(face) => serializer.serialize_some(&to_u8(*face)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<BlockFace>, D::Error> {
+            match Option::<u8>::deserialize(deserializer)? {
+                Some(index) => from_u8(index)
+                    .map(Some)
+                    .ok_or_else(|| D::Error::custom(format!("invalid block face {}", index))),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brine_data::ItemId;
+    use minecraft_assets::{api::ResourceIdentifier, schemas::models::BlockFace};
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::bakery::{
+        block_states::{BakedBlockState, BakedBlockStateTable, BlockStateGrabBag},
+        items::BakedItemTable,
+        models::{BakedModel, BakedModelKey, BakedModelTable, BakedQuad},
+        textures::{TextureKey, TextureTable},
+    };
+
+    fn quad(face: BlockFace, cull_face: Option<BlockFace>, texture: TextureKey) -> BakedQuad {
+        BakedQuad {
+            positions: [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+            normal: [0.0, 0.0, -1.0],
+            tex_coords: [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]],
+            texture,
+            face,
+            cull_face,
+            tint_index: (face == BlockFace::Up).then(|| 0),
+            shade: true,
+        }
+    }
+
+    fn baked_assets() -> BakedAssets {
+        let mut textures = TextureTable::default();
+        let stone = textures.insert(ResourceIdentifier::texture("block/stone"));
+        let torch = textures.insert_from_pack(ResourceIdentifier::texture("block/torch"), 1);
+
+        let mut models = BakedModelTable::default();
+        let cube = models.insert(BakedModel {
+            is_full_cube: true,
+            quads: [
+                BlockFace::Down,
+                BlockFace::Up,
+                BlockFace::North,
+                BlockFace::South,
+                BlockFace::West,
+                BlockFace::East,
+            ]
+            .into_iter()
+            .map(|face| quad(face, Some(face), stone))
+            .collect(),
+        });
+        let flat = models.insert(BakedModel {
+            is_full_cube: false,
+            quads: smallvec![quad(BlockFace::North, None, torch)],
+        });
+
+        let mut block_states = BakedBlockStateTable::default();
+        block_states.insert(BakedBlockState::default());
+        block_states.insert(BakedBlockState {
+            is_full_cube: true,
+            models: smallvec![BlockStateGrabBag {
+                choices: smallvec![cube],
+            }],
+            fluid_overlay: None,
+            emissive: false,
+        });
+        block_states.insert(BakedBlockState {
+            is_full_cube: false,
+            models: smallvec![BlockStateGrabBag {
+                choices: smallvec![flat, flat, BakedModelKey(cube.0)],
+            }],
+            fluid_overlay: Some(flat),
+            emissive: true,
+        });
+
+        let mut items = BakedItemTable::default();
+        items.insert(ItemId(1), cube);
+        items.insert(ItemId(3), flat);
+
+        BakedAssets {
+            block_states,
+            items,
+            models,
+            textures,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let baked = baked_assets();
+
+        let mut bytes = Vec::new();
+        write_to(&mut bytes, &baked, 42).unwrap();
+
+        let read_back = read_from(&bytes[..], 42).unwrap().unwrap();
+
+        assert_eq!(read_back, baked);
+    }
+
+    #[test]
+    fn mismatched_key_is_a_miss() {
+        let baked = baked_assets();
+
+        let mut bytes = Vec::new();
+        write_to(&mut bytes, &baked, 42).unwrap();
+
+        assert!(read_from(&bytes[..], 43).unwrap().is_none());
+    }
+
+    #[test]
+    fn mismatched_format_version_is_a_miss() {
+        let baked = baked_assets();
+
+        let mut bytes = Vec::new();
+        write_to(&mut bytes, &baked, 42).unwrap();
+
+        // Format version immediately follows the magic bytes.
+        bytes[MAGIC.len()] = bytes[MAGIC.len()].wrapping_add(1);
+
+        assert!(read_from(&bytes[..], 42).unwrap().is_none());
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use brine_data::ItemId;
+
+use crate::bakery::models::BakedModelKey;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BakedItemTable {
+    /// Indexed by [`ItemId`]. `None` for items that have no model that can be
+    /// baked, e.g., those drawn by the game itself like chests.
+    pub items: Vec<Option<BakedModelKey>>,
+}
+
+impl BakedItemTable {
+    pub fn insert(&mut self, item_id: ItemId, model: BakedModelKey) {
+        let index = item_id.0 as usize;
+
+        if self.items.len() <= index {
+            self.items.resize(index + 1, None);
+        }
+
+        self.items[index] = Some(model);
+    }
+
+    /// Returns the model to draw the item with, if it has one.
+    pub fn get_model_for_item(&self, item_id: ItemId) -> Option<BakedModelKey> {
+        self.items.get(item_id.0 as usize).copied().flatten()
+    }
+
+    /// Returns the number of items that have a model.
+    pub fn len(&self) -> usize {
+        self.items.iter().filter(|model| model.is_some()).count()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
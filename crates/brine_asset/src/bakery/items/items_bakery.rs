@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use minecraft_assets::{
+    api::{ModelResolver, ResourceIdentifier},
+    schemas::models::Texture,
+};
+use smallvec::SmallVec;
+use tracing::*;
+
+use brine_data::MinecraftData;
+
+use crate::bakery::{
+    items::BakedItemTable,
+    models::{
+        bake_generated_item, BakedModelKey, BakedModelKeys, BakedModelTable, ModelBakery,
+        ModelReference, UnbakedModel, UnbakedModels,
+    },
+    textures::{TextureKey, TextureTable},
+    warnings::{self, BakeWarning, BakeWarnings},
+};
+
+/// What an item model turns out to be once its parents are resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ResolvedItemModel {
+    /// Derives from `builtin/generated`, with these layer textures.
+    Generated(Vec<TextureKey>),
+
+    /// Derives from this block model.
+    Block(String),
+
+    /// Derives from `builtin/entity`, i.e., is drawn by the game itself.
+    Entity,
+}
+
+/// Bakes the model of every item.
+///
+/// Items whose models derive from block models share the models baked for
+/// block states, so this runs after the block states have been baked.
+pub struct ItemsBakery<'a> {
+    unbaked_item_models: &'a UnbakedModels,
+    texture_table: &'a TextureTable,
+    warnings: Option<&'a BakeWarnings>,
+}
+
+impl<'a> ItemsBakery<'a> {
+    pub fn new(unbaked_item_models: &'a UnbakedModels, texture_table: &'a TextureTable) -> Self {
+        Self {
+            unbaked_item_models,
+            texture_table,
+            warnings: None,
+        }
+    }
+
+    /// Records the problems found while baking in `warnings`, in addition to
+    /// logging them.
+    pub fn with_warnings(mut self, warnings: &'a BakeWarnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    /// Bakes a model for every item in `mc_data`, adding them to
+    /// `baked_models`.
+    ///
+    /// Block models that were already baked (as listed in `model_keys`) are
+    /// reused. Others, like the inventory models of fences, are baked with
+    /// `model_bakery`. Items are baked in order of id, so the keys of the new
+    /// models are deterministic.
+    pub fn bake_items(
+        &self,
+        mc_data: &MinecraftData,
+        model_bakery: &ModelBakery,
+        model_keys: &BakedModelKeys,
+        baked_models: &mut BakedModelTable,
+    ) -> BakedItemTable {
+        let mut table = BakedItemTable::default();
+        let mut extra_block_models: HashMap<String, BakedModelKey> = HashMap::new();
+
+        for item in mc_data.items().iter() {
+            let model_key = match self.resolve_item_model(item.name) {
+                Some(ResolvedItemModel::Generated(layers)) => {
+                    baked_models.insert(bake_generated_item(&layers))
+                }
+                Some(ResolvedItemModel::Block(model)) => {
+                    let reference = ModelReference {
+                        model,
+                        x: 0,
+                        y: 0,
+                        uv_lock: false,
+                    };
+
+                    if let Some(key) = model_keys.get(&reference) {
+                        key
+                    } else if let Some(key) = extra_block_models.get(&reference.model) {
+                        *key
+                    } else {
+                        let baked_model = match model_bakery.bake_model_reference(&reference) {
+                            Some(baked_model) => baked_model,
+                            None => {
+                                warnings::report(
+                                    self.warnings,
+                                    BakeWarning::MissingModel(reference.model),
+                                );
+                                continue;
+                            }
+                        };
+                        let key = baked_models.insert(baked_model);
+                        extra_block_models.insert(reference.model, key);
+                        key
+                    }
+                }
+                Some(ResolvedItemModel::Entity) | None => continue,
+            };
+
+            table.insert(item.id, model_key);
+        }
+
+        table
+    }
+
+    /// Follows the item's model up through its parents to find out what kind
+    /// of model it is.
+    fn resolve_item_model(&self, item_name: &str) -> Option<ResolvedItemModel> {
+        let mut chain: SmallVec<[&UnbakedModel; 4]> = SmallVec::new();
+        let mut model_name = format!("item/{}", item_name);
+
+        loop {
+            let model = self
+                .unbaked_item_models
+                .get(&ResourceIdentifier::item_model(&model_name))
+                .or_else(|| {
+                    debug!("No item model {} for item {}", model_name, item_name);
+                    None
+                })?;
+            chain.push(model);
+
+            let parent = model.parent.as_deref().or_else(|| {
+                debug!("Item model {} has no parent", model_name);
+                None
+            })?;
+            let parent = parent.strip_prefix("minecraft:").unwrap_or(parent);
+
+            match parent {
+                "builtin/generated" => {
+                    let layers = self.resolve_layers(&model_name, &chain);
+                    return Some(ResolvedItemModel::Generated(layers));
+                }
+                "builtin/entity" => return Some(ResolvedItemModel::Entity),
+                block_model if block_model.starts_with("block/") => {
+                    return Some(ResolvedItemModel::Block(block_model.to_string()));
+                }
+                item_model => model_name = item_model.to_string(),
+            }
+        }
+    }
+
+    /// Returns the textures of the `layer0`, `layer1`, ... texture variables,
+    /// up to the first one that isn't defined.
+    fn resolve_layers(&self, model_name: &str, chain: &[&UnbakedModel]) -> Vec<TextureKey> {
+        let resolved_textures = ModelResolver::resolve_textures(chain.iter().copied());
+
+        let mut layers = Vec::new();
+
+        for layer in 0.. {
+            let variable = Texture(format!("#layer{}", layer));
+            let texture = match variable.resolve(&resolved_textures) {
+                Some(texture) => texture,
+                None => break,
+            };
+
+            match self
+                .texture_table
+                .get_key(&ResourceIdentifier::texture(texture))
+            {
+                Some(texture_key) => layers.push(texture_key),
+                None => warnings::report(
+                    self.warnings,
+                    BakeWarning::MissingTexture {
+                        model: model_name.to_string(),
+                        texture: texture.to_string(),
+                    },
+                ),
+            }
+        }
+
+        layers
+    }
+}
@@ -0,0 +1,5 @@
+mod baked;
+mod items_bakery;
+
+pub use baked::BakedItemTable;
+pub use items_bakery::ItemsBakery;
@@ -1,6 +1,12 @@
 mod bake;
 pub mod block_states;
+pub mod cache;
+pub mod items;
 pub mod models;
+pub mod packs;
 pub mod textures;
+pub mod warnings;
 
 pub use bake::{bake_all, BakedAssets};
+pub use packs::{AssetPacks, AssetRoots};
+pub use warnings::{BakeWarning, BakeWarnings};
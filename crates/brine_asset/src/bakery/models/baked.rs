@@ -1,10 +1,11 @@
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
 use minecraft_assets::schemas::models::BlockFace;
 
 use crate::bakery::{models::Cuboid, textures::TextureKey};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BakedQuad {
     pub positions: [[f32; 3]; 4],
 
@@ -14,11 +15,16 @@ pub struct BakedQuad {
 
     pub texture: TextureKey,
 
+    #[serde(with = "crate::bakery::cache::block_face")]
     pub face: BlockFace,
 
+    #[serde(with = "crate::bakery::cache::block_face::option")]
     pub cull_face: Option<BlockFace>,
 
-    pub tinted: bool,
+    /// Which color the quad is tinted with, if any, as set by the model's
+    /// `tintindex`. Blocks only ever use index 0, which is colored by the
+    /// biome (e.g., the top of grass blocks).
+    pub tint_index: Option<u8>,
 
     pub shade: bool,
 }
@@ -28,6 +34,33 @@ impl BakedQuad {
     pub fn indices(&self) -> [u8; 6] {
         Cuboid::get_indices(self.face)
     }
+
+    /// Returns the direction that the quad faces, i.e., the side of the block
+    /// whose axis is closest to the quad's normal.
+    ///
+    /// Unlike `face`, this takes any rotation of the model into account, and
+    /// unlike `cull_face`, it is defined for every quad.
+    pub fn facing(&self) -> BlockFace {
+        let [x, y, z] = self.normal;
+
+        if x.abs() >= y.abs() && x.abs() >= z.abs() {
+            if x >= 0.0 {
+                BlockFace::East
+            } else {
+                BlockFace::West
+            }
+        } else if y.abs() >= z.abs() {
+            if y >= 0.0 {
+                BlockFace::Up
+            } else {
+                BlockFace::Down
+            }
+        } else if z >= 0.0 {
+            BlockFace::South
+        } else {
+            BlockFace::North
+        }
+    }
 }
 
 pub struct BakedCuboid {
@@ -35,7 +68,7 @@ pub struct BakedCuboid {
     pub quads: SmallVec<[BakedQuad; 6]>,
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BakedModel {
     pub is_full_cube: bool,
     pub quads: SmallVec<[BakedQuad; 6]>,
@@ -47,10 +80,10 @@ pub struct BakedModel {
     */
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BakedModelKey(pub usize);
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BakedModelTable {
     pub models: Vec<BakedModel>,
 }
@@ -3,11 +3,11 @@ use minecraft_assets::{
     schemas::models::{BlockFace, Textures},
 };
 use smallvec::SmallVec;
-use tracing::*;
 
 use crate::bakery::{
     models::{BakedCuboid, BakedQuad, Cuboid, CuboidRotation, UnbakedCuboid, UnbakedQuad},
     textures::TextureTable,
+    warnings::{self, BakeWarning, BakeWarnings},
 };
 
 /// Bakes a single cuboid for a model.
@@ -20,6 +20,9 @@ pub struct CuboidBakery<'a> {
     rotation: CuboidRotation,
     rotated_and_scaled_cuboid: Cuboid,
     uv_lock: bool,
+
+    model_name: &'a str,
+    warnings: Option<&'a BakeWarnings>,
 }
 
 impl<'a> CuboidBakery<'a> {
@@ -42,9 +45,29 @@ impl<'a> CuboidBakery<'a> {
             rotation,
             rotated_and_scaled_cuboid,
             uv_lock,
+            model_name: "",
+            warnings: None,
         }
     }
 
+    /// Sets the name of the model that the cuboid belongs to, which is used
+    /// when reporting problems with it.
+    pub fn with_model_name(mut self, model_name: &'a str) -> Self {
+        self.model_name = model_name;
+        self
+    }
+
+    /// Records the problems found while baking in `warnings`, in addition to
+    /// logging them.
+    pub fn with_warnings(mut self, warnings: &'a BakeWarnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    fn warn(&self, warning: BakeWarning) {
+        warnings::report(self.warnings, warning);
+    }
+
     pub fn bake(&self) -> BakedCuboid {
         let is_full_cube = self.rotated_and_scaled_cuboid.is_full_cube();
 
@@ -85,10 +108,10 @@ impl<'a> CuboidBakery<'a> {
         let tex_coords = self.get_quad_tex_coords(quad, face)?;
 
         let resolved_texture = quad.texture.resolve(self.resolved_textures).or_else(|| {
-            warn!(
-                "No resolution for texture {:?} in {:?}",
-                quad.texture, self.resolved_textures
-            );
+            self.warn(BakeWarning::UnresolvedTexture {
+                model: self.model_name.to_string(),
+                var: quad.texture.0.trim_start_matches('#').to_string(),
+            });
             None
         })?;
 
@@ -96,7 +119,10 @@ impl<'a> CuboidBakery<'a> {
             .texture_table
             .get_key(&ResourceIdentifier::texture(resolved_texture))
             .or_else(|| {
-                warn!("Texture not in texture table: {}", resolved_texture);
+                self.warn(BakeWarning::MissingTexture {
+                    model: self.model_name.to_string(),
+                    texture: resolved_texture.to_string(),
+                });
                 None
             })?;
 
@@ -107,7 +133,7 @@ impl<'a> CuboidBakery<'a> {
             shade: self.unbaked_cuboid.shade,
             face,
             cull_face: quad.cull_face,
-            tinted: quad.tint_index >= 0,
+            tint_index: u8::try_from(quad.tint_index).ok(),
             texture: texture_key,
         })
     }
@@ -174,7 +200,10 @@ impl<'a> CuboidBakery<'a> {
             (false, 270) => Some([a, c, b, d]),
 
             (false, x) => {
-                warn!("Invalid face rotation: {}", x);
+                self.warn(BakeWarning::InvalidFaceRotation {
+                    model: self.model_name.to_string(),
+                    rotation: x,
+                });
                 None
             }
         };
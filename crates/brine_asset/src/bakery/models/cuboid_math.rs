@@ -182,7 +182,10 @@ pub struct CuboidRotation {
     pub origin: [f32; 3],
     pub axis: Axis,
     pub angle: EighthRotation,
-    // TODO!
+
+    /// Whether to stretch the rotated element across the whole block, by
+    /// scaling it by `1 / cos(angle)` along the axes perpendicular to
+    /// [`axis`][Self::axis].
     pub rescale: bool,
 }
 
@@ -198,16 +201,33 @@ impl CuboidRotation {
         transform.transform_vector3a(vec)
     }
 
+    /// Rotates (and, if [`rescale`][Self::rescale] is set, scales) a point
+    /// about the origin of the rotation.
     #[inline(always)]
     pub fn rotate_point(&self, point: Vec3A) -> Vec3A {
         let origin = Vec3A::from(self.origin);
         let from_origin = point - origin;
 
-        let from_origin = self.rotate_vector(from_origin);
+        let from_origin = self.rotate_vector(from_origin) * self.scale();
 
         origin + from_origin
     }
 
+    /// Returns the factor that rotated points are scaled by along each axis.
+    #[inline(always)]
+    pub fn scale(&self) -> Vec3A {
+        if !self.rescale {
+            return Vec3A::ONE;
+        }
+
+        let factor = 1.0 / f32::from(self.angle).to_radians().cos();
+        match self.axis {
+            Axis::X => Vec3A::new(1.0, factor, factor),
+            Axis::Y => Vec3A::new(factor, 1.0, factor),
+            Axis::Z => Vec3A::new(factor, factor, 1.0),
+        }
+    }
+
     #[inline(always)]
     pub fn rotate_cuboid(&self, cuboid: Cuboid) -> Cuboid {
         let vertices = cuboid.vertices.map(|vertex| self.rotate_point(vertex));
@@ -279,6 +299,8 @@ impl QuadRotation {
         }
     }
 
+    /// Rotates the quad about the center of the block, along with its normal
+    /// and its cull face.
     #[inline(always)]
     pub fn rotate_quad(&self, quad: &mut BakedQuad) {
         let vertices = quad.positions;
@@ -287,6 +309,29 @@ impl QuadRotation {
         let vertices = vertices.map(|vertex| vertex.map(|coord| coord + 0.5));
 
         quad.positions = vertices;
+        quad.normal = self.rotate_point(quad.normal);
+        quad.cull_face = quad.cull_face.map(|face| self.rotate_face(face));
+    }
+
+    /// Returns the side of the block that `face` ends up on after the
+    /// rotation.
+    ///
+    /// Follows the same convention as [`rotate_point`][Self::rotate_point], so
+    /// a face keeps matching the normal of a quad rotated with it (e.g.,
+    /// `North` rotated 90° about Y is `West`).
+    #[inline(always)]
+    pub fn rotate_face(&self, face: BlockFace) -> BlockFace {
+        let [x, y, z] = self.rotate_point(Cuboid::get_normal(face).into());
+
+        match [x.round() as i32, y.round() as i32, z.round() as i32] {
+            [0, -1, 0] => BlockFace::Down,
+            [0, 1, 0] => BlockFace::Up,
+            [0, 0, -1] => BlockFace::North,
+            [0, 0, 1] => BlockFace::South,
+            [-1, 0, 0] => BlockFace::West,
+            [1, 0, 0] => BlockFace::East,
+            normal => unreachable!("quarter rotations keep normals axis-aligned: {:?}", normal),
+        }
     }
 
     #[inline(always)]
@@ -515,6 +560,136 @@ mod tests {
         }
     }
 
+    #[test]
+    fn quad_rotation_rotates_normal() {
+        let mut quad = BakedQuad {
+            positions: [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+            normal: [0.0, 0.0, -1.0],
+            tex_coords: Default::default(),
+            texture: Default::default(),
+            face: BlockFace::North,
+            cull_face: None,
+            tint_index: None,
+            shade: true,
+        };
+
+        QuadRotation::new(0, 90).rotate_quad(&mut quad);
+
+        // The normal must stay perpendicular to the rotated quad.
+        assert_eq!(quad.normal, [-1.0, 0.0, 0.0]);
+        assert_eq!(quad.facing(), BlockFace::West);
+        assert!(quad.positions.iter().all(|[x, _, _]| *x == 0.0));
+    }
+
+    #[test]
+    fn quad_rotation_rotates_cull_face() {
+        let mut quad = BakedQuad {
+            positions: [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+            normal: [0.0, 0.0, -1.0],
+            tex_coords: Default::default(),
+            texture: Default::default(),
+            face: BlockFace::North,
+            cull_face: Some(BlockFace::North),
+            tint_index: None,
+            shade: true,
+        };
+
+        QuadRotation::new(0, 90).rotate_quad(&mut quad);
+
+        // The quad still lies on the side of the block that it's culled by.
+        assert_eq!(quad.cull_face, Some(BlockFace::West));
+        assert_eq!(quad.cull_face, Some(quad.facing()));
+    }
+
+    #[test]
+    fn face_rotation_matches_normal_rotation() {
+        let faces = [
+            BlockFace::Down,
+            BlockFace::Up,
+            BlockFace::North,
+            BlockFace::South,
+            BlockFace::West,
+            BlockFace::East,
+        ];
+
+        for x in [0, 90, 180, 270] {
+            for y in [0, 90, 180, 270] {
+                let rotation = QuadRotation::new(x, y);
+
+                for face in faces {
+                    let normal = rotation.rotate_point(Cuboid::get_normal(face).into());
+                    let rotated = Cuboid::get_normal(rotation.rotate_face(face));
+
+                    assert!(
+                        (rotated - Vec3A::from(normal)).abs().max_element() <= 0.0001,
+                        "face: {:?}, rotation: {:?}",
+                        face,
+                        rotation
+                    );
+                }
+            }
+        }
+    }
+
+    fn assert_extent(cuboid: &Cuboid, expected: Vec3A) {
+        let min = cuboid
+            .vertices
+            .iter()
+            .fold(Vec3A::splat(f32::MAX), |a, b| a.min(*b));
+        let max = cuboid
+            .vertices
+            .iter()
+            .fold(Vec3A::splat(f32::MIN), |a, b| a.max(*b));
+        let extent = max - min;
+
+        assert!(
+            (extent - expected).abs().max_element() <= 0.0001,
+            "extent: {:?}, expected: {:?}",
+            extent,
+            expected
+        );
+    }
+
+    #[test]
+    fn cuboid_rotation_rescale() {
+        let cuboid = Cuboid::new([0.0, 0.0, 0.0], [16.0, 16.0, 16.0]);
+        let mut rotation = CuboidRotation {
+            origin: [8.0, 8.0, 8.0],
+            axis: Axis::Y,
+            angle: EighthRotation::Pos45,
+            rescale: false,
+        };
+
+        // The diagonals of the top and bottom faces line up with X and Z.
+        let diagonal = 16.0 * 2f32.sqrt();
+        assert_extent(
+            &rotation.rotate_cuboid(cuboid.clone()),
+            Vec3A::new(diagonal, 16.0, diagonal),
+        );
+
+        // Then they're scaled by 1 / cos(45°) = sqrt(2).
+        rotation.rescale = true;
+        assert_extent(
+            &rotation.rotate_cuboid(cuboid.clone()),
+            Vec3A::new(32.0, 16.0, 32.0),
+        );
+
+        // The origin stays put, and nothing is scaled without an angle.
+        assert_eq!(rotation.rotate_point(Vec3A::splat(8.0)), Vec3A::splat(8.0));
+        rotation.angle = EighthRotation::Zero;
+        assert_eq!(rotation.rotate_cuboid(cuboid.clone()), cuboid);
+    }
+
     #[test]
     fn quad_rotation() {
         for x in [-1.0, -0.5, 0.0, 0.5, 1.0] {
@@ -0,0 +1,102 @@
+use minecraft_assets::schemas::models::BlockFace;
+use smallvec::SmallVec;
+
+use crate::bakery::{
+    models::{BakedModel, BakedQuad, Cuboid},
+    textures::TextureKey,
+};
+
+/// Name of the texture used for the surface of still water.
+pub const WATER_TEXTURE: &str = "block/water_still";
+
+/// Height of the surface of a water source block, in sixteenths of a block.
+const WATER_SURFACE_HEIGHT: f32 = 14.0;
+
+/// Bakes the model that is drawn in addition to a waterlogged block's own
+/// models.
+///
+/// Water doesn't have a usable block model (`block/water` only defines a
+/// particle texture), so this bakes one by hand: a cuboid covering the block up
+/// to the surface height of a water source block.
+///
+/// All quads but the top one lie on the boundary of the block, so they are
+/// culled against their neighbors like any other full block face.
+pub fn bake_water_overlay(texture: TextureKey) -> BakedModel {
+    let cuboid = Cuboid::new([0.0, 0.0, 0.0], [16.0, WATER_SURFACE_HEIGHT, 16.0]);
+    let scaled_cuboid = cuboid.clone().scaled(1.0 / 16.0);
+
+    let quads: SmallVec<[BakedQuad; 6]> = [
+        BlockFace::Down,
+        BlockFace::Up,
+        BlockFace::North,
+        BlockFace::South,
+        BlockFace::West,
+        BlockFace::East,
+    ]
+    .into_iter()
+    .map(|face| BakedQuad {
+        positions: scaled_cuboid.get_face(face).map(Into::into),
+        normal: Cuboid::get_normal(face).into(),
+        tex_coords: tex_coords(&cuboid, face),
+        texture,
+        face,
+        cull_face: match face {
+            BlockFace::Up => None,
+            _ => Some(face),
+        },
+        // Water is colored by the biome.
+        tint_index: Some(0),
+        shade: true,
+    })
+    .collect();
+
+    BakedModel {
+        is_full_cube: false,
+        quads,
+    }
+}
+
+/// Maps each vertex of a face to the part of the texture that lines up with
+/// it, the same way faces without explicit UVs are textured in block models.
+fn tex_coords(cuboid: &Cuboid, face: BlockFace) -> [[f32; 2]; 4] {
+    let face_verts: [[f32; 3]; 4] = cuboid.get_face(face).map(Into::into);
+
+    face_verts.map(|[x, y, z]| {
+        let [u, v] = match face {
+            BlockFace::Down => [x, 16.0 - z],
+            BlockFace::Up => [x, z],
+            BlockFace::North => [16.0 - x, 16.0 - y],
+            BlockFace::South => [x, 16.0 - y],
+            BlockFace::West => [z, 16.0 - y],
+            BlockFace::East => [16.0 - z, 16.0 - y],
+        };
+
+        [u / 16.0, v / 16.0]
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn water_overlay_stops_below_top_of_block() {
+        let model = bake_water_overlay(TextureKey(3));
+
+        assert_eq!(model.quads.len(), 6);
+        assert!(!model.is_full_cube);
+        assert!(model.quads.iter().all(|quad| quad.texture == TextureKey(3)));
+
+        let top = model
+            .quads
+            .iter()
+            .find(|quad| quad.face == BlockFace::Up)
+            .unwrap();
+        assert_eq!(top.cull_face, None);
+        assert!(top.positions.iter().all(|[_, y, _]| *y == 14.0 / 16.0));
+
+        for quad in model.quads.iter().filter(|quad| quad.face != BlockFace::Up) {
+            assert_eq!(quad.cull_face, Some(quad.face));
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use minecraft_assets::schemas::models::BlockFace;
+
+use crate::bakery::{
+    models::{BakedModel, BakedQuad, Cuboid},
+    textures::TextureKey,
+};
+
+/// Distance between the quads of consecutive layers, in sixteenths of a block,
+/// so that each layer is drawn on top of the one before it.
+const LAYER_SPACING: f32 = 0.01;
+
+/// Bakes the model of an item whose model derives from `builtin/generated`,
+/// given the textures of its layers (`layer0`, `layer1`, ...) in order.
+///
+/// Vanilla extrudes these textures into thin slabs. This bakes each layer as a
+/// single quad instead, facing south through the middle of the block, which is
+/// all that is needed to draw the item in a user interface.
+pub fn bake_generated_item(layers: &[TextureKey]) -> BakedModel {
+    let face = BlockFace::South;
+
+    let quads = layers
+        .iter()
+        .enumerate()
+        .map(|(layer, texture)| {
+            let z = 8.0 + layer as f32 * LAYER_SPACING;
+            let cuboid = Cuboid::new([0.0, 0.0, z], [16.0, 16.0, z]).scaled(1.0 / 16.0);
+            let positions: [[f32; 3]; 4] = cuboid.get_face(face).map(Into::into);
+
+            BakedQuad {
+                positions,
+                normal: Cuboid::get_normal(face).into(),
+                tex_coords: positions.map(|[x, y, _]| [x, 1.0 - y]),
+                texture: *texture,
+                face,
+                cull_face: None,
+                // Which layers are tinted depends on the item, not its model.
+                tint_index: None,
+                shade: false,
+            }
+        })
+        .collect();
+
+    BakedModel {
+        is_full_cube: false,
+        quads,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn one_quad_per_layer() {
+        let model = bake_generated_item(&[TextureKey(4), TextureKey(2)]);
+
+        assert_eq!(model.quads.len(), 2);
+        assert!(!model.is_full_cube);
+        assert_eq!(model.quads[0].texture, TextureKey(4));
+        assert_eq!(model.quads[1].texture, TextureKey(2));
+
+        for quad in model.quads.iter() {
+            assert_eq!(quad.facing(), BlockFace::South);
+            assert_eq!(quad.cull_face, None);
+        }
+
+        // The second layer is in front of the first.
+        assert!(model.quads[1].positions[0][2] > model.quads[0].positions[0][2]);
+    }
+}
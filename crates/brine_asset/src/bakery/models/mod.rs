@@ -1,13 +1,20 @@
 mod baked;
 mod cuboid_bakery;
 mod cuboid_math;
+mod fluid;
+mod generated;
 mod model_bakery;
+mod model_keys;
 mod unbaked;
 
 pub use baked::{BakedCuboid, BakedModel, BakedModelKey, BakedModelTable, BakedQuad};
 pub use cuboid_bakery::CuboidBakery;
 pub use cuboid_math::{Cuboid, CuboidRotation, EighthRotation, QuarterRotation};
+pub use fluid::{bake_water_overlay, WATER_TEXTURE};
+pub use generated::bake_generated_item;
 pub use model_bakery::ModelBakery;
+pub use model_keys::{assign_model_keys, BakedModelKeys, ModelReference};
 pub use unbaked::{
-    load_unbaked_block_models, UnbakedCuboid, UnbakedModel, UnbakedModels, UnbakedQuad,
+    load_unbaked_block_models, load_unbaked_item_models, UnbakedCuboid, UnbakedModel,
+    UnbakedModels, UnbakedQuad,
 };
@@ -2,20 +2,24 @@ use minecraft_assets::{
     api::{ModelResolver, ResourceIdentifier},
     schemas::{blockstates::ModelProperties, models::Textures},
 };
+use rayon::prelude::*;
 use smallvec::SmallVec;
 use tracing::*;
 
 use crate::bakery::{
     models::{
-        cuboid_math::QuadRotation, BakedCuboid, BakedModel, CuboidBakery, UnbakedCuboid,
-        UnbakedModel, UnbakedModels,
+        assign_model_keys, cuboid_math::QuadRotation, BakedCuboid, BakedModel, BakedModelKeys,
+        BakedModelTable, CuboidBakery, ModelReference, UnbakedCuboid, UnbakedModel,
+        UnbakedModels,
     },
     textures::TextureTable,
+    warnings::{self, BakeWarning, BakeWarnings},
 };
 
 pub struct ModelBakery<'a> {
     unbaked_models: &'a UnbakedModels,
     texture_table: &'a TextureTable,
+    warnings: Option<&'a BakeWarnings>,
 }
 
 impl<'a> ModelBakery<'a> {
@@ -23,16 +27,55 @@ impl<'a> ModelBakery<'a> {
         Self {
             unbaked_models,
             texture_table,
+            warnings: None,
         }
     }
 
+    /// Records the problems found while baking in `warnings`, in addition to
+    /// logging them.
+    pub fn with_warnings(mut self, warnings: &'a BakeWarnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    /// Bakes every referenced model in parallel.
+    ///
+    /// Keys are assigned in sorted order of the references (see
+    /// [`assign_model_keys`]), so the result is the same no matter how the
+    /// work was split between threads. References to models that could not be
+    /// baked are left out.
+    pub fn bake_models<'r>(
+        &self,
+        references: impl IntoParallelIterator<Item = &'r ModelReference>,
+    ) -> (BakedModelTable, BakedModelKeys) {
+        let baked_models: Vec<(ModelReference, BakedModel)> = references
+            .into_par_iter()
+            .filter_map(|reference| {
+                let baked_model = self.bake_model_reference(reference).or_else(|| {
+                    warnings::report(
+                        self.warnings,
+                        BakeWarning::MissingModel(reference.model.clone()),
+                    );
+                    None
+                })?;
+                Some((reference.clone(), baked_model))
+            })
+            .collect();
+
+        assign_model_keys(baked_models)
+    }
+
     pub fn bake_model_from_properties(
         &self,
         model_properties: &ModelProperties,
     ) -> Option<BakedModel> {
-        let mut baked_model = self.bake_model(&model_properties.model, model_properties.uv_lock)?;
+        self.bake_model_reference(&ModelReference::from(model_properties))
+    }
 
-        let rotation = QuadRotation::new(model_properties.x, model_properties.y);
+    pub fn bake_model_reference(&self, reference: &ModelReference) -> Option<BakedModel> {
+        let mut baked_model = self.bake_model(&reference.model, reference.uv_lock)?;
+
+        let rotation = QuadRotation::new(reference.x, reference.y);
 
         for quad in baked_model.quads.iter_mut() {
             rotation.rotate_quad(quad);
@@ -61,7 +104,7 @@ impl<'a> ModelBakery<'a> {
                 let BakedCuboid {
                     is_full_cube,
                     mut quads,
-                } = self.bake_cuboid(&cuboid, &resolved_textures, uv_lock);
+                } = self.bake_cuboid(model_name, &cuboid, &resolved_textures, uv_lock);
 
                 if !is_full_cube {
                     all_cuboids_full_cubes = false;
@@ -79,12 +122,17 @@ impl<'a> ModelBakery<'a> {
 
     pub fn bake_cuboid(
         &self,
+        model_name: &str,
         cuboid: &'a UnbakedCuboid,
         resolved_textures: &Textures,
         uv_lock: bool,
     ) -> BakedCuboid {
-        let cuboid_bakery =
-            CuboidBakery::new(cuboid, resolved_textures, self.texture_table, uv_lock);
+        let mut cuboid_bakery =
+            CuboidBakery::new(cuboid, resolved_textures, self.texture_table, uv_lock)
+                .with_model_name(model_name);
+        if let Some(warnings) = self.warnings {
+            cuboid_bakery = cuboid_bakery.with_warnings(warnings);
+        }
 
         cuboid_bakery.bake()
     }
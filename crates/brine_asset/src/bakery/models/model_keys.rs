@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use minecraft_assets::schemas::blockstates::ModelProperties;
+
+use crate::bakery::models::{BakedModel, BakedModelKey, BakedModelTable};
+
+/// A model as referenced by a blockstates definition, i.e., a model name plus
+/// the rotation and uv lock to bake it with.
+///
+/// References order by model name first, which is the order in which baked
+/// models are assigned their [`BakedModelKey`]s.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModelReference {
+    pub model: String,
+    pub x: u32,
+    pub y: u32,
+    pub uv_lock: bool,
+}
+
+impl From<&ModelProperties> for ModelReference {
+    fn from(model_properties: &ModelProperties) -> Self {
+        Self {
+            model: model_properties.model.clone(),
+            x: model_properties.x,
+            y: model_properties.y,
+            uv_lock: model_properties.uv_lock,
+        }
+    }
+}
+
+/// Maps each [`ModelReference`] to the key of its model in a
+/// [`BakedModelTable`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BakedModelKeys {
+    keys: HashMap<ModelReference, BakedModelKey>,
+}
+
+impl BakedModelKeys {
+    pub fn get(&self, reference: &ModelReference) -> Option<BakedModelKey> {
+        self.keys.get(reference).copied()
+    }
+
+    pub fn get_by_properties(&self, model_properties: &ModelProperties) -> Option<BakedModelKey> {
+        self.get(&ModelReference::from(model_properties))
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Collects baked models into a table, assigning keys in sorted order of
+/// their references.
+///
+/// The resulting table does not depend on the order in which the models are
+/// provided, so models can be baked in parallel without making the keys (and
+/// therefore the asset cache) nondeterministic. Duplicate references keep the
+/// first model provided for them.
+pub fn assign_model_keys(
+    baked_models: impl IntoIterator<Item = (ModelReference, BakedModel)>,
+) -> (BakedModelTable, BakedModelKeys) {
+    let mut baked_models: Vec<(ModelReference, BakedModel)> = baked_models.into_iter().collect();
+    baked_models.sort_by(|(a, _), (b, _)| a.cmp(b));
+    baked_models.dedup_by(|(a, _), (b, _)| a == b);
+
+    let mut table = BakedModelTable {
+        models: Vec::with_capacity(baked_models.len()),
+    };
+    let mut keys = BakedModelKeys::default();
+
+    for (reference, baked_model) in baked_models.into_iter() {
+        let key = table.insert(baked_model);
+        keys.keys.insert(reference, key);
+    }
+
+    (table, keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(model: &str, y: u32) -> ModelReference {
+        ModelReference {
+            model: model.to_string(),
+            x: 0,
+            y,
+            uv_lock: false,
+        }
+    }
+
+    /// Models that can be told apart by their contents.
+    fn models() -> Vec<(ModelReference, BakedModel)> {
+        [
+            ("block/stone", 0),
+            ("block/oak_stairs", 90),
+            ("block/oak_stairs", 0),
+            ("block/torch", 0),
+            ("block/oak_stairs", 180),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (model, y))| {
+            (
+                reference(model, y),
+                BakedModel {
+                    is_full_cube: i % 2 == 0,
+                    quads: Default::default(),
+                },
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn keys_are_sorted_by_model_name() {
+        let (table, keys) = assign_model_keys(models());
+
+        assert_eq!(table.models.len(), 5);
+        assert_eq!(keys.len(), 5);
+
+        assert_eq!(keys.get(&reference("block/oak_stairs", 0)), Some(BakedModelKey(0)));
+        assert_eq!(keys.get(&reference("block/oak_stairs", 90)), Some(BakedModelKey(1)));
+        assert_eq!(keys.get(&reference("block/oak_stairs", 180)), Some(BakedModelKey(2)));
+        assert_eq!(keys.get(&reference("block/stone", 0)), Some(BakedModelKey(3)));
+        assert_eq!(keys.get(&reference("block/torch", 0)), Some(BakedModelKey(4)));
+        assert_eq!(keys.get(&reference("block/dirt", 0)), None);
+    }
+
+    #[test]
+    fn deterministic_regardless_of_bake_order() {
+        // Simulate two bakes where worker threads finished in different orders.
+        let first = assign_model_keys(models());
+        let second = assign_model_keys(models().into_iter().rev());
+
+        let mut rotated = models();
+        rotated.rotate_left(2);
+        let third = assign_model_keys(rotated);
+
+        assert_eq!(first, second);
+        assert_eq!(first, third);
+    }
+
+    #[test]
+    fn duplicates_get_one_key() {
+        let mut duplicated = models();
+        duplicated.extend(models());
+
+        let (table, keys) = assign_model_keys(duplicated);
+
+        assert_eq!(table.models.len(), 5);
+        assert_eq!(keys.len(), 5);
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use minecraft_assets::api::{AssetPack, ResourceIdentifier, ResourceKind, Result};
+use minecraft_assets::api::{ResourceIdentifier, ResourceKind, Result};
+use tracing::*;
+
+use crate::bakery::packs::AssetPacks;
 
 pub type UnbakedQuad = minecraft_assets::schemas::models::ElementFace;
 
@@ -10,13 +13,37 @@ pub type UnbakedModel = minecraft_assets::schemas::models::Model;
 
 pub type UnbakedModels = HashMap<ResourceIdentifier<'static>, UnbakedModel>;
 
-pub fn load_unbaked_block_models(mc_assets: &AssetPack) -> Result<UnbakedModels> {
+pub fn load_unbaked_block_models(mc_assets: &AssetPacks) -> Result<UnbakedModels> {
     let model_ids = mc_assets.enumerate_resources("minecraft", ResourceKind::BlockModel)?;
 
     let unbaked_models = model_ids
         .into_iter()
-        .map(|model_id| {
-            let model = mc_assets.load_block_model(model_id.as_str())?;
+        .map(|(model_id, pack)| {
+            let model = mc_assets.pack(pack).load_block_model(model_id.as_str())?;
+            Ok((model_id, model))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(unbaked_models)
+}
+
+/// Loads every model in the asset packs' `models/item` directories.
+///
+/// Unlike block models, item models are optional: an asset pack without any
+/// just has no item models.
+pub fn load_unbaked_item_models(mc_assets: &AssetPacks) -> Result<UnbakedModels> {
+    let model_ids = match mc_assets.enumerate_resources("minecraft", ResourceKind::ItemModel) {
+        Ok(model_ids) => model_ids,
+        Err(e) => {
+            debug!("No item models found: {}", e);
+            return Ok(UnbakedModels::default());
+        }
+    };
+
+    let unbaked_models = model_ids
+        .into_iter()
+        .map(|(model_id, pack)| {
+            let model = mc_assets.pack(pack).load_item_model(model_id.as_str())?;
             Ok((model_id, model))
         })
         .collect::<Result<_>>()?;
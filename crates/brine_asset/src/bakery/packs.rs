@@ -0,0 +1,176 @@
+//! Layering resource packs on top of the base assets.
+//!
+//! Assets are loaded from an ordered list of asset roots (see [`AssetRoots`]):
+//! the base assets first, followed by any number of resource packs that
+//! override them. Each resource (a texture, a model, or a block state
+//! definition) is loaded from the last root that has it, so a pack only needs
+//! to contain the resources that it changes. Everything else falls back to
+//! the packs before it, and ultimately to the base assets.
+
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use minecraft_assets::api::{AssetPack, ResourceIdentifier, ResourceKind, Result};
+use tracing::*;
+
+/// The directories that assets are loaded from, in increasing order of
+/// priority.
+///
+/// The first root is the base assets. Any others are resource packs, each of
+/// which overrides the resources of the roots before it. Every root is laid
+/// out like the base assets, i.e., with its resources in `assets/minecraft`.
+///
+/// A single path converts into roots without any packs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AssetRoots {
+    roots: Vec<PathBuf>,
+}
+
+impl AssetRoots {
+    /// Creates roots consisting of only the base assets at `base`.
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self {
+            roots: vec![base.into()],
+        }
+    }
+
+    /// Adds a resource pack that overrides every root added before it.
+    pub fn with_pack(mut self, pack: impl Into<PathBuf>) -> Self {
+        self.roots.push(pack.into());
+        self
+    }
+
+    /// Returns the root of the base assets.
+    #[inline]
+    pub fn base(&self) -> &Path {
+        &self.roots[0]
+    }
+
+    /// Returns the root at the given index, where `0` is the base assets and
+    /// `1..` are the resource packs in the order they were added.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Path> {
+        self.roots.get(index).map(PathBuf::as_path)
+    }
+
+    /// Returns the number of roots, including the base assets.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Always false, since there are always base assets.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Iterates over the roots in increasing order of priority.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Path> {
+        self.roots.iter().map(PathBuf::as_path)
+    }
+}
+
+impl From<PathBuf> for AssetRoots {
+    fn from(base: PathBuf) -> Self {
+        Self::new(base)
+    }
+}
+
+impl From<&PathBuf> for AssetRoots {
+    fn from(base: &PathBuf) -> Self {
+        Self::new(base)
+    }
+}
+
+impl From<&Path> for AssetRoots {
+    fn from(base: &Path) -> Self {
+        Self::new(base)
+    }
+}
+
+impl From<&str> for AssetRoots {
+    fn from(base: &str) -> Self {
+        Self::new(base)
+    }
+}
+
+impl From<String> for AssetRoots {
+    fn from(base: String) -> Self {
+        Self::new(base)
+    }
+}
+
+/// Converts a non-empty list of roots, base assets first.
+///
+/// # Panics
+///
+/// Panics if the list is empty.
+impl From<Vec<PathBuf>> for AssetRoots {
+    fn from(roots: Vec<PathBuf>) -> Self {
+        assert!(
+            !roots.is_empty(),
+            "asset roots must include the base assets"
+        );
+        Self { roots }
+    }
+}
+
+/// An [`AssetPack`] for each of a set of [`AssetRoots`], which resolves every
+/// resource against the packs in priority order.
+pub struct AssetPacks {
+    packs: Vec<AssetPack>,
+}
+
+impl AssetPacks {
+    /// Opens the asset pack at each of the given roots.
+    pub fn new(roots: &AssetRoots) -> Self {
+        Self {
+            packs: roots.iter().map(AssetPack::at_path).collect(),
+        }
+    }
+
+    /// Opens only the base assets at `root`.
+    pub fn at_path(root: impl AsRef<Path>) -> Self {
+        Self::new(&AssetRoots::new(root.as_ref()))
+    }
+
+    /// Returns the asset pack at the given index of the [`AssetRoots`].
+    #[inline]
+    pub fn pack(&self, index: usize) -> &AssetPack {
+        &self.packs[index]
+    }
+
+    /// Lists every resource of the given kind in any of the packs, along with
+    /// the index of the pack that it should be loaded from.
+    ///
+    /// Resources are listed in the base assets' order, followed by those that
+    /// only resource packs have. An error listing the base assets is returned
+    /// as is, but a resource pack that has no resources of this kind is simply
+    /// skipped.
+    pub fn enumerate_resources(
+        &self,
+        namespace: &str,
+        kind: ResourceKind,
+    ) -> Result<Vec<(ResourceIdentifier<'static>, usize)>> {
+        let mut resources: IndexMap<ResourceIdentifier<'static>, usize> = IndexMap::new();
+
+        for (index, pack) in self.packs.iter().enumerate() {
+            let ids = match pack.enumerate_resources(namespace, kind) {
+                Ok(ids) => ids,
+                Err(e) if index > 0 => {
+                    trace!("Resource pack {} has no {:?}: {}", index, kind, e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            for id in ids {
+                resources.insert(id, index);
+            }
+        }
+
+        Ok(resources.into_iter().collect())
+    }
+}
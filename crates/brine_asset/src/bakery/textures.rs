@@ -1,12 +1,21 @@
 use indexmap::IndexSet;
-use minecraft_assets::api::{AssetPack, ResourceIdentifier, ResourceKind, Result};
+use minecraft_assets::api::{ResourceIdentifier, ResourceKind, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+use crate::bakery::packs::AssetPacks;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TextureKey(pub usize);
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct TextureTable {
     textures: IndexSet<ResourceIdentifier<'static>>,
+
+    /// Index of the asset root (see [`AssetRoots`]) that each texture is loaded
+    /// from, in key order.
+    ///
+    /// [`AssetRoots`]: crate::bakery::packs::AssetRoots
+    packs: Vec<usize>,
 }
 
 impl TextureTable {
@@ -18,9 +27,21 @@ impl TextureTable {
             .map(|(index, id)| (TextureKey(index), id))
     }
 
+    /// Inserts a texture from the base assets.
     #[inline]
     pub fn insert(&mut self, id: ResourceIdentifier<'static>) -> TextureKey {
-        let (index, _) = self.textures.insert_full(id);
+        self.insert_from_pack(id, 0)
+    }
+
+    /// Inserts a texture from the asset root at index `pack`.
+    ///
+    /// If the texture is already in the table, its key is returned and the
+    /// pack it came from is left as is.
+    pub fn insert_from_pack(&mut self, id: ResourceIdentifier<'static>, pack: usize) -> TextureKey {
+        let (index, inserted) = self.textures.insert_full(id);
+        if inserted {
+            self.packs.push(pack);
+        }
 
         TextureKey(index)
     }
@@ -34,16 +55,52 @@ impl TextureTable {
     pub fn get_key(&self, name: &ResourceIdentifier) -> Option<TextureKey> {
         self.textures.get_index_of(name).map(TextureKey)
     }
+
+    /// Returns the index of the asset root that the texture is loaded from,
+    /// where `0` is the base assets.
+    #[inline]
+    pub fn get_pack(&self, key: TextureKey) -> Option<usize> {
+        self.packs.get(key.0).copied()
+    }
+}
+
+/// Serialized as the list of texture names and the packs they come from, in
+/// key order.
+impl Serialize for TextureTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.textures
+                .iter()
+                .zip(self.packs.iter())
+                .map(|(id, pack)| (id.as_str(), *pack)),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for TextureTable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let entries = Vec::<(String, usize)>::deserialize(deserializer)?;
+
+        let mut table = Self::default();
+        for (name, pack) in entries {
+            table.insert_from_pack(
+                ResourceIdentifier::new_owned(ResourceKind::Texture, name),
+                pack,
+            );
+        }
+
+        Ok(table)
+    }
 }
 
-pub fn load_texture_table(assets: &AssetPack) -> Result<TextureTable> {
+pub fn load_texture_table(assets: &AssetPacks) -> Result<TextureTable> {
     let mut table = TextureTable::default();
 
-    for texture_id in assets
+    for (texture_id, pack) in assets
         .enumerate_resources("minecraft", ResourceKind::Texture)?
         .into_iter()
     {
-        table.insert(texture_id);
+        table.insert_from_pack(texture_id, pack);
     }
 
     Ok(table)
@@ -0,0 +1,97 @@
+//! Problems found while baking, such as references to models or textures that
+//! aren't in the asset pack.
+//!
+//! None of these stop the bake. Whatever couldn't be baked is left out, the
+//! problem is logged, and (if the bakery was given a [`BakeWarnings`]) it is
+//! recorded so that tools can report on gaps in the assets' coverage.
+
+use std::{fmt, sync::Mutex};
+
+use tracing::*;
+
+/// Something that had to be left out of the baked assets.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BakeWarning {
+    /// A block has no blockstates definition.
+    MissingBlockStates(String),
+
+    /// A blockstates definition is for a block that doesn't exist.
+    MissingBlockData(String),
+
+    /// A block state refers to a model that doesn't exist.
+    MissingModel(String),
+
+    /// A model refers to a texture variable that neither it nor any of its
+    /// parents define.
+    UnresolvedTexture { model: String, var: String },
+
+    /// A model refers to a texture that isn't in the texture table.
+    MissingTexture { model: String, texture: String },
+
+    /// A model has a face with a rotation other than 0, 90, 180, or 270.
+    InvalidFaceRotation { model: String, rotation: u32 },
+
+    /// The texture for a fluid isn't in the texture table.
+    MissingFluidTexture(String),
+}
+
+impl fmt::Display for BakeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingBlockStates(block) => {
+                write!(f, "No blockstates definition found for block {}", block)
+            }
+            Self::MissingBlockData(block) => write!(f, "No block data for block {}", block),
+            Self::MissingModel(model) => write!(f, "Failed to bake model {}", model),
+            Self::UnresolvedTexture { model, var } => {
+                write!(f, "No resolution for texture #{} in model {}", var, model)
+            }
+            Self::MissingTexture { model, texture } => write!(
+                f,
+                "Texture not in texture table: {} (used by model {})",
+                texture, model
+            ),
+            Self::InvalidFaceRotation { model, rotation } => {
+                write!(f, "Invalid face rotation: {} in model {}", rotation, model)
+            }
+            Self::MissingFluidTexture(texture) => {
+                write!(f, "Texture not in texture table: {}", texture)
+            }
+        }
+    }
+}
+
+/// Collects the [`BakeWarning`]s from bakeries that may be running on several
+/// threads at once.
+#[derive(Debug, Default)]
+pub struct BakeWarnings {
+    warnings: Mutex<Vec<BakeWarning>>,
+}
+
+impl BakeWarnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logs the warning and records it.
+    pub fn push(&self, warning: BakeWarning) {
+        warn!("{}", warning);
+        self.warnings.lock().unwrap().push(warning);
+    }
+
+    /// Returns the recorded warnings, sorted and without duplicates.
+    pub fn into_vec(self) -> Vec<BakeWarning> {
+        let mut warnings = self.warnings.into_inner().unwrap();
+        warnings.sort();
+        warnings.dedup();
+        warnings
+    }
+}
+
+/// Records `warning` in `warnings` if there is one, or just logs it otherwise.
+pub(crate) fn report(warnings: Option<&BakeWarnings>, warning: BakeWarning) {
+    match warnings {
+        Some(warnings) => warnings.push(warning),
+        None => warn!("{}", warning),
+    }
+}
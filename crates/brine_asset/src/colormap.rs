@@ -0,0 +1,458 @@
+//! Biome-dependent colors that some block textures are tinted with.
+//!
+//! Grass and leaves are drawn with grayscale textures that are multiplied by a
+//! color picked from a colormap (`textures/colormap/grass.png` and
+//! `textures/colormap/foliage.png`) using the temperature and downfall of the
+//! biome that the block is in. A few other blocks are tinted by a fixed color,
+//! by a color that depends on their state (e.g., the power of redstone wire),
+//! or, in the case of water, by a color that each biome picks.
+//!
+//! Only quads with a [`tint_index`][crate::BakedQuad::tint_index] of `0` are
+//! tinted.
+//!
+//! See <https://minecraft.fandom.com/wiki/Color#Biome_colors>.
+
+use std::{collections::HashMap, io, path::Path};
+
+use brine_data::{blocks::BlockState, BiomeId, BlockId, BlockStateId, MinecraftData};
+use image::RgbImage;
+use tracing::*;
+
+use crate::bakery::packs::AssetRoots;
+
+/// Width and height of a colormap texture.
+pub const COLORMAP_SIZE: u32 = 256;
+
+/// Grass color when there is no grass colormap.
+pub const DEFAULT_GRASS_COLOR: [u8; 3] = [0x91, 0xbd, 0x59];
+
+/// Foliage color when there is no foliage colormap.
+pub const DEFAULT_FOLIAGE_COLOR: [u8; 3] = [0x77, 0xab, 0x2f];
+
+/// Water color of every biome that doesn't pick its own.
+pub const DEFAULT_WATER_COLOR: [u8; 3] = [0x3f, 0x76, 0xe4];
+
+/// Temperature of biomes that aren't in the Minecraft data (that of plains).
+pub const DEFAULT_TEMPERATURE: f32 = 0.8;
+
+/// Downfall of biomes that aren't in the Minecraft data (that of plains).
+pub const DEFAULT_DOWNFALL: f32 = 0.4;
+
+/// A colormap texture that is indexed by temperature and downfall.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colormap {
+    image: RgbImage,
+}
+
+impl Colormap {
+    /// Returns `None` if the image isn't [`COLORMAP_SIZE`] pixels square.
+    pub fn from_image(image: RgbImage) -> Option<Self> {
+        (image.dimensions() == (COLORMAP_SIZE, COLORMAP_SIZE)).then(|| Self { image })
+    }
+
+    /// Loads `assets/minecraft/textures/colormap/<name>.png` from the asset
+    /// pack at `root`.
+    pub fn load(root: &Path, name: &str) -> io::Result<Self> {
+        let path = root
+            .join("assets/minecraft/textures/colormap")
+            .join(name)
+            .with_extension("png");
+        let image = image::open(&path)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            .into_rgb8();
+
+        Self::from_image(image).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} is not {}x{}",
+                    path.display(),
+                    COLORMAP_SIZE,
+                    COLORMAP_SIZE
+                ),
+            )
+        })
+    }
+
+    /// Returns the color for the given temperature and downfall.
+    pub fn get(&self, temperature: f32, downfall: f32) -> [u8; 3] {
+        let (x, y) = colormap_coords(temperature, downfall);
+
+        self.image.get_pixel(x, y).0
+    }
+}
+
+/// Returns the pixel of a colormap that holds the color for the given
+/// temperature and downfall.
+///
+/// Both are clamped to `0.0..=1.0`, and the downfall is scaled by the
+/// temperature, so only the lower left triangle of the colormap is used. The
+/// math is done in `f64` like in Minecraft, since the rounding can differ by a
+/// pixel otherwise.
+pub fn colormap_coords(temperature: f32, downfall: f32) -> (u32, u32) {
+    let temperature = temperature.clamp(0.0, 1.0) as f64;
+    let downfall = downfall.clamp(0.0, 1.0) as f64 * temperature;
+
+    let x = ((1.0 - temperature) * 255.0) as u32;
+    let y = ((1.0 - downfall) * 255.0) as u32;
+
+    (x, y)
+}
+
+/// The grass and foliage colormaps of a set of asset packs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Colormaps {
+    pub grass: Option<Colormap>,
+    pub foliage: Option<Colormap>,
+}
+
+impl Colormaps {
+    /// Loads each colormap from the highest-priority root that has it.
+    pub fn load(roots: &AssetRoots) -> Self {
+        let load = |name: &str| {
+            let colormap = (0..roots.len())
+                .rev()
+                .filter_map(|index| roots.get(index))
+                .find_map(|root| Colormap::load(root, name).ok());
+
+            if colormap.is_none() {
+                debug!("No {} colormap loaded", name);
+            }
+
+            colormap
+        };
+
+        Self {
+            grass: load("grass"),
+            foliage: load("foliage"),
+        }
+    }
+
+    pub fn grass_color(&self, temperature: f32, downfall: f32) -> [u8; 3] {
+        self.grass
+            .as_ref()
+            .map(|grass| grass.get(temperature, downfall))
+            .unwrap_or(DEFAULT_GRASS_COLOR)
+    }
+
+    pub fn foliage_color(&self, temperature: f32, downfall: f32) -> [u8; 3] {
+        self.foliage
+            .as_ref()
+            .map(|foliage| foliage.get(temperature, downfall))
+            .unwrap_or(DEFAULT_FOLIAGE_COLOR)
+    }
+}
+
+/// How a block state's tinted quads are colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTint {
+    /// By the grass colormap.
+    Grass,
+
+    /// By the foliage colormap.
+    Foliage,
+
+    /// By the water color of the biome.
+    Water,
+
+    /// By the same color in every biome.
+    Color([u8; 3]),
+}
+
+impl BlockTint {
+    /// Returns how the given block state is tinted, or `None` if it isn't.
+    pub fn for_block(name: &str, state: &BlockState<'_>) -> Option<Self> {
+        let int_property = |property: &str| {
+            state
+                .get(property)
+                .and_then(|value| value.as_int())
+                .unwrap_or(0)
+        };
+
+        let tint = match name {
+            "grass_block" | "grass" | "fern" | "tall_grass" | "large_fern" | "potted_fern"
+            | "sugar_cane" => Self::Grass,
+
+            "oak_leaves" | "jungle_leaves" | "acacia_leaves" | "dark_oak_leaves" | "vine" => {
+                Self::Foliage
+            }
+
+            "water" | "bubble_column" => Self::Water,
+
+            "spruce_leaves" => Self::Color([0x61, 0x99, 0x61]),
+            "birch_leaves" => Self::Color([0x80, 0xa7, 0x55]),
+            "lily_pad" => Self::Color([0x20, 0x80, 0x30]),
+            "attached_melon_stem" | "attached_pumpkin_stem" => Self::Color([0xe0, 0xc7, 0x1c]),
+
+            "melon_stem" | "pumpkin_stem" => Self::Color(stem_color(int_property("age"))),
+            "redstone_wire" => Self::Color(redstone_wire_color(int_property("power"))),
+
+            _ => return None,
+        };
+
+        Some(tint)
+    }
+}
+
+/// Stems turn from green to yellow as they grow.
+fn stem_color(age: i32) -> [u8; 3] {
+    let age = age.clamp(0, 7) as u8;
+
+    [age * 32, 255 - age * 8, age * 4]
+}
+
+/// Redstone wire turns from dark to bright red as its power increases.
+fn redstone_wire_color(power: i32) -> [u8; 3] {
+    let f = power.clamp(0, 15) as f32 / 15.0;
+
+    let red = if power <= 0 { 0.3 } else { f * 0.6 + 0.4 };
+    let green = (f * f * 0.7 - 0.5).max(0.0);
+    let blue = (f * f * 0.6 - 0.7).max(0.0);
+
+    [red, green, blue].map(|channel| (channel * 255.0) as u8)
+}
+
+/// Returns the water color of the biome with the given name.
+pub fn water_color(biome_name: &str) -> [u8; 3] {
+    match biome_name {
+        "swamp" | "swamp_hills" => [0x61, 0x7b, 0x64],
+        "warm_ocean" | "deep_warm_ocean" => [0x43, 0xd5, 0xee],
+        "lukewarm_ocean" | "deep_lukewarm_ocean" => [0x45, 0xad, 0xf2],
+        "cold_ocean" | "deep_cold_ocean" => [0x3d, 0x57, 0xd6],
+        "frozen_ocean" | "deep_frozen_ocean" | "frozen_river" => [0x39, 0x38, 0xc9],
+        _ => DEFAULT_WATER_COLOR,
+    }
+}
+
+/// The colors that a biome tints blocks with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BiomeColors {
+    pub grass: [u8; 3],
+    pub foliage: [u8; 3],
+    pub water: [u8; 3],
+}
+
+impl BiomeColors {
+    pub fn new(colormaps: &Colormaps, temperature: f32, downfall: f32, name: &str) -> Self {
+        Self {
+            grass: colormaps.grass_color(temperature, downfall),
+            foliage: colormaps.foliage_color(temperature, downfall),
+            water: water_color(name),
+        }
+    }
+
+    pub fn get(&self, tint: BlockTint) -> [u8; 3] {
+        match tint {
+            BlockTint::Grass => self.grass,
+            BlockTint::Foliage => self.foliage,
+            BlockTint::Water => self.water,
+            BlockTint::Color(color) => color,
+        }
+    }
+}
+
+/// Provides the tint of every tinted block state in every biome.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TintTable {
+    block_tints: HashMap<BlockStateId, BlockTint>,
+    biome_colors: HashMap<BiomeId, BiomeColors>,
+
+    /// Colors of biomes that aren't in the Minecraft data.
+    default_colors: BiomeColors,
+}
+
+impl TintTable {
+    /// Works out the tint of every block state and the colors of every biome
+    /// in `data`.
+    pub fn new(colormaps: &Colormaps, data: &MinecraftData) -> Self {
+        Self {
+            block_tints: Self::tints_of_blocks(data),
+            biome_colors: Self::colors_of_biomes(colormaps, data),
+            default_colors: BiomeColors::new(
+                colormaps,
+                DEFAULT_TEMPERATURE,
+                DEFAULT_DOWNFALL,
+                "plains",
+            ),
+        }
+    }
+
+    fn tints_of_blocks(data: &MinecraftData) -> HashMap<BlockStateId, BlockTint> {
+        let blocks = data.blocks();
+
+        blocks
+            .iter_blocks()
+            .filter(|block| BlockTint::for_block(block.name, &block.state).is_some())
+            .flat_map(|block| blocks.iter_states_for_block(BlockId(block.id)).unwrap())
+            .filter_map(|(block_state_id, block)| {
+                let tint = BlockTint::for_block(block.name, &block.state)?;
+                Some((block_state_id, tint))
+            })
+            .collect()
+    }
+
+    fn colors_of_biomes(
+        colormaps: &Colormaps,
+        data: &MinecraftData,
+    ) -> HashMap<BiomeId, BiomeColors> {
+        data.biomes()
+            .iter()
+            .map(|biome| {
+                let colors =
+                    BiomeColors::new(colormaps, biome.temperature, biome.downfall, biome.name);
+                (biome.id, colors)
+            })
+            .collect()
+    }
+
+    /// Returns how the block state is tinted, or `None` if it isn't.
+    #[inline]
+    pub fn block_tint(&self, block_state_id: BlockStateId) -> Option<BlockTint> {
+        self.block_tints.get(&block_state_id).copied()
+    }
+
+    /// Returns the colors of the biome, or those of plains if there is no such
+    /// biome.
+    #[inline]
+    pub fn biome_colors(&self, biome_id: BiomeId) -> &BiomeColors {
+        self.biome_colors
+            .get(&biome_id)
+            .unwrap_or(&self.default_colors)
+    }
+
+    /// Returns the color that the block state is tinted with in the biome, or
+    /// `None` if it isn't tinted.
+    #[inline]
+    pub fn get(&self, block_state_id: BlockStateId, biome_id: BiomeId) -> Option<[u8; 3]> {
+        let tint = self.block_tint(block_state_id)?;
+
+        Some(self.biome_colors(biome_id).get(tint))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use image::Rgb;
+
+    use super::*;
+
+    /// A colormap whose pixels hold their own coordinates.
+    fn coords_colormap() -> Colormap {
+        let image = RgbImage::from_fn(COLORMAP_SIZE, COLORMAP_SIZE, |x, y| {
+            Rgb([x as u8, y as u8, 0])
+        });
+
+        Colormap::from_image(image).unwrap()
+    }
+
+    fn coords_colormaps() -> Colormaps {
+        Colormaps {
+            grass: Some(coords_colormap()),
+            foliage: Some(coords_colormap()),
+        }
+    }
+
+    fn biome_id(data: &MinecraftData, name: &str) -> BiomeId {
+        data.biomes().get_by_name(name).unwrap().id
+    }
+
+    fn state_id(data: &MinecraftData, name: &str, props: &[(&str, &str)]) -> BlockStateId {
+        data.blocks().get_state_id(name, props).unwrap()
+    }
+
+    #[test]
+    fn colormap_coords_of_vanilla_biomes() {
+        // plains, swamp, jungle, desert, and snowy_tundra.
+        assert_eq!(colormap_coords(0.8, 0.4), (50, 173));
+        assert_eq!(colormap_coords(0.8, 0.9), (50, 71));
+        assert_eq!(colormap_coords(0.95, 0.9), (12, 36));
+        assert_eq!(colormap_coords(2.0, 0.0), (0, 255));
+        assert_eq!(colormap_coords(0.0, 0.5), (255, 255));
+    }
+
+    #[test]
+    fn colormap_lookup() {
+        let colormaps = coords_colormaps();
+
+        assert_eq!(colormaps.grass_color(0.8, 0.4), [50, 173, 0]);
+        assert_eq!(colormaps.foliage_color(2.0, 0.0), [0, 255, 0]);
+
+        assert_eq!(
+            Colormaps::default().grass_color(0.8, 0.4),
+            DEFAULT_GRASS_COLOR
+        );
+        assert_eq!(
+            Colormaps::default().foliage_color(0.8, 0.4),
+            DEFAULT_FOLIAGE_COLOR
+        );
+    }
+
+    #[test]
+    fn colormap_must_be_square() {
+        assert!(Colormap::from_image(RgbImage::new(256, 128)).is_none());
+        assert!(Colormap::load(Path::new("no/such/pack"), "grass").is_err());
+    }
+
+    #[test]
+    fn state_dependent_tints() {
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+        let tint = |state_id| {
+            let block = blocks.get_by_state_id(state_id).unwrap();
+            BlockTint::for_block(block.name, &block.state)
+        };
+
+        // A fully grown stem is the color of an attached one.
+        let stem = state_id(&data, "pumpkin_stem", &[("age", "7")]);
+        let attached_stem = state_id(&data, "attached_pumpkin_stem", &[]);
+        assert_eq!(tint(stem), Some(BlockTint::Color([0xe0, 0xc7, 0x1c])));
+        assert_eq!(tint(stem), tint(attached_stem));
+        assert_eq!(
+            tint(state_id(&data, "melon_stem", &[("age", "0")])),
+            Some(BlockTint::Color([0, 255, 0]))
+        );
+
+        let unpowered = state_id(&data, "redstone_wire", &[("power", "0")]);
+        assert_eq!(tint(unpowered), Some(BlockTint::Color([76, 0, 0])));
+        let powered = state_id(&data, "redstone_wire", &[("power", "15")]);
+        assert!(matches!(tint(powered), Some(BlockTint::Color([255, _, 0]))));
+
+        assert_eq!(tint(state_id(&data, "stone", &[])), None);
+    }
+
+    #[test]
+    fn tint_table() {
+        let data = MinecraftData::for_version("1.14.4");
+        let table = TintTable::new(&coords_colormaps(), &data);
+
+        let plains = biome_id(&data, "plains");
+        let swamp = biome_id(&data, "swamp");
+        let desert = biome_id(&data, "desert");
+
+        let grass_block = state_id(&data, "grass_block", &[("snowy", "false")]);
+        assert_eq!(table.get(grass_block, plains), Some([50, 173, 0]));
+        assert_eq!(table.get(grass_block, swamp), Some([50, 71, 0]));
+
+        let oak_leaves = state_id(&data, "oak_leaves", &[]);
+        assert_eq!(table.get(oak_leaves, desert), Some([0, 255, 0]));
+
+        let spruce_leaves = state_id(&data, "spruce_leaves", &[]);
+        assert_eq!(table.get(spruce_leaves, desert), Some([0x61, 0x99, 0x61]));
+
+        let water = state_id(&data, "water", &[("level", "0")]);
+        assert_eq!(table.get(water, plains), Some(DEFAULT_WATER_COLOR));
+        assert_eq!(table.get(water, swamp), Some([0x61, 0x7b, 0x64]));
+        assert_eq!(
+            table.get(water, biome_id(&data, "warm_ocean")),
+            Some([0x43, 0xd5, 0xee])
+        );
+
+        assert_eq!(table.get(state_id(&data, "stone", &[]), plains), None);
+
+        // Unknown biomes are treated like plains.
+        assert_eq!(
+            table.get(grass_block, BiomeId(u16::MAX)),
+            Some([50, 173, 0])
+        );
+    }
+}
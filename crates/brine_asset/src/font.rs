@@ -0,0 +1,511 @@
+//! The bitmap font that Minecraft draws text with.
+//!
+//! The font is defined by `assets/minecraft/font/default.json`, as a list of
+//! glyph providers. Two kinds of providers are supported:
+//!
+//! * `bitmap` providers, which lay glyphs out in a grid on a texture such as
+//!   `font/ascii.png`. The width of each glyph is found by looking for its
+//!   rightmost non-transparent column of pixels.
+//! * The `legacy_unicode` provider, which covers the rest of the Basic
+//!   Multilingual Plane with one texture per 256 code points
+//!   (`font/unicode_page_XX.png`) and a file listing the width of each glyph.
+//!
+//! When more than one provider has a glyph for a character, the first one in
+//! the list wins.
+//!
+//! See <https://minecraft.fandom.com/wiki/Resource_Pack#Fonts>.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use minecraft_assets::api::ResourceIdentifier;
+use serde::Deserialize;
+use tracing::*;
+
+use crate::bakery::textures::{TextureKey, TextureTable};
+
+/// Horizontal advance of the space character, which has no pixels to measure.
+pub const SPACE_ADVANCE: f32 = 4.0;
+
+/// A single character of the font.
+///
+/// All measurements are in font pixels, of which a line of regular text is 8
+/// tall.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    /// Texture that contains the glyph.
+    pub texture_key: TextureKey,
+
+    /// Region of the texture that the glyph occupies, as `[min, max]`, from
+    /// `0.0` to `1.0`.
+    pub uv: [[f32; 2]; 2],
+
+    /// Distance from the start of this glyph to the start of the next one.
+    pub advance: f32,
+
+    /// Width of the visible part of the glyph.
+    pub width: f32,
+
+    pub height: f32,
+
+    /// Distance from the top of the glyph down to the baseline.
+    pub ascent: f32,
+}
+
+/// The contents of a font definition like `font/default.json`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FontDefinition {
+    pub providers: Vec<GlyphProvider>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GlyphProvider {
+    Bitmap {
+        /// Texture file, e.g., `minecraft:font/ascii.png`.
+        file: String,
+
+        #[serde(default = "default_bitmap_height")]
+        height: f32,
+
+        ascent: f32,
+
+        /// Rows of the grid of glyphs. `\u0000` marks an empty cell.
+        chars: Vec<String>,
+    },
+    LegacyUnicode {
+        /// Glyph widths file, e.g., `minecraft:font/glyph_sizes.bin`.
+        sizes: String,
+
+        /// Texture file of each page, with `%s` in place of the page number,
+        /// e.g., `minecraft:font/unicode_page_%s.png`.
+        template: String,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+fn default_bitmap_height() -> f32 {
+    8.0
+}
+
+impl FontDefinition {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Provides the glyphs of the default font.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Font {
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl Font {
+    /// Loads `assets/minecraft/font/default.json` from the asset pack at
+    /// `root`, along with the textures and glyph widths that it refers to.
+    pub fn load(root: &Path, texture_table: &TextureTable) -> io::Result<Self> {
+        let path = root.join("assets/minecraft/font/default.json");
+        let definition = FontDefinition::from_json(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut font = Self::default();
+
+        for provider in definition.providers.iter() {
+            let glyphs = match provider {
+                GlyphProvider::Bitmap {
+                    file,
+                    height,
+                    ascent,
+                    chars,
+                } => {
+                    let texture_key = texture_key_for_file(texture_table, file)?;
+                    let image = image::open(resource_path(root, "textures", file))
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+                        .into_rgba8();
+                    bitmap_glyphs(&image, texture_key, *height, *ascent, chars)
+                }
+                GlyphProvider::LegacyUnicode { sizes, template } => {
+                    let sizes = fs::read(resource_path(root, "", sizes))?;
+                    legacy_unicode_glyphs(&sizes, |page| {
+                        let file = template.replace("%s", &format!("{:02x}", page));
+                        texture_key_for_file(texture_table, &file).ok()
+                    })
+                }
+                GlyphProvider::Unsupported => {
+                    debug!("Skipping unsupported glyph provider");
+                    continue;
+                }
+            };
+
+            font.add_glyphs(glyphs);
+        }
+
+        Ok(font)
+    }
+
+    /// Adds glyphs for the characters that don't have one yet.
+    pub fn add_glyphs(&mut self, glyphs: impl IntoIterator<Item = (char, Glyph)>) {
+        for (c, glyph) in glyphs {
+            self.glyphs.entry(c).or_insert(glyph);
+        }
+    }
+
+    pub fn get_glyph(&self, c: char) -> Option<Glyph> {
+        self.glyphs.get(&c).copied()
+    }
+
+    /// Returns every texture that contains glyphs, without duplicates.
+    pub fn textures(&self) -> Vec<TextureKey> {
+        let mut textures: Vec<TextureKey> = self
+            .glyphs
+            .values()
+            .map(|glyph| glyph.texture_key)
+            .collect();
+        textures.sort_by_key(|texture| texture.0);
+        textures.dedup();
+        textures
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+}
+
+/// Strips the namespace from a resource location like
+/// `minecraft:font/ascii.png`.
+fn strip_namespace(location: &str) -> &str {
+    location.split_once(':').map_or(location, |(_, path)| path)
+}
+
+/// Returns the path of the file at `location` in the `kind` directory (e.g.,
+/// `textures`) of the asset pack at `root`.
+fn resource_path(root: &Path, kind: &str, location: &str) -> PathBuf {
+    root.join("assets/minecraft")
+        .join(kind)
+        .join(strip_namespace(location))
+}
+
+fn texture_key_for_file(texture_table: &TextureTable, file: &str) -> io::Result<TextureKey> {
+    let name = strip_namespace(file).trim_end_matches(".png");
+
+    texture_table
+        .get_key(&ResourceIdentifier::texture(name))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Texture not in texture table: {}", name),
+            )
+        })
+}
+
+/// Returns the width, in pixels, of the glyph in the cell at the given
+/// position, i.e., one more than its rightmost column with a visible pixel.
+pub fn glyph_width(
+    image: &image::RgbaImage,
+    cell_x: u32,
+    cell_y: u32,
+    cell_width: u32,
+    cell_height: u32,
+) -> u32 {
+    (0..cell_width)
+        .rev()
+        .find(|column| {
+            (0..cell_height).any(|row| {
+                let pixel =
+                    image.get_pixel(cell_x * cell_width + column, cell_y * cell_height + row);
+                pixel[3] != 0
+            })
+        })
+        .map_or(0, |column| column + 1)
+}
+
+/// Returns the glyphs of a `bitmap` provider whose texture is `image`.
+pub fn bitmap_glyphs(
+    image: &image::RgbaImage,
+    texture_key: TextureKey,
+    height: f32,
+    ascent: f32,
+    chars: &[String],
+) -> Vec<(char, Glyph)> {
+    let rows = chars.len() as u32;
+    let columns = chars
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0) as u32;
+    if rows == 0 || columns == 0 {
+        return Vec::new();
+    }
+
+    let (image_width, image_height) = image.dimensions();
+    let cell_width = image_width / columns;
+    let cell_height = image_height / rows;
+    let scale = height / cell_height as f32;
+
+    let mut glyphs = Vec::new();
+
+    for (cell_y, row) in (0..).zip(chars.iter()) {
+        for (cell_x, c) in (0..).zip(row.chars()) {
+            if c == '\0' {
+                continue;
+            }
+
+            let width = glyph_width(image, cell_x, cell_y, cell_width, cell_height);
+
+            let advance = if c == ' ' {
+                SPACE_ADVANCE
+            } else {
+                (width as f32 * scale).round() + 1.0
+            };
+
+            let min = [
+                (cell_x * cell_width) as f32 / image_width as f32,
+                (cell_y * cell_height) as f32 / image_height as f32,
+            ];
+            let max = [
+                (cell_x * cell_width + width) as f32 / image_width as f32,
+                ((cell_y + 1) * cell_height) as f32 / image_height as f32,
+            ];
+
+            glyphs.push((
+                c,
+                Glyph {
+                    texture_key,
+                    uv: [min, max],
+                    advance,
+                    width: width as f32 * scale,
+                    height,
+                    ascent,
+                },
+            ));
+        }
+    }
+
+    glyphs
+}
+
+/// Returns the glyphs of the `legacy_unicode` provider, given the contents of
+/// its glyph widths file and a way to find the texture of each page.
+///
+/// Each byte of `sizes` describes one code point: the high nibble is the first
+/// column of the glyph in its 16 by 16 cell, and the low nibble is the last.
+/// Code points whose byte is 0, or whose page has no texture, have no glyph.
+pub fn legacy_unicode_glyphs(
+    sizes: &[u8],
+    mut page_texture: impl FnMut(u32) -> Option<TextureKey>,
+) -> Vec<(char, Glyph)> {
+    const CELL: u32 = 16;
+    const PAGE: f32 = 256.0;
+    const SCALE: f32 = 0.5;
+
+    let mut page_textures: HashMap<u32, Option<TextureKey>> = HashMap::new();
+    let mut glyphs = Vec::new();
+
+    for (code_point, size) in (0u32..).zip(sizes.iter().copied()) {
+        if size == 0 {
+            continue;
+        }
+
+        let c = match char::from_u32(code_point) {
+            Some(c) => c,
+            None => continue,
+        };
+
+        let page = code_point >> 8;
+        let texture_key = match *page_textures
+            .entry(page)
+            .or_insert_with(|| page_texture(page))
+        {
+            Some(texture_key) => texture_key,
+            None => continue,
+        };
+
+        let start = (size >> 4) as u32;
+        let end = (size & 0xf) as u32 + 1;
+        let width = end.saturating_sub(start);
+
+        let cell_x = (code_point & 0xf) * CELL;
+        let cell_y = ((code_point >> 4) & 0xf) * CELL;
+
+        glyphs.push((
+            c,
+            Glyph {
+                texture_key,
+                uv: [
+                    [(cell_x + start) as f32 / PAGE, cell_y as f32 / PAGE],
+                    [(cell_x + end) as f32 / PAGE, (cell_y + CELL) as f32 / PAGE],
+                ],
+                advance: width as f32 * SCALE + 1.0,
+                width: width as f32 * SCALE,
+                height: CELL as f32 * SCALE,
+                ascent: 7.0,
+            },
+        ));
+    }
+
+    glyphs
+}
+
+#[cfg(test)]
+mod test {
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    #[test]
+    fn parse_definition() {
+        let json = r#"{
+            "providers": [
+                {
+                    "type": "bitmap",
+                    "file": "minecraft:font/ascii.png",
+                    "ascent": 7,
+                    "chars": ["\u0000 !", "abc"]
+                },
+                {
+                    "type": "bitmap",
+                    "file": "minecraft:font/accented.png",
+                    "height": 12,
+                    "ascent": 10,
+                    "chars": ["À"]
+                },
+                {
+                    "type": "legacy_unicode",
+                    "sizes": "minecraft:font/glyph_sizes.bin",
+                    "template": "minecraft:font/unicode_page_%s.png"
+                },
+                { "type": "ttf", "file": "minecraft:font/some.ttf" }
+            ]
+        }"#;
+
+        assert_eq!(
+            FontDefinition::from_json(json).unwrap().providers,
+            vec![
+                GlyphProvider::Bitmap {
+                    file: String::from("minecraft:font/ascii.png"),
+                    height: 8.0,
+                    ascent: 7.0,
+                    chars: vec![String::from("\0 !"), String::from("abc")],
+                },
+                GlyphProvider::Bitmap {
+                    file: String::from("minecraft:font/accented.png"),
+                    height: 12.0,
+                    ascent: 10.0,
+                    chars: vec![String::from("\u{c0}")],
+                },
+                GlyphProvider::LegacyUnicode {
+                    sizes: String::from("minecraft:font/glyph_sizes.bin"),
+                    template: String::from("minecraft:font/unicode_page_%s.png"),
+                },
+                GlyphProvider::Unsupported,
+            ]
+        );
+    }
+
+    /// Makes a texture with 8x8 cells in which `widths[i]` columns of the
+    /// `i`th cell are filled in.
+    fn bitmap(widths: &[u32]) -> RgbaImage {
+        let mut image = RgbaImage::new(8 * widths.len() as u32, 8);
+        for (cell, width) in widths.iter().enumerate() {
+            for x in 0..*width {
+                image.put_pixel(cell as u32 * 8 + x, 3, Rgba([255, 255, 255, 255]));
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn bitmap_advances() {
+        // Like `!`, `a`, and `i` in ascii.png, plus a space.
+        let image = bitmap(&[1, 5, 0, 1]);
+        let chars = [String::from("!a i")];
+        let glyphs: HashMap<char, Glyph> = bitmap_glyphs(&image, TextureKey(3), 8.0, 7.0, &chars)
+            .into_iter()
+            .collect();
+
+        assert_eq!(glyphs[&'!'].advance, 2.0);
+        assert_eq!(glyphs[&'a'].advance, 6.0);
+        assert_eq!(glyphs[&' '].advance, SPACE_ADVANCE);
+        assert_eq!(glyphs[&'i'].advance, 2.0);
+
+        let a = glyphs[&'a'];
+        assert_eq!(a.texture_key, TextureKey(3));
+        assert_eq!(a.width, 5.0);
+        assert_eq!(a.uv, [[0.25, 0.0], [13.0 / 32.0, 1.0]]);
+    }
+
+    #[test]
+    fn bitmap_glyphs_are_scaled_to_height() {
+        // 16x16 cells drawn 8 pixels tall, as in high resolution packs.
+        let mut image = RgbaImage::new(16, 16);
+        for x in 0..10 {
+            image.put_pixel(x, 0, Rgba([0, 0, 0, 255]));
+        }
+
+        let glyphs = bitmap_glyphs(&image, TextureKey(0), 8.0, 7.0, &[String::from("a")]);
+
+        assert_eq!(glyphs[0].1.width, 5.0);
+        assert_eq!(glyphs[0].1.advance, 6.0);
+    }
+
+    #[test]
+    fn empty_cells_have_no_glyph() {
+        let image = bitmap(&[3, 3]);
+        let glyphs = bitmap_glyphs(&image, TextureKey(0), 8.0, 7.0, &[String::from("\0b")]);
+
+        assert_eq!(glyphs.len(), 1);
+        assert_eq!(glyphs[0].0, 'b');
+    }
+
+    #[test]
+    fn legacy_unicode_advances() {
+        let mut sizes = vec![0; 0x4e01];
+        // Columns 0 through 14.
+        sizes[0x4e00] = 0x0e;
+        // Columns 2 through 5.
+        sizes[0x41] = 0x25;
+
+        let glyphs: HashMap<char, Glyph> = legacy_unicode_glyphs(&sizes, |page| {
+            (page == 0x4e).then(|| TextureKey(page as usize))
+        })
+        .into_iter()
+        .collect();
+
+        // The glyph for `A` is on a page without a texture.
+        assert_eq!(glyphs.len(), 1);
+
+        let glyph = glyphs[&'\u{4e00}'];
+        assert_eq!(glyph.texture_key, TextureKey(0x4e));
+        assert_eq!(glyph.width, 7.5);
+        assert_eq!(glyph.advance, 8.5);
+        assert_eq!(glyph.uv, [[0.0, 0.0], [15.0 / 256.0, 16.0 / 256.0]]);
+    }
+
+    #[test]
+    fn earlier_providers_win() {
+        let glyph = |advance| Glyph {
+            texture_key: TextureKey(0),
+            uv: [[0.0, 0.0], [1.0, 1.0]],
+            advance,
+            width: advance - 1.0,
+            height: 8.0,
+            ascent: 7.0,
+        };
+
+        let mut font = Font::default();
+        font.add_glyphs([('a', glyph(6.0))]);
+        font.add_glyphs([('a', glyph(9.0)), ('b', glyph(4.0))]);
+
+        assert_eq!(font.get_glyph('a').unwrap().advance, 6.0);
+        assert_eq!(font.get_glyph('b').unwrap().advance, 4.0);
+        assert_eq!(font.get_glyph('c'), None);
+    }
+}
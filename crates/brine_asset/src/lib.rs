@@ -3,10 +3,16 @@
 
 pub mod api;
 pub mod bakery;
+pub mod colormap;
+pub mod font;
 
 pub use api::{BlockFace, MinecraftAssets};
 pub use bakery::{
-    block_states::BakedBlockStateTable,
+    block_states::{BakedBlockState, BakedBlockStateTable},
+    items::BakedItemTable,
     models::{BakedModel, BakedModelKey, BakedModelTable, BakedQuad},
+    packs::AssetRoots,
     textures::{TextureKey, TextureTable},
 };
+pub use colormap::{BlockTint, Colormap, Colormaps, TintTable};
+pub use font::{Font, Glyph};
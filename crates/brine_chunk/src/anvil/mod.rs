@@ -0,0 +1,455 @@
+//! Reading chunks from the Anvil region files (`.mca`) of a vanilla world.
+//!
+//! A region file holds up to 32x32 chunks. It starts with a table of where each
+//! chunk is stored in the file, and each chunk is stored as compressed
+//! [NBT][crate::nbt].
+//!
+//! Only the chunk format used between 1.13 and 1.15 (which includes 1.14.4) is
+//! supported: each section has its own palette of block names, and block
+//! states are packed into a long array that lets entries span two longs, just
+//! like the network format.
+//!
+//! Block states in region files are identified by name and properties rather
+//! than by ID, so converting a chunk requires a lookup function that maps them
+//! to the [`BlockState`] IDs of the Minecraft version being viewed (see
+//! `brine_data`'s `Blocks::get_state_id`).
+//!
+//! See <https://minecraft.fandom.com/wiki/Region_file_format?oldid=1676399> and
+//! <https://minecraft.fandom.com/wiki/Chunk_format?oldid=1646203>.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use byteorder::{BigEndian, ReadBytesExt};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use tracing::warn;
+
+use crate::{
+    decode::PackedIntSlice,
+    nbt::{self, Tag},
+    BiomeId, BlockState, Chunk, ChunkSection, BLOCKS_PER_SECTION, LIGHT_ARRAY_LENGTH,
+    SECTIONS_PER_CHUNK,
+};
+
+/// Number of chunks along each horizontal axis of a region.
+pub const REGION_WIDTH: usize = 32;
+
+/// Region files are divided into sectors of this many bytes.
+const SECTOR_SIZE: u64 = 4096;
+
+const COMPRESSION_GZIP: u8 = 1;
+const COMPRESSION_ZLIB: u8 = 2;
+const COMPRESSION_NONE: u8 = 3;
+
+/// Earliest data version with section palettes (17w47a, "the flattening").
+const MIN_DATA_VERSION: i32 = 1451;
+
+/// Latest data version whose block states may span two longs. Starting with
+/// 20w17a they are padded instead.
+const MAX_DATA_VERSION: i32 = 2528;
+
+/// Block names that are not counted in [`ChunkSection::block_count`].
+const AIR_BLOCKS: &[&str] = &["minecraft:air", "minecraft:cave_air", "minecraft:void_air"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("unknown chunk compression type {0}")]
+    UnknownCompression(u8),
+
+    #[error(transparent)]
+    Nbt(#[from] nbt::Error),
+
+    #[error("missing or malformed tag: {0}")]
+    MissingTag(&'static str),
+
+    #[error(
+        "unsupported data version {0} (expected {}..={})",
+        MIN_DATA_VERSION,
+        MAX_DATA_VERSION
+    )]
+    UnsupportedDataVersion(i32),
+
+    #[error("invalid chunk data: {0}")]
+    InvalidData(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An open region file.
+pub struct RegionFile<R> {
+    reader: R,
+
+    /// Location of each chunk in the file, as (first sector, sector count).
+    locations: Box<[(u32, u8); REGION_WIDTH * REGION_WIDTH]>,
+}
+
+impl RegionFile<BufReader<File>> {
+    /// Opens the region file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> RegionFile<R> {
+    /// Reads the header of a region file.
+    pub fn new(mut reader: R) -> Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut locations = Box::new([(0, 0); REGION_WIDTH * REGION_WIDTH]);
+        for location in locations.iter_mut() {
+            let entry = reader.read_u32::<BigEndian>()?;
+            *location = (entry >> 8, (entry & 0xFF) as u8);
+        }
+
+        Ok(Self { reader, locations })
+    }
+
+    /// Returns the coordinates, relative to the region, of every chunk stored
+    /// in the region file.
+    pub fn chunks(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.locations
+            .iter()
+            .enumerate()
+            .filter(|(_, (offset, _))| *offset != 0)
+            .map(|(index, _)| ((index % REGION_WIDTH) as u8, (index / REGION_WIDTH) as u8))
+    }
+
+    /// Reads and decompresses the NBT of the chunk at the given coordinates
+    /// relative to the region.
+    ///
+    /// Returns `None` if that chunk is not stored in the region file.
+    pub fn read_chunk_nbt(&mut self, local_x: u8, local_z: u8) -> Result<Option<Tag>> {
+        let index =
+            local_z as usize % REGION_WIDTH * REGION_WIDTH + local_x as usize % REGION_WIDTH;
+        let (offset, sector_count) = self.locations[index];
+        if offset == 0 {
+            return Ok(None);
+        }
+
+        self.reader
+            .seek(SeekFrom::Start(offset as u64 * SECTOR_SIZE))?;
+
+        let length = self.reader.read_u32::<BigEndian>()? as u64;
+        if length == 0 || length + 4 > sector_count as u64 * SECTOR_SIZE {
+            return Err(Error::InvalidData("chunk length exceeds its sectors"));
+        }
+
+        let compression = self.reader.read_u8()?;
+        let mut compressed = (&mut self.reader).take(length - 1);
+
+        let (_, tag) = match compression {
+            COMPRESSION_GZIP => Tag::read_named(&mut GzDecoder::new(&mut compressed))?,
+            COMPRESSION_ZLIB => Tag::read_named(&mut ZlibDecoder::new(&mut compressed))?,
+            COMPRESSION_NONE => Tag::read_named(&mut compressed)?,
+            other => return Err(Error::UnknownCompression(other)),
+        };
+
+        Ok(Some(tag))
+    }
+
+    /// Reads the chunk at the given coordinates relative to the region.
+    ///
+    /// See [`chunk_from_nbt`] for how block states are looked up.
+    ///
+    /// Returns `None` if that chunk is not stored in the region file.
+    pub fn read_chunk(
+        &mut self,
+        local_x: u8,
+        local_z: u8,
+        lookup: impl FnMut(&str, &[(&str, &str)]) -> Option<BlockState>,
+    ) -> Result<Option<Chunk>> {
+        self.read_chunk_nbt(local_x, local_z)?
+            .map(|nbt| chunk_from_nbt(&nbt, lookup))
+            .transpose()
+    }
+}
+
+/// Converts the NBT of a chunk from a region file into a [`Chunk`].
+///
+/// `lookup` is called with the name and sorted properties of each entry in a
+/// section's palette, and returns the corresponding block state. Entries that
+/// it can't find are replaced with air (and a warning is logged).
+///
+/// Sections that only hold light data are skipped.
+pub fn chunk_from_nbt(
+    nbt: &Tag,
+    mut lookup: impl FnMut(&str, &[(&str, &str)]) -> Option<BlockState>,
+) -> Result<Chunk> {
+    let data_version = nbt
+        .get("DataVersion")
+        .and_then(Tag::as_int)
+        .ok_or(Error::MissingTag("DataVersion"))?;
+    if !(MIN_DATA_VERSION..=MAX_DATA_VERSION).contains(&data_version) {
+        return Err(Error::UnsupportedDataVersion(data_version));
+    }
+
+    let level = nbt.get("Level").ok_or(Error::MissingTag("Level"))?;
+    let chunk_x = level
+        .get("xPos")
+        .and_then(Tag::as_int)
+        .ok_or(Error::MissingTag("xPos"))?;
+    let chunk_z = level
+        .get("zPos")
+        .and_then(Tag::as_int)
+        .ok_or(Error::MissingTag("zPos"))?;
+
+    let mut chunk = Chunk::empty(chunk_x, chunk_z);
+
+    if let Some(biomes) = level.get("Biomes").and_then(Tag::as_int_array) {
+        let ids = &mut chunk.biomes.as_mut().unwrap().0;
+        if biomes.len() != ids.len() {
+            return Err(Error::InvalidData("wrong number of biomes"));
+        }
+        for (id, biome) in ids.iter_mut().zip(biomes) {
+            *id = BiomeId(*biome as u16);
+        }
+    }
+
+    let sections = match level.get("Sections") {
+        Some(sections) => sections.as_list().ok_or(Error::MissingTag("Sections"))?,
+        None => &[],
+    };
+
+    for section in sections {
+        if let Some(section) = section_from_nbt(section, &mut lookup)? {
+            chunk.sections.push(section);
+        }
+    }
+
+    chunk.sections.sort_by_key(|section| section.chunk_y);
+    chunk.sections.dedup_by_key(|section| section.chunk_y);
+
+    Ok(chunk)
+}
+
+fn section_from_nbt(
+    nbt: &Tag,
+    lookup: &mut impl FnMut(&str, &[(&str, &str)]) -> Option<BlockState>,
+) -> Result<Option<ChunkSection>> {
+    let chunk_y = nbt
+        .get("Y")
+        .and_then(Tag::as_byte)
+        .ok_or(Error::MissingTag("Y"))?;
+
+    let (palette, longs) = match (nbt.get("Palette"), nbt.get("BlockStates")) {
+        (Some(palette), Some(longs)) => (
+            palette.as_list().ok_or(Error::MissingTag("Palette"))?,
+            longs
+                .as_long_array()
+                .ok_or(Error::MissingTag("BlockStates"))?,
+        ),
+        // Light-only sections above and below the world.
+        _ => return Ok(None),
+    };
+
+    if chunk_y < 0 || chunk_y as usize >= SECTIONS_PER_CHUNK {
+        return Ok(None);
+    }
+
+    // Each entry of the palette, and whether it counts towards the block count.
+    let palette = palette
+        .iter()
+        .map(|entry| palette_entry_to_block_state(entry, lookup))
+        .collect::<Result<Vec<_>>>()?;
+
+    let words: Vec<u64> = longs.iter().map(|long| *long as u64).collect();
+    let bits_per_block = (words.len() * 64 / BLOCKS_PER_SECTION) as u8;
+    if palette.len() > 1 << bits_per_block.min(31) {
+        return Err(Error::InvalidData("palette doesn't fit in block states"));
+    }
+    let packed = PackedIntSlice::new(&words, BLOCKS_PER_SECTION, bits_per_block)
+        .map_err(|_| Error::InvalidData("wrong number of block states"))?;
+
+    let mut section = ChunkSection::empty(chunk_y as u8);
+    let mut block_count = 0;
+    for (block_state, index) in section.block_states.0.iter_mut().zip(packed.iter()) {
+        let (state, counted) = *palette
+            .get(index as usize)
+            .ok_or(Error::InvalidData("palette index out of range"))?;
+        *block_state = state;
+        block_count += counted as u16;
+    }
+    section.block_count = block_count;
+
+    section.block_light = light_from_nbt(nbt, "BlockLight")?;
+    section.sky_light = light_from_nbt(nbt, "SkyLight")?;
+
+    Ok(Some(section))
+}
+
+fn palette_entry_to_block_state(
+    entry: &Tag,
+    lookup: &mut impl FnMut(&str, &[(&str, &str)]) -> Option<BlockState>,
+) -> Result<(BlockState, bool)> {
+    let name = entry
+        .get("Name")
+        .and_then(Tag::as_str)
+        .ok_or(Error::MissingTag("Name"))?;
+
+    let mut properties = Vec::new();
+    if let Some(entries) = entry.get("Properties").and_then(Tag::as_compound) {
+        for (key, value) in entries {
+            let value = value.as_str().ok_or(Error::MissingTag("Properties"))?;
+            properties.push((key.as_str(), value));
+        }
+    }
+    properties.sort_unstable();
+
+    match lookup(name, &properties) {
+        Some(block_state) => Ok((block_state, !AIR_BLOCKS.contains(&name))),
+        None => {
+            warn!(
+                "Unknown block state {}{:?}, replacing with air",
+                name, properties
+            );
+            Ok((BlockState::AIR, false))
+        }
+    }
+}
+
+fn light_from_nbt(nbt: &Tag, name: &'static str) -> Result<Option<Box<[u8; LIGHT_ARRAY_LENGTH]>>> {
+    match nbt.get(name) {
+        Some(light) => {
+            let light = light.as_byte_array().ok_or(Error::MissingTag(name))?;
+            let light: [u8; LIGHT_ARRAY_LENGTH] = light
+                .try_into()
+                .map_err(|_| Error::InvalidData("wrong light array length"))?;
+            Ok(Some(Box::new(light)))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// A region file with two chunks:
+    ///
+    /// * Chunk (0, 0), zlib-compressed, with a light-only section at `Y = -1`
+    ///   and a section at `Y = 0` whose palette is air, stone, east-facing oak
+    ///   stairs, and an unknown block. The bottom layer is stone, the stairs
+    ///   are at (1, 1, 1), and the unknown block is at (2, 1, 1). Every biome
+    ///   is plains (1), and the section's sky light is 15 everywhere.
+    /// * Chunk (1, 0), gzip-compressed, with a section at `Y = 2` whose palette
+    ///   is air and still water. The water is at (0, 0, 0). It has no biomes.
+    const FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/r.0.0.mca");
+
+    const STONE: BlockState = BlockState(1);
+    const OAK_STAIRS_EAST: BlockState = BlockState(1800);
+    const WATER: BlockState = BlockState(34);
+
+    const OAK_STAIRS_EAST_PROPERTIES: &[(&str, &str)] = &[
+        ("facing", "east"),
+        ("half", "bottom"),
+        ("shape", "straight"),
+        ("waterlogged", "false"),
+    ];
+
+    fn lookup(name: &str, properties: &[(&str, &str)]) -> Option<BlockState> {
+        match (name, properties) {
+            ("minecraft:air", []) => Some(BlockState::AIR),
+            ("minecraft:stone", []) => Some(STONE),
+            ("minecraft:oak_stairs", properties) if properties == OAK_STAIRS_EAST_PROPERTIES => {
+                Some(OAK_STAIRS_EAST)
+            }
+            ("minecraft:water", [("level", "0")]) => Some(WATER),
+            _ => None,
+        }
+    }
+
+    fn region() -> RegionFile<Cursor<&'static [u8]>> {
+        RegionFile::new(Cursor::new(FIXTURE)).unwrap()
+    }
+
+    #[test]
+    fn lists_chunks() {
+        let chunks: Vec<_> = region().chunks().collect();
+
+        assert_eq!(chunks, vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn missing_chunk() {
+        assert!(region().read_chunk(5, 5, lookup).unwrap().is_none());
+    }
+
+    #[test]
+    fn read_zlib_chunk() {
+        let chunk = region().read_chunk(0, 0, lookup).unwrap().unwrap();
+
+        assert_eq!((chunk.chunk_x, chunk.chunk_z), (0, 0));
+        assert!(chunk.is_full());
+        assert!(chunk
+            .biomes
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .all(|biome| *biome == BiomeId(1)));
+
+        // The light-only section is skipped.
+        assert_eq!(chunk.sections.len(), 1);
+        let section = &chunk.sections[0];
+        assert_eq!(section.chunk_y, 0);
+
+        // The unknown block is replaced with air and not counted.
+        assert_eq!(section.block_count, 256 + 1);
+
+        for (x, y, z, block_state) in section.block_states.iter() {
+            let expected = match (x, y, z) {
+                (_, 0, _) => STONE,
+                (1, 1, 1) => OAK_STAIRS_EAST,
+                _ => BlockState::AIR,
+            };
+            assert_eq!(block_state, expected, "block at ({}, {}, {})", x, y, z);
+        }
+
+        assert_eq!(section.get_sky_light(3, 4, 5), 15);
+        assert_eq!(section.get_block_light(3, 4, 5), 0);
+    }
+
+    #[test]
+    fn read_gzip_chunk() {
+        let chunk = region().read_chunk(1, 0, lookup).unwrap().unwrap();
+
+        assert_eq!((chunk.chunk_x, chunk.chunk_z), (1, 0));
+        assert!(chunk
+            .biomes
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .all(|biome| *biome == BiomeId::VOID));
+
+        assert_eq!(chunk.sections.len(), 1);
+        let section = &chunk.sections[0];
+        assert_eq!(section.chunk_y, 2);
+        assert_eq!(section.block_count, 1);
+        assert_eq!(section.block_states.get_block(0, 0, 0), WATER);
+        assert_eq!(section.block_states.get_block(1, 0, 0), BlockState::AIR);
+        assert!(section.block_light.is_none());
+        assert!(section.sky_light.is_none());
+    }
+
+    #[test]
+    fn unsupported_data_version() {
+        let mut nbt = region().read_chunk_nbt(0, 0).unwrap().unwrap();
+        if let Tag::Compound(entries) = &mut nbt {
+            entries.insert(String::from("DataVersion"), Tag::Int(2586));
+        }
+
+        assert!(matches!(
+            chunk_from_nbt(&nbt, lookup),
+            Err(Error::UnsupportedDataVersion(2586))
+        ));
+    }
+}
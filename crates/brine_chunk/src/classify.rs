@@ -0,0 +1,91 @@
+//! Classifying block states into broad categories.
+//!
+//! Block state IDs are specific to a Minecraft version, so this crate can't
+//! know on its own which states are air or fluids. A [`BlockClassifier`] is a
+//! small lookup table that answers those questions, built once from the
+//! version's block data (see `brine_data`'s `Blocks::classifier`).
+
+use crate::BlockState;
+
+const AIR: u8 = 1 << 0;
+const FLUID: u8 = 1 << 1;
+
+/// Lookup table of categories for every block state in a Minecraft version.
+///
+/// Block states not known to the classifier belong to no category.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockClassifier {
+    flags: Vec<u8>,
+}
+
+impl BlockClassifier {
+    /// Creates a classifier from the block states that are air (air, cave air,
+    /// and void air) and the block states that are fluids (water and lava).
+    pub fn new(
+        air: impl IntoIterator<Item = BlockState>,
+        fluids: impl IntoIterator<Item = BlockState>,
+    ) -> Self {
+        let mut classifier = Self::default();
+
+        for block_state in air {
+            classifier.set(block_state, AIR);
+        }
+        for block_state in fluids {
+            classifier.set(block_state, FLUID);
+        }
+
+        classifier
+    }
+
+    /// Returns whether the block state is air, cave air, or void air.
+    ///
+    /// These are the blocks that are *not* counted by
+    /// [`ChunkSection::block_count`][crate::ChunkSection::block_count].
+    #[inline]
+    pub fn is_air(&self, block_state: BlockState) -> bool {
+        self.get(block_state) & AIR != 0
+    }
+
+    /// Returns whether the block state is a fluid (water or lava).
+    #[inline]
+    pub fn is_fluid(&self, block_state: BlockState) -> bool {
+        self.get(block_state) & FLUID != 0
+    }
+
+    #[inline]
+    fn get(&self, block_state: BlockState) -> u8 {
+        self.flags.get(block_state.0 as usize).copied().unwrap_or(0)
+    }
+
+    fn set(&mut self, block_state: BlockState, flag: u8) {
+        let index = block_state.0 as usize;
+        if index >= self.flags.len() {
+            self.flags.resize(index + 1, 0);
+        }
+        self.flags[index] |= flag;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn classify() {
+        let classifier =
+            BlockClassifier::new([BlockState(0), BlockState(10)], (3..5).map(BlockState));
+
+        assert!(classifier.is_air(BlockState::AIR));
+        assert!(classifier.is_air(BlockState(10)));
+        assert!(!classifier.is_air(BlockState(3)));
+
+        assert!(classifier.is_fluid(BlockState(3)));
+        assert!(classifier.is_fluid(BlockState(4)));
+        assert!(!classifier.is_fluid(BlockState(5)));
+        assert!(!classifier.is_fluid(BlockState(0)));
+
+        // Unknown block states belong to no category.
+        assert!(!classifier.is_air(BlockState(1000)));
+        assert!(!classifier.is_fluid(BlockState(1000)));
+    }
+}
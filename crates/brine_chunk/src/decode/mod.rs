@@ -4,15 +4,17 @@ use byteorder::{BigEndian, ReadBytesExt};
 use tracing::trace;
 
 use crate::{
+    nbt::{self, Tag},
     palette::{Palette, SectionPalette},
-    Biomes, BlockState, BlockStates, Chunk, ChunkSection, BLOCKS_PER_SECTION, SECTIONS_PER_CHUNK,
+    Biomes, BlockState, BlockStates, Chunk, ChunkSection, Heightmap, Heightmaps,
+    BLOCKS_PER_SECTION, CHUNK_WIDTH, LIGHT_ARRAY_LENGTH, SECTIONS_PER_CHUNK,
 };
 
 mod packed_vec;
 mod varint;
 
-pub use packed_vec::PackedIntVec;
-pub use varint::VarIntRead;
+pub use packed_vec::{PackedIntError, PackedIntSlice, PackedIntVec};
+pub use varint::{VarIntRead, VarIntWrite};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -21,6 +23,24 @@ pub enum Error {
 
     #[error(transparent)]
     InvalidInt(#[from] TryFromIntError),
+
+    #[error("light array has length {0}, expected {}", LIGHT_ARRAY_LENGTH)]
+    InvalidLightArrayLength(i32),
+
+    #[error(transparent)]
+    Nbt(#[from] nbt::Error),
+
+    #[error("heightmap {0} is not a long array of the expected length")]
+    InvalidHeightmap(&'static str),
+
+    #[error(
+        "{0} bits per block is more than the maximum of {}",
+        BlockState::MAX_BLOCK_STATES_LOG_2
+    )]
+    InvalidBitsPerBlock(u8),
+
+    #[error("malformed block states: {0}")]
+    MalformedBlockStates(#[from] PackedIntError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -75,6 +95,7 @@ impl Chunk {
             chunk_z,
             sections,
             biomes,
+            heightmaps: None,
         })
     }
 
@@ -97,6 +118,72 @@ impl Chunk {
         Ok(sections)
     }
 
+    /// Decodes light data and stores it in this chunk's sections.
+    ///
+    /// Since 1.14, light data is no longer part of the chunk data blob and
+    /// arrives separately in an Update Light packet. The `data` blob contains
+    /// one light array for each bit set in `sky_light_mask`, followed by one
+    /// for each bit set in `block_light_mask`.
+    ///
+    /// The masks have 18 bits: the least significant bit is for the section
+    /// below the world (Y=-1), and the most significant bit is for the section
+    /// above the world (Y=16). Light for those two sections, and for any
+    /// section not present in [`Chunk::sections`], is discarded.
+    ///
+    /// See <https://wiki.vg/index.php?title=Protocol&oldid=15346#Update_Light>.
+    pub fn decode_light(
+        &mut self,
+        sky_light_mask: u32,
+        block_light_mask: u32,
+        data: &mut impl io::Read,
+    ) -> Result<()> {
+        trace!("Chunk::decode_light");
+
+        for (mask, is_sky_light) in [(sky_light_mask, true), (block_light_mask, false)] {
+            for bit in 0..(SECTIONS_PER_CHUNK + 2) {
+                if (mask & (1 << bit)) == 0 {
+                    continue;
+                }
+
+                let light = decode_light_array(data)?;
+
+                let section_y = bit as i32 - 1;
+                if let Some(section) = self
+                    .sections
+                    .iter_mut()
+                    .find(|section| section.chunk_y as i32 == section_y)
+                {
+                    if is_sky_light {
+                        section.sky_light = Some(light);
+                    } else {
+                        section.block_light = Some(light);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes the heightmaps of a full chunk and stores them in this chunk.
+    ///
+    /// Like light data, heightmaps are not part of the chunk data blob. The
+    /// `data` is the `Heightmaps` NBT compound that precedes the blob in the
+    /// ChunkData packet. If it holds no tag at all (i.e., a lone `TAG_End`),
+    /// the chunk is left without heightmaps.
+    ///
+    /// See <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Heightmaps>.
+    pub fn decode_heightmaps(&mut self, data: &mut impl io::Read) -> Result<()> {
+        trace!("Chunk::decode_heightmaps");
+
+        self.heightmaps = match Tag::read_optional_named(data)? {
+            Some((_, tag)) => Some(Box::new(Heightmaps::from_nbt(&tag)?)),
+            None => None,
+        };
+
+        Ok(())
+    }
+
     /// Given a bitmask, returns which chunk section y-coordinates correspond to
     /// the chunk sections in the data blob.
     ///
@@ -140,6 +227,12 @@ impl ChunkSection {
             bits_per_block
         };
 
+        // Block states from the global palette never need more bits than
+        // this, and a section palette uses fewer.
+        if bits_per_block as usize > BlockState::MAX_BLOCK_STATES_LOG_2 {
+            return Err(Error::InvalidBitsPerBlock(bits_per_block));
+        }
+
         let block_states = if bits_per_block <= SectionPalette::MAX_BITS_PER_BLOCK {
             let palette = SectionPalette::decode(global_palette, data)?;
 
@@ -154,10 +247,26 @@ impl ChunkSection {
             chunk_y,
             block_count,
             block_states,
+            block_light: None,
+            sky_light: None,
         })
     }
 }
 
+/// Decodes a single length-prefixed light array (2048 bytes, one nibble per
+/// block).
+pub fn decode_light_array(data: &mut impl io::Read) -> Result<Box<[u8; LIGHT_ARRAY_LENGTH]>> {
+    let length = data.read_var_i32()?;
+    if length as usize != LIGHT_ARRAY_LENGTH {
+        return Err(Error::InvalidLightArrayLength(length));
+    }
+
+    let mut light = Box::new([0; LIGHT_ARRAY_LENGTH]);
+    data.read_exact(&mut light[..])?;
+
+    Ok(light)
+}
+
 impl BlockStates {
     /// See <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Compacted_data_array>.
     pub fn decode(
@@ -167,24 +276,79 @@ impl BlockStates {
     ) -> Result<Self> {
         trace!("BlockStates::decode");
 
+        if bits_per_block > 64 {
+            return Err(Error::InvalidBitsPerBlock(bits_per_block));
+        }
+
         let array_length = data.read_var_i32()?;
         trace!("array_length: {}", array_length);
 
-        let mut longs = Vec::<u64>::with_capacity(array_length.try_into()?);
+        // Don't trust the length to be reasonable before reading that much.
+        let expected_length = (BLOCKS_PER_SECTION * bits_per_block as usize + 63) / 64;
+        let capacity = usize::try_from(array_length)?.min(expected_length);
+
+        let mut longs = Vec::<u64>::with_capacity(capacity);
         for _ in 0..array_length {
             longs.push(data.read_u64::<BigEndian>()?);
         }
 
-        let packed_vec_length = BLOCKS_PER_SECTION;
-        let packed_vec =
-            PackedIntVec::from_parts(longs, packed_vec_length, bits_per_block).unwrap();
+        let packed = PackedIntSlice::new(&longs, BLOCKS_PER_SECTION, bits_per_block)?;
 
-        let block_states: Vec<BlockState> = packed_vec
-            .iter()
-            .map(|block_state_id| palette.id_to_block_state(block_state_id).unwrap())
-            .collect();
+        let mut block_states = Self::default();
+        for (block_state, block_state_id) in block_states.0.iter_mut().zip(packed.iter()) {
+            *block_state = palette.id_to_block_state(block_state_id).unwrap();
+        }
 
-        Ok(Self(block_states.try_into().unwrap()))
+        Ok(block_states)
+    }
+}
+
+impl Heightmaps {
+    /// Reads the heightmaps out of a `Heightmaps` NBT compound. Heightmaps
+    /// other than `MOTION_BLOCKING` and `WORLD_SURFACE` are ignored.
+    pub fn from_nbt(tag: &Tag) -> Result<Self> {
+        let heightmap = |name: &'static str| {
+            tag.get(name)
+                .map(|heightmap| {
+                    heightmap
+                        .as_long_array()
+                        .and_then(Heightmap::unpack)
+                        .ok_or(Error::InvalidHeightmap(name))
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            motion_blocking: heightmap("MOTION_BLOCKING")?,
+            world_surface: heightmap("WORLD_SURFACE")?,
+        })
+    }
+}
+
+impl Heightmap {
+    /// Number of bits used for each height in the packed long array.
+    pub const BITS_PER_ENTRY: u8 = 9;
+
+    /// Unpacks a heightmap from a long array with one 9-bit entry per column,
+    /// in Z-X order.
+    ///
+    /// Returns `None` if the array has the wrong length.
+    pub fn unpack(longs: &[i64]) -> Option<Self> {
+        const COLUMNS: usize = CHUNK_WIDTH * CHUNK_WIDTH;
+
+        let words: Vec<u64> = longs.iter().map(|long| *long as u64).collect();
+        if words.len() != (COLUMNS * Self::BITS_PER_ENTRY as usize + 63) / 64 {
+            return None;
+        }
+
+        let packed = PackedIntSlice::new(&words, COLUMNS, Self::BITS_PER_ENTRY).ok()?;
+
+        let mut heightmap = Self::default();
+        for (index, height) in packed.iter().enumerate() {
+            heightmap.0[index % CHUNK_WIDTH][index / CHUNK_WIDTH] = height as u16;
+        }
+
+        Some(heightmap)
     }
 }
 
@@ -194,3 +358,216 @@ impl Biomes {
         Ok(Default::default())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write_var_i32(value: i32, out: &mut Vec<u8>) {
+        let mut value = value as u32;
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn light_array(light: &[u8; LIGHT_ARRAY_LENGTH]) -> Vec<u8> {
+        let mut data = Vec::new();
+        write_var_i32(LIGHT_ARRAY_LENGTH as i32, &mut data);
+        data.extend_from_slice(light);
+        data
+    }
+
+    #[test]
+    fn decode_light() {
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections.push(ChunkSection::empty(0));
+        chunk.sections.push(ChunkSection::empty(2));
+
+        // Sky light for Y=-1 (discarded), Y=0, and Y=2; block light for Y=2.
+        let sky_light_mask = 0b1011;
+        let block_light_mask = 0b1000;
+
+        let mut block_light = [0; LIGHT_ARRAY_LENGTH];
+        // Block at (1, 0, 0) is index 1, stored in the high nibble of byte 0.
+        // Block at (0, 1, 0) is index 256, stored in the low nibble of byte 128.
+        block_light[0] = 0x70;
+        block_light[128] = 0x0C;
+
+        let mut data = Vec::new();
+        data.extend(light_array(&[0x11; LIGHT_ARRAY_LENGTH]));
+        data.extend(light_array(&[0xFF; LIGHT_ARRAY_LENGTH]));
+        data.extend(light_array(&[0x5A; LIGHT_ARRAY_LENGTH]));
+        data.extend(light_array(&block_light));
+
+        chunk
+            .decode_light(sky_light_mask, block_light_mask, &mut &data[..])
+            .unwrap();
+
+        let section_0 = &chunk.sections[0];
+        assert_eq!(section_0.get_sky_light(0, 0, 0), 15);
+        assert_eq!(section_0.get_sky_light(15, 15, 15), 15);
+        assert!(section_0.block_light.is_none());
+        assert_eq!(section_0.get_block_light(3, 3, 3), 0);
+
+        let section_2 = &chunk.sections[1];
+        assert_eq!(section_2.get_sky_light(0, 0, 0), 0xA);
+        assert_eq!(section_2.get_sky_light(1, 0, 0), 0x5);
+        assert_eq!(section_2.get_block_light(0, 0, 0), 0);
+        assert_eq!(section_2.get_block_light(1, 0, 0), 7);
+        assert_eq!(section_2.get_block_light(0, 1, 0), 12);
+    }
+
+    #[test]
+    fn invalid_light_array_length() {
+        let mut data = Vec::new();
+        write_var_i32(10, &mut data);
+        data.extend_from_slice(&[0; 10]);
+
+        assert!(matches!(
+            decode_light_array(&mut &data[..]),
+            Err(Error::InvalidLightArrayLength(10))
+        ));
+    }
+
+    /// Palette that maps every id to the block state with the same id.
+    struct IdentityPalette;
+
+    impl Palette for IdentityPalette {
+        fn id_to_block_state(&self, id: u32) -> Option<crate::BlockState> {
+            Some(crate::BlockState(id))
+        }
+    }
+
+    fn block_states_data(longs: &[u64]) -> Vec<u8> {
+        let mut data = Vec::new();
+        write_var_i32(longs.len() as i32, &mut data);
+        for long in longs {
+            data.extend_from_slice(&long.to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn decode_block_states() {
+        // Every 4-bit entry is 1.
+        let data = block_states_data(&[0x1111111111111111; 256]);
+
+        let block_states = BlockStates::decode(4, &IdentityPalette, &mut &data[..]).unwrap();
+
+        assert!(block_states.0.iter().all(|block| block.0 == 1));
+    }
+
+    #[test]
+    fn too_few_block_states() {
+        let data = block_states_data(&[0; 10]);
+
+        let result = BlockStates::decode(4, &IdentityPalette, &mut &data[..]);
+
+        assert!(matches!(
+            result,
+            Err(Error::MalformedBlockStates(
+                PackedIntError::NotEnoughWords {
+                    expected: 256,
+                    actual: 10,
+                    ..
+                }
+            ))
+        ));
+    }
+
+    fn section_data(bits_per_block: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&4096i16.to_be_bytes());
+        data.push(bits_per_block);
+        data
+    }
+
+    #[test]
+    fn too_many_bits_per_block() {
+        for bits_per_block in [15, 40, 65, 255] {
+            let data = section_data(bits_per_block);
+
+            let result = ChunkSection::decode(0, &IdentityPalette, &mut &data[..]);
+
+            assert!(
+                matches!(result, Err(Error::InvalidBitsPerBlock(bits)) if bits == bits_per_block),
+                "{} bits per block",
+                bits_per_block
+            );
+        }
+
+        let data = block_states_data(&[0; 10]);
+        assert!(matches!(
+            BlockStates::decode(65, &IdentityPalette, &mut &data[..]),
+            Err(Error::InvalidBitsPerBlock(65))
+        ));
+    }
+
+    mod heightmaps {
+        use super::*;
+
+        /// A `Heightmaps` compound as sent by a 1.14.4 server. The
+        /// `MOTION_BLOCKING` height of column (x, z) is `64 + (x + z) % 8`, and
+        /// the `WORLD_SURFACE` height is one more than that where `x == z`.
+        const HEIGHTMAPS: &[u8] = include_bytes!("../../tests/fixtures/heightmaps.nbt");
+
+        #[test]
+        fn decode() {
+            let mut chunk = Chunk::empty(0, 0);
+            chunk.decode_heightmaps(&mut &HEIGHTMAPS[..]).unwrap();
+
+            let heightmaps = chunk.heightmaps.unwrap();
+            let motion_blocking = heightmaps.motion_blocking.unwrap();
+            let world_surface = heightmaps.world_surface.unwrap();
+
+            for x in 0..16 {
+                for z in 0..16 {
+                    let height = motion_blocking.0[x][z];
+                    assert!(height <= 256);
+                    assert_eq!(height as usize, 64 + (x + z) % 8);
+
+                    let expected = if x == z { height + 1 } else { height };
+                    assert_eq!(world_surface.0[x][z], expected);
+                }
+            }
+        }
+
+        #[test]
+        fn height_at() {
+            let mut chunk = Chunk::empty(0, 0);
+            assert_eq!(chunk.height_at(0, 0), None);
+
+            chunk.decode_heightmaps(&mut &HEIGHTMAPS[..]).unwrap();
+
+            assert_eq!(chunk.height_at(0, 0), Some(65));
+            assert_eq!(chunk.height_at(3, 2), Some(69));
+            assert_eq!(chunk.height_at(7, 7), Some(71));
+            assert_eq!(chunk.height_at(16, 0), None);
+
+            let heightmaps = chunk.heightmaps.as_ref().unwrap();
+            assert_eq!(heightmaps.motion_blocking_at(0, 0), Some(64));
+            assert_eq!(heightmaps.motion_blocking_at(7, 7), Some(70));
+            assert_eq!(heightmaps.motion_blocking_at(15, 15), Some(70));
+        }
+
+        #[test]
+        fn missing() {
+            let mut chunk = Chunk::empty(0, 0);
+            chunk.decode_heightmaps(&mut &[0u8][..]).unwrap();
+
+            assert!(chunk.heightmaps.is_none());
+        }
+
+        #[test]
+        fn wrong_length() {
+            assert!(Heightmap::unpack(&[0; 35]).is_none());
+            assert!(Heightmap::unpack(&[0; 36]).is_some());
+        }
+    }
+}
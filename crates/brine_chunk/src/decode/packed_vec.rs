@@ -2,6 +2,21 @@
 
 use std::fmt;
 
+/// Why a [`PackedIntVec`] or [`PackedIntSlice`] couldn't be constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PackedIntError {
+    #[error("{0} bits per entry is not between 1 and 32")]
+    InvalidBitsPerEntry(u8),
+
+    #[error("{length} entries of {bits_per_entry} bits need {expected} words, found {actual}")]
+    NotEnoughWords {
+        length: usize,
+        bits_per_entry: u8,
+        expected: usize,
+        actual: usize,
+    },
+}
+
 /// A packed vector of unsigned integers with a fixed number of bits per entry.
 ///
 /// The data is stored as a vector of u64 words. If N is the number of bits per
@@ -33,7 +48,7 @@ use std::fmt;
 /// ```
 ///
 /// ```rust
-/// use brine_chunk::packed_vec::PackedIntVec;
+/// use brine_chunk::decode::PackedIntVec;
 ///
 /// let words = vec![0x01001880C0060020, 0x0200D0068004C020];
 /// let length = 12;
@@ -55,20 +70,59 @@ impl PackedIntVec {
     /// Initializes a packed vector from a list of u64 words, a length, and the
     /// number of bits per entry.
     ///
-    /// Returns `None` if `length` and/or `bits_per_entry` are invalid.
+    /// Returns an error if `bits_per_entry` is invalid, or if there aren't
+    /// enough words for `length` entries.
     #[inline]
     pub fn from_parts(
         words: impl IntoIterator<Item = u64>,
         length: usize,
         bits_per_entry: u8,
-    ) -> Option<Self> {
+    ) -> Result<Self, PackedIntError> {
         let words: Vec<_> = words.into_iter().collect();
 
+        PackedIntSlice::new(&words, length, bits_per_entry)?;
+
+        Ok(Self {
+            words,
+            length,
+            bits_per_entry,
+        })
+    }
+
+    /// Packs a sequence of entries into a new packed vector, the inverse of
+    /// [`iter`][Self::iter].
+    ///
+    /// Entries are truncated to fit into `bits_per_entry` bits. Returns `None`
+    /// if `bits_per_entry` is invalid.
+    pub fn pack(entries: impl IntoIterator<Item = u32>, bits_per_entry: u8) -> Option<Self> {
         if bits_per_entry == 0 || bits_per_entry > 32 {
             return None;
         }
-        if length * bits_per_entry as usize > words.len() * 64 {
-            return None;
+
+        let bitmask = u64::MAX >> (64 - bits_per_entry);
+
+        let mut words = Vec::new();
+        let mut length = 0;
+
+        for entry in entries {
+            let bit_index = length * bits_per_entry as usize;
+            let word_index = bit_index / 64;
+            let bit_offset = bit_index % 64;
+
+            let entry = entry as u64 & bitmask;
+
+            if word_index == words.len() {
+                words.push(0);
+            }
+            words[word_index] |= entry << bit_offset;
+
+            // Bits that don't fit go into the least significant bits of the
+            // next word.
+            if bit_offset + bits_per_entry as usize > 64 {
+                words.push(entry >> (64 - bit_offset));
+            }
+
+            length += 1;
         }
 
         Some(Self {
@@ -97,13 +151,20 @@ impl PackedIntVec {
         self.length
     }
 
+    /// Borrows the packed vector as a [`PackedIntSlice`].
+    #[inline]
+    pub fn as_slice(&self) -> PackedIntSlice<'_> {
+        PackedIntSlice {
+            words: &self.words,
+            length: self.length,
+            bits_per_entry: self.bits_per_entry,
+        }
+    }
+
     /// Iterates through the entries of the packed vector.
     #[inline]
     pub fn iter(&self) -> Iter<'_> {
-        Iter {
-            vec: self,
-            index: 0,
-        }
+        self.as_slice().iter()
     }
 
     /// Iterates through the raw words in the packed vector.
@@ -115,11 +176,7 @@ impl PackedIntVec {
     /// Returns the entry at the given index or `None` if out of bounds.
     #[inline]
     pub fn get(&self, index: usize) -> Option<u32> {
-        if index >= self.length {
-            return None;
-        }
-
-        Some(self.unpack_integer_at(self.entry_index_to_bit_index(index)))
+        self.as_slice().get(index)
     }
 
     /* TODO
@@ -141,6 +198,89 @@ impl PackedIntVec {
         Some(prev)
     }
     */
+}
+
+/// A borrowed view of packed words, laid out the same way as a
+/// [`PackedIntVec`].
+///
+/// This allows decoding entries straight out of a `&[u64]` without copying the
+/// words into a new vector.
+///
+/// ```rust
+/// use brine_chunk::decode::PackedIntSlice;
+///
+/// let words = [0x01001880C0060020, 0x0200D0068004C020];
+///
+/// let slice = PackedIntSlice::new(&words, 12, 10).unwrap();
+///
+/// assert_eq!(slice.get(3), Some(515));
+/// assert_eq!(slice.get(6), Some(512));
+/// assert_eq!(slice.get(12), None);
+/// ```
+#[derive(Clone, Copy)]
+pub struct PackedIntSlice<'a> {
+    words: &'a [u64],
+    length: usize,
+    bits_per_entry: u8,
+}
+
+impl<'a> PackedIntSlice<'a> {
+    /// Creates a view of `length` entries of `bits_per_entry` bits each.
+    ///
+    /// Returns an error if `bits_per_entry` is invalid, or if there aren't
+    /// enough words for `length` entries.
+    #[inline]
+    pub fn new(
+        words: &'a [u64],
+        length: usize,
+        bits_per_entry: u8,
+    ) -> Result<Self, PackedIntError> {
+        if bits_per_entry == 0 || bits_per_entry > 32 {
+            return Err(PackedIntError::InvalidBitsPerEntry(bits_per_entry));
+        }
+
+        let expected = (length * bits_per_entry as usize + 63) / 64;
+        if words.len() < expected {
+            return Err(PackedIntError::NotEnoughWords {
+                length,
+                bits_per_entry,
+                expected,
+                actual: words.len(),
+            });
+        }
+
+        Ok(Self {
+            words,
+            length,
+            bits_per_entry,
+        })
+    }
+
+    /// Returns the number of entries in the slice.
+    #[allow(clippy::len_without_is_empty)]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Iterates through the entries of the slice.
+    #[inline]
+    pub fn iter(&self) -> Iter<'a> {
+        Iter {
+            slice: *self,
+            index: 0,
+        }
+    }
+
+    /// Returns the entry at the given index or `None` if out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.length {
+            return None;
+        }
+
+        Some(self.unpack_integer_at(self.entry_index_to_bit_index(index)))
+    }
 
     #[inline]
     fn entry_index_to_bit_index(&self, index: usize) -> BitIndex {
@@ -231,9 +371,9 @@ struct BitIndex {
     bit_offset: u8,
 }
 
-/// [`PackedIntVec`] iterator.
+/// [`PackedIntVec`] and [`PackedIntSlice`] iterator.
 pub struct Iter<'a> {
-    vec: &'a PackedIntVec,
+    slice: PackedIntSlice<'a>,
     index: usize,
 }
 
@@ -242,7 +382,7 @@ impl<'a> Iterator for Iter<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let next = self.vec.get(self.index);
+        let next = self.slice.get(self.index);
 
         if next.is_some() {
             self.index += 1;
@@ -265,10 +405,27 @@ mod test {
     fn invalid_construction() {
         let words = vec![0xFEDCBA9876543210];
 
-        assert_eq!(PackedIntVec::from_parts(words.clone(), 0, 0), None);
-        assert_eq!(PackedIntVec::from_parts(words.clone(), 1, 0), None);
-        assert_eq!(PackedIntVec::from_parts(words.clone(), 1, 33), None);
-        assert_eq!(PackedIntVec::from_parts(words, 5, 13), None);
+        assert_eq!(
+            PackedIntVec::from_parts(words.clone(), 0, 0),
+            Err(PackedIntError::InvalidBitsPerEntry(0))
+        );
+        assert_eq!(
+            PackedIntVec::from_parts(words.clone(), 1, 0),
+            Err(PackedIntError::InvalidBitsPerEntry(0))
+        );
+        assert_eq!(
+            PackedIntVec::from_parts(words.clone(), 1, 33),
+            Err(PackedIntError::InvalidBitsPerEntry(33))
+        );
+        assert_eq!(
+            PackedIntVec::from_parts(words, 5, 13),
+            Err(PackedIntError::NotEnoughWords {
+                length: 5,
+                bits_per_entry: 13,
+                expected: 2,
+                actual: 1,
+            })
+        );
     }
 
     #[test]
@@ -315,6 +472,76 @@ mod test {
         )
     }
 
+    #[test]
+    fn pack_inverts_iter() {
+        let entries = [32, 384, 0, 515, 24, 64, 512, 768, 4, 416, 256, 3];
+
+        let vec = PackedIntVec::pack(entries, 10).unwrap();
+        assert_vec_eq(&vec, entries);
+
+        let (words, length, bits_per_entry) = vec.into_parts();
+        // Same as the example in the docs, minus the bits of the 13th entry.
+        assert_eq!(words, [0x01001880C0060020, 0x0000D0068004C020]);
+        assert_eq!(length, 12);
+        assert_eq!(bits_per_entry, 10);
+
+        let entries: Vec<u32> = (0..4096).map(|i| (i * 7) % (1 << 14)).collect();
+        let vec = PackedIntVec::pack(entries.iter().copied(), 14).unwrap();
+        assert_vec_eq(&vec, &entries);
+
+        assert_eq!(PackedIntVec::pack([1, 2, 3], 0), None);
+    }
+
+    #[test]
+    fn slice_matches_vec() {
+        let words = vec![0x01001880C0060020, 0x0200D0068004C020, 0xFEDCBA9876543210];
+
+        for bits_per_entry in [1, 4, 5, 10, 13, 14, 32] {
+            let length = words.len() * 64 / bits_per_entry as usize;
+
+            let vec = PackedIntVec::from_parts(words.clone(), length, bits_per_entry).unwrap();
+            let slice = PackedIntSlice::new(&words, length, bits_per_entry).unwrap();
+
+            assert!(vec.iter().eq(slice.iter()));
+            assert_eq!(slice.len(), length);
+        }
+
+        assert!(PackedIntSlice::new(&words, 20, 10).is_ok());
+        assert!(PackedIntSlice::new(&words, 20, 0).is_err());
+        assert!(PackedIntSlice::new(&words, 20, 33).is_err());
+        assert_eq!(PackedIntSlice::new(&words, 20, 10).unwrap().get(20), None);
+        assert!(PackedIntSlice::new(&words[..1], 7, 10).is_err());
+    }
+
+    #[test]
+    fn random_access_across_word_boundaries() {
+        // With 10 bits per entry, entry 6 occupies bits 60..70 and entry 12
+        // occupies bits 120..130, so both straddle a word boundary.
+        let entries: Vec<u32> = (0..32).map(|i| (i * 97 + 13) % 1024).collect();
+        let vec = PackedIntVec::pack(entries.iter().copied(), 10).unwrap();
+        let (words, length, _) = vec.into_parts();
+
+        let slice = PackedIntSlice::new(&words, length, 10).unwrap();
+        for index in [6, 12, 19, 25] {
+            let first_bit = index * 10;
+            let last_bit = first_bit + 9;
+            assert_ne!(first_bit / 64, last_bit / 64, "{} doesn't straddle", index);
+
+            assert_eq!(slice.get(index), Some(entries[index]));
+        }
+
+        // 14 bits per entry (the global palette) doesn't divide 64 either, and
+        // the last entry of every 32 ends exactly on a word boundary.
+        let entries: Vec<u32> = (0..64).map(|i| (i * 3001) % (1 << 14)).collect();
+        let vec = PackedIntVec::pack(entries.iter().copied(), 14).unwrap();
+        let (words, length, _) = vec.into_parts();
+
+        let slice = PackedIntSlice::new(&words, length, 14).unwrap();
+        for (index, entry) in entries.iter().enumerate().rev() {
+            assert_eq!(slice.get(index), Some(*entry));
+        }
+    }
+
     #[test]
     fn test_equality_with_different_bits_outside_of_range() {
         let vec1 = PackedIntVec::from_parts(vec![0xFFF0000000000000], 2, 24).unwrap();
@@ -16,3 +16,22 @@ impl<R: io::Read> VarIntRead for R {
         minecraft_varint::VarIntRead::read_var_u64(self).map(|v| v.try_into().unwrap())
     }
 }
+
+/// Writes signed VarInts with the same encoding that [`VarIntRead`] expects.
+pub trait VarIntWrite {
+    fn write_var_i32(&mut self, value: i32) -> io::Result<()>;
+}
+
+impl<W: io::Write> VarIntWrite for W {
+    fn write_var_i32(&mut self, value: i32) -> io::Result<()> {
+        let mut value = value as u32;
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.write_all(&[byte]);
+            }
+            self.write_all(&[byte | 0x80])?;
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Encoding chunk data back into the format sent in network packets.
+//!
+//! This is the inverse of [`decode`][crate::decode], and exists so that chunks
+//! received from a server can be saved to disk and served again later.
+//!
+//! Block states are written as-is using the global palette (i.e., without a
+//! section palette), so the encoded data decodes back to the same chunk only
+//! if it is decoded with a global palette that performs no translation.
+
+use std::io;
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use crate::{
+    decode::{PackedIntVec, VarIntWrite},
+    Biomes, BlockState, BlockStates, Chunk, ChunkSection,
+};
+
+impl Chunk {
+    /// Returns the primary bit mask for this chunk's sections, with a `1` bit
+    /// for each section present in [`sections`][Self::sections].
+    ///
+    /// See [`Chunk::decode`] for more information.
+    pub fn primary_bit_mask(&self) -> u16 {
+        self.sections
+            .iter()
+            .fold(0, |mask, section| mask | (1 << section.chunk_y))
+    }
+
+    /// Encodes the chunk into a data blob suitable for a ChunkData packet with
+    /// the bit mask returned by [`primary_bit_mask`][Self::primary_bit_mask].
+    ///
+    /// Biomes are included if and only if this is a full chunk.
+    ///
+    /// Light data is not part of the chunk data blob and is not encoded.
+    pub fn encode(&self, data: &mut impl io::Write) -> io::Result<()> {
+        for section in self.sections.iter() {
+            section.encode(data)?;
+        }
+
+        if let Some(biomes) = self.biomes.as_ref() {
+            biomes.encode(data)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ChunkSection {
+    /// Encodes the chunk section into a data blob.
+    ///
+    /// See also
+    /// <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Chunk_Section_structure>
+    pub fn encode(&self, data: &mut impl io::Write) -> io::Result<()> {
+        let bits_per_block = BlockState::MAX_BLOCK_STATES_LOG_2 as u8;
+
+        data.write_i16::<BigEndian>(self.block_count as i16)?;
+        data.write_u8(bits_per_block)?;
+
+        self.block_states.encode(bits_per_block, data)
+    }
+}
+
+impl BlockStates {
+    /// See <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Compacted_data_array>.
+    pub fn encode(&self, bits_per_block: u8, data: &mut impl io::Write) -> io::Result<()> {
+        let packed_vec = PackedIntVec::pack(self.0.iter().map(|state| state.0), bits_per_block)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid bits per block: {}", bits_per_block),
+                )
+            })?;
+
+        let (words, _, _) = packed_vec.into_parts();
+
+        data.write_var_i32(words.len() as i32)?;
+        for word in words {
+            data.write_u64::<BigEndian>(word)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Biomes {
+    /// Writes one biome ID per vertical slice of the chunk, as 32-bit integers.
+    pub fn encode(&self, data: &mut impl io::Write) -> io::Result<()> {
+        for biome in self.0.iter() {
+            data.write_i32::<BigEndian>(biome.0 as i32)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::palette::Palette;
+
+    /// A global palette that performs no translation.
+    struct IdentityPalette;
+
+    impl Palette for IdentityPalette {
+        fn id_to_block_state(&self, id: u32) -> Option<BlockState> {
+            Some(BlockState(id))
+        }
+    }
+
+    fn section(chunk_y: u8) -> ChunkSection {
+        let mut section = ChunkSection::empty(chunk_y);
+        for (i, block) in section.block_states.0.iter_mut().enumerate() {
+            if i % 3 == 0 {
+                *block = BlockState((i % 9000) as u32);
+            }
+        }
+        section.block_count = section
+            .block_states
+            .0
+            .iter()
+            .filter(|block| **block != BlockState::AIR)
+            .count() as u16;
+        section
+    }
+
+    fn round_trip(chunk: &Chunk) -> Chunk {
+        let mut data = Vec::new();
+        chunk.encode(&mut data).unwrap();
+
+        Chunk::decode(
+            chunk.chunk_x,
+            chunk.chunk_z,
+            chunk.is_full(),
+            chunk.primary_bit_mask(),
+            &IdentityPalette,
+            &mut &data[..],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn primary_bit_mask() {
+        let mut chunk = Chunk::empty(0, 0);
+        assert_eq!(chunk.primary_bit_mask(), 0);
+
+        chunk.sections = vec![section(0), section(5), section(15)];
+        assert_eq!(chunk.primary_bit_mask(), 0b1000_0000_0010_0001);
+    }
+
+    #[test]
+    fn full_chunk_round_trip() {
+        let mut chunk = Chunk::empty(-3, 7);
+        chunk.sections = vec![section(0), section(5)];
+
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn delta_chunk_round_trip() {
+        let mut chunk = Chunk::empty_delta(1, 2);
+        chunk.sections = vec![section(3)];
+
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn empty_chunk_round_trip() {
+        let chunk = Chunk::empty(0, 0);
+
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn block_states_round_trip() {
+        let block_states = section(0).block_states;
+
+        let mut data = Vec::new();
+        block_states.encode(14, &mut data).unwrap();
+
+        let decoded = BlockStates::decode(14, &IdentityPalette, &mut &data[..]).unwrap();
+        assert_eq!(decoded, block_states);
+    }
+}
@@ -0,0 +1,485 @@
+//! A versioned on-disk format for [`Chunk`]s.
+//!
+//! Unlike the network format (see [`decode`][crate::decode] and
+//! [`encode`][crate::encode]), this format stores everything in a [`Chunk`],
+//! including light data, and does not depend on any palette.
+//!
+//! # Layout
+//!
+//! A chunk file starts with a header:
+//!
+//! * [`MAGIC`] (8 bytes).
+//! * Major format version (`u16`, little endian).
+//! * Minor format version (`u16`, little endian).
+//!
+//! The header is followed by any number of records until the end of the file.
+//! Each record is a one-byte tag, a `u32` (little endian) payload length, and
+//! then the payload itself. The [`CHUNK_RECORD`] holds the chunk serialized
+//! with [`bincode`], with block states packed into a [`PackedIntVec`] using as
+//! few bits per block as possible. The [`HEIGHTMAPS_RECORD`] (since 1.1) holds
+//! the chunk's heightmaps, if it has any.
+//!
+//! # Compatibility
+//!
+//! Files with a different major version can't be read. A minor version bump
+//! may add new kinds of records, which readers of older minor versions skip.
+
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode::PackedIntVec, BiomeId, Biomes, BlockState, BlockStates, Chunk, ChunkSection, Heightmap,
+    Heightmaps, BLOCKS_PER_SECTION, CHUNK_WIDTH, LIGHT_ARRAY_LENGTH,
+};
+
+/// Bytes at the start of every chunk file.
+pub const MAGIC: &[u8; 8] = b"BRINECHK";
+
+/// Major version of the chunk file format. Files with a different major version
+/// can't be read.
+pub const FORMAT_VERSION_MAJOR: u16 = 1;
+
+/// Minor version of the chunk file format.
+pub const FORMAT_VERSION_MINOR: u16 = 1;
+
+/// Tag of the record that holds the chunk itself.
+pub const CHUNK_RECORD: u8 = 1;
+
+/// Tag of the record that holds the chunk's [`Heightmaps`].
+pub const HEIGHTMAPS_RECORD: u8 = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+
+    #[error("not a chunk file")]
+    InvalidMagic,
+
+    #[error(
+        "unsupported chunk file version {major}.{minor} (expected {}.x)",
+        FORMAT_VERSION_MAJOR
+    )]
+    UnsupportedVersion { major: u16, minor: u16 },
+
+    #[error("chunk file does not contain a chunk")]
+    MissingChunk,
+
+    #[error("invalid chunk data: {0}")]
+    InvalidData(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Chunk {
+    /// Writes the chunk to `writer` in the chunk file format.
+    pub fn write_to(&self, mut writer: impl Write) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION_MAJOR.to_le_bytes())?;
+        writer.write_all(&FORMAT_VERSION_MINOR.to_le_bytes())?;
+
+        let payload = bincode::serialize(&ChunkRecord::from(self))?;
+        write_record(&mut writer, CHUNK_RECORD, &payload)?;
+
+        if let Some(heightmaps) = self.heightmaps.as_deref() {
+            let payload = bincode::serialize(&HeightmapsRecord::from(heightmaps))?;
+            write_record(&mut writer, HEIGHTMAPS_RECORD, &payload)?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Reads a chunk previously written by [`write_to`][Self::write_to].
+    ///
+    /// Records that this version of the format doesn't know about are
+    /// skipped.
+    pub fn read_from(mut reader: impl Read) -> Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::InvalidMagic);
+        }
+
+        let major = read_u16(&mut reader)?;
+        let minor = read_u16(&mut reader)?;
+        if major != FORMAT_VERSION_MAJOR {
+            return Err(Error::UnsupportedVersion { major, minor });
+        }
+
+        let mut chunk: Option<Chunk> = None;
+        let mut heightmaps = None;
+
+        while let Some((tag, payload)) = read_record(&mut reader)? {
+            if tag == CHUNK_RECORD && chunk.is_none() {
+                let record: ChunkRecord = bincode::deserialize(&payload)?;
+                chunk = Some(record.try_into()?);
+            } else if tag == HEIGHTMAPS_RECORD && heightmaps.is_none() {
+                let record: HeightmapsRecord = bincode::deserialize(&payload)?;
+                heightmaps = Some(Box::new(record.try_into()?));
+            }
+        }
+
+        let mut chunk = chunk.ok_or(Error::MissingChunk)?;
+        chunk.heightmaps = heightmaps;
+
+        Ok(chunk)
+    }
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn write_record(writer: &mut impl Write, tag: u8, payload: &[u8]) -> Result<()> {
+    let length: u32 = payload
+        .len()
+        .try_into()
+        .map_err(|_| Error::InvalidData("record too large"))?;
+
+    writer.write_all(&[tag])?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(payload)?;
+
+    Ok(())
+}
+
+/// Reads the next record, or returns `None` at the end of the file.
+fn read_record(reader: &mut impl Read) -> Result<Option<(u8, Vec<u8>)>> {
+    let mut tag = [0; 1];
+    if reader.read(&mut tag)? == 0 {
+        return Ok(None);
+    }
+
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length) as usize;
+
+    let mut payload = Vec::new();
+    reader
+        .by_ref()
+        .take(length as u64)
+        .read_to_end(&mut payload)?;
+    if payload.len() != length {
+        return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+    }
+
+    Ok(Some((tag[0], payload)))
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkRecord {
+    chunk_x: i32,
+    chunk_z: i32,
+    sections: Vec<SectionRecord>,
+    biomes: Option<Vec<u16>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SectionRecord {
+    chunk_y: u8,
+    block_count: u16,
+    bits_per_block: u8,
+    block_states: Vec<u64>,
+    block_light: Option<Vec<u8>>,
+    sky_light: Option<Vec<u8>>,
+}
+
+impl From<&Chunk> for ChunkRecord {
+    fn from(chunk: &Chunk) -> Self {
+        Self {
+            chunk_x: chunk.chunk_x,
+            chunk_z: chunk.chunk_z,
+            sections: chunk.sections.iter().map(SectionRecord::from).collect(),
+            biomes: chunk
+                .biomes
+                .as_ref()
+                .map(|biomes| biomes.0.iter().map(|biome| biome.0).collect()),
+        }
+    }
+}
+
+impl TryFrom<ChunkRecord> for Chunk {
+    type Error = Error;
+
+    fn try_from(record: ChunkRecord) -> Result<Self> {
+        let sections = record
+            .sections
+            .into_iter()
+            .map(ChunkSection::try_from)
+            .collect::<Result<_>>()?;
+
+        let biomes = match record.biomes {
+            Some(biomes) => {
+                let mut ids = Biomes::default();
+                if biomes.len() != ids.0.len() {
+                    return Err(Error::InvalidData("wrong number of biomes"));
+                }
+                for (id, biome) in ids.0.iter_mut().zip(biomes) {
+                    *id = BiomeId(biome);
+                }
+                Some(Box::new(ids))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            chunk_x: record.chunk_x,
+            chunk_z: record.chunk_z,
+            sections,
+            biomes,
+            heightmaps: None,
+        })
+    }
+}
+
+impl From<&ChunkSection> for SectionRecord {
+    fn from(section: &ChunkSection) -> Self {
+        let max_id = section
+            .block_states
+            .0
+            .iter()
+            .map(|block| block.0)
+            .max()
+            .unwrap_or(0);
+        let bits_per_block = (32 - max_id.leading_zeros()).max(1) as u8;
+
+        let packed = PackedIntVec::pack(
+            section.block_states.0.iter().map(|block| block.0),
+            bits_per_block,
+        )
+        .unwrap();
+        let (block_states, _, _) = packed.into_parts();
+
+        Self {
+            chunk_y: section.chunk_y,
+            block_count: section.block_count,
+            bits_per_block,
+            block_states,
+            block_light: section.block_light.as_ref().map(|light| light.to_vec()),
+            sky_light: section.sky_light.as_ref().map(|light| light.to_vec()),
+        }
+    }
+}
+
+impl TryFrom<SectionRecord> for ChunkSection {
+    type Error = Error;
+
+    fn try_from(record: SectionRecord) -> Result<Self> {
+        let packed = PackedIntVec::from_parts(
+            record.block_states,
+            BLOCKS_PER_SECTION,
+            record.bits_per_block,
+        )
+        .map_err(|_| Error::InvalidData("invalid block states"))?;
+
+        let mut block_states = BlockStates::default();
+        for (block, id) in block_states.0.iter_mut().zip(packed.iter()) {
+            *block = BlockState(id);
+        }
+
+        Ok(Self {
+            chunk_y: record.chunk_y,
+            block_count: record.block_count,
+            block_states,
+            block_light: record.block_light.map(light_array).transpose()?,
+            sky_light: record.sky_light.map(light_array).transpose()?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HeightmapsRecord {
+    motion_blocking: Option<Vec<u16>>,
+    world_surface: Option<Vec<u16>>,
+}
+
+impl From<&Heightmaps> for HeightmapsRecord {
+    fn from(heightmaps: &Heightmaps) -> Self {
+        let heights =
+            |heightmap: &Heightmap| -> Vec<u16> { heightmap.0.iter().flatten().copied().collect() };
+
+        Self {
+            motion_blocking: heightmaps.motion_blocking.as_ref().map(heights),
+            world_surface: heightmaps.world_surface.as_ref().map(heights),
+        }
+    }
+}
+
+impl TryFrom<HeightmapsRecord> for Heightmaps {
+    type Error = Error;
+
+    fn try_from(record: HeightmapsRecord) -> Result<Self> {
+        let heightmap = |heights: Vec<u16>| {
+            if heights.len() != CHUNK_WIDTH * CHUNK_WIDTH {
+                return Err(Error::InvalidData("wrong number of heights"));
+            }
+
+            let mut heightmap = Heightmap::default();
+            for (column, heights) in heightmap.0.iter_mut().zip(heights.chunks(CHUNK_WIDTH)) {
+                column.copy_from_slice(heights);
+            }
+            Ok(heightmap)
+        };
+
+        Ok(Self {
+            motion_blocking: record.motion_blocking.map(heightmap).transpose()?,
+            world_surface: record.world_surface.map(heightmap).transpose()?,
+        })
+    }
+}
+
+fn light_array(light: Vec<u8>) -> Result<Box<[u8; LIGHT_ARRAY_LENGTH]>> {
+    light
+        .into_boxed_slice()
+        .try_into()
+        .map_err(|_| Error::InvalidData("wrong light array length"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn section(chunk_y: u8, max_id: u32) -> ChunkSection {
+        let mut section = ChunkSection::empty(chunk_y);
+        for (i, block) in section.block_states.0.iter_mut().enumerate() {
+            *block = BlockState(i as u32 % (max_id + 1));
+        }
+        section.block_count = 1234;
+        section
+    }
+
+    fn lit_section(chunk_y: u8) -> ChunkSection {
+        ChunkSection {
+            block_light: Some(Box::new([0x3C; LIGHT_ARRAY_LENGTH])),
+            sky_light: Some(Box::new([0xF0; LIGHT_ARRAY_LENGTH])),
+            ..section(chunk_y, 3)
+        }
+    }
+
+    fn round_trip(chunk: &Chunk) -> Chunk {
+        let mut bytes = Vec::new();
+        chunk.write_to(&mut bytes).unwrap();
+        Chunk::read_from(&bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn empty_chunk() {
+        let chunk = Chunk::empty(0, 0);
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn empty_delta_chunk() {
+        let chunk = Chunk::empty_delta(-5, 12);
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn full_chunk() {
+        let mut chunk = Chunk::empty(3, -4);
+        chunk.sections = vec![section(0, 0), lit_section(1), section(15, 16000)];
+
+        let mut biomes = Biomes::default();
+        for (i, biome) in biomes.0.iter_mut().enumerate() {
+            *biome = BiomeId(i as u16);
+        }
+        chunk.biomes = Some(Box::new(biomes));
+
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn heightmaps() {
+        let mut motion_blocking = Heightmap::default();
+        for (x, column) in motion_blocking.0.iter_mut().enumerate() {
+            for (z, height) in column.iter_mut().enumerate() {
+                *height = (x * 16 + z) as u16;
+            }
+        }
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.heightmaps = Some(Box::new(Heightmaps {
+            motion_blocking: Some(motion_blocking),
+            world_surface: None,
+        }));
+
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn delta_chunk() {
+        let mut chunk = Chunk::empty_delta(1, 1);
+        chunk.sections = vec![lit_section(7), section(8, 1)];
+
+        assert_eq!(round_trip(&chunk), chunk);
+    }
+
+    #[test]
+    fn block_states_use_few_bits() {
+        let record = SectionRecord::from(&section(0, 1));
+        assert_eq!(record.bits_per_block, 1);
+        assert_eq!(record.block_states.len(), BLOCKS_PER_SECTION / 64);
+
+        let record = SectionRecord::from(&section(0, 300));
+        assert_eq!(record.bits_per_block, 9);
+    }
+
+    #[test]
+    fn unknown_records_are_skipped() {
+        let chunk = Chunk {
+            sections: vec![section(2, 5)],
+            ..Chunk::empty(0, 0)
+        };
+
+        let mut bytes = Vec::new();
+        chunk.write_to(&mut bytes).unwrap();
+
+        // Pretend a newer minor version added a record after the chunk.
+        bytes[MAGIC.len() + 2] = 7;
+        write_record(&mut bytes, 200, b"from the future").unwrap();
+
+        assert_eq!(Chunk::read_from(&bytes[..]).unwrap(), chunk);
+    }
+
+    #[test]
+    fn major_version_mismatch() {
+        let mut bytes = Vec::new();
+        Chunk::empty(0, 0).write_to(&mut bytes).unwrap();
+
+        bytes[MAGIC.len()] = bytes[MAGIC.len()].wrapping_add(1);
+
+        assert!(matches!(
+            Chunk::read_from(&bytes[..]),
+            Err(Error::UnsupportedVersion { major: 2, minor: 0 })
+        ));
+    }
+
+    #[test]
+    fn not_a_chunk_file() {
+        assert!(matches!(
+            Chunk::read_from(&b"BRINEBAK\x01\x00\x00\x00"[..]),
+            Err(Error::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn missing_chunk_record() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION_MAJOR.to_le_bytes());
+        bytes.extend_from_slice(&FORMAT_VERSION_MINOR.to_le_bytes());
+
+        assert!(matches!(
+            Chunk::read_from(&bytes[..]),
+            Err(Error::MissingChunk)
+        ));
+    }
+}
@@ -1,12 +1,25 @@
-//! A library for decoding Minecraft chunk data from network packets.
+//! A library for decoding (and encoding) Minecraft chunk data from network
+//! packets.
 //!
 //! Currently only supports version 1.14.4.
 
 use std::fmt;
 
+#[cfg(feature = "anvil")]
+pub mod anvil;
+pub mod classify;
 pub mod decode;
+pub mod encode;
+#[cfg(feature = "serde")]
+pub mod file;
+pub mod nbt;
+pub mod occupancy;
 pub mod palette;
+#[cfg(feature = "worldgen")]
+pub mod worldgen;
 
+pub use classify::BlockClassifier;
+pub use occupancy::SectionOccupancy;
 pub use palette::{Palette, SectionPalette};
 
 pub const CHUNK_HEIGHT: usize = 256;
@@ -15,6 +28,8 @@ pub const SECTION_HEIGHT: usize = 16;
 pub const SECTION_WIDTH: usize = CHUNK_WIDTH;
 pub const SECTIONS_PER_CHUNK: usize = CHUNK_HEIGHT / SECTION_HEIGHT;
 pub const BLOCKS_PER_SECTION: usize = SECTION_HEIGHT * SECTION_WIDTH * SECTION_WIDTH;
+/// Light levels are stored as one nibble per block.
+pub const LIGHT_ARRAY_LENGTH: usize = BLOCKS_PER_SECTION / 2;
 
 /// A [`Chunk`] is a 16x256x16 chunk of blocks. It is split vertically into 16 chunk
 /// sections (see [`ChunkSection`]).
@@ -40,6 +55,12 @@ pub struct Chunk {
     ///
     /// If this is not the full data of a chunk, this is not included.
     pub biomes: Option<Box<Biomes>>,
+
+    /// Heightmaps computed by the server.
+    ///
+    /// These are only sent with the full data of a chunk, and are decoded
+    /// separately with [`Chunk::decode_heightmaps`].
+    pub heightmaps: Option<Box<Heightmaps>>,
     // TODO: block entities
 }
 
@@ -50,6 +71,7 @@ impl Chunk {
             chunk_z,
             sections: Vec::new(),
             biomes: Some(Box::new(Biomes::default())),
+            heightmaps: None,
         }
     }
 
@@ -65,6 +87,173 @@ impl Chunk {
     pub fn is_full(&self) -> bool {
         self.biomes.is_some()
     }
+
+    /// Returns the `WORLD_SURFACE` height of the given column, i.e., one more
+    /// than the Y coordinate of its highest non-air block.
+    ///
+    /// Returns `None` if the chunk has no such heightmap or the column is
+    /// outside the chunk.
+    pub fn height_at(&self, x: u8, z: u8) -> Option<u16> {
+        self.heightmaps.as_ref()?.world_surface_at(x, z)
+    }
+
+    /// Returns the block at the given position relative to the chunk, where
+    /// `x` and `z` are in `0..16` and `y` is in `0..256`.
+    ///
+    /// Blocks in sections that are not present in the chunk are
+    /// [`BlockState::AIR`]. Returns `None` if the position is outside the chunk.
+    pub fn get_block_at_local(&self, x: u8, y: u16, z: u8) -> Option<BlockState> {
+        if x as usize >= CHUNK_WIDTH || y as usize >= CHUNK_HEIGHT || z as usize >= CHUNK_WIDTH {
+            return None;
+        }
+
+        let chunk_y = (y as usize / SECTION_HEIGHT) as u8;
+        let section_y = (y as usize % SECTION_HEIGHT) as u8;
+
+        let block = self
+            .sections
+            .iter()
+            .find(|section| section.chunk_y == chunk_y)
+            .map(|section| section.block_states.get_block(x, section_y, z))
+            .unwrap_or(BlockState::AIR);
+
+        Some(block)
+    }
+
+    /// Sets the block at the given position relative to the chunk, where `x`
+    /// and `z` are in `0..16` and `y` is in `0..256`, and returns the block
+    /// that was there before.
+    ///
+    /// If the section that the block is in isn't present, an empty one is
+    /// added. Returns `None` if the position is outside the chunk.
+    pub fn set_block_at_local(
+        &mut self,
+        x: u8,
+        y: u16,
+        z: u8,
+        block: BlockState,
+        classifier: &BlockClassifier,
+    ) -> Option<BlockState> {
+        if x as usize >= CHUNK_WIDTH || y as usize >= CHUNK_HEIGHT || z as usize >= CHUNK_WIDTH {
+            return None;
+        }
+
+        let chunk_y = (y as usize / SECTION_HEIGHT) as u8;
+        let section_y = (y as usize % SECTION_HEIGHT) as u8;
+
+        let index = match self
+            .sections
+            .binary_search_by_key(&chunk_y, |section| section.chunk_y)
+        {
+            Ok(index) => index,
+            Err(index) => {
+                self.sections.insert(index, ChunkSection::empty(chunk_y));
+                index
+            }
+        };
+
+        Some(self.sections[index].set_block(x, section_y, z, block, classifier))
+    }
+
+    /// Copies the light data of `light`'s sections (e.g., as decoded by
+    /// [`Chunk::decode_light`]) into the sections of this chunk with the same
+    /// Y coordinates.
+    ///
+    /// Light that `light` doesn't have is left as is, and light for sections
+    /// that this chunk doesn't have is discarded.
+    pub fn update_light(&mut self, light: &Chunk) {
+        for light_section in light.sections.iter() {
+            let section = self
+                .sections
+                .iter_mut()
+                .find(|section| section.chunk_y == light_section.chunk_y);
+
+            if let Some(section) = section {
+                section.update_light(light_section);
+            }
+        }
+    }
+
+    /// Computes the height of the highest non-air block in each vertical
+    /// column of the chunk, indexed as `heightmap[x][z]`.
+    ///
+    /// Columns that are entirely air have a height of 0.
+    pub fn compute_heightmap(
+        &self,
+        classifier: &BlockClassifier,
+    ) -> [[u16; CHUNK_WIDTH]; CHUNK_WIDTH] {
+        let mut heightmap = [[0; CHUNK_WIDTH]; CHUNK_WIDTH];
+        let mut remaining = CHUNK_WIDTH * CHUNK_WIDTH;
+        let mut found = [[false; CHUNK_WIDTH]; CHUNK_WIDTH];
+
+        for section in self.sections.iter().rev() {
+            for y in (0..SECTION_HEIGHT as u8).rev() {
+                for x in 0..CHUNK_WIDTH as u8 {
+                    for z in 0..CHUNK_WIDTH as u8 {
+                        let column = &mut found[x as usize][z as usize];
+                        if *column || classifier.is_air(section.block_states.get_block(x, y, z)) {
+                            continue;
+                        }
+
+                        *column = true;
+                        heightmap[x as usize][z as usize] =
+                            section.chunk_y as u16 * SECTION_HEIGHT as u16 + y as u16;
+
+                        remaining -= 1;
+                        if remaining == 0 {
+                            return heightmap;
+                        }
+                    }
+                }
+            }
+        }
+
+        heightmap
+    }
+
+    /// Iterates over all [`SECTIONS_PER_CHUNK`] Y levels of the chunk in
+    /// increasing Y order, yielding the section at each level or `None` if that
+    /// section is not present in [`sections`][Self::sections].
+    pub fn iter_all_sections(&self) -> impl Iterator<Item = (u8, Option<&ChunkSection>)> + '_ {
+        let mut sections = self.sections.iter().peekable();
+
+        (0..SECTIONS_PER_CHUNK as u8).map(move |chunk_y| {
+            let section = sections.next_if(|section| section.chunk_y == chunk_y);
+            (chunk_y, section)
+        })
+    }
+
+    /// Returns the minimum and maximum corners of the chunk's axis-aligned
+    /// bounding box, in world coordinates.
+    ///
+    /// The box spans the full width of the chunk, but only the heights of the
+    /// sections that have blocks in them, so that chunks with only a few
+    /// sections can be culled more tightly. A chunk with no blocks at all has
+    /// an empty box at the bottom of the chunk.
+    pub fn world_aabb(&self) -> ([f32; 3], [f32; 3]) {
+        let min_x = (self.chunk_x * CHUNK_WIDTH as i32) as f32;
+        let min_z = (self.chunk_z * CHUNK_WIDTH as i32) as f32;
+
+        let mut populated = self
+            .sections
+            .iter()
+            .filter(|section| section.block_count > 0)
+            .map(|section| section.chunk_y as usize);
+
+        let (bottom, top) = match populated.next() {
+            Some(bottom) => (bottom, populated.last().unwrap_or(bottom) + 1),
+            None => return ([min_x, 0.0, min_z], [min_x, 0.0, min_z]),
+        };
+
+        (
+            [min_x, (bottom * SECTION_HEIGHT) as f32, min_z],
+            [
+                min_x + CHUNK_WIDTH as f32,
+                (top * SECTION_HEIGHT) as f32,
+                min_z + CHUNK_WIDTH as f32,
+            ],
+        )
+    }
 }
 
 /// A [`ChunkSection`] is a 16x16x16 cubic section of a [`Chunk`].
@@ -76,9 +265,24 @@ pub struct ChunkSection {
     /// purposes. "Non-air" is defined as any block other than air, cave air,
     /// and void air (in particular, note that fluids such as water are still
     /// counted).
+    ///
+    /// This is trusted as received; see [`assert_block_count`] to check it.
+    ///
+    /// [`assert_block_count`]: ChunkSection::assert_block_count
     pub block_count: u16,
     /// The block state for every block in the chunk section.
     pub block_states: BlockStates,
+    /// Block light level (0-15) for every block in the chunk section, packed
+    /// as nibbles in the same Y-Z-X order as [`BlockStates`].
+    ///
+    /// This is `None` if no light data has been received for this section.
+    pub block_light: Option<Box<[u8; LIGHT_ARRAY_LENGTH]>>,
+    /// Sky light level (0-15) for every block in the chunk section, packed
+    /// the same way as `block_light`.
+    ///
+    /// This is `None` if no light data has been received for this section, or
+    /// if the section is in a dimension without a sky.
+    pub sky_light: Option<Box<[u8; LIGHT_ARRAY_LENGTH]>>,
 }
 
 impl ChunkSection {
@@ -87,9 +291,42 @@ impl ChunkSection {
             chunk_y,
             block_count: 0,
             block_states: Default::default(),
+            block_light: None,
+            sky_light: None,
+        }
+    }
+
+    /// Copies the sky light and block light that `light` has into this
+    /// section. Light that `light` doesn't have is left as is.
+    pub fn update_light(&mut self, light: &ChunkSection) {
+        if let Some(sky_light) = light.sky_light.as_ref() {
+            self.sky_light = Some(sky_light.clone());
+        }
+        if let Some(block_light) = light.block_light.as_ref() {
+            self.block_light = Some(block_light.clone());
         }
     }
 
+    /// Returns the block light level at the given position, or 0 if there is no
+    /// block light data for this section.
+    #[inline]
+    pub fn get_block_light(&self, x: u8, y: u8, z: u8) -> u8 {
+        self.block_light
+            .as_deref()
+            .map(|light| get_nibble(light, x, y, z))
+            .unwrap_or(0)
+    }
+
+    /// Returns the sky light level at the given position, or 0 if there is no
+    /// sky light data for this section.
+    #[inline]
+    pub fn get_sky_light(&self, x: u8, y: u8, z: u8) -> u8 {
+        self.sky_light
+            .as_deref()
+            .map(|light| get_nibble(light, x, y, z))
+            .unwrap_or(0)
+    }
+
     #[inline]
     pub fn get_block<K>(&self, key: K) -> Result<BlockState, <K as TryInto<SectionKey>>::Error>
     where
@@ -100,6 +337,58 @@ impl ChunkSection {
         let SectionKey { x, y, z } = key;
         Ok(self.block_states.get_block(x, y, z))
     }
+
+    /// Sets the block at the given position in the section, keeping
+    /// [`block_count`][Self::block_count] up to date, and returns the block
+    /// that was there before.
+    pub fn set_block(
+        &mut self,
+        x: u8,
+        y: u8,
+        z: u8,
+        block: BlockState,
+        classifier: &BlockClassifier,
+    ) -> BlockState {
+        let index = BlockStates::xyz_to_index(x, y, z);
+        let previous = std::mem::replace(&mut self.block_states.0[index], block);
+
+        match (classifier.is_air(previous), classifier.is_air(block)) {
+            (true, false) => self.block_count += 1,
+            (false, true) => self.block_count -= 1,
+            _ => {}
+        }
+
+        previous
+    }
+
+    /// Panics if [`block_count`][Self::block_count] isn't the number of
+    /// non-air blocks in the section, as counted by
+    /// [`BlockStates::count_non_air`].
+    #[track_caller]
+    pub fn assert_block_count(&self, classifier: &BlockClassifier) {
+        let counted = self.block_states.count_non_air(classifier);
+        assert_eq!(
+            self.block_count, counted,
+            "section {} has a block count of {}, but {} non-air blocks",
+            self.chunk_y, self.block_count, counted
+        );
+    }
+}
+
+/// Even indices are stored in the low nibble of each byte, odd indices in the
+/// high nibble.
+///
+/// See <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Data_structure>.
+#[inline]
+fn get_nibble(light: &[u8; LIGHT_ARRAY_LENGTH], x: u8, y: u8, z: u8) -> u8 {
+    let index = BlockStates::xyz_to_index(x, y, z);
+    let byte = light[index / 2];
+
+    if index % 2 == 0 {
+        byte & 0x0F
+    } else {
+        byte >> 4
+    }
 }
 
 /// A [`SectionKey`] is used to index a single block in a [`ChunkSection`]
@@ -179,6 +468,16 @@ impl BlockStates {
         let z = (index & Self::Z_MASK) >> Self::Z_SHIFT;
         (x as u8, y as u8, z as u8)
     }
+
+    /// Returns the number of blocks that aren't air, the way Minecraft counts
+    /// them for [`ChunkSection::block_count`]: air, cave air, and void air are
+    /// left out, but fluids are counted.
+    pub fn count_non_air(&self, classifier: &BlockClassifier) -> u16 {
+        self.0
+            .iter()
+            .filter(|block_state| !classifier.is_air(**block_state))
+            .count() as u16
+    }
 }
 
 impl Default for BlockStates {
@@ -240,6 +539,42 @@ impl BlockState {
 
     /// See <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Direct>.
     pub const MAX_BLOCK_STATES_LOG_2: usize = 14;
+
+    /// Returns a wrapper that formats the block state as the name that
+    /// `registry` gives its id (e.g., `minecraft:stone`), or as `BlockState(1)`
+    /// if the registry doesn't know it.
+    #[inline]
+    pub fn debug_with<'a>(
+        &self,
+        registry: &'a dyn Fn(u32) -> Option<&'a str>,
+    ) -> DisplayBlockState<'a> {
+        DisplayBlockState {
+            block_state: *self,
+            registry,
+        }
+    }
+}
+
+/// Formats a [`BlockState`] by name. See [`BlockState::debug_with`].
+#[derive(Clone, Copy)]
+pub struct DisplayBlockState<'a> {
+    block_state: BlockState,
+    registry: &'a dyn Fn(u32) -> Option<&'a str>,
+}
+
+impl<'a> fmt::Display for DisplayBlockState<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.registry)(self.block_state.0) {
+            Some(name) => f.write_str(name),
+            None => fmt::Debug::fmt(&self.block_state, f),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for DisplayBlockState<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
 }
 
 /// Grid of biome IDs dictating which biome a given vertical X,Z slice of a
@@ -268,3 +603,289 @@ pub struct BiomeId(pub u16);
 impl BiomeId {
     pub const VOID: Self = Self(127);
 }
+
+/// The heightmaps that the server sends along with a full [`Chunk`].
+///
+/// See <https://wiki.vg/index.php?title=Chunk_Format&oldid=14901#Heightmaps>.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Heightmaps {
+    /// Heights of the highest blocks that block motion or contain a fluid.
+    pub motion_blocking: Option<Heightmap>,
+
+    /// Heights of the highest non-air blocks.
+    pub world_surface: Option<Heightmap>,
+}
+
+impl Heightmaps {
+    /// Returns the `MOTION_BLOCKING` height of the given column, if the server
+    /// sent that heightmap and the column is inside the chunk.
+    pub fn motion_blocking_at(&self, x: u8, z: u8) -> Option<u16> {
+        self.motion_blocking.as_ref()?.get(x, z)
+    }
+
+    /// Returns the `WORLD_SURFACE` height of the given column, if the server
+    /// sent that heightmap and the column is inside the chunk.
+    pub fn world_surface_at(&self, x: u8, z: u8) -> Option<u16> {
+        self.world_surface.as_ref()?.get(x, z)
+    }
+}
+
+/// Grid of heights, one for each vertical X,Z column of a [`Chunk`], indexed
+/// as `heightmap.0[x][z]`.
+///
+/// A height is one more than the Y coordinate of the highest matching block in
+/// the column, or 0 if the column has no matching block.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Heightmap(pub [[u16; CHUNK_WIDTH]; CHUNK_WIDTH]);
+
+impl Heightmap {
+    /// Returns the height of the given column, or `None` if it's outside the
+    /// chunk.
+    pub fn get(&self, x: u8, z: u8) -> Option<u16> {
+        self.0.get(x as usize)?.get(z as usize).copied()
+    }
+}
+
+impl Default for Heightmap {
+    fn default() -> Self {
+        Self([[0; CHUNK_WIDTH]; CHUNK_WIDTH])
+    }
+}
+
+impl fmt::Debug for Heightmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Heightmap").field(&"...").finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn iter_all_sections() {
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![ChunkSection::empty(0), ChunkSection::empty(5)];
+
+        let all: Vec<(u8, Option<u8>)> = chunk
+            .iter_all_sections()
+            .map(|(chunk_y, section)| (chunk_y, section.map(|section| section.chunk_y)))
+            .collect();
+
+        assert_eq!(all.len(), SECTIONS_PER_CHUNK);
+        for (i, (chunk_y, section)) in all.into_iter().enumerate() {
+            assert_eq!(chunk_y as usize, i);
+            match chunk_y {
+                0 | 5 => assert_eq!(section, Some(chunk_y)),
+                _ => assert_eq!(section, None),
+            }
+        }
+    }
+
+    #[test]
+    fn world_aabb() {
+        let mut chunk = Chunk::empty(2, -1);
+        assert_eq!(chunk.world_aabb(), ([32.0, 0.0, -16.0], [32.0, 0.0, -16.0]));
+
+        chunk.sections = (2..4)
+            .map(|chunk_y| ChunkSection {
+                block_count: 1,
+                ..ChunkSection::empty(chunk_y)
+            })
+            .collect();
+        // Sections without blocks don't count.
+        chunk.sections.push(ChunkSection::empty(9));
+
+        assert_eq!(chunk.world_aabb(), ([32.0, 32.0, -16.0], [48.0, 64.0, 0.0]));
+    }
+
+    #[test]
+    fn compute_heightmap() {
+        let classifier = BlockClassifier::new([BlockState::AIR], []);
+
+        // Section 2 is filled up to (and including) Y = 5 within the section.
+        let mut floor = ChunkSection::empty(2);
+        for (i, block) in floor.block_states.0.iter_mut().enumerate() {
+            let (_, y, _) = BlockStates::index_to_xyz(i);
+            if y <= 5 {
+                *block = BlockState(1);
+            }
+        }
+        floor.block_count = 6 * 16 * 16;
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![ChunkSection::empty(0), floor, ChunkSection::empty(9)];
+
+        assert_eq!(chunk.compute_heightmap(&classifier), [[2 * 16 + 5; 16]; 16]);
+        assert_eq!(
+            Chunk::empty(0, 0).compute_heightmap(&classifier),
+            [[0; 16]; 16]
+        );
+    }
+
+    #[test]
+    fn count_non_air() {
+        const STONE: BlockState = BlockState(1);
+        const WATER: BlockState = BlockState(34);
+        const CAVE_AIR: BlockState = BlockState(9130);
+        const VOID_AIR: BlockState = BlockState(9129);
+
+        let classifier = BlockClassifier::new([BlockState::AIR, CAVE_AIR, VOID_AIR], [WATER]);
+
+        // Stone in the bottom layer, water in the next two, and every kind of
+        // air above that.
+        let mut section = ChunkSection::empty(0);
+        for (i, block) in section.block_states.0.iter_mut().enumerate() {
+            let (_, y, _) = BlockStates::index_to_xyz(i);
+            *block = match y {
+                0 => STONE,
+                1 | 2 => WATER,
+                3 => CAVE_AIR,
+                4 => VOID_AIR,
+                _ => BlockState::AIR,
+            };
+        }
+
+        assert_eq!(section.block_states.count_non_air(&classifier), 3 * 16 * 16);
+
+        section.block_count = 3 * 16 * 16;
+        section.assert_block_count(&classifier);
+    }
+
+    #[test]
+    #[should_panic(expected = "block count of 0")]
+    fn wrong_block_count() {
+        let classifier = BlockClassifier::new([BlockState::AIR], []);
+
+        let mut section = ChunkSection::empty(0);
+        section.block_states.0[0] = BlockState(1);
+
+        section.assert_block_count(&classifier);
+    }
+
+    #[test]
+    fn display_block_state_by_name() {
+        let registry: &dyn Fn(u32) -> Option<&str> = &|id| match id {
+            1 => Some("minecraft:stone"),
+            _ => None,
+        };
+
+        assert_eq!(
+            BlockState(1).debug_with(registry).to_string(),
+            "minecraft:stone"
+        );
+        assert_eq!(
+            BlockState(2).debug_with(registry).to_string(),
+            "BlockState(2)"
+        );
+        assert_eq!(
+            BlockState(1).debug_with(&|_| None).to_string(),
+            "BlockState(1)"
+        );
+    }
+
+    #[test]
+    fn set_block_at_local() {
+        const STONE: BlockState = BlockState(1);
+        let classifier = BlockClassifier::new([BlockState::AIR], []);
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![ChunkSection::empty(2)];
+
+        assert_eq!(
+            chunk.set_block_at_local(3, 5 * 16 + 4, 7, STONE, &classifier),
+            Some(BlockState::AIR)
+        );
+        assert_eq!(chunk.get_block_at_local(3, 5 * 16 + 4, 7), Some(STONE));
+        assert_eq!(
+            chunk
+                .sections
+                .iter()
+                .map(|section| section.chunk_y)
+                .collect::<Vec<_>>(),
+            [2, 5]
+        );
+        chunk.sections[1].assert_block_count(&classifier);
+
+        assert_eq!(
+            chunk.set_block_at_local(3, 5 * 16 + 4, 7, BlockState::AIR, &classifier),
+            Some(STONE)
+        );
+        chunk.sections[1].assert_block_count(&classifier);
+
+        assert_eq!(
+            chunk.set_block_at_local(0, 256, 0, STONE, &classifier),
+            None
+        );
+    }
+
+    #[test]
+    fn update_light() {
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![ChunkSection::empty(0), ChunkSection::empty(1)];
+        chunk.sections[0].block_light = Some(Box::new([0x33; LIGHT_ARRAY_LENGTH]));
+        chunk.sections[0].sky_light = Some(Box::new([0xff; LIGHT_ARRAY_LENGTH]));
+
+        // Only sky light for section 0, both for section 1, and a section that
+        // the chunk doesn't have.
+        let mut light = Chunk::empty_delta(0, 0);
+        let mut section_0 = ChunkSection::empty(0);
+        section_0.sky_light = Some(Box::new([0x00; LIGHT_ARRAY_LENGTH]));
+        let mut section_1 = ChunkSection::empty(1);
+        section_1.block_light = Some(Box::new([0x77; LIGHT_ARRAY_LENGTH]));
+        section_1.sky_light = Some(Box::new([0xee; LIGHT_ARRAY_LENGTH]));
+        light.sections = vec![section_0, section_1, ChunkSection::empty(7)];
+
+        chunk.update_light(&light);
+
+        assert_eq!(chunk.sections[0].get_block_light(1, 2, 3), 3);
+        assert_eq!(chunk.sections[0].get_sky_light(1, 2, 3), 0);
+        assert_eq!(chunk.sections[1].get_block_light(1, 2, 3), 7);
+        assert_eq!(chunk.sections[1].get_sky_light(1, 2, 3), 14);
+        assert_eq!(chunk.sections.len(), 2);
+    }
+
+    mod get_block_at_local {
+        use super::*;
+
+        fn chunk_with_block_in_section_5() -> Chunk {
+            let mut section = ChunkSection::empty(5);
+            section.block_states.0[BlockStates::xyz_to_index(3, 4, 7)] = BlockState(1);
+
+            let mut chunk = Chunk::empty(0, 0);
+            chunk.sections = vec![section];
+            chunk
+        }
+
+        #[test]
+        fn in_range() {
+            let chunk = chunk_with_block_in_section_5();
+
+            assert_eq!(
+                chunk.get_block_at_local(3, 5 * 16 + 4, 7),
+                Some(BlockState(1))
+            );
+            assert_eq!(
+                chunk.get_block_at_local(3, 5 * 16 + 5, 7),
+                Some(BlockState::AIR)
+            );
+        }
+
+        #[test]
+        fn empty_section() {
+            let chunk = chunk_with_block_in_section_5();
+
+            assert_eq!(chunk.get_block_at_local(3, 4, 7), Some(BlockState::AIR));
+            assert_eq!(chunk.get_block_at_local(15, 255, 15), Some(BlockState::AIR));
+        }
+
+        #[test]
+        fn out_of_range() {
+            let chunk = chunk_with_block_in_section_5();
+
+            assert_eq!(chunk.get_block_at_local(3, 256, 7), None);
+            assert_eq!(chunk.get_block_at_local(16, 0, 0), None);
+            assert_eq!(chunk.get_block_at_local(0, 0, 16), None);
+        }
+    }
+}
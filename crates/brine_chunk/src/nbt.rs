@@ -0,0 +1,265 @@
+//! A minimal reader for Minecraft's Named Binary Tag (NBT) format.
+//!
+//! Only reading is supported, and only as much as is needed to pull chunk data
+//! out of packets and region files.
+//!
+//! See <https://wiki.vg/NBT>.
+
+use std::{collections::HashMap, io};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("invalid NBT: {0}")]
+    Invalid(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Compound tags nested deeper than this are rejected rather than risking a
+/// stack overflow on malicious input.
+const MAX_DEPTH: usize = 64;
+
+/// A single NBT tag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<u8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(HashMap<String, Tag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    /// Reads a named root tag, returning its name and value.
+    pub fn read_named(data: &mut impl io::Read) -> Result<(String, Self)> {
+        Self::read_optional_named(data)?.ok_or(Error::Invalid("root tag is TAG_End"))
+    }
+
+    /// Reads a named root tag, or returns `None` if the root tag is a lone
+    /// `TAG_End` (which is how packets encode a missing tag).
+    pub fn read_optional_named(data: &mut impl io::Read) -> Result<Option<(String, Self)>> {
+        let tag_type = data.read_u8()?;
+        if tag_type == 0 {
+            return Ok(None);
+        }
+
+        let name = read_string(data)?;
+        let tag = Self::read_payload(tag_type, data, 0)?;
+
+        Ok(Some((name, tag)))
+    }
+
+    fn read_payload(tag_type: u8, data: &mut impl io::Read, depth: usize) -> Result<Self> {
+        if depth > MAX_DEPTH {
+            return Err(Error::Invalid("tags nested too deeply"));
+        }
+
+        Ok(match tag_type {
+            1 => Self::Byte(data.read_i8()?),
+            2 => Self::Short(data.read_i16::<BigEndian>()?),
+            3 => Self::Int(data.read_i32::<BigEndian>()?),
+            4 => Self::Long(data.read_i64::<BigEndian>()?),
+            5 => Self::Float(data.read_f32::<BigEndian>()?),
+            6 => Self::Double(data.read_f64::<BigEndian>()?),
+            7 => {
+                let length = read_length(data)?;
+                let mut bytes = vec![0; length];
+                data.read_exact(&mut bytes)?;
+                Self::ByteArray(bytes)
+            }
+            8 => Self::String(read_string(data)?),
+            9 => {
+                let element_type = data.read_u8()?;
+                let length = read_length(data)?;
+                let mut elements = Vec::with_capacity(length.min(1024));
+                for _ in 0..length {
+                    elements.push(Self::read_payload(element_type, data, depth + 1)?);
+                }
+                Self::List(elements)
+            }
+            10 => {
+                let mut entries = HashMap::new();
+                loop {
+                    let entry_type = data.read_u8()?;
+                    if entry_type == 0 {
+                        break;
+                    }
+                    let name = read_string(data)?;
+                    let entry = Self::read_payload(entry_type, data, depth + 1)?;
+                    entries.insert(name, entry);
+                }
+                Self::Compound(entries)
+            }
+            11 => {
+                let length = read_length(data)?;
+                let mut ints = Vec::with_capacity(length.min(1024));
+                for _ in 0..length {
+                    ints.push(data.read_i32::<BigEndian>()?);
+                }
+                Self::IntArray(ints)
+            }
+            12 => {
+                let length = read_length(data)?;
+                let mut longs = Vec::with_capacity(length.min(1024));
+                for _ in 0..length {
+                    longs.push(data.read_i64::<BigEndian>()?);
+                }
+                Self::LongArray(longs)
+            }
+            _ => return Err(Error::Invalid("unknown tag type")),
+        })
+    }
+
+    /// Looks up an entry of a compound tag.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.as_compound()?.get(name)
+    }
+
+    #[inline]
+    pub fn as_byte(&self) -> Option<i8> {
+        match self {
+            Self::Byte(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            Self::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_byte_array(&self) -> Option<&[u8]> {
+        match self {
+            Self::ByteArray(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_int_array(&self) -> Option<&[i32]> {
+        match self {
+            Self::IntArray(ints) => Some(ints),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_long_array(&self) -> Option<&[i64]> {
+        match self {
+            Self::LongArray(longs) => Some(longs),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Self::List(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_compound(&self) -> Option<&HashMap<String, Tag>> {
+        match self {
+            Self::Compound(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+fn read_length(data: &mut impl io::Read) -> Result<usize> {
+    let length = data.read_i32::<BigEndian>()?;
+    length
+        .try_into()
+        .map_err(|_| Error::Invalid("negative length"))
+}
+
+fn read_string(data: &mut impl io::Read) -> Result<String> {
+    let length = data.read_u16::<BigEndian>()?;
+    let mut bytes = vec![0; length as usize];
+    data.read_exact(&mut bytes)?;
+
+    // NBT strings are "modified UTF-8", which only differs from UTF-8 for
+    // characters that never appear in block names.
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_compound() {
+        #[rustfmt::skip]
+        let data: &[u8] = &[
+            10, 0, 4, b'r', b'o', b'o', b't',
+                1, 0, 1, b'Y', 0xFF,
+                8, 0, 4, b'N', b'a', b'm', b'e', 0, 3, b'f', b'o', b'o',
+                9, 0, 1, b'L', 3, 0, 0, 0, 2,
+                    0, 0, 0, 1,
+                    0, 0, 0, 2,
+                12, 0, 1, b'A', 0, 0, 0, 1,
+                    0x80, 0, 0, 0, 0, 0, 0, 1,
+            0,
+        ];
+
+        let (name, tag) = Tag::read_named(&mut &data[..]).unwrap();
+
+        assert_eq!(name, "root");
+        assert_eq!(tag.get("Y").and_then(Tag::as_byte), Some(-1));
+        assert_eq!(tag.get("Name").and_then(Tag::as_str), Some("foo"));
+        assert_eq!(
+            tag.get("L").and_then(Tag::as_list),
+            Some(&[Tag::Int(1), Tag::Int(2)][..])
+        );
+        assert_eq!(
+            tag.get("A").and_then(Tag::as_long_array),
+            Some(&[i64::MIN + 1][..])
+        );
+        assert_eq!(tag.get("missing"), None);
+    }
+
+    #[test]
+    fn invalid_tag_type() {
+        let data: &[u8] = &[10, 0, 0, 42, 0, 0];
+
+        assert!(matches!(
+            Tag::read_named(&mut &data[..]),
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn missing_root_tag() {
+        let data: &[u8] = &[0];
+
+        assert!(Tag::read_optional_named(&mut &data[..]).unwrap().is_none());
+        assert!(Tag::read_named(&mut &data[..]).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Coarse occupancy of chunk sections, for quickly skipping empty space.
+//!
+//! A [`SectionOccupancy`] splits a [`ChunkSection`] into 4x4x4 regions of
+//! blocks and records which of them contain anything other than air, in a
+//! single `u64`. It is cheap to copy and to query, so it can be computed once
+//! when a section is loaded and reused by anything that wants to skip over
+//! empty space, e.g., frustum culling or a physics broadphase.
+
+use crate::{BlockClassifier, BlockStates, ChunkSection, SECTION_WIDTH};
+
+/// Which of the 4x4x4 regions of a [`ChunkSection`] contain any non-air
+/// blocks.
+///
+/// See [`ChunkSection::occupancy_mask`] and the [module documentation][self].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectionOccupancy(pub u64);
+
+impl SectionOccupancy {
+    /// Width of each region along every axis, in blocks.
+    pub const REGION_WIDTH: u8 = 4;
+
+    /// Number of regions along every axis of a section.
+    pub const REGIONS_PER_AXIS: u8 = (SECTION_WIDTH as u8) / Self::REGION_WIDTH;
+
+    /// The occupancy of a section where every region has a non-air block.
+    pub const FULL: Self = Self(u64::MAX);
+
+    /// Returns the occupancy of the given section's blocks.
+    pub fn of(block_states: &BlockStates, classifier: &BlockClassifier) -> Self {
+        let mut occupancy = Self::default();
+
+        for (x, y, z, block_state) in block_states.iter() {
+            if !classifier.is_air(block_state) {
+                occupancy.0 |= Self::block_bit(x, y, z);
+            }
+        }
+
+        occupancy
+    }
+
+    /// Returns true if every block in the section is air.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of regions that contain non-air blocks.
+    #[inline]
+    pub fn count_occupied(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns true if the region at region coordinates `[x, y, z]` (each in
+    /// `0..4`) contains any non-air blocks.
+    #[inline]
+    pub fn is_region_occupied(&self, x: u8, y: u8, z: u8) -> bool {
+        self.0 & Self::region_bit(x, y, z) != 0
+    }
+
+    /// Returns true if the region that contains the block at `[x, y, z]`
+    /// contains any non-air blocks.
+    ///
+    /// If this returns false, the block is definitely air.
+    #[inline]
+    pub fn is_block_region_occupied(&self, x: u8, y: u8, z: u8) -> bool {
+        self.0 & Self::block_bit(x, y, z) != 0
+    }
+
+    /// Returns true if any of the regions that overlap the box of blocks from
+    /// `min` to `max` (inclusive) contain non-air blocks.
+    ///
+    /// If this returns false, every block in the box is definitely air.
+    pub fn is_box_occupied(&self, min: [u8; 3], max: [u8; 3]) -> bool {
+        let [min_x, min_y, min_z] = min.map(|coord| coord / Self::REGION_WIDTH);
+        let [max_x, max_y, max_z] = max.map(|coord| coord / Self::REGION_WIDTH);
+
+        (min_y..=max_y).any(|y| {
+            (min_z..=max_z).any(|z| (min_x..=max_x).any(|x| self.is_region_occupied(x, y, z)))
+        })
+    }
+
+    /// Iterates over the region coordinates of every occupied region, in
+    /// Y-Z-X order.
+    pub fn iter_occupied(&self) -> impl Iterator<Item = [u8; 3]> + '_ {
+        let n = Self::REGIONS_PER_AXIS;
+
+        (0..n)
+            .flat_map(move |y| (0..n).flat_map(move |z| (0..n).map(move |x| [x, y, z])))
+            .filter(|&[x, y, z]| self.is_region_occupied(x, y, z))
+    }
+
+    /// Regions are stored in the same Y-Z-X order as blocks are in
+    /// [`BlockStates`], 2 bits per axis.
+    #[inline]
+    fn region_bit(x: u8, y: u8, z: u8) -> u64 {
+        debug_assert!(x < Self::REGIONS_PER_AXIS);
+        debug_assert!(y < Self::REGIONS_PER_AXIS);
+        debug_assert!(z < Self::REGIONS_PER_AXIS);
+
+        1 << ((y << 4) | (z << 2) | x)
+    }
+
+    #[inline]
+    fn block_bit(x: u8, y: u8, z: u8) -> u64 {
+        Self::region_bit(
+            x / Self::REGION_WIDTH,
+            y / Self::REGION_WIDTH,
+            z / Self::REGION_WIDTH,
+        )
+    }
+}
+
+impl ChunkSection {
+    /// Computes which 4x4x4 regions of the section contain non-air blocks.
+    ///
+    /// This looks at every block in the section, so hold on to the result
+    /// rather than calling this repeatedly.
+    pub fn occupancy_mask(&self, classifier: &BlockClassifier) -> SectionOccupancy {
+        if self.block_count == 0 {
+            return SectionOccupancy::default();
+        }
+
+        SectionOccupancy::of(&self.block_states, classifier)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::BlockState;
+
+    use super::*;
+
+    #[test]
+    fn single_block_occupies_one_region() {
+        let classifier = BlockClassifier::new([BlockState::AIR], []);
+
+        let mut section = ChunkSection::empty(0);
+        assert!(section.occupancy_mask(&classifier).is_empty());
+
+        section.set_block(5, 14, 3, BlockState(1), &classifier);
+        let occupancy = section.occupancy_mask(&classifier);
+
+        assert_eq!(occupancy.count_occupied(), 1);
+        assert_eq!(occupancy.iter_occupied().collect::<Vec<_>>(), [[1, 3, 0]]);
+        assert!(occupancy.is_region_occupied(1, 3, 0));
+        assert!(occupancy.is_block_region_occupied(4, 15, 0));
+        assert!(!occupancy.is_block_region_occupied(3, 14, 3));
+
+        assert!(occupancy.is_box_occupied([0, 0, 0], [15, 15, 15]));
+        assert!(occupancy.is_box_occupied([7, 12, 3], [7, 12, 3]));
+        assert!(!occupancy.is_box_occupied([0, 0, 0], [15, 11, 15]));
+        assert!(!occupancy.is_box_occupied([8, 0, 0], [15, 15, 15]));
+    }
+
+    #[test]
+    fn fluids_are_occupied() {
+        const WATER: BlockState = BlockState(34);
+        let classifier = BlockClassifier::new([BlockState::AIR], [WATER]);
+
+        let mut section = ChunkSection::empty(0);
+        for (i, block) in section.block_states.0.iter_mut().enumerate() {
+            let (_, y, _) = BlockStates::index_to_xyz(i);
+            if y < 4 {
+                *block = WATER;
+            }
+        }
+        section.block_count = 4 * 16 * 16;
+
+        let occupancy = section.occupancy_mask(&classifier);
+        assert_eq!(occupancy.count_occupied(), 16);
+        assert!(occupancy.iter_occupied().all(|[_, y, _]| y == 0));
+
+        section.block_states = BlockStates([BlockState(1); crate::BLOCKS_PER_SECTION]);
+        section.block_count = crate::BLOCKS_PER_SECTION as u16;
+        assert_eq!(section.occupancy_mask(&classifier), SectionOccupancy::FULL);
+    }
+}
@@ -0,0 +1,335 @@
+//! Deterministic generation of test worlds.
+//!
+//! Every [`Preset`] produces the same chunks for the same seed, so that
+//! examples can be compared visually and benchmarks can be compared from run to
+//! run.
+//!
+//! Blocks use the block state IDs of Minecraft 1.14.4.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    BlockState, BlockStates, Chunk, ChunkSection, CHUNK_WIDTH, SECTIONS_PER_CHUNK, SECTION_HEIGHT,
+};
+
+pub const STONE: BlockState = BlockState(1);
+pub const GRASS_BLOCK: BlockState = BlockState(9);
+pub const DIRT: BlockState = BlockState(10);
+pub const BEDROCK: BlockState = BlockState(33);
+
+/// Height of the ground in [`Preset::Flat`], and the height that the ground in
+/// [`Preset::Hills`] rolls around.
+const GROUND_HEIGHT: i32 = 64;
+
+/// How far [`Preset::Hills`] rises above and falls below [`GROUND_HEIGHT`].
+const HILL_AMPLITUDE: f64 = 24.0;
+
+/// Number of dirt blocks between the grass and the stone.
+const DIRT_DEPTH: i32 = 3;
+
+/// Number of sections filled by [`Preset::Checkerboard`] and
+/// [`Preset::SwissCheese`].
+const FILLED_SECTIONS: u8 = 4;
+
+/// Number of holes carved out of each chunk by [`Preset::SwissCheese`], and
+/// the range of their radii.
+const HOLES_PER_CHUNK: u64 = 12;
+const MIN_HOLE_RADIUS: u64 = 2;
+const MAX_HOLE_RADIUS: u64 = 6;
+
+/// A kind of test world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// A superflat world: bedrock, then dirt, then grass.
+    Flat,
+
+    /// Rolling sine-wave hills of stone, topped with dirt and grass.
+    Hills,
+
+    /// Alternating stone and air blocks in every direction, which is the worst
+    /// case for the number of faces in a mesh.
+    Checkerboard,
+
+    /// Solid stone full of spherical holes.
+    SwissCheese,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Flat,
+        Preset::Hills,
+        Preset::Checkerboard,
+        Preset::SwissCheese,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Flat => "flat",
+            Preset::Hills => "hills",
+            Preset::Checkerboard => "checkerboard",
+            Preset::SwissCheese => "swiss-cheese",
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown preset {0:?}, expected one of: flat, hills, checkerboard, swiss-cheese")]
+pub struct UnknownPreset(String);
+
+impl FromStr for Preset {
+    type Err = UnknownPreset;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == s)
+            .ok_or_else(|| UnknownPreset(s.to_string()))
+    }
+}
+
+/// Generator of the chunks of a [`Preset`] world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldGen {
+    pub preset: Preset,
+    pub seed: u64,
+}
+
+impl WorldGen {
+    pub fn new(preset: Preset, seed: u64) -> Self {
+        Self { preset, seed }
+    }
+
+    /// Generates the `size` by `size` chunks whose chunk coordinates are in
+    /// `0..size`, in X-major order.
+    pub fn chunks(&self, size: u32) -> Vec<Chunk> {
+        let size = size as i32;
+        (0..size)
+            .flat_map(|chunk_x| (0..size).map(move |chunk_z| (chunk_x, chunk_z)))
+            .map(|(chunk_x, chunk_z)| self.chunk(chunk_x, chunk_z))
+            .collect()
+    }
+
+    /// Generates the full data of a single chunk.
+    ///
+    /// Neighboring chunks line up with each other, except for the holes of
+    /// [`Preset::SwissCheese`], which don't cross chunk boundaries.
+    pub fn chunk(&self, chunk_x: i32, chunk_z: i32) -> Chunk {
+        let mut chunk = Chunk::empty(chunk_x, chunk_z);
+        let holes = self.holes(chunk_x, chunk_z);
+
+        for chunk_y in 0..SECTIONS_PER_CHUNK as u8 {
+            let mut section = ChunkSection::empty(chunk_y);
+
+            for (i, block) in section.block_states.0.iter_mut().enumerate() {
+                let (x, y, z) = BlockStates::index_to_xyz(i);
+                let local = [
+                    x as i32,
+                    (chunk_y as usize * SECTION_HEIGHT) as i32 + y as i32,
+                    z as i32,
+                ];
+                let world = [
+                    chunk_x * CHUNK_WIDTH as i32 + local[0],
+                    local[1],
+                    chunk_z * CHUNK_WIDTH as i32 + local[2],
+                ];
+                *block = self.block_at(world, local, &holes);
+            }
+
+            section.block_count = section
+                .block_states
+                .0
+                .iter()
+                .filter(|block| **block != BlockState::AIR)
+                .count() as u16;
+
+            if section.block_count > 0 {
+                chunk.sections.push(section);
+            }
+        }
+
+        chunk
+    }
+
+    fn block_at(&self, [x, y, z]: [i32; 3], local: [i32; 3], holes: &[Hole]) -> BlockState {
+        match self.preset {
+            Preset::Flat => layered(y, GROUND_HEIGHT),
+            Preset::Hills => layered(y, self.hill_height(x, z)),
+            Preset::Checkerboard => {
+                let parity = (self.seed % 2) as i32;
+                if y < filled_height() && (x + y + z).rem_euclid(2) == parity {
+                    STONE
+                } else {
+                    BlockState::AIR
+                }
+            }
+            Preset::SwissCheese => {
+                if y >= filled_height() || holes.iter().any(|hole| hole.contains(local)) {
+                    BlockState::AIR
+                } else if y == 0 {
+                    BEDROCK
+                } else {
+                    STONE
+                }
+            }
+        }
+    }
+
+    /// Returns the height of the grass at world coordinates `x` and `z`.
+    fn hill_height(&self, x: i32, z: i32) -> i32 {
+        let random = |i| hash(&[self.seed, i]) as f64 / u64::MAX as f64;
+
+        // Wavelengths of 32 to 96 blocks, shifted by up to a full period.
+        let wave = |i, coordinate: i32| {
+            let wavelength = 32.0 + 64.0 * random(i);
+            let phase = std::f64::consts::TAU * random(i + 1);
+            (std::f64::consts::TAU * coordinate as f64 / wavelength + phase).sin()
+        };
+
+        let offset = HILL_AMPLITUDE * (wave(0, x) + wave(2, z)) / 2.0;
+        GROUND_HEIGHT + offset.round() as i32
+    }
+
+    /// Returns the holes in a chunk of [`Preset::SwissCheese`], in chunk-local
+    /// coordinates.
+    fn holes(&self, chunk_x: i32, chunk_z: i32) -> Vec<Hole> {
+        if self.preset != Preset::SwissCheese {
+            return Vec::new();
+        }
+
+        (0..HOLES_PER_CHUNK)
+            .map(|i| {
+                let random = |j, range: u64| {
+                    hash(&[self.seed, chunk_x as u64, chunk_z as u64, i, j]) % range
+                };
+
+                Hole {
+                    center: [
+                        random(0, CHUNK_WIDTH as u64) as i32,
+                        random(1, filled_height() as u64) as i32,
+                        random(2, CHUNK_WIDTH as u64) as i32,
+                    ],
+                    radius: (MIN_HOLE_RADIUS + random(3, MAX_HOLE_RADIUS - MIN_HOLE_RADIUS + 1))
+                        as i32,
+                }
+            })
+            .collect()
+    }
+}
+
+fn filled_height() -> i32 {
+    FILLED_SECTIONS as i32 * SECTION_HEIGHT as i32
+}
+
+/// Returns the block at height `y` in a column whose grass is at `height`.
+fn layered(y: i32, height: i32) -> BlockState {
+    if y == 0 {
+        BEDROCK
+    } else if y < height - DIRT_DEPTH {
+        STONE
+    } else if y < height {
+        DIRT
+    } else if y == height {
+        GRASS_BLOCK
+    } else {
+        BlockState::AIR
+    }
+}
+
+#[derive(Debug)]
+struct Hole {
+    center: [i32; 3],
+    radius: i32,
+}
+
+impl Hole {
+    fn contains(&self, [x, y, z]: [i32; 3]) -> bool {
+        let [cx, cy, cz] = self.center;
+        let (dx, dy, dz) = (x - cx, y - cy, z - cz);
+        dx * dx + dy * dy + dz * dz <= self.radius * self.radius
+    }
+}
+
+/// Hashes the values together with SplitMix64, so that the same values always
+/// give the same result on every platform.
+fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0x9e37_79b9_7f4a_7c15, |state, value| {
+        let mut z = (state ^ value).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::BlockClassifier;
+
+    #[test]
+    fn same_seed_same_chunks() {
+        for preset in Preset::ALL {
+            assert_eq!(
+                WorldGen::new(preset, 42).chunks(2),
+                WorldGen::new(preset, 42).chunks(2),
+                "{}",
+                preset
+            );
+        }
+
+        assert_ne!(
+            WorldGen::new(Preset::Hills, 42).chunk(0, 0),
+            WorldGen::new(Preset::Hills, 43).chunk(0, 0)
+        );
+        assert_ne!(
+            WorldGen::new(Preset::SwissCheese, 42).chunk(0, 0),
+            WorldGen::new(Preset::SwissCheese, 43).chunk(0, 0)
+        );
+    }
+
+    #[test]
+    fn block_counts_match_contents() {
+        let classifier = BlockClassifier::new([BlockState::AIR], []);
+
+        for preset in Preset::ALL {
+            for chunk in WorldGen::new(preset, 7).chunks(2) {
+                assert!(chunk.is_full());
+                for section in chunk.sections.iter() {
+                    section.assert_block_count(&classifier);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn presets() {
+        let flat = WorldGen::new(Preset::Flat, 0).chunk(0, 0);
+        assert_eq!(flat.get_block_at_local(3, 0, 3), Some(BEDROCK));
+        assert_eq!(flat.get_block_at_local(3, 63, 3), Some(DIRT));
+        assert_eq!(flat.get_block_at_local(3, 64, 3), Some(GRASS_BLOCK));
+        assert_eq!(flat.get_block_at_local(3, 65, 3), Some(BlockState::AIR));
+
+        let hills = WorldGen::new(Preset::Hills, 0).chunk(0, 0);
+        let heights = hills.compute_heightmap(&BlockClassifier::new([BlockState::AIR], []));
+        let amplitude = HILL_AMPLITUDE as i32;
+        for height in heights.iter().flatten() {
+            let height = *height as i32;
+            assert!((GROUND_HEIGHT - amplitude..=GROUND_HEIGHT + amplitude).contains(&height));
+        }
+
+        let checkerboard = WorldGen::new(Preset::Checkerboard, 0).chunk(0, 0);
+        assert_eq!(checkerboard.sections.len(), FILLED_SECTIONS as usize);
+        assert_eq!(checkerboard.sections[0].block_count, 4096 / 2);
+
+        assert_eq!(
+            "swiss-cheese".parse::<Preset>().unwrap(),
+            Preset::SwissCheese
+        );
+        assert!("cheese".parse::<Preset>().is_err());
+    }
+}
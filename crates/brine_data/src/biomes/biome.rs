@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use brine_chunk::BiomeId;
+pub use minecraft_data_rs::models::biome::Biome as McBiome;
+
+use crate::Api;
+
+/// A reference to a biome in the [`Biomes`] data provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Biome<'a> {
+    pub id: BiomeId,
+    pub name: &'a str,
+    pub temperature: f32,
+
+    /// How much rain (or snow) falls in the biome, from `0.0` to `1.0`.
+    ///
+    /// Called `rainfall` in the Minecraft data.
+    pub downfall: f32,
+}
+
+/// Provides access to Minecraft biome data for a specific version.
+pub struct Biomes {
+    /// List of biomes by increasing [`BiomeId`].
+    biomes: Vec<McBiome>,
+
+    /// Mapping from biome name to biome index.
+    name_to_biome: HashMap<String, usize>,
+}
+
+impl Biomes {
+    /// Returns the number of unique biomes in this version of Minecraft.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.biomes.len()
+    }
+
+    /// Returns the [`Biome`] with the given biome id, or `None` if no such
+    /// biome exists.
+    #[inline]
+    pub fn get_by_id(&self, biome_id: BiomeId) -> Option<Biome<'_>> {
+        let index = self
+            .biomes
+            .binary_search_by_key(&(biome_id.0 as u32), |mc_biome| mc_biome.id)
+            .ok()?;
+
+        self.get_by_index(index)
+    }
+
+    /// Returns the [`Biome`] with the given name, or `None` if no such biome
+    /// exists.
+    ///
+    /// The name may optionally include the `minecraft:` namespace.
+    #[inline]
+    pub fn get_by_name(&self, name: &str) -> Option<Biome<'_>> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let index = self.name_to_biome.get(name)?;
+
+        self.get_by_index(*index)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Biome<'_>> + '_ {
+        self.biomes.iter().map(Self::biome_from_mc_biome)
+    }
+
+    #[inline]
+    fn get_by_index(&self, index: usize) -> Option<Biome<'_>> {
+        let mc_biome = self.biomes.get(index)?;
+
+        Some(Self::biome_from_mc_biome(mc_biome))
+    }
+
+    fn biome_from_mc_biome(mc_biome: &McBiome) -> Biome<'_> {
+        Biome {
+            id: BiomeId(mc_biome.id as u16),
+            name: &mc_biome.name,
+            temperature: mc_biome.temperature,
+            downfall: mc_biome.rainfall,
+        }
+    }
+
+    pub(crate) fn from_api(api: &Api) -> Self {
+        let mut biomes = api.biomes.biomes_array().unwrap();
+        biomes.sort_by_key(|mc_biome| mc_biome.id);
+
+        let name_to_biome = biomes
+            .iter()
+            .enumerate()
+            .map(|(index, mc_biome)| (mc_biome.name.clone(), index))
+            .collect();
+
+        Self {
+            biomes,
+            name_to_biome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::MinecraftData;
+
+    use super::*;
+
+    #[test]
+    fn get_by_id() {
+        let data = MinecraftData::for_version("1.14.4");
+        let biomes = data.biomes();
+
+        let plains = biomes.get_by_id(BiomeId(1)).unwrap();
+        assert_eq!(plains.name, "plains");
+        assert_eq!(plains.temperature, 0.8);
+        assert_eq!(plains.downfall, 0.4);
+
+        let desert = biomes.get_by_id(BiomeId(2)).unwrap();
+        assert_eq!(desert.name, "desert");
+        assert_eq!(desert.temperature, 2.0);
+        assert_eq!(desert.downfall, 0.0);
+
+        assert_eq!(biomes.get_by_id(BiomeId::VOID).unwrap().name, "the_void");
+        assert_eq!(biomes.get_by_id(BiomeId(u16::MAX)), None);
+    }
+
+    #[test]
+    fn get_by_name() {
+        let data = MinecraftData::for_version("1.14.4");
+        let biomes = data.biomes();
+
+        let swamp = biomes.get_by_name("minecraft:swamp").unwrap();
+        assert_eq!(swamp.id, BiomeId(6));
+        assert_eq!(swamp.temperature, 0.8);
+        assert_eq!(swamp.downfall, 0.9);
+
+        assert_eq!(biomes.get_by_name("not_a_biome"), None);
+    }
+
+    #[test]
+    fn iter() {
+        let data = MinecraftData::for_version("1.14.4");
+        let biomes = data.biomes();
+
+        assert_eq!(biomes.iter().count(), biomes.count());
+        assert!(biomes.iter().any(|biome| biome.name == "jungle"));
+    }
+}
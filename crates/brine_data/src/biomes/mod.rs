@@ -0,0 +1,6 @@
+//! Minecraft biome data.
+
+mod biome;
+
+pub use biome::{Biome, Biomes};
+pub use brine_chunk::BiomeId;
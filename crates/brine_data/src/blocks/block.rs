@@ -1,11 +1,16 @@
 use std::collections::HashMap;
 
+use brine_chunk::{BlockClassifier, BlockState as ChunkBlockState};
 use minecraft_data_rs::models::block::BoundingBox;
 pub use minecraft_data_rs::models::block::{Block as McBlock, State as McState};
 
-use crate::Api;
+use crate::{Api, ItemId};
 
-use super::{state::McBlockExt, BlockState};
+use super::{
+    state::McBlockExt,
+    state_string::{self, StateParseError},
+    BlockState,
+};
 
 pub(crate) type IndexType = u16;
 
@@ -35,6 +40,13 @@ where
     }
 }
 
+impl From<BlockStateId> for ChunkBlockState {
+    #[inline]
+    fn from(block_state_id: BlockStateId) -> Self {
+        Self(block_state_id.0 as u32)
+    }
+}
+
 /// A reference to a block in the [`Blocks`] data provider.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Block<'a> {
@@ -43,6 +55,15 @@ pub struct Block<'a> {
     pub name: &'a str,
     pub transparent: bool,
     pub empty: bool,
+
+    /// How long the block takes to break, or `None` if it can't be broken
+    /// (e.g., bedrock). See <https://minecraft.fandom.com/wiki/Breaking>.
+    pub hardness: Option<f32>,
+
+    /// What the block is made of (e.g., `rock` or `wood`), which decides what
+    /// tools break it faster.
+    pub material: Option<&'a str>,
+
     pub state: BlockState<'a>,
 }
 
@@ -53,6 +74,12 @@ impl<'a> Block<'a> {
     }
 }
 
+/// Names of the blocks classified as air by [`Blocks::classifier`].
+const AIR_BLOCKS: &[&str] = &["air", "cave_air", "void_air"];
+
+/// Names of the blocks classified as fluids by [`Blocks::classifier`].
+const FLUID_BLOCKS: &[&str] = &["water", "lava"];
+
 /// Provides access to Minecraft block data for a specific version.
 ///
 /// See the [module documentation][self] for more information.
@@ -141,6 +168,147 @@ impl Blocks {
         }))
     }
 
+    /// Returns the items that the block with the given id drops anything when
+    /// broken with, or `None` if it drops something whatever it's broken with.
+    ///
+    /// Also returns `None` if no such block exists.
+    pub fn harvest_tools(&self, block_id: BlockId) -> Option<Vec<ItemId>> {
+        let mc_block = self.blocks.get(block_id.0 as usize)?;
+        let tools = mc_block.harvest_tools.as_ref()?;
+
+        Some(
+            tools
+                .keys()
+                .filter_map(|item_id| item_id.to_string().parse().ok())
+                .map(ItemId)
+                .collect(),
+        )
+    }
+
+    /// Builds a [`BlockClassifier`] for this version's block states.
+    pub fn classifier(&self) -> BlockClassifier {
+        let states_of = |names: &'static [&'static str]| {
+            names
+                .iter()
+                .filter_map(|name| self.get_by_name(name))
+                .flat_map(|block| self.iter_states_for_block(BlockId(block.id)).unwrap())
+                .map(|(block_state_id, _)| ChunkBlockState::from(block_state_id))
+        };
+
+        BlockClassifier::new(states_of(AIR_BLOCKS), states_of(FLUID_BLOCKS))
+    }
+
+    /// Returns the name of the fluid (`"water"` or `"lava"`) that the block
+    /// state is, along with the value of its `level` property, or `None` if the
+    /// block state is not a fluid.
+    ///
+    /// Level `0` is a source block, levels `1` through `7` are flowing fluid
+    /// that gets lower the further it is from its source, and levels `8` and up
+    /// are falling fluid.
+    pub fn fluid_level(&self, block_state_id: BlockStateId) -> Option<(&str, u8)> {
+        let block = self.get_by_state_id(block_state_id)?;
+        if !FLUID_BLOCKS.contains(&block.name) {
+            return None;
+        }
+
+        let level = block.state.get("level")?.as_int()?;
+
+        Some((block.name, level as u8))
+    }
+
+    /// Returns the [`BlockStateId`] for the block with the given name and
+    /// property values, or `None` if no such block state exists.
+    ///
+    /// The name may optionally include the `minecraft:` namespace. Any
+    /// properties not listed in `props` take on their value from the block's
+    /// default state.
+    ///
+    /// See [`try_get_state_id`][Self::try_get_state_id] for a version that
+    /// reports why the lookup failed.
+    pub fn get_state_id(&self, name: &str, props: &[(&str, &str)]) -> Option<BlockStateId> {
+        self.try_get_state_id(name, props).ok()
+    }
+
+    /// Like [`get_state_id`][Self::get_state_id], but returns the block state
+    /// as a [`brine_chunk::BlockState`], e.g., for building chunks by hand.
+    ///
+    /// ```
+    /// # use brine_data::MinecraftData;
+    /// let mc_data = MinecraftData::for_version("1.14.4");
+    /// let log = mc_data.blocks().block_state("oak_log", &[("axis", "y")]);
+    /// assert!(log.is_some());
+    /// ```
+    pub fn block_state(&self, name: &str, props: &[(&str, &str)]) -> Option<ChunkBlockState> {
+        self.get_state_id(name, props).map(ChunkBlockState::from)
+    }
+
+    /// Like [`get_state_id`][Self::get_state_id], but returns a descriptive
+    /// error if the block, a property, or a value does not exist.
+    pub fn try_get_state_id(
+        &self,
+        name: &str,
+        props: &[(&str, &str)],
+    ) -> Result<BlockStateId, StateParseError> {
+        let name = state_string::strip_namespace(name);
+
+        let index = *self
+            .name_to_block
+            .get(name)
+            .ok_or_else(|| StateParseError::UnknownBlock(name.to_string()))?;
+        let mc_block = &self.blocks[index as usize];
+
+        let possible_block_states = mc_block.possible_block_states();
+        let mut state = Self::block_from_mc_block(mc_block, None).state;
+
+        for (property, value) in props.iter() {
+            let (property_name, possible_values) = possible_block_states
+                .get_property(property)
+                .ok_or_else(|| StateParseError::UnknownProperty {
+                    block: name.to_string(),
+                    property: property.to_string(),
+                })?;
+
+            let value = possible_values
+                .iter()
+                .find(|possible_value| possible_value.to_string() == *value)
+                .ok_or_else(|| StateParseError::InvalidValue {
+                    block: name.to_string(),
+                    property: property.to_string(),
+                    value: value.to_string(),
+                })?;
+
+            state.insert(property_name, *value);
+        }
+
+        let state_offset = possible_block_states.index_of(&state).unwrap();
+        let min_state_id = mc_block.min_state_id.unwrap() as IndexType;
+
+        Ok(BlockStateId(min_state_id + state_offset))
+    }
+
+    /// Returns the [`BlockStateId`] described by a block state string such as
+    /// `minecraft:oak_stairs[facing=east,half=bottom]`.
+    ///
+    /// See the [`state_string`][super::state_string] module for the accepted
+    /// syntax.
+    pub fn parse_state(&self, state_string: &str) -> Result<BlockStateId, StateParseError> {
+        let (name, props) = state_string::parse(state_string)?;
+
+        self.try_get_state_id(name, &props)
+    }
+
+    /// Returns the canonical string form of the given block state, e.g.,
+    /// `minecraft:oak_stairs[facing=east,half=bottom,shape=straight,waterlogged=false]`,
+    /// or `None` if no such block state exists.
+    ///
+    /// The result can be turned back into a [`BlockStateId`] with
+    /// [`parse_state`][Self::parse_state].
+    pub fn format_state(&self, block_state_id: BlockStateId) -> Option<String> {
+        let block = self.get_by_state_id(block_state_id)?;
+
+        Some(state_string::format(block.name, &block.state))
+    }
+
     #[inline]
     pub(crate) fn get_by_index_and_state_id(
         &self,
@@ -168,6 +336,8 @@ impl Blocks {
             name: &mc_block.name,
             transparent: mc_block.transparent,
             empty: matches!(mc_block.bounding_box, BoundingBox::Empty),
+            hardness: mc_block.hardness.filter(|hardness| *hardness >= 0.0),
+            material: mc_block.material.as_deref(),
             state,
         }
     }
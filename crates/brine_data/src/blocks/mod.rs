@@ -4,6 +4,8 @@
 
 mod block;
 mod state;
+pub mod state_string;
 
 pub use block::{Block, BlockId, BlockStateId, Blocks};
 pub use state::{BlockState, StateValue};
+pub use state_string::StateParseError;
@@ -154,6 +154,32 @@ impl<'a> PossibleBlockStates<'a> {
 
         state
     }
+
+    /// Returns the name of the given property as stored in the block data,
+    /// along with all of its possible values.
+    pub fn get_property(&self, property: &str) -> Option<(&'a str, &[StateValue<'a>])> {
+        self.state_values
+            .iter()
+            .find(|(state_name, _)| *state_name == property)
+            .map(|(state_name, state_values)| (*state_name, &state_values[..]))
+    }
+
+    /// The inverse of [`get_nth`][Self::get_nth].
+    ///
+    /// Returns `None` if `state` is missing a property or has a value that is
+    /// not possible for that property.
+    pub fn index_of(&self, state: &BlockState<'_>) -> Option<IndexType> {
+        let mut n = 0;
+
+        for (state_name, state_values) in self.state_values.iter() {
+            let value = state.get(state_name)?;
+            let state_index = state_values.iter().position(|v| v == value)?;
+
+            n = n * state_values.len() as IndexType + state_index as IndexType;
+        }
+
+        Some(n)
+    }
 }
 
 #[cfg(test)]
@@ -354,5 +380,25 @@ mod test {
                 }
             );
         }
+
+        #[test]
+        fn index_of_inverts_get_nth() {
+            let block = McBlock {
+                states: Some(vec![
+                    test_int_state(),
+                    test_enum_state(),
+                    test_bool_state(),
+                ]),
+                ..Default::default()
+            };
+            let possible_states = block.possible_block_states();
+
+            for n in 0..18 {
+                let state = possible_states.get_nth(n);
+                assert_eq!(possible_states.index_of(&state), Some(n));
+            }
+
+            assert_eq!(possible_states.index_of(&HashMap::default()), None);
+        }
     }
 }
@@ -0,0 +1,260 @@
+//! Parsing and formatting of block states in their bracketed string form.
+//!
+//! A block state can be written as the block's name followed by an optional
+//! list of `property=value` pairs in square brackets, with an optional
+//! `minecraft:` namespace. For example:
+//!
+//! ```text
+//! minecraft:oak_stairs[facing=east,half=bottom,shape=straight,waterlogged=false]
+//! oak_stairs[facing=east]
+//! stone
+//! ```
+
+use super::BlockState;
+
+pub(crate) const NAMESPACE: &str = "minecraft";
+
+/// An error produced when a block state cannot be resolved from a name and a
+/// list of properties.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StateParseError {
+    #[error("malformed block state string {0:?}")]
+    Malformed(String),
+
+    #[error("unknown block {0:?}")]
+    UnknownBlock(String),
+
+    #[error("block {block:?} has no property {property:?}")]
+    UnknownProperty { block: String, property: String },
+
+    #[error("invalid value {value:?} for property {property:?} of block {block:?}")]
+    InvalidValue {
+        block: String,
+        property: String,
+        value: String,
+    },
+}
+
+/// Removes the `minecraft:` namespace from the front of a block name, if
+/// present.
+pub(crate) fn strip_namespace(name: &str) -> &str {
+    name.strip_prefix(NAMESPACE)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(name)
+}
+
+/// Splits a block state string into its block name and list of properties.
+///
+/// This only checks syntax; it does not check that the block or any of its
+/// properties exist.
+pub(crate) fn parse(state_string: &str) -> Result<(&str, Vec<(&str, &str)>), StateParseError> {
+    let malformed = || StateParseError::Malformed(state_string.to_string());
+
+    let state_string = state_string.trim();
+
+    let (name, props) = match state_string.find('[') {
+        Some(open) => {
+            let props = state_string[open + 1..]
+                .strip_suffix(']')
+                .ok_or_else(malformed)?;
+            (&state_string[..open], Some(props))
+        }
+        None => (state_string, None),
+    };
+
+    let name = strip_namespace(name);
+    if name.is_empty() || name.contains(|c: char| c == ']' || c.is_whitespace()) {
+        return Err(malformed());
+    }
+
+    let props = match props {
+        Some(props) if !props.trim().is_empty() => props
+            .split(',')
+            .map(|pair| {
+                let (property, value) = pair.split_once('=').ok_or_else(malformed)?;
+                let (property, value) = (property.trim(), value.trim());
+                if property.is_empty() || value.is_empty() {
+                    Err(malformed())
+                } else {
+                    Ok((property, value))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => Vec::new(),
+    };
+
+    Ok((name, props))
+}
+
+/// Formats a block state in its canonical bracketed form, with properties
+/// sorted by name.
+pub(crate) fn format(name: &str, state: &BlockState<'_>) -> String {
+    let mut output = format!("{}:{}", NAMESPACE, name);
+
+    if !state.is_empty() {
+        let mut properties: Vec<_> = state.iter().collect();
+        properties.sort_by_key(|(property, _)| **property);
+
+        let properties: Vec<String> = properties
+            .into_iter()
+            .map(|(property, value)| format!("{}={}", property, value))
+            .collect();
+
+        output.push('[');
+        output.push_str(&properties.join(","));
+        output.push(']');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{BlockStateId, MinecraftData};
+
+    #[test]
+    fn parse_name_only() {
+        assert_eq!(parse("stone"), Ok(("stone", vec![])));
+        assert_eq!(parse("minecraft:stone"), Ok(("stone", vec![])));
+        assert_eq!(parse("minecraft:stone[]"), Ok(("stone", vec![])));
+    }
+
+    #[test]
+    fn parse_properties() {
+        assert_eq!(
+            parse("minecraft:oak_stairs[facing=east, half=bottom]"),
+            Ok(("oak_stairs", vec![("facing", "east"), ("half", "bottom")]))
+        );
+    }
+
+    #[test]
+    fn parse_malformed() {
+        for bad in [
+            "",
+            "oak_stairs[",
+            "oak_stairs[facing]",
+            "oak_stairs[facing=]",
+            "oak_stairs]",
+        ] {
+            assert!(
+                matches!(parse(bad), Err(StateParseError::Malformed(_))),
+                "{:?}",
+                bad
+            );
+        }
+    }
+
+    fn assert_round_trip(data: &MinecraftData, block_name: &str) {
+        let blocks = data.blocks();
+        let block = blocks.get_by_name(block_name).unwrap();
+
+        let states = blocks.iter_states_for_block(block.id.into()).unwrap();
+
+        for (block_state_id, _) in states {
+            let formatted = blocks.format_state(block_state_id).unwrap();
+            let parsed = blocks.parse_state(&formatted);
+
+            assert_eq!(parsed, Ok(block_state_id), "{}", formatted);
+        }
+    }
+
+    #[test]
+    fn round_trip_stairs() {
+        let data = MinecraftData::for_version("1.14.4");
+        assert_round_trip(&data, "oak_stairs");
+    }
+
+    #[test]
+    fn round_trip_redstone_wire() {
+        let data = MinecraftData::for_version("1.14.4");
+        assert_round_trip(&data, "redstone_wire");
+    }
+
+    #[test]
+    fn unspecified_properties_use_defaults() {
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+
+        let default = blocks.get_by_name("oak_stairs").unwrap();
+        let default_id = blocks
+            .iter_states_for_block(default.id.into())
+            .unwrap()
+            .find(|(_, block)| *block == default)
+            .map(|(id, _)| id)
+            .unwrap();
+
+        assert_eq!(blocks.get_state_id("oak_stairs", &[]), Some(default_id));
+        assert_eq!(blocks.parse_state("minecraft:oak_stairs"), Ok(default_id));
+
+        let east = blocks
+            .get_state_id("minecraft:oak_stairs", &[("facing", "east")])
+            .unwrap();
+        let east = blocks.get_by_state_id(east).unwrap();
+        assert_eq!(east.state["facing"].as_enum_value(), Some("east"));
+        assert_eq!(east.state["half"], default.state["half"]);
+        assert_eq!(east.state["waterlogged"], default.state["waterlogged"]);
+    }
+
+    #[test]
+    fn format_without_properties() {
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+
+        assert_eq!(
+            blocks.format_state(BlockStateId(1)).as_deref(),
+            Some("minecraft:stone")
+        );
+    }
+
+    #[test]
+    fn descriptive_errors() {
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+
+        assert_eq!(
+            blocks.parse_state("not_a_block"),
+            Err(StateParseError::UnknownBlock(String::from("not_a_block")))
+        );
+        assert_eq!(
+            blocks.parse_state("oak_stairs[color=red]"),
+            Err(StateParseError::UnknownProperty {
+                block: String::from("oak_stairs"),
+                property: String::from("color"),
+            })
+        );
+        assert_eq!(
+            blocks.parse_state("redstone_wire[power=16]"),
+            Err(StateParseError::InvalidValue {
+                block: String::from("redstone_wire"),
+                property: String::from("power"),
+                value: String::from("16"),
+            })
+        );
+        assert_eq!(blocks.get_state_id("oak_stairs", &[("facing", "up")]), None);
+    }
+
+    #[test]
+    fn block_state_from_name_and_props() {
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+
+        assert_eq!(
+            blocks.block_state("stone", &[]),
+            Some(brine_chunk::BlockState(1))
+        );
+
+        let log = blocks.block_state("oak_log", &[("axis", "y")]).unwrap();
+        let log = blocks.get_by_state_id(BlockStateId(log.0 as u16)).unwrap();
+        assert_eq!(log.name, "oak_log");
+        assert_eq!(log.state["axis"].as_enum_value(), Some("y"));
+
+        let x_log = blocks.block_state("oak_log", &[("axis", "x")]).unwrap();
+        assert_ne!(blocks.block_state("oak_log", &[("axis", "y")]), Some(x_log));
+
+        assert_eq!(blocks.block_state("oak_log", &[("axis", "up")]), None);
+        assert_eq!(blocks.block_state("stone", &[("axis", "y")]), None);
+        assert_eq!(blocks.block_state("not_a_block", &[]), None);
+    }
+}
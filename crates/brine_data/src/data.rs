@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{Api, Blocks, Version};
+use crate::{version::UnsupportedVersion, Api, Biomes, Blocks, Items, Sounds, Version};
 
 /// Provides access to all Minecraft data for a specific version.
 ///
@@ -19,28 +19,157 @@ impl MinecraftData {
         Self::for_version(Version::latest_stable())
     }
 
+    /// Returns every [`Version`] that data can be constructed for, from oldest
+    /// to newest.
+    pub fn available_versions() -> Vec<Version> {
+        Version::available()
+    }
+
+    /// Constructs Minecraft data for the version with the given name or
+    /// protocol version number, e.g., `"1.14.4"` or `"498"`.
+    ///
+    /// Returns an error listing the supported versions if there is no data for
+    /// the requested version.
+    pub fn try_for_version(version: &str) -> Result<Self, UnsupportedVersion> {
+        let version: Version = version.parse()?;
+
+        Ok(Self::for_version(version))
+    }
+
     /// Constructs Minecraft data for the specified [`Version`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no data for the version. See
+    /// [`try_for_version`][Self::try_for_version] for a non-panicking
+    /// alternative.
     pub fn for_version(version: impl Into<Version>) -> Self {
         let version = version.into();
         let api = Api::new(version.0.clone());
         Self {
             inner: Arc::new(MinecraftDataInner {
+                biomes: Biomes::from_api(&api),
                 blocks: Blocks::from_api(&api),
+                items: Items::from_api(&api),
+                sounds: Sounds::from_api(&api),
                 version,
             }),
         }
     }
 
+    /// Returns true if `self` and `other` share the same underlying data.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    pub fn biomes(&self) -> &Biomes {
+        &self.inner.biomes
+    }
+
     pub fn blocks(&self) -> &Blocks {
         &self.inner.blocks
     }
 
+    pub fn items(&self) -> &Items {
+        &self.inner.items
+    }
+
+    pub fn sounds(&self) -> &Sounds {
+        &self.inner.sounds
+    }
+
     pub fn version(&self) -> &Version {
         &self.inner.version
     }
 }
 
 struct MinecraftDataInner {
+    pub biomes: Biomes,
     pub blocks: Blocks,
+    pub items: Items,
+    pub sounds: Sounds,
     pub version: Version,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_for_version() {
+        let data = MinecraftData::try_for_version("1.14.4").unwrap();
+        assert_eq!(data.version().name(), "1.14.4");
+
+        let error = MinecraftData::try_for_version("bogus").err().unwrap();
+        assert_eq!(error.requested, "bogus");
+        assert!(!error.supported.is_empty());
+    }
+
+    #[test]
+    fn block_hardness() {
+        use crate::BlockId;
+
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+
+        let stone = blocks.get_by_name("stone").unwrap();
+        assert_eq!(stone.hardness, Some(1.5));
+        assert_eq!(stone.material, Some("rock"));
+
+        let wooden_pickaxe = data.items().get_by_name("wooden_pickaxe").unwrap();
+        let tools = blocks.harvest_tools(BlockId(stone.id)).unwrap();
+        assert!(tools.contains(&wooden_pickaxe.id));
+
+        let dirt = blocks.get_by_name("dirt").unwrap();
+        assert_eq!(dirt.hardness, Some(0.5));
+        assert_eq!(blocks.harvest_tools(BlockId(dirt.id)), None);
+
+        let bedrock = blocks.get_by_name("bedrock").unwrap();
+        assert_eq!(bedrock.hardness, None);
+    }
+
+    #[test]
+    fn block_classifier() {
+        use brine_chunk::BlockState;
+
+        let data = MinecraftData::for_version("1.14.4");
+        let classifier = data.blocks().classifier();
+
+        // air, void_air, and cave_air.
+        for id in [0, 9129, 9130] {
+            assert!(classifier.is_air(BlockState(id)), "{}", id);
+            assert!(!classifier.is_fluid(BlockState(id)), "{}", id);
+        }
+
+        // Every level of water (34..=49) and lava (50..=65).
+        for id in 34..=65 {
+            assert!(classifier.is_fluid(BlockState(id)), "{}", id);
+            assert!(!classifier.is_air(BlockState(id)), "{}", id);
+        }
+
+        // stone, and the blocks on either side of the fluids.
+        for id in [1, 33, 66] {
+            assert!(!classifier.is_air(BlockState(id)), "{}", id);
+            assert!(!classifier.is_fluid(BlockState(id)), "{}", id);
+        }
+    }
+
+    #[test]
+    fn fluid_level() {
+        use crate::BlockStateId;
+
+        let data = MinecraftData::for_version("1.14.4");
+        let blocks = data.blocks();
+
+        assert_eq!(blocks.fluid_level(BlockStateId(34)), Some(("water", 0)));
+        assert_eq!(blocks.fluid_level(BlockStateId(41)), Some(("water", 7)));
+        assert_eq!(blocks.fluid_level(BlockStateId(42)), Some(("water", 8)));
+        assert_eq!(blocks.fluid_level(BlockStateId(50)), Some(("lava", 0)));
+        assert_eq!(blocks.fluid_level(BlockStateId(65)), Some(("lava", 15)));
+
+        for id in [0, 1, 33, 66] {
+            assert_eq!(blocks.fluid_level(BlockStateId(id)), None, "{}", id);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+pub use minecraft_data_rs::models::item::Item as McItem;
+
+use crate::Api;
+
+pub(crate) type IndexType = u16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId(pub IndexType);
+
+impl<T> From<T> for ItemId
+where
+    T: Into<IndexType>,
+{
+    #[inline]
+    fn from(source: T) -> Self {
+        Self(source.into())
+    }
+}
+
+/// A reference to an item in the [`Items`] data provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item<'a> {
+    pub id: ItemId,
+    pub display_name: &'a str,
+    pub name: &'a str,
+    pub stack_size: u8,
+}
+
+/// Provides access to Minecraft item data for a specific version.
+pub struct Items {
+    /// List of items by increasing [`ItemId`].
+    items: Vec<McItem>,
+
+    /// Mapping from item name to item index.
+    name_to_item: HashMap<String, IndexType>,
+}
+
+impl Items {
+    /// Returns the number of unique items in this version of Minecraft.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns the [`Item`] with the given item id, or `None` if no such item
+    /// exists.
+    #[inline]
+    pub fn get_by_id(&self, item_id: ItemId) -> Option<Item<'_>> {
+        let index = self
+            .items
+            .binary_search_by_key(&(item_id.0 as u32), |mc_item| mc_item.id)
+            .ok()?;
+
+        self.get_by_index(index as IndexType)
+    }
+
+    /// Returns the [`Item`] with the given name, or `None` if no such item
+    /// exists.
+    ///
+    /// The name may optionally include the `minecraft:` namespace.
+    #[inline]
+    pub fn get_by_name(&self, name: &str) -> Option<Item<'_>> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let index = self.name_to_item.get(name)?;
+
+        self.get_by_index(*index)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Item<'_>> + '_ {
+        self.items.iter().map(Self::item_from_mc_item)
+    }
+
+    #[inline]
+    fn get_by_index(&self, index: IndexType) -> Option<Item<'_>> {
+        let mc_item = self.items.get(index as usize)?;
+
+        Some(Self::item_from_mc_item(mc_item))
+    }
+
+    fn item_from_mc_item(mc_item: &McItem) -> Item<'_> {
+        Item {
+            id: ItemId(mc_item.id as IndexType),
+            display_name: &mc_item.display_name,
+            name: &mc_item.name,
+            stack_size: mc_item.stack_size as u8,
+        }
+    }
+
+    pub(crate) fn from_api(api: &Api) -> Self {
+        let mut items = api.items.items_array().unwrap();
+        items.sort_by_key(|mc_item| mc_item.id);
+
+        let name_to_item = items
+            .iter()
+            .enumerate()
+            .map(|(index, mc_item)| (mc_item.name.clone(), index as IndexType))
+            .collect();
+
+        Self {
+            items,
+            name_to_item,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::MinecraftData;
+
+    use super::*;
+
+    #[test]
+    fn get_by_name() {
+        let data = MinecraftData::for_version("1.14.4");
+        let items = data.items();
+
+        let stone = items.get_by_name("stone").unwrap();
+        assert_eq!(stone.id, ItemId(1));
+        assert_eq!(stone.display_name, "Stone");
+        assert_eq!(stone.stack_size, 64);
+
+        let sword = items.get_by_name("minecraft:diamond_sword").unwrap();
+        assert_eq!(sword.name, "diamond_sword");
+        assert_eq!(sword.stack_size, 1);
+
+        let pearl = items.get_by_name("ender_pearl").unwrap();
+        assert_eq!(pearl.stack_size, 16);
+
+        assert_eq!(items.get_by_name("not_an_item"), None);
+    }
+
+    #[test]
+    fn get_by_id() {
+        let data = MinecraftData::for_version("1.14.4");
+        let items = data.items();
+
+        assert_eq!(items.get_by_id(ItemId(0)).unwrap().name, "air");
+        assert_eq!(items.get_by_id(ItemId(1)).unwrap().name, "stone");
+
+        let sword = items.get_by_name("diamond_sword").unwrap();
+        assert_eq!(items.get_by_id(sword.id), Some(sword));
+
+        assert_eq!(items.get_by_id(ItemId(u16::MAX)), None);
+    }
+
+    #[test]
+    fn iter() {
+        let data = MinecraftData::for_version("1.14.4");
+        let items = data.items();
+
+        assert_eq!(items.iter().count(), items.count());
+        assert!(items.iter().any(|item| item.name == "diamond_sword"));
+    }
+}
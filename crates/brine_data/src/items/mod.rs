@@ -0,0 +1,5 @@
+//! Minecraft item data.
+
+mod item;
+
+pub use item::{Item, ItemId, Items};
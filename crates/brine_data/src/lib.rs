@@ -2,11 +2,19 @@
 
 pub(crate) use minecraft_data_rs::api::Api;
 
+pub mod biomes;
 pub mod blocks;
+pub mod items;
+pub mod sounds;
 
 mod data;
+mod registry;
 mod version;
 
+pub use biomes::{Biome, BiomeId, Biomes};
 pub use blocks::{BlockId, BlockState, BlockStateId, Blocks};
 pub use data::MinecraftData;
-pub use version::Version;
+pub use items::{Item, ItemId, Items};
+pub use registry::MinecraftDataRegistry;
+pub use sounds::{Sound, SoundId, Sounds};
+pub use version::{UnsupportedVersion, Version};
@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{MinecraftData, Version};
+
+/// A shared, lazily-populated cache of [`MinecraftData`] for multiple versions.
+///
+/// Loading data for a version is expensive, so the registry does it at most
+/// once per version and hands out cheap clones thereafter. Cloning the registry
+/// itself is also cheap; all clones share the same cache, so it can be moved
+/// into a background task to do the loading off of the main thread.
+#[derive(Clone, Default)]
+pub struct MinecraftDataRegistry {
+    loaded: Arc<Mutex<HashMap<String, MinecraftData>>>,
+}
+
+impl MinecraftDataRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the data for the given version if it has already been loaded.
+    pub fn get(&self, version: &Version) -> Option<MinecraftData> {
+        self.loaded.lock().unwrap().get(version.name()).cloned()
+    }
+
+    /// Returns the data for the given version, loading it first if it has not
+    /// been loaded yet.
+    ///
+    /// The registry is locked for the duration of the load, so concurrent
+    /// requests for the same version will not load it twice.
+    pub fn get_or_load(&self, version: impl Into<Version>) -> MinecraftData {
+        let version = version.into();
+
+        self.loaded
+            .lock()
+            .unwrap()
+            .entry(version.name().to_string())
+            .or_insert_with(|| MinecraftData::for_version(version))
+            .clone()
+    }
+
+    /// Returns the data for the newest version that speaks the given protocol
+    /// version, loading it first if necessary, or `None` if there is no data
+    /// for that protocol version.
+    pub fn get_or_load_for_protocol_version(&self, protocol_version: i32) -> Option<MinecraftData> {
+        Version::from_protocol_version(protocol_version).map(|version| self.get_or_load(version))
+    }
+
+    /// Returns the versions that have been loaded so far.
+    pub fn loaded_versions(&self) -> Vec<Version> {
+        let mut versions: Vec<Version> = self
+            .loaded
+            .lock()
+            .unwrap()
+            .values()
+            .map(|data| data.version().clone())
+            .collect();
+
+        versions.sort();
+        versions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn loads_once_and_shares() {
+        let registry = MinecraftDataRegistry::new();
+        let version = Version::from("1.14.4");
+
+        assert!(registry.get(&version).is_none());
+
+        let first = registry.get_or_load(version.clone());
+        let second = registry.clone().get_or_load(version.clone());
+
+        assert!(first.ptr_eq(&second));
+        assert!(registry.get(&version).unwrap().ptr_eq(&first));
+        assert_eq!(registry.loaded_versions(), vec![version]);
+    }
+
+    #[test]
+    fn by_protocol_version() {
+        let registry = MinecraftDataRegistry::new();
+
+        let data = registry.get_or_load_for_protocol_version(498).unwrap();
+        assert_eq!(data.version().name(), "1.14.4");
+
+        assert!(registry.get_or_load_for_protocol_version(-1).is_none());
+    }
+}
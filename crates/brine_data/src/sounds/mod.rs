@@ -0,0 +1,5 @@
+//! Minecraft sound data.
+
+mod sound;
+
+pub use sound::{Sound, SoundId, Sounds};
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+pub use minecraft_data_rs::models::sound::Sound as McSound;
+
+use crate::Api;
+
+pub(crate) type IndexType = u16;
+
+/// Numeric id of a sound event, as sent by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SoundId(pub IndexType);
+
+impl<T> From<T> for SoundId
+where
+    T: Into<IndexType>,
+{
+    #[inline]
+    fn from(source: T) -> Self {
+        Self(source.into())
+    }
+}
+
+/// A reference to a sound event in the [`Sounds`] data provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sound<'a> {
+    pub id: SoundId,
+
+    /// Name of the sound event, e.g., `block.stone.break`.
+    pub name: &'a str,
+}
+
+/// Provides access to Minecraft sound event data for a specific version.
+pub struct Sounds {
+    /// List of sounds by increasing [`SoundId`].
+    sounds: Vec<McSound>,
+
+    /// Mapping from sound name to sound index.
+    name_to_sound: HashMap<String, IndexType>,
+}
+
+impl Sounds {
+    /// Returns the number of unique sound events in this version of
+    /// Minecraft.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.sounds.len()
+    }
+
+    /// Returns the [`Sound`] with the given sound id, or `None` if no such
+    /// sound exists.
+    #[inline]
+    pub fn get_by_id(&self, sound_id: SoundId) -> Option<Sound<'_>> {
+        let index = self
+            .sounds
+            .binary_search_by_key(&(sound_id.0 as u32), |mc_sound| mc_sound.id)
+            .ok()?;
+
+        self.get_by_index(index as IndexType)
+    }
+
+    /// Returns the [`Sound`] with the given name, or `None` if no such sound
+    /// exists.
+    ///
+    /// The name may optionally include the `minecraft:` namespace.
+    #[inline]
+    pub fn get_by_name(&self, name: &str) -> Option<Sound<'_>> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let index = self.name_to_sound.get(name)?;
+
+        self.get_by_index(*index)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Sound<'_>> + '_ {
+        self.sounds.iter().map(Self::sound_from_mc_sound)
+    }
+
+    #[inline]
+    fn get_by_index(&self, index: IndexType) -> Option<Sound<'_>> {
+        let mc_sound = self.sounds.get(index as usize)?;
+
+        Some(Self::sound_from_mc_sound(mc_sound))
+    }
+
+    fn sound_from_mc_sound(mc_sound: &McSound) -> Sound<'_> {
+        Sound {
+            id: SoundId(mc_sound.id as IndexType),
+            name: &mc_sound.name,
+        }
+    }
+
+    pub(crate) fn from_api(api: &Api) -> Self {
+        let mut sounds = api.sounds.sounds_array().unwrap();
+        sounds.sort_by_key(|mc_sound| mc_sound.id);
+
+        let name_to_sound = sounds
+            .iter()
+            .enumerate()
+            .map(|(index, mc_sound)| (mc_sound.name.clone(), index as IndexType))
+            .collect();
+
+        Self {
+            sounds,
+            name_to_sound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::MinecraftData;
+
+    use super::*;
+
+    #[test]
+    fn get_by_id() {
+        let data = MinecraftData::for_version("1.14.4");
+        let sounds = data.sounds();
+
+        assert_eq!(sounds.get_by_id(SoundId(0)).unwrap().name, "ambient.cave");
+        assert_eq!(sounds.get_by_id(SoundId(u16::MAX)), None);
+    }
+
+    #[test]
+    fn get_by_name() {
+        let data = MinecraftData::for_version("1.14.4");
+        let sounds = data.sounds();
+
+        let stone = sounds.get_by_name("block.stone.break").unwrap();
+        assert_eq!(stone.name, "block.stone.break");
+        assert_eq!(sounds.get_by_id(stone.id), Some(stone.clone()));
+        assert_eq!(
+            sounds.get_by_name("minecraft:block.stone.break"),
+            Some(stone)
+        );
+
+        assert_eq!(sounds.get_by_name("not.a.sound"), None);
+    }
+}
@@ -1,11 +1,16 @@
-use std::ops::Deref;
+use std::{cmp::Ordering, fmt, ops::Deref, str::FromStr};
 
 use minecraft_data_rs::{
-    api::versions::{latest_stable, versions_by_minecraft_version},
+    api::versions::{available_versions, latest_stable, versions_by_minecraft_version},
     models::version::Version as McVersion,
 };
 
 /// Represents a version of the Minecraft game.
+///
+/// Versions compare first by protocol version number, and then by their
+/// semantic name (e.g., `1.16.4 < 1.16.5`, even though both speak protocol
+/// version 754).
+#[derive(Clone)]
 pub struct Version(pub(crate) McVersion);
 
 impl Version {
@@ -13,6 +18,111 @@ impl Version {
     pub fn latest_stable() -> Self {
         Self(latest_stable().unwrap())
     }
+
+    /// Returns every version for which this crate has data, from oldest to newest.
+    pub fn available() -> Vec<Self> {
+        let versions = versions_by_minecraft_version().unwrap();
+
+        let mut available: Vec<Self> = available_versions()
+            .unwrap()
+            .into_iter()
+            .filter_map(|name| versions.get(&name).cloned().map(Self))
+            .collect();
+
+        available.sort();
+        available.dedup();
+        available
+    }
+
+    /// Looks up a version by its name, e.g., `"1.14.4"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        versions_by_minecraft_version()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .map(Self)
+    }
+
+    /// Returns the newest version with data available that speaks the given
+    /// protocol version, e.g., `498 => 1.14.4`.
+    pub fn from_protocol_version(protocol_version: i32) -> Option<Self> {
+        Self::available()
+            .into_iter()
+            .filter(|version| version.protocol_version() == protocol_version)
+            .last()
+    }
+
+    /// Parses either a version name (`"1.14.4"`) or a protocol version number
+    /// (`"498"`).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        Self::from_name(s).or_else(|| {
+            s.parse::<i32>()
+                .ok()
+                .and_then(Self::from_protocol_version)
+        })
+    }
+
+    /// The name of this version, e.g., `"1.14.4"`.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.0.minecraft_version
+    }
+
+    /// The protocol version number spoken by this version, e.g., `498`.
+    #[inline]
+    pub fn protocol_version(&self) -> i32 {
+        self.0.version
+    }
+
+    /// Splits the name into numeric components for comparison, e.g., `"1.14.4"
+    /// => [1, 14, 4]`. Non-numeric components (as found in snapshot names)
+    /// compare as zero.
+    fn name_components(&self) -> Vec<u32> {
+        self.name()
+            .split('.')
+            .map(|component| component.parse().unwrap_or(0))
+            .collect()
+    }
+}
+
+/// Error returned when there is no data available for a requested version.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "unsupported Minecraft version {requested:?} (supported versions: {})",
+    .supported.join(", ")
+)]
+pub struct UnsupportedVersion {
+    /// The version string that was requested.
+    pub requested: String,
+
+    /// Names of all versions for which data is available.
+    pub supported: Vec<String>,
+}
+
+impl UnsupportedVersion {
+    pub(crate) fn new(requested: impl Into<String>) -> Self {
+        Self {
+            requested: requested.into(),
+            supported: Version::available()
+                .iter()
+                .map(|version| version.name().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Parses a version with [`Version::parse`], accepting only versions for which
+/// data is available.
+impl FromStr for Version {
+    type Err = UnsupportedVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+            .filter(|version| Self::available().contains(version))
+            .ok_or_else(|| UnsupportedVersion::new(s))
+    }
 }
 
 impl<S: Into<String>> From<S> for Version {
@@ -34,3 +144,84 @@ impl Deref for Version {
         &self.0
     }
 }
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.protocol_version()
+            .cmp(&other.protocol_version())
+            .then_with(|| self.name_components().cmp(&other.name_components()))
+            .then_with(|| self.name().cmp(other.name()))
+    }
+}
+
+impl fmt::Debug for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Version({} / {})", self.name(), self.protocol_version())
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let by_name = Version::parse("1.14.4").unwrap();
+        assert_eq!(by_name.name(), "1.14.4");
+        assert_eq!(by_name.protocol_version(), 498);
+
+        let by_protocol = Version::parse("498").unwrap();
+        assert_eq!(by_protocol, by_name);
+
+        assert_eq!(Version::parse("not a version"), None);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("1.14.4".parse::<Version>().unwrap().name(), "1.14.4");
+
+        let error = "0.0.0".parse::<Version>().unwrap_err();
+        assert_eq!(error.requested, "0.0.0");
+        assert!(error.supported.contains(&String::from("1.14.4")));
+        assert!(error.to_string().contains("1.14.4"));
+    }
+
+    #[test]
+    fn compare() {
+        let v1_14_4 = Version::from("1.14.4");
+        let v1_16_4 = Version::from("1.16.4");
+        let v1_16_5 = Version::from("1.16.5");
+
+        assert!(v1_14_4 < v1_16_4);
+        assert!(v1_16_4 < v1_16_5);
+        assert_eq!(v1_16_4.protocol_version(), v1_16_5.protocol_version());
+    }
+
+    #[test]
+    fn available() {
+        let available = Version::available();
+
+        assert!(available.contains(&Version::from("1.14.4")));
+        assert!(available.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -71,7 +71,7 @@ fn read_net_events(
     for event in event_reader.iter() {
         println!("NetworkEvent: {:?}", &event);
 
-        if let NetworkEvent::Connected = event {
+        if let NetworkEvent::Connected { .. } = event {
             let packet = String::from("hello world");
             codec_writer.send(packet);
         }
@@ -0,0 +1,166 @@
+use std::io;
+
+use async_codec::{Decode, DecodeResult, Encode, EncodeResult};
+use minecraft_varint::{VarIntRead, VarIntWrite};
+
+/// A simple codec that sends and receives arbitrary byte payloads, each
+/// prefixed with its length as a VarInt.
+///
+/// This is the same framing that the Minecraft protocol uses for its packets,
+/// which makes it a good starting point for prototyping other protocols.
+#[derive(Debug, Default, Clone)]
+pub struct BytesCodec;
+
+/// Returns the number of bytes needed to encode `value` as a VarInt.
+fn var_u32_len(value: u32) -> usize {
+    let bits = 32 - value.leading_zeros() as usize;
+    (bits.max(1) + 6) / 7
+}
+
+impl Encode for BytesCodec {
+    type Item = Vec<u8>;
+    type Error = ();
+
+    fn encode(&mut self, item: &Self::Item, buf: &mut [u8]) -> EncodeResult<Self::Error> {
+        let len: u32 = match item.len().try_into() {
+            Ok(len) => len,
+            Err(_) => return EncodeResult::Err(()),
+        };
+
+        let prefix_len = var_u32_len(len);
+        let bytes_needed = prefix_len + item.len();
+        if buf.len() < bytes_needed {
+            return EncodeResult::Overflow(bytes_needed);
+        }
+
+        (&mut buf[..prefix_len]).write_var_u32(len).unwrap();
+        buf[prefix_len..bytes_needed].copy_from_slice(item);
+
+        EncodeResult::Ok(bytes_needed)
+    }
+}
+
+impl Decode for BytesCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &mut [u8]) -> (usize, DecodeResult<Self::Item, Self::Error>) {
+        let mut body: &[u8] = buf;
+
+        let len = match body.read_var_u32() {
+            Ok(len) => len as usize,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return (0, DecodeResult::UnexpectedEnd)
+            }
+            Err(e) => return (0, DecodeResult::Err(e)),
+        };
+        let prefix_len = buf.len() - body.len();
+
+        if body.len() < len {
+            return (0, DecodeResult::UnexpectedEnd);
+        }
+
+        (prefix_len + len, DecodeResult::Ok(body[..len].to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(payloads: &[&[u8]]) -> Vec<u8> {
+        let mut codec = BytesCodec;
+        let mut encoded = Vec::new();
+
+        for payload in payloads {
+            let mut buf = vec![0; 1024];
+            match codec.encode(&payload.to_vec(), &mut buf) {
+                EncodeResult::Ok(len) => encoded.extend_from_slice(&buf[..len]),
+                _ => panic!("failed to encode {:?}", payload),
+            }
+        }
+
+        encoded
+    }
+
+    /// Decodes frames until the buffer runs out, returning them along with the
+    /// number of bytes left over.
+    fn decode_all(mut buf: Vec<u8>) -> (Vec<Vec<u8>>, usize) {
+        let mut codec = BytesCodec;
+        let mut frames = Vec::new();
+
+        loop {
+            match codec.decode(&mut buf) {
+                (len, DecodeResult::Ok(frame)) => {
+                    frames.push(frame);
+                    buf.drain(..len);
+                }
+                (0, DecodeResult::UnexpectedEnd) => return (frames, buf.len()),
+                (_, DecodeResult::Err(e)) => panic!("failed to decode: {}", e),
+                (len, DecodeResult::UnexpectedEnd) => {
+                    panic!("consumed {} bytes without decoding a frame", len)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let encoded = encode(&[b"hello"]);
+
+        assert_eq!(encoded, b"\x05hello");
+        assert_eq!(decode_all(encoded), (vec![b"hello".to_vec()], 0));
+    }
+
+    #[test]
+    fn empty_payload() {
+        let encoded = encode(&[b""]);
+
+        assert_eq!(encoded, b"\x00");
+        assert_eq!(decode_all(encoded), (vec![vec![]], 0));
+    }
+
+    #[test]
+    fn multiple_frames() {
+        let long = [7; 300];
+        let encoded = encode(&[b"a", b"", &long, b"bc"]);
+
+        // 300 needs a two-byte VarInt.
+        assert_eq!(encoded.len(), (1 + 1) + 1 + (2 + 300) + (1 + 2));
+        assert_eq!(
+            decode_all(encoded),
+            (
+                vec![b"a".to_vec(), vec![], long.to_vec(), b"bc".to_vec()],
+                0
+            )
+        );
+    }
+
+    #[test]
+    fn partial_frame() {
+        let mut encoded = encode(&[b"hello", &[7; 300]]);
+
+        // Part of the body is missing.
+        let (frames, left_over) = decode_all(encoded[..encoded.len() - 1].to_vec());
+        assert_eq!(frames, vec![b"hello".to_vec()]);
+        assert_eq!(left_over, 2 + 299);
+
+        // Part of the length prefix is missing.
+        encoded.truncate(6 + 1);
+        let mut codec = BytesCodec;
+        assert!(matches!(
+            codec.decode(&mut encoded[6..]),
+            (0, DecodeResult::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn encode_overflow() {
+        let mut buf = [0; 4];
+
+        assert!(matches!(
+            BytesCodec.encode(&b"hello".to_vec(), &mut buf),
+            EncodeResult::Overflow(6)
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 use async_codec::{Decode, DecodeResult, Encode, EncodeResult};
 
 /// A dummy codec useful for testing.
+///
+/// It never sends or receives anything meaningful. For tests that need packets
+/// to flow, see [`ScriptedCodec`][super::ScriptedCodec].
 #[derive(Debug, Default, Clone)]
 pub struct DummyCodec;
 
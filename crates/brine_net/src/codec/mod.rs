@@ -1,7 +1,11 @@
 //! Implementations of a small number of network codecs.
 
+mod bytes;
 mod dummy;
+mod scripted;
 mod string;
 
+pub use bytes::BytesCodec;
 pub use dummy::DummyCodec;
-pub use string::StringCodec;
+pub use scripted::ScriptedCodec;
+pub use string::{StringCodec, StringCodecError};
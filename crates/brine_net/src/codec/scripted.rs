@@ -0,0 +1,230 @@
+use std::fmt;
+
+use async_codec::{Decode, DecodeResult, Encode, EncodeResult};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+/// A codec for testing that "receives" items from a queue filled in by the
+/// test, and records the items that are sent instead of encoding them.
+///
+/// Clones share the same queues, so items can be queued, and sent items
+/// checked, through the codec returned by
+/// [`NetworkResource::codec`][crate::NetworkResource::codec].
+///
+/// Each call to `decode` receives the next queued item and consumes the whole
+/// buffer, whatever it holds, so no socket is needed: play back a recorded
+/// session (see [`replay`][crate::replay]) with one selfbound frame for each
+/// queued item. Sent items are encoded as zero bytes.
+pub struct ScriptedCodec<T> {
+    received_sender: Sender<T>,
+    received_receiver: Receiver<T>,
+    sent_sender: Sender<T>,
+    sent_receiver: Receiver<T>,
+}
+
+impl<T> ScriptedCodec<T> {
+    pub fn new() -> Self {
+        let (received_sender, received_receiver) = unbounded();
+        let (sent_sender, sent_receiver) = unbounded();
+
+        Self {
+            received_sender,
+            received_receiver,
+            sent_sender,
+            sent_receiver,
+        }
+    }
+
+    /// Queues an item to be received by the next call to `decode`.
+    pub fn queue_received(&self, item: T) {
+        self.received_sender.send(item).unwrap();
+    }
+
+    /// Returns how many queued items haven't been received yet.
+    pub fn num_queued(&self) -> usize {
+        self.received_receiver.len()
+    }
+
+    /// Takes the items that have been sent since the last call, in the order
+    /// they were sent.
+    pub fn take_sent(&self) -> Vec<T> {
+        self.sent_receiver.try_iter().collect()
+    }
+}
+
+impl<T> Default for ScriptedCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ScriptedCodec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            received_sender: self.received_sender.clone(),
+            received_receiver: self.received_receiver.clone(),
+            sent_sender: self.sent_sender.clone(),
+            sent_receiver: self.sent_receiver.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ScriptedCodec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptedCodec")
+            .field("queued", &self.received_receiver.len())
+            .field("sent", &self.sent_receiver.len())
+            .finish()
+    }
+}
+
+impl<T: Clone> Encode for ScriptedCodec<T> {
+    type Item = T;
+    type Error = ();
+
+    fn encode(&mut self, item: &Self::Item, _buf: &mut [u8]) -> EncodeResult<Self::Error> {
+        self.sent_sender.send(item.clone()).unwrap();
+        EncodeResult::Ok(0)
+    }
+}
+
+impl<T> Decode for ScriptedCodec<T> {
+    type Item = T;
+    type Error = ();
+
+    fn decode(&mut self, buf: &mut [u8]) -> (usize, DecodeResult<Self::Item, Self::Error>) {
+        match self.received_receiver.try_recv() {
+            Ok(item) => (buf.len(), DecodeResult::Ok(item)),
+            Err(_) => (0, DecodeResult::UnexpectedEnd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use bevy::prelude::*;
+
+    use crate::{
+        record::{FrameDirection, RecordedFrame, RecordedSession, Recording},
+        replay::{ReplayConnectionPlugin, ReplayTiming},
+        resolve::ServerTarget,
+        CodecReader, CodecWriter, NetworkPlugin, NetworkResource,
+    };
+
+    use super::*;
+
+    type Codec = ScriptedCodec<String>;
+
+    #[derive(Default)]
+    struct Received(Vec<String>);
+
+    fn echo(
+        mut reader: CodecReader<Codec>,
+        mut writer: CodecWriter<Codec>,
+        mut received: ResMut<Received>,
+    ) {
+        for packet in reader.iter() {
+            received.0.push(packet.clone());
+            writer.send(format!("{} back", packet));
+        }
+    }
+
+    /// A session that stays connected, with a selfbound frame for each item
+    /// that will be received.
+    fn session(num_received: usize) -> RecordedSession {
+        let frame = RecordedFrame {
+            time: Duration::ZERO,
+            direction: FrameDirection::Selfbound,
+            bytes: Vec::new(),
+        };
+
+        RecordedSession {
+            target: ServerTarget {
+                host: String::from("scripted.invalid"),
+                port: 25565,
+                from_srv: false,
+            },
+            connected_at: Duration::ZERO,
+            frames: vec![frame; num_received],
+            disconnected: false,
+        }
+    }
+
+    #[test]
+    fn decode_and_encode() {
+        let mut codec = Codec::new();
+        codec.queue_received(String::from("one"));
+        assert_eq!(codec.num_queued(), 1);
+
+        let (length, result) = codec.decode(&mut [1, 2, 3]);
+        assert_eq!(length, 3);
+        assert!(matches!(result, DecodeResult::Ok(item) if item == "one"));
+
+        let (length, result) = codec.decode(&mut []);
+        assert_eq!(length, 0);
+        assert!(matches!(result, DecodeResult::UnexpectedEnd));
+
+        assert!(matches!(
+            codec.encode(&String::from("two"), &mut []),
+            EncodeResult::Ok(0)
+        ));
+        assert_eq!(codec.take_sent(), ["two"]);
+        assert!(codec.take_sent().is_empty());
+    }
+
+    #[test]
+    fn clones_share_queues() {
+        let codec = Codec::new();
+        let mut clone = codec.clone();
+
+        codec.queue_received(String::from("one"));
+        let (_, result) = clone.decode(&mut []);
+        assert!(matches!(result, DecodeResult::Ok(item) if item == "one"));
+        assert_eq!(codec.num_queued(), 0);
+
+        let _ = clone.encode(&String::from("two"), &mut []);
+        assert_eq!(codec.take_sent(), ["two"]);
+    }
+
+    #[test]
+    fn items_flow_through_codec_reader_and_writer() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(NetworkPlugin::<Codec>::default())
+            .add_plugin(ReplayConnectionPlugin::<Codec>::new(
+                Recording {
+                    sessions: vec![session(2)],
+                },
+                ReplayTiming::FullSpeed,
+            ))
+            .init_resource::<Received>()
+            .add_system(echo);
+
+        let mut net_resource = app
+            .world
+            .get_resource_mut::<NetworkResource<Codec>>()
+            .unwrap();
+        let codec = net_resource.codec().clone();
+        codec.queue_received(String::from("hello"));
+        codec.queue_received(String::from("world"));
+        net_resource.connect(String::from("scripted.invalid"));
+
+        let mut sent = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while sent.len() < 2 {
+            assert!(Instant::now() < deadline, "timed out");
+            app.update();
+            sent.extend(codec.take_sent());
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let received = &app.world.get_resource::<Received>().unwrap().0;
+        assert_eq!(received, &["hello", "world"]);
+        assert_eq!(sent, ["hello back", "world back"]);
+        assert_eq!(codec.num_queued(), 0);
+    }
+}
@@ -5,8 +5,49 @@ use bevy::log;
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
 /// A simple codec that sends and receives length-prefixed strings.
-#[derive(Debug, Default, Clone)]
-pub struct StringCodec;
+///
+/// Frames whose length prefix is longer than the codec's maximum length (by
+/// default, [`DEFAULT_MAX_LENGTH`][Self::DEFAULT_MAX_LENGTH]) are rejected
+/// without waiting for the rest of the frame to arrive, so that a misbehaving
+/// peer can't make the codec buffer an unbounded amount of data.
+#[derive(Debug, Clone)]
+pub struct StringCodec {
+    max_length: usize,
+}
+
+/// An error decoding a frame with a [`StringCodec`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StringCodecError {
+    #[error("frame of {length} bytes is longer than the maximum of {max_length}")]
+    TooLong { length: usize, max_length: usize },
+
+    #[error(transparent)]
+    Utf8(#[from] Utf8Error),
+}
+
+impl StringCodec {
+    /// The maximum length of a string, in bytes, for a default codec (1 MiB).
+    pub const DEFAULT_MAX_LENGTH: usize = 1024 * 1024;
+
+    /// Creates a codec that rejects strings longer than `max_length` bytes.
+    ///
+    /// Once a frame is rejected, the rest of it is still in the stream, so the
+    /// connection should be dropped.
+    pub fn with_max_length(max_length: usize) -> Self {
+        Self { max_length }
+    }
+
+    /// Returns the maximum length of a string, in bytes.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+impl Default for StringCodec {
+    fn default() -> Self {
+        Self::with_max_length(Self::DEFAULT_MAX_LENGTH)
+    }
+}
 
 impl Encode for StringCodec {
     type Item = String;
@@ -28,7 +69,7 @@ impl Encode for StringCodec {
 
 impl Decode for StringCodec {
     type Item = String;
-    type Error = Utf8Error;
+    type Error = StringCodecError;
 
     fn decode(&mut self, buf: &mut [u8]) -> (usize, DecodeResult<Self::Item, Self::Error>) {
         log::trace!("decode: buf = {:?}", &buf);
@@ -41,13 +82,81 @@ impl Decode for StringCodec {
 
         log::trace!("decode: len={}, buf={:?}", len, &buf);
 
+        if len > self.max_length {
+            let error = StringCodecError::TooLong {
+                length: len,
+                max_length: self.max_length,
+            };
+            return (mem::size_of::<u32>(), DecodeResult::Err(error));
+        }
+
         if buf.len() < len {
             return (0, DecodeResult::UnexpectedEnd);
         }
         let string_bytes = &buf[..len];
         (
             mem::size_of::<u32>() + len,
-            std::str::from_utf8(string_bytes).map(String::from).into(),
+            std::str::from_utf8(string_bytes)
+                .map(String::from)
+                .map_err(StringCodecError::from)
+                .into(),
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn frame(length: u32, body: &[u8]) -> Vec<u8> {
+        let mut buf = length.to_be_bytes().to_vec();
+        buf.extend_from_slice(body);
+        buf
+    }
+
+    #[test]
+    fn under_limit_decodes() {
+        let mut codec = StringCodec::with_max_length(5);
+        let mut buf = frame(5, b"hello");
+
+        let (consumed, result) = codec.decode(&mut buf);
+
+        assert_eq!(consumed, 9);
+        assert!(matches!(result, DecodeResult::Ok(string) if string == "hello"));
+    }
+
+    #[test]
+    fn over_limit_is_an_error() {
+        let mut codec = StringCodec::with_max_length(5);
+
+        // The error doesn't wait for the body to arrive.
+        let mut buf = frame(6, b"");
+
+        let (consumed, result) = codec.decode(&mut buf);
+
+        assert_eq!(consumed, 4);
+        assert!(matches!(
+            result,
+            DecodeResult::Err(StringCodecError::TooLong {
+                length: 6,
+                max_length: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn default_limit() {
+        let mut codec = StringCodec::default();
+        let mut buf = frame(u32::MAX, b"");
+
+        let (_, result) = codec.decode(&mut buf);
+
+        assert!(matches!(
+            result,
+            DecodeResult::Err(StringCodecError::TooLong {
+                max_length: StringCodec::DEFAULT_MAX_LENGTH,
+                ..
+            })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{any::Any, fmt::Debug};
+use std::{any::Any, fmt::Debug, io, sync::Arc};
 
 use async_channel::{Receiver, Sender};
 use async_codec::{Decode, Encode, Framed, ReadFrameError, WriteFrameError};
@@ -6,7 +6,13 @@ use async_net::TcpStream;
 use bevy::log;
 use futures::{FutureExt, SinkExt, StreamExt};
 
-use crate::{event::NetworkError, resource::NetworkResource, NetworkEvent};
+use crate::{
+    event::NetworkError,
+    record::{Recorder, RecordingCodec},
+    resolve::{self, Resolver, ServerTarget},
+    resource::{ConnectionStatus, NetworkResource, SharedConnectionStatus},
+    NetworkEvent,
+};
 
 /// Internal utility struct responsible for running
 pub(crate) struct Connection<Codec: Decode + Encode>
@@ -14,9 +20,12 @@ where
     <Codec as Decode>::Error: Debug,
     <Codec as Encode>::Error: Debug,
 {
-    network_event_sender: Sender<NetworkEvent<Codec>>,
-    peerbound_packet_receiver: Receiver<<Codec as Encode>::Item>,
-    selfbound_packet_sender: Sender<<Codec as Decode>::Item>,
+    pub(crate) network_event_sender: Sender<NetworkEvent<Codec>>,
+    pub(crate) peerbound_packet_receiver: Receiver<<Codec as Encode>::Item>,
+    pub(crate) selfbound_packet_sender: Sender<<Codec as Decode>::Item>,
+    resolver: Arc<dyn Resolver>,
+    recorder: Option<Recorder>,
+    status: SharedConnectionStatus,
 }
 
 impl<Codec> Connection<Codec>
@@ -32,33 +41,81 @@ where
             network_event_sender: net_resource.network_event_sender.clone(),
             peerbound_packet_receiver: net_resource.peerbound_packet_receiver.clone(),
             selfbound_packet_sender: net_resource.selfbound_packet_sender.clone(),
+            resolver: net_resource.resolver.clone(),
+            recorder: net_resource.recorder.clone(),
+            status: net_resource.status.clone(),
         }
     }
 
-    async fn send_event(&self, event: NetworkEvent<Codec>) {
+    /// Sends an event to the plugin, first updating the connection status if
+    /// the event changes it.
+    pub(crate) async fn send_event(&self, event: NetworkEvent<Codec>) {
+        match &event {
+            NetworkEvent::Connected { .. } => self.status.set(ConnectionStatus::Connected),
+            NetworkEvent::Disconnected | NetworkEvent::Error(NetworkError::ConnectFailed(_)) => {
+                self.status.set(ConnectionStatus::Disconnected)
+            }
+            NetworkEvent::Error(_) => {}
+        }
+
         self.network_event_sender.send(event).await.unwrap();
     }
 
-    async fn send_error(&self, error: NetworkError<Codec>) {
+    pub(crate) async fn send_error(&self, error: NetworkError<Codec>) {
         self.send_event(NetworkEvent::Error(error)).await;
     }
 
+    /// Wraps the codec so that it records frames if recording is enabled.
+    fn recording_codec(&self, codec: Codec) -> RecordingCodec<Codec> {
+        RecordingCodec {
+            inner: codec,
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    /// Resolves the server address and opens a TCP connection to it.
+    async fn connect(&self, server_addr: &str) -> io::Result<(ServerTarget, TcpStream)> {
+        let target = resolve::resolve_server_address(server_addr, &*self.resolver).await?;
+
+        if target.from_srv {
+            log::info!("Resolved {} to {} via SRV record", server_addr, &target);
+        }
+
+        let socket_addrs = async_net::resolve((target.host.as_str(), target.port))
+            .await
+            .map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("could not resolve {}: {}", &target, err),
+                )
+            })?;
+
+        log::debug!("Connecting to {} ({:?}) ...", &target, &socket_addrs);
+
+        let tcp_stream = TcpStream::connect(&socket_addrs[..]).await?;
+
+        Ok((target, tcp_stream))
+    }
+
     /// Connects to a remote host and runs two background tasks to encode and
     /// decode network packets.
-    pub(crate) async fn connect_and_run(self, peer_addr: String, codec: Codec) {
-        log::debug!("Connecting to {} ...", &peer_addr);
-
-        let tcp_stream = match TcpStream::connect(peer_addr.clone()).await {
-            Ok(stream) => stream,
+    pub(crate) async fn connect_and_run(self, server_addr: String, codec: Codec) {
+        let (target, tcp_stream) = match self.connect(&server_addr).await {
+            Ok(connected) => connected,
             Err(err) => {
                 self.send_error(NetworkError::ConnectFailed(err)).await;
                 return;
             }
         };
 
+        let peer_addr = target.to_string();
         log::debug!("Connected to {}", &peer_addr);
 
-        self.send_event(NetworkEvent::Connected).await;
+        if let Some(recorder) = &self.recorder {
+            recorder.record_connected(&target);
+        }
+
+        self.send_event(NetworkEvent::Connected { target }).await;
 
         let peerbound_future = self.run_peerbound(tcp_stream.clone(), codec.clone()).fuse();
         let selfbound_future = self.run_selfbound(tcp_stream, codec).fuse();
@@ -75,6 +132,10 @@ where
 
         log::debug!("Disconnected from {}", &peer_addr);
 
+        if let Some(recorder) = &self.recorder {
+            recorder.record_disconnected();
+        }
+
         self.send_event(NetworkEvent::Disconnected).await;
     }
 
@@ -83,7 +144,7 @@ where
     async fn run_peerbound(&self, tcp_stream: TcpStream, codec: Codec) {
         log::trace!("peerbound writer task: starting");
 
-        let mut codec_writer = Framed::new(tcp_stream, codec);
+        let mut codec_writer = Framed::new(tcp_stream, self.recording_codec(codec));
 
         loop {
             let peerbound_packet = self.peerbound_packet_receiver.recv().await.unwrap();
@@ -107,7 +168,7 @@ where
     async fn run_selfbound(&self, tcp_stream: TcpStream, codec: Codec) {
         log::trace!("selfbound reader task: starting");
 
-        let mut codec_reader = Framed::new(tcp_stream, codec);
+        let mut codec_reader = Framed::new(tcp_stream, self.recording_codec(codec));
 
         loop {
             let selfbound_packet = codec_reader.next().await;
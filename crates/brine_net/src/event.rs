@@ -4,13 +4,18 @@ use std::{fmt::Debug, io};
 
 use async_codec::{Decode, Encode};
 
+use crate::resolve::ServerTarget;
+
 #[derive(Debug)]
 pub enum NetworkEvent<Codec: Decode + Encode>
 where
     <Codec as Decode>::Error: Debug,
     <Codec as Encode>::Error: Debug,
 {
-    Connected,
+    /// A connection was established with the server at `target`.
+    Connected {
+        target: ServerTarget,
+    },
     Disconnected,
     Error(NetworkError<Codec>),
 }
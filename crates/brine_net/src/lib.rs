@@ -76,8 +76,8 @@
 //! ) {
 //!     for event in event_reader.iter() {
 //!         // Let's send a single string once the connection is established.
-//!         if let NetworkEvent::Connected = event {
-//!             println!("Connection established!");
+//!         if let NetworkEvent::Connected { target } = event {
+//!             println!("Connection established with {}!", target);
 //!
 //!             let packet = String::from("hello world!");
 //!
@@ -102,10 +102,16 @@
 //!
 //! ```txt
 //! Connecting to my.echo.server:8000 ...
-//! Connection established!
+//! Connection established with my.echo.server:8000!
 //! Client sending packet: hello world!
 //! Client received packet: hello world!
 //! ```
+//!
+//! # Recording and replaying
+//!
+//! The frames of a connection can be recorded to a file (see [`record`]) and
+//! played back later in place of a real connection (see [`replay`]), which
+//! makes it possible to reproduce bugs without a server.
 
 mod connection;
 mod event;
@@ -114,9 +120,12 @@ mod resource;
 mod system_param;
 
 pub mod codec;
+pub mod record;
+pub mod replay;
+pub mod resolve;
 
 pub use async_codec::{Decode, DecodeResult, Encode, EncodeResult};
 
 pub use event::{NetworkError, NetworkEvent};
 pub use plugin::{CodecReader, CodecWriter, NetworkPlugin};
-pub use resource::NetworkResource;
+pub use resource::{ConnectionStatus, NetworkResource};
@@ -13,8 +13,8 @@ use bevy::{
 };
 
 use crate::{
-    event::NetworkEvent,
-    resource::NetworkResource,
+    event::{NetworkError, NetworkEvent},
+    resource::{ConnectionStatus, NetworkResource},
     system_param::{self, Read, Write},
 };
 
@@ -58,6 +58,8 @@ pub type CodecWriter<'w, 's, Codec> =
 /// The plugin registers the following resources:
 /// * [`NetworkResource<Codec>`]
 ///   * Use [`connect()`][NetworkResource::connect] to establish a connection.
+///   * Use [`status()`][NetworkResource::status] to check whether a connection
+///     is established.
 ///
 /// The plugin expects no resources to exist.
 ///
@@ -119,9 +121,15 @@ where
         mut event_writer: EventWriter<NetworkEvent<Codec>>,
     ) {
         while let Ok(event) = net_resource.network_event_receiver.try_recv() {
-            // Clear the connection task if the connection has terminated,
-            // thus allowing a new connection to form in the future.
-            if let NetworkEvent::Disconnected = event {
+            // Clear the connection task if the connection has terminated or
+            // failed to be established, thus allowing a new connection to
+            // form in the future. The status is checked in case a new
+            // connection was started after a call to `disconnect`.
+            let terminated = matches!(
+                event,
+                NetworkEvent::Disconnected | NetworkEvent::Error(NetworkError::ConnectFailed(_))
+            );
+            if terminated && net_resource.status.get() == ConnectionStatus::Disconnected {
                 net_resource.connection_task = None;
             }
 
@@ -0,0 +1,387 @@
+//! Recording of the raw frames sent and received over a connection.
+//!
+//! When a [`Recorder`] is given to
+//! [`NetworkResource::start_recording`][crate::NetworkResource::start_recording],
+//! the bytes of every frame that the codec decodes or encodes are written to
+//! the recording along with their direction and the time they were seen. The
+//! recording can later be loaded as a [`Recording`] and played back with the
+//! [`ReplayConnectionPlugin`][crate::replay::ReplayConnectionPlugin].
+//!
+//! # File format
+//!
+//! A recording starts with the magic bytes `BRINEREC` and a format version
+//! byte, followed by any number of records. Each record is laid out like so
+//! (all integers big-endian):
+//!
+//! | Field     | Type        | Notes                                              |
+//! |-----------|-------------|----------------------------------------------------|
+//! | kind      | `u8`        | 0 = connected, 1 = disconnected, 2 = selfbound frame, 3 = peerbound frame |
+//! | time      | `u64`       | Microseconds since the recording was started        |
+//! | length    | `u32`       | Length of the data                                 |
+//! | data      | `[u8]`      | The frame, or the server's port and host for a connected record |
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_codec::{Decode, DecodeResult, Encode, EncodeResult};
+use bevy::log;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::resolve::ServerTarget;
+
+const MAGIC: &[u8; 8] = b"BRINEREC";
+const FORMAT_VERSION: u8 = 1;
+
+const KIND_CONNECTED: u8 = 0;
+const KIND_DISCONNECTED: u8 = 1;
+const KIND_SELFBOUND: u8 = 2;
+const KIND_PEERBOUND: u8 = 3;
+
+/// Which way a frame was travelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameDirection {
+    /// Received from the remote host.
+    Selfbound,
+
+    /// Sent to the remote host.
+    Peerbound,
+}
+
+/// Writes frames to a recording as they are sent and received.
+///
+/// Clones of a recorder write to the same recording.
+#[derive(Clone)]
+pub struct Recorder {
+    inner: Arc<Mutex<RecorderInner>>,
+}
+
+struct RecorderInner {
+    writer: Box<dyn Write + Send>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Creates (or truncates) the file at `path` and records to it.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Self::new(BufWriter::new(file))
+    }
+
+    /// Records to `writer`, starting with the recording's header.
+    pub fn new(mut writer: impl Write + Send + 'static) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_u8(FORMAT_VERSION)?;
+        writer.flush()?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(RecorderInner {
+                writer: Box::new(writer),
+                started: Instant::now(),
+            })),
+        })
+    }
+
+    pub(crate) fn record_connected(&self, target: &ServerTarget) {
+        let mut data = Vec::with_capacity(2 + target.host.len());
+        data.write_u16::<BigEndian>(target.port).unwrap();
+        data.extend_from_slice(target.host.as_bytes());
+
+        self.record(KIND_CONNECTED, &data);
+    }
+
+    pub(crate) fn record_disconnected(&self) {
+        self.record(KIND_DISCONNECTED, &[]);
+    }
+
+    pub(crate) fn record_frame(&self, direction: FrameDirection, frame: &[u8]) {
+        let kind = match direction {
+            FrameDirection::Selfbound => KIND_SELFBOUND,
+            FrameDirection::Peerbound => KIND_PEERBOUND,
+        };
+
+        self.record(kind, frame);
+    }
+
+    fn record(&self, kind: u8, data: &[u8]) {
+        let mut inner = self.inner.lock().unwrap();
+        let time = inner.started.elapsed().as_micros() as u64;
+
+        // Flush every record so that the recording survives a crash.
+        let result = (|| {
+            inner.writer.write_u8(kind)?;
+            inner.writer.write_u64::<BigEndian>(time)?;
+            inner.writer.write_u32::<BigEndian>(data.len() as u32)?;
+            inner.writer.write_all(data)?;
+            inner.writer.flush()
+        })();
+
+        if let Err(err) = result {
+            log::error!("Failed to write to recording: {}", err);
+        }
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder").finish_non_exhaustive()
+    }
+}
+
+/// A frame read from a recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedFrame {
+    /// When the frame was seen, relative to the start of the recording.
+    pub time: Duration,
+
+    pub direction: FrameDirection,
+
+    pub bytes: Vec<u8>,
+}
+
+/// Everything that was recorded during one connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedSession {
+    /// The server that was connected to.
+    pub target: ServerTarget,
+
+    /// When the connection was established, relative to the start of the
+    /// recording.
+    pub connected_at: Duration,
+
+    /// The frames sent and received, in the order they were seen.
+    pub frames: Vec<RecordedFrame>,
+
+    /// Whether the connection was closed before the recording ended.
+    pub disconnected: bool,
+}
+
+/// A recording that has been read back in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub sessions: Vec<RecordedSession>,
+}
+
+impl Recording {
+    /// Reads the recording in the file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Self::read_from(BufReader::new(file))
+    }
+
+    /// Reads a recording from `reader`.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a recording"));
+        }
+
+        let version = reader.read_u8()?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported recording format version {}",
+                version
+            )));
+        }
+
+        let mut recording = Recording::default();
+
+        loop {
+            let kind = match reader.read_u8() {
+                Ok(kind) => kind,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            };
+            let time = Duration::from_micros(reader.read_u64::<BigEndian>()?);
+            let length = reader.read_u32::<BigEndian>()? as usize;
+            let mut data = vec![0; length];
+            reader.read_exact(&mut data)?;
+
+            let current_session = recording
+                .sessions
+                .last_mut()
+                .filter(|session| !session.disconnected);
+
+            match (kind, current_session) {
+                (KIND_CONNECTED, _) => {
+                    recording.sessions.push(RecordedSession {
+                        target: parse_target(&data)?,
+                        connected_at: time,
+                        frames: Vec::new(),
+                        disconnected: false,
+                    });
+                }
+                (KIND_DISCONNECTED, Some(session)) => {
+                    session.disconnected = true;
+                }
+                (KIND_SELFBOUND | KIND_PEERBOUND, Some(session)) => {
+                    session.frames.push(RecordedFrame {
+                        time,
+                        direction: if kind == KIND_SELFBOUND {
+                            FrameDirection::Selfbound
+                        } else {
+                            FrameDirection::Peerbound
+                        },
+                        bytes: data,
+                    });
+                }
+                (KIND_DISCONNECTED | KIND_SELFBOUND | KIND_PEERBOUND, None) => {
+                    return Err(invalid_data("record outside of a connection"));
+                }
+                (kind, _) => {
+                    return Err(invalid_data(format!("unknown record kind {}", kind)));
+                }
+            }
+        }
+
+        Ok(recording)
+    }
+}
+
+fn parse_target(mut data: &[u8]) -> io::Result<ServerTarget> {
+    let port = data.read_u16::<BigEndian>()?;
+    let host = String::from_utf8(data.to_vec()).map_err(invalid_data)?;
+
+    Ok(ServerTarget {
+        host,
+        port,
+        from_srv: false,
+    })
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Wraps a codec to record every frame that it decodes or encodes.
+#[derive(Clone)]
+pub(crate) struct RecordingCodec<Codec> {
+    pub(crate) inner: Codec,
+    pub(crate) recorder: Option<Recorder>,
+}
+
+impl<Codec: Decode> Decode for RecordingCodec<Codec> {
+    type Item = Codec::Item;
+    type Error = Codec::Error;
+
+    fn decode(&mut self, buf: &mut [u8]) -> (usize, DecodeResult<Self::Item, Self::Error>) {
+        let (length, result) = self.inner.decode(buf);
+
+        if let Some(recorder) = &self.recorder {
+            if length > 0 {
+                recorder.record_frame(FrameDirection::Selfbound, &buf[..length]);
+            }
+        }
+
+        (length, result)
+    }
+}
+
+impl<Codec: Encode> Encode for RecordingCodec<Codec> {
+    type Item = Codec::Item;
+    type Error = Codec::Error;
+
+    fn encode(&mut self, item: &Self::Item, buf: &mut [u8]) -> EncodeResult<Self::Error> {
+        let result = self.inner.encode(item, buf);
+
+        if let (Some(recorder), EncodeResult::Ok(length)) = (&self.recorder, &result) {
+            recorder.record_frame(FrameDirection::Peerbound, &buf[..*length]);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A writer whose contents can be read after it's been given away.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn target(host: &str, port: u16) -> ServerTarget {
+        ServerTarget {
+            host: host.to_string(),
+            port,
+            from_srv: false,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let buf = SharedBuf::default();
+        let recorder = Recorder::new(buf.clone()).unwrap();
+
+        recorder.record_connected(&target("localhost", 25565));
+        recorder.record_frame(FrameDirection::Peerbound, &[1, 2]);
+        recorder.record_frame(FrameDirection::Selfbound, &[3]);
+        recorder.record_disconnected();
+        recorder.record_connected(&target("::1", 1234));
+        recorder.record_frame(FrameDirection::Selfbound, &[]);
+
+        let bytes = buf.0.lock().unwrap().clone();
+        let recording = Recording::read_from(&bytes[..]).unwrap();
+
+        assert_eq!(recording.sessions.len(), 2);
+
+        let first = &recording.sessions[0];
+        assert_eq!(first.target, target("localhost", 25565));
+        assert!(first.disconnected);
+        assert_eq!(
+            first
+                .frames
+                .iter()
+                .map(|frame| (frame.direction, &frame.bytes[..]))
+                .collect::<Vec<_>>(),
+            [
+                (FrameDirection::Peerbound, &[1, 2][..]),
+                (FrameDirection::Selfbound, &[3][..])
+            ]
+        );
+        assert!(first.frames[0].time >= first.connected_at);
+        assert!(first.frames[1].time >= first.frames[0].time);
+
+        let second = &recording.sessions[1];
+        assert_eq!(second.target, target("::1", 1234));
+        assert!(!second.disconnected);
+        assert_eq!(second.frames.len(), 1);
+    }
+
+    #[test]
+    fn invalid_recordings() {
+        assert!(Recording::read_from(&b"NOTAREC\x01"[..]).is_err());
+        assert!(Recording::read_from(&b"BRINEREC\x09"[..]).is_err());
+
+        // A frame before any connection.
+        let mut bytes = b"BRINEREC\x01".to_vec();
+        bytes.extend([KIND_SELFBOUND, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(Recording::read_from(&bytes[..]).is_err());
+
+        // A truncated record.
+        let mut bytes = b"BRINEREC\x01".to_vec();
+        bytes.extend([KIND_CONNECTED, 0, 0]);
+        assert!(Recording::read_from(&bytes[..]).is_err());
+
+        assert_eq!(
+            Recording::read_from(&b"BRINEREC\x01"[..]).unwrap(),
+            Recording::default()
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Playing back a [`Recording`] in place of a real connection.
+//!
+//! With the [`ReplayConnectionPlugin`] added, each call to
+//! [`NetworkResource::connect`] plays back the next connection in the
+//! recording instead of connecting to the server. The recorded selfbound
+//! frames are fed to the codec as if they had just been received, so the rest
+//! of the app sees exactly the same packets it saw when the recording was made.
+//!
+//! Packets that the app sends are not sent anywhere, but they are still encoded
+//! with the codec and compared with the ones in the recording. The recorded
+//! peerbound frames also keep the replay in step with the app: when the replay
+//! reaches one, it waits (for up to [`SYNC_TIMEOUT`]) for the app to send its
+//! next packet before feeding it any more frames, just like a server would wait
+//! for the client's request before responding to it.
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt::Debug,
+    io,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use async_codec::{Decode, DecodeResult, Encode, EncodeResult};
+use async_io::Timer;
+use bevy::{
+    app::{App, Plugin},
+    log,
+};
+use futures_lite::FutureExt;
+
+use crate::{
+    connection::Connection,
+    event::{NetworkError, NetworkEvent},
+    record::{FrameDirection, RecordedFrame, RecordedSession, Recording},
+    resource::NetworkResource,
+};
+
+/// How long a replay waits for the app to send a packet that's in the
+/// recording before giving up and carrying on without it.
+pub const SYNC_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How fast a recording is played back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayTiming {
+    /// Feed each frame to the codec as soon as possible.
+    FullSpeed,
+
+    /// Feed each frame to the codec at the same time, relative to the start of
+    /// its connection, as it was originally received.
+    Original,
+}
+
+/// Plugin that replaces the real network connection of a [`NetworkPlugin`]
+/// with one that plays back a [`Recording`].
+///
+/// This plugin must be added after the [`NetworkPlugin`] for the same codec.
+///
+/// [`NetworkPlugin`]: crate::NetworkPlugin
+pub struct ReplayConnectionPlugin<Codec> {
+    recording: Recording,
+    timing: ReplayTiming,
+    _phantom: PhantomData<Codec>,
+}
+
+impl<Codec> ReplayConnectionPlugin<Codec> {
+    pub fn new(recording: Recording, timing: ReplayTiming) -> Self {
+        Self {
+            recording,
+            timing,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Codec> Plugin for ReplayConnectionPlugin<Codec>
+where
+    Codec: Decode + Encode + Default + Clone + Unpin + Any + Send + Sync,
+    <Codec as Decode>::Item: Debug + Send + Sync,
+    <Codec as Encode>::Item: Debug + Send + Sync,
+    <Codec as Decode>::Error: Debug + Send + Sync,
+    <Codec as Encode>::Error: Debug + Send + Sync,
+{
+    fn build(&self, app: &mut App) {
+        let mut net_resource = app
+            .world
+            .get_resource_mut::<NetworkResource<Codec>>()
+            .expect("ReplayConnectionPlugin must be added after NetworkPlugin");
+
+        net_resource.replay = Some(Replay {
+            sessions: self.recording.sessions.iter().cloned().collect(),
+            timing: self.timing,
+        });
+    }
+}
+
+/// The connections of a recording that haven't been played back yet.
+pub(crate) struct Replay {
+    sessions: VecDeque<RecordedSession>,
+    timing: ReplayTiming,
+}
+
+impl Replay {
+    pub(crate) fn next_session(&mut self) -> Option<(RecordedSession, ReplayTiming)> {
+        self.sessions
+            .pop_front()
+            .map(|session| (session, self.timing))
+    }
+}
+
+impl<Codec> Connection<Codec>
+where
+    Codec: Decode + Encode + Default + Clone + Any + Unpin + Send + 'static,
+    <Codec as Decode>::Item: Debug + Send + 'static,
+    <Codec as Encode>::Item: Debug + Send + 'static,
+    <Codec as Decode>::Error: Debug + Send + 'static,
+    <Codec as Encode>::Error: Debug + Send + 'static,
+{
+    /// Plays back a recorded connection in place of connecting to
+    /// `server_addr`.
+    pub(crate) async fn replay_and_run(
+        self,
+        server_addr: String,
+        session: Option<(RecordedSession, ReplayTiming)>,
+        mut codec: Codec,
+    ) {
+        let (session, timing) = match session {
+            Some(session) => session,
+            None => {
+                let err = io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "there are no more connections in the recording",
+                );
+                self.send_error(NetworkError::ConnectFailed(err)).await;
+                return;
+            }
+        };
+
+        log::info!(
+            "Replaying recorded connection to {} in place of {}",
+            &session.target,
+            server_addr
+        );
+
+        self.send_event(NetworkEvent::Connected {
+            target: session.target.clone(),
+        })
+        .await;
+
+        let started = Instant::now();
+
+        for frame in session.frames {
+            match frame.direction {
+                FrameDirection::Selfbound => {
+                    if timing == ReplayTiming::Original {
+                        let offset = frame.time.saturating_sub(session.connected_at);
+                        Timer::at(started + offset).await;
+                    }
+
+                    self.replay_selfbound(&mut codec, frame).await;
+                }
+                FrameDirection::Peerbound => {
+                    self.await_peerbound(&mut codec, frame).await;
+                }
+            }
+        }
+
+        if session.disconnected {
+            log::debug!("Recorded connection to {} finished", &session.target);
+            self.send_event(NetworkEvent::Disconnected).await;
+            return;
+        }
+
+        // The recording ended while still connected, so stay connected.
+        loop {
+            let packet = self.peerbound_packet_receiver.recv().await.unwrap();
+            log::debug!("Packet sent after the end of the recording: {:?}", &packet);
+            encode(&mut codec, &packet);
+        }
+    }
+
+    /// Decodes a recorded frame as if it had just been received.
+    async fn replay_selfbound(&self, codec: &mut Codec, mut frame: RecordedFrame) {
+        let (length, result) = codec.decode(&mut frame.bytes[..]);
+
+        if length != frame.bytes.len() {
+            log::warn!(
+                "Codec decoded {} bytes of a {} byte recorded frame",
+                length,
+                frame.bytes.len()
+            );
+        }
+
+        match result {
+            DecodeResult::Ok(packet) => self.selfbound_packet_sender.send(packet).await.unwrap(),
+            DecodeResult::Err(err) => self.send_error(NetworkError::DecodeError(err)).await,
+            DecodeResult::UnexpectedEnd => {
+                let err =
+                    io::Error::new(io::ErrorKind::UnexpectedEof, "recorded frame is incomplete");
+                self.send_error(NetworkError::TransportError(err)).await;
+            }
+        }
+    }
+
+    /// Waits for the app to send the packet that comes next in the recording,
+    /// and checks that it matches.
+    async fn await_peerbound(&self, codec: &mut Codec, frame: RecordedFrame) {
+        let receive = async { Some(self.peerbound_packet_receiver.recv().await.unwrap()) };
+        let timeout = async {
+            Timer::after(SYNC_TIMEOUT).await;
+            None
+        };
+
+        match receive.or(timeout).await {
+            Some(packet) => match encode(codec, &packet) {
+                Some(bytes) if bytes == frame.bytes => {
+                    log::trace!("Sent packet matches the recording: {:?}", &packet);
+                }
+                _ => log::warn!("Sent packet does not match the recording: {:?}", &packet),
+            },
+            None => log::warn!(
+                "Timed out waiting for a packet to be sent at {:?} into the recording",
+                frame.time
+            ),
+        }
+    }
+}
+
+/// Encodes `item` the same way it would be before being sent.
+fn encode<Codec>(codec: &mut Codec, item: &<Codec as Encode>::Item) -> Option<Vec<u8>>
+where
+    Codec: Encode,
+    <Codec as Encode>::Error: Debug,
+{
+    let mut buf = vec![0; 256];
+
+    loop {
+        match codec.encode(item, &mut buf[..]) {
+            EncodeResult::Ok(length) => {
+                buf.truncate(length);
+                return Some(buf);
+            }
+            EncodeResult::Overflow(needed) => {
+                let new_len = needed.max(buf.len() * 2);
+                buf.resize(new_len, 0);
+            }
+            EncodeResult::Err(err) => {
+                log::warn!("Failed to encode packet: {:?}", err);
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! Resolving server addresses the same way the vanilla Minecraft client does.
+//!
+//! An address with an explicit port is used as-is. Otherwise, the
+//! `_minecraft._tcp.<host>` SRV record is looked up, which is how servers
+//! advertise a nonstandard port behind a friendly hostname. If there is no SRV
+//! record, the default port (25565) is used.
+
+use std::{
+    fmt,
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    time::{Duration, SystemTime},
+};
+
+use async_io::Timer;
+use async_net::UdpSocket;
+use bevy::log;
+use futures_lite::FutureExt;
+
+/// Port used when an address doesn't specify one and has no SRV record.
+pub const DEFAULT_PORT: u16 = 25565;
+
+/// Where a server address resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServerTarget {
+    pub host: String,
+    pub port: u16,
+
+    /// Whether the host and port came from an SRV record.
+    pub from_srv: bool,
+}
+
+impl fmt::Display for ServerTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// The target of an SRV record.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SrvRecord {
+    pub target: String,
+    pub port: u16,
+}
+
+pub type SrvFuture<'a> = Pin<Box<dyn Future<Output = io::Result<Option<SrvRecord>>> + Send + 'a>>;
+
+/// Something that can look up SRV records.
+///
+/// [`DnsResolver`] is used by default. Other implementations can be installed
+/// with [`NetworkResource::set_resolver`][crate::NetworkResource::set_resolver]
+/// (e.g., to stub out DNS in tests).
+pub trait Resolver: Send + Sync + 'static {
+    /// Looks up the SRV record for `name` (e.g., `_minecraft._tcp.example.com`),
+    /// returning `None` if there is no such record.
+    fn lookup_srv<'a>(&'a self, name: &'a str) -> SrvFuture<'a>;
+}
+
+/// Resolves a user-supplied server address to the host and port to connect to.
+///
+/// Fails if the address is malformed. A failed SRV lookup is not an error; the
+/// default port is used instead, like when there is no SRV record.
+pub async fn resolve_server_address(
+    server_addr: &str,
+    resolver: &dyn Resolver,
+) -> io::Result<ServerTarget> {
+    let (host, port) = split_host_and_port(server_addr)?;
+
+    if let Some(port) = port {
+        return Ok(ServerTarget {
+            host: host.to_string(),
+            port,
+            from_srv: false,
+        });
+    }
+
+    // IP addresses don't have SRV records.
+    if host.parse::<IpAddr>().is_err() {
+        let srv_name = format!("_minecraft._tcp.{}", host);
+
+        match resolver.lookup_srv(&srv_name).await {
+            Ok(Some(record)) => {
+                return Ok(ServerTarget {
+                    host: record.target,
+                    port: record.port,
+                    from_srv: true,
+                });
+            }
+            Ok(None) => log::debug!("No SRV record for {}", srv_name),
+            Err(e) => log::debug!("Failed to look up SRV record {}: {}", srv_name, e),
+        }
+    }
+
+    Ok(ServerTarget {
+        host: host.to_string(),
+        port: DEFAULT_PORT,
+        from_srv: false,
+    })
+}
+
+/// Splits `host:port`, `host`, `[ipv6]:port`, or `ipv6` into its parts.
+fn split_host_and_port(server_addr: &str) -> io::Result<(&str, Option<u16>)> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid server address {:?}", server_addr),
+        )
+    };
+
+    let server_addr = server_addr.trim();
+
+    if let Ok(socket_addr) = server_addr.parse::<SocketAddr>() {
+        let host = match socket_addr {
+            SocketAddr::V4(_) => server_addr.rsplit_once(':').unwrap().0,
+            SocketAddr::V6(_) => server_addr
+                .rsplit_once(':')
+                .unwrap()
+                .0
+                .trim_start_matches('[')
+                .trim_end_matches(']'),
+        };
+        return Ok((host, Some(socket_addr.port())));
+    }
+
+    // A bare IPv6 address has colons but no port.
+    if server_addr.parse::<IpAddr>().is_ok() {
+        return Ok((server_addr, None));
+    }
+
+    let (host, port) = match server_addr.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port.parse::<u16>().map_err(|_| invalid())?)),
+        None => (server_addr, None),
+    };
+
+    if host.is_empty() || host.contains(':') {
+        return Err(invalid());
+    }
+
+    Ok((host, port))
+}
+
+/// A [`Resolver`] that sends SRV queries over UDP to the first nameserver in
+/// `/etc/resolv.conf`.
+#[derive(Debug, Clone)]
+pub struct DnsResolver {
+    timeout: Duration,
+}
+
+impl DnsResolver {
+    const RESOLV_CONF: &'static str = "/etc/resolv.conf";
+
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(3),
+        }
+    }
+
+    fn nameserver() -> io::Result<SocketAddr> {
+        let resolv_conf = std::fs::read_to_string(Self::RESOLV_CONF)?;
+
+        resolv_conf
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("nameserver"))
+            .filter_map(|addr| addr.trim().parse::<IpAddr>().ok())
+            .map(|ip| SocketAddr::new(ip, 53))
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no nameserver in {}", Self::RESOLV_CONF),
+                )
+            })
+    }
+
+    async fn query(&self, name: &str) -> io::Result<Option<SrvRecord>> {
+        let nameserver = Self::nameserver()?;
+
+        let bind_addr: SocketAddr = match nameserver {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+        let socket = UdpSocket::bind(bind_addr).await?;
+        socket.connect(nameserver).await?;
+
+        let id = query_id();
+        socket.send(&dns::encode_srv_query(id, name)?).await?;
+
+        let mut response = [0; 1500];
+        let len = socket.recv(&mut response).await?;
+
+        dns::decode_srv_response(id, &response[..len])
+    }
+}
+
+impl Default for DnsResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver for DnsResolver {
+    fn lookup_srv<'a>(&'a self, name: &'a str) -> SrvFuture<'a> {
+        let timeout = async {
+            Timer::after(self.timeout).await;
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "timed out waiting for DNS response",
+            ))
+        };
+
+        Box::pin(self.query(name).or(timeout))
+    }
+}
+
+fn query_id() -> u16 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos() as u16)
+        .unwrap_or(0)
+}
+
+/// Just enough of the DNS wire format (RFC 1035) to look up SRV records (RFC
+/// 2782).
+mod dns {
+    use std::io;
+
+    use super::SrvRecord;
+
+    const TYPE_SRV: u16 = 33;
+    const CLASS_IN: u16 = 1;
+
+    const FLAG_RECURSION_DESIRED: u16 = 1 << 8;
+    const FLAG_TRUNCATED: u16 = 1 << 9;
+    const RCODE_MASK: u16 = 0xf;
+    const RCODE_NAME_ERROR: u16 = 3;
+
+    const HEADER_LEN: usize = 12;
+    const MAX_POINTERS: usize = 16;
+
+    fn malformed() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, "malformed DNS response")
+    }
+
+    pub fn encode_srv_query(id: u16, name: &str) -> io::Result<Vec<u8>> {
+        let mut query = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+
+        // Header: one question, no answers.
+        for field in [id, FLAG_RECURSION_DESIRED, 1, 0, 0, 0] {
+            query.extend_from_slice(&field.to_be_bytes());
+        }
+
+        for label in name.trim_end_matches('.').split('.') {
+            if label.is_empty() || label.len() > 63 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid DNS name {:?}", name),
+                ));
+            }
+            query.push(label.len() as u8);
+            query.extend_from_slice(label.as_bytes());
+        }
+        query.push(0);
+
+        query.extend_from_slice(&TYPE_SRV.to_be_bytes());
+        query.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+        Ok(query)
+    }
+
+    /// Returns the most preferred SRV record in the response (lowest priority,
+    /// then highest weight).
+    pub fn decode_srv_response(id: u16, response: &[u8]) -> io::Result<Option<SrvRecord>> {
+        if read_u16(response, 0)? != id {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "DNS response doesn't match query",
+            ));
+        }
+
+        let flags = read_u16(response, 2)?;
+        match flags & RCODE_MASK {
+            0 => {}
+            RCODE_NAME_ERROR => return Ok(None),
+            rcode => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("DNS query failed with response code {}", rcode),
+                ))
+            }
+        }
+        if flags & FLAG_TRUNCATED != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "DNS response was truncated",
+            ));
+        }
+
+        let question_count = read_u16(response, 4)?;
+        let answer_count = read_u16(response, 6)?;
+
+        let mut pos = HEADER_LEN;
+        for _ in 0..question_count {
+            pos = read_name(response, pos)?.1 + 4;
+        }
+
+        let mut best: Option<(u16, u16, SrvRecord)> = None;
+
+        for _ in 0..answer_count {
+            pos = read_name(response, pos)?.1;
+
+            let record_type = read_u16(response, pos)?;
+            let data_len = read_u16(response, pos + 8)? as usize;
+            let data = pos + 10;
+            pos = data + data_len;
+
+            if record_type != TYPE_SRV {
+                continue;
+            }
+
+            let priority = read_u16(response, data)?;
+            let weight = read_u16(response, data + 2)?;
+            let port = read_u16(response, data + 4)?;
+            let (target, _) = read_name(response, data + 6)?;
+
+            let is_better = best
+                .as_ref()
+                .map_or(true, |(best_priority, best_weight, _)| {
+                    (priority, u16::MAX - weight) < (*best_priority, u16::MAX - *best_weight)
+                });
+
+            if is_better {
+                best = Some((priority, weight, SrvRecord { target, port }));
+            }
+        }
+
+        // A target of "." means the service is explicitly not available.
+        Ok(best
+            .map(|(_, _, record)| record)
+            .filter(|record| !record.target.is_empty()))
+    }
+
+    fn read_u16(message: &[u8], pos: usize) -> io::Result<u16> {
+        message
+            .get(pos..pos + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(malformed)
+    }
+
+    /// Reads a possibly-compressed name, returning it and the position just
+    /// after it.
+    fn read_name(message: &[u8], mut pos: usize) -> io::Result<(String, usize)> {
+        let mut labels = Vec::new();
+        let mut end = None;
+        let mut pointers = 0;
+
+        loop {
+            let len = *message.get(pos).ok_or_else(malformed)? as usize;
+
+            if len & 0xc0 == 0xc0 {
+                pointers += 1;
+                if pointers > MAX_POINTERS {
+                    return Err(malformed());
+                }
+                end.get_or_insert(pos + 2);
+                pos = (read_u16(message, pos)? & 0x3fff) as usize;
+                continue;
+            }
+
+            pos += 1;
+            if len == 0 {
+                break;
+            }
+
+            let label = message.get(pos..pos + len).ok_or_else(malformed)?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            pos += len;
+        }
+
+        Ok((labels.join("."), end.unwrap_or(pos)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures_lite::future::block_on;
+
+    use super::*;
+
+    /// Resolver with canned answers that remembers what it was asked.
+    #[derive(Default)]
+    struct StubResolver {
+        record: Option<SrvRecord>,
+        lookups: Mutex<Vec<String>>,
+    }
+
+    impl Resolver for StubResolver {
+        fn lookup_srv<'a>(&'a self, name: &'a str) -> SrvFuture<'a> {
+            self.lookups.lock().unwrap().push(name.to_string());
+            let record = self.record.clone();
+            Box::pin(async move { Ok(record) })
+        }
+    }
+
+    fn with_srv(target: &str, port: u16) -> StubResolver {
+        StubResolver {
+            record: Some(SrvRecord {
+                target: target.to_string(),
+                port,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn resolve(server_addr: &str, resolver: &StubResolver) -> io::Result<ServerTarget> {
+        block_on(resolve_server_address(server_addr, resolver))
+    }
+
+    #[test]
+    fn srv_present() {
+        let resolver = with_srv("node1.example.com", 25600);
+
+        let target = resolve("example.com", &resolver).unwrap();
+
+        assert_eq!(
+            target,
+            ServerTarget {
+                host: "node1.example.com".to_string(),
+                port: 25600,
+                from_srv: true,
+            }
+        );
+        assert_eq!(
+            *resolver.lookups.lock().unwrap(),
+            vec!["_minecraft._tcp.example.com"]
+        );
+    }
+
+    #[test]
+    fn srv_absent() {
+        let resolver = StubResolver::default();
+
+        let target = resolve("example.com", &resolver).unwrap();
+
+        assert_eq!(target.host, "example.com");
+        assert_eq!(target.port, DEFAULT_PORT);
+        assert!(!target.from_srv);
+        assert_eq!(resolver.lookups.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn explicit_port_overrides_srv() {
+        let resolver = with_srv("node1.example.com", 25600);
+
+        let target = resolve("example.com:1234", &resolver).unwrap();
+
+        assert_eq!(target.to_string(), "example.com:1234");
+        assert!(resolver.lookups.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn ip_addresses_skip_srv() {
+        let resolver = with_srv("node1.example.com", 25600);
+
+        assert_eq!(
+            resolve("127.0.0.1", &resolver).unwrap().to_string(),
+            "127.0.0.1:25565"
+        );
+        assert_eq!(
+            resolve("::1", &resolver).unwrap().to_string(),
+            "[::1]:25565"
+        );
+        assert_eq!(
+            resolve("[::1]:80", &resolver).unwrap().to_string(),
+            "[::1]:80"
+        );
+        assert!(resolver.lookups.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_addresses() {
+        let resolver = StubResolver::default();
+
+        for server_addr in ["", ":25565", "example.com:", "example.com:port", "a:b:c"] {
+            let error = resolve(server_addr, &resolver).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                io::ErrorKind::InvalidInput,
+                "{:?}",
+                server_addr
+            );
+        }
+    }
+
+    /// Builds a response to `query` with the given (priority, weight, port,
+    /// target) SRV answers, using a compression pointer for each answer's
+    /// name.
+    fn srv_response(query: &[u8], answers: &[(u16, u16, u16, &str)]) -> Vec<u8> {
+        let mut response = query.to_vec();
+        response[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+        response[6..8].copy_from_slice(&(answers.len() as u16).to_be_bytes());
+
+        for (priority, weight, port, target) in answers {
+            let mut data = Vec::new();
+            for field in [*priority, *weight, *port] {
+                data.extend_from_slice(&field.to_be_bytes());
+            }
+            for label in target.split('.').filter(|label| !label.is_empty()) {
+                data.push(label.len() as u8);
+                data.extend_from_slice(label.as_bytes());
+            }
+            data.push(0);
+
+            // Pointer to the name in the question.
+            response.extend_from_slice(&0xc00cu16.to_be_bytes());
+            response.extend_from_slice(&33u16.to_be_bytes());
+            response.extend_from_slice(&1u16.to_be_bytes());
+            response.extend_from_slice(&300u32.to_be_bytes());
+            response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            response.extend_from_slice(&data);
+        }
+
+        response
+    }
+
+    #[test]
+    fn decode_dns_response() {
+        let query = dns::encode_srv_query(7, "_minecraft._tcp.example.com").unwrap();
+
+        let response = srv_response(
+            &query,
+            &[
+                (10, 5, 1000, "backup.example.com"),
+                (5, 1, 2000, "low-weight.example.com"),
+                (5, 9, 3000, "best.example.com"),
+            ],
+        );
+        assert_eq!(
+            dns::decode_srv_response(7, &response).unwrap(),
+            Some(SrvRecord {
+                target: "best.example.com".to_string(),
+                port: 3000
+            })
+        );
+
+        let empty = srv_response(&query, &[]);
+        assert_eq!(dns::decode_srv_response(7, &empty).unwrap(), None);
+
+        let unavailable = srv_response(&query, &[(0, 0, 0, ".")]);
+        assert_eq!(dns::decode_srv_response(7, &unavailable).unwrap(), None);
+
+        assert!(dns::decode_srv_response(8, &response).is_err());
+        assert!(dns::decode_srv_response(7, &response[..response.len() - 3]).is_err());
+    }
+}
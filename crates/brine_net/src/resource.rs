@@ -1,6 +1,12 @@
 //! Resources exposed by this crate.
 
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+};
 
 use async_channel::{unbounded, Receiver, Sender};
 use async_codec::{Decode, Encode};
@@ -9,8 +15,50 @@ use bevy::tasks::{Task, TaskPool};
 use crate::{
     connection::Connection,
     event::{NetworkError, NetworkEvent},
+    record::Recorder,
+    replay::Replay,
+    resolve::{DnsResolver, Resolver},
 };
 
+/// The state of a [`NetworkResource`]'s connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// There is no connection, either because none was ever established, the
+    /// last attempt to establish one failed, or the last one ended.
+    Disconnected,
+
+    /// A connection is being established.
+    Connecting,
+
+    /// A connection is established.
+    Connected,
+}
+
+/// A [`ConnectionStatus`] that is shared between the [`NetworkResource`] and
+/// its connection task.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedConnectionStatus(Arc<AtomicU8>);
+
+impl SharedConnectionStatus {
+    fn new() -> Self {
+        Self(Arc::new(AtomicU8::new(
+            ConnectionStatus::Disconnected as u8,
+        )))
+    }
+
+    pub(crate) fn get(&self) -> ConnectionStatus {
+        match self.0.load(Ordering::Acquire) {
+            status if status == ConnectionStatus::Connecting as u8 => ConnectionStatus::Connecting,
+            status if status == ConnectionStatus::Connected as u8 => ConnectionStatus::Connected,
+            _ => ConnectionStatus::Disconnected,
+        }
+    }
+
+    pub(crate) fn set(&self, status: ConnectionStatus) {
+        self.0.store(status as u8, Ordering::Release);
+    }
+}
+
 /// Resource that provides a TCP connection that encodes and decodes
 /// packets as specified by the given codec.
 pub struct NetworkResource<Codec: Decode + Encode>
@@ -22,6 +70,10 @@ where
     pub(crate) task_pool: TaskPool,
     pub(crate) connection_task: Option<Task<()>>,
 
+    /// Updated by [`connect`][Self::connect], [`disconnect`][Self::disconnect],
+    /// and the connection task.
+    pub(crate) status: SharedConnectionStatus,
+
     /// Used by background tasks to produce [`NetworkEvent`]s.
     pub(crate) network_event_sender: Sender<NetworkEvent<Codec>>,
 
@@ -43,6 +95,16 @@ where
     /// Used by the plugin to forward packets to the
     /// [`CodecReader`][crate::system_param::CodecReader].
     pub(crate) selfbound_packet_receiver: Receiver<<Codec as Decode>::Item>,
+
+    /// Used to look up SRV records for server addresses without a port.
+    pub(crate) resolver: Arc<dyn Resolver>,
+
+    /// Records the frames of new connections, if set.
+    pub(crate) recorder: Option<Recorder>,
+
+    /// Plays back recorded connections in place of real ones, if set by the
+    /// [`ReplayConnectionPlugin`][crate::replay::ReplayConnectionPlugin].
+    pub(crate) replay: Option<Replay>,
 }
 
 impl<Codec> NetworkResource<Codec>
@@ -62,12 +124,16 @@ where
             codec: Default::default(),
             task_pool,
             connection_task: None,
+            status: SharedConnectionStatus::new(),
             network_event_sender,
             network_event_receiver,
             peerbound_packet_sender,
             peerbound_packet_receiver,
             selfbound_packet_sender,
             selfbound_packet_receiver,
+            resolver: Arc::new(DnsResolver::new()),
+            recorder: None,
+            replay: None,
         }
     }
 
@@ -78,12 +144,38 @@ where
         &self.codec
     }
 
+    /// Returns the state of the connection.
+    ///
+    /// The status changes as soon as the connection task notices, which may be
+    /// a frame or so before the corresponding [`NetworkEvent`] is delivered.
+    pub fn status(&self) -> ConnectionStatus {
+        self.status.get()
+    }
+
+    /// Replaces the resolver used to look up SRV records.
+    pub fn set_resolver(&mut self, resolver: impl Resolver) {
+        self.resolver = Arc::new(resolver);
+    }
+
+    /// Records the frames of every connection established from now on (see
+    /// [`record`][crate::record]).
+    ///
+    /// A connection that is already established is not recorded.
+    pub fn start_recording(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Stops recording new connections.
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
     /// Establish a connection with a server that speaks this codec.
     ///
     /// The server address argument can be a `<hostname>:<port>` pair or an
-    /// `<ip_addr>:<port>` pair (or anything that can be successfully resolved
-    /// to one or more IP addresses with
-    /// [`ToSocketAddrs`][std::net::ToSocketAddrs]).
+    /// `<ip_addr>:<port>` pair. If the port is left out, it is looked up in the
+    /// host's `_minecraft._tcp` SRV record, falling back to 25565 (see
+    /// [`resolve`][crate::resolve]).
     ///
     /// If any error occurs in the process of establishing the connection or
     /// while the connection is active, it will be delivered as a
@@ -99,12 +191,118 @@ where
                 });
             });
         } else {
+            self.status.set(ConnectionStatus::Connecting);
             let connection = Connection::new(self);
 
             let codec = self.codec.clone();
-            self.connection_task = Some(self.task_pool.spawn(async move {
-                connection.connect_and_run(server_addr, codec).await;
-            }));
+            self.connection_task = Some(match &mut self.replay {
+                Some(replay) => {
+                    let session = replay.next_session();
+                    self.task_pool.spawn(async move {
+                        connection.replay_and_run(server_addr, session, codec).await;
+                    })
+                }
+                None => self.task_pool.spawn(async move {
+                    connection.connect_and_run(server_addr, codec).await;
+                }),
+            });
+        }
+    }
+    /// Closes the connection, or gives up on establishing one.
+    ///
+    /// A [`NetworkEvent::Disconnected`] is delivered as if the remote host had
+    /// closed the connection. Does nothing if there is no connection.
+    pub fn disconnect(&mut self) {
+        // Dropping the task cancels it, which closes the socket.
+        if self.connection_task.take().is_none() {
+            return;
+        }
+
+        if self.status.get() == ConnectionStatus::Connected {
+            if let Some(recorder) = &self.recorder {
+                recorder.record_disconnected();
+            }
+        }
+
+        self.status.set(ConnectionStatus::Disconnected);
+        self.network_event_sender
+            .try_send(NetworkEvent::Disconnected)
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io, net::TcpListener, thread};
+
+    use async_channel::bounded;
+    use futures_lite::future::block_on;
+
+    use crate::{
+        codec::BytesCodec,
+        resolve::{SrvFuture, SrvRecord},
+    };
+
+    use super::*;
+
+    /// Resolver that points every name at a local port, but only once it's
+    /// told to, so that the connection can be observed while it's connecting.
+    struct GatedResolver {
+        port: u16,
+        gate: Receiver<()>,
+    }
+
+    impl Resolver for GatedResolver {
+        fn lookup_srv<'a>(&'a self, _name: &'a str) -> SrvFuture<'a> {
+            Box::pin(async move {
+                self.gate.recv().await.unwrap();
+                Ok(Some(SrvRecord {
+                    target: String::from("127.0.0.1"),
+                    port: self.port,
+                }))
+            })
         }
     }
+
+    /// Starts a server that sends back everything it receives on the first
+    /// connection it accepts.
+    fn start_echo_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = stream.try_clone().unwrap();
+            let _ = io::copy(&mut reader, &mut stream);
+        });
+
+        port
+    }
+
+    #[test]
+    fn status_follows_connection() {
+        let port = start_echo_server();
+        let (open_gate, gate) = bounded(1);
+
+        let mut net_resource = NetworkResource::<BytesCodec>::new(TaskPool::new());
+        net_resource.set_resolver(GatedResolver { port, gate });
+        assert_eq!(net_resource.status(), ConnectionStatus::Disconnected);
+
+        net_resource.connect(String::from("echo.invalid"));
+        assert_eq!(net_resource.status(), ConnectionStatus::Connecting);
+
+        block_on(open_gate.send(())).unwrap();
+        let event = block_on(net_resource.network_event_receiver.recv()).unwrap();
+        assert!(matches!(event, NetworkEvent::Connected { .. }));
+        assert_eq!(net_resource.status(), ConnectionStatus::Connected);
+
+        block_on(net_resource.peerbound_packet_sender.send(vec![1, 2, 3])).unwrap();
+        let echoed = block_on(net_resource.selfbound_packet_receiver.recv()).unwrap();
+        assert_eq!(echoed, vec![1, 2, 3]);
+
+        net_resource.disconnect();
+        assert_eq!(net_resource.status(), ConnectionStatus::Disconnected);
+        let event = block_on(net_resource.network_event_receiver.recv()).unwrap();
+        assert!(matches!(event, NetworkEvent::Disconnected));
+    }
 }
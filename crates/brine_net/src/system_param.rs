@@ -36,6 +36,18 @@ impl<'w, 's, Packet: Resource, Codec: Resource> CodecReader<'w, 's, Packet, Code
     }
 }
 
+impl<'w, 's, Packet: Resource + Clone, Codec: Resource> CodecReader<'w, 's, Packet, Codec> {
+    /// Appends the packets this [`CodecReader`] has not seen yet to `out`, in
+    /// the order they were received.
+    ///
+    /// Like [`iter`][Self::iter], this leaves the reader empty until more
+    /// packets arrive (i.e., for the rest of this frame). Other readers still
+    /// see the packets, so they are cloned rather than moved out.
+    pub fn drain_into(&mut self, out: &mut Vec<Packet>) {
+        out.extend(self.iter().cloned());
+    }
+}
+
 /// Newtype around some packet type `T` from some codec `U`.
 ///
 /// Exists in tandem with [`Read<T, U>`] to ensure that there are two distinct
@@ -61,3 +73,48 @@ impl<'w, 's, Packet: Resource, Codec: Resource> CodecWriter<'w, 's, Packet, Code
         self.event_writer.send(Write(packet, PhantomData));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        app::App,
+        ecs::{event::Events, system::ResMut},
+    };
+
+    use crate::codec::StringCodec;
+
+    use super::*;
+
+    type StringReader<'w, 's> = CodecReader<'w, 's, String, StringCodec>;
+
+    #[derive(Default)]
+    struct Drained(Vec<String>);
+
+    fn drain(mut reader: StringReader, mut drained: ResMut<Drained>) {
+        reader.drain_into(&mut drained.0);
+
+        // Nothing is left for this frame.
+        assert_eq!(reader.iter().count(), 0);
+    }
+
+    #[test]
+    fn drain_into_keeps_packet_order() {
+        let mut app = App::new();
+        app.add_event::<Read<String, StringCodec>>()
+            .init_resource::<Drained>()
+            .add_system(drain);
+
+        let mut events = app
+            .world
+            .get_resource_mut::<Events<Read<String, StringCodec>>>()
+            .unwrap();
+        for packet in ["one", "two", "three"] {
+            events.send(Read(packet.to_string(), PhantomData));
+        }
+
+        app.update();
+
+        let drained = &app.world.get_resource::<Drained>().unwrap().0;
+        assert_eq!(drained, &["one", "two", "three"]);
+    }
+}
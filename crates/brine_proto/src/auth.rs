@@ -0,0 +1,73 @@
+//! Authenticating the player's account, for joining online-mode servers.
+//!
+//! Online-mode servers ask the client to prove that it owns the account it is
+//! logging in with by sending an encryption request during login. The protocol
+//! backend answers it using the [`Authenticator`] in the [`LoginAuthenticator`]
+//! resource, so that different ways of signing in (e.g., with a Microsoft
+//! account) can be plugged in without changing the login flow itself.
+//!
+//! By default, the client is in offline mode ([`NoAuth`]) and can only join
+//! servers that don't require authentication.
+
+use std::{fmt, sync::Arc};
+
+use uuid::Uuid;
+
+/// The profile of an authenticated account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameProfile {
+    pub uuid: Uuid,
+
+    /// The account's username, which the client has to log in with.
+    pub name: String,
+}
+
+/// Something that can sign the player in to their account.
+///
+/// Both methods return `None` if the player isn't signed in.
+pub trait Authenticator: Send + Sync + 'static {
+    /// Returns the access token used to tell the session server that the
+    /// player is joining a server.
+    fn access_token(&self) -> Option<String>;
+
+    /// Returns the profile of the account that the player is signed in to.
+    fn profile(&self) -> Option<GameProfile>;
+}
+
+/// Offline mode: the player is never signed in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NoAuth;
+
+impl Authenticator for NoAuth {
+    fn access_token(&self) -> Option<String> {
+        None
+    }
+
+    fn profile(&self) -> Option<GameProfile> {
+        None
+    }
+}
+
+/// Resource that holds the [`Authenticator`] consulted during login.
+///
+/// Defaults to [`NoAuth`].
+#[derive(Clone)]
+pub struct LoginAuthenticator(pub Arc<dyn Authenticator>);
+
+impl LoginAuthenticator {
+    pub fn new(authenticator: impl Authenticator) -> Self {
+        Self(Arc::new(authenticator))
+    }
+}
+
+impl Default for LoginAuthenticator {
+    fn default() -> Self {
+        Self::new(NoAuth)
+    }
+}
+
+impl fmt::Debug for LoginAuthenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LoginAuthenticator").finish()
+    }
+}
@@ -0,0 +1,669 @@
+//! Minecraft chat components.
+//!
+//! Disconnect reasons, server MOTDs, chat messages, and so on are sent by the
+//! server as JSON "chat components": trees of text with styling that children
+//! inherit from their parents. [`ChatComponent`] deserializes them, and
+//! [`ChatComponent::to_sections`] flattens them into runs of uniformly-styled
+//! text that can be rendered directly.
+//!
+//! Legacy formatting codes (`§` followed by a color or format character) that
+//! are embedded in text are also understood.
+//!
+//! See <https://wiki.vg/Chat> for reference.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+
+/// The character that starts a legacy formatting code.
+pub const LEGACY_CODE_PREFIX: char = '§';
+
+/// A node in a tree of chat components.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChatComponent {
+    pub content: ChatContent,
+
+    /// Styling of this component, which applies to its children too unless
+    /// they override it.
+    pub style: ChatStyle,
+
+    /// Components displayed after this one's content.
+    pub extra: Vec<ChatComponent>,
+
+    /// The component's `hoverEvent`, if it has one. Not interpreted.
+    pub hover_event: Option<serde_json::Value>,
+
+    /// The component's `clickEvent`, if it has one. Not interpreted.
+    pub click_event: Option<serde_json::Value>,
+}
+
+/// What a [`ChatComponent`] displays before its children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatContent {
+    Text(String),
+
+    /// A translation key, and the components to substitute into the
+    /// translation's `%s` placeholders.
+    Translate {
+        key: String,
+        with: Vec<ChatComponent>,
+    },
+
+    /// A key binding, such as `key.jump`. Displayed as the name of the binding.
+    Keybind(String),
+}
+
+impl Default for ChatContent {
+    fn default() -> Self {
+        ChatContent::Text(String::new())
+    }
+}
+
+/// Styling that a [`ChatComponent`] may specify. `None` means inherit from
+/// the parent component.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChatStyle {
+    pub color: Option<ChatColor>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+}
+
+impl ChatStyle {
+    fn resolve(&self, parent: &SectionStyle) -> SectionStyle {
+        SectionStyle {
+            color: match self.color {
+                Some(ChatColor::Reset) => None,
+                Some(color) => Some(color),
+                None => parent.color,
+            },
+            flags: TextFlags {
+                bold: self.bold.unwrap_or(parent.flags.bold),
+                italic: self.italic.unwrap_or(parent.flags.italic),
+                underlined: self.underlined.unwrap_or(parent.flags.underlined),
+                strikethrough: self.strikethrough.unwrap_or(parent.flags.strikethrough),
+                obfuscated: self.obfuscated.unwrap_or(parent.flags.obfuscated),
+            },
+        }
+    }
+}
+
+/// A text color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChatColor {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+
+    /// The default color.
+    Reset,
+
+    /// An arbitrary color given as `#rrggbb`.
+    Rgb(u8, u8, u8),
+}
+
+impl ChatColor {
+    const NAMED: [(&'static str, ChatColor, [u8; 3]); 16] = [
+        ("black", ChatColor::Black, [0x00, 0x00, 0x00]),
+        ("dark_blue", ChatColor::DarkBlue, [0x00, 0x00, 0xaa]),
+        ("dark_green", ChatColor::DarkGreen, [0x00, 0xaa, 0x00]),
+        ("dark_aqua", ChatColor::DarkAqua, [0x00, 0xaa, 0xaa]),
+        ("dark_red", ChatColor::DarkRed, [0xaa, 0x00, 0x00]),
+        ("dark_purple", ChatColor::DarkPurple, [0xaa, 0x00, 0xaa]),
+        ("gold", ChatColor::Gold, [0xff, 0xaa, 0x00]),
+        ("gray", ChatColor::Gray, [0xaa, 0xaa, 0xaa]),
+        ("dark_gray", ChatColor::DarkGray, [0x55, 0x55, 0x55]),
+        ("blue", ChatColor::Blue, [0x55, 0x55, 0xff]),
+        ("green", ChatColor::Green, [0x55, 0xff, 0x55]),
+        ("aqua", ChatColor::Aqua, [0x55, 0xff, 0xff]),
+        ("red", ChatColor::Red, [0xff, 0x55, 0x55]),
+        ("light_purple", ChatColor::LightPurple, [0xff, 0x55, 0xff]),
+        ("yellow", ChatColor::Yellow, [0xff, 0xff, 0x55]),
+        ("white", ChatColor::White, [0xff, 0xff, 0xff]),
+    ];
+
+    /// Parses a color name like `dark_red`, `reset`, or `#ff8800`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name == "reset" {
+            return Some(ChatColor::Reset);
+        }
+
+        if let Some(hex) = name.strip_prefix('#') {
+            if hex.len() != 6 {
+                return None;
+            }
+            let rgb = u32::from_str_radix(hex, 16).ok()?;
+            return Some(ChatColor::Rgb(
+                (rgb >> 16) as u8,
+                (rgb >> 8) as u8,
+                rgb as u8,
+            ));
+        }
+
+        Self::NAMED
+            .iter()
+            .find(|(color_name, _, _)| *color_name == name)
+            .map(|(_, color, _)| *color)
+    }
+
+    /// Returns the color for a legacy color code character (`0`-`9`, `a`-`f`).
+    pub fn from_legacy_code(code: char) -> Option<Self> {
+        let index = code.to_digit(16)?;
+        Some(Self::NAMED[index as usize].1)
+    }
+
+    /// Returns the color's red, green, and blue components, or `None` for
+    /// [`ChatColor::Reset`].
+    pub fn rgb(self) -> Option<[u8; 3]> {
+        match self {
+            ChatColor::Rgb(r, g, b) => Some([r, g, b]),
+            ChatColor::Reset => None,
+            named => Self::NAMED
+                .iter()
+                .find(|(_, color, _)| *color == named)
+                .map(|(_, _, rgb)| *rgb),
+        }
+    }
+}
+
+/// Formatting that applies to a [`StyledSection`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextFlags {
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+    pub obfuscated: bool,
+}
+
+/// A run of text that is displayed with the same style throughout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSection {
+    pub text: String,
+
+    /// The color of the text, or `None` for the default color.
+    pub color: Option<ChatColor>,
+
+    pub flags: TextFlags,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct SectionStyle {
+    color: Option<ChatColor>,
+    flags: TextFlags,
+}
+
+impl ChatComponent {
+    /// Creates a component that displays `text` with no styling.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: ChatContent::Text(text.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Returns the text of this component and its children without any
+    /// styling.
+    pub fn to_plain_text(&self) -> String {
+        self.to_sections()
+            .into_iter()
+            .map(|section| section.text)
+            .collect()
+    }
+
+    /// Flattens this component and its children into runs of text with the
+    /// same style. Adjacent runs with the same style are merged.
+    pub fn to_sections(&self) -> Vec<StyledSection> {
+        let mut sections = Vec::new();
+        self.push_sections(&SectionStyle::default(), &mut sections);
+        sections
+    }
+
+    fn push_sections(&self, parent_style: &SectionStyle, sections: &mut Vec<StyledSection>) {
+        let style = self.style.resolve(parent_style);
+
+        match &self.content {
+            ChatContent::Text(text) => push_text(text, &style, sections),
+            ChatContent::Keybind(key) => push_text(key, &style, sections),
+            ChatContent::Translate { key, with } => {
+                let format = translation(key).unwrap_or(key);
+                let mut next_arg = 0;
+
+                for piece in parse_format(format) {
+                    match piece {
+                        FormatPiece::Literal(text) => push_text(text, &style, sections),
+                        FormatPiece::Arg(index) => {
+                            let index = index.unwrap_or_else(|| {
+                                next_arg += 1;
+                                next_arg - 1
+                            });
+                            match with.get(index) {
+                                Some(arg) => arg.push_sections(&style, sections),
+                                None => push_text("%s", &style, sections),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for child in self.extra.iter() {
+            child.push_sections(&style, sections);
+        }
+    }
+}
+
+impl fmt::Display for ChatComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_plain_text())
+    }
+}
+
+/// Appends `text` in the given style, applying any legacy formatting codes in
+/// it. A reset code (`§r`) goes back to `style`.
+fn push_text(text: &str, style: &SectionStyle, sections: &mut Vec<StyledSection>) {
+    let mut current = *style;
+    let mut chars = text.char_indices().peekable();
+    let mut start = 0;
+
+    while let Some((index, c)) = chars.next() {
+        if c != LEGACY_CODE_PREFIX {
+            continue;
+        }
+
+        push_section(&text[start..index], &current, sections);
+
+        let code = chars.next().map(|(_, code)| code.to_ascii_lowercase());
+        start = chars.peek().map_or(text.len(), |(index, _)| *index);
+
+        match code {
+            // Like in vanilla, a color code also clears any formatting.
+            Some(code) if ChatColor::from_legacy_code(code).is_some() => {
+                current = SectionStyle {
+                    color: ChatColor::from_legacy_code(code),
+                    flags: TextFlags::default(),
+                };
+            }
+            Some('k') => current.flags.obfuscated = true,
+            Some('l') => current.flags.bold = true,
+            Some('m') => current.flags.strikethrough = true,
+            Some('n') => current.flags.underlined = true,
+            Some('o') => current.flags.italic = true,
+            Some('r') => current = *style,
+            _ => {}
+        }
+    }
+
+    push_section(&text[start..], &current, sections);
+}
+
+fn push_section(text: &str, style: &SectionStyle, sections: &mut Vec<StyledSection>) {
+    if text.is_empty() {
+        return;
+    }
+
+    match sections.last_mut() {
+        Some(last) if last.color == style.color && last.flags == style.flags => {
+            last.text.push_str(text);
+        }
+        _ => sections.push(StyledSection {
+            text: text.to_string(),
+            color: style.color,
+            flags: style.flags,
+        }),
+    }
+}
+
+enum FormatPiece<'a> {
+    Literal(&'a str),
+
+    /// `%s` (`None`) or `%1$s` (`Some(0)`).
+    Arg(Option<usize>),
+}
+
+/// Splits a translation's format string into literals and placeholders.
+fn parse_format(format: &str) -> Vec<FormatPiece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = format;
+
+    while let Some(percent) = rest.find('%') {
+        if percent > 0 {
+            pieces.push(FormatPiece::Literal(&rest[..percent]));
+        }
+        let after = &rest[percent + 1..];
+
+        if let Some(after_s) = after.strip_prefix('s') {
+            pieces.push(FormatPiece::Arg(None));
+            rest = after_s;
+        } else if let Some(after_percent) = after.strip_prefix('%') {
+            pieces.push(FormatPiece::Literal("%"));
+            rest = after_percent;
+        } else if let Some((position, after_arg)) = after.split_once("$s") {
+            match position.parse::<usize>() {
+                Ok(position) if position > 0 => {
+                    pieces.push(FormatPiece::Arg(Some(position - 1)));
+                    rest = after_arg;
+                }
+                _ => {
+                    pieces.push(FormatPiece::Literal("%"));
+                    rest = after;
+                }
+            }
+        } else {
+            pieces.push(FormatPiece::Literal("%"));
+            rest = after;
+        }
+    }
+
+    if !rest.is_empty() {
+        pieces.push(FormatPiece::Literal(rest));
+    }
+
+    pieces
+}
+
+/// Returns the English translation of a few commonly-sent translation keys.
+///
+/// Keys that aren't known are displayed as-is, which is also what the vanilla
+/// client does with keys that are missing from its language file.
+pub fn translation(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "chat.type.text" => "<%s> %s",
+        "chat.type.emote" => "* %s %s",
+        "chat.type.announcement" => "[%s] %s",
+        "chat.type.admin" => "[%s: %s]",
+        "commands.message.display.incoming" => "%s whispers to you: %s",
+        "commands.message.display.outgoing" => "You whisper to %s: %s",
+        "multiplayer.player.joined" => "%s joined the game",
+        "multiplayer.player.left" => "%s left the game",
+        "multiplayer.disconnect.kicked" => "Kicked by an operator",
+        "multiplayer.disconnect.server_shutdown" => "Server closed",
+        "multiplayer.disconnect.not_whitelisted" => "You are not white-listed on this server!",
+        "multiplayer.disconnect.outdated_client" => "Outdated client! Please use %s",
+        "multiplayer.disconnect.outdated_server" => "Outdated server! I'm still on %s",
+        "disconnect.timeout" => "Timed out",
+        "disconnect.lost" => "Connection Lost",
+        "death.attack.generic" => "%1$s died",
+        "death.attack.player" => "%1$s was slain by %2$s",
+        "death.attack.mob" => "%1$s was slain by %2$s",
+        "death.attack.arrow" => "%1$s was shot by %2$s",
+        "death.attack.explosion.player" => "%1$s was blown up by %2$s",
+        "death.attack.fall" => "%1$s hit the ground too hard",
+        "death.attack.lava" => "%1$s tried to swim in lava",
+        "death.attack.drown" => "%1$s drowned",
+        "death.attack.inFire" => "%1$s went up in flames",
+        "death.attack.onFire" => "%1$s burned to death",
+        "death.attack.outOfWorld" => "%1$s fell out of the world",
+        "death.attack.starve" => "%1$s starved to death",
+        "death.fell.accident.generic" => "%1$s fell from a high place",
+        _ => return None,
+    })
+}
+
+/// Any of the JSON shapes a chat component can take.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawComponent {
+    Text(String),
+    Number(serde_json::Number),
+    Bool(bool),
+    List(Vec<ChatComponent>),
+    Object(Box<RawObject>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawObject {
+    text: Option<String>,
+    translate: Option<String>,
+    #[serde(default)]
+    with: Vec<ChatComponent>,
+    keybind: Option<String>,
+
+    color: Option<String>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underlined: Option<bool>,
+    strikethrough: Option<bool>,
+    obfuscated: Option<bool>,
+
+    #[serde(default)]
+    extra: Vec<ChatComponent>,
+
+    hover_event: Option<serde_json::Value>,
+    click_event: Option<serde_json::Value>,
+}
+
+impl<'de> Deserialize<'de> for ChatComponent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match RawComponent::deserialize(deserializer)? {
+            RawComponent::Text(text) => ChatComponent::text(text),
+            RawComponent::Number(number) => ChatComponent::text(number.to_string()),
+            RawComponent::Bool(value) => ChatComponent::text(value.to_string()),
+
+            // A list is the first component with the rest as its children.
+            RawComponent::List(components) => {
+                let mut components = components.into_iter();
+                let mut first = components.next().unwrap_or_default();
+                first.extra.extend(components);
+                first
+            }
+
+            RawComponent::Object(object) => {
+                let object = *object;
+
+                let content = if let Some(key) = object.translate {
+                    ChatContent::Translate {
+                        key,
+                        with: object.with,
+                    }
+                } else if let Some(key) = object.keybind {
+                    ChatContent::Keybind(key)
+                } else {
+                    ChatContent::Text(object.text.unwrap_or_default())
+                };
+
+                ChatComponent {
+                    content,
+                    style: ChatStyle {
+                        color: object.color.as_deref().and_then(ChatColor::from_name),
+                        bold: object.bold,
+                        italic: object.italic,
+                        underlined: object.underlined,
+                        strikethrough: object.strikethrough,
+                        obfuscated: object.obfuscated,
+                    },
+                    extra: object.extra,
+                    hover_event: object.hover_event,
+                    click_event: object.click_event,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn section(text: &str, color: Option<ChatColor>, flags: TextFlags) -> StyledSection {
+        StyledSection {
+            text: text.to_string(),
+            color,
+            flags,
+        }
+    }
+
+    const BOLD: TextFlags = TextFlags {
+        bold: true,
+        italic: false,
+        underlined: false,
+        strikethrough: false,
+        obfuscated: false,
+    };
+
+    const NONE: TextFlags = TextFlags {
+        bold: false,
+        italic: false,
+        underlined: false,
+        strikethrough: false,
+        obfuscated: false,
+    };
+
+    #[test]
+    fn translated_death_message() {
+        // As sent by a 1.14.4 server when one player kills another.
+        let json = r#"{
+            "translate": "death.attack.player",
+            "with": [
+                {
+                    "insertion": "Steve",
+                    "clickEvent": { "action": "suggest_command", "value": "/tell Steve " },
+                    "hoverEvent": {
+                        "action": "show_entity",
+                        "value": { "text": "{name:\"{\\\"text\\\":\\\"Steve\\\"}\",id:\"c359bd18-3a2c-4b8c-9ac0-657d3c1c0bc0\"}" }
+                    },
+                    "text": "Steve"
+                },
+                {
+                    "insertion": "Alex",
+                    "text": "Alex",
+                    "color": "red"
+                }
+            ]
+        }"#;
+
+        let component = ChatComponent::from_json(json).unwrap();
+
+        assert_eq!(component.to_plain_text(), "Steve was slain by Alex");
+        assert_eq!(
+            component.to_sections(),
+            vec![
+                section("Steve was slain by ", None, NONE),
+                section("Alex", Some(ChatColor::Red), NONE),
+            ]
+        );
+    }
+
+    #[test]
+    fn hover_event_is_parsed_but_ignored() {
+        let json = r#"{
+            "text": "Click here",
+            "color": "gold",
+            "bold": true,
+            "hoverEvent": {
+                "action": "show_text",
+                "value": { "text": "Opens the rules", "color": "gray" }
+            },
+            "clickEvent": { "action": "open_url", "value": "https://example.com/rules" },
+            "extra": [
+                { "text": " to read the " },
+                { "text": "rules", "bold": false, "underlined": true }
+            ]
+        }"#;
+
+        let component = ChatComponent::from_json(json).unwrap();
+
+        assert_eq!(
+            component.hover_event.as_ref().unwrap()["action"],
+            "show_text"
+        );
+        assert_eq!(component.to_plain_text(), "Click here to read the rules");
+        assert_eq!(
+            component.to_sections(),
+            vec![
+                section("Click here to read the ", Some(ChatColor::Gold), BOLD),
+                section(
+                    "rules",
+                    Some(ChatColor::Gold),
+                    TextFlags {
+                        underlined: true,
+                        ..NONE
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_coded_motd() {
+        // The description from a server list ping response.
+        let json = r#"{"text":"§6§lBrine Test Server§r §7- §aNow on 1.14.4\n§cNo griefing!"}"#;
+
+        let component = ChatComponent::from_json(json).unwrap();
+
+        assert_eq!(
+            component.to_plain_text(),
+            "Brine Test Server - Now on 1.14.4\nNo griefing!"
+        );
+        assert_eq!(
+            component.to_sections(),
+            vec![
+                section("Brine Test Server", Some(ChatColor::Gold), BOLD),
+                section(" ", None, NONE),
+                section("- ", Some(ChatColor::Gray), NONE),
+                section("Now on 1.14.4\n", Some(ChatColor::Green), NONE),
+                section("No griefing!", Some(ChatColor::Red), NONE),
+            ]
+        );
+    }
+
+    #[test]
+    fn shapes_of_components() {
+        assert_eq!(
+            ChatComponent::from_json(r#""plain""#).unwrap(),
+            ChatComponent::text("plain")
+        );
+        assert_eq!(
+            ChatComponent::from_json(r#"["a", {"text": "b"}, "c"]"#)
+                .unwrap()
+                .to_plain_text(),
+            "abc"
+        );
+        assert_eq!(
+            ChatComponent::from_json(r#"{"translate": "no.such.key"}"#)
+                .unwrap()
+                .to_plain_text(),
+            "no.such.key"
+        );
+        assert_eq!(
+            ChatComponent::from_json(r#"{"translate": "chat.type.text", "with": ["Steve", 42]}"#)
+                .unwrap()
+                .to_plain_text(),
+            "<Steve> 42"
+        );
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(ChatColor::from_name("dark_aqua"), Some(ChatColor::DarkAqua));
+        assert_eq!(
+            ChatColor::from_name("#ff8800"),
+            Some(ChatColor::Rgb(0xff, 0x88, 0x00))
+        );
+        assert_eq!(ChatColor::from_name("#ff88"), None);
+        assert_eq!(ChatColor::from_name("mauve"), None);
+        assert_eq!(ChatColor::from_legacy_code('e'), Some(ChatColor::Yellow));
+        assert_eq!(ChatColor::Gold.rgb(), Some([0xff, 0xaa, 0x00]));
+        assert_eq!(ChatColor::Reset.rgb(), None);
+    }
+}
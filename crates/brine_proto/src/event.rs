@@ -10,6 +10,20 @@
 
 pub use uuid::Uuid;
 
+/// A stack of items in an inventory slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemStack {
+    /// Numeric id of the item in the server's protocol version.
+    pub item_id: i32,
+
+    /// Number of items in the stack.
+    pub count: u8,
+
+    /// The stack's NBT data (enchantments, custom name, etc.), still encoded
+    /// as a named root compound tag.
+    pub nbt: Option<Vec<u8>>,
+}
+
 pub mod serverbound {
     //! Definitions for all serverbound events.
 
@@ -33,8 +47,178 @@ pub mod serverbound {
         pub username: String,
     }
 
+    /// Selects one of the hotbar slots as the held item.
+    ///
+    /// # See also
+    ///
+    /// * [`clientbound::HeldItemChanged`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct HeldItemChange {
+        /// Index of the hotbar slot (0-8).
+        pub slot: u8,
+    }
+
+    /// Tells the server where the player is and which way they're looking.
+    ///
+    /// The client is responsible for moving the player (applying gravity,
+    /// resolving collisions, etc.), and should send this whenever the player
+    /// moves, and at least once a second otherwise.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PlayerPosition {
+        /// Position of the player's feet, in blocks.
+        pub position: bevy::math::Vec3,
+
+        /// Rotation around the Y axis, in degrees.
+        pub yaw: f32,
+
+        /// Rotation around the X axis, in degrees.
+        pub pitch: f32,
+
+        /// Whether the player is standing on a block.
+        pub on_ground: bool,
+    }
+
+    /// Which of the player's hands is used.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Hand {
+        Main,
+        Off,
+    }
+
+    impl Hand {
+        /// Returns the numeric id of the hand (0 for the main hand, 1 for the
+        /// off hand).
+        pub fn id(self) -> i32 {
+            match self {
+                Self::Main => 0,
+                Self::Off => 1,
+            }
+        }
+    }
+
+    /// A face of a block.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum BlockFace {
+        /// -Y
+        Bottom,
+        /// +Y
+        Top,
+        /// -Z
+        North,
+        /// +Z
+        South,
+        /// -X
+        West,
+        /// +X
+        East,
+    }
+
+    impl BlockFace {
+        /// Returns the numeric id of the face, in the order above.
+        pub fn id(self) -> i32 {
+            match self {
+                Self::Bottom => 0,
+                Self::Top => 1,
+                Self::North => 2,
+                Self::South => 3,
+                Self::West => 4,
+                Self::East => 5,
+            }
+        }
+    }
+
+    /// Uses the item in one of the player's hands on a block, e.g., to place a
+    /// block against it.
+    ///
+    /// The server answers with [`clientbound::BlockChanged`] events for the
+    /// blocks that changed (or that the client might think changed), so the
+    /// client can predict the outcome and correct itself afterwards.
+    ///
+    /// # See also
+    ///
+    /// * [`SwingArm`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct UseItemOn {
+        /// World position of the block that the item is used on.
+        pub block_pos: bevy::math::IVec3,
+
+        /// The face of the block that the player is looking at.
+        pub face: BlockFace,
+
+        /// Where on the block the player is looking, from 0 to 1 on each axis.
+        pub cursor_pos: bevy::math::Vec3,
+
+        pub hand: Hand,
+    }
+
+    /// Plays the animation of the player swinging one of their arms.
+    ///
+    /// Vanilla clients send this whenever the player attacks, uses an item, or
+    /// breaks or places a block.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SwingArm {
+        pub hand: Hand,
+    }
+
+    /// What a [`PlayerDigging`] event tells the server.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum DiggingStatus {
+        Started,
+        Cancelled,
+        Finished,
+    }
+
+    impl DiggingStatus {
+        /// Returns the numeric id of the status.
+        pub fn id(self) -> i32 {
+            match self {
+                Self::Started => 0,
+                Self::Cancelled => 1,
+                Self::Finished => 2,
+            }
+        }
+
+        /// Returns the status with the given numeric id, or `None` if there is
+        /// no such status.
+        pub fn from_id(id: i32) -> Option<Self> {
+            match id {
+                0 => Some(Self::Started),
+                1 => Some(Self::Cancelled),
+                2 => Some(Self::Finished),
+                _ => None,
+            }
+        }
+    }
+
+    /// Tells the server that the player started, stopped, or finished digging
+    /// a block.
+    ///
+    /// The client decides how long digging takes, and sends
+    /// [`DiggingStatus::Finished`] once the block should break. Blocks that
+    /// break instantly (e.g., in creative mode) only need
+    /// [`DiggingStatus::Started`].
+    ///
+    /// # See also
+    ///
+    /// * [`clientbound::DiggingAcknowledged`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PlayerDigging {
+        pub status: DiggingStatus,
+
+        /// World position of the block being dug.
+        pub block_pos: bevy::math::IVec3,
+
+        /// The face of the block that the player is looking at.
+        pub face: BlockFace,
+    }
+
     pub(crate) fn add_events(app: &mut bevy::app::App) {
         app.add_event::<Login>();
+        app.add_event::<HeldItemChange>();
+        app.add_event::<PlayerPosition>();
+        app.add_event::<UseItemOn>();
+        app.add_event::<SwingArm>();
+        app.add_event::<PlayerDigging>();
     }
 }
 
@@ -56,6 +240,10 @@ pub mod clientbound {
 
         /// Username that was used to join the game.
         pub username: String,
+
+        /// Protocol version number that the server speaks (e.g., 498 for
+        /// Minecraft 1.14.4).
+        pub protocol_version: i32,
     }
 
     /// Notifies the client they have been disconnected from the server.
@@ -72,15 +260,628 @@ pub mod clientbound {
         pub reason: String,
     }
 
+    /// A dimension of the Minecraft world.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Dimension {
+        Overworld,
+        Nether,
+        End,
+    }
+
+    impl Dimension {
+        /// Returns the dimension with the given numeric id (-1 for the Nether,
+        /// 0 for the Overworld, and 1 for the End), or `None` if there is no
+        /// such dimension.
+        pub fn from_id(id: i32) -> Option<Self> {
+            match id {
+                -1 => Some(Self::Nether),
+                0 => Some(Self::Overworld),
+                1 => Some(Self::End),
+                _ => None,
+            }
+        }
+    }
+
+    /// The game mode of a player.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum GameMode {
+        Survival,
+        Creative,
+        Adventure,
+        Spectator,
+    }
+
+    impl GameMode {
+        /// Returns the game mode with the given numeric id, or `None` if there
+        /// is no such game mode.
+        ///
+        /// The hardcore flag (`0x8`) is ignored.
+        pub fn from_id(id: u8) -> Option<Self> {
+            match id & !0x8 {
+                0 => Some(Self::Survival),
+                1 => Some(Self::Creative),
+                2 => Some(Self::Adventure),
+                3 => Some(Self::Spectator),
+                _ => None,
+            }
+        }
+    }
+
+    /// Notifies the client that the player has joined the game world.
+    ///
+    /// This arrives after [`LoginSuccess`], before any [`ChunkData`].
+    ///
+    /// # See also
+    ///
+    /// * [`Respawn`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct JoinGame {
+        /// Id of the player's own entity, which other events may refer to.
+        pub entity_id: i32,
+
+        pub gamemode: GameMode,
+
+        /// The dimension that the player spawned in.
+        pub dimension: Dimension,
+
+        /// Maximum number of players, as shown in the player list. Ignored by
+        /// vanilla clients.
+        pub max_players: u8,
+
+        /// Type of world generation used by the world (e.g., `default`,
+        /// `flat`, or `amplified`), which affects how the sky is drawn.
+        pub level_type: String,
+    }
+
+    /// Notifies the client that the player has respawned, possibly in a
+    /// different dimension.
+    ///
+    /// If the dimension changed, all of the chunks that the client has loaded
+    /// belong to the old dimension and should be unloaded. The server sends
+    /// the chunks of the new dimension afterwards.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Respawn {
+        /// The dimension that the player respawned in.
+        pub dimension: Dimension,
+
+        pub gamemode: GameMode,
+    }
+
+    /// Tells the client how old the world is and what time of day it is.
+    ///
+    /// The server sends this about once a second. In between, the client
+    /// should advance the time by itself, at 20 ticks per second.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TimeUpdate {
+        /// Age of the world, in ticks. Not affected by changing the time.
+        pub world_age: i64,
+
+        /// Time of day, in ticks. A day is 24000 ticks long, starting at
+        /// sunrise, with noon at 6000.
+        ///
+        /// If this is negative, the time of day is frozen (i.e., the
+        /// `doDaylightCycle` game rule is off) at its absolute value.
+        pub time_of_day: i64,
+    }
+
     /// Contains data relating to a 16x256x16 chunk of the Minecraft world.
     #[derive(Debug, Clone, PartialEq)]
     pub struct ChunkData {
         pub chunk_data: brine_chunk::Chunk,
     }
 
+    /// Notifies the client that the light in a chunk it already received has
+    /// changed.
+    ///
+    /// Light that arrives before its chunk is already part of the
+    /// [`ChunkData`] event.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct LightUpdated {
+        /// The new light, as sections that hold only light data. Sections
+        /// without sky or block light leave that light unchanged.
+        ///
+        /// Apply it with [`brine_chunk::Chunk::update_light`].
+        pub light: brine_chunk::Chunk,
+    }
+
+    /// Notifies the client that a single block in a loaded chunk changed.
+    ///
+    /// # See also
+    ///
+    /// * [`serverbound::UseItemOn`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct BlockChanged {
+        /// World position of the block.
+        pub block_pos: bevy::math::IVec3,
+
+        /// The block's new state.
+        pub block_state: brine_chunk::BlockState,
+    }
+
+    /// Tells the client whether a [`serverbound::PlayerDigging`] event was
+    /// accepted, and what the block is now.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DiggingAcknowledged {
+        /// World position of the block.
+        pub block_pos: bevy::math::IVec3,
+
+        /// The block's state after digging, e.g., air if it was broken.
+        pub block_state: brine_chunk::BlockState,
+
+        /// The status of the digging event that this acknowledges.
+        pub status: serverbound::DiggingStatus,
+
+        pub successful: bool,
+    }
+
+    /// The kind of an entity spawned by an [`EntitySpawned`] event.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EntityKind {
+        /// Another player.
+        Player,
+
+        /// A living entity, such as a zombie or a cow.
+        ///
+        /// `type_id` is the entity's numeric type in the server's protocol
+        /// version (see <https://wiki.vg/Entity_metadata#Mobs>).
+        Mob { type_id: i32 },
+
+        /// A non-living entity, such as a dropped item, an arrow, or a
+        /// minecart.
+        ///
+        /// `type_id` is the entity's numeric type in the server's protocol
+        /// version, and `data` is extra information whose meaning depends on
+        /// the type, e.g., the id of the entity that shot an arrow (see
+        /// <https://wiki.vg/Object_Data>).
+        Object { type_id: i32, data: i32 },
+    }
+
+    /// Notifies the client that an entity has come into view.
+    ///
+    /// # See also
+    ///
+    /// * [`EntityMoved`]
+    /// * [`EntityVelocity`]
+    /// * [`EntitiesDestroyed`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EntitySpawned {
+        /// Server-assigned id used to refer to the entity in later events.
+        pub entity_id: i32,
+
+        pub kind: EntityKind,
+
+        pub uuid: uuid::Uuid,
+
+        /// Position of the entity's feet, in blocks.
+        pub position: bevy::math::Vec3,
+
+        /// Rotation around the Y axis, in degrees.
+        pub yaw: f32,
+
+        /// Rotation around the X axis, in degrees.
+        pub pitch: f32,
+
+        /// Initial velocity, in blocks per tick.
+        pub velocity: bevy::math::Vec3,
+    }
+
+    /// How an entity moved, as part of an [`EntityMoved`] event.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum EntityMovement {
+        /// The entity moved by this many blocks.
+        Relative(bevy::math::Vec3),
+
+        /// The entity moved to this position, in blocks.
+        Absolute(bevy::math::Vec3),
+    }
+
+    /// Notifies the client that an entity has moved.
+    ///
+    /// This may arrive for entities that the client has not seen an
+    /// [`EntitySpawned`] event for (e.g., if they spawned before the client
+    /// started tracking them), so consumers should not assume that the entity
+    /// exists.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EntityMoved {
+        pub entity_id: i32,
+
+        pub movement: EntityMovement,
+
+        /// New yaw and pitch of the entity, in degrees, if they changed.
+        pub rotation: Option<(f32, f32)>,
+
+        pub on_ground: bool,
+    }
+
+    /// Notifies the client that an entity's velocity has changed.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EntityVelocity {
+        pub entity_id: i32,
+
+        /// New velocity, in blocks per tick.
+        pub velocity: bevy::math::Vec3,
+    }
+
+    /// Notifies the client that entities have gone out of view or been removed
+    /// from the world.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EntitiesDestroyed {
+        pub entity_ids: Vec<i32>,
+    }
+
+    /// Replaces the contents of every slot in a window.
+    ///
+    /// # See also
+    ///
+    /// * [`SlotChanged`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct InventoryChanged {
+        /// The window whose slots changed. Window 0 is the player's inventory.
+        pub window_id: u8,
+
+        /// The contents of each slot, or `None` if the slot is empty.
+        pub slots: Vec<Option<super::ItemStack>>,
+    }
+
+    /// Replaces the contents of a single slot in a window.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SlotChanged {
+        /// The window whose slot changed. Window 0 is the player's inventory,
+        /// and window -1 is the item being dragged by the cursor.
+        pub window_id: i8,
+
+        pub slot: i16,
+
+        /// The new contents of the slot, or `None` if it's now empty.
+        pub item: Option<super::ItemStack>,
+    }
+
+    /// Notifies the client that the player's selected hotbar slot changed.
+    ///
+    /// # See also
+    ///
+    /// * [`serverbound::HeldItemChange`]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct HeldItemChanged {
+        /// Index of the hotbar slot (0-8).
+        pub slot: u8,
+    }
+
+    /// Which volume slider controls a [`Sound`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum SoundCategory {
+        Master,
+        Music,
+        Records,
+        Weather,
+        Blocks,
+        Hostile,
+        Neutral,
+        Players,
+        Ambient,
+        Voice,
+    }
+
+    impl SoundCategory {
+        /// Returns the category with the given numeric id, or `None` if there
+        /// is no such category.
+        pub fn from_id(id: i32) -> Option<Self> {
+            match id {
+                0 => Some(Self::Master),
+                1 => Some(Self::Music),
+                2 => Some(Self::Records),
+                3 => Some(Self::Weather),
+                4 => Some(Self::Blocks),
+                5 => Some(Self::Hostile),
+                6 => Some(Self::Neutral),
+                7 => Some(Self::Players),
+                8 => Some(Self::Ambient),
+                9 => Some(Self::Voice),
+                _ => None,
+            }
+        }
+    }
+
+    /// The sound event played by a [`Sound`].
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub enum SoundNameOrId {
+        /// Name of the sound event, e.g., `minecraft:block.stone.break`. Not
+        /// necessarily a vanilla sound.
+        Name(String),
+
+        /// Numeric id of a vanilla sound event in the server's protocol
+        /// version. See `brine_data::Sounds` for looking up its name.
+        Id(i32),
+    }
+
+    /// Tells the client to play a sound at a position in the world.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Sound {
+        pub name_or_id: SoundNameOrId,
+
+        pub category: SoundCategory,
+
+        /// Where the sound comes from, in blocks.
+        pub position: bevy::math::Vec3,
+
+        /// 1 is full volume. Values above 1 don't make the sound louder, but
+        /// let it be heard from further away.
+        pub volume: f32,
+
+        /// Playback speed, from 0.5 to 2.
+        pub pitch: f32,
+    }
+
+    /// A change to one player's entry in the tab list, as part of a
+    /// [`PlayerInfo`] event.
+    ///
+    /// `gamemode` is `None` if the server sent a game mode that isn't known.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum PlayerInfoUpdate {
+        /// Adds the player to the tab list, or replaces their entry.
+        Add {
+            uuid: uuid::Uuid,
+            name: String,
+            gamemode: Option<GameMode>,
+
+            /// Round-trip time to the server, in milliseconds.
+            latency: i32,
+
+            /// Name to show in place of `name`, if any.
+            display_name: Option<crate::chat::ChatComponent>,
+        },
+
+        UpdateGameMode {
+            uuid: uuid::Uuid,
+            gamemode: Option<GameMode>,
+        },
+
+        UpdateLatency {
+            uuid: uuid::Uuid,
+            latency: i32,
+        },
+
+        UpdateDisplayName {
+            uuid: uuid::Uuid,
+            display_name: Option<crate::chat::ChatComponent>,
+        },
+
+        /// Removes the player from the tab list.
+        Remove {
+            uuid: uuid::Uuid,
+        },
+    }
+
+    impl PlayerInfoUpdate {
+        /// Returns the UUID of the player that the update is for.
+        pub fn uuid(&self) -> uuid::Uuid {
+            match self {
+                Self::Add { uuid, .. }
+                | Self::UpdateGameMode { uuid, .. }
+                | Self::UpdateLatency { uuid, .. }
+                | Self::UpdateDisplayName { uuid, .. }
+                | Self::Remove { uuid } => *uuid,
+            }
+        }
+    }
+
+    /// Adds, changes, or removes players in the tab list.
+    ///
+    /// Updates may arrive for players that were never added, which consumers
+    /// should ignore.
+    ///
+    /// # See also
+    ///
+    /// * [`TabList`][crate::TabList]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PlayerInfo {
+        pub updates: Vec<PlayerInfoUpdate>,
+    }
+
+    /// The color of a boss bar.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum BossBarColor {
+        Pink,
+        Blue,
+        Red,
+        Green,
+        Yellow,
+        Purple,
+        White,
+    }
+
+    impl BossBarColor {
+        /// Returns the color with the given numeric id, or `None` if there is
+        /// no such color.
+        pub fn from_id(id: i32) -> Option<Self> {
+            match id {
+                0 => Some(Self::Pink),
+                1 => Some(Self::Blue),
+                2 => Some(Self::Red),
+                3 => Some(Self::Green),
+                4 => Some(Self::Yellow),
+                5 => Some(Self::Purple),
+                6 => Some(Self::White),
+                _ => None,
+            }
+        }
+    }
+
+    /// How many notches a boss bar is divided into.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum BossBarDivision {
+        None,
+        Notches6,
+        Notches10,
+        Notches12,
+        Notches20,
+    }
+
+    impl BossBarDivision {
+        /// Returns the division with the given numeric id, or `None` if there
+        /// is no such division.
+        pub fn from_id(id: i32) -> Option<Self> {
+            match id {
+                0 => Some(Self::None),
+                1 => Some(Self::Notches6),
+                2 => Some(Self::Notches10),
+                3 => Some(Self::Notches12),
+                4 => Some(Self::Notches20),
+                _ => None,
+            }
+        }
+
+        /// Returns the number of notches, or 0 for an undivided bar.
+        pub fn notches(self) -> u32 {
+            match self {
+                Self::None => 0,
+                Self::Notches6 => 6,
+                Self::Notches10 => 10,
+                Self::Notches12 => 12,
+                Self::Notches20 => 20,
+            }
+        }
+    }
+
+    /// Effects that a boss bar has on the world while it is shown.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct BossBarFlags {
+        pub darken_sky: bool,
+
+        /// Whether the ender dragon's music plays.
+        pub dragon_bar: bool,
+
+        pub create_fog: bool,
+    }
+
+    impl BossBarFlags {
+        pub fn from_bits(bits: u8) -> Self {
+            Self {
+                darken_sky: bits & 0x1 != 0,
+                dragon_bar: bits & 0x2 != 0,
+                create_fog: bits & 0x4 != 0,
+            }
+        }
+    }
+
+    /// What a [`BossBar`] event does to its bar.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum BossBarAction {
+        /// Shows a new bar.
+        Add {
+            title: crate::chat::ChatComponent,
+
+            /// How full the bar is, from 0 to 1.
+            health: f32,
+
+            color: BossBarColor,
+            division: BossBarDivision,
+            flags: BossBarFlags,
+        },
+
+        /// Removes the bar.
+        Remove,
+
+        /// Changes how full the bar is, from 0 to 1.
+        UpdateHealth(f32),
+
+        UpdateTitle(crate::chat::ChatComponent),
+
+        UpdateStyle {
+            color: BossBarColor,
+            division: BossBarDivision,
+        },
+
+        UpdateFlags(BossBarFlags),
+    }
+
+    /// Adds, removes, or changes one of the boss bars at the top of the
+    /// screen.
+    ///
+    /// Several bars may be shown at once, so each is identified by a UUID.
+    /// Updates may arrive for bars that were never added, which consumers
+    /// should ignore.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct BossBar {
+        pub uuid: uuid::Uuid,
+        pub action: BossBarAction,
+    }
+
+    /// What a [`Title`] event does to the title on the screen.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum TitleAction {
+        /// Shows a title in the middle of the screen.
+        SetTitle(crate::chat::ChatComponent),
+
+        /// Sets the text shown below the title. The subtitle is not shown
+        /// until the next [`TitleAction::SetTitle`].
+        SetSubtitle(crate::chat::ChatComponent),
+
+        /// Shows text above the hotbar.
+        SetActionBar(crate::chat::ChatComponent),
+
+        /// Sets how long titles fade in, stay, and fade out for, in ticks.
+        ///
+        /// This also applies to the title that is currently shown, if any,
+        /// restarting its display.
+        SetTimes {
+            fade_in: i32,
+            stay: i32,
+            fade_out: i32,
+        },
+
+        /// Hides the current title.
+        Hide,
+
+        /// Hides the current title, clears the subtitle, and resets the times
+        /// to their defaults.
+        Reset,
+    }
+
+    /// Changes the title, subtitle, or action bar text.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Title {
+        pub action: TitleAction,
+    }
+
+    /// A packet that the protocol backend received but doesn't understand.
+    ///
+    /// These are only sent if the backend has been configured to forward them,
+    /// which is mostly useful for debugging and for implementing support for
+    /// new packets.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UnknownPacket {
+        /// Name of the protocol state the packet was received in (e.g.,
+        /// `"Play"`).
+        pub state: &'static str,
+
+        pub packet_id: i32,
+
+        /// The packet's data, not including its length and id.
+        pub body: Vec<u8>,
+    }
+
     pub(crate) fn add_events(app: &mut bevy::app::App) {
         app.add_event::<LoginSuccess>();
         app.add_event::<Disconnect>();
+        app.add_event::<JoinGame>();
+        app.add_event::<Respawn>();
+        app.add_event::<TimeUpdate>();
         app.add_event::<ChunkData>();
+        app.add_event::<LightUpdated>();
+        app.add_event::<BlockChanged>();
+        app.add_event::<DiggingAcknowledged>();
+        app.add_event::<EntitySpawned>();
+        app.add_event::<EntityMoved>();
+        app.add_event::<EntityVelocity>();
+        app.add_event::<EntitiesDestroyed>();
+        app.add_event::<InventoryChanged>();
+        app.add_event::<SlotChanged>();
+        app.add_event::<HeldItemChanged>();
+        app.add_event::<Sound>();
+        app.add_event::<PlayerInfo>();
+        app.add_event::<BossBar>();
+        app.add_event::<Title>();
+        app.add_event::<UnknownPacket>();
     }
 }
@@ -1,6 +1,13 @@
 //! High-level client-server API definition.
 
+pub mod auth;
+pub mod chat;
 pub mod event;
+pub mod player;
 mod plugin;
+pub mod tab_list;
 
+pub use auth::{Authenticator, GameProfile, LoginAuthenticator, NoAuth};
+pub use player::LocalPlayer;
 pub use plugin::{AlwaysSuccessfulLoginPlugin, ProtocolPlugin};
+pub use tab_list::{TabList, TabListChanged, TabListEntry};
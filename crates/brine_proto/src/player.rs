@@ -0,0 +1,46 @@
+//! State of the player that the client is logged in as.
+
+use bevy::prelude::*;
+
+use crate::event::clientbound::{Dimension, Disconnect, GameMode, JoinGame, Respawn};
+
+/// Resource that holds what the server has told the client about its own
+/// player.
+///
+/// Everything is `None` until the player has joined the game, and again after
+/// the client is disconnected.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LocalPlayer {
+    /// Id of the player's own entity, as given by [`JoinGame`].
+    pub entity_id: Option<i32>,
+
+    /// The dimension that the player is currently in.
+    pub dimension: Option<Dimension>,
+
+    pub gamemode: Option<GameMode>,
+}
+
+/// System that keeps the [`LocalPlayer`] resource up to date.
+pub(crate) fn update_local_player(
+    mut join_events: EventReader<JoinGame>,
+    mut respawn_events: EventReader<Respawn>,
+    mut disconnect_events: EventReader<Disconnect>,
+    mut player: ResMut<LocalPlayer>,
+) {
+    for join in join_events.iter() {
+        *player = LocalPlayer {
+            entity_id: Some(join.entity_id),
+            dimension: Some(join.dimension),
+            gamemode: Some(join.gamemode),
+        };
+    }
+
+    for respawn in respawn_events.iter() {
+        player.dimension = Some(respawn.dimension);
+        player.gamemode = Some(respawn.gamemode);
+    }
+
+    if disconnect_events.iter().next().is_some() {
+        *player = LocalPlayer::default();
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::app::{App, Plugin};
 
-use crate::event;
+use crate::{auth, event, player, tab_list};
 
 /// Protocol "front-end" plugin.
 ///
@@ -14,13 +14,25 @@ use crate::event;
 /// * [`event::clientbound::*`][event::clientbound]
 /// * [`event::serverbound::*`][event::serverbound]
 ///
-/// The plugin does not react to any events.
+/// The plugin acts on the following events:
 ///
-/// The plugin does not send any events.
+/// * [`event::clientbound::JoinGame`]
+/// * [`event::clientbound::Respawn`]
+/// * [`event::clientbound::Disconnect`]
+/// * [`event::clientbound::PlayerInfo`]
+///
+/// The plugin sends the following events:
+///
+/// * [`TabListChanged`][crate::TabListChanged]
 ///
 /// # Resources
 ///
-/// The plugin registers no resources.
+/// The plugin registers the following resources:
+///
+/// * [`LocalPlayer`][crate::LocalPlayer]
+/// * [`TabList`][crate::TabList]
+/// * [`LoginAuthenticator`][crate::LoginAuthenticator], unless it already
+///   exists
 ///
 /// The plugin expects no resources to exist.
 pub struct ProtocolPlugin;
@@ -29,5 +41,14 @@ impl Plugin for ProtocolPlugin {
     fn build(&self, app: &mut App) {
         event::serverbound::add_events(app);
         event::clientbound::add_events(app);
+
+        app.init_resource::<player::LocalPlayer>();
+        app.add_system(player::update_local_player);
+
+        app.init_resource::<tab_list::TabList>();
+        app.add_event::<tab_list::TabListChanged>();
+        app.add_system(tab_list::update_tab_list);
+
+        app.init_resource::<auth::LoginAuthenticator>();
     }
 }
@@ -30,6 +30,9 @@ impl Plugin for AlwaysSuccessfulLoginPlugin {
     }
 }
 
+/// The dummy server claims to speak the protocol of Minecraft 1.14.4.
+const PROTOCOL_VERSION: i32 = 498;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 enum ServerState {
     Login,
@@ -48,6 +51,7 @@ fn handle_login(
         tx.send(LoginSuccess {
             uuid: Uuid::new_v4(),
             username: login.username.clone(),
+            protocol_version: PROTOCOL_VERSION,
         });
     }
 }
@@ -0,0 +1,247 @@
+//! The list of players on the server, as shown when the tab key is held.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    chat::ChatComponent,
+    event::{
+        clientbound::{Disconnect, GameMode, PlayerInfo, PlayerInfoUpdate},
+        Uuid,
+    },
+};
+
+/// A player in the [`TabList`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabListEntry {
+    pub name: String,
+
+    /// Name to show in place of `name`, if any.
+    pub display_name: Option<ChatComponent>,
+
+    /// `None` if the server sent a game mode that isn't known.
+    pub gamemode: Option<GameMode>,
+
+    /// Round-trip time to the server, in milliseconds.
+    pub latency: i32,
+}
+
+/// Event sent whenever an entry of the [`TabList`] changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabListChanged {
+    Added(Uuid),
+    Updated(Uuid),
+    Removed(Uuid),
+}
+
+/// Resource that holds the players on the server, kept up to date with
+/// [`PlayerInfo`] events.
+///
+/// The list is cleared when the client is disconnected.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TabList {
+    entries: HashMap<Uuid, TabListEntry>,
+}
+
+impl TabList {
+    pub fn get(&self, uuid: &Uuid) -> Option<&TabListEntry> {
+        self.entries.get(uuid)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Uuid, &TabListEntry)> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Applies an update, returning how the list changed, if it did.
+    ///
+    /// Updates for players that aren't in the list are ignored.
+    pub fn apply(&mut self, update: &PlayerInfoUpdate) -> Option<TabListChanged> {
+        let uuid = update.uuid();
+
+        match update {
+            PlayerInfoUpdate::Add {
+                name,
+                gamemode,
+                latency,
+                display_name,
+                ..
+            } => {
+                let entry = TabListEntry {
+                    name: name.clone(),
+                    display_name: display_name.clone(),
+                    gamemode: *gamemode,
+                    latency: *latency,
+                };
+
+                return Some(match self.entries.insert(uuid, entry) {
+                    Some(_) => TabListChanged::Updated(uuid),
+                    None => TabListChanged::Added(uuid),
+                });
+            }
+            PlayerInfoUpdate::Remove { .. } => {
+                return self
+                    .entries
+                    .remove(&uuid)
+                    .map(|_| TabListChanged::Removed(uuid));
+            }
+            PlayerInfoUpdate::UpdateGameMode { gamemode, .. } => {
+                self.entries.get_mut(&uuid)?.gamemode = *gamemode;
+            }
+            PlayerInfoUpdate::UpdateLatency { latency, .. } => {
+                self.entries.get_mut(&uuid)?.latency = *latency;
+            }
+            PlayerInfoUpdate::UpdateDisplayName { display_name, .. } => {
+                self.entries.get_mut(&uuid)?.display_name = display_name.clone();
+            }
+        }
+
+        Some(TabListChanged::Updated(uuid))
+    }
+
+    /// Removes every entry, returning the corresponding changes.
+    pub fn clear(&mut self) -> Vec<TabListChanged> {
+        self.entries
+            .drain()
+            .map(|(uuid, _)| TabListChanged::Removed(uuid))
+            .collect()
+    }
+}
+
+/// System that keeps the [`TabList`] resource up to date.
+pub(crate) fn update_tab_list(
+    mut player_info_events: EventReader<PlayerInfo>,
+    mut disconnect_events: EventReader<Disconnect>,
+    mut tab_list: ResMut<TabList>,
+    mut changed_events: EventWriter<TabListChanged>,
+) {
+    for player_info in player_info_events.iter() {
+        for update in player_info.updates.iter() {
+            match tab_list.apply(update) {
+                Some(changed) => changed_events.send(changed),
+                None => trace!("Ignoring update for unknown player {}", update.uuid()),
+            }
+        }
+    }
+
+    if disconnect_events.iter().next().is_some() {
+        for changed in tab_list.clear() {
+            changed_events.send(changed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn uuid(n: u8) -> Uuid {
+        Uuid::from_bytes([n; 16])
+    }
+
+    fn add(n: u8, name: &str) -> PlayerInfoUpdate {
+        PlayerInfoUpdate::Add {
+            uuid: uuid(n),
+            name: name.to_string(),
+            gamemode: Some(GameMode::Survival),
+            latency: 50,
+            display_name: None,
+        }
+    }
+
+    fn update_latency(n: u8, latency: i32) -> PlayerInfoUpdate {
+        PlayerInfoUpdate::UpdateLatency {
+            uuid: uuid(n),
+            latency,
+        }
+    }
+
+    #[test]
+    fn add_update_remove() {
+        let mut tab_list = TabList::default();
+
+        assert_eq!(
+            tab_list.apply(&add(1, "alice")),
+            Some(TabListChanged::Added(uuid(1)))
+        );
+        assert_eq!(tab_list.get(&uuid(1)).unwrap().name, "alice");
+
+        assert_eq!(
+            tab_list.apply(&update_latency(1, 120)),
+            Some(TabListChanged::Updated(uuid(1)))
+        );
+        assert_eq!(
+            tab_list.apply(&PlayerInfoUpdate::UpdateGameMode {
+                uuid: uuid(1),
+                gamemode: Some(GameMode::Creative),
+            }),
+            Some(TabListChanged::Updated(uuid(1)))
+        );
+        assert_eq!(
+            tab_list.apply(&PlayerInfoUpdate::UpdateDisplayName {
+                uuid: uuid(1),
+                display_name: Some(ChatComponent::text("Alice")),
+            }),
+            Some(TabListChanged::Updated(uuid(1)))
+        );
+        assert_eq!(
+            tab_list.get(&uuid(1)),
+            Some(&TabListEntry {
+                name: String::from("alice"),
+                display_name: Some(ChatComponent::text("Alice")),
+                gamemode: Some(GameMode::Creative),
+                latency: 120,
+            })
+        );
+
+        assert_eq!(
+            tab_list.apply(&PlayerInfoUpdate::Remove { uuid: uuid(1) }),
+            Some(TabListChanged::Removed(uuid(1)))
+        );
+        assert!(tab_list.is_empty());
+    }
+
+    #[test]
+    fn updates_for_unknown_players_are_ignored() {
+        let mut tab_list = TabList::default();
+
+        // An update that arrives before the player is added doesn't create an
+        // entry, and doesn't affect the entry once it's added.
+        assert_eq!(tab_list.apply(&update_latency(1, 999)), None);
+        assert!(tab_list.is_empty());
+
+        tab_list.apply(&add(1, "alice"));
+        assert_eq!(tab_list.get(&uuid(1)).unwrap().latency, 50);
+
+        // Nor does an update that arrives after the player is removed.
+        tab_list.apply(&PlayerInfoUpdate::Remove { uuid: uuid(1) });
+        assert_eq!(tab_list.apply(&update_latency(1, 999)), None);
+        assert_eq!(
+            tab_list.apply(&PlayerInfoUpdate::Remove { uuid: uuid(1) }),
+            None
+        );
+        assert!(tab_list.is_empty());
+    }
+
+    #[test]
+    fn adding_again_replaces_the_entry() {
+        let mut tab_list = TabList::default();
+        tab_list.apply(&add(1, "alice"));
+        tab_list.apply(&add(2, "bob"));
+
+        assert_eq!(
+            tab_list.apply(&add(1, "alice2")),
+            Some(TabListChanged::Updated(uuid(1)))
+        );
+        assert_eq!(tab_list.len(), 2);
+        assert_eq!(tab_list.get(&uuid(1)).unwrap().name, "alice2");
+    }
+}
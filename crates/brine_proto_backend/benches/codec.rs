@@ -0,0 +1,188 @@
+//! Encodes and decodes a stream of KeepAlive and ChunkData packets, and prints
+//! how many heap allocations each packet takes.
+//!
+//! ```text
+//! cargo bench -p brine_proto_backend --bench codec
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use brine_proto_backend::{
+    backend_stevenarella::codec::{Direction, MinecraftCodec, Packet},
+    codec::MinecraftProtocolState,
+};
+
+/// Protocol version of 1.14.4.
+const PROTOCOL_VERSION: i32 = 498;
+
+/// Packet ids in 1.14.4.
+const KEEP_ALIVE: u32 = 0x20;
+const CHUNK_DATA: u32 = 0x21;
+
+/// Number of KeepAlive packets for every ChunkData packet in the stream.
+const KEEP_ALIVES_PER_CHUNK: usize = 4;
+const CHUNKS: usize = 64;
+
+/// Size of the section data in each ChunkData packet, about that of a chunk
+/// with a few sections of terrain.
+const CHUNK_DATA_BYTES: usize = 12 * 1024;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts every allocation made by the benchmark.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the result of `f` and the number of allocations it made.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+fn write_var_int(buf: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn write_packet(stream: &mut Vec<u8>, id: u32, body: &[u8]) {
+    let mut id_and_body = Vec::new();
+    write_var_int(&mut id_and_body, id);
+    id_and_body.extend_from_slice(body);
+
+    write_var_int(stream, id_and_body.len() as u32);
+    stream.extend_from_slice(&id_and_body);
+}
+
+fn keep_alive_body(id: i64) -> Vec<u8> {
+    id.to_be_bytes().to_vec()
+}
+
+/// The body of a full ChunkData packet without heightmaps or block entities,
+/// whose section data is all zeros.
+fn chunk_data_body(chunk_x: i32, chunk_z: i32) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&chunk_x.to_be_bytes());
+    body.extend_from_slice(&chunk_z.to_be_bytes());
+    // Full chunk.
+    body.push(1);
+    // Bitmask.
+    write_var_int(&mut body, 0b111);
+    // Heightmaps (TAG_End).
+    body.push(0);
+    write_var_int(&mut body, CHUNK_DATA_BYTES as u32);
+    body.resize(body.len() + CHUNK_DATA_BYTES, 0);
+    // Block entities.
+    write_var_int(&mut body, 0);
+    body
+}
+
+/// Returns the bytes of the stream, and the number of packets in it.
+fn packet_stream() -> (Vec<u8>, usize) {
+    let mut stream = Vec::new();
+    let mut packets = 0;
+
+    for chunk in 0..CHUNKS {
+        for keep_alive in 0..KEEP_ALIVES_PER_CHUNK {
+            let id = (chunk * KEEP_ALIVES_PER_CHUNK + keep_alive) as i64;
+            write_packet(&mut stream, KEEP_ALIVE, &keep_alive_body(id));
+            packets += 1;
+        }
+
+        write_packet(&mut stream, CHUNK_DATA, &chunk_data_body(chunk as i32, 0));
+        packets += 1;
+    }
+
+    (stream, packets)
+}
+
+fn decode_stream(stream: &[u8]) -> Vec<Packet> {
+    let mut packets = Vec::with_capacity(CHUNKS * (KEEP_ALIVES_PER_CHUNK + 1));
+    let mut offset = 0;
+
+    while offset < stream.len() {
+        let (length, packet) = MinecraftCodec::decode_packet(
+            PROTOCOL_VERSION,
+            MinecraftProtocolState::Play,
+            Direction::Clientbound,
+            &stream[offset..],
+        )
+        .unwrap();
+
+        assert!(matches!(packet, Packet::Known(_)), "{:?}", packet);
+        packets.push(packet);
+        offset += length;
+    }
+
+    packets
+}
+
+fn encode_stream(packets: &[Packet], buf: &mut [u8]) -> usize {
+    packets
+        .iter()
+        .map(|packet| MinecraftCodec::encode_packet(PROTOCOL_VERSION, packet, &mut *buf).unwrap())
+        .sum()
+}
+
+fn codec(c: &mut Criterion) {
+    let (stream, num_packets) = packet_stream();
+    let mut buf = vec![0; 2 * CHUNK_DATA_BYTES];
+
+    let (packets, decode_allocations) = count_allocations(|| decode_stream(&stream));
+    let (encoded_bytes, encode_allocations) =
+        count_allocations(|| encode_stream(&packets, &mut buf));
+    assert_eq!(encoded_bytes, stream.len());
+
+    println!(
+        "{} packets ({} bytes): {:.2} allocations per decoded packet, {:.2} per encoded packet",
+        num_packets,
+        stream.len(),
+        decode_allocations as f64 / num_packets as f64,
+        encode_allocations as f64 / num_packets as f64,
+    );
+
+    let mut group = c.benchmark_group("codec");
+    group.throughput(Throughput::Bytes(stream.len() as u64));
+
+    group.bench_function("decode", |b| {
+        b.iter(|| decode_stream(black_box(&stream)));
+    });
+    group.bench_function("encode", |b| {
+        b.iter(|| encode_stream(black_box(&packets), &mut buf));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, codec);
+criterion_main!(benches);
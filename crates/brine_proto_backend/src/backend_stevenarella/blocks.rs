@@ -0,0 +1,255 @@
+//! Decoding of single block changes, and digging and using items on blocks.
+//!
+//! See these pages for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Block_Change>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Acknowledge_Player_Digging>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Player_Digging>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Player_Block_Placement>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Animation_.28serverbound.29>
+
+use bevy::prelude::*;
+use steven_protocol::protocol::VarInt;
+use steven_shared::Position;
+
+use brine_chunk::BlockState;
+use brine_net::{CodecReader, CodecWriter};
+use brine_proto::event::{
+    clientbound::{BlockChanged, DiggingAcknowledged},
+    serverbound::{DiggingStatus, PlayerDigging, SwingArm, UseItemOn},
+};
+
+use super::codec::{packet, Packet, ProtocolCodec};
+
+/// Decodes a [`BlockChanged`] event from a packet, if it's a BlockChange
+/// packet.
+pub fn get_block_change_from_packet(packet: &Packet) -> Option<BlockChanged> {
+    match packet {
+        Packet::Known(packet::Packet::BlockChange_VarInt(block_change)) => Some(BlockChanged {
+            block_pos: IVec3::new(
+                block_change.location.x,
+                block_change.location.y,
+                block_change.location.z,
+            ),
+            block_state: BlockState(block_change.block_id.0 as u32),
+        }),
+        _ => None,
+    }
+}
+
+/// Decodes a [`DiggingAcknowledged`] event from a packet, if it's an
+/// AcknowledgePlayerDigging packet.
+///
+/// Packets with a status that isn't known are logged and ignored.
+pub fn get_digging_ack_from_packet(packet: &Packet) -> Option<DiggingAcknowledged> {
+    let ack = match packet {
+        Packet::Known(packet::Packet::AcknowledgePlayerDigging(ack)) => ack,
+        _ => return None,
+    };
+
+    let status = match DiggingStatus::from_id(ack.status.0) {
+        Some(status) => status,
+        None => {
+            warn!("Unknown digging status {}", ack.status.0);
+            return None;
+        }
+    };
+
+    Some(DiggingAcknowledged {
+        block_pos: IVec3::new(ack.location.x, ack.location.y, ack.location.z),
+        block_state: BlockState(ack.block.0 as u32),
+        status,
+        successful: ack.successful,
+    })
+}
+
+/// Encodes a [`PlayerDigging`] event as a PlayerDigging packet.
+pub fn player_digging_packet(event: &PlayerDigging) -> Packet {
+    Packet::Known(packet::Packet::PlayerDigging(Box::new(
+        packet::play::serverbound::PlayerDigging {
+            status: VarInt(event.status.id()),
+            location: Position::new(event.block_pos.x, event.block_pos.y, event.block_pos.z),
+            face: event.face.id() as u8,
+        },
+    )))
+}
+
+/// Encodes a [`UseItemOn`] event as a PlayerBlockPlacement packet.
+pub fn use_item_on_packet(event: &UseItemOn) -> Packet {
+    Packet::Known(packet::Packet::PlayerBlockPlacement_insideblock(Box::new(
+        packet::play::serverbound::PlayerBlockPlacement_insideblock {
+            hand: VarInt(event.hand.id()),
+            location: Position::new(event.block_pos.x, event.block_pos.y, event.block_pos.z),
+            face: VarInt(event.face.id()),
+            cursor_x: event.cursor_pos.x,
+            cursor_y: event.cursor_pos.y,
+            cursor_z: event.cursor_pos.z,
+            // Only matters for placing blocks from inside of a block, e.g.,
+            // while standing in tall grass, which isn't tracked yet.
+            inside_block: false,
+        },
+    )))
+}
+
+/// Encodes a [`SwingArm`] event as an Animation packet.
+pub fn swing_arm_packet(event: &SwingArm) -> Packet {
+    Packet::Known(packet::Packet::ArmSwing(Box::new(
+        packet::play::serverbound::ArmSwing {
+            hand: VarInt(event.hand.id()),
+        },
+    )))
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_block_change_packets);
+    app.add_system(send_block_interactions);
+}
+
+/// System that listens for BlockChange and AcknowledgePlayerDigging packets
+/// and sends the corresponding events to the client application.
+fn handle_block_change_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut block_events: EventWriter<BlockChanged>,
+    mut digging_events: EventWriter<DiggingAcknowledged>,
+) {
+    for packet in packet_reader.iter() {
+        if let Some(event) = get_block_change_from_packet(packet) {
+            trace!("{:?}", event);
+            block_events.send(event);
+        } else if let Some(event) = get_digging_ack_from_packet(packet) {
+            trace!("{:?}", event);
+            digging_events.send(event);
+        }
+    }
+}
+
+/// System that tells the server when the player digs, uses an item on a
+/// block, or swings their arm.
+fn send_block_interactions(
+    mut digging_events: EventReader<PlayerDigging>,
+    mut use_item_events: EventReader<UseItemOn>,
+    mut swing_arm_events: EventReader<SwingArm>,
+    mut packet_writer: CodecWriter<ProtocolCodec>,
+) {
+    for event in digging_events.iter() {
+        packet_writer.send(player_digging_packet(event));
+    }
+
+    for event in use_item_events.iter() {
+        packet_writer.send(use_item_on_packet(event));
+    }
+
+    for event in swing_arm_events.iter() {
+        packet_writer.send(swing_arm_packet(event));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use brine_proto::event::serverbound::{BlockFace, Hand};
+
+    use super::*;
+
+    #[test]
+    fn block_change() {
+        let packet = Packet::Known(packet::Packet::BlockChange_VarInt(Box::new(
+            packet::play::clientbound::BlockChange_VarInt {
+                location: Position::new(-12, 64, 300),
+                block_id: VarInt(9),
+            },
+        )));
+
+        assert_eq!(
+            get_block_change_from_packet(&packet),
+            Some(BlockChanged {
+                block_pos: IVec3::new(-12, 64, 300),
+                block_state: BlockState(9),
+            })
+        );
+    }
+
+    #[test]
+    fn digging_ack() {
+        let ack = |status| {
+            Packet::Known(packet::Packet::AcknowledgePlayerDigging(Box::new(
+                packet::play::clientbound::AcknowledgePlayerDigging {
+                    location: Position::new(1, 2, 3),
+                    block: VarInt(0),
+                    status: VarInt(status),
+                    successful: true,
+                },
+            )))
+        };
+
+        assert_eq!(
+            get_digging_ack_from_packet(&ack(2)),
+            Some(DiggingAcknowledged {
+                block_pos: IVec3::new(1, 2, 3),
+                block_state: BlockState::AIR,
+                status: DiggingStatus::Finished,
+                successful: true,
+            })
+        );
+        assert_eq!(get_digging_ack_from_packet(&ack(6)), None);
+    }
+
+    #[test]
+    fn player_digging() {
+        let packet = player_digging_packet(&PlayerDigging {
+            status: DiggingStatus::Cancelled,
+            block_pos: IVec3::new(-12, 64, 300),
+            face: BlockFace::East,
+        });
+
+        match packet {
+            Packet::Known(packet::Packet::PlayerDigging(digging)) => {
+                assert_eq!(digging.status.0, 1);
+                assert_eq!(
+                    (digging.location.x, digging.location.y, digging.location.z),
+                    (-12, 64, 300)
+                );
+                assert_eq!(digging.face, 5);
+            }
+            _ => panic!("not a PlayerDigging packet"),
+        }
+    }
+
+    #[test]
+    fn use_item_on() {
+        let packet = use_item_on_packet(&UseItemOn {
+            block_pos: IVec3::new(-12, 64, 300),
+            face: BlockFace::Top,
+            cursor_pos: Vec3::new(0.5, 1.0, 0.25),
+            hand: Hand::Off,
+        });
+
+        match packet {
+            Packet::Known(packet::Packet::PlayerBlockPlacement_insideblock(placement)) => {
+                assert_eq!(placement.hand.0, 1);
+                assert_eq!(
+                    (
+                        placement.location.x,
+                        placement.location.y,
+                        placement.location.z
+                    ),
+                    (-12, 64, 300)
+                );
+                assert_eq!(placement.face.0, 1);
+                assert_eq!(
+                    (placement.cursor_x, placement.cursor_y, placement.cursor_z),
+                    (0.5, 1.0, 0.25)
+                );
+                assert!(!placement.inside_block);
+            }
+            _ => panic!("not a PlayerBlockPlacement packet"),
+        }
+    }
+
+    #[test]
+    fn swing_arm() {
+        match swing_arm_packet(&SwingArm { hand: Hand::Main }) {
+            Packet::Known(packet::Packet::ArmSwing(swing)) => assert_eq!(swing.hand.0, 0),
+            _ => panic!("not an Animation packet"),
+        }
+    }
+}
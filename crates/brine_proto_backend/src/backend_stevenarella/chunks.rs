@@ -1,10 +1,32 @@
-use bevy::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
 
-use brine_chunk::{decode::Result, BlockState, Chunk, Palette};
+use bevy::{
+    diagnostic::{DiagnosticId, Diagnostics},
+    prelude::*,
+};
+
+use brine_chunk::{
+    decode::Result, Biomes, BlockState, Chunk, ChunkSection, Palette, SECTIONS_PER_CHUNK,
+};
 use brine_net::CodecReader;
 use brine_proto::event;
 
-use super::codec::{packet, Packet, ProtocolCodec};
+use crate::version::VersionAdapter;
+
+use super::{
+    codec::{packet, Packet, ProtocolCodec, Serializable},
+    world::{get_world_event_from_packet, WorldEvent},
+};
+
+/// Time (in milliseconds) that it took to decode the chunk of a ChunkData
+/// packet.
+///
+/// Measured whenever the diagnostic has been registered with [`Diagnostics`].
+pub const CHUNK_DECODE_TIME: DiagnosticId =
+    DiagnosticId::from_u128(0xd3a8_5e06_1f7b_42c9_9e4d_b06a_38f2_c715);
 
 /// A dummy palette for testing that performs no translation.
 pub struct DummyPalette;
@@ -21,111 +43,205 @@ pub struct ChunkData<T> {
     pub chunk_z: i32,
     pub full_chunk: bool,
     pub bitmask: u16,
+
+    /// The `Heightmaps` NBT compound, re-encoded as bytes, for versions that
+    /// send one.
+    pub heightmaps: Option<Vec<u8>>,
+
+    /// Whether the data of a full chunk ends with its biomes, as it does before
+    /// 1.15.
+    pub biomes_in_data: bool,
+
     pub data: T,
 }
 
 impl<'d> ChunkData<&'d [u8]> {
+    /// Returns the parts of a ChunkData packet of the default protocol
+    /// version, if it is one.
+    ///
+    /// Systems should use the [`VersionAdapter`] instead, which knows the
+    /// protocol version that the server speaks.
     pub fn from_packet(packet: &'d Packet) -> Option<Self> {
-        let (chunk_x, chunk_z, full_chunk, bitmask, data) = match packet {
-            /*Packet::Known(packet::Packet::ChunkData_Biomes3D_Bitmasks(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                ??,
-                ??,
-                &chunk_data.data[..],
-            ),*/
-            Packet::Known(packet::Packet::ChunkData_Biomes3D_VarInt(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask.0 as u16,
-                &chunk_data.data.data[..],
-            ),
-            Packet::Known(packet::Packet::ChunkData_Biomes3D_bool(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask.0 as u16,
-                &chunk_data.data.data[..],
-            ),
-            Packet::Known(packet::Packet::ChunkData_Biomes3D(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask.0 as u16,
-                &chunk_data.data.data[..],
-            ),
-            Packet::Known(packet::Packet::ChunkData_HeightMap(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask.0 as u16,
-                &chunk_data.data.data[..],
-            ),
-            Packet::Known(packet::Packet::ChunkData(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask.0 as u16,
-                &chunk_data.data.data[..],
-            ),
-            Packet::Known(packet::Packet::ChunkData_NoEntities(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask.0 as u16,
-                &chunk_data.data.data[..],
-            ),
-            /*Packet::Known(packet::Packet::ChunkData_NoEntities_u16(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask,
-                &chunk_data.data.data[..] ?? varints,
-            ),*/
-            /*Packet::Known(packet::Packet::ChunkData_17(chunk_data)) => (
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-                chunk_data.new,
-                chunk_data.bitmask,
-                &chunk_data.data.data[..] ?? compressed data,
-            ),*/
-            _ => return None,
-        };
-
-        Some(ChunkData {
-            chunk_x,
-            chunk_z,
-            bitmask,
-            full_chunk,
-            data,
-        })
+        VersionAdapter::default().chunk_data_from_packet(packet)
     }
 }
 
+/// Encodes an NBT tag that was decoded by `steven_protocol` back into bytes, so
+/// that it can be decoded by `brine_chunk`.
+pub(crate) fn encode_nbt(tag: &impl Serializable) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    tag.write_to(&mut bytes).ok()?;
+    Some(bytes)
+}
+
 impl<T: AsRef<[u8]>> ChunkData<T> {
     pub fn decode(&self) -> Result<Chunk> {
         let mut buf = self.data.as_ref();
-        Chunk::decode(
+        let mut chunk = Chunk::decode(
             self.chunk_x,
             self.chunk_z,
-            self.full_chunk,
+            self.full_chunk && self.biomes_in_data,
             self.bitmask,
             &DummyPalette,
             &mut buf,
-        )
+        )?;
+
+        // Biomes sent outside of the data are 3D, which `Chunk` can't hold yet,
+        // so full chunks just get placeholder biomes.
+        if self.full_chunk && !self.biomes_in_data {
+            chunk.biomes = Some(Box::new(Biomes::default()));
+        }
+
+        // Heightmaps are only meaningful for full chunks.
+        if self.full_chunk {
+            if let Some(heightmaps) = self.heightmaps.as_ref() {
+                chunk.decode_heightmaps(&mut &heightmaps[..])?;
+            }
+        }
+
+        Ok(chunk)
+    }
+}
+
+/// Common representation of the different versions of UpdateLight packets.
+pub struct LightUpdate<T> {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub sky_light_mask: u32,
+    pub block_light_mask: u32,
+
+    /// The light arrays, as laid out for [`Chunk::decode_light`].
+    pub data: T,
+}
+
+impl<T: AsRef<[u8]>> LightUpdate<T> {
+    /// Decodes the light into a chunk without blocks, whose sections hold only
+    /// the light that was sent.
+    ///
+    /// Apply it to a decoded chunk with [`Chunk::update_light`].
+    pub fn decode(&self) -> Result<Chunk> {
+        let mut light = Chunk::empty_delta(self.chunk_x, self.chunk_z);
+        light.sections = (0..SECTIONS_PER_CHUNK as u8)
+            .map(ChunkSection::empty)
+            .collect();
+
+        let mut buf = self.data.as_ref();
+        light.decode_light(self.sky_light_mask, self.block_light_mask, &mut buf)?;
+
+        light
+            .sections
+            .retain(|section| section.sky_light.is_some() || section.block_light.is_some());
+
+        Ok(light)
     }
 }
 
 pub fn get_chunk_from_packet(packet: &Packet) -> Result<Option<Chunk>> {
-    if let Some(chunk_data) = ChunkData::from_packet(packet) {
+    get_chunk_from_packet_with(&VersionAdapter::default(), packet)
+}
+
+/// Like [`get_chunk_from_packet`], but for the protocol version of `adapter`.
+pub fn get_chunk_from_packet_with(
+    adapter: &VersionAdapter,
+    packet: &Packet,
+) -> Result<Option<Chunk>> {
+    if let Some(chunk_data) = adapter.chunk_data_from_packet(packet) {
         Ok(Some(chunk_data.decode()?))
     } else {
         Ok(None)
     }
 }
 
+/// Like [`get_chunk_from_packet_with`], but for UpdateLight packets.
+pub fn get_light_from_packet_with(
+    adapter: &VersionAdapter,
+    packet: &Packet,
+) -> Result<Option<Chunk>> {
+    if let Some(light_update) = adapter.light_update_from_packet(packet) {
+        Ok(Some(light_update.decode()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the coordinates of the chunk in an UnloadChunk packet, if it is one.
+fn get_chunk_unload_from_packet(packet: &Packet) -> Option<(i32, i32)> {
+    match packet {
+        Packet::Known(packet::Packet::ChunkUnload(unload)) => Some((unload.x, unload.z)),
+        _ => None,
+    }
+}
+
+/// Light that has been received, by chunk coordinates.
+///
+/// The server sends the light of a chunk in an UpdateLight packet just
+/// *before* the chunk's ChunkData packet, so that light is held here until the
+/// chunk arrives. Light for chunks that have already arrived is sent on as
+/// [`event::clientbound::LightUpdated`] events.
+///
+/// Unloaded chunks are forgotten, so that the light sent ahead of a chunk that
+/// is loaded again is held for it too.
+#[derive(Debug, Default)]
+pub struct ChunkLight {
+    pending: HashMap<(i32, i32), Chunk>,
+    received: HashSet<(i32, i32)>,
+}
+
+impl ChunkLight {
+    /// Adds any pending light for the chunk to it, and remembers that the
+    /// chunk has been received.
+    fn receive_chunk(&mut self, chunk: &mut Chunk) {
+        let coords = (chunk.chunk_x, chunk.chunk_z);
+
+        if let Some(light) = self.pending.remove(&coords) {
+            chunk.update_light(&light);
+        }
+
+        self.received.insert(coords);
+    }
+
+    /// Returns the light back if its chunk has been received. Otherwise, holds
+    /// it until the chunk arrives.
+    fn receive_light(&mut self, light: Chunk) -> Option<Chunk> {
+        let coords = (light.chunk_x, light.chunk_z);
+
+        if self.received.contains(&coords) {
+            return Some(light);
+        }
+
+        match self.pending.get_mut(&coords) {
+            Some(pending) => {
+                // Newer light replaces older light section by section.
+                for section in light.sections {
+                    pending.sections.retain(|s| s.chunk_y != section.chunk_y);
+                    pending.sections.push(section);
+                }
+            }
+            None => {
+                self.pending.insert(coords, light);
+            }
+        }
+
+        None
+    }
+
+    /// Forgets the chunk and any light held for it.
+    fn unload_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        let coords = (chunk_x, chunk_z);
+        self.pending.remove(&coords);
+        self.received.remove(&coords);
+    }
+
+    /// Forgets all light and chunks, e.g., when the player changes worlds.
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.received.clear();
+    }
+}
+
 pub(crate) fn build(app: &mut App) {
+    app.init_resource::<VersionAdapter>();
+    app.init_resource::<ChunkLight>();
     app.add_system(handle_chunk_data);
 }
 
@@ -134,10 +250,52 @@ pub(crate) fn build(app: &mut App) {
 fn handle_chunk_data(
     mut packet_reader: CodecReader<ProtocolCodec>,
     mut chunk_events: EventWriter<event::clientbound::ChunkData>,
+    mut light_events: EventWriter<event::clientbound::LightUpdated>,
+    mut diagnostics: Option<ResMut<Diagnostics>>,
+    mut chunk_light: ResMut<ChunkLight>,
+    adapter: Res<VersionAdapter>,
 ) {
     for packet in packet_reader.iter() {
-        match get_chunk_from_packet(packet) {
-            Ok(Some(chunk_data)) => {
+        if let Some(WorldEvent::JoinGame(_) | WorldEvent::Respawn(_)) =
+            get_world_event_from_packet(packet)
+        {
+            chunk_light.clear();
+            continue;
+        }
+
+        if let Some((chunk_x, chunk_z)) = get_chunk_unload_from_packet(packet) {
+            chunk_light.unload_chunk(chunk_x, chunk_z);
+            continue;
+        }
+
+        match get_light_from_packet_with(&adapter, packet) {
+            Ok(Some(light)) => {
+                if let Some(light) = chunk_light.receive_light(light) {
+                    trace!("Light: {:?}", light);
+                    light_events.send(event::clientbound::LightUpdated { light });
+                }
+                continue;
+            }
+            Err(e) => {
+                error!("{}", e);
+                continue;
+            }
+            Ok(None) => {}
+        }
+
+        let start = Instant::now();
+        let result = get_chunk_from_packet_with(&adapter, packet);
+        let decode_time = start.elapsed();
+
+        match result {
+            Ok(Some(mut chunk_data)) => {
+                chunk_light.receive_chunk(&mut chunk_data);
+
+                if let Some(diagnostics) = diagnostics.as_mut() {
+                    diagnostics
+                        .add_measurement(CHUNK_DECODE_TIME, decode_time.as_secs_f64() * 1000.0);
+                }
+
                 trace!("Chunk: {:?}", chunk_data);
                 chunk_events.send(event::clientbound::ChunkData { chunk_data });
             }
@@ -146,3 +304,121 @@ fn handle_chunk_data(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::LIGHT_ARRAY_LENGTH;
+
+    use super::*;
+
+    /// Light arrays for the sections in `mask`, all of the given level.
+    fn light_arrays(mask: u32, level: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        for _ in 0..mask.count_ones() {
+            // VarInt length of 2048.
+            data.extend_from_slice(&[0x80, 0x10]);
+            data.extend_from_slice(&[(level << 4) | level; LIGHT_ARRAY_LENGTH]);
+        }
+        data
+    }
+
+    fn light_update(chunk_x: i32, sky_light_mask: u32, level: u8) -> Chunk {
+        LightUpdate {
+            chunk_x,
+            chunk_z: 0,
+            sky_light_mask,
+            block_light_mask: 0,
+            data: light_arrays(sky_light_mask, level),
+        }
+        .decode()
+        .unwrap()
+    }
+
+    #[test]
+    fn light_update_holds_only_sent_sections() {
+        // Sections -1, 0, and 3.
+        let light = light_update(0, 0b10011, 15);
+
+        assert_eq!(
+            light
+                .sections
+                .iter()
+                .map(|section| section.chunk_y)
+                .collect::<Vec<_>>(),
+            [0, 3]
+        );
+        assert_eq!(light.sections[1].get_sky_light(0, 0, 0), 15);
+        assert!(light.sections[1].block_light.is_none());
+    }
+
+    #[test]
+    fn light_before_chunk_is_added_to_chunk() {
+        let mut chunk_light = ChunkLight::default();
+
+        assert!(chunk_light
+            .receive_light(light_update(0, 0b10, 4))
+            .is_none());
+        assert!(chunk_light
+            .receive_light(light_update(0, 0b10, 9))
+            .is_none());
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![ChunkSection::empty(0)];
+        chunk_light.receive_chunk(&mut chunk);
+
+        assert_eq!(chunk.sections[0].get_sky_light(0, 0, 0), 9);
+    }
+
+    #[test]
+    fn light_after_chunk_is_passed_on() {
+        let mut chunk_light = ChunkLight::default();
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk_light.receive_chunk(&mut chunk);
+
+        assert!(chunk_light
+            .receive_light(light_update(0, 0b10, 4))
+            .is_some());
+        assert!(chunk_light
+            .receive_light(light_update(1, 0b10, 4))
+            .is_none());
+
+        chunk_light.clear();
+        assert!(chunk_light
+            .receive_light(light_update(0, 0b10, 4))
+            .is_none());
+    }
+
+    #[test]
+    fn light_for_reloaded_chunk_is_held_for_it() {
+        let mut chunk_light = ChunkLight::default();
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk_light.receive_chunk(&mut chunk);
+
+        // The player walks away, and then back.
+        chunk_light.unload_chunk(0, 0);
+        assert!(chunk_light
+            .receive_light(light_update(0, 0b10, 7))
+            .is_none());
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![ChunkSection::empty(0)];
+        chunk_light.receive_chunk(&mut chunk);
+
+        assert_eq!(chunk.sections[0].get_sky_light(0, 0, 0), 7);
+    }
+
+    #[test]
+    fn unloading_drops_pending_light() {
+        let mut chunk_light = ChunkLight::default();
+
+        assert!(chunk_light
+            .receive_light(light_update(0, 0b10, 7))
+            .is_none());
+        chunk_light.unload_chunk(0, 0);
+
+        assert!(chunk_light.pending.is_empty());
+        assert!(chunk_light.received.is_empty());
+    }
+}
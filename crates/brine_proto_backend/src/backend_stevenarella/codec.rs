@@ -14,6 +14,9 @@ use crate::codec::{
     UnknownPacket, HANDSHAKE_LOGIN_NEXT, HANDSHAKE_STATUS_NEXT,
 };
 
+/// The most bytes that a [`VarInt`] can take up.
+const MAX_VAR_INT_BYTES: usize = 5;
+
 /// Packet representation used by this implementation of the protocol codec.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Packet {
@@ -64,8 +67,25 @@ impl MinecraftCodec {
         direction: Direction,
         buf: impl AsRef<[u8]>,
     ) -> Result<(usize, Packet), Error> {
-        let buf = buf.as_ref();
+        let (total_packet_bytes, id, data_slice) = Self::split_packet(buf.as_ref())?;
 
+        let packet = Self::decode_packet_with_id(
+            protocol_version,
+            protocol_state,
+            direction,
+            id,
+            data_slice,
+        )?;
+
+        Ok((total_packet_bytes, packet))
+    }
+
+    /// Splits the first length-prefixed packet in the buffer into its id and
+    /// data, returning them along with the total length of the packet.
+    ///
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] if the buffer doesn't hold
+    /// the whole packet yet.
+    pub fn split_packet(buf: &[u8]) -> Result<(usize, i32, &[u8]), Error> {
         // Use a cursor so we can track how many bytes we've read
         // (VarInts have variable length).
         let mut cursor = Cursor::new(buf);
@@ -79,10 +99,7 @@ impl MinecraftCodec {
         // Ensure that there's enough data in the buffer to read the rest of the packet.
         let total_packet_bytes = length_length + length;
         if buf.len() < total_packet_bytes {
-            return Err(Error::IOError(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "Not enough bytes in buffer",
-            )));
+            return Err(not_enough_bytes());
         }
 
         // Next field is the packet id.
@@ -95,15 +112,7 @@ impl MinecraftCodec {
         let data_length = length - id_length;
         let data_slice = &buf[data_start..data_start + data_length];
 
-        let packet = Self::decode_packet_with_id(
-            protocol_version,
-            protocol_state,
-            direction,
-            id,
-            data_slice,
-        )?;
-
-        Ok((total_packet_bytes, packet))
+        Ok((total_packet_bytes, id, data_slice))
     }
 
     /// Decodes packet contents from a byte slice. Byte slice must be exactly
@@ -119,62 +128,93 @@ impl MinecraftCodec {
 
         let mut cursor = Cursor::new(buf);
 
-        let packet = packet::packet_by_id(
+        let maybe_packet = packet::packet_by_id(
             protocol_version,
             protocol_state.into(),
             direction,
             packet_id,
             &mut cursor,
-        )
-        .map(|maybe_packet| match maybe_packet {
-            Some(packet) => Packet::Known(packet),
-            None => Packet::Unknown(UnknownPacket {
-                packet_id,
-                body: Vec::from(buf),
-            }),
-        })?;
+        )?;
 
-        // All of the data should have been read.
-        assert_eq!(cursor.position() as usize, buf.len());
+        let packet = match maybe_packet {
+            Some(packet) => packet,
+            None => {
+                return Ok(Packet::Unknown(UnknownPacket {
+                    state: protocol_state,
+                    packet_id,
+                    body: Vec::from(buf),
+                }))
+            }
+        };
+
+        // All of the data should have been read. If it wasn't, the packet
+        // definition doesn't match what the server sent.
+        let bytes_read = cursor.position() as usize;
+        if bytes_read != buf.len() {
+            return Err(Error::Err(format!(
+                "Packet {:#04x} in state {:?} has {} bytes of data, but {} were decoded",
+                packet_id,
+                protocol_state,
+                buf.len(),
+                bytes_read
+            )));
+        }
 
-        Ok(packet)
+        Ok(Packet::Known(packet))
     }
 
+    /// Encodes a length-prefixed packet into the start of the buffer, returning
+    /// the total length of the packet.
+    ///
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] if the packet doesn't fit in
+    /// the buffer.
     pub fn encode_packet(
         protocol_version: i32,
         packet: &Packet,
         mut buf: impl AsMut<[u8]>,
     ) -> Result<usize, Error> {
-        match packet {
-            Packet::Known(packet) => {
-                let mut cursor = Cursor::new(buf.as_mut());
-
-                let mut id_and_data = Vec::new();
-                Self::encode_packet_id_and_data(protocol_version, packet, &mut id_and_data)?;
-                let length = id_and_data.len();
-
-                VarInt(length as i32).write_to(&mut cursor)?;
-                let length_length = cursor.position() as usize;
-
-                let total_packet_bytes = length_length + length;
-                if cursor.get_ref().len() < total_packet_bytes {
-                    return Err(Error::IOError(io::Error::new(
-                        io::ErrorKind::UnexpectedEof,
-                        "Not enough bytes in buffer",
-                    )));
-                }
-
-                cursor.write_all(&id_and_data[..])?;
+        let packet = match packet {
+            Packet::Known(packet) => packet,
+            Packet::Unknown(packet) => {
+                return Err(Error::Err(format!(
+                    "Attempted to encode unknown packet: {:?}",
+                    packet
+                )))
+            }
+        };
 
-                assert_eq!(cursor.position() as usize, total_packet_bytes);
+        let buf = buf.as_mut();
+        if buf.len() <= MAX_VAR_INT_BYTES {
+            return Err(not_enough_bytes());
+        }
 
-                Ok(total_packet_bytes)
+        // The length of the packet isn't known until it's been encoded, so the
+        // id and data are encoded after room for the longest possible length,
+        // and moved up against the length once it's known.
+        let mut cursor = Cursor::new(&mut buf[MAX_VAR_INT_BYTES..]);
+        match Self::encode_packet_id_and_data(protocol_version, packet, &mut cursor) {
+            Err(Error::IOError(io_error)) if io_error.kind() == io::ErrorKind::WriteZero => {
+                return Err(not_enough_bytes());
             }
-            Packet::Unknown(packet) => Err(Error::Err(format!(
-                "Attempted to encode unknown packet: {:?}",
-                packet
-            ))),
+            result => result?,
+        }
+        let length = cursor.position() as usize;
+
+        // A packet that fills the buffer exactly might have been cut short by
+        // a write that didn't report it, so ask for more room to be sure.
+        if MAX_VAR_INT_BYTES + length == buf.len() {
+            return Err(not_enough_bytes());
         }
+
+        let mut length_bytes = [0; MAX_VAR_INT_BYTES];
+        let mut cursor = Cursor::new(&mut length_bytes[..]);
+        VarInt(length as i32).write_to(&mut cursor)?;
+        let length_length = cursor.position() as usize;
+
+        buf.copy_within(MAX_VAR_INT_BYTES..MAX_VAR_INT_BYTES + length, length_length);
+        buf[..length_length].copy_from_slice(&length_bytes[..length_length]);
+
+        Ok(length_length + length)
     }
 
     pub fn encode_packet_id_and_data(
@@ -214,6 +254,13 @@ impl MinecraftCodec {
     }
 }
 
+fn not_enough_bytes() -> Error {
+    Error::IOError(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "Not enough bytes in buffer",
+    ))
+}
+
 impl<T> IntoDecodeResult for Result<(usize, T), Error> {
     type Item = T;
     type Error = Error;
@@ -303,18 +350,28 @@ impl Decode for MinecraftClientCodec<MinecraftCodec> {
     type Error = Error;
 
     fn decode(&mut self, buf: &mut [u8]) -> (usize, DecodeResult<Packet, Error>) {
-        let result = MinecraftCodec::decode_packet(
+        let (length, packet_id, data) = match MinecraftCodec::split_packet(buf) {
+            Ok(split) => split,
+            Err(err) => return Err::<(usize, Packet), _>(err).into_decode_result(),
+        };
+
+        let result = MinecraftCodec::decode_packet_with_id(
             self.protocol_version(),
             self.protocol_state(),
             Direction::Clientbound,
-            buf,
+            packet_id,
+            data,
         );
 
-        if let Ok((_, ref packet)) = result {
-            self.react_to_packet(packet);
+        match result {
+            Ok(packet) => {
+                self.react_to_packet(&packet);
+                (length, DecodeResult::Ok(packet))
+            }
+            // The whole packet is skipped so that decoding can pick up again at
+            // the next one.
+            Err(err) => (length, DecodeResult::Err(err)),
         }
-
-        result.into_decode_result()
     }
 }
 
@@ -407,6 +464,89 @@ mod test {
         .await
     }
 
+    fn login_start(username: &str) -> packet::Packet {
+        packet::Packet::LoginStart(Box::new(packet::login::serverbound::LoginStart {
+            username: String::from(username),
+        }))
+    }
+
+    #[test]
+    fn long_packet_is_encoded_in_place() {
+        // Long enough for the length to take two bytes.
+        let packet = login_start(&"a".repeat(200));
+        let mut body = Vec::new();
+        MinecraftCodec::encode_packet_data(&packet, &mut body).unwrap();
+        let expected = encode_packet_from_file(0x00, &body);
+
+        let mut buf = [0xff; 1024];
+        let length =
+            MinecraftCodec::encode_packet(PROTOCOL_VERSION, &Packet::from(packet), &mut buf[..])
+                .unwrap();
+
+        assert_eq!(&buf[..length], &expected[..]);
+    }
+
+    #[test]
+    fn packet_too_big_for_buffer() {
+        let packet = Packet::from(login_start("Username"));
+
+        for len in [0, 5, 10] {
+            let mut buf = vec![0; len];
+            let result = MinecraftCodec::encode_packet(PROTOCOL_VERSION, &packet, &mut buf[..]);
+
+            assert!(
+                matches!(result.into_encode_result(len), EncodeResult::Overflow(_)),
+                "buffer of {} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn bad_packet_is_skipped() {
+        let mut codec = MinecraftClientCodec::new(MinecraftProtocolState::Login);
+
+        let body = include_bytes!("../../test/packet-data/login/login_success.dat");
+        let mut too_long_body = body.to_vec();
+        too_long_body.push(0);
+
+        let mut bytes = encode_packet_from_file(0x02, &too_long_body);
+        let bad_packet_len = bytes.len();
+        bytes.extend(encode_packet_from_file(0x02, body));
+
+        let (consumed, result) = codec.decode(&mut bytes[..]);
+        assert_eq!(consumed, bad_packet_len);
+        assert!(matches!(result, DecodeResult::Err(_)));
+
+        let (consumed, result) = codec.decode(&mut bytes[bad_packet_len..]);
+        assert_eq!(consumed, bytes.len() - bad_packet_len);
+        assert!(matches!(
+            result,
+            DecodeResult::Ok(Packet::Known(packet::Packet::LoginSuccess_String(_)))
+        ));
+    }
+
+    #[test]
+    fn unknown_packet_remembers_state() {
+        let packet = MinecraftCodec::decode_packet_with_id(
+            PROTOCOL_VERSION,
+            MinecraftProtocolState::Play,
+            Direction::Clientbound,
+            0x7f,
+            [1, 2, 3],
+        )
+        .unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::Unknown(UnknownPacket {
+                state: MinecraftProtocolState::Play,
+                packet_id: 0x7f,
+                body: vec![1, 2, 3],
+            })
+        );
+    }
+
     #[test]
     fn packet_size() {
         assert_eq!(std::mem::size_of::<packet::Packet>(), 16);
@@ -0,0 +1,310 @@
+//! Decoding of entity spawn, movement, and removal packets.
+//!
+//! See these pages for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Spawn_Object>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Spawn_Mob>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Entity_Relative_Move>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Entity_Teleport>
+
+use bevy::prelude::*;
+use steven_protocol::protocol::Serializable;
+
+use brine_net::CodecReader;
+use brine_proto::event::{
+    clientbound::{
+        EntitiesDestroyed, EntityKind, EntityMoved, EntityMovement, EntitySpawned, EntityVelocity,
+    },
+    Uuid,
+};
+
+use super::codec::{packet, Packet, ProtocolCodec};
+
+/// Relative moves are sent as fixed-point numbers with 12 fractional bits
+/// (`(current * 32 - previous * 32) * 128`).
+const RELATIVE_MOVE_SCALE: f32 = 1.0 / 4096.0;
+
+/// Velocities are sent in units of 1/8000 of a block per tick.
+const VELOCITY_SCALE: f32 = 1.0 / 8000.0;
+
+/// Converts a relative move packet's deltas to blocks.
+pub fn decode_relative_move(delta_x: i16, delta_y: i16, delta_z: i16) -> Vec3 {
+    Vec3::new(delta_x as f32, delta_y as f32, delta_z as f32) * RELATIVE_MOVE_SCALE
+}
+
+/// Converts a velocity packet's components to blocks per tick.
+pub fn decode_velocity(velocity_x: i16, velocity_y: i16, velocity_z: i16) -> Vec3 {
+    Vec3::new(velocity_x as f32, velocity_y as f32, velocity_z as f32) * VELOCITY_SCALE
+}
+
+/// Converts an angle in steps of 1/256 of a full turn to degrees.
+pub fn decode_angle(angle: i8) -> f32 {
+    angle as u8 as f32 * (360.0 / 256.0)
+}
+
+/// One of the entity events that can be decoded from a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityEvent {
+    Spawned(EntitySpawned),
+    Moved(EntityMoved),
+    Velocity(EntityVelocity),
+    Destroyed(EntitiesDestroyed),
+}
+
+/// Decodes an entity event from a packet, if it's one of the entity packets
+/// supported by this backend.
+pub fn get_entity_event_from_packet(packet: &Packet) -> Option<EntityEvent> {
+    let packet = match packet {
+        Packet::Known(packet) => packet,
+        Packet::Unknown(_) => return None,
+    };
+
+    let event = match packet {
+        packet::Packet::SpawnPlayer_f64(spawn) => EntityEvent::Spawned(EntitySpawned {
+            entity_id: spawn.entity_id.0,
+            kind: EntityKind::Player,
+            uuid: decode_uuid(&spawn.uuid)?,
+            position: Vec3::new(spawn.x as f32, spawn.y as f32, spawn.z as f32),
+            yaw: decode_angle(spawn.yaw),
+            pitch: decode_angle(spawn.pitch),
+            velocity: Vec3::ZERO,
+        }),
+
+        packet::Packet::SpawnObject_VarInt(spawn) => EntityEvent::Spawned(EntitySpawned {
+            entity_id: spawn.entity_id.0,
+            kind: EntityKind::Object {
+                type_id: spawn.ty.0,
+                data: spawn.data,
+            },
+            uuid: decode_uuid(&spawn.uuid)?,
+            position: Vec3::new(spawn.x as f32, spawn.y as f32, spawn.z as f32),
+            yaw: decode_angle(spawn.yaw),
+            pitch: decode_angle(spawn.pitch),
+            velocity: decode_velocity(spawn.velocity_x, spawn.velocity_y, spawn.velocity_z),
+        }),
+
+        packet::Packet::SpawnMob_WithMeta(spawn) => EntityEvent::Spawned(EntitySpawned {
+            entity_id: spawn.entity_id.0,
+            kind: EntityKind::Mob {
+                type_id: spawn.ty.0,
+            },
+            uuid: decode_uuid(&spawn.uuid)?,
+            position: Vec3::new(spawn.x as f32, spawn.y as f32, spawn.z as f32),
+            yaw: decode_angle(spawn.yaw),
+            pitch: decode_angle(spawn.pitch),
+            velocity: decode_velocity(spawn.velocity_x, spawn.velocity_y, spawn.velocity_z),
+        }),
+
+        packet::Packet::EntityMove_i16(entity_move) => EntityEvent::Moved(EntityMoved {
+            entity_id: entity_move.entity_id.0,
+            movement: EntityMovement::Relative(decode_relative_move(
+                entity_move.delta_x,
+                entity_move.delta_y,
+                entity_move.delta_z,
+            )),
+            rotation: None,
+            on_ground: entity_move.on_ground,
+        }),
+
+        packet::Packet::EntityLookAndMove_i16(entity_move) => EntityEvent::Moved(EntityMoved {
+            entity_id: entity_move.entity_id.0,
+            movement: EntityMovement::Relative(decode_relative_move(
+                entity_move.delta_x,
+                entity_move.delta_y,
+                entity_move.delta_z,
+            )),
+            rotation: Some((
+                decode_angle(entity_move.yaw),
+                decode_angle(entity_move.pitch),
+            )),
+            on_ground: entity_move.on_ground,
+        }),
+
+        packet::Packet::EntityTeleport_f64(teleport) => EntityEvent::Moved(EntityMoved {
+            entity_id: teleport.entity_id.0,
+            movement: EntityMovement::Absolute(Vec3::new(
+                teleport.x as f32,
+                teleport.y as f32,
+                teleport.z as f32,
+            )),
+            rotation: Some((decode_angle(teleport.yaw), decode_angle(teleport.pitch))),
+            on_ground: teleport.on_ground,
+        }),
+
+        packet::Packet::EntityVelocity(velocity) => EntityEvent::Velocity(EntityVelocity {
+            entity_id: velocity.entity_id.0,
+            velocity: decode_velocity(
+                velocity.velocity_x,
+                velocity.velocity_y,
+                velocity.velocity_z,
+            ),
+        }),
+
+        packet::Packet::EntityDestroy(destroy) => EntityEvent::Destroyed(EntitiesDestroyed {
+            entity_ids: destroy.entity_ids.data.iter().map(|id| id.0).collect(),
+        }),
+
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+pub(crate) fn decode_uuid(uuid: &impl Serializable) -> Option<Uuid> {
+    // The UUID's fields aren't public, so round-trip it through its encoding.
+    let mut uuid_bytes = Vec::with_capacity(16);
+    uuid.write_to(&mut uuid_bytes).ok()?;
+    Some(Uuid::from_bytes(uuid_bytes.try_into().ok()?))
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_entity_packets);
+}
+
+/// System that listens for entity packets and sends the corresponding entity
+/// events to the client application.
+fn handle_entity_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut spawned_events: EventWriter<EntitySpawned>,
+    mut moved_events: EventWriter<EntityMoved>,
+    mut velocity_events: EventWriter<EntityVelocity>,
+    mut destroyed_events: EventWriter<EntitiesDestroyed>,
+) {
+    for packet in packet_reader.iter() {
+        match get_entity_event_from_packet(packet) {
+            Some(EntityEvent::Spawned(event)) => {
+                trace!("{:?}", event);
+                spawned_events.send(event);
+            }
+            Some(EntityEvent::Moved(event)) => moved_events.send(event),
+            Some(EntityEvent::Velocity(event)) => velocity_events.send(event),
+            Some(EntityEvent::Destroyed(event)) => {
+                trace!("{:?}", event);
+                destroyed_events.send(event);
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::codec::MinecraftProtocolState;
+
+    use super::{
+        super::codec::{Direction, MinecraftCodec},
+        *,
+    };
+
+    // Packet ids in 1.14.4.
+    const SPAWN_OBJECT: i32 = 0x00;
+    const SPAWN_MOB: i32 = 0x03;
+    const ENTITY_RELATIVE_MOVE: i32 = 0x28;
+
+    fn decode_entity_event(packet_id: i32, body: &[u8]) -> Option<EntityEvent> {
+        let packet = MinecraftCodec::decode_packet_with_id(
+            498,
+            MinecraftProtocolState::Play,
+            Direction::Clientbound,
+            packet_id,
+            body,
+        )
+        .unwrap();
+
+        get_entity_event_from_packet(&packet)
+    }
+
+    #[test]
+    fn spawn_object() {
+        let event = decode_entity_event(
+            SPAWN_OBJECT,
+            include_bytes!("../../test/packet-data/play/spawn_object.dat"),
+        );
+
+        assert_eq!(
+            event,
+            Some(EntityEvent::Spawned(EntitySpawned {
+                entity_id: 206,
+                // An arrow, shot by entity 190.
+                kind: EntityKind::Object {
+                    type_id: 2,
+                    data: 191,
+                },
+                uuid: Uuid::from_u128(0x7d1c2f3e_4a5b_4c6d_8e7f_9a0b1c2d3e4f),
+                position: Vec3::new(-12.5, 65.0, 300.25),
+                yaw: 90.0,
+                pitch: 315.0,
+                velocity: Vec3::new(1.0, 0.5, 0.0),
+            }))
+        );
+    }
+
+    #[test]
+    fn spawn_mob() {
+        let event = decode_entity_event(
+            SPAWN_MOB,
+            include_bytes!("../../test/packet-data/play/spawn_mob.dat"),
+        );
+
+        assert_eq!(
+            event,
+            Some(EntityEvent::Spawned(EntitySpawned {
+                entity_id: 205,
+                // A creeper.
+                kind: EntityKind::Mob { type_id: 11 },
+                uuid: Uuid::from_u128(0x0f1e2d3c_4b5a_4697_a8b9_cadbecfd0e1f),
+                position: Vec3::new(8.5, 64.0, -3.5),
+                yaw: 180.0,
+                pitch: 0.0,
+                velocity: Vec3::ZERO,
+            }))
+        );
+    }
+
+    #[test]
+    fn entity_relative_move() {
+        let event = decode_entity_event(
+            ENTITY_RELATIVE_MOVE,
+            include_bytes!("../../test/packet-data/play/entity_relative_move.dat"),
+        );
+
+        assert_eq!(
+            event,
+            Some(EntityEvent::Moved(EntityMoved {
+                entity_id: 205,
+                movement: EntityMovement::Relative(Vec3::new(1.0, 0.0, -0.5)),
+                rotation: None,
+                on_ground: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn relative_moves_are_fixed_point() {
+        assert_eq!(
+            decode_relative_move(4096, -4096, 0),
+            Vec3::new(1.0, -1.0, 0.0)
+        );
+        assert_eq!(
+            decode_relative_move(128, 0, -2048),
+            Vec3::new(1.0 / 32.0, 0.0, -0.5)
+        );
+
+        // A relative move can cover at most 8 blocks in either direction.
+        assert!(decode_relative_move(i16::MAX, 0, 0).x < 8.0);
+        assert_eq!(decode_relative_move(i16::MIN, 0, 0).x, -8.0);
+    }
+
+    #[test]
+    fn velocities_are_in_eight_thousandths() {
+        assert_eq!(decode_velocity(8000, -4000, 0), Vec3::new(1.0, -0.5, 0.0));
+    }
+
+    #[test]
+    fn angles_wrap_around() {
+        assert_eq!(decode_angle(0), 0.0);
+        assert_eq!(decode_angle(64), 90.0);
+        assert_eq!(decode_angle(-128), 180.0);
+        assert_eq!(decode_angle(-64), 270.0);
+    }
+}
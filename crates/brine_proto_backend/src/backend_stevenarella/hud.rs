@@ -0,0 +1,332 @@
+//! Decoding of the packets that show things on the player's screen: boss bars,
+//! titles, and the action bar.
+//!
+//! See these pages for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Boss_Bar>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Title>
+
+use bevy::prelude::*;
+use steven_protocol::protocol::Serializable;
+
+use brine_net::CodecReader;
+use brine_proto::{
+    chat::ChatComponent,
+    event::clientbound::{
+        BossBar, BossBarAction, BossBarColor, BossBarDivision, BossBarFlags, Title, TitleAction,
+    },
+};
+
+use super::{
+    codec::{packet, Packet, ProtocolCodec},
+    entities::decode_uuid,
+};
+
+/// One of the HUD events that can be decoded from a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HudEvent {
+    BossBar(BossBar),
+    Title(Title),
+}
+
+/// Decodes a HUD event from a packet, if it's a BossBar or Title packet.
+///
+/// Packets with an action, color, or division that isn't known are logged and
+/// ignored.
+pub fn get_hud_event_from_packet(packet: &Packet) -> Option<HudEvent> {
+    let packet = match packet {
+        Packet::Known(packet) => packet,
+        Packet::Unknown(_) => return None,
+    };
+
+    match packet {
+        packet::Packet::BossBar(boss_bar) => decode_boss_bar(boss_bar).map(HudEvent::BossBar),
+        packet::Packet::Title(title) => decode_title(title).map(HudEvent::Title),
+        _ => None,
+    }
+}
+
+fn decode_boss_bar(boss_bar: &packet::play::clientbound::BossBar) -> Option<BossBar> {
+    let action = match boss_bar.action.0 {
+        0 => BossBarAction::Add {
+            title: convert_component(&boss_bar.title)?,
+            health: boss_bar.health,
+            color: decode_color(boss_bar.color.0)?,
+            division: decode_division(boss_bar.style.0)?,
+            flags: BossBarFlags::from_bits(boss_bar.flags),
+        },
+        1 => BossBarAction::Remove,
+        2 => BossBarAction::UpdateHealth(boss_bar.health),
+        3 => BossBarAction::UpdateTitle(convert_component(&boss_bar.title)?),
+        4 => BossBarAction::UpdateStyle {
+            color: decode_color(boss_bar.color.0)?,
+            division: decode_division(boss_bar.style.0)?,
+        },
+        5 => BossBarAction::UpdateFlags(BossBarFlags::from_bits(boss_bar.flags)),
+        action => {
+            warn!("Unknown boss bar action {}", action);
+            return None;
+        }
+    };
+
+    Some(BossBar {
+        uuid: decode_uuid(&boss_bar.uuid)?,
+        action,
+    })
+}
+
+fn decode_title(title: &packet::play::clientbound::Title) -> Option<Title> {
+    let action = match title.action.0 {
+        0 => TitleAction::SetTitle(convert_component(title.title.as_ref()?)?),
+        1 => TitleAction::SetSubtitle(convert_component(title.sub_title.as_ref()?)?),
+        2 => TitleAction::SetActionBar(convert_component(title.action_bar.as_ref()?)?),
+        3 => TitleAction::SetTimes {
+            fade_in: title.fade_in?,
+            stay: title.fade_stay?,
+            fade_out: title.fade_out?,
+        },
+        4 => TitleAction::Hide,
+        5 => TitleAction::Reset,
+        action => {
+            warn!("Unknown title action {}", action);
+            return None;
+        }
+    };
+
+    Some(Title { action })
+}
+
+fn decode_color(id: i32) -> Option<BossBarColor> {
+    let color = BossBarColor::from_id(id);
+    if color.is_none() {
+        warn!("Unknown boss bar color {}", id);
+    }
+    color
+}
+
+fn decode_division(id: i32) -> Option<BossBarDivision> {
+    let division = BossBarDivision::from_id(id);
+    if division.is_none() {
+        warn!("Unknown boss bar division {}", id);
+    }
+    division
+}
+
+/// Converts a chat component that was decoded by `steven_protocol` by encoding
+/// it again and parsing the JSON with [`ChatComponent::from_json`].
+pub(crate) fn convert_component(component: &impl Serializable) -> Option<ChatComponent> {
+    let mut bytes = Vec::new();
+    component.write_to(&mut bytes).ok()?;
+    let json = String::read_from(&mut &bytes[..]).ok()?;
+
+    match ChatComponent::from_json(&json) {
+        Ok(component) => Some(component),
+        Err(e) => {
+            warn!("Invalid chat component {:?}: {}", json, e);
+            None
+        }
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_hud_packets);
+}
+
+/// System that listens for HUD packets and sends the corresponding events to
+/// the client application.
+fn handle_hud_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut boss_bar_events: EventWriter<BossBar>,
+    mut title_events: EventWriter<Title>,
+) {
+    for packet in packet_reader.iter() {
+        match get_hud_event_from_packet(packet) {
+            Some(HudEvent::BossBar(event)) => {
+                trace!("{:?}", event);
+                boss_bar_events.send(event);
+            }
+            Some(HudEvent::Title(event)) => {
+                trace!("{:?}", event);
+                title_events.send(event);
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use steven_protocol::{
+        format::Component,
+        protocol::{VarInt, UUID},
+    };
+
+    use brine_proto::event::Uuid;
+
+    use super::*;
+
+    const UUID_BYTES: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+    fn component(text: &str) -> Component {
+        let mut bytes = Vec::new();
+        format!(r#"{{"text":"{}"}}"#, text)
+            .write_to(&mut bytes)
+            .unwrap();
+        Component::read_from(&mut &bytes[..]).unwrap()
+    }
+
+    fn boss_bar(action: i32, health: f32, color: i32, style: i32, flags: u8) -> Packet {
+        Packet::Known(packet::Packet::BossBar(Box::new(
+            packet::play::clientbound::BossBar {
+                uuid: UUID::read_from(&mut &UUID_BYTES[..]).unwrap(),
+                action: VarInt(action),
+                title: component("Ender Dragon"),
+                health,
+                color: VarInt(color),
+                style: VarInt(style),
+                flags,
+            },
+        )))
+    }
+
+    fn boss_bar_event(action: BossBarAction) -> Option<HudEvent> {
+        Some(HudEvent::BossBar(BossBar {
+            uuid: Uuid::from_bytes(UUID_BYTES),
+            action,
+        }))
+    }
+
+    fn title(action: i32) -> packet::play::clientbound::Title {
+        packet::play::clientbound::Title {
+            action: VarInt(action),
+            title: None,
+            sub_title: None,
+            action_bar: None,
+            fade_in: None,
+            fade_stay: None,
+            fade_out: None,
+        }
+    }
+
+    fn title_event(action: TitleAction) -> Option<HudEvent> {
+        Some(HudEvent::Title(Title { action }))
+    }
+
+    fn decode_title_packet(title: packet::play::clientbound::Title) -> Option<HudEvent> {
+        get_hud_event_from_packet(&Packet::Known(packet::Packet::Title(Box::new(title))))
+    }
+
+    #[test]
+    fn boss_bar_add() {
+        assert_eq!(
+            get_hud_event_from_packet(&boss_bar(0, 0.5, 5, 2, 0x1 | 0x2)),
+            boss_bar_event(BossBarAction::Add {
+                title: ChatComponent::text("Ender Dragon"),
+                health: 0.5,
+                color: BossBarColor::Purple,
+                division: BossBarDivision::Notches10,
+                flags: BossBarFlags {
+                    darken_sky: true,
+                    dragon_bar: true,
+                    create_fog: false,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn boss_bar_updates() {
+        assert_eq!(
+            get_hud_event_from_packet(&boss_bar(1, 0.0, 0, 0, 0)),
+            boss_bar_event(BossBarAction::Remove)
+        );
+        assert_eq!(
+            get_hud_event_from_packet(&boss_bar(2, 0.25, 0, 0, 0)),
+            boss_bar_event(BossBarAction::UpdateHealth(0.25))
+        );
+        assert_eq!(
+            get_hud_event_from_packet(&boss_bar(3, 0.0, 0, 0, 0)),
+            boss_bar_event(BossBarAction::UpdateTitle(ChatComponent::text(
+                "Ender Dragon"
+            )))
+        );
+        assert_eq!(
+            get_hud_event_from_packet(&boss_bar(4, 0.0, 2, 4, 0)),
+            boss_bar_event(BossBarAction::UpdateStyle {
+                color: BossBarColor::Red,
+                division: BossBarDivision::Notches20,
+            })
+        );
+        assert_eq!(
+            get_hud_event_from_packet(&boss_bar(5, 0.0, 0, 0, 0x4)),
+            boss_bar_event(BossBarAction::UpdateFlags(BossBarFlags {
+                create_fog: true,
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn unknown_boss_bar_values_are_ignored() {
+        assert_eq!(get_hud_event_from_packet(&boss_bar(6, 0.0, 0, 0, 0)), None);
+        assert_eq!(get_hud_event_from_packet(&boss_bar(0, 1.0, 7, 0, 0)), None);
+        assert_eq!(get_hud_event_from_packet(&boss_bar(4, 1.0, 0, 5, 0)), None);
+    }
+
+    #[test]
+    fn title_text() {
+        let mut set_title = title(0);
+        set_title.title = Some(component("Welcome"));
+        assert_eq!(
+            decode_title_packet(set_title),
+            title_event(TitleAction::SetTitle(ChatComponent::text("Welcome")))
+        );
+
+        let mut set_subtitle = title(1);
+        set_subtitle.sub_title = Some(component("to the server"));
+        assert_eq!(
+            decode_title_packet(set_subtitle),
+            title_event(TitleAction::SetSubtitle(ChatComponent::text(
+                "to the server"
+            )))
+        );
+
+        let mut set_action_bar = title(2);
+        set_action_bar.action_bar = Some(component("Hello"));
+        assert_eq!(
+            decode_title_packet(set_action_bar),
+            title_event(TitleAction::SetActionBar(ChatComponent::text("Hello")))
+        );
+    }
+
+    #[test]
+    fn title_times_and_clearing() {
+        let mut set_times = title(3);
+        set_times.fade_in = Some(5);
+        set_times.fade_stay = Some(40);
+        set_times.fade_out = Some(10);
+        assert_eq!(
+            decode_title_packet(set_times),
+            title_event(TitleAction::SetTimes {
+                fade_in: 5,
+                stay: 40,
+                fade_out: 10,
+            })
+        );
+
+        assert_eq!(
+            decode_title_packet(title(4)),
+            title_event(TitleAction::Hide)
+        );
+        assert_eq!(
+            decode_title_packet(title(5)),
+            title_event(TitleAction::Reset)
+        );
+    }
+
+    #[test]
+    fn title_without_its_field_is_ignored() {
+        assert_eq!(decode_title_packet(title(0)), None);
+        assert_eq!(decode_title_packet(title(6)), None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Decoding of inventory packets, and switching the held item.
+//!
+//! See these pages for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Slot_Data&oldid=15346>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Window_Items>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Set_Slot>
+
+use bevy::prelude::*;
+use steven_protocol::protocol::{Error, Serializable, VarInt};
+
+use brine_chunk::nbt;
+use brine_net::{CodecReader, CodecWriter};
+use brine_proto::event::{
+    clientbound::{HeldItemChanged, InventoryChanged, SlotChanged},
+    serverbound::HeldItemChange,
+    ItemStack,
+};
+
+use super::codec::{packet, Packet, ProtocolCodec};
+
+/// Decodes the 1.14.4 encoding of a slot: a present flag, followed by the item
+/// id, count, and NBT data if the slot isn't empty.
+///
+/// The NBT data is not decoded, only checked for validity and copied as-is.
+pub fn decode_slot(buf: &mut &[u8]) -> Result<Option<ItemStack>, Error> {
+    let present = bool::read_from(buf)?;
+    if !present {
+        return Ok(None);
+    }
+
+    let item_id = VarInt::read_from(buf)?.0;
+    let count = u8::read_from(buf)?;
+
+    // Parse the tag to find out where it ends.
+    let tag_bytes = *buf;
+    let tag = nbt::Tag::read_optional_named(buf)
+        .map_err(|e| Error::Err(format!("Invalid slot NBT: {}", e)))?;
+    let tag_length = tag_bytes.len() - buf.len();
+
+    Ok(Some(ItemStack {
+        item_id,
+        count,
+        nbt: tag.map(|_| tag_bytes[..tag_length].to_vec()),
+    }))
+}
+
+/// Converts a slot that was decoded by `steven_protocol` by encoding it again
+/// and decoding it with [`decode_slot`].
+fn convert_slot(slot: &impl Serializable) -> Result<Option<ItemStack>, Error> {
+    let mut bytes = Vec::new();
+    slot.write_to(&mut bytes)?;
+    decode_slot(&mut &bytes[..])
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_inventory_packets);
+    app.add_system(send_held_item_change);
+}
+
+/// System that listens for inventory packets and sends the corresponding
+/// inventory events to the client application.
+fn handle_inventory_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut inventory_events: EventWriter<InventoryChanged>,
+    mut slot_events: EventWriter<SlotChanged>,
+    mut held_item_events: EventWriter<HeldItemChanged>,
+) {
+    for packet in packet_reader.iter() {
+        match packet {
+            Packet::Known(packet::Packet::WindowItems(window_items)) => {
+                let slots: Result<Vec<_>, _> =
+                    window_items.items.data.iter().map(convert_slot).collect();
+
+                match slots {
+                    Ok(slots) => inventory_events.send(InventoryChanged {
+                        window_id: window_items.id,
+                        slots,
+                    }),
+                    Err(e) => error!("Failed to decode window items: {}", e),
+                }
+            }
+
+            Packet::Known(packet::Packet::WindowSetSlot(set_slot)) => {
+                match convert_slot(&set_slot.item) {
+                    Ok(item) => slot_events.send(SlotChanged {
+                        window_id: set_slot.id,
+                        slot: set_slot.slot,
+                        item,
+                    }),
+                    Err(e) => error!("Failed to decode slot: {}", e),
+                }
+            }
+
+            Packet::Known(packet::Packet::SetCurrentHotbarSlot(hotbar_slot)) => {
+                held_item_events.send(HeldItemChanged {
+                    slot: hotbar_slot.slot,
+                });
+            }
+
+            _ => {}
+        }
+    }
+}
+
+/// System that tells the server when the client switches hotbar slots.
+fn send_held_item_change(
+    mut held_item_events: EventReader<HeldItemChange>,
+    mut packet_writer: CodecWriter<ProtocolCodec>,
+) {
+    for event in held_item_events.iter() {
+        packet_writer.send(Packet::Known(packet::Packet::HeldItemChange(Box::new(
+            packet::play::serverbound::HeldItemChange {
+                slot: event.slot as i16,
+            },
+        ))));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_whole_slot(bytes: &[u8]) -> Option<ItemStack> {
+        let mut buf = bytes;
+        let slot = decode_slot(&mut buf).unwrap();
+        assert!(buf.is_empty(), "{} bytes left over", buf.len());
+        slot
+    }
+
+    #[test]
+    fn empty_slot() {
+        let slot = decode_whole_slot(include_bytes!("../../test/packet-data/play/slot_empty.dat"));
+
+        assert_eq!(slot, None);
+    }
+
+    #[test]
+    fn simple_stack() {
+        let slot = decode_whole_slot(include_bytes!("../../test/packet-data/play/slot_stone.dat"));
+
+        assert_eq!(
+            slot,
+            Some(ItemStack {
+                item_id: 1,
+                count: 64,
+                nbt: None,
+            })
+        );
+    }
+
+    #[test]
+    fn enchanted_item() {
+        let bytes = include_bytes!("../../test/packet-data/play/slot_enchanted.dat");
+        let slot = decode_whole_slot(bytes).unwrap();
+
+        assert_eq!(slot.item_id, 598);
+        assert_eq!(slot.count, 1);
+
+        // The NBT is everything after the present flag, two byte VarInt, and
+        // count.
+        let nbt = slot.nbt.unwrap();
+        assert_eq!(nbt, &bytes[4..]);
+
+        let (_, tag) = nbt::Tag::read_named(&mut &nbt[..]).unwrap();
+        let enchantment = &tag.get("Enchantments").unwrap().as_list().unwrap()[0];
+        assert_eq!(
+            enchantment.get("id").unwrap().as_str(),
+            Some("minecraft:sharpness")
+        );
+    }
+
+    #[test]
+    fn truncated_slot() {
+        let bytes = include_bytes!("../../test/packet-data/play/slot_enchanted.dat");
+
+        assert!(decode_slot(&mut &bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_slot(&mut &bytes[..3]).is_err());
+    }
+}
@@ -22,29 +22,44 @@
 //!   3. C -> S: Login Start
 //!   4. S -> C: Login Success
 //!
+//!   An online-mode server sends an Encryption Request instead of Login
+//!   Success. The client consults the [`Authenticator`] in the
+//!   [`LoginAuthenticator`] resource, but encryption isn't supported yet, so it
+//!   disconnects either way.
+//!
 //! * Play
 //!   * Periodic KeepAlive packets
 //!   * Other play packets
 //!
+//! If the server stops sending KeepAlive packets for longer than the
+//! [`KeepAliveTimeout`], the connection is assumed to be dead and the client
+//! disconnects.
+//!
 //! See these pages for reference:
 //!
 //! * <https://wiki.vg/Protocol#Handshaking>
 //! * <https://wiki.vg/Protocol#Login>
 //! * <https://wiki.vg/Protocol_FAQ#What.27s_the_normal_login_sequence_for_a_client.3F>
 
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use bevy::prelude::*;
 use steven_protocol::protocol::{Serializable, VarInt};
 
 use brine_net::{CodecReader, CodecWriter, NetworkError, NetworkEvent, NetworkResource};
-use brine_proto::event::{
-    clientbound::{Disconnect, LoginSuccess},
-    serverbound::Login,
-    Uuid,
+use brine_proto::{
+    event::{
+        clientbound::{Disconnect, LoginSuccess},
+        serverbound::Login,
+        Uuid,
+    },
+    LoginAuthenticator,
 };
 
-use crate::codec::{HANDSHAKE_LOGIN_NEXT, HANDSHAKE_STATUS_NEXT};
+use crate::{
+    codec::{HANDSHAKE_LOGIN_NEXT, HANDSHAKE_STATUS_NEXT},
+    version::VersionAdapter,
+};
 
 use super::codec::{packet, Packet, ProtocolCodec};
 
@@ -70,8 +85,22 @@ struct LoginResource {
     server_addr: String,
 }
 
+/// Resource that sets how long the client waits for a KeepAlive packet from
+/// the server before giving up on the connection.
+///
+/// Defaults to 30 seconds, like the vanilla client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAliveTimeout(pub Duration);
+
+impl Default for KeepAliveTimeout {
+    fn default() -> Self {
+        Self(Duration::from_secs(30))
+    }
+}
+
 pub(crate) fn build(app: &mut App) {
     app.add_state(LoginState::Idle);
+    app.init_resource::<KeepAliveTimeout>();
 
     protocol_discovery::build(app);
     login::build(app);
@@ -159,8 +188,9 @@ mod protocol_discovery {
         net_resource: Res<NetworkResource<ProtocolCodec>>,
     ) {
         for event in network_events.iter() {
-            if let NetworkEvent::Connected = event {
-                debug!("Connection established. Sending Handshake and StatusRequest packets.");
+            if let NetworkEvent::Connected { target } = event {
+                debug!("Connection established with {}.", target);
+                debug!("Sending Handshake and StatusRequest packets.");
 
                 let handshake = make_handshake_packet(
                     net_resource.codec().protocol_version(),
@@ -185,6 +215,7 @@ mod protocol_discovery {
         mut packet_writer: CodecWriter<ProtocolCodec>,
         mut login_state: ResMut<State<LoginState>>,
         net_resource: Res<NetworkResource<ProtocolCodec>>,
+        mut commands: Commands,
     ) {
         for packet in packet_reader.iter() {
             if let Packet::Known(packet::Packet::StatusResponse(_)) = packet {
@@ -198,6 +229,8 @@ mod protocol_discovery {
                     protocol_version
                 );
 
+                commands.insert_resource(VersionAdapter::for_protocol_version(protocol_version));
+
                 debug!("Sending StatusPing.");
                 let status_ping = Packet::Known(packet::Packet::StatusPing(Box::new(
                     packet::status::serverbound::StatusPing::default(),
@@ -261,7 +294,7 @@ mod login {
         net_resource: Res<NetworkResource<ProtocolCodec>>,
     ) {
         for event in network_events.iter() {
-            if let NetworkEvent::Connected = event {
+            if let NetworkEvent::Connected { .. } = event {
                 debug!("Connection established. Sending Handshake and LoginStart packets.");
 
                 let protocol_version = net_resource.codec().protocol_version();
@@ -281,18 +314,45 @@ mod login {
         }
     }
 
+    /// Returns the reason to disconnect with when the server asks the client
+    /// to authenticate.
+    fn on_encryption_request(authenticator: &LoginAuthenticator) -> String {
+        let authenticator = &authenticator.0;
+
+        match (authenticator.profile(), authenticator.access_token()) {
+            (Some(profile), Some(_access_token)) => {
+                debug!("Authenticated as {} ({})", profile.name, profile.uuid);
+                String::from("Login failed: encryption is not supported yet")
+            }
+            _ => String::from(
+                "Login failed: server is in online mode, but client is not authenticated",
+            ),
+        }
+    }
+
     /// System that listens for either a LoginSuccess or LoginDisconnect packet and
     /// emits the proper event in response.
+    ///
+    /// An EncryptionRequest packet is answered by disconnecting (see
+    /// [`on_encryption_request`]).
     fn await_login_success(
         mut packet_reader: CodecReader<ProtocolCodec>,
         mut login_success_events: EventWriter<LoginSuccess>,
         mut disconnect_events: EventWriter<Disconnect>,
         mut login_state: ResMut<State<LoginState>>,
+        mut net_resource: ResMut<NetworkResource<ProtocolCodec>>,
+        authenticator: Res<LoginAuthenticator>,
     ) {
+        let protocol_version = net_resource.codec().protocol_version();
+
         let mut on_login_success = |username: String, uuid: Uuid| {
             info!("Successfully logged in to server.");
 
-            login_success_events.send(LoginSuccess { username, uuid });
+            login_success_events.send(LoginSuccess {
+                username,
+                uuid,
+                protocol_version,
+            });
 
             login_state.set(LoginState::Play).unwrap();
         };
@@ -316,6 +376,18 @@ mod login {
                     break;
                 }
 
+                Packet::Known(packet::Packet::EncryptionRequest(_)) => {
+                    let message = on_encryption_request(&*authenticator);
+                    error!("{}", &message);
+
+                    net_resource.disconnect();
+
+                    disconnect_events.send(Disconnect { reason: message });
+
+                    login_state.set(LoginState::Idle).unwrap();
+                    break;
+                }
+
                 Packet::Known(packet::Packet::LoginDisconnect(login_disconnect)) => {
                     let message = format!("Login disconnect: {}", login_disconnect.reason);
                     error!("{}", &message);
@@ -335,47 +407,75 @@ mod login {
 mod play {
     use super::*;
 
+    /// Time since startup at which the last KeepAlive packet was received (or
+    /// at which the play state was entered, if none has been yet).
+    #[derive(Default)]
+    struct LastKeepAlive(Duration);
+
     pub(crate) fn build(app: &mut App) {
+        app.init_resource::<LastKeepAlive>();
+        app.init_resource::<VersionAdapter>();
+        app.add_system_set(
+            SystemSet::on_enter(LoginState::Play).with_system(reset_keep_alive_timer),
+        );
         app.add_system_set(
             SystemSet::on_update(LoginState::Play)
                 .with_system(respond_to_keep_alive_packets)
+                .with_system(disconnect_on_keep_alive_timeout)
                 .with_system(handle_disconnect),
         );
     }
 
+    fn reset_keep_alive_timer(time: Res<Time>, mut last_keep_alive: ResMut<LastKeepAlive>) {
+        last_keep_alive.0 = time.time_since_startup();
+    }
+
     fn respond_to_keep_alive_packets(
         mut packet_reader: CodecReader<ProtocolCodec>,
         mut packet_writer: CodecWriter<ProtocolCodec>,
+        mut last_keep_alive: ResMut<LastKeepAlive>,
+        time: Res<Time>,
+        adapter: Res<VersionAdapter>,
     ) {
         for packet in packet_reader.iter() {
-            let response = match packet {
-                Packet::Known(packet::Packet::KeepAliveClientbound_VarInt(keep_alive)) => {
-                    Packet::Known(packet::Packet::KeepAliveServerbound_VarInt(Box::new(
-                        packet::play::serverbound::KeepAliveServerbound_VarInt {
-                            id: keep_alive.id,
-                        },
-                    )))
-                }
-                Packet::Known(packet::Packet::KeepAliveClientbound_i32(keep_alive)) => {
-                    Packet::Known(packet::Packet::KeepAliveServerbound_i32(Box::new(
-                        packet::play::serverbound::KeepAliveServerbound_i32 { id: keep_alive.id },
-                    )))
-                }
-                Packet::Known(packet::Packet::KeepAliveClientbound_i64(keep_alive)) => {
-                    Packet::Known(packet::Packet::KeepAliveServerbound_i64(Box::new(
-                        packet::play::serverbound::KeepAliveServerbound_i64 { id: keep_alive.id },
-                    )))
-                }
-
-                _ => continue,
+            let response = match adapter.keep_alive_response(packet) {
+                Some(response) => response,
+                None => continue,
             };
 
             debug!("KeepAlive");
             packet_writer.send(response);
+            last_keep_alive.0 = time.time_since_startup();
             break;
         }
     }
 
+    /// System that disconnects from the server if it hasn't sent a KeepAlive
+    /// packet within the [`KeepAliveTimeout`].
+    fn disconnect_on_keep_alive_timeout(
+        mut disconnect_events: EventWriter<Disconnect>,
+        mut login_state: ResMut<State<LoginState>>,
+        mut net_resource: ResMut<NetworkResource<ProtocolCodec>>,
+        last_keep_alive: Res<LastKeepAlive>,
+        timeout: Res<KeepAliveTimeout>,
+        time: Res<Time>,
+    ) {
+        let elapsed = time.time_since_startup().saturating_sub(last_keep_alive.0);
+        if elapsed <= timeout.0 {
+            return;
+        }
+
+        error!("No KeepAlive received for {:?}, disconnecting.", elapsed);
+
+        net_resource.disconnect();
+
+        disconnect_events.send(Disconnect {
+            reason: String::from("keep-alive timeout"),
+        });
+
+        login_state.set(LoginState::Idle).unwrap();
+    }
+
     fn handle_disconnect(
         mut packet_reader: CodecReader<ProtocolCodec>,
         mut disconnect_events: EventWriter<Disconnect>,
@@ -388,3 +488,94 @@ mod play {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use bevy::{
+        ecs::event::Events,
+        tasks::{IoTaskPool, TaskPool},
+    };
+    use brine_net::NetworkPlugin;
+
+    use super::*;
+
+    /// Makes an app that is already in the play state.
+    fn playing_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .insert_resource(Time::default())
+            .add_plugin(NetworkPlugin::<ProtocolCodec>::default())
+            .add_event::<Disconnect>()
+            .add_state(LoginState::Play)
+            .init_resource::<KeepAliveTimeout>();
+        play::build(&mut app);
+        app
+    }
+
+    fn advance_time_to(app: &mut App, since_startup: Duration) {
+        let mut time = app.world.get_resource_mut::<Time>().unwrap();
+        let now = time.startup() + since_startup;
+        time.update_with_instant(now);
+    }
+
+    fn disconnect_reasons(app: &App) -> Vec<String> {
+        let events = app.world.get_resource::<Events<Disconnect>>().unwrap();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|disconnect| disconnect.reason.clone())
+            .collect()
+    }
+
+    fn login_state(app: &App) -> LoginState {
+        *app.world
+            .get_resource::<State<LoginState>>()
+            .unwrap()
+            .current()
+    }
+
+    #[test]
+    fn disconnects_without_keep_alive() {
+        let mut app = playing_app();
+        app.update();
+
+        advance_time_to(&mut app, Duration::from_secs(29));
+        app.update();
+        assert!(disconnect_reasons(&app).is_empty());
+        assert_eq!(login_state(&app), LoginState::Play);
+
+        advance_time_to(&mut app, Duration::from_secs(31));
+        app.update();
+        assert_eq!(disconnect_reasons(&app), vec!["keep-alive timeout"]);
+        assert_eq!(login_state(&app), LoginState::Idle);
+    }
+
+    #[test]
+    fn timeout_is_configurable() {
+        let mut app = playing_app();
+        app.insert_resource(KeepAliveTimeout(Duration::from_secs(5)));
+        app.update();
+
+        advance_time_to(&mut app, Duration::from_secs(6));
+        app.update();
+        assert_eq!(disconnect_reasons(&app), vec!["keep-alive timeout"]);
+    }
+
+    #[test]
+    fn timer_starts_when_play_begins() {
+        let mut app = playing_app();
+
+        // Time spent before the play state doesn't count toward the timeout.
+        let start = Instant::now() + Duration::from_secs(60);
+        app.world
+            .get_resource_mut::<Time>()
+            .unwrap()
+            .update_with_instant(start);
+        app.update();
+
+        assert!(disconnect_reasons(&app).is_empty());
+        assert_eq!(login_state(&app), LoginState::Play);
+    }
+}
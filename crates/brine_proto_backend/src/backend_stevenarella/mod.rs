@@ -1,13 +1,32 @@
 //! Implementation of the Minecraft codec using stevenarella's protocol crate as
 //! the backend.
 
+pub mod blocks;
 pub mod chunks;
 pub mod codec;
+pub mod entities;
+pub mod hud;
+pub mod inventory;
 mod login;
+mod movement;
+pub mod player_info;
+pub mod sound;
+pub mod unknown;
+pub mod world;
 
 pub use codec::ProtocolCodec;
+pub use login::KeepAliveTimeout;
 
 pub(crate) fn build(app: &mut bevy::app::App) {
+    blocks::build(app);
     chunks::build(app);
+    entities::build(app);
+    hud::build(app);
+    inventory::build(app);
     login::build(app);
+    movement::build(app);
+    player_info::build(app);
+    sound::build(app);
+    unknown::build(app);
+    world::build(app);
 }
@@ -0,0 +1,28 @@
+//! Telling the server where the player is.
+//!
+//! See <https://wiki.vg/index.php?title=Protocol&oldid=15346#Player_Position_And_Rotation_.28serverbound.29>.
+
+use bevy::prelude::*;
+
+use brine_net::CodecWriter;
+use brine_proto::event::serverbound::PlayerPosition;
+
+use crate::version::VersionAdapter;
+
+use super::codec::ProtocolCodec;
+
+pub(crate) fn build(app: &mut App) {
+    app.init_resource::<VersionAdapter>();
+    app.add_system(send_player_position);
+}
+
+/// System that sends the player's position and rotation to the server.
+fn send_player_position(
+    mut position_events: EventReader<PlayerPosition>,
+    mut packet_writer: CodecWriter<ProtocolCodec>,
+    adapter: Res<VersionAdapter>,
+) {
+    for event in position_events.iter() {
+        packet_writer.send(adapter.make_movement_packet(event));
+    }
+}
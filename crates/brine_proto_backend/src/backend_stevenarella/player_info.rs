@@ -0,0 +1,220 @@
+//! Decoding of the packet that maintains the tab list.
+//!
+//! See this page for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Player_Info>
+
+use bevy::prelude::*;
+
+use brine_net::CodecReader;
+use brine_proto::event::clientbound::{GameMode, PlayerInfo, PlayerInfoUpdate};
+
+use super::{
+    codec::{packet, Packet, ProtocolCodec},
+    entities::decode_uuid,
+    hud::convert_component,
+};
+
+/// Decodes a [`PlayerInfo`] event from a packet, if it's a PlayerInfo packet.
+///
+/// Entries that can't be decoded are logged and left out.
+pub fn get_player_info_from_packet(packet: &Packet) -> Option<PlayerInfo> {
+    let player_info = match packet {
+        Packet::Known(packet::Packet::PlayerInfo(player_info)) => player_info,
+        _ => return None,
+    };
+
+    let updates = player_info
+        .inner
+        .players
+        .iter()
+        .filter_map(decode_player_detail)
+        .collect();
+
+    Some(PlayerInfo { updates })
+}
+
+fn decode_player_detail(detail: &packet::PlayerDetail) -> Option<PlayerInfoUpdate> {
+    let update = match detail {
+        packet::PlayerDetail::Add {
+            uuid,
+            name,
+            gamemode,
+            ping,
+            display,
+            ..
+        } => PlayerInfoUpdate::Add {
+            uuid: decode_uuid(uuid)?,
+            name: name.clone(),
+            gamemode: decode_gamemode(gamemode.0),
+            latency: ping.0,
+            display_name: display.as_ref().and_then(convert_component),
+        },
+        packet::PlayerDetail::UpdateGamemode { uuid, gamemode } => {
+            PlayerInfoUpdate::UpdateGameMode {
+                uuid: decode_uuid(uuid)?,
+                gamemode: decode_gamemode(gamemode.0),
+            }
+        }
+        packet::PlayerDetail::UpdateLatency { uuid, ping } => PlayerInfoUpdate::UpdateLatency {
+            uuid: decode_uuid(uuid)?,
+            latency: ping.0,
+        },
+        packet::PlayerDetail::UpdateDisplayName { uuid, display } => {
+            PlayerInfoUpdate::UpdateDisplayName {
+                uuid: decode_uuid(uuid)?,
+                display_name: display.as_ref().and_then(convert_component),
+            }
+        }
+        packet::PlayerDetail::Remove { uuid } => PlayerInfoUpdate::Remove {
+            uuid: decode_uuid(uuid)?,
+        },
+    };
+
+    Some(update)
+}
+
+fn decode_gamemode(id: i32) -> Option<GameMode> {
+    let gamemode = u8::try_from(id).ok().and_then(GameMode::from_id);
+    if gamemode.is_none() {
+        warn!("Unknown game mode {}", id);
+    }
+    gamemode
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_player_info_packets);
+}
+
+/// System that listens for PlayerInfo packets and sends the corresponding
+/// events to the client application.
+fn handle_player_info_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut player_info_events: EventWriter<PlayerInfo>,
+) {
+    for packet in packet_reader.iter() {
+        if let Some(event) = get_player_info_from_packet(packet) {
+            trace!("{:?}", event);
+            player_info_events.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use steven_protocol::{
+        format::Component,
+        protocol::{Serializable, VarInt, UUID},
+    };
+
+    use brine_proto::{chat::ChatComponent, event::Uuid};
+
+    use super::*;
+
+    fn uuid(n: u8) -> UUID {
+        UUID::read_from(&mut &[n; 16][..]).unwrap()
+    }
+
+    fn component(text: &str) -> Component {
+        let mut bytes = Vec::new();
+        format!(r#"{{"text":"{}"}}"#, text)
+            .write_to(&mut bytes)
+            .unwrap();
+        Component::read_from(&mut &bytes[..]).unwrap()
+    }
+
+    fn player_info(players: Vec<packet::PlayerDetail>) -> Packet {
+        Packet::Known(packet::Packet::PlayerInfo(Box::new(
+            packet::play::clientbound::PlayerInfo {
+                inner: packet::PlayerInfoData { players },
+            },
+        )))
+    }
+
+    #[test]
+    fn add_players() {
+        let packet = player_info(vec![
+            packet::PlayerDetail::Add {
+                uuid: uuid(1),
+                name: String::from("alice"),
+                properties: Vec::new(),
+                gamemode: VarInt(1),
+                ping: VarInt(35),
+                display: None,
+            },
+            packet::PlayerDetail::Add {
+                uuid: uuid(2),
+                name: String::from("bob"),
+                properties: Vec::new(),
+                gamemode: VarInt(9),
+                ping: VarInt(-1),
+                display: Some(component("Bob")),
+            },
+        ]);
+
+        assert_eq!(
+            get_player_info_from_packet(&packet),
+            Some(PlayerInfo {
+                updates: vec![
+                    PlayerInfoUpdate::Add {
+                        uuid: Uuid::from_bytes([1; 16]),
+                        name: String::from("alice"),
+                        gamemode: Some(GameMode::Creative),
+                        latency: 35,
+                        display_name: None,
+                    },
+                    PlayerInfoUpdate::Add {
+                        uuid: Uuid::from_bytes([2; 16]),
+                        name: String::from("bob"),
+                        gamemode: None,
+                        latency: -1,
+                        display_name: Some(ChatComponent::text("Bob")),
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn update_and_remove_players() {
+        let packet = player_info(vec![
+            packet::PlayerDetail::UpdateGamemode {
+                uuid: uuid(1),
+                gamemode: VarInt(3),
+            },
+            packet::PlayerDetail::UpdateLatency {
+                uuid: uuid(1),
+                ping: VarInt(80),
+            },
+            packet::PlayerDetail::UpdateDisplayName {
+                uuid: uuid(1),
+                display: None,
+            },
+            packet::PlayerDetail::Remove { uuid: uuid(2) },
+        ]);
+
+        let uuid_1 = Uuid::from_bytes([1; 16]);
+        assert_eq!(
+            get_player_info_from_packet(&packet),
+            Some(PlayerInfo {
+                updates: vec![
+                    PlayerInfoUpdate::UpdateGameMode {
+                        uuid: uuid_1,
+                        gamemode: Some(GameMode::Spectator),
+                    },
+                    PlayerInfoUpdate::UpdateLatency {
+                        uuid: uuid_1,
+                        latency: 80,
+                    },
+                    PlayerInfoUpdate::UpdateDisplayName {
+                        uuid: uuid_1,
+                        display_name: None,
+                    },
+                    PlayerInfoUpdate::Remove {
+                        uuid: Uuid::from_bytes([2; 16]),
+                    },
+                ],
+            })
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Decoding of the packets that play sounds.
+//!
+//! See these pages for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Named_Sound_Effect>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Sound_Effect>
+
+use bevy::prelude::*;
+
+use brine_net::CodecReader;
+use brine_proto::event::clientbound::{Sound, SoundCategory, SoundNameOrId};
+
+use super::codec::{packet, Packet, ProtocolCodec};
+
+/// Sound positions are sent as fixed-point numbers with 3 fractional bits.
+const POSITION_SCALE: f32 = 1.0 / 8.0;
+
+/// Converts a sound packet's fixed-point position to blocks.
+pub fn decode_sound_position(x: i32, y: i32, z: i32) -> Vec3 {
+    Vec3::new(x as f32, y as f32, z as f32) * POSITION_SCALE
+}
+
+/// Decodes a [`Sound`] event from a packet, if it's a NamedSoundEffect or
+/// SoundEffect packet.
+///
+/// Packets with a category that isn't known are logged and ignored.
+pub fn get_sound_from_packet(packet: &Packet) -> Option<Sound> {
+    let packet = match packet {
+        Packet::Known(packet) => packet,
+        Packet::Unknown(_) => return None,
+    };
+
+    let (name_or_id, category, x, y, z, volume, pitch) = match packet {
+        packet::Packet::NamedSoundEffect(sound) => (
+            SoundNameOrId::Name(sound.name.clone()),
+            sound.category.0,
+            sound.x,
+            sound.y,
+            sound.z,
+            sound.volume,
+            sound.pitch,
+        ),
+        packet::Packet::SoundEffect(sound) => (
+            SoundNameOrId::Id(sound.name.0),
+            sound.category.0,
+            sound.x,
+            sound.y,
+            sound.z,
+            sound.volume,
+            sound.pitch,
+        ),
+        _ => return None,
+    };
+
+    let category = match SoundCategory::from_id(category) {
+        Some(category) => category,
+        None => {
+            warn!("Unknown sound category {}", category);
+            return None;
+        }
+    };
+
+    Some(Sound {
+        name_or_id,
+        category,
+        position: decode_sound_position(x, y, z),
+        volume,
+        pitch,
+    })
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_sound_packets);
+}
+
+/// System that listens for sound packets and sends the corresponding events to
+/// the client application.
+fn handle_sound_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut sound_events: EventWriter<Sound>,
+) {
+    for packet in packet_reader.iter() {
+        if let Some(event) = get_sound_from_packet(packet) {
+            trace!("{:?}", event);
+            sound_events.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use steven_protocol::protocol::VarInt;
+
+    use super::*;
+
+    #[test]
+    fn named_sound() {
+        let packet = Packet::Known(packet::Packet::NamedSoundEffect(Box::new(
+            packet::play::clientbound::NamedSoundEffect {
+                name: String::from("minecraft:block.note_block.harp"),
+                category: VarInt(2),
+                x: 8 * 10 + 4,
+                y: 8 * 64,
+                z: -8 * 3,
+                volume: 3.0,
+                pitch: 0.5,
+            },
+        )));
+
+        assert_eq!(
+            get_sound_from_packet(&packet),
+            Some(Sound {
+                name_or_id: SoundNameOrId::Name(String::from("minecraft:block.note_block.harp")),
+                category: SoundCategory::Records,
+                position: Vec3::new(10.5, 64.0, -3.0),
+                volume: 3.0,
+                pitch: 0.5,
+            })
+        );
+    }
+
+    #[test]
+    fn sound_by_id() {
+        let packet = Packet::Known(packet::Packet::SoundEffect(Box::new(
+            packet::play::clientbound::SoundEffect {
+                name: VarInt(57),
+                category: VarInt(4),
+                x: 0,
+                y: 8,
+                z: 0,
+                volume: 1.0,
+                pitch: 1.0,
+            },
+        )));
+
+        assert_eq!(
+            get_sound_from_packet(&packet),
+            Some(Sound {
+                name_or_id: SoundNameOrId::Id(57),
+                category: SoundCategory::Blocks,
+                position: Vec3::new(0.0, 1.0, 0.0),
+                volume: 1.0,
+                pitch: 1.0,
+            })
+        );
+    }
+}
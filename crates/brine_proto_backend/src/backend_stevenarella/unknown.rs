@@ -0,0 +1,187 @@
+//! Bookkeeping for packets that the backend doesn't understand.
+//!
+//! Unknown packets are counted by protocol state and packet id, and a summary
+//! of the ones received recently is logged every so often, so that a server
+//! speaking a slightly different dialect doesn't flood the log.
+//!
+//! If [`UnknownPacketSettings::forward_events`] is set, each unknown packet is
+//! also sent to the client application as a
+//! [`brine_proto::event::clientbound::UnknownPacket`] event.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use brine_net::CodecReader;
+use brine_proto::event::clientbound::UnknownPacket as UnknownPacketEvent;
+
+use crate::codec::MinecraftProtocolState;
+
+use super::codec::{Packet, ProtocolCodec};
+
+/// Resource that controls what happens to unknown packets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownPacketSettings {
+    /// Seconds between summaries of the unknown packets that were received.
+    pub summary_interval: f64,
+
+    /// Whether to send an event for every unknown packet.
+    pub forward_events: bool,
+}
+
+impl Default for UnknownPacketSettings {
+    fn default() -> Self {
+        Self {
+            summary_interval: 10.0,
+            forward_events: false,
+        }
+    }
+}
+
+/// Resource that counts the unknown packets received by protocol state and
+/// packet id.
+#[derive(Debug, Default)]
+pub struct UnknownPacketCounts {
+    total: HashMap<(MinecraftProtocolState, i32), usize>,
+    since_summary: HashMap<(MinecraftProtocolState, i32), usize>,
+}
+
+impl UnknownPacketCounts {
+    /// Number of unknown packets with the given id received in the given state.
+    pub fn get(&self, state: MinecraftProtocolState, packet_id: i32) -> usize {
+        self.total.get(&(state, packet_id)).copied().unwrap_or(0)
+    }
+
+    /// Number of unknown packets received, of any id.
+    pub fn total(&self) -> usize {
+        self.total.values().sum()
+    }
+
+    /// Iterates over the count of each kind of unknown packet received.
+    pub fn iter(&self) -> impl Iterator<Item = (MinecraftProtocolState, i32, usize)> + '_ {
+        self.total
+            .iter()
+            .map(|(&(state, packet_id), &count)| (state, packet_id, count))
+    }
+
+    fn record(&mut self, state: MinecraftProtocolState, packet_id: i32) {
+        *self.total.entry((state, packet_id)).or_default() += 1;
+        *self.since_summary.entry((state, packet_id)).or_default() += 1;
+    }
+
+    /// Describes the unknown packets received since the last summary, and
+    /// starts counting again. Returns `None` if there weren't any.
+    fn take_summary(&mut self) -> Option<String> {
+        if self.since_summary.is_empty() {
+            return None;
+        }
+
+        let mut counts: Vec<_> = self.since_summary.drain().collect();
+        counts.sort_by_key(|&((state, packet_id), _)| (state_name(state), packet_id));
+
+        let total: usize = counts.iter().map(|(_, count)| count).sum();
+        let details: Vec<_> = counts
+            .into_iter()
+            .map(|((state, packet_id), count)| {
+                format!("{}/{:#04x} x{}", state_name(state), packet_id, count)
+            })
+            .collect();
+
+        Some(format!(
+            "Received {} unknown packets: {}",
+            total,
+            details.join(", ")
+        ))
+    }
+}
+
+fn state_name(state: MinecraftProtocolState) -> &'static str {
+    match state {
+        MinecraftProtocolState::Handshaking => "Handshaking",
+        MinecraftProtocolState::Status => "Status",
+        MinecraftProtocolState::Login => "Login",
+        MinecraftProtocolState::Play => "Play",
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.init_resource::<UnknownPacketSettings>();
+    app.init_resource::<UnknownPacketCounts>();
+    app.add_system(handle_unknown_packets);
+    app.add_system(log_unknown_packet_summary);
+}
+
+/// System that counts unknown packets and forwards them if requested.
+fn handle_unknown_packets(
+    settings: Res<UnknownPacketSettings>,
+    mut counts: ResMut<UnknownPacketCounts>,
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut unknown_events: EventWriter<UnknownPacketEvent>,
+) {
+    for packet in packet_reader.iter() {
+        if let Packet::Unknown(packet) = packet {
+            trace!("{:?}", packet);
+
+            counts.record(packet.state, packet.packet_id);
+
+            if settings.forward_events {
+                unknown_events.send(UnknownPacketEvent {
+                    state: state_name(packet.state),
+                    packet_id: packet.packet_id,
+                    body: packet.body.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// System that periodically logs which unknown packets have been received.
+fn log_unknown_packet_summary(
+    time: Res<Time>,
+    settings: Res<UnknownPacketSettings>,
+    mut counts: ResMut<UnknownPacketCounts>,
+    mut last_summary: Local<f64>,
+) {
+    let now = time.seconds_since_startup();
+    if now - *last_summary < settings.summary_interval {
+        return;
+    }
+    *last_summary = now;
+
+    if let Some(summary) = counts.take_summary() {
+        debug!("{}", summary);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_and_summary() {
+        let mut counts = UnknownPacketCounts::default();
+        assert_eq!(counts.take_summary(), None);
+
+        counts.record(MinecraftProtocolState::Play, 0x7f);
+        counts.record(MinecraftProtocolState::Play, 0x0a);
+        counts.record(MinecraftProtocolState::Play, 0x7f);
+
+        assert_eq!(counts.get(MinecraftProtocolState::Play, 0x7f), 2);
+        assert_eq!(counts.get(MinecraftProtocolState::Login, 0x7f), 0);
+        assert_eq!(counts.total(), 3);
+
+        assert_eq!(
+            counts.take_summary().unwrap(),
+            "Received 3 unknown packets: Play/0x0a x1, Play/0x7f x2"
+        );
+        assert_eq!(counts.take_summary(), None);
+
+        // Totals are kept across summaries.
+        counts.record(MinecraftProtocolState::Login, 0x05);
+        assert_eq!(counts.total(), 4);
+        assert_eq!(
+            counts.take_summary().unwrap(),
+            "Received 1 unknown packets: Login/0x05 x1"
+        );
+    }
+}
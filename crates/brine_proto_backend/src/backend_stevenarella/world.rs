@@ -0,0 +1,208 @@
+//! Decoding of the packets that put the player into a world (JoinGame and
+//! Respawn), and of the world's time.
+//!
+//! See these pages for reference:
+//!
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Join_Game>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Respawn>
+//! * <https://wiki.vg/index.php?title=Protocol&oldid=15346#Time_Update>
+
+use bevy::prelude::*;
+
+use brine_net::CodecReader;
+use brine_proto::event::clientbound::{Dimension, GameMode, JoinGame, Respawn, TimeUpdate};
+
+use super::codec::{packet, Packet, ProtocolCodec};
+
+/// One of the world events that can be decoded from a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldEvent {
+    JoinGame(JoinGame),
+    Respawn(Respawn),
+    TimeUpdate(TimeUpdate),
+}
+
+/// Decodes a world event from a packet, if it's a JoinGame, Respawn, or
+/// TimeUpdate packet.
+///
+/// JoinGame and Respawn packets with a dimension or game mode that isn't known
+/// are logged and ignored.
+pub fn get_world_event_from_packet(packet: &Packet) -> Option<WorldEvent> {
+    let packet = match packet {
+        Packet::Known(packet) => packet,
+        Packet::Unknown(_) => return None,
+    };
+
+    if let packet::Packet::TimeUpdate(time_update) = packet {
+        return Some(WorldEvent::TimeUpdate(TimeUpdate {
+            world_age: time_update.world_age,
+            time_of_day: time_update.time_of_day,
+        }));
+    }
+
+    // Only JoinGame packets have these.
+    let (join_game, dimension, gamemode) = match packet {
+        packet::Packet::JoinGame_i32_ViewDistance(join_game) => (
+            Some((
+                join_game.entity_id,
+                join_game.max_players,
+                join_game.level_type.clone(),
+            )),
+            join_game.dimension,
+            join_game.gamemode,
+        ),
+        packet::Packet::JoinGame_HashedSeed_Respawn(join_game) => (
+            Some((
+                join_game.entity_id,
+                join_game.max_players,
+                join_game.level_type.clone(),
+            )),
+            join_game.dimension,
+            join_game.gamemode,
+        ),
+        packet::Packet::Respawn_Gamemode(respawn) => (None, respawn.dimension, respawn.gamemode),
+        packet::Packet::Respawn_HashedSeed(respawn) => (None, respawn.dimension, respawn.gamemode),
+        _ => return None,
+    };
+
+    let dimension = match Dimension::from_id(dimension) {
+        Some(dimension) => dimension,
+        None => {
+            warn!("Unknown dimension {}", dimension);
+            return None;
+        }
+    };
+
+    let gamemode = match GameMode::from_id(gamemode) {
+        Some(gamemode) => gamemode,
+        None => {
+            warn!("Unknown game mode {}", gamemode);
+            return None;
+        }
+    };
+
+    let event = match join_game {
+        Some((entity_id, max_players, level_type)) => WorldEvent::JoinGame(JoinGame {
+            entity_id,
+            gamemode,
+            dimension,
+            max_players,
+            level_type,
+        }),
+        None => WorldEvent::Respawn(Respawn {
+            dimension,
+            gamemode,
+        }),
+    };
+
+    Some(event)
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.add_system(handle_world_packets);
+}
+
+/// System that listens for world packets and sends the corresponding events to
+/// the client application.
+fn handle_world_packets(
+    mut packet_reader: CodecReader<ProtocolCodec>,
+    mut join_game_events: EventWriter<JoinGame>,
+    mut respawn_events: EventWriter<Respawn>,
+    mut time_events: EventWriter<TimeUpdate>,
+) {
+    for packet in packet_reader.iter() {
+        match get_world_event_from_packet(packet) {
+            Some(WorldEvent::JoinGame(event)) => {
+                debug!("{:?}", event);
+                join_game_events.send(event);
+            }
+            Some(WorldEvent::Respawn(event)) => {
+                debug!("{:?}", event);
+                respawn_events.send(event);
+            }
+            Some(WorldEvent::TimeUpdate(event)) => time_events.send(event),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::codec::MinecraftProtocolState;
+
+    use super::{
+        super::codec::{Direction, MinecraftCodec},
+        *,
+    };
+
+    fn respawn(dimension: i32, gamemode: u8) -> Packet {
+        Packet::Known(packet::Packet::Respawn_Gamemode(Box::new(
+            packet::play::clientbound::Respawn_Gamemode {
+                dimension,
+                difficulty: 0,
+                gamemode,
+                level_type: String::from("default"),
+            },
+        )))
+    }
+
+    #[test]
+    fn join_game() {
+        // JoinGame's packet id in 1.14.4.
+        const JOIN_GAME: i32 = 0x25;
+
+        let packet = MinecraftCodec::decode_packet_with_id(
+            498,
+            MinecraftProtocolState::Play,
+            Direction::Clientbound,
+            JOIN_GAME,
+            include_bytes!("../../test/packet-data/play/join_game.dat"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_world_event_from_packet(&packet),
+            Some(WorldEvent::JoinGame(JoinGame {
+                entity_id: 190,
+                gamemode: GameMode::Creative,
+                dimension: Dimension::Overworld,
+                max_players: 20,
+                level_type: String::from("default"),
+            }))
+        );
+    }
+
+    #[test]
+    fn respawn_in_the_nether() {
+        assert_eq!(
+            get_world_event_from_packet(&respawn(-1, 0x8 | 1)),
+            Some(WorldEvent::Respawn(Respawn {
+                dimension: Dimension::Nether,
+                gamemode: GameMode::Creative,
+            }))
+        );
+    }
+
+    #[test]
+    fn frozen_time_update() {
+        let packet = Packet::Known(packet::Packet::TimeUpdate(Box::new(
+            packet::play::clientbound::TimeUpdate {
+                world_age: 123456,
+                time_of_day: -6000,
+            },
+        )));
+
+        assert_eq!(
+            get_world_event_from_packet(&packet),
+            Some(WorldEvent::TimeUpdate(TimeUpdate {
+                world_age: 123456,
+                time_of_day: -6000,
+            }))
+        );
+    }
+
+    #[test]
+    fn unknown_dimension_is_ignored() {
+        assert_eq!(get_world_event_from_packet(&respawn(7, 0)), None);
+    }
+}
@@ -149,6 +149,8 @@ pub trait IntoEncodeResult {
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct UnknownPacket {
+    /// Protocol state the codec was in when the packet was decoded.
+    pub state: MinecraftProtocolState,
     pub packet_id: i32,
     pub body: Vec<u8>,
 }
@@ -156,6 +158,7 @@ pub struct UnknownPacket {
 impl fmt::Debug for UnknownPacket {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("UnknownPacket")
+            .field("state", &self.state)
             .field("packet_id", &self.packet_id)
             .field("body", &hex_dump(&self.body))
             .finish()
@@ -24,6 +24,12 @@ use crate::backend::{self, ProtocolCodec};
 ///
 /// The plugin registers a [`NetworkPlugin`] which provides things. See its
 /// documentation.
+///
+/// The plugin inserts a [`KeepAliveTimeout`] resource if one doesn't already
+/// exist. It controls how long the server can go without sending a KeepAlive
+/// packet before the client disconnects.
+///
+/// [`KeepAliveTimeout`]: crate::backend_stevenarella::KeepAliveTimeout
 pub struct ProtocolBackendPlugin;
 
 impl Plugin for ProtocolBackendPlugin {
@@ -0,0 +1,220 @@
+//! Conversion between Minecraft versions and protocol version numbers, and the
+//! packet translation that differs between protocol versions.
+//!
+//! Systems that translate packets to and from events shouldn't match on the
+//! packets of a specific version. Instead, they go through the
+//! [`VersionAdapter`] resource, which holds the [`ProtocolVersionAdapter`] for
+//! the protocol version that the server speaks.
+
+use std::ops::Deref;
+
+use bevy::log;
+
+use brine_proto::event::serverbound::PlayerPosition;
+
+use crate::backend::{
+    chunks::{ChunkData, LightUpdate},
+    codec::Packet,
+};
+
+mod v1_14_4;
+mod v1_15_2;
+
+pub use v1_14_4::V1_14_4;
+pub use v1_15_2::V1_15_2;
+
+macro_rules! protocol_versions {
+    (
+        $(
+            $version:expr => $protocol_version:expr,
+        )+
+    ) => {
+        const fn get_protocol_version_internal(version_string: &str) -> Option<i32> {
+            match version_string.as_bytes() {
+                $(
+                $version => Some($protocol_version),
+                )+
+
+                _ => None
+            }
+        }
+    };
+}
+
+protocol_versions! {
+    b"1.18.1" => 757,
+    b"1.18"   => 757,
+    b"1.17.1" => 756,
+    b"1.17"   => 755,
+    b"1.16.5" => 754,
+    b"1.16.4" => 754,
+    b"1.16.3" => 753,
+    b"1.16.2" => 751,
+    b"1.16.1" => 736,
+    b"1.16"   => 735,
+    b"1.15.2" => 578,
+    b"1.15.1" => 575,
+    b"1.15"   => 573,
+    b"1.14.4" => 498,
+    b"1.14.3" => 490,
+    b"1.14.2" => 485,
+    b"1.14.1" => 480,
+    b"1.14"   => 477,
+    b"1.13.2" => 404,
+    b"1.13.1" => 401,
+    b"1.13"   => 393,
+    b"1.12.2" => 340,
+    b"1.12.1" => 338,
+    b"1.12"   => 335,
+    b"1.11.2" => 316,
+    b"1.11.1" => 316,
+    b"1.11"   => 315,
+    b"1.10.2" => 210,
+    b"1.10.1" => 210,
+    b"1.10"   => 210,
+    b"1.9.4"  => 110,
+    b"1.9.3"  => 110,
+    b"1.9.2"  => 109,
+    b"1.9.1"  => 108,
+    b"1.9"    => 107,
+    b"1.8.9"  => 47,
+    b"1.8.8"  => 47,
+    b"1.8.7"  => 47,
+    b"1.8.6"  => 47,
+    b"1.8.5"  => 47,
+    b"1.8.4"  => 47,
+    b"1.8.3"  => 47,
+    b"1.8.2"  => 47,
+    b"1.8.1"  => 47,
+    b"1.8"    => 47,
+    b"1.7.10" => 5,
+    b"1.7.9"  => 5,
+    b"1.7.8"  => 5,
+    b"1.7.7"  => 5,
+    b"1.7.6"  => 5,
+    b"1.7.5"  => 4,
+    b"1.7.4"  => 4,
+    b"1.7.2"  => 4,
+}
+
+pub const fn get_protocol_version(version_string: &str) -> Option<i32> {
+    get_protocol_version_internal(version_string)
+}
+
+/// The parts of a ChunkData packet, borrowed from the packet.
+pub type ChunkDataParts<'p> = ChunkData<&'p [u8]>;
+
+/// The parts of an UpdateLight packet, borrowed from the packet.
+pub type LightUpdateParts<'p> = LightUpdate<&'p [u8]>;
+
+/// Translation of the packets whose layout differs between protocol versions.
+pub trait ProtocolVersionAdapter: Send + Sync + 'static {
+    /// The protocol version that this adapter speaks.
+    fn protocol_version(&self) -> i32;
+
+    /// Returns the parts of a ChunkData packet, if it is one.
+    fn chunk_data_from_packet<'p>(&self, packet: &'p Packet) -> Option<ChunkDataParts<'p>>;
+
+    /// Returns the parts of an UpdateLight packet, if it is one.
+    fn light_update_from_packet<'p>(&self, packet: &'p Packet) -> Option<LightUpdateParts<'p>>;
+
+    /// Returns the packet to send back in response to a KeepAlive packet, if it
+    /// is one.
+    fn keep_alive_response(&self, packet: &Packet) -> Option<Packet>;
+
+    /// Returns true if the packet is a KeepAlive packet.
+    fn is_keep_alive(&self, packet: &Packet) -> bool {
+        self.keep_alive_response(packet).is_some()
+    }
+
+    /// Makes the packet that tells the server where the player is.
+    fn make_movement_packet(&self, position: &PlayerPosition) -> Packet;
+}
+
+/// Returns the adapter for a protocol version, if it is supported.
+pub fn adapter_for(protocol_version: i32) -> Option<Box<dyn ProtocolVersionAdapter>> {
+    match protocol_version {
+        // 1.14 through 1.14.4.
+        477..=498 => Some(Box::new(V1_14_4)),
+        // 1.15 through 1.15.2.
+        573..=578 => Some(Box::new(V1_15_2)),
+        _ => None,
+    }
+}
+
+/// Resource that holds the [`ProtocolVersionAdapter`] for the protocol version
+/// in use.
+///
+/// Until the server's protocol version has been discovered, this is the adapter
+/// for the protocol version that the client sends in its first Handshake.
+pub struct VersionAdapter(pub Box<dyn ProtocolVersionAdapter>);
+
+impl VersionAdapter {
+    /// Returns the adapter for a protocol version, falling back to the default
+    /// adapter if the protocol version isn't supported.
+    pub fn for_protocol_version(protocol_version: i32) -> Self {
+        match adapter_for(protocol_version) {
+            Some(adapter) => Self(adapter),
+            None => {
+                log::warn!(
+                    "Protocol version {} is not supported, falling back to {}",
+                    protocol_version,
+                    V1_14_4.protocol_version()
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+impl Default for VersionAdapter {
+    fn default() -> Self {
+        Self(Box::new(V1_14_4))
+    }
+}
+
+impl Deref for VersionAdapter {
+    type Target = dyn ProtocolVersionAdapter;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        assert_eq!(get_protocol_version("1.14.4"), Some(498));
+        assert_eq!(get_protocol_version("foo"), None);
+    }
+
+    #[test]
+    fn adapters_speak_their_protocol_version() {
+        for version in ["1.14", "1.14.4", "1.15", "1.15.2"] {
+            let protocol_version = get_protocol_version(version).unwrap();
+            assert!(adapter_for(protocol_version).is_some(), "{}", version);
+        }
+
+        assert_eq!(
+            adapter_for(498).unwrap().protocol_version(),
+            get_protocol_version("1.14.4").unwrap()
+        );
+        assert_eq!(
+            adapter_for(578).unwrap().protocol_version(),
+            get_protocol_version("1.15.2").unwrap()
+        );
+
+        assert!(adapter_for(get_protocol_version("1.16.5").unwrap()).is_none());
+    }
+
+    #[test]
+    fn unsupported_versions_fall_back_to_default() {
+        assert_eq!(
+            VersionAdapter::for_protocol_version(47).protocol_version(),
+            VersionAdapter::default().protocol_version()
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Packet translation for 1.14.4, which is also used for 1.14 through 1.14.3.
+//!
+//! See <https://wiki.vg/index.php?title=Protocol&oldid=15346>.
+
+use brine_proto::event::serverbound::PlayerPosition;
+
+use crate::backend::{
+    chunks::{encode_nbt, ChunkData, LightUpdate},
+    codec::{packet, Packet},
+};
+
+use super::{ChunkDataParts, LightUpdateParts, ProtocolVersionAdapter};
+
+/// Adapter for protocol version 498.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct V1_14_4;
+
+impl ProtocolVersionAdapter for V1_14_4 {
+    fn protocol_version(&self) -> i32 {
+        498
+    }
+
+    fn chunk_data_from_packet<'p>(&self, packet: &'p Packet) -> Option<ChunkDataParts<'p>> {
+        let (chunk_x, chunk_z, full_chunk, bitmask, heightmaps, data) = match packet {
+            /*Packet::Known(packet::Packet::ChunkData_Biomes3D_Bitmasks(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                ??,
+                ??,
+                &chunk_data.data[..],
+            ),*/
+            Packet::Known(packet::Packet::ChunkData_Biomes3D_VarInt(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask.0 as u16,
+                None,
+                &chunk_data.data.data[..],
+            ),
+            Packet::Known(packet::Packet::ChunkData_Biomes3D_bool(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask.0 as u16,
+                None,
+                &chunk_data.data.data[..],
+            ),
+            Packet::Known(packet::Packet::ChunkData_Biomes3D(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask.0 as u16,
+                None,
+                &chunk_data.data.data[..],
+            ),
+            Packet::Known(packet::Packet::ChunkData_HeightMap(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask.0 as u16,
+                encode_nbt(&chunk_data.heightmaps),
+                &chunk_data.data.data[..],
+            ),
+            Packet::Known(packet::Packet::ChunkData(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask.0 as u16,
+                None,
+                &chunk_data.data.data[..],
+            ),
+            Packet::Known(packet::Packet::ChunkData_NoEntities(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask.0 as u16,
+                None,
+                &chunk_data.data.data[..],
+            ),
+            /*Packet::Known(packet::Packet::ChunkData_NoEntities_u16(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask,
+                &chunk_data.data.data[..] ?? varints,
+            ),*/
+            /*Packet::Known(packet::Packet::ChunkData_17(chunk_data)) => (
+                chunk_data.chunk_x,
+                chunk_data.chunk_z,
+                chunk_data.new,
+                chunk_data.bitmask,
+                &chunk_data.data.data[..] ?? compressed data,
+            ),*/
+            _ => return None,
+        };
+
+        Some(ChunkData {
+            chunk_x,
+            chunk_z,
+            bitmask,
+            full_chunk,
+            heightmaps,
+            biomes_in_data: true,
+            data,
+        })
+    }
+
+    fn light_update_from_packet<'p>(&self, packet: &'p Packet) -> Option<LightUpdateParts<'p>> {
+        match packet {
+            Packet::Known(packet::Packet::UpdateLight_NoTrust(update_light)) => Some(LightUpdate {
+                chunk_x: update_light.chunk_x.0,
+                chunk_z: update_light.chunk_z.0,
+                sky_light_mask: update_light.sky_light_mask.0 as u32,
+                block_light_mask: update_light.block_light_mask.0 as u32,
+                data: &update_light.light_arrays[..],
+            }),
+            _ => None,
+        }
+    }
+
+    fn keep_alive_response(&self, packet: &Packet) -> Option<Packet> {
+        let response = match packet {
+            Packet::Known(packet::Packet::KeepAliveClientbound_VarInt(keep_alive)) => {
+                packet::Packet::KeepAliveServerbound_VarInt(Box::new(
+                    packet::play::serverbound::KeepAliveServerbound_VarInt { id: keep_alive.id },
+                ))
+            }
+            Packet::Known(packet::Packet::KeepAliveClientbound_i32(keep_alive)) => {
+                packet::Packet::KeepAliveServerbound_i32(Box::new(
+                    packet::play::serverbound::KeepAliveServerbound_i32 { id: keep_alive.id },
+                ))
+            }
+            Packet::Known(packet::Packet::KeepAliveClientbound_i64(keep_alive)) => {
+                packet::Packet::KeepAliveServerbound_i64(Box::new(
+                    packet::play::serverbound::KeepAliveServerbound_i64 { id: keep_alive.id },
+                ))
+            }
+
+            _ => return None,
+        };
+
+        Some(Packet::Known(response))
+    }
+
+    fn make_movement_packet(&self, position: &PlayerPosition) -> Packet {
+        Packet::Known(packet::Packet::PlayerPositionLook(Box::new(
+            packet::play::serverbound::PlayerPositionLook {
+                x: position.position.x as f64,
+                y: position.position.y as f64,
+                z: position.position.z as f64,
+                yaw: position.yaw,
+                pitch: position.pitch,
+                on_ground: position.on_ground,
+            },
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keep_alive_is_answered_with_the_same_id() {
+        let keep_alive = Packet::Known(packet::Packet::KeepAliveClientbound_i64(Box::new(
+            packet::play::clientbound::KeepAliveClientbound_i64 { id: 1234 },
+        )));
+
+        assert!(V1_14_4.is_keep_alive(&keep_alive));
+        match V1_14_4.keep_alive_response(&keep_alive) {
+            Some(Packet::Known(packet::Packet::KeepAliveServerbound_i64(response))) => {
+                assert_eq!(response.id, 1234);
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Packet translation for 1.15.2, which is also used for 1.15 and 1.15.1.
+//!
+//! The only difference from 1.14.4 that matters so far is the ChunkData
+//! packet: the biomes of a full chunk are sent as a 3D grid ahead of the data
+//! blob, instead of as a 2D grid at the end of it.
+//!
+//! See <https://wiki.vg/index.php?title=Protocol&oldid=16067#Chunk_Data>.
+
+use brine_proto::event::serverbound::PlayerPosition;
+
+use crate::backend::{
+    chunks::{encode_nbt, ChunkData},
+    codec::{packet, Packet},
+};
+
+use super::{ChunkDataParts, LightUpdateParts, ProtocolVersionAdapter, V1_14_4};
+
+/// Adapter for protocol version 578.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct V1_15_2;
+
+impl ProtocolVersionAdapter for V1_15_2 {
+    fn protocol_version(&self) -> i32 {
+        578
+    }
+
+    fn chunk_data_from_packet<'p>(&self, packet: &'p Packet) -> Option<ChunkDataParts<'p>> {
+        match packet {
+            Packet::Known(packet::Packet::ChunkData_Biomes3D(chunk_data)) => Some(ChunkData {
+                chunk_x: chunk_data.chunk_x,
+                chunk_z: chunk_data.chunk_z,
+                full_chunk: chunk_data.new,
+                bitmask: chunk_data.bitmask.0 as u16,
+                heightmaps: encode_nbt(&chunk_data.heightmaps),
+                biomes_in_data: false,
+                data: &chunk_data.data.data[..],
+            }),
+            _ => V1_14_4.chunk_data_from_packet(packet),
+        }
+    }
+
+    fn light_update_from_packet<'p>(&self, packet: &'p Packet) -> Option<LightUpdateParts<'p>> {
+        V1_14_4.light_update_from_packet(packet)
+    }
+
+    fn keep_alive_response(&self, packet: &Packet) -> Option<Packet> {
+        V1_14_4.keep_alive_response(packet)
+    }
+
+    fn make_movement_packet(&self, position: &PlayerPosition) -> Packet {
+        V1_14_4.make_movement_packet(position)
+    }
+}
@@ -0,0 +1,131 @@
+//! Logs in to a scripted online-mode server and checks that the
+//! [`LoginAuthenticator`] is consulted.
+
+mod common;
+
+use std::{
+    io::Read,
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{ecs::event::Events, prelude::*};
+
+use brine_proto::{
+    event::{clientbound::Disconnect, serverbound::Login, Uuid},
+    Authenticator, GameProfile, LoginAuthenticator, ProtocolPlugin,
+};
+use brine_proto_backend::ProtocolBackendPlugin;
+
+use common::{accept_login, serve_status, write_packet, write_string, write_var_int};
+
+/// Plays the part of a 1.14.4 server in online mode, which asks the client to
+/// authenticate instead of letting it log in.
+fn online_mode_server(listener: TcpListener) {
+    serve_status(&listener);
+
+    let mut login = accept_login(&listener);
+
+    let mut body = Vec::new();
+    write_string(&mut body, ""); // Server ID
+    write_var_int(&mut body, 4);
+    body.extend_from_slice(&[1, 2, 3, 4]); // Public key
+    write_var_int(&mut body, 4);
+    body.extend_from_slice(&[5, 6, 7, 8]); // Verify token
+    write_packet(&mut login, 0x01, &body);
+
+    // Wait for the client to hang up.
+    login
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let _ = login.read_to_end(&mut Vec::new());
+}
+
+/// Records which of its methods are called.
+#[derive(Clone, Default)]
+struct MockAuthenticator {
+    calls: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Authenticator for MockAuthenticator {
+    fn access_token(&self) -> Option<String> {
+        self.calls.lock().unwrap().push("access_token");
+        Some(String::from("token"))
+    }
+
+    fn profile(&self) -> Option<GameProfile> {
+        self.calls.lock().unwrap().push("profile");
+        Some(GameProfile {
+            uuid: Uuid::from_u128(0x35ee313b_d89a_41b8_b25e_d32e8aff0389),
+            name: String::from("Username"),
+        })
+    }
+}
+
+/// Logs in with the given authenticator (or the default one) and returns the
+/// reason that the client was disconnected.
+fn log_in(authenticator: Option<LoginAuthenticator>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = listener.local_addr().unwrap().to_string();
+    let server_thread = thread::spawn(move || online_mode_server(listener));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(ProtocolPlugin)
+        .add_plugin(ProtocolBackendPlugin);
+    if let Some(authenticator) = authenticator {
+        app.insert_resource(authenticator);
+    }
+
+    app.world
+        .get_resource_mut::<Events<Login>>()
+        .unwrap()
+        .send(Login {
+            server,
+            username: "Username".to_string(),
+        });
+
+    let mut disconnect_reader = app
+        .world
+        .get_resource::<Events<Disconnect>>()
+        .unwrap()
+        .get_reader();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let reason = loop {
+        app.update();
+
+        let events = app.world.get_resource::<Events<Disconnect>>().unwrap();
+        if let Some(disconnect) = disconnect_reader.iter(events).next() {
+            break disconnect.reason.clone();
+        }
+
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(5));
+    };
+
+    server_thread.join().unwrap();
+
+    reason
+}
+
+#[test]
+fn authenticator_is_consulted_on_encryption_request() {
+    let authenticator = MockAuthenticator::default();
+
+    let reason = log_in(Some(LoginAuthenticator::new(authenticator.clone())));
+
+    let calls = authenticator.calls.lock().unwrap().clone();
+    assert!(calls.contains(&"profile"), "{:?}", calls);
+    assert!(calls.contains(&"access_token"), "{:?}", calls);
+    assert!(reason.contains("encryption"), "{}", reason);
+}
+
+#[test]
+fn offline_login_to_online_mode_server_fails() {
+    let reason = log_in(None);
+
+    assert!(reason.contains("not authenticated"), "{}", reason);
+}
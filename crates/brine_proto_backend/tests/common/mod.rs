@@ -0,0 +1,100 @@
+//! A scripted server for login tests.
+//!
+//! The root crate's integration tests include this module by path, so it
+//! can't use anything that only this crate depends on.
+
+// Each test binary only uses some of these.
+#![allow(dead_code)]
+
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use brine_chunk::Chunk;
+
+pub const STATUS_RESPONSE: &str = r#"{"version":{"name":"1.14.4","protocol":498},"players":{"max":1,"online":0},"description":{"text":""}}"#;
+
+pub fn write_var_int(buf: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+pub fn write_string(buf: &mut Vec<u8>, string: &str) {
+    write_var_int(buf, string.len() as u32);
+    buf.extend_from_slice(string.as_bytes());
+}
+
+pub fn read_var_int(stream: &mut impl Read) -> io::Result<u32> {
+    let mut value = 0;
+    for shift in (0..35).step_by(7) {
+        let mut byte = [0];
+        stream.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u32) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+    Ok(value)
+}
+
+/// Reads a packet and returns its id.
+pub fn read_packet(stream: &mut TcpStream) -> u8 {
+    let length = read_var_int(stream).unwrap();
+    let mut packet = vec![0; length as usize];
+    stream.read_exact(&mut packet).unwrap();
+    packet[0]
+}
+
+pub fn write_packet(stream: &mut TcpStream, id: u8, body: &[u8]) {
+    let mut packet = Vec::new();
+    write_var_int(&mut packet, 1 + body.len() as u32);
+    packet.push(id);
+    packet.extend_from_slice(body);
+    stream.write_all(&packet).unwrap();
+}
+
+/// Plays the part of a 1.14.4 server for the protocol discovery connection of
+/// a login.
+pub fn serve_status(listener: &TcpListener) {
+    let (mut status, _) = listener.accept().unwrap();
+    assert_eq!(read_packet(&mut status), 0x00); // Handshake
+    assert_eq!(read_packet(&mut status), 0x00); // StatusRequest
+    let mut body = Vec::new();
+    write_string(&mut body, STATUS_RESPONSE);
+    write_packet(&mut status, 0x00, &body);
+    assert_eq!(read_packet(&mut status), 0x01); // StatusPing
+}
+
+/// Accepts the login connection and reads the packets that start the login.
+pub fn accept_login(listener: &TcpListener) -> TcpStream {
+    let (mut login, _) = listener.accept().unwrap();
+    assert_eq!(read_packet(&mut login), 0x00); // Handshake
+    assert_eq!(read_packet(&mut login), 0x00); // LoginStart
+    login
+}
+
+/// Encodes the body of a 1.14.4 ChunkData packet, without heightmaps or block
+/// entities.
+pub fn chunk_data_body(chunk: &Chunk) -> Vec<u8> {
+    let mut data = Vec::new();
+    chunk.encode(&mut data).unwrap();
+
+    let mut body = Vec::new();
+    body.extend_from_slice(&chunk.chunk_x.to_be_bytes());
+    body.extend_from_slice(&chunk.chunk_z.to_be_bytes());
+    body.push(chunk.is_full() as u8);
+    write_var_int(&mut body, chunk.primary_bit_mask() as u32);
+    body.push(0x00); // No heightmaps (TAG_End)
+    write_var_int(&mut body, data.len() as u32);
+    body.extend_from_slice(&data);
+    write_var_int(&mut body, 0); // No block entities
+    body
+}
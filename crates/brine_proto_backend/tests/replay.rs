@@ -0,0 +1,147 @@
+//! Records a login with a scripted server and checks that replaying the
+//! recording produces the same events.
+
+mod common;
+
+use std::{
+    io::{self, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{ecs::event::Events, prelude::*};
+
+use brine_net::{
+    record::{Recorder, Recording},
+    replay::{ReplayConnectionPlugin, ReplayTiming},
+    NetworkResource,
+};
+use brine_proto::{
+    event::{
+        clientbound::{Disconnect, LoginSuccess},
+        serverbound::Login,
+    },
+    ProtocolPlugin,
+};
+use brine_proto_backend::{backend_stevenarella::ProtocolCodec, ProtocolBackendPlugin};
+
+use common::{accept_login, serve_status, write_packet, write_string};
+
+const DISCONNECT_REASON: &str = r#"{"text":"Test over"}"#;
+
+/// Plays the part of a 1.14.4 server for the two connections of a login.
+fn scripted_server(listener: TcpListener) {
+    serve_status(&listener);
+
+    let mut login = accept_login(&listener);
+    write_packet(
+        &mut login,
+        0x02,
+        include_bytes!("../test/packet-data/login/login_success.dat"),
+    );
+    let mut body = Vec::new();
+    write_string(&mut body, DISCONNECT_REASON);
+    write_packet(&mut login, 0x1a, &body);
+    login.flush().unwrap();
+}
+
+/// Writer whose contents can still be read after it's been handed off.
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct ReceivedEvents(Vec<String>);
+
+fn collect_events(
+    mut received: ResMut<ReceivedEvents>,
+    mut login_success_events: EventReader<LoginSuccess>,
+    mut disconnect_events: EventReader<Disconnect>,
+) {
+    for event in login_success_events.iter() {
+        received.0.push(format!("{:?}", event));
+    }
+    for event in disconnect_events.iter() {
+        received.0.push(format!("{:?}", event));
+    }
+}
+
+fn make_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(ProtocolPlugin)
+        .add_plugin(ProtocolBackendPlugin)
+        .init_resource::<ReceivedEvents>()
+        .add_system(collect_events);
+    app
+}
+
+/// Logs in and runs the app until the server disconnects it.
+fn log_in(mut app: App, server: &str) -> Vec<String> {
+    app.world
+        .get_resource_mut::<Events<Login>>()
+        .unwrap()
+        .send(Login {
+            server: server.to_string(),
+            username: "Username".to_string(),
+        });
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        app.update();
+
+        let received = &app.world.get_resource::<ReceivedEvents>().unwrap().0;
+        if received.iter().any(|event| event.starts_with("Disconnect")) {
+            return received.clone();
+        }
+
+        assert!(Instant::now() < deadline, "timed out; got {:?}", received);
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn replay_produces_the_same_events() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = listener.local_addr().unwrap().to_string();
+    let server_thread = thread::spawn(move || scripted_server(listener));
+
+    let buf = SharedBuf::default();
+    let mut app = make_app();
+    app.world
+        .get_resource_mut::<NetworkResource<ProtocolCodec>>()
+        .unwrap()
+        .start_recording(Recorder::new(buf.clone()).unwrap());
+
+    let recorded_events = log_in(app, &server);
+    server_thread.join().unwrap();
+
+    assert_eq!(recorded_events.len(), 2, "{:?}", recorded_events);
+    assert!(recorded_events[0].starts_with("LoginSuccess"));
+
+    let bytes = buf.0.lock().unwrap().clone();
+    let recording = Recording::read_from(&bytes[..]).unwrap();
+    assert_eq!(recording.sessions.len(), 2);
+
+    // Nothing is listening anymore, so the replay can't be talking to it.
+    let mut app = make_app();
+    app.add_plugin(ReplayConnectionPlugin::<ProtocolCodec>::new(
+        recording,
+        ReplayTiming::FullSpeed,
+    ));
+
+    let replayed_events = log_in(app, &server);
+
+    assert_eq!(replayed_events, recorded_events);
+}
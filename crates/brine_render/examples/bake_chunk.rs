@@ -8,7 +8,7 @@ use bevy_inspector_egui::WorldInspectorPlugin;
 use brine_asset::MinecraftAssets;
 use brine_chunk::{BlockState, BlockStates, ChunkSection, BLOCKS_PER_SECTION};
 use brine_data::MinecraftData;
-use brine_render::chunk::ChunkBakery;
+use brine_render::chunk::{BakedChunk, ChunkBakery};
 
 fn main() {
     let mc_data = MinecraftData::for_version("1.14.4");
@@ -50,32 +50,55 @@ fn random_chunk() -> ChunkSection {
         block_count,
         chunk_y: 0,
         block_states: BlockStates(block_states),
+        block_light: None,
+        sky_light: None,
     }
 }
 
-fn bake_chunk(chunk: &ChunkSection, mc_data: &MinecraftData, mc_assets: &MinecraftAssets) -> Mesh {
+fn bake_chunk(
+    chunk: &ChunkSection,
+    mc_data: &MinecraftData,
+    mc_assets: &MinecraftAssets,
+) -> BakedChunk {
     let chunk_bakery = ChunkBakery::new(mc_data, mc_assets);
 
-    let baked_chunk = chunk_bakery.bake_chunk(chunk);
-
-    baked_chunk.mesh
+    chunk_bakery.bake_chunk(chunk)
 }
 
 fn setup(
     mc_data: Res<MinecraftData>,
     mc_assets: Res<MinecraftAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
     mut commands: Commands,
 ) {
     let chunk = random_chunk();
 
-    let mesh = bake_chunk(&chunk, &*mc_data, &*mc_assets);
+    let baked_chunk = bake_chunk(&chunk, &*mc_data, &*mc_assets);
 
     commands.spawn_bundle(PbrBundle {
-        mesh: meshes.add(mesh),
+        mesh: meshes.add(baked_chunk.mesh),
         ..Default::default()
     });
 
+    for fluid in baked_chunk.fluids {
+        let (base_color, alpha_mode) = if fluid.kind.is_translucent() {
+            (Color::rgba(0.25, 0.4, 0.9, 0.6), AlphaMode::Blend)
+        } else {
+            (Color::ORANGE_RED, AlphaMode::Opaque)
+        };
+
+        commands.spawn_bundle(PbrBundle {
+            mesh: meshes.add(fluid.mesh),
+            material: materials.add(StandardMaterial {
+                base_color,
+                alpha_mode,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+    }
+
     commands.spawn_bundle(PerspectiveCameraBundle {
         transform: Transform::from_translation(Vec3::new(30.0, 24.0, 30.0))
             .looking_at(Vec3::ONE * 8.0, Vec3::Y),
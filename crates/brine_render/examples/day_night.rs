@@ -0,0 +1,57 @@
+//! Fast-forwards through a full Minecraft day in 24 seconds, lighting a few
+//! boxes with the [`SkyPlugin`].
+
+use bevy::prelude::*;
+
+use brine_proto::{event::clientbound::TimeUpdate, ProtocolPlugin};
+use brine_render::sky::{SkyPlugin, TICKS_PER_DAY};
+
+/// How long the day takes, in seconds.
+const DAY_LENGTH: f64 = 24.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugin(ProtocolPlugin)
+        .add_plugin(SkyPlugin)
+        .add_startup_system(setup)
+        .add_system(fast_forward)
+        .run();
+}
+
+fn setup(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    commands.spawn_bundle(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Plane { size: 20.0 })),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..Default::default()
+    });
+
+    for x in -2..=2 {
+        commands.spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+            transform: Transform::from_xyz(x as f32 * 3.0, 0.5, 0.0),
+            ..Default::default()
+        });
+    }
+
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(0.0, 8.0, 16.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..Default::default()
+    });
+}
+
+/// Pretends to be a server that tells the client the time every frame.
+fn fast_forward(time: Res<Time>, mut time_events: EventWriter<TimeUpdate>) {
+    let ticks = time.seconds_since_startup() / DAY_LENGTH * TICKS_PER_DAY as f64;
+
+    time_events.send(TimeUpdate {
+        world_age: ticks as i64,
+        time_of_day: ticks as i64 % TICKS_PER_DAY,
+    });
+}
@@ -6,23 +6,43 @@ use bevy::render::{
 use brine_asset::MinecraftAssets;
 use brine_chunk::ChunkSection;
 use brine_data::MinecraftData;
-use brine_voxel::{Mesh as VoxelMesh, Mesher, SimpleMesher};
+use brine_voxel::{meshing::FluidKind, FluidMesher, Mesh as VoxelMesh, Mesher, SimpleMesher};
 
 use super::meshing_view::ChunkView;
 
 #[derive(Debug)]
 pub struct BakedChunk {
     pub mesh: Mesh,
+
+    /// One mesh for each kind of fluid in the chunk, drawn separately from
+    /// [`mesh`][Self::mesh].
+    pub fluids: Vec<BakedFluid>,
 }
 
 impl Default for BakedChunk {
     fn default() -> Self {
         Self {
             mesh: Mesh::new(PrimitiveTopology::TriangleList),
+            fluids: Vec::new(),
         }
     }
 }
 
+/// The surfaces of all of one kind of fluid in a chunk.
+///
+/// The mesh should be textured with the fluid's [`still_texture`], tinted by
+/// the biome if it has a [`tint_index`], and drawn in the translucent render
+/// pass if the fluid [`is_translucent`].
+///
+/// [`still_texture`]: FluidKind::still_texture
+/// [`tint_index`]: FluidKind::tint_index
+/// [`is_translucent`]: FluidKind::is_translucent
+#[derive(Debug)]
+pub struct BakedFluid {
+    pub kind: FluidKind,
+    pub mesh: Mesh,
+}
+
 pub struct ChunkBakery<'a> {
     mc_data: &'a MinecraftData,
     mc_assets: &'a MinecraftAssets,
@@ -40,7 +60,20 @@ impl<'a> ChunkBakery<'a> {
 
         let mesh = build_bevy_mesh(&voxel_mesh);
 
-        BakedChunk { mesh }
+        let view = ChunkView::new(self.mc_data, self.mc_assets, chunk);
+
+        let fluid_mesh = FluidMesher.generate_mesh(view);
+
+        let fluids = fluid_mesh
+            .iter()
+            .filter(|(_, voxel_mesh)| !voxel_mesh.quads.is_empty())
+            .map(|(kind, voxel_mesh)| BakedFluid {
+                kind,
+                mesh: build_bevy_mesh(voxel_mesh),
+            })
+            .collect();
+
+        BakedChunk { mesh, fluids }
     }
 }
 
@@ -64,11 +97,17 @@ pub fn build_bevy_mesh(voxel_mesh: &VoxelMesh) -> Mesh {
         tex_coords.extend_from_slice(&quad.get_tex_coords());
     }
 
+    let indices = if voxel_mesh.needs_u32_indices() {
+        Indices::U32(indices)
+    } else {
+        Indices::U16(indices.into_iter().map(|i| i as u16).collect())
+    };
+
     let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
     mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, positions);
     mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
     mesh.set_attribute(Mesh::ATTRIBUTE_UV_0, tex_coords);
-    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh.set_indices(Some(indices));
 
     mesh
 }
@@ -1,20 +1,23 @@
 use smallvec::SmallVec;
 
-use brine_asset::{BakedModel, BlockFace, MinecraftAssets};
+use brine_asset::{BakedBlockState, BakedModel, BlockFace, MinecraftAssets};
 use brine_chunk::{ChunkSection, SECTION_HEIGHT, SECTION_WIDTH};
 use brine_data::{blocks::Block, BlockStateId, MinecraftData};
-use brine_voxel::{meshing::QuadPositions, Direction, MeshingView, VoxelView};
+use brine_voxel::{
+    meshing::{Fluid, FluidKind, QuadPositions},
+    ChunkSectionView, Direction, FluidView, MeshingView, VoxelView,
+};
 
 pub struct ChunkView<'a> {
     mc_data: &'a MinecraftData,
     mc_assets: &'a MinecraftAssets,
-    chunk: &'a ChunkSection,
+    section: ChunkSectionView<'a>,
 }
 
 impl<'a> ChunkView<'a> {
-    const MAX_X: u8 = (SECTION_WIDTH as u8) - 1;
-    const MAX_Y: u8 = (SECTION_HEIGHT as u8) - 1;
-    const MAX_Z: u8 = (SECTION_WIDTH as u8) - 1;
+    const MAX_X: u16 = (SECTION_WIDTH as u16) - 1;
+    const MAX_Y: u16 = (SECTION_HEIGHT as u16) - 1;
+    const MAX_Z: u16 = (SECTION_WIDTH as u16) - 1;
 
     pub fn new(
         mc_data: &'a MinecraftData,
@@ -24,101 +27,123 @@ impl<'a> ChunkView<'a> {
         Self {
             mc_data,
             mc_assets,
-            chunk,
+            section: ChunkSectionView::new(chunk),
         }
     }
 
     #[inline]
-    pub fn get_block_state_id(&self, x: u8, y: u8, z: u8) -> BlockStateId {
-        let block_state = self.chunk.get_block((x, y, z)).unwrap();
+    pub fn get_block_state_id(&self, x: u16, y: u16, z: u16) -> BlockStateId {
+        let block_state = self.section.get_block(x, y, z);
         BlockStateId(block_state.0 as u16)
     }
 
     #[inline]
-    pub fn get_block(&self, x: u8, y: u8, z: u8) -> Option<Block<'a>> {
+    pub fn get_block(&self, x: u16, y: u16, z: u16) -> Option<Block<'a>> {
         let block_state_id = self.get_block_state_id(x, y, z);
         self.mc_data.blocks().get_by_state_id(block_state_id)
     }
 
     #[inline]
-    pub fn get_block_model(&self, x: u8, y: u8, z: u8) -> Option<&'a BakedModel> {
+    pub fn get_block_state(&self, x: u16, y: u16, z: u16) -> Option<&'a BakedBlockState> {
         let block_state_id = self.get_block_state_id(x, y, z);
-        let baked_block_state = self.mc_assets.block_states().get_by_key(block_state_id)?;
-        let model_key = baked_block_state.get_first_model()?;
-        self.mc_assets.models().get_by_key(model_key)
+        self.mc_assets.block_states().get_by_key(block_state_id)
     }
 
+    /// Returns all of the models that make up the block, e.g., the post plus
+    /// every arm of a fence, plus the water in it if it's waterlogged.
     #[inline]
-    pub fn is_air(&self, x: u8, y: u8, z: u8) -> bool {
+    pub fn get_block_models(
+        &self,
+        x: u16,
+        y: u16,
+        z: u16,
+    ) -> impl Iterator<Item = &'a BakedModel> + 'a {
+        let mc_assets = self.mc_assets;
+
+        self.get_block_state(x, y, z)
+            .into_iter()
+            .flat_map(|baked_block_state| baked_block_state.iter_first_models_with_overlay())
+            .filter_map(move |model_key| mc_assets.models().get_by_key(model_key))
+    }
+
+    #[inline]
+    pub fn is_air(&self, x: u16, y: u16, z: u16) -> bool {
         self.get_block(x, y, z)
             .map_or(false, |block| block.is_air())
     }
 
+    /// Returns the fluid that the block is, if it's water or lava.
+    ///
+    /// Waterlogged blocks are not fluids; their water is drawn as part of
+    /// their models.
+    #[inline]
+    pub fn get_fluid(&self, x: u16, y: u16, z: u16) -> Option<Fluid> {
+        let block_state_id = self.get_block_state_id(x, y, z);
+        let (name, level) = self.mc_data.blocks().fluid_level(block_state_id)?;
+
+        Some(Fluid {
+            kind: FluidKind::from_block_name(name)?,
+            level,
+        })
+    }
+
     #[inline]
     fn get_quads_for_block_face(
         &self,
-        x: u8,
-        y: u8,
-        z: u8,
+        x: u16,
+        y: u16,
+        z: u16,
         face: Option<Direction>,
     ) -> SmallVec<[QuadPositions; 6]> {
-        self.get_block_model(x, y, z)
-            .map_or(Default::default(), |model| {
-                let face = face.map(|direction| match direction {
-                    Direction::XNeg => BlockFace::West,
-                    Direction::XPos => BlockFace::East,
-                    Direction::YNeg => BlockFace::Down,
-                    Direction::YPos => BlockFace::Up,
-                    Direction::ZNeg => BlockFace::North,
-                    Direction::ZPos => BlockFace::South,
-                });
-
-                model
-                    .quads
-                    .iter()
-                    .filter(|quad| quad.cull_face == face)
-                    .map(|quad| {
-                        quad.positions
-                            .map(|[x0, y0, z0]| [x0 + x as f32, y0 + y as f32, z0 + z as f32])
-                    })
-                    .collect()
+        let face = face.map(BlockFace::from);
+
+        self.get_block_models(x, y, z)
+            .flat_map(|model| model.quads.iter())
+            .filter(|quad| quad.cull_face == face)
+            .map(|quad| {
+                quad.positions
+                    .map(|[x0, y0, z0]| [x0 + x as f32, y0 + y as f32, z0 + z as f32])
             })
+            .collect()
     }
 }
 
 impl<'a> VoxelView for ChunkView<'a> {
     #[inline(always)]
-    fn size_x(&self) -> u8 {
-        SECTION_WIDTH as u8
+    fn size_x(&self) -> u16 {
+        self.section.size_x()
     }
 
     #[inline(always)]
-    fn size_y(&self) -> u8 {
-        SECTION_HEIGHT as u8
+    fn size_y(&self) -> u16 {
+        self.section.size_y()
     }
 
     #[inline(always)]
-    fn size_z(&self) -> u8 {
-        SECTION_WIDTH as u8
+    fn size_z(&self) -> u16 {
+        self.section.size_z()
     }
 }
 
 impl<'a> MeshingView for ChunkView<'a> {
     type Quads = SmallVec<[QuadPositions; 6]>;
 
+    /// Fluids are meshed separately, by the [`FluidView`] implementation.
     #[inline]
-    fn is_empty(&self, x: u8, y: u8, z: u8) -> bool {
-        self.is_air(x, y, z)
+    fn is_empty(&self, x: u16, y: u16, z: u16) -> bool {
+        self.is_air(x, y, z) || self.get_fluid(x, y, z).is_some()
     }
 
     #[inline]
-    fn is_full_cube(&self, x: u8, y: u8, z: u8) -> bool {
-        self.get_block_model(x, y, z)
-            .map_or(false, |model| model.is_full_cube)
+    fn is_full_cube(&self, x: u16, y: u16, z: u16) -> bool {
+        self.get_block_state(x, y, z)
+            .map_or(false, |baked_block_state| {
+                !baked_block_state.models.is_empty() && baked_block_state.is_full_cube
+            })
     }
 
     #[inline]
-    fn is_face_occluded(&self, x: u8, y: u8, z: u8, face: Direction) -> bool {
+    fn is_face_occluded(&self, x: u16, y: u16, z: u16, face: Direction) -> bool {
         match (face, x, y, z) {
             // Faces on the edge of the chunk are always visible.
             (Direction::XNeg, 0, _, _)
@@ -136,12 +161,24 @@ impl<'a> MeshingView for ChunkView<'a> {
     }
 
     #[inline]
-    fn face_quads(&self, x: u8, y: u8, z: u8, face: Direction) -> Self::Quads {
+    fn face_quads(&self, x: u16, y: u16, z: u16, face: Direction) -> Self::Quads {
         self.get_quads_for_block_face(x, y, z, Some(face))
     }
 
     #[inline]
-    fn non_face_quads(&self, x: u8, y: u8, z: u8) -> Self::Quads {
+    fn non_face_quads(&self, x: u16, y: u16, z: u16) -> Self::Quads {
         self.get_quads_for_block_face(x, y, z, None)
     }
 }
+
+impl<'a> FluidView for ChunkView<'a> {
+    #[inline]
+    fn fluid(&self, x: u16, y: u16, z: u16) -> Option<Fluid> {
+        self.get_fluid(x, y, z)
+    }
+
+    #[inline]
+    fn is_opaque(&self, x: u16, y: u16, z: u16) -> bool {
+        !self.is_empty(x, y, z) && self.is_full_cube(x, y, z)
+    }
+}
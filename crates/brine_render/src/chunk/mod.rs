@@ -1,4 +1,4 @@
 mod chunk_bakery;
 pub(crate) mod meshing_view;
 
-pub use chunk_bakery::{BakedChunk, ChunkBakery};
+pub use chunk_bakery::{BakedChunk, BakedFluid, ChunkBakery};
@@ -1,2 +1,4 @@
 pub mod chunk;
+pub mod sky;
+pub mod text;
 pub mod texture;
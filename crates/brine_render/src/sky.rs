@@ -0,0 +1,223 @@
+//! Lighting the world according to the time of day.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use brine_proto::event::clientbound::TimeUpdate;
+
+/// Number of ticks in a Minecraft day.
+pub const TICKS_PER_DAY: i64 = 24000;
+
+/// Number of ticks that pass every second.
+pub const TICKS_PER_SECOND: f64 = 20.0;
+
+/// Illuminance of the sun at noon, in lux.
+const NOON_ILLUMINANCE: f32 = 32000.0;
+
+/// Brightness of the ambient light at noon and at midnight.
+const DAY_AMBIENT: f32 = 0.6;
+const NIGHT_AMBIENT: f32 = 0.1;
+
+const DAY_SKY: Color = Color::rgb(0.47, 0.65, 1.0);
+const NIGHT_SKY: Color = Color::rgb(0.0, 0.0, 0.04);
+
+/// Plugin that keeps track of the time of day sent by the server, and moves
+/// the sun (a directional light) across the sky to match it.
+///
+/// The clear color and the ambient light are also made darker at night.
+///
+/// # Resources
+///
+/// The plugin registers the following resources:
+///
+/// * [`WorldTime`]
+/// * [`ClearColor`]
+/// * [`AmbientLight`]
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldTime>()
+            .insert_resource(ClearColor(NIGHT_SKY))
+            .init_resource::<AmbientLight>()
+            .add_startup_system(spawn_sun)
+            .add_system(update_world_time.label(System::UpdateTime))
+            .add_system(update_sky.after(System::UpdateTime));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+pub enum System {
+    UpdateTime,
+}
+
+/// Resource that holds the current time in the world.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WorldTime {
+    /// Age of the world, in ticks.
+    pub world_age: f64,
+
+    /// Time of day, in ticks, between 0 and [`TICKS_PER_DAY`].
+    pub time_of_day: f64,
+
+    /// Whether the time of day stays the same as the world ages.
+    pub frozen: bool,
+}
+
+impl WorldTime {
+    /// Sets the time from a [`TimeUpdate`] event.
+    pub fn update(&mut self, time_update: &TimeUpdate) {
+        self.world_age = time_update.world_age as f64;
+        self.time_of_day = time_update.time_of_day.abs().rem_euclid(TICKS_PER_DAY) as f64;
+        self.frozen = time_update.time_of_day < 0;
+    }
+
+    /// Lets `seconds` of time pass.
+    pub fn advance(&mut self, seconds: f64) {
+        let ticks = seconds * TICKS_PER_SECOND;
+
+        self.world_age += ticks;
+        if !self.frozen {
+            self.time_of_day = (self.time_of_day + ticks).rem_euclid(TICKS_PER_DAY as f64);
+        }
+    }
+}
+
+/// Returns the angle of the sun above the eastern horizon, in radians, at the
+/// given time of day.
+///
+/// The sun rises at 0 ticks, is straight up at noon (6000 ticks), and sets at
+/// 12000 ticks. The angle keeps increasing past the western horizon during the
+/// night, wrapping around to 0 the next morning.
+pub fn sun_angle(time_of_day: f64) -> f32 {
+    let day_fraction = time_of_day.rem_euclid(TICKS_PER_DAY as f64) / TICKS_PER_DAY as f64;
+    day_fraction as f32 * TAU
+}
+
+/// Returns how bright it is at the given time of day, from 0 at night to 1
+/// during the day, changing gradually around sunrise and sunset.
+pub fn daylight(time_of_day: f64) -> f32 {
+    let sun_height = sun_angle(time_of_day).sin();
+    (sun_height * 2.0 + 0.5).clamp(0.0, 1.0)
+}
+
+/// Returns the direction pointing towards the sun at the given time of day.
+///
+/// The sun rises in the east (+X) and sets in the west (-X).
+pub fn sun_direction(time_of_day: f64) -> Vec3 {
+    let angle = sun_angle(time_of_day);
+    Vec3::new(angle.cos(), angle.sin(), 0.0)
+}
+
+/// Marker component for the directional light that acts as the sun.
+#[derive(Component)]
+pub struct Sun;
+
+fn spawn_sun(mut commands: Commands) {
+    commands
+        .spawn_bundle(DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: NOON_ILLUMINANCE,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert_bundle((Name::new("Sun"), Sun));
+}
+
+fn update_world_time(
+    time: Res<Time>,
+    mut time_events: EventReader<TimeUpdate>,
+    mut world_time: ResMut<WorldTime>,
+) {
+    match time_events.iter().last() {
+        Some(time_update) => world_time.update(time_update),
+        None => world_time.advance(time.delta_seconds_f64()),
+    }
+}
+
+fn update_sky(
+    world_time: Res<WorldTime>,
+    mut clear_color: ResMut<ClearColor>,
+    mut ambient_light: ResMut<AmbientLight>,
+    mut suns: Query<(&mut Transform, &mut DirectionalLight), With<Sun>>,
+) {
+    if !world_time.is_changed() {
+        return;
+    }
+
+    let time_of_day = world_time.time_of_day;
+    let daylight = daylight(time_of_day);
+
+    for (mut transform, mut light) in suns.iter_mut() {
+        // The light shines from the sun towards the ground.
+        let direction = sun_direction(time_of_day);
+        *transform = Transform::identity().looking_at(-direction, Vec3::Z);
+
+        light.illuminance = NOON_ILLUMINANCE * direction.y.max(0.0);
+    }
+
+    clear_color.0 = lerp_color(NIGHT_SKY, DAY_SKY, daylight);
+    ambient_light.brightness = NIGHT_AMBIENT + (DAY_AMBIENT - NIGHT_AMBIENT) * daylight;
+}
+
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = Vec4::from(from.as_rgba_f32());
+    let to = Vec4::from(to.as_rgba_f32());
+
+    Color::from(from.lerp(to, t))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    fn assert_approx_eq(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn sun_angle_follows_the_day() {
+        assert_approx_eq(sun_angle(0.0), 0.0);
+        assert_approx_eq(sun_angle(6000.0), FRAC_PI_2);
+        assert_approx_eq(sun_angle(12000.0), PI);
+        assert_approx_eq(sun_angle(18000.0), 3.0 * FRAC_PI_2);
+        assert_approx_eq(sun_angle(24000.0), 0.0);
+        assert_approx_eq(sun_angle(30000.0), FRAC_PI_2);
+    }
+
+    #[test]
+    fn sun_is_up_at_noon() {
+        assert!(sun_direction(6000.0).abs_diff_eq(Vec3::Y, 1e-5));
+        assert!(sun_direction(18000.0).abs_diff_eq(-Vec3::Y, 1e-5));
+
+        assert_eq!(daylight(6000.0), 1.0);
+        assert_eq!(daylight(18000.0), 0.0);
+        assert!(daylight(0.0) > 0.0 && daylight(0.0) < 1.0);
+    }
+
+    #[test]
+    fn time_advances_unless_frozen() {
+        let mut world_time = WorldTime::default();
+
+        world_time.update(&TimeUpdate {
+            world_age: 100,
+            time_of_day: 23990,
+        });
+        world_time.advance(1.0);
+        assert_eq!(world_time.world_age, 120.0);
+        assert_eq!(world_time.time_of_day, 10.0);
+
+        world_time.update(&TimeUpdate {
+            world_age: 200,
+            time_of_day: -6000,
+        });
+        world_time.advance(1.0);
+        assert_eq!(world_time.world_age, 220.0);
+        assert_eq!(world_time.time_of_day, 6000.0);
+        assert!(world_time.frozen);
+    }
+}
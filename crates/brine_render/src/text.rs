@@ -0,0 +1,277 @@
+//! Building meshes of text drawn with the Minecraft font.
+//!
+//! Text is laid out in font pixels, with the baseline of the first character
+//! at the origin and `+y` pointing up. A line of regular text is 8 pixels tall.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, Mesh},
+        render_resource::PrimitiveTopology,
+    },
+    sprite::Rect,
+};
+
+use brine_asset::{Font, Glyph};
+use brine_proto::chat::StyledSection;
+
+use crate::texture::TextureAtlas;
+
+/// Name of the per-vertex color attribute of text meshes.
+pub const ATTRIBUTE_COLOR: &str = "Vertex_Color";
+
+/// How far bold text is drawn a second time to the right of itself.
+const BOLD_OFFSET: f32 = 1.0;
+
+/// How far italic text leans to the right for each pixel above the baseline.
+const ITALIC_SHEAR: f32 = 0.25;
+
+/// Size of the box drawn for characters that aren't in the font.
+const MISSING_GLYPH_WIDTH: f32 = 5.0;
+const MISSING_GLYPH_HEIGHT: f32 = 8.0;
+
+const DEFAULT_COLOR: [u8; 3] = [0xff, 0xff, 0xff];
+
+/// The geometry of a piece of laid out text.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TextMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub colors: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+
+    /// Distance from the start of the text to the end of its last character.
+    pub width: f32,
+}
+
+impl TextMesh {
+    pub fn to_mesh(&self) -> Mesh {
+        let normals = vec![[0.0, 0.0, 1.0]; self.positions.len()];
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, self.positions.clone());
+        mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.set_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs.clone());
+        mesh.set_attribute(ATTRIBUTE_COLOR, self.colors.clone());
+        mesh.set_indices(Some(Indices::U32(self.indices.clone())));
+
+        mesh
+    }
+
+    /// Adds a quad with corners `min` and `max` that shows the `uv` region of
+    /// the atlas, leaning to the right by `shear` per pixel above the baseline.
+    fn add_quad(&mut self, min: Vec2, max: Vec2, uv: Rect, shear: f32, color: [f32; 4]) {
+        let base = self.positions.len() as u32;
+
+        let corners = [
+            (Vec2::new(min.x, max.y), Vec2::new(uv.min.x, uv.min.y)),
+            (Vec2::new(min.x, min.y), Vec2::new(uv.min.x, uv.max.y)),
+            (Vec2::new(max.x, min.y), Vec2::new(uv.max.x, uv.max.y)),
+            (Vec2::new(max.x, max.y), Vec2::new(uv.max.x, uv.min.y)),
+        ];
+
+        for (position, uv) in corners {
+            self.positions
+                .push([position.x + position.y * shear, position.y, 0.0]);
+            self.uvs.push(uv.into());
+            self.colors.push(color);
+        }
+
+        self.indices
+            .extend_from_slice(&[0, 1, 2, 0, 2, 3].map(|i| base + i));
+    }
+}
+
+/// Lays out styled text into a [`TextMesh`], using the glyphs of a [`Font`]
+/// whose textures have been stitched into a [`TextureAtlas`].
+pub struct TextMeshBuilder<'a> {
+    font: &'a Font,
+    atlas: &'a TextureAtlas,
+}
+
+impl<'a> TextMeshBuilder<'a> {
+    pub fn new(font: &'a Font, atlas: &'a TextureAtlas) -> Self {
+        Self { font, atlas }
+    }
+
+    /// Lays out the sections one after another on a single line.
+    pub fn build(&self, sections: &[StyledSection]) -> TextMesh {
+        let mut mesh = TextMesh::default();
+        let mut x = 0.0;
+
+        for section in sections.iter() {
+            let [r, g, b] = section
+                .color
+                .and_then(|color| color.rgb())
+                .unwrap_or(DEFAULT_COLOR);
+            let color = [r, g, b, 0xff].map(|c| c as f32 / 255.0);
+
+            let shear = if section.flags.italic {
+                ITALIC_SHEAR
+            } else {
+                0.0
+            };
+
+            for c in section.text.chars() {
+                let (min, max, uv, advance) = match self.font.get_glyph(c) {
+                    Some(glyph) => self.place_glyph(&glyph, x),
+                    None => (
+                        Vec2::new(x, -1.0),
+                        Vec2::new(x + MISSING_GLYPH_WIDTH, MISSING_GLYPH_HEIGHT - 1.0),
+                        self.atlas.placeholder_region,
+                        MISSING_GLYPH_WIDTH + 1.0,
+                    ),
+                };
+
+                if max.x > min.x {
+                    mesh.add_quad(min, max, uv, shear, color);
+
+                    if section.flags.bold {
+                        let offset = Vec2::new(BOLD_OFFSET, 0.0);
+                        mesh.add_quad(min + offset, max + offset, uv, shear, color);
+                    }
+                }
+
+                x += advance;
+                if section.flags.bold {
+                    x += BOLD_OFFSET;
+                }
+
+                mesh.width = x;
+            }
+        }
+
+        mesh
+    }
+
+    /// Returns the corners of the quad for a glyph that starts at `x`, the
+    /// region of the atlas that it shows, and its advance.
+    fn place_glyph(&self, glyph: &Glyph, x: f32) -> (Vec2, Vec2, Rect, f32) {
+        let texture = self.atlas.get_uv(glyph.texture_key);
+        let size = texture.max - texture.min;
+        let uv = Rect {
+            min: texture.min + Vec2::from(glyph.uv[0]) * size,
+            max: texture.min + Vec2::from(glyph.uv[1]) * size,
+        };
+
+        let top = glyph.ascent;
+        let min = Vec2::new(x, top - glyph.height);
+        let max = Vec2::new(x + glyph.width, top);
+
+        (min, max, uv, glyph.advance)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::utils::HashMap;
+
+    use brine_asset::TextureKey;
+    use brine_proto::chat::{ChatColor, TextFlags};
+
+    use super::*;
+
+    fn glyph(width: f32) -> Glyph {
+        Glyph {
+            texture_key: TextureKey(0),
+            uv: [[0.0, 0.0], [0.5, 1.0]],
+            advance: width + 1.0,
+            width,
+            height: 8.0,
+            ascent: 7.0,
+        }
+    }
+
+    fn font() -> Font {
+        let mut font = Font::default();
+        font.add_glyphs([('a', glyph(5.0)), ('!', glyph(1.0))]);
+        font
+    }
+
+    fn atlas() -> TextureAtlas {
+        let mut regions = HashMap::default();
+        regions.insert(
+            TextureKey(0),
+            Rect {
+                min: Vec2::new(0.5, 0.0),
+                max: Vec2::new(1.0, 0.5),
+            },
+        );
+
+        TextureAtlas {
+            texture: Default::default(),
+            regions,
+            placeholder_region: Rect {
+                min: Vec2::new(0.0, 0.5),
+                max: Vec2::new(0.5, 1.0),
+            },
+        }
+    }
+
+    fn section(text: &str, flags: TextFlags) -> StyledSection {
+        StyledSection {
+            text: String::from(text),
+            color: None,
+            flags,
+        }
+    }
+
+    #[test]
+    fn glyphs_are_placed_by_advance() {
+        let (font, atlas) = (font(), atlas());
+        let mesh = TextMeshBuilder::new(&font, &atlas).build(&[section("a!a", Default::default())]);
+
+        assert_eq!(mesh.width, 6.0 + 2.0 + 6.0);
+        assert_eq!(mesh.indices.len(), 3 * 6);
+
+        // Top left and bottom right corners of the `!`.
+        assert_eq!(mesh.positions[4], [6.0, 7.0, 0.0]);
+        assert_eq!(mesh.positions[6], [7.0, -1.0, 0.0]);
+
+        // Glyph UVs are relative to the glyph's texture in the atlas.
+        assert_eq!(mesh.uvs[4], [0.5, 0.0]);
+        assert_eq!(mesh.uvs[6], [0.75, 0.5]);
+    }
+
+    #[test]
+    fn styles() {
+        let (font, atlas) = (font(), atlas());
+        let builder = TextMeshBuilder::new(&font, &atlas);
+
+        let bold = TextFlags {
+            bold: true,
+            ..Default::default()
+        };
+        let mesh = builder.build(&[section("a", bold)]);
+        assert_eq!(mesh.width, 7.0);
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.positions[4][0], mesh.positions[0][0] + 1.0);
+
+        let italic = TextFlags {
+            italic: true,
+            ..Default::default()
+        };
+        let mesh = builder.build(&[section("a", italic)]);
+        assert_eq!(mesh.positions[0], [7.0 * 0.25, 7.0, 0.0]);
+        assert_eq!(mesh.positions[1], [-0.25, -1.0, 0.0]);
+
+        let mut red = section("a", Default::default());
+        red.color = Some(ChatColor::Red);
+        let mesh = builder.build(&[red]);
+        assert_eq!(
+            mesh.colors[0],
+            [1.0, 0x55 as f32 / 255.0, 0x55 as f32 / 255.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn missing_glyphs_are_boxes() {
+        let (font, atlas) = (font(), atlas());
+        let mesh =
+            TextMeshBuilder::new(&font, &atlas).build(&[section("\u{2603}", Default::default())]);
+
+        assert_eq!(mesh.width, 6.0);
+        assert_eq!(mesh.uvs[0], [0.0, 0.5]);
+        assert_eq!(mesh.uvs[2], [0.5, 1.0]);
+    }
+}
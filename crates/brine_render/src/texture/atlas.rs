@@ -1,7 +1,9 @@
-use bevy::{prelude::*, reflect::TypeUuid, sprite::Rect, utils::HashMap};
+use bevy::{prelude::*, reflect::TypeUuid, sprite::Rect, tasks::Task, utils::HashMap};
 
 use brine_asset::TextureKey;
 
+use super::stitch::StitchedImage;
+
 #[derive(Debug, Clone, TypeUuid)]
 #[uuid = "3e8bc6e9-b91f-4f11-81ef-105ec53fa370"]
 pub struct TextureAtlas {
@@ -30,53 +32,31 @@ impl TextureAtlas {
             .unwrap_or(self.placeholder_region)
     }
 
-    pub fn stitch<'a, T>(
+    /// Creates an atlas from images that have been stitched together with
+    /// [`stitch_images`][super::stitch_images], adding the atlas image to
+    /// `assets`.
+    ///
+    /// The stitched images must be the textures for the given keys, in order,
+    /// followed by the placeholder texture.
+    pub fn from_stitched(
         assets: &mut Assets<Image>,
-        textures: T,
-        placeholder_texture: &Handle<Image>,
-        max_texture_size: u32,
-    ) -> Self
-    where
-        T: IntoIterator<Item = (TextureKey, &'a Handle<Image>)>,
-    {
-        let textures: Vec<(TextureKey, &Handle<Image>)> = textures.into_iter().collect();
-
-        debug!("Stitching texture atlas with {} textures", textures.len());
-
-        let mut builder = bevy::sprite::TextureAtlasBuilder::default()
-            .max_size(Vec2::new(max_texture_size as f32, max_texture_size as f32));
-
-        for (_, handle) in textures.iter() {
-            let image = assets.get(*handle).expect("all textures must be loaded");
-            builder.add_texture(handle.clone_weak(), image);
-        }
-
-        builder.add_texture(
-            placeholder_texture.clone_weak(),
-            assets.get(placeholder_texture).unwrap(),
-        );
-
-        let bevy_atlas = builder.finish(assets).unwrap();
-
-        let atlas_image = assets.get(&bevy_atlas.texture).unwrap();
-        let atlas_size = atlas_image.texture_descriptor.size;
-        let atlas_size = Vec2::new(atlas_size.width as f32, atlas_size.height as f32);
-
-        let handle_to_uv = |handle: &Handle<Image>| {
-            let index = bevy_atlas.get_texture_index(handle).unwrap();
-            let pixel_rect = bevy_atlas.textures[index];
-            Rect {
-                min: pixel_rect.min / atlas_size,
-                max: pixel_rect.max / atlas_size,
-            }
+        keys: &[TextureKey],
+        stitched: StitchedImage,
+    ) -> Self {
+        let atlas_size = stitched.size();
+
+        let to_uv = |pixel_rect: &Rect| Rect {
+            min: pixel_rect.min / atlas_size,
+            max: pixel_rect.max / atlas_size,
         };
 
-        let key_to_uv = textures
+        let key_to_uv = keys
             .iter()
-            .map(|(key, handle)| (*key, handle_to_uv(handle)))
+            .zip(stitched.rects.iter())
+            .map(|(key, pixel_rect)| (*key, to_uv(pixel_rect)))
             .collect();
 
-        let placeholder_uv = handle_to_uv(placeholder_texture);
+        let placeholder_uv = to_uv(stitched.rects.last().unwrap());
 
         debug!(
             "Done. Final atlas size: {} x {}",
@@ -84,7 +64,7 @@ impl TextureAtlas {
         );
 
         Self {
-            texture: bevy_atlas.texture,
+            texture: assets.add(stitched.image),
             regions: key_to_uv,
             placeholder_region: placeholder_uv,
         }
@@ -108,3 +88,16 @@ impl PendingAtlas {
             .all(|(_, handle)| assets.contains(handle))
     }
 }
+
+/// An atlas whose textures are being stitched together on the
+/// [`AsyncComputeTaskPool`][bevy::tasks::AsyncComputeTaskPool].
+#[derive(Debug)]
+pub(crate) struct StitchingAtlas {
+    /// The textures in the atlas, not counting the placeholder.
+    pub keys: Vec<TextureKey>,
+
+    /// Strong handle that we will populate with the atlas once it's stitched.
+    pub handle: Handle<TextureAtlas>,
+
+    pub task: Task<Option<StitchedImage>>,
+}
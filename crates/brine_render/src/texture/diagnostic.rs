@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+};
+
+/// Time (in milliseconds) that the main thread spent on texture atlases last
+/// frame, i.e., copying textures to stitch and adding stitched atlases.
+///
+/// The stitching itself happens on the [`AsyncComputeTaskPool`] and isn't
+/// counted.
+///
+/// [`AsyncComputeTaskPool`]: bevy::tasks::AsyncComputeTaskPool
+pub const ATLAS_MAIN_THREAD_TIME: DiagnosticId =
+    DiagnosticId::from_u128(0x2f6a_b0d3_97c4_4e15_a8d1_63e9_0b7c_4a52);
+
+/// Resource that systems working on texture atlases add their main thread time
+/// to, once it exists.
+#[derive(Debug, Default)]
+pub struct AtlasMainThreadTime(pub Duration);
+
+/// Plugin that reports [`ATLAS_MAIN_THREAD_TIME`] as a diagnostic.
+///
+/// Compare it with the frame time while lots of chunks are arriving to see how
+/// much of each frame goes to texture atlases.
+pub struct AtlasDiagnosticsPlugin;
+
+impl Plugin for AtlasDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AtlasMainThreadTime>();
+        app.add_startup_system(setup_diagnostics);
+        app.add_system_to_stage(CoreStage::Last, measure_atlas_main_thread_time);
+    }
+}
+
+fn setup_diagnostics(diagnostics: Option<ResMut<Diagnostics>>) {
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add(Diagnostic::new(
+            ATLAS_MAIN_THREAD_TIME,
+            "atlas_main_thread_time",
+            20,
+        ));
+    }
+}
+
+fn measure_atlas_main_thread_time(
+    mut time: ResMut<AtlasMainThreadTime>,
+    diagnostics: Option<ResMut<Diagnostics>>,
+) {
+    let elapsed = std::mem::take(&mut time.0);
+
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add_measurement(ATLAS_MAIN_THREAD_TIME, elapsed.as_secs_f64() * 1000.0);
+    }
+}
@@ -1,8 +1,16 @@
-use bevy::{asset::HandleId, prelude::*, render::options::WgpuOptions, utils::HashMap};
+use std::time::Instant;
+
+use bevy::{
+    asset::HandleId, prelude::*, render::options::WgpuOptions, tasks::AsyncComputeTaskPool,
+    utils::HashMap,
+};
+use futures_lite::future;
 
 use brine_asset::TextureKey;
 
-use crate::texture::{PendingAtlas, TextureAtlas};
+use crate::texture::{
+    stitch_images, AtlasMainThreadTime, PendingAtlas, StitchingAtlas, TextureAtlas,
+};
 
 const PLACEHOLDER_PATH: &str = "placeholder.png";
 
@@ -23,6 +31,9 @@ pub struct TextureManager {
 
     /// List of atlases that are waiting for their textures to be loaded.
     pending_atlases: Vec<PendingAtlas>,
+
+    /// List of atlases that are being stitched.
+    stitching_atlases: Vec<StitchingAtlas>,
 }
 
 impl TextureManager {
@@ -33,6 +44,7 @@ impl TextureManager {
             atlases: Default::default(),
             key_to_atlas: Default::default(),
             pending_atlases: Default::default(),
+            stitching_atlases: Default::default(),
         }
     }
 
@@ -77,43 +89,83 @@ impl TextureManager {
         self.atlases.iter()
     }
 
+    /// Starts stitching each pending atlas whose textures have all loaded.
+    ///
+    /// The textures are copied so that they can be stitched on the task pool.
+    /// See [`finish_stitched_atlases`][Self::finish_stitched_atlases].
     pub fn try_stitch_pending_atlases(
         &mut self,
-        textures: &mut Assets<Image>,
-        atlases: &mut Assets<TextureAtlas>,
+        textures: &Assets<Image>,
+        task_pool: &AsyncComputeTaskPool,
     ) {
-        if !textures.contains(&self.placeholder_texture) {
-            return;
-        }
+        let placeholder = match textures.get(&self.placeholder_texture) {
+            Some(placeholder) => placeholder,
+            None => return,
+        };
+
+        let max_texture_size = self.max_texture_size;
+        let stitching_atlases = &mut self.stitching_atlases;
 
         self.pending_atlases.retain(|pending_atlas| {
             let is_ready = pending_atlas.all_textures_loaded(textures);
 
             if is_ready {
-                let atlas_handle = pending_atlas.handle.clone();
-                let atlas = TextureAtlas::stitch(
-                    textures,
-                    pending_atlas
-                        .textures
-                        .iter()
-                        .map(|(key, handle)| (*key, handle)),
-                    &self.placeholder_texture,
-                    self.max_texture_size,
+                debug!(
+                    "Stitching texture atlas with {} textures",
+                    pending_atlas.textures.len()
                 );
 
-                let index = self.atlases.len();
-                for texture_key in atlas.regions.keys() {
-                    self.key_to_atlas.insert(*texture_key, index);
-                }
+                let (keys, mut images): (Vec<TextureKey>, Vec<Image>) = pending_atlas
+                    .textures
+                    .iter()
+                    .map(|(key, handle)| (*key, textures.get(handle).unwrap().clone()))
+                    .unzip();
+                images.push(placeholder.clone());
 
-                atlases.set_untracked(&atlas_handle, atlas);
-                self.atlases.push(atlas_handle);
+                let task = task_pool.spawn(async move { stitch_images(&images, max_texture_size) });
+
+                stitching_atlases.push(StitchingAtlas {
+                    keys,
+                    handle: pending_atlas.handle.clone(),
+                    task,
+                });
             }
 
-            // Remove it from the list if we just stitched it.
+            // Remove it from the list if we started stitching it.
             !is_ready
         });
     }
+
+    /// Adds each atlas that has finished stitching to `atlases`.
+    pub fn finish_stitched_atlases(
+        &mut self,
+        textures: &mut Assets<Image>,
+        atlases: &mut Assets<TextureAtlas>,
+    ) {
+        let mut still_stitching = Vec::new();
+
+        for mut stitching_atlas in std::mem::take(&mut self.stitching_atlases) {
+            let stitched = match future::block_on(future::poll_once(&mut stitching_atlas.task)) {
+                Some(stitched) => stitched.expect("textures must fit in the atlas"),
+                None => {
+                    still_stitching.push(stitching_atlas);
+                    continue;
+                }
+            };
+
+            let atlas = TextureAtlas::from_stitched(textures, &stitching_atlas.keys, stitched);
+
+            let index = self.atlases.len();
+            for texture_key in atlas.regions.keys() {
+                self.key_to_atlas.insert(*texture_key, index);
+            }
+
+            atlases.set_untracked(&stitching_atlas.handle, atlas);
+            self.atlases.push(stitching_atlas.handle);
+        }
+
+        self.stitching_atlases = still_stitching;
+    }
 }
 
 impl FromWorld for TextureManager {
@@ -142,6 +194,85 @@ fn stitch_pending_atlases(
     mut manager: ResMut<TextureManager>,
     mut textures: ResMut<Assets<Image>>,
     mut atlases: ResMut<Assets<TextureAtlas>>,
+    task_pool: Res<AsyncComputeTaskPool>,
+    main_thread_time: Option<ResMut<AtlasMainThreadTime>>,
 ) {
-    manager.try_stitch_pending_atlases(&mut *textures, &mut *atlases);
+    let start = Instant::now();
+
+    manager.finish_stitched_atlases(&mut *textures, &mut *atlases);
+    manager.try_stitch_pending_atlases(&*textures, &*task_pool);
+
+    if let Some(mut main_thread_time) = main_thread_time {
+        main_thread_time.0 += start.elapsed();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use bevy::{
+        asset::AssetPlugin,
+        core::CorePlugin,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::*;
+
+    fn image(value: u8) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[value; 4],
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    #[test]
+    fn atlases_are_added_once_stitched() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .add_system(stitch_pending_atlases);
+
+        let (placeholder, texture) = {
+            let mut images = app.world.get_resource_mut::<Assets<Image>>().unwrap();
+            (images.add(image(0)), images.add(image(1)))
+        };
+
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap().clone();
+        let mut manager = TextureManager::new(placeholder, 1024);
+        let handle = manager.create_atlas(&asset_server, [(TextureKey(7), texture)]);
+        app.insert_resource(manager);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app
+            .world
+            .get_resource::<Assets<TextureAtlas>>()
+            .unwrap()
+            .contains(&handle)
+        {
+            assert!(Instant::now() < deadline, "atlas was never stitched");
+            app.update();
+        }
+
+        let manager = app.world.get_resource::<TextureManager>().unwrap();
+        assert_eq!(manager.get_atlas(TextureKey(7)), Some(handle.clone()));
+
+        let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+        let atlas = atlases.get(&handle).unwrap();
+        assert_ne!(
+            atlas.get_uv(TextureKey(7)).min,
+            atlas.placeholder_region.min
+        );
+
+        let images = app.world.get_resource::<Assets<Image>>().unwrap();
+        assert!(images.contains(&atlas.texture));
+    }
 }
@@ -15,7 +15,7 @@ pub struct MinecraftTexturesPlugin;
 impl Plugin for MinecraftTexturesPlugin {
     fn build(&self, app: &mut App) {
         app.add_state(MinecraftTexturesState::Loading);
-        app.init_resource::<TheAtlas>();
+        app.init_resource::<MinecraftTextureAtlas>();
         // app.add_startup_system(setup);
         app.add_system_set(SystemSet::on_enter(MinecraftTexturesState::Loading).with_system(setup));
         app.add_system_set(
@@ -24,9 +24,11 @@ impl Plugin for MinecraftTexturesPlugin {
     }
 }
 
+/// Resource holding the handle of the atlas of every Minecraft texture, which
+/// is available once the state is [`MinecraftTexturesState::Loaded`].
 #[derive(Default)]
-struct TheAtlas {
-    handle: Handle<TextureAtlas>,
+pub struct MinecraftTextureAtlas {
+    pub handle: Handle<TextureAtlas>,
 }
 
 fn get_all_textures<'a>(
@@ -41,6 +43,8 @@ fn get_all_textures<'a>(
 
             if texture_id.path().starts_with("block/")
                 // || texture_id.path().starts_with("effect/")
+                || (texture_id.path().starts_with("font/")
+                    && !texture_id.path().starts_with("font/unicode_page_"))
                 // || texture_id.path().starts_with("item/")
                 // || texture_id.path().starts_with("mob_effect/")
                 || texture_id.path().starts_with("painting/")
@@ -59,7 +63,7 @@ fn get_all_textures<'a>(
 fn setup(
     mc_assets: Res<MinecraftAssets>,
     asset_server: Res<AssetServer>,
-    mut the_atlas: ResMut<TheAtlas>,
+    mut the_atlas: ResMut<MinecraftTextureAtlas>,
     mut texture_manager: ResMut<TextureManager>,
 ) {
     let textures = get_all_textures(&*mc_assets, &*asset_server);
@@ -70,7 +74,7 @@ fn setup(
 
 /// This system advances the state to `Loaded` once the texture atlas(es) is/are available.
 fn await_loaded(
-    the_atlas: Res<TheAtlas>,
+    the_atlas: Res<MinecraftTextureAtlas>,
     atlases: Res<Assets<TextureAtlas>>,
     mut state: ResMut<State<MinecraftTexturesState>>,
 ) {
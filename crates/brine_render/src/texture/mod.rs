@@ -1,9 +1,13 @@
 mod atlas;
+mod diagnostic;
 mod manager;
 mod mc_textures;
+mod stitch;
 
 pub use atlas::TextureAtlas;
+pub use diagnostic::{AtlasDiagnosticsPlugin, AtlasMainThreadTime, ATLAS_MAIN_THREAD_TIME};
 pub use manager::{TextureManager, TextureManagerPlugin};
-pub use mc_textures::{MinecraftTexturesPlugin, MinecraftTexturesState};
+pub use mc_textures::{MinecraftTextureAtlas, MinecraftTexturesPlugin, MinecraftTexturesState};
+pub use stitch::{stitch_images, StitchedImage};
 
-pub(crate) use atlas::PendingAtlas;
+pub(crate) use atlas::{PendingAtlas, StitchingAtlas};
@@ -0,0 +1,203 @@
+//! Stitching images together into the image of a texture atlas.
+//!
+//! Stitching works on copies of the images rather than on handles into
+//! [`Assets<Image>`], so that it can run on a task pool without holding up the
+//! main thread.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    sprite::Rect,
+};
+
+/// The format of stitched atlases. Images in any other format are converted.
+const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+const BYTES_PER_PIXEL: usize = 4;
+
+/// Size of the smallest atlas that is tried. The size is doubled until all of
+/// the images fit.
+const INITIAL_SIZE: u32 = 256;
+
+/// The result of [`stitch_images`].
+#[derive(Debug)]
+pub struct StitchedImage {
+    /// The image of the atlas.
+    pub image: Image,
+
+    /// The region of the atlas (in pixels) that each image was copied to, in
+    /// the order in which the images were given.
+    pub rects: Vec<Rect>,
+}
+
+impl StitchedImage {
+    pub fn size(&self) -> Vec2 {
+        let size = self.image.texture_descriptor.size;
+        Vec2::new(size.width as f32, size.height as f32)
+    }
+}
+
+/// Copies the images into a single square image, no larger than `max_size`
+/// pixels on each side.
+///
+/// Returns `None` if the images don't fit, or if one of them can't be
+/// converted to the format of the atlas.
+pub fn stitch_images(images: &[Image], max_size: u32) -> Option<StitchedImage> {
+    let converted: Vec<Option<Image>> = images
+        .iter()
+        .map(|image| {
+            if image.texture_descriptor.format == FORMAT {
+                Some(None)
+            } else {
+                image.convert(FORMAT).map(Some)
+            }
+        })
+        .collect::<Option<_>>()?;
+
+    let images: Vec<&Image> = images
+        .iter()
+        .zip(converted.iter())
+        .map(|(image, converted)| converted.as_ref().unwrap_or(image))
+        .collect();
+
+    let sizes: Vec<UVec2> = images
+        .iter()
+        .map(|image| {
+            let size = image.texture_descriptor.size;
+            UVec2::new(size.width, size.height)
+        })
+        .collect();
+
+    let mut atlas_size = INITIAL_SIZE.min(max_size);
+    let positions = loop {
+        if let Some(positions) = pack(&sizes, atlas_size) {
+            break positions;
+        }
+
+        if atlas_size >= max_size {
+            return None;
+        }
+        atlas_size = (atlas_size * 2).min(max_size);
+    };
+
+    let row_length = atlas_size as usize * BYTES_PER_PIXEL;
+    let mut data = vec![0; row_length * atlas_size as usize];
+
+    for ((image, size), position) in images.iter().zip(sizes.iter()).zip(positions.iter()) {
+        let image_row_length = size.x as usize * BYTES_PER_PIXEL;
+
+        for (y, row) in image.data.chunks_exact(image_row_length).enumerate() {
+            let start =
+                (position.y as usize + y) * row_length + position.x as usize * BYTES_PER_PIXEL;
+            data[start..start + image_row_length].copy_from_slice(row);
+        }
+    }
+
+    let rects = sizes
+        .iter()
+        .zip(positions.iter())
+        .map(|(size, position)| Rect {
+            min: position.as_vec2(),
+            max: (*position + *size).as_vec2(),
+        })
+        .collect();
+
+    let image = Image::new(
+        Extent3d {
+            width: atlas_size,
+            height: atlas_size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        FORMAT,
+    );
+
+    Some(StitchedImage { image, rects })
+}
+
+/// Places rectangles of the given sizes on shelves, tallest first, inside a
+/// square of side `atlas_size`.
+///
+/// Returns the top left corner of each rectangle, or `None` if they don't fit.
+fn pack(sizes: &[UVec2], atlas_size: u32) -> Option<Vec<UVec2>> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(sizes[index].y));
+
+    let mut positions = vec![UVec2::ZERO; sizes.len()];
+    let mut shelf = UVec2::ZERO;
+    let mut shelf_height = 0;
+
+    for index in order {
+        let size = sizes[index];
+
+        if shelf.x + size.x > atlas_size {
+            shelf = UVec2::new(0, shelf.y + shelf_height);
+            shelf_height = 0;
+        }
+
+        if shelf.x + size.x > atlas_size || shelf.y + size.y > atlas_size {
+            return None;
+        }
+
+        positions[index] = shelf;
+        shelf.x += size.x;
+        shelf_height = shelf_height.max(size.y);
+    }
+
+    Some(positions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn image(width: u32, height: u32, value: u8) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[value; BYTES_PER_PIXEL],
+            FORMAT,
+        )
+    }
+
+    fn pixel(image: &Image, x: f32, y: f32) -> u8 {
+        let width = image.texture_descriptor.size.width as usize;
+        image.data[(y as usize * width + x as usize) * BYTES_PER_PIXEL]
+    }
+
+    #[test]
+    fn images_are_copied_to_their_rects() {
+        let images = [image(16, 16, 1), image(16, 32, 2), image(8, 8, 3)];
+        let stitched = stitch_images(&images, 1024).unwrap();
+
+        assert_eq!(stitched.size(), Vec2::new(256.0, 256.0));
+        assert_eq!(stitched.rects.len(), 3);
+
+        for (rect, value) in stitched.rects.iter().zip([1, 2, 3]) {
+            assert_eq!(pixel(&stitched.image, rect.min.x, rect.min.y), value);
+            assert_eq!(
+                pixel(&stitched.image, rect.max.x - 1.0, rect.max.y - 1.0),
+                value
+            );
+        }
+
+        assert_eq!(
+            stitched.rects[1].max - stitched.rects[1].min,
+            Vec2::new(16.0, 32.0)
+        );
+    }
+
+    #[test]
+    fn atlas_grows_until_images_fit() {
+        let images = vec![image(16, 16, 1); 512];
+
+        let stitched = stitch_images(&images, 1024).unwrap();
+        assert_eq!(stitched.size(), Vec2::new(512.0, 512.0));
+
+        assert!(stitch_images(&images, 256).is_none());
+    }
+}
@@ -17,14 +17,28 @@ struct Root;
 
 pub struct MeshViewerPlugin {
     mesh: VoxelMesh,
+    side: u8,
 }
 
 impl MeshViewerPlugin {
     pub fn new(mesh: VoxelMesh) -> Self {
-        Self { mesh }
+        Self {
+            mesh,
+            side: CHUNK_SIDE,
+        }
+    }
+
+    /// Centers the view on a mesh of voxels that are `side` voxels across,
+    /// instead of [`CHUNK_SIDE`].
+    pub fn with_side(mut self, side: u8) -> Self {
+        self.side = side;
+        self
     }
 }
 
+/// Number of voxels across the mesh being viewed.
+struct Side(u8);
+
 impl Plugin for MeshViewerPlugin {
     fn build(&self, app: &mut App) {
         let mesh = build_bevy_mesh(&self.mesh);
@@ -33,6 +47,7 @@ impl Plugin for MeshViewerPlugin {
         let handle = meshes.add(mesh);
 
         app.world.insert_resource(handle);
+        app.world.insert_resource(Side(self.side));
 
         app.insert_resource(Msaa { samples: 4 })
             .insert_resource(WgpuOptions {
@@ -48,11 +63,12 @@ impl Plugin for MeshViewerPlugin {
 
 fn setup(
     mesh: Res<Handle<Mesh>>,
+    side: Res<Side>,
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut commands: Commands,
 ) {
-    let offset = CHUNK_SIDE as f32 / 2.0;
+    let offset = side.0 as f32 / 2.0;
 
     commands
         .spawn_bundle((Transform::default(), GlobalTransform::default(), Root))
@@ -74,8 +90,9 @@ fn setup(
     //     Transform::from_translation(Vec3::new(5.0, 5.0, 5.0)).looking_at(Vec3::ZERO, Vec3::Y);
     // camera.orthographic_projection.scale = 5.0;
 
+    let distance = side.0 as f32 * 1.25;
     let camera = PerspectiveCameraBundle {
-        transform: Transform::from_translation(Vec3::new(5.0, 5.0, 5.0))
+        transform: Transform::from_translation(Vec3::new(distance, distance, distance))
             .looking_at(Vec3::ZERO, Vec3::Y),
         ..Default::default()
     };
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use brine_chunk::worldgen::Preset;
+
 pub const CHUNK_SIDE: u8 = 4;
 pub const CHUNK_SIDE_USIZE: usize = CHUNK_SIDE as usize;
 pub const CHUNK_VOXELS: usize = CHUNK_SIDE_USIZE * CHUNK_SIDE_USIZE * CHUNK_SIDE_USIZE;
@@ -15,10 +17,13 @@ impl IntChunk {
         Self([value; CHUNK_VOXELS])
     }
 
-    pub fn random(max: u32) -> Self {
+    /// Returns a chunk of random values up to `max`, which are the same for
+    /// the same `seed`.
+    pub fn random(max: u32, seed: u64) -> Self {
+        let rng = fastrand::Rng::with_seed(seed);
         let mut ints = [0; CHUNK_VOXELS];
         for i in ints.iter_mut() {
-            *i = fastrand::u32(..max + 1);
+            *i = rng.u32(..max + 1);
         }
         Self(ints)
     }
@@ -37,6 +42,38 @@ impl IntChunk {
     }
 }
 
+/// Command line arguments shared by the examples.
+///
+/// ```text
+/// cargo run --example mesher -- --preset hills --seed 42
+/// ```
+#[derive(Debug, Default)]
+pub struct ExampleArgs {
+    /// Which test world to generate, if any.
+    pub preset: Option<Preset>,
+    pub seed: u64,
+}
+
+impl ExampleArgs {
+    pub fn parse() -> Self {
+        let mut parsed = Self::default();
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .unwrap_or_else(|| panic!("{} needs a value", arg));
+            match arg.as_str() {
+                "--preset" => parsed.preset = Some(value.parse().unwrap()),
+                "--seed" => parsed.seed = value.parse().unwrap(),
+                _ => panic!("unknown argument {}", arg),
+            }
+        }
+
+        parsed
+    }
+}
+
 /*
       0   1   0   1
     0   0   1   1
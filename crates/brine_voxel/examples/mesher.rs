@@ -1,53 +1,85 @@
 use bevy::prelude::*;
 
+use brine_chunk::{worldgen::WorldGen, BlockState, ChunkSection, SECTION_WIDTH};
 use brine_voxel::{Direction, Mesher, MeshingView, SimpleMesher, VoxelView};
 
 mod common;
 
-use common::{IntChunk, MeshViewerPlugin, CHUNK_SIDE};
+use common::{ExampleArgs, IntChunk, MeshViewerPlugin, CHUNK_SIDE};
 
-struct BoolView<'a> {
-    chunk: &'a IntChunk,
+/// Cube of voxels that are either empty or not, stored in X-Y-Z-major order.
+struct BoolView {
+    side: u16,
+    empty: Vec<bool>,
 }
 
-impl<'a> BoolView<'a> {
-    fn is_empty(&self, x: u8, y: u8, z: u8) -> Option<bool> {
-        self.chunk.get(x, y, z).map(|i| i == 0)
+impl BoolView {
+    fn from_int_chunk(chunk: &IntChunk) -> Self {
+        Self::from_fn(CHUNK_SIDE as u16, |x, y, z| {
+            chunk.get(x as u8, y as u8, z as u8).unwrap() == 0
+        })
+    }
+
+    fn from_section(section: &ChunkSection) -> Self {
+        Self::from_fn(SECTION_WIDTH as u16, |x, y, z| {
+            section.block_states.get_block(x as u8, y as u8, z as u8) == BlockState::AIR
+        })
+    }
+
+    fn from_fn(side: u16, is_empty: impl Fn(u16, u16, u16) -> bool) -> Self {
+        let mut empty = Vec::with_capacity(side as usize * side as usize * side as usize);
+        for x in 0..side {
+            for y in 0..side {
+                for z in 0..side {
+                    empty.push(is_empty(x, y, z));
+                }
+            }
+        }
+        Self { side, empty }
+    }
+
+    fn is_empty(&self, x: u16, y: u16, z: u16) -> Option<bool> {
+        if x >= self.side || y >= self.side || z >= self.side {
+            return None;
+        }
+
+        let side = self.side as usize;
+        Some(self.empty[(x as usize * side + y as usize) * side + z as usize])
     }
 }
 
-impl<'a> VoxelView for BoolView<'a> {
+impl VoxelView for BoolView {
     #[inline(always)]
-    fn size_x(&self) -> u8 {
-        CHUNK_SIDE
+    fn size_x(&self) -> u16 {
+        self.side
     }
 
     #[inline(always)]
-    fn size_y(&self) -> u8 {
-        CHUNK_SIDE
+    fn size_y(&self) -> u16 {
+        self.side
     }
 
     #[inline(always)]
-    fn size_z(&self) -> u8 {
-        CHUNK_SIDE
+    fn size_z(&self) -> u16 {
+        self.side
     }
 }
 
-impl<'a> MeshingView for BoolView<'a> {
+impl MeshingView for BoolView {
     type Quads = Option<[[f32; 3]; 4]>;
 
     #[inline(always)]
-    fn is_empty(&self, x: u8, y: u8, z: u8) -> bool {
+    fn is_empty(&self, x: u16, y: u16, z: u16) -> bool {
         Self::is_empty(self, x, y, z).unwrap()
     }
 
     #[inline(always)]
-    fn is_full_cube(&self, _x: u8, _y: u8, _z: u8) -> bool {
+    fn is_full_cube(&self, _x: u16, _y: u16, _z: u16) -> bool {
         true
     }
 
     #[inline]
-    fn is_face_occluded(&self, x: u8, y: u8, z: u8, direction: Direction) -> bool {
+    fn is_face_occluded(&self, x: u16, y: u16, z: u16, direction: Direction) -> bool {
         println!("pos: {:?}, direction: {:?}", [x, y, z], direction);
         direction
             .translate_pos([x, y, z], 1)
@@ -63,30 +95,48 @@ impl<'a> MeshingView for BoolView<'a> {
     }
 
     #[inline(always)]
-    fn face_quads(&self, _x: u8, _y: u8, _z: u8, _face: Direction) -> Self::Quads {
+    fn face_quads(&self, _x: u16, _y: u16, _z: u16, _face: Direction) -> Self::Quads {
         None
     }
 
     #[inline(always)]
-    fn non_face_quads(&self, _x: u8, _y: u8, _z: u8) -> Self::Quads {
+    fn non_face_quads(&self, _x: u16, _y: u16, _z: u16) -> Self::Quads {
         None
     }
 }
 
+/// Meshes a small random chunk, or the topmost section of a generated test
+/// world with `--preset NAME`. Either way, `--seed N` picks the blocks.
 fn main() {
-    let chunk = IntChunk::random(1);
-
-    let bool_view = BoolView { chunk: &chunk };
+    let args = ExampleArgs::parse();
+
+    let bool_view = match args.preset {
+        Some(preset) => {
+            let chunk = WorldGen::new(preset, args.seed).chunk(0, 0);
+            let section = chunk.sections.last().expect("preset has no blocks");
+
+            println!(
+                "Section {} of {} (seed {})",
+                section.chunk_y, preset, args.seed
+            );
+            BoolView::from_section(section)
+        }
+        None => {
+            let chunk = IntChunk::random(1, args.seed);
+
+            println!("Chunk:");
+            println!("{chunk}");
+            BoolView::from_int_chunk(&chunk)
+        }
+    };
+    let side = bool_view.side;
 
     let mesh = SimpleMesher.generate_mesh(bool_view);
 
-    println!("Chunk:");
-    println!("{chunk}");
-
     // println!("Mesh: {mesh:#?}");
 
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugin(MeshViewerPlugin::new(mesh))
+        .add_plugin(MeshViewerPlugin::new(mesh).with_side(side as u8))
         .run();
 }
@@ -10,11 +10,35 @@ pub enum Axis {
 
 impl Axis {
     /// Returns the possible values of this enum as an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use brine_voxel::*;
+    /// assert_eq!(Axis::values(), [Axis::X, Axis::Y, Axis::Z]);
+    /// ```
     #[inline]
     pub const fn values() -> [Self; 3] {
         [Self::X, Self::Y, Self::Z]
     }
 
+    /// Returns the unit vector pointing in the positive direction of this axis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use brine_voxel::*;
+    /// assert_eq!(Axis::Y.unit_vec(), [0.0, 1.0, 0.0]);
+    /// ```
+    #[inline]
+    pub const fn unit_vec(self) -> [f32; 3] {
+        match self {
+            Axis::X => [1.0, 0.0, 0.0],
+            Axis::Y => [0.0, 1.0, 0.0],
+            Axis::Z => [0.0, 0.0, 1.0],
+        }
+    }
+
     /// Turns this axis into a [`Direction`] using the provided sign.
     ///
     /// # Example
@@ -0,0 +1,384 @@
+use brine_chunk::{Chunk, CHUNK_HEIGHT, SECTIONS_PER_CHUNK, SECTION_HEIGHT, SECTION_WIDTH};
+
+use crate::{
+    meshing::{MeshingView, QuadPositions},
+    ChunkSectionView, Direction, IndexTy, VoxelView,
+};
+
+/// A [`MeshingView`] of a whole chunk column, 16×256×16 voxels, made of a
+/// [`MeshingView`] for each of its sections.
+///
+/// Each voxel is delegated to the view of the section that it's in, except for
+/// the faces between two sections: those are occluded if the voxel on the
+/// other side is a non-empty full cube. Sections without a view are treated as
+/// air. This way, a single mesher pass produces one mesh for the whole chunk,
+/// without any faces hidden between sections.
+///
+/// Quads from the section views are moved up to the height of their section.
+/// Faces in different sections are never merged.
+///
+/// # Example
+///
+/// ```
+/// # use brine_voxel::*;
+/// use brine_chunk::{BlockState, BlockStates, Chunk, ChunkSection};
+///
+/// let mut below = ChunkSection::empty(0);
+/// below.block_states.0[BlockStates::xyz_to_index(0, 15, 0)] = BlockState(1);
+/// let mut above = ChunkSection::empty(1);
+/// above.block_states.0[BlockStates::xyz_to_index(0, 0, 0)] = BlockState(1);
+///
+/// let mut chunk = Chunk::empty(0, 0);
+/// chunk.sections = vec![below, above];
+///
+/// // Two blocks stacked on top of each other, across the two sections.
+/// let mesh = SimpleMesher.generate_mesh(ChunkView::from_chunk(&chunk));
+/// assert_eq!(mesh.quads.len(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChunkView<V> {
+    sections: [Option<V>; SECTIONS_PER_CHUNK],
+}
+
+impl<V> Default for ChunkView<V> {
+    fn default() -> Self {
+        Self {
+            sections: Default::default(),
+        }
+    }
+}
+
+impl<V: MeshingView> ChunkView<V> {
+    const SECTION_HEIGHT: IndexTy = SECTION_HEIGHT as IndexTy;
+
+    /// Returns a view of a chunk where every section is air.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a view of a chunk made of the given views, each with the index
+    /// of its section from the bottom of the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a section index is outside the chunk.
+    pub fn from_sections(sections: impl IntoIterator<Item = (u8, V)>) -> Self {
+        sections
+            .into_iter()
+            .fold(Self::new(), |view, (chunk_y, section)| {
+                view.with_section(chunk_y, section)
+            })
+    }
+
+    /// Uses `section` as the view of the section with index `chunk_y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the section index is outside the chunk.
+    #[inline]
+    pub fn with_section(mut self, chunk_y: u8, section: V) -> Self {
+        self.sections[chunk_y as usize] = Some(section);
+        self
+    }
+
+    /// Returns the view of the section with index `chunk_y`, if it has one.
+    #[inline]
+    pub fn section(&self, chunk_y: u8) -> Option<&V> {
+        self.sections.get(chunk_y as usize)?.as_ref()
+    }
+
+    /// Returns the view of the section that holds the voxel at height `y`, and
+    /// the height of the voxel within that section.
+    #[inline]
+    fn locate(&self, y: IndexTy) -> Option<(&V, IndexTy)> {
+        let section = self.sections.get((y / Self::SECTION_HEIGHT) as usize)?;
+
+        section
+            .as_ref()
+            .map(|section| (section, y % Self::SECTION_HEIGHT))
+    }
+}
+
+impl<'a> ChunkView<ChunkSectionView<'a>> {
+    /// Returns a view of every section of `chunk`, each with a plain
+    /// [`ChunkSectionView`].
+    ///
+    /// Use [`from_sections`][Self::from_sections] to configure the section
+    /// views (e.g., to give them a [`BlockClassifier`]).
+    ///
+    /// [`BlockClassifier`]: brine_chunk::BlockClassifier
+    pub fn from_chunk(chunk: &'a Chunk) -> Self {
+        Self::from_sections(
+            chunk
+                .sections
+                .iter()
+                .map(|section| (section.chunk_y, ChunkSectionView::new(section))),
+        )
+    }
+}
+
+impl<V: MeshingView> VoxelView for ChunkView<V> {
+    #[inline(always)]
+    fn size_x(&self) -> IndexTy {
+        SECTION_WIDTH as IndexTy
+    }
+
+    #[inline(always)]
+    fn size_y(&self) -> IndexTy {
+        CHUNK_HEIGHT as IndexTy
+    }
+
+    #[inline(always)]
+    fn size_z(&self) -> IndexTy {
+        SECTION_WIDTH as IndexTy
+    }
+}
+
+impl<V: MeshingView> MeshingView for ChunkView<V> {
+    type Quads = RaisedQuads<V::Quads>;
+
+    #[inline]
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        self.locate(y)
+            .map_or(true, |(section, y)| section.is_empty(x, y, z))
+    }
+
+    #[inline]
+    fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        self.locate(y)
+            .map_or(false, |(section, y)| section.is_full_cube(x, y, z))
+    }
+
+    #[inline]
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+        let (section, local_y) = match self.locate(y) {
+            Some(located) => located,
+            None => return false,
+        };
+
+        let crosses_section = match face {
+            Direction::YNeg => local_y == 0 && y > 0,
+            Direction::YPos => local_y == Self::SECTION_HEIGHT - 1 && y < self.size_y() - 1,
+            _ => false,
+        };
+
+        if !crosses_section {
+            return section.is_face_occluded(x, local_y, z, face);
+        }
+
+        let [x, y, z] = face.translate_pos([x, y, z], 1).unwrap();
+        !self.is_empty(x, y, z) && self.is_full_cube(x, y, z)
+    }
+
+    #[inline]
+    fn face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Self::Quads {
+        let (section, local_y) = self.locate(y).unwrap();
+
+        RaisedQuads::new(section.face_quads(x, local_y, z, face), y - local_y)
+    }
+
+    #[inline]
+    fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads {
+        let (section, local_y) = self.locate(y).unwrap();
+
+        RaisedQuads::new(section.non_face_quads(x, local_y, z), y - local_y)
+    }
+
+    #[inline]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        let [ax, ay, az] = a;
+        let [bx, by, bz] = b;
+
+        if ay / Self::SECTION_HEIGHT != by / Self::SECTION_HEIGHT {
+            return false;
+        }
+
+        let (section, ay) = self.locate(ay).unwrap();
+        let by = by % Self::SECTION_HEIGHT;
+
+        section.can_merge_faces([ax, ay, az], [bx, by, bz], face)
+    }
+}
+
+/// The quads of a section in a [`ChunkView`], moved up to the section's
+/// height.
+#[derive(Debug, Clone)]
+pub struct RaisedQuads<Q> {
+    quads: Q,
+    height: f32,
+}
+
+impl<Q> RaisedQuads<Q> {
+    #[inline]
+    fn new(quads: Q, height: IndexTy) -> Self {
+        Self {
+            quads,
+            height: height as f32,
+        }
+    }
+}
+
+impl<Q: IntoIterator<Item = QuadPositions>> IntoIterator for RaisedQuads<Q> {
+    type Item = QuadPositions;
+    type IntoIter = RaisedQuadsIter<Q::IntoIter>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        RaisedQuadsIter {
+            quads: self.quads.into_iter(),
+            height: self.height,
+        }
+    }
+}
+
+/// Iterator over [`RaisedQuads`].
+#[derive(Debug, Clone)]
+pub struct RaisedQuadsIter<I> {
+    quads: I,
+    height: f32,
+}
+
+impl<I: Iterator<Item = QuadPositions>> Iterator for RaisedQuadsIter<I> {
+    type Item = QuadPositions;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let height = self.height;
+
+        self.quads
+            .next()
+            .map(|quad| quad.map(|[x, y, z]| [x, y + height, z]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brine_chunk::{BlockState, BlockStates, ChunkSection};
+
+    use crate::{meshing::DelegatingMeshingView, GreedyMesher, Mesher, SimpleMesher};
+
+    use super::*;
+
+    const STONE: BlockState = BlockState(1);
+
+    fn section_with(
+        chunk_y: u8,
+        blocks: impl IntoIterator<Item = ([u8; 3], BlockState)>,
+    ) -> ChunkSection {
+        let mut section = ChunkSection::empty(chunk_y);
+        for ([x, y, z], block_state) in blocks {
+            section.block_states.0[BlockStates::xyz_to_index(x, y, z)] = block_state;
+        }
+        section
+    }
+
+    /// A chunk with a stone block on either side of the boundary between its
+    /// first two sections, and one at the top of the second section.
+    fn two_section_chunk() -> Chunk {
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![
+            section_with(0, [([3, 15, 4], STONE)]),
+            section_with(1, [([3, 0, 4], STONE), ([8, 15, 8], STONE)]),
+        ];
+        chunk
+    }
+
+    #[test]
+    fn size_and_sections() {
+        let chunk = two_section_chunk();
+        let view = ChunkView::from_chunk(&chunk);
+
+        assert_eq!([view.size_x(), view.size_y(), view.size_z()], [16, 256, 16]);
+        assert!(view.section(1).is_some());
+        assert!(view.section(2).is_none());
+
+        assert!(!view.is_empty(3, 15, 4));
+        assert!(!view.is_empty(3, 16, 4));
+        assert!(!view.is_empty(8, 31, 8));
+        assert!(view.is_empty(3, 17, 4));
+        assert!(view.is_empty(8, 32, 8));
+    }
+
+    #[test]
+    fn section_boundaries_are_culled() {
+        let chunk = two_section_chunk();
+
+        // Meshed one section at a time, the faces between the sections are
+        // both visible.
+        let per_section: usize = chunk
+            .sections
+            .iter()
+            .map(|section| SimpleMesher.generate_mesh(ChunkSectionView::new(section)))
+            .map(|mesh| mesh.quads.len())
+            .sum();
+        assert_eq!(per_section, 18);
+
+        let view = ChunkView::from_chunk(&chunk);
+        assert!(view.is_face_occluded(3, 15, 4, Direction::YPos));
+        assert!(view.is_face_occluded(3, 16, 4, Direction::YNeg));
+
+        // The top of the second section borders an empty section.
+        assert!(!view.is_face_occluded(8, 31, 8, Direction::YPos));
+
+        let mesh = SimpleMesher.generate_mesh(view);
+        assert_eq!(mesh.quads.len(), 16);
+        assert!(mesh
+            .quads
+            .iter()
+            .all(|quad| quad.voxel != [3, 15, 4] || quad.face != Some(Direction::YPos)));
+    }
+
+    #[test]
+    fn faces_in_different_sections_are_not_merged() {
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![
+            section_with(0, [([0, 15, 0], STONE)]),
+            section_with(1, [([0, 0, 0], STONE)]),
+        ];
+
+        let mesh = GreedyMesher.generate_mesh(ChunkView::from_chunk(&chunk));
+
+        // The sides of the two blocks stay separate quads.
+        assert_eq!(mesh.quads.len(), 10);
+    }
+
+    /// A section view whose blocks are small boxes, which only provide a
+    /// single non-face quad at their bottom.
+    struct SlabView<'a>(ChunkSectionView<'a>);
+
+    impl<'a> DelegatingMeshingView for SlabView<'a> {
+        type Delegate = ChunkSectionView<'a>;
+
+        fn delegate(&self) -> &Self::Delegate {
+            &self.0
+        }
+
+        fn is_full_cube(&self, _x: IndexTy, _y: IndexTy, _z: IndexTy) -> bool {
+            false
+        }
+
+        fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Option<QuadPositions> {
+            let [x, y, z] = [x, y, z].map(|coord| coord as f32);
+
+            Some([
+                [x, y, z],
+                [x + 1.0, y, z],
+                [x, y, z + 1.0],
+                [x + 1.0, y, z + 1.0],
+            ])
+        }
+    }
+
+    #[test]
+    fn section_quads_are_raised_to_their_section() {
+        let section = section_with(2, [([1, 2, 3], STONE)]);
+        let view = ChunkView::new().with_section(2, SlabView(ChunkSectionView::new(&section)));
+
+        let mesh = SimpleMesher.generate_mesh(view);
+
+        assert_eq!(mesh.quads.len(), 1);
+        assert_eq!(mesh.quads[0].voxel, [1, 34, 3]);
+        assert!(mesh.quads[0].positions.iter().all(|[_, y, _]| *y == 34.0));
+    }
+}
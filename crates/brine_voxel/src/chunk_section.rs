@@ -0,0 +1,459 @@
+use std::fmt;
+
+use brine_chunk::{BlockClassifier, BlockState, ChunkSection, SECTION_HEIGHT, SECTION_WIDTH};
+
+use crate::{
+    meshing::{MeshingView, QuadPositions},
+    Axis, AxisSign, Direction, IndexTy, VoxelView,
+};
+
+/// How a [`ChunkSectionView`] treats a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoxelDescriptor {
+    /// The block has no geometry, and doesn't hide its neighbors' faces.
+    Empty,
+
+    /// The block is an opaque full cube. Its faces are only merged with those
+    /// of the same block state.
+    Opaque,
+
+    /// The block is an opaque full cube with the given texture id. Its faces
+    /// are merged with those that have the same texture, whatever their block
+    /// state.
+    Textured(u32),
+}
+
+/// The layers of blocks just outside of a [`ChunkSection`], one for each face
+/// of the section that has a neighbor.
+///
+/// Given to [`ChunkSectionView::with_borders`], these decide whether the faces
+/// on the boundary of the section are occluded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionBorders {
+    slices: [Option<Vec<BlockState>>; 6],
+}
+
+impl SectionBorders {
+    const LAYER_SIZE: usize = SECTION_WIDTH * SECTION_HEIGHT;
+
+    /// Returns borders without any neighbors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the neighbor on the given face of the section, copying the layer of
+    /// blocks that touches the section.
+    pub fn with_neighbor(mut self, face: Direction, neighbor: &ChunkSection) -> Self {
+        let near = match face.sign() {
+            AxisSign::Neg => (SECTION_WIDTH - 1) as IndexTy,
+            AxisSign::Pos => 0,
+        };
+
+        let mut slice = Vec::with_capacity(Self::LAYER_SIZE);
+        for a in 0..SECTION_WIDTH as IndexTy {
+            for b in 0..SECTION_WIDTH as IndexTy {
+                let [x, y, z] = match face.axis() {
+                    Axis::X => [near, a, b],
+                    Axis::Y => [a, near, b],
+                    Axis::Z => [a, b, near],
+                };
+                slice.push(neighbor.block_states.get_block(x as u8, y as u8, z as u8));
+            }
+        }
+
+        self.slices[face as usize] = Some(slice);
+        self
+    }
+
+    /// Returns the block just outside of the section on the given face of the
+    /// voxel at index `[x, y, z]`, if that face has a neighbor.
+    ///
+    /// The voxel is assumed to be on the boundary of the section on that face.
+    pub fn get(&self, face: Direction, [x, y, z]: [IndexTy; 3]) -> Option<BlockState> {
+        let [a, b] = match face.axis() {
+            Axis::X => [y, z],
+            Axis::Y => [x, z],
+            Axis::Z => [x, y],
+        };
+
+        self.slices[face as usize]
+            .as_ref()
+            .map(|slice| slice[a as usize * SECTION_WIDTH + b as usize])
+    }
+}
+
+/// A [`VoxelView`] of a [`ChunkSection`] decoded by `brine_chunk`.
+///
+/// As a [`MeshingView`], every non-air block is treated as a full cube, and a
+/// face is occluded if the neighboring block in that direction is not air.
+/// Faces on the boundary of the section are never occluded, unless the view
+/// has [`SectionBorders`].
+///
+/// Without a [`BlockClassifier`], only [`BlockState::AIR`] counts as air. Use
+/// [`with_classifier`][ChunkSectionView::with_classifier] to also treat cave
+/// air and void air as air, or
+/// [`with_descriptors`][ChunkSectionView::with_descriptors] to decide how each
+/// block state is treated.
+///
+/// Use [`with_borders`][ChunkSectionView::with_borders] to take the
+/// neighboring sections into account for the faces on the boundary.
+///
+/// # Example
+///
+/// ```
+/// # use brine_voxel::*;
+/// use brine_chunk::{BlockState, ChunkSection};
+///
+/// let mut section = ChunkSection::empty(0);
+/// section.block_states.0[0] = BlockState(1);
+///
+/// let mesh = SimpleMesher.generate_mesh(ChunkSectionView::new(&section));
+/// assert_eq!(mesh.quads.len(), 6);
+/// ```
+#[derive(Clone, Copy)]
+pub struct ChunkSectionView<'a> {
+    section: &'a ChunkSection,
+    classifier: Option<&'a BlockClassifier>,
+    descriptors: Option<&'a (dyn Fn(BlockState) -> VoxelDescriptor + Sync)>,
+    borders: Option<&'a SectionBorders>,
+}
+
+impl<'a> ChunkSectionView<'a> {
+    const MAX_X: IndexTy = (SECTION_WIDTH as IndexTy) - 1;
+    const MAX_Y: IndexTy = (SECTION_HEIGHT as IndexTy) - 1;
+    const MAX_Z: IndexTy = (SECTION_WIDTH as IndexTy) - 1;
+
+    #[inline]
+    pub fn new(section: &'a ChunkSection) -> Self {
+        Self {
+            section,
+            classifier: None,
+            descriptors: None,
+            borders: None,
+        }
+    }
+
+    /// Uses `classifier` to decide which blocks are air.
+    #[inline]
+    pub fn with_classifier(mut self, classifier: &'a BlockClassifier) -> Self {
+        self.classifier = Some(classifier);
+        self
+    }
+
+    /// Uses `descriptors` to decide how each block state is treated. This takes
+    /// precedence over any [`BlockClassifier`].
+    ///
+    /// To look block states up in a table, use a closure that indexes it.
+    #[inline]
+    pub fn with_descriptors(
+        mut self,
+        descriptors: &'a (dyn Fn(BlockState) -> VoxelDescriptor + Sync),
+    ) -> Self {
+        self.descriptors = Some(descriptors);
+        self
+    }
+
+    /// Uses the blocks in `borders` to decide whether faces on the boundary of
+    /// the section are occluded.
+    #[inline]
+    pub fn with_borders(mut self, borders: &'a SectionBorders) -> Self {
+        self.borders = Some(borders);
+        self
+    }
+
+    /// Returns the section being viewed.
+    #[inline]
+    pub fn section(&self) -> &'a ChunkSection {
+        self.section
+    }
+
+    /// Returns the block state of the voxel at index `[x, y, z]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if the index is outside the section.
+    #[inline]
+    pub fn get_block(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> BlockState {
+        debug_assert!(x <= Self::MAX_X && y <= Self::MAX_Y && z <= Self::MAX_Z);
+
+        self.section
+            .block_states
+            .get_block(x as u8, y as u8, z as u8)
+    }
+
+    /// Returns how the given block state is treated by this view.
+    #[inline]
+    pub fn describe_block(&self, block_state: BlockState) -> VoxelDescriptor {
+        if let Some(descriptors) = self.descriptors {
+            return descriptors(block_state);
+        }
+
+        let is_air = match self.classifier {
+            Some(classifier) => classifier.is_air(block_state),
+            None => block_state == BlockState::AIR,
+        };
+
+        if is_air {
+            VoxelDescriptor::Empty
+        } else {
+            VoxelDescriptor::Opaque
+        }
+    }
+
+    /// Returns how the voxel at index `[x, y, z]` is treated by this view.
+    #[inline]
+    pub fn describe(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> VoxelDescriptor {
+        self.describe_block(self.get_block(x, y, z))
+    }
+
+    /// Returns true if the voxel at index `[x, y, z]` is anything but air (or,
+    /// with descriptors, anything but [`VoxelDescriptor::Empty`]).
+    #[inline]
+    pub fn is_occupied(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        self.describe(x, y, z) != VoxelDescriptor::Empty
+    }
+}
+
+impl<'a> fmt::Debug for ChunkSectionView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkSectionView")
+            .field("section", &self.section)
+            .field("classifier", &self.classifier)
+            .field("descriptors", &self.descriptors.map(|_| ".."))
+            .field("borders", &self.borders)
+            .finish()
+    }
+}
+
+impl<'a> VoxelView for ChunkSectionView<'a> {
+    #[inline(always)]
+    fn size_x(&self) -> IndexTy {
+        SECTION_WIDTH as IndexTy
+    }
+
+    #[inline(always)]
+    fn size_y(&self) -> IndexTy {
+        SECTION_HEIGHT as IndexTy
+    }
+
+    #[inline(always)]
+    fn size_z(&self) -> IndexTy {
+        SECTION_WIDTH as IndexTy
+    }
+}
+
+impl<'a> MeshingView for ChunkSectionView<'a> {
+    type Quads = Option<QuadPositions>;
+
+    #[inline]
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        !self.is_occupied(x, y, z)
+    }
+
+    #[inline(always)]
+    fn is_full_cube(&self, _x: IndexTy, _y: IndexTy, _z: IndexTy) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+        match (face, x, y, z) {
+            // Faces on the edge of the section are only occluded by the
+            // neighboring section, if there is one.
+            (Direction::XNeg, 0, _, _)
+            | (Direction::YNeg, _, 0, _)
+            | (Direction::ZNeg, _, _, 0)
+            | (Direction::XPos, Self::MAX_X.., _, _)
+            | (Direction::YPos, _, Self::MAX_Y.., _)
+            | (Direction::ZPos, _, _, Self::MAX_Z..) => self
+                .borders
+                .and_then(|borders| borders.get(face, [x, y, z]))
+                .map_or(false, |neighbor| {
+                    self.describe_block(neighbor) != VoxelDescriptor::Empty
+                }),
+
+            _ => {
+                let [x, y, z] = face.translate_pos([x, y, z], 1).unwrap();
+                self.is_occupied(x, y, z)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn face_quads(&self, _x: IndexTy, _y: IndexTy, _z: IndexTy, _face: Direction) -> Self::Quads {
+        None
+    }
+
+    #[inline(always)]
+    fn non_face_quads(&self, _x: IndexTy, _y: IndexTy, _z: IndexTy) -> Self::Quads {
+        None
+    }
+
+    /// Only faces of the same block state, or with the same texture, are
+    /// merged.
+    #[inline]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], _face: Direction) -> bool {
+        let [ax, ay, az] = a;
+        let [bx, by, bz] = b;
+
+        match (self.describe(ax, ay, az), self.describe(bx, by, bz)) {
+            (VoxelDescriptor::Textured(a), VoxelDescriptor::Textured(b)) => a == b,
+            _ => self.get_block(ax, ay, az) == self.get_block(bx, by, bz),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use brine_chunk::{anvil::RegionFile, BlockStates, BLOCKS_PER_SECTION};
+
+    use crate::{GreedyMesher, Mesher, SimpleMesher};
+
+    use super::*;
+
+    const STONE: BlockState = BlockState(1);
+    const OAK_STAIRS_EAST: BlockState = BlockState(1800);
+    const CAVE_AIR: BlockState = BlockState(9671);
+
+    /// Reads the section at `Y = 0` of chunk (0, 0) from `brine_chunk`'s region
+    /// file fixture. Its bottom layer is stone, and there are east-facing oak
+    /// stairs at (1, 1, 1).
+    fn fixture_section() -> ChunkSection {
+        const FIXTURE: &[u8] = include_bytes!("../../brine_chunk/tests/fixtures/r.0.0.mca");
+
+        let mut region = RegionFile::new(Cursor::new(FIXTURE)).unwrap();
+        let chunk = region
+            .read_chunk(0, 0, |name, properties| match (name, properties) {
+                ("minecraft:air", []) => Some(BlockState::AIR),
+                ("minecraft:stone", []) => Some(STONE),
+                ("minecraft:oak_stairs", _) => Some(OAK_STAIRS_EAST),
+                _ => None,
+            })
+            .unwrap()
+            .unwrap();
+
+        chunk.sections.into_iter().find(|s| s.chunk_y == 0).unwrap()
+    }
+
+    fn section_with(blocks: impl IntoIterator<Item = ([u8; 3], BlockState)>) -> ChunkSection {
+        let mut section = ChunkSection::empty(0);
+        for ([x, y, z], block_state) in blocks {
+            section.block_states.0[BlockStates::xyz_to_index(x, y, z)] = block_state;
+        }
+        section
+    }
+
+    #[test]
+    fn size_and_blocks() {
+        let section = section_with([([3, 4, 5], STONE)]);
+        let view = ChunkSectionView::new(&section);
+
+        assert_eq!([view.size_x(), view.size_y(), view.size_z()], [16, 16, 16]);
+        assert_eq!(view.get_block(3, 4, 5), STONE);
+        assert_eq!(view.get_block(5, 4, 3), BlockState::AIR);
+        assert!(view.is_occupied(3, 4, 5));
+        assert!(!view.is_occupied(0, 0, 0));
+    }
+
+    #[test]
+    fn mesh_empty_section() {
+        let section = ChunkSection::empty(0);
+
+        let mesh = SimpleMesher.generate_mesh(ChunkSectionView::new(&section));
+
+        assert!(mesh.quads.is_empty());
+    }
+
+    #[test]
+    fn mesh_stone_layer() {
+        let layer = (0..16).flat_map(|x| (0..16).map(move |z| ([x, 0, z], STONE)));
+        let section = section_with(layer);
+
+        let mesh = SimpleMesher.generate_mesh(ChunkSectionView::new(&section));
+
+        // Top and bottom of every block, plus the blocks around the edge.
+        assert_eq!(mesh.quads.len(), 16 * 16 * 2 + 16 * 4);
+        assert!(mesh.quads.iter().all(|quad| quad.voxel[1] == 0));
+    }
+
+    #[test]
+    fn mesh_full_section() {
+        let mut section = ChunkSection::empty(0);
+        section.block_states.0 = [STONE; BLOCKS_PER_SECTION];
+
+        let mesh = SimpleMesher.generate_mesh(ChunkSectionView::new(&section));
+
+        assert_eq!(mesh.quads.len(), 16 * 16 * 6);
+    }
+
+    #[test]
+    fn classifier_decides_what_is_air() {
+        let section = section_with([([0, 0, 0], STONE), ([1, 0, 0], CAVE_AIR)]);
+        let classifier = BlockClassifier::new([BlockState::AIR, CAVE_AIR], []);
+
+        // Without a classifier, cave air is meshed like any other block.
+        let mesh = SimpleMesher.generate_mesh(ChunkSectionView::new(&section));
+        assert_eq!(mesh.quads.len(), 10);
+
+        let view = ChunkSectionView::new(&section).with_classifier(&classifier);
+        assert!(!view.is_occupied(1, 0, 0));
+        let mesh = SimpleMesher.generate_mesh(view);
+        assert_eq!(mesh.quads.len(), 6);
+    }
+
+    #[test]
+    fn mesh_fixture_section() {
+        let section = fixture_section();
+
+        let mesh = SimpleMesher.generate_mesh(ChunkSectionView::new(&section));
+
+        // The stone layer, plus the stairs as a full cube. The bottom of the
+        // stairs and the top of the stone under them are hidden.
+        assert_eq!(mesh.quads.len(), 16 * 16 * 2 + 16 * 4 - 1 + 5);
+    }
+
+    #[test]
+    fn descriptors_decide_what_is_meshed() {
+        let section = fixture_section();
+        let stone_only = |block_state: BlockState| match block_state {
+            STONE => VoxelDescriptor::Textured(7),
+            _ => VoxelDescriptor::Empty,
+        };
+        let view = ChunkSectionView::new(&section).with_descriptors(&stone_only);
+
+        assert_eq!(view.describe(1, 0, 1), VoxelDescriptor::Textured(7));
+        assert_eq!(view.describe(1, 1, 1), VoxelDescriptor::Empty);
+
+        let mesh = SimpleMesher.generate_mesh(view);
+        assert_eq!(mesh.quads.len(), 16 * 16 * 2 + 16 * 4);
+
+        // Every face of the layer has the same texture, so each side is merged
+        // into a single quad.
+        let mesh = GreedyMesher.generate_mesh(view);
+        assert_eq!(mesh.quads.len(), 6);
+    }
+
+    #[test]
+    fn borders_occlude_boundary_faces() {
+        let section = section_with([([0, 15, 0], STONE), ([15, 15, 0], STONE)]);
+        let above = section_with([([0, 0, 0], STONE)]);
+        let borders = SectionBorders::new().with_neighbor(Direction::YPos, &above);
+
+        assert_eq!(borders.get(Direction::YPos, [0, 15, 0]), Some(STONE));
+        assert_eq!(
+            borders.get(Direction::YPos, [15, 15, 0]),
+            Some(BlockState::AIR)
+        );
+        assert_eq!(borders.get(Direction::XNeg, [0, 15, 0]), None);
+
+        let view = ChunkSectionView::new(&section).with_borders(&borders);
+        assert!(view.is_face_occluded(0, 15, 0, Direction::YPos));
+        assert!(!view.is_face_occluded(15, 15, 0, Direction::YPos));
+        assert!(!view.is_face_occluded(0, 15, 0, Direction::XNeg));
+
+        let mesh = SimpleMesher.generate_mesh(view);
+        assert_eq!(mesh.quads.len(), 11);
+    }
+}
@@ -1,6 +1,6 @@
 use glam::{const_vec3a, Vec3A};
 
-use crate::Direction;
+use crate::{Axis, AxisSign, Direction};
 
 /// An axis-aligned cuboid.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
@@ -95,6 +95,236 @@ impl AaCuboid {
         ]
     }
 
+    /// Returns whether the point is inside the cuboid or on its surface.
+    ///
+    /// A zero-size cuboid contains only the point it sits at.
+    #[inline]
+    pub fn contains_point<T: Into<Vec3A>>(&self, point: T) -> bool {
+        let point: Vec3A = point.into();
+
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Returns whether the two cuboids overlap.
+    ///
+    /// Cuboids that only touch (i.e., that share part of a face, an edge, or a
+    /// corner) do not count as overlapping. A cuboid with zero size along some
+    /// axis intersects another cuboid only if it is strictly inside of it
+    /// along that axis, so a flat cuboid lying on the face of another doesn't
+    /// intersect it.
+    #[inline]
+    pub fn intersects(&self, other: &AaCuboid) -> bool {
+        self.min.cmplt(other.max).all() && other.min.cmplt(self.max).all()
+    }
+
+    /// Returns the cuboid where the two cuboids overlap, or `None` if they
+    /// don't [intersect][Self::intersects].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use brine_voxel::*;
+    /// let a = AaCuboid::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+    /// let b = AaCuboid::new([0.5, -1.0, 0.0], [2.0, 0.5, 1.0]);
+    /// assert_eq!(
+    ///     a.intersection(&b),
+    ///     Some(AaCuboid::new([0.5, 0.0, 0.0], [1.0, 0.5, 1.0]))
+    /// );
+    /// ```
+    #[inline]
+    pub fn intersection(&self, other: &AaCuboid) -> Option<AaCuboid> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        Some(Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+
+    /// Returns the cuboid grown by `by` on every side, e.g., by half the size
+    /// of another cuboid to turn a test against that cuboid into a test
+    /// against its center point.
+    ///
+    /// Negative values shrink the cuboid, but never past zero size: an axis
+    /// that would be shrunk too far collapses to its center instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use brine_voxel::*;
+    /// let cuboid = AaCuboid::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+    /// assert_eq!(
+    ///     cuboid.expand([0.5, 0.0, -1.0]),
+    ///     AaCuboid::new([-0.5, 0.0, 0.5], [1.5, 1.0, 0.5])
+    /// );
+    /// ```
+    #[inline]
+    pub fn expand<T: Into<Vec3A>>(&self, by: T) -> Self {
+        let by: Vec3A = by.into();
+        let center = (self.min + self.max) * 0.5;
+
+        Self {
+            min: (self.min - by).min(center),
+            max: (self.max + by).max(center),
+        }
+    }
+
+    /// Moves the cuboid by `velocity` and finds when it first runs into
+    /// `other`.
+    ///
+    /// Returns the time of impact, as a fraction of `velocity` between 0 and
+    /// 1, and the face of `other` that was hit. Returns `None` if the cuboid
+    /// doesn't [intersect][Self::intersects] `other` at any point along the
+    /// way, which includes sliding along one of its faces.
+    ///
+    /// If the cuboid is touching `other` and moving into it, the time of
+    /// impact is zero. If the cuboid already intersects `other`, the time of
+    /// impact is also zero, and the face is the one the cuboid is the least
+    /// distance from being pushed out of.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use brine_voxel::*;
+    /// let falling = AaCuboid::new([0.0, 3.0, 0.0], [1.0, 4.0, 1.0]);
+    /// let ground = AaCuboid::new([-5.0, 0.0, -5.0], [5.0, 1.0, 5.0]);
+    ///
+    /// assert_eq!(
+    ///     falling.sweep([0.0, -4.0, 0.0], &ground),
+    ///     Some((0.5, Direction::YPos))
+    /// );
+    /// assert_eq!(falling.sweep([0.0, -1.0, 0.0], &ground), None);
+    /// ```
+    pub fn sweep<T: Into<Vec3A>>(&self, velocity: T, other: &AaCuboid) -> Option<(f32, Direction)> {
+        let velocity: Vec3A = velocity.into();
+
+        // Find when the cuboid starts and stops overlapping `other` along each
+        // axis. It intersects `other` while it overlaps along all three.
+        let mut entry = f32::NEG_INFINITY;
+        let mut exit = f32::INFINITY;
+        let mut entry_face = None;
+
+        for axis in Axis::values() {
+            let i = axis as usize;
+            let v = velocity[i];
+
+            let (axis_entry, axis_exit, face_sign) = if v > 0.0 {
+                (
+                    (other.min[i] - self.max[i]) / v,
+                    (other.max[i] - self.min[i]) / v,
+                    AxisSign::Neg,
+                )
+            } else if v < 0.0 {
+                (
+                    (other.max[i] - self.min[i]) / v,
+                    (other.min[i] - self.max[i]) / v,
+                    AxisSign::Pos,
+                )
+            } else if self.min[i] < other.max[i] && other.min[i] < self.max[i] {
+                // Not moving along this axis, but always overlapping.
+                continue;
+            } else {
+                // Not moving along this axis, and never overlapping.
+                return None;
+            };
+
+            if axis_entry > entry {
+                entry = axis_entry;
+                entry_face = Some(axis.with_sign(face_sign));
+            }
+            exit = exit.min(axis_exit);
+        }
+
+        // Overlapping along each axis at different times, or only touching.
+        if entry >= exit || exit <= 0.0 || entry > 1.0 {
+            return None;
+        }
+
+        match entry_face {
+            // Normalize a `-0.0` impact from touching to `0.0`.
+            Some(face) if entry > 0.0 => Some((entry, face)),
+            Some(face) if entry == 0.0 => Some((0.0, face)),
+            _ => Some((0.0, self.least_penetrated_face(other))),
+        }
+    }
+
+    /// Returns the face of `other` that an intersecting cuboid can be pushed
+    /// out of by the shortest distance.
+    fn least_penetrated_face(&self, other: &AaCuboid) -> Direction {
+        let mut least = (f32::INFINITY, Direction::XNeg);
+
+        for axis in Axis::values() {
+            let i = axis as usize;
+
+            for (depth, sign) in [
+                (self.max[i] - other.min[i], AxisSign::Neg),
+                (other.max[i] - self.min[i], AxisSign::Pos),
+            ] {
+                if depth < least.0 {
+                    least = (depth, axis.with_sign(sign));
+                }
+            }
+        }
+
+        least.1
+    }
+
+    /// Casts a ray against the cuboid, returning the distance along the ray to
+    /// the point where it first hits the cuboid.
+    ///
+    /// The distance is in multiples of `dir`, so it is only in world units if
+    /// `dir` is normalized. If `origin` is inside the cuboid, the distance is
+    /// zero. Returns `None` if the ray misses the cuboid, or if the cuboid is
+    /// behind the ray.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use brine_voxel::*;
+    /// let cuboid = AaCuboid::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+    /// assert_eq!(cuboid.intersect_ray([-2.0, 0.5, 0.5], [1.0, 0.0, 0.0]), Some(2.0));
+    /// assert_eq!(cuboid.intersect_ray([-2.0, 0.5, 0.5], [-1.0, 0.0, 0.0]), None);
+    /// ```
+    pub fn intersect_ray<T: Into<Vec3A>>(&self, origin: T, dir: T) -> Option<f32> {
+        let origin: Vec3A = origin.into();
+        let dir: Vec3A = dir.into();
+
+        // Intersect the ray with the slab between each pair of opposite faces.
+        // It hits the cuboid if it's inside all three slabs at the same time.
+        let mut near = f32::NEG_INFINITY;
+        let mut far = f32::INFINITY;
+
+        for axis in 0..3 {
+            let (min, max) = (self.min[axis], self.max[axis]);
+
+            if dir[axis] == 0.0 {
+                // Parallel to the slab, so it's either always or never inside.
+                if origin[axis] < min || origin[axis] > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let t0 = (min - origin[axis]) / dir[axis];
+            let t1 = (max - origin[axis]) / dir[axis];
+
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+
+            if near > far {
+                return None;
+            }
+        }
+
+        if far < 0.0 {
+            return None;
+        }
+
+        Some(near.max(0.0))
+    }
+
     #[inline(always)]
     pub const fn get_normal(face: Direction) -> Vec3A {
         match face {
@@ -107,3 +337,352 @@ impl AaCuboid {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_cube() -> AaCuboid {
+        AaCuboid::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0])
+    }
+
+    #[test]
+    fn contains_point() {
+        let cuboid = unit_cube();
+
+        assert!(cuboid.contains_point([0.5, 0.5, 0.5]));
+        assert!(cuboid.contains_point([0.0, 1.0, 0.5]));
+        assert!(!cuboid.contains_point([0.5, 1.01, 0.5]));
+        assert!(!cuboid.contains_point([-0.5, 0.5, 0.5]));
+
+        let point = AaCuboid::new([2.0, 2.0, 2.0], [2.0, 2.0, 2.0]);
+        assert!(point.contains_point([2.0, 2.0, 2.0]));
+        assert!(!point.contains_point([2.0, 2.0, 2.01]));
+    }
+
+    #[test]
+    fn intersects() {
+        let cuboid = unit_cube();
+
+        let overlapping = AaCuboid::new([0.5, 0.5, 0.5], [1.5, 1.5, 1.5]);
+        let touching = AaCuboid::new([1.0, 0.0, 0.0], [2.0, 1.0, 1.0]);
+        let apart = AaCuboid::new([1.5, 0.0, 0.0], [2.0, 1.0, 1.0]);
+        let inside = AaCuboid::new([0.25, 0.25, 0.25], [0.75, 0.75, 0.75]);
+
+        assert!(cuboid.intersects(&overlapping));
+        assert!(overlapping.intersects(&cuboid));
+        assert!(!cuboid.intersects(&touching));
+        assert!(!touching.intersects(&cuboid));
+        assert!(!cuboid.intersects(&apart));
+        assert!(!apart.intersects(&cuboid));
+        assert!(cuboid.intersects(&inside));
+        assert!(inside.intersects(&cuboid));
+
+        // A flat cuboid is still a cuboid, but lying on a face only touches it.
+        let flat = AaCuboid::new([0.5, 0.5, 0.5], [3.0, 0.5, 3.0]);
+        let flat_on_face = AaCuboid::new([0.5, 1.0, 0.5], [3.0, 1.0, 3.0]);
+        assert!(cuboid.intersects(&flat));
+        assert!(!cuboid.intersects(&flat_on_face));
+        assert!(!apart.intersects(&flat));
+
+        let point = AaCuboid::new([0.5, 0.5, 0.5], [0.5, 0.5, 0.5]);
+        let corner = AaCuboid::new([1.0, 1.0, 1.0], [1.0, 1.0, 1.0]);
+        assert!(cuboid.intersects(&point));
+        assert!(!cuboid.intersects(&corner));
+    }
+
+    #[test]
+    fn intersection() {
+        let cuboid = unit_cube();
+
+        let overlapping = AaCuboid::new([0.5, 0.5, 0.5], [1.5, 1.5, 1.5]);
+        assert_eq!(
+            cuboid.intersection(&overlapping),
+            Some(AaCuboid::new([0.5, 0.5, 0.5], [1.0, 1.0, 1.0]))
+        );
+        assert_eq!(
+            overlapping.intersection(&cuboid),
+            cuboid.intersection(&overlapping)
+        );
+
+        let inside = AaCuboid::new([0.25, 0.25, 0.25], [0.75, 0.75, 0.75]);
+        assert_eq!(cuboid.intersection(&inside), Some(inside));
+
+        let touching = AaCuboid::new([1.0, 0.0, 0.0], [2.0, 1.0, 1.0]);
+        assert_eq!(cuboid.intersection(&touching), None);
+
+        let flat = AaCuboid::new([0.5, 0.5, -1.0], [3.0, 0.5, 3.0]);
+        assert_eq!(
+            cuboid.intersection(&flat),
+            Some(AaCuboid::new([0.5, 0.5, 0.0], [1.0, 0.5, 1.0]))
+        );
+    }
+
+    #[test]
+    fn expand() {
+        let cuboid = unit_cube();
+
+        assert_eq!(
+            cuboid.expand([1.0, 2.0, 0.0]),
+            AaCuboid::new([-1.0, -2.0, 0.0], [2.0, 3.0, 1.0])
+        );
+        assert_eq!(cuboid.expand([0.0, 0.0, 0.0]), cuboid);
+        assert_eq!(
+            cuboid.expand([-0.25, -0.5, -3.0]),
+            AaCuboid::new([0.25, 0.5, 0.5], [0.75, 0.5, 0.5])
+        );
+    }
+
+    #[test]
+    fn sweep_hits_faces() {
+        let cuboid = unit_cube();
+
+        for face in Direction::values() {
+            // Start two units away from the face and move towards it.
+            let normal = AaCuboid::get_normal(face);
+            let start = AaCuboid {
+                min: cuboid.min + normal * 2.0,
+                max: cuboid.max + normal * 2.0,
+            };
+
+            assert_eq!(
+                start.sweep(normal * -4.0, &cuboid),
+                Some((0.25, face)),
+                "{:?}",
+                face
+            );
+            assert_eq!(start.sweep(normal * -0.5, &cuboid), None, "{:?}", face);
+            assert_eq!(start.sweep(normal * 4.0, &cuboid), None, "{:?}", face);
+        }
+    }
+
+    #[test]
+    fn sweep_from_touching() {
+        let cuboid = unit_cube();
+        let on_top = AaCuboid::new([0.0, 1.0, 0.0], [1.0, 2.0, 1.0]);
+
+        // Moving into the face is an immediate contact.
+        assert_eq!(
+            on_top.sweep([0.0, -1.0, 0.0], &cuboid),
+            Some((0.0, Direction::YPos))
+        );
+        assert_eq!(
+            on_top.sweep([0.3, -1.0, 0.2], &cuboid),
+            Some((0.0, Direction::YPos))
+        );
+
+        // Moving away from, or sliding along, the face is not.
+        assert_eq!(on_top.sweep([0.0, 1.0, 0.0], &cuboid), None);
+        assert_eq!(on_top.sweep([0.5, 0.0, 0.0], &cuboid), None);
+        assert_eq!(on_top.sweep([0.0, 0.0, 0.0], &cuboid), None);
+
+        // Moving diagonally away from an edge it's touching.
+        let by_edge = AaCuboid::new([-1.0, 0.0, -1.0], [0.0, 1.0, 0.0]);
+        assert_eq!(by_edge.sweep([2.0, 0.0, -2.0], &cuboid), None);
+
+        // Passing exactly by an edge only touches it for an instant.
+        let passing = AaCuboid::new([-2.0, 0.0, 4.0], [-1.0, 1.0, 5.0]);
+        assert_eq!(passing.sweep([4.0, 0.0, -4.0], &cuboid), None);
+    }
+
+    #[test]
+    fn sweep_from_inside() {
+        let cuboid = unit_cube();
+
+        let near_top = AaCuboid::new([0.25, 0.75, 0.25], [0.75, 1.25, 0.75]);
+        assert_eq!(
+            near_top.sweep([1.0, 0.0, 0.0], &cuboid),
+            Some((0.0, Direction::YPos))
+        );
+        assert_eq!(
+            near_top.sweep([0.0, 0.0, 0.0], &cuboid),
+            Some((0.0, Direction::YPos))
+        );
+    }
+
+    #[test]
+    fn sweep_zero_size() {
+        let cuboid = unit_cube();
+
+        let point = AaCuboid::new([0.5, 3.0, 0.5], [0.5, 3.0, 0.5]);
+        assert_eq!(
+            point.sweep([0.0, -4.0, 0.0], &cuboid),
+            Some((0.5, Direction::YPos))
+        );
+
+        // A point along an edge of the cuboid only ends up touching it.
+        let on_edge = AaCuboid::new([1.0, 3.0, 1.0], [1.0, 3.0, 1.0]);
+        assert_eq!(on_edge.sweep([0.0, -4.0, 0.0], &cuboid), None);
+
+        let flat = AaCuboid::new([0.0, 3.0, 0.0], [1.0, 3.0, 1.0]);
+        assert_eq!(
+            flat.sweep([0.0, -4.0, 0.0], &cuboid),
+            Some((0.5, Direction::YPos))
+        );
+        assert_eq!(flat.sweep([0.0, 0.0, 3.0], &cuboid), None);
+    }
+
+    fn moved(cuboid: &AaCuboid, offset: Vec3A) -> AaCuboid {
+        AaCuboid {
+            min: cuboid.min + offset,
+            max: cuboid.max + offset,
+        }
+    }
+
+    fn random_cuboid(rng: &fastrand::Rng) -> AaCuboid {
+        let mut from = [0.0; 3];
+        let mut to = [0.0; 3];
+        for axis in 0..3 {
+            from[axis] = rng.f32() * 8.0 - 4.0;
+            // Sometimes make the cuboid flat along this axis.
+            to[axis] = if rng.u8(0..4) == 0 {
+                from[axis]
+            } else {
+                from[axis] + rng.f32() * 3.0
+            };
+        }
+        AaCuboid::new(from, to)
+    }
+
+    /// Compares the result of [`AaCuboid::sweep`] against moving the cuboid in
+    /// small steps and checking for intersection after each one.
+    #[test]
+    fn sweep_matches_stepping() {
+        const STEPS: usize = 500;
+        const EPSILON: f32 = 1e-4;
+
+        let rng = fastrand::Rng::with_seed(0xb41e);
+
+        for _ in 0..1000 {
+            let moving = random_cuboid(&rng);
+            let other = random_cuboid(&rng);
+            let velocity = Vec3A::new(
+                rng.f32() * 16.0 - 8.0,
+                rng.f32() * 16.0 - 8.0,
+                rng.f32() * 16.0 - 8.0,
+            );
+
+            let first_step_hit = (0..=STEPS)
+                .map(|step| step as f32 / STEPS as f32)
+                .find(|&t| moved(&moving, velocity * t).intersects(&other));
+
+            let hit = moving.sweep(velocity, &other);
+            let context = format!("{:?} moving by {:?} into {:?}", moving, velocity, other);
+
+            let (toi, face) = match hit {
+                Some(hit) => hit,
+                None => {
+                    assert_eq!(first_step_hit, None, "{}", context);
+                    continue;
+                }
+            };
+
+            assert!((0.0..=1.0).contains(&toi), "{}", context);
+            if let Some(t) = first_step_hit {
+                assert!(toi <= t + EPSILON, "{}: {} > {}", context, toi, t);
+            }
+
+            if toi == 0.0 {
+                continue;
+            }
+
+            // Not intersecting before the impact...
+            let before = moved(&moving, velocity * (toi - EPSILON).max(0.0));
+            assert!(!before.intersects(&other), "{}", context);
+
+            // ...and touching the face that was hit at the impact.
+            let at = moved(&moving, velocity * toi);
+            let i = face.axis() as usize;
+            let (moving_side, other_side) = match face.sign() {
+                AxisSign::Neg => (at.max[i], other.min[i]),
+                AxisSign::Pos => (at.min[i], other.max[i]),
+            };
+            assert!(
+                (moving_side - other_side).abs() < EPSILON * 16.0,
+                "{}: {:?}",
+                context,
+                face
+            );
+        }
+    }
+
+    #[test]
+    fn ray_hits() {
+        let cuboid = unit_cube();
+
+        assert_eq!(
+            cuboid.intersect_ray([0.5, 3.0, 0.5], [0.0, -1.0, 0.0]),
+            Some(2.0)
+        );
+
+        // Distances are scaled by the length of `dir`.
+        assert_eq!(
+            cuboid.intersect_ray([0.5, 3.0, 0.5], [0.0, -2.0, 0.0]),
+            Some(1.0)
+        );
+
+        // Diagonally through the corner.
+        let t = cuboid
+            .intersect_ray([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0])
+            .unwrap();
+        assert!((t - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ray_misses() {
+        let cuboid = unit_cube();
+
+        // Pointing away.
+        assert_eq!(cuboid.intersect_ray([0.5, 3.0, 0.5], [0.0, 1.0, 0.0]), None);
+
+        // Passing by.
+        assert_eq!(
+            cuboid.intersect_ray([2.0, 3.0, 0.5], [0.0, -1.0, 0.0]),
+            None
+        );
+        assert_eq!(
+            cuboid.intersect_ray([-1.0, 0.0, 3.0], [1.0, 1.0, 0.0]),
+            None
+        );
+
+        // A zero direction never gets anywhere.
+        assert_eq!(cuboid.intersect_ray([2.0, 2.0, 2.0], [0.0, 0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn ray_from_inside() {
+        let cuboid = unit_cube();
+
+        assert_eq!(
+            cuboid.intersect_ray([0.5, 0.5, 0.5], [1.0, 0.0, 0.0]),
+            Some(0.0)
+        );
+        assert_eq!(
+            cuboid.intersect_ray([0.0, 0.5, 0.5], [-1.0, 0.0, 0.0]),
+            Some(0.0)
+        );
+        assert_eq!(
+            cuboid.intersect_ray([0.5, 0.5, 0.5], [0.0, 0.0, 0.0]),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn ray_against_degenerate_cuboid() {
+        let flat = AaCuboid::new([0.0, 1.0, 0.0], [1.0, 1.0, 1.0]);
+
+        assert_eq!(
+            flat.intersect_ray([0.5, 3.0, 0.5], [0.0, -1.0, 0.0]),
+            Some(2.0)
+        );
+        assert_eq!(flat.intersect_ray([-1.0, 2.0, 0.5], [1.0, 0.0, 0.0]), None);
+
+        let point = AaCuboid::new([1.0, 1.0, 1.0], [1.0, 1.0, 1.0]);
+
+        assert_eq!(
+            point.intersect_ray([1.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+            Some(1.0)
+        );
+        assert_eq!(point.intersect_ray([1.0, 1.1, 0.0], [0.0, 0.0, 1.0]), None);
+    }
+}
@@ -67,6 +67,24 @@ impl CuboidTransform {
         }
     }
 
+    /// Returns a transform that applies `self` and then `next`.
+    ///
+    /// `a.then(b)` is the same as `b * a`.
+    #[inline]
+    pub fn then(self, next: Self) -> Self {
+        next * self
+    }
+
+    /// Returns the transform that undoes `self`.
+    ///
+    /// The result is meaningless if `self` has a scale of zero.
+    #[inline]
+    pub fn inverse(&self) -> Self {
+        Self {
+            affine3: self.affine3.inverse(),
+        }
+    }
+
     #[inline]
     pub fn transform_aa_cuboid(&self, aa_cuboid: AaCuboid) -> Cuboid {
         *self * aa_cuboid
@@ -201,6 +219,62 @@ mod tests {
         do_test(CuboidTransform::default().with_rotation_z(270f32.to_radians()));
     }
 
+    fn sample_transforms() -> [CuboidTransform; 3] {
+        [
+            CuboidTransform::default()
+                .with_rotation_y(90f32.to_radians())
+                .with_origin([0.5, 0.5, 0.5]),
+            CuboidTransform::default()
+                .with_rotation_x(22.5f32.to_radians())
+                .with_scale(2.0)
+                .with_origin([8.0, 0.0, 8.0]),
+            CuboidTransform::from_rotation_about_origin(
+                Quat::from_rotation_z(-45f32.to_radians()),
+                [0.0, 16.0, 4.0],
+            ),
+        ]
+    }
+
+    const SAMPLE_POINTS: [Vec3A; 4] = [
+        const_vec3a!([0.0, 0.0, 0.0]),
+        const_vec3a!([1.0, 1.0, 1.0]),
+        const_vec3a!([16.0, 0.0, -3.5]),
+        const_vec3a!([-7.25, 12.0, 5.0]),
+    ];
+
+    #[test]
+    fn then_inverse_is_identity() {
+        for transform in sample_transforms() {
+            let identity = transform.then(transform.inverse());
+
+            for point in SAMPLE_POINTS {
+                assert_eq_epsilon(identity.transform_point(point), point, EPS_VEC3A * 10.0);
+            }
+        }
+    }
+
+    #[test]
+    fn then_applies_transforms_in_order() {
+        let [a, b, c] = sample_transforms();
+        let composed = a.then(b).then(c);
+
+        for point in SAMPLE_POINTS {
+            let sequential = c.transform_point(b.transform_point(a.transform_point(point)));
+            assert_eq_epsilon(
+                composed.transform_point(point),
+                sequential,
+                EPS_VEC3A * 10.0,
+            );
+        }
+
+        // Rotations about different axes don't commute.
+        let point = const_vec3a!([1.0, 2.0, 3.0]);
+        assert!(!a
+            .then(b)
+            .transform_point(point)
+            .eq_eps(&b.then(a).transform_point(point), &EPS_VEC3A));
+    }
+
     #[test]
     fn transformed_face() {
         let original = AaCuboid::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
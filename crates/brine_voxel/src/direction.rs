@@ -232,10 +232,85 @@ impl Direction {
     }
 }
 
+/// Conversions to and from `minecraft_assets`' [`BlockFace`].
+///
+/// Minecraft's world uses the same axes as this crate, with
+///
+/// | `BlockFace` | `Direction` |
+/// |-------------|-------------|
+/// | `West`      | `XNeg`      |
+/// | `East`      | `XPos`      |
+/// | `Down`      | `YNeg`      |
+/// | `Up`        | `YPos`      |
+/// | `North`     | `ZNeg`      |
+/// | `South`     | `ZPos`      |
+///
+/// [`BlockFace`]: minecraft_assets::schemas::models::BlockFace
+#[cfg(feature = "minecraft-assets")]
+mod block_face {
+    use minecraft_assets::schemas::models::BlockFace;
+
+    use super::Direction;
+
+    impl From<BlockFace> for Direction {
+        #[inline]
+        fn from(face: BlockFace) -> Self {
+            match face {
+                BlockFace::West => Direction::XNeg,
+                BlockFace::East => Direction::XPos,
+                BlockFace::Down => Direction::YNeg,
+                BlockFace::Up => Direction::YPos,
+                BlockFace::North => Direction::ZNeg,
+                BlockFace::South => Direction::ZPos,
+            }
+        }
+    }
+
+    impl From<Direction> for BlockFace {
+        #[inline]
+        fn from(direction: Direction) -> Self {
+            match direction {
+                Direction::XNeg => BlockFace::West,
+                Direction::XPos => BlockFace::East,
+                Direction::YNeg => BlockFace::Down,
+                Direction::YPos => BlockFace::Up,
+                Direction::ZNeg => BlockFace::North,
+                Direction::ZPos => BlockFace::South,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[cfg(feature = "minecraft-assets")]
+    #[test]
+    fn block_face_round_trip() {
+        use minecraft_assets::schemas::models::BlockFace;
+
+        for direction in Direction::values() {
+            let face = BlockFace::from(direction);
+            assert_eq!(Direction::from(face), direction);
+        }
+
+        for face in [
+            BlockFace::Down,
+            BlockFace::Up,
+            BlockFace::North,
+            BlockFace::South,
+            BlockFace::West,
+            BlockFace::East,
+        ] {
+            let direction = Direction::from(face);
+            assert_eq!(BlockFace::from(direction), face);
+        }
+
+        assert_eq!(BlockFace::from(Direction::ZNeg), BlockFace::North);
+        assert_eq!(Direction::from(BlockFace::East), Direction::XPos);
+    }
+
     fn assert_rotations(direction: Direction, axis: Axis, expected: [Direction; 4]) {
         assert_eq!(direction.with_rotation(axis, -360), expected[0]);
         assert_eq!(direction.with_rotation(axis, -270), expected[1]);
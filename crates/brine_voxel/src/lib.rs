@@ -1,17 +1,32 @@
 #![doc = include_str!("../README.md")]
 #![allow(clippy::module_inception)]
 
-pub(crate) type IndexTy = u8;
+/// The type of a voxel's index along each axis.
+///
+/// Wide enough for a whole chunk column, which is 256 voxels tall.
+pub(crate) type IndexTy = u16;
 
 mod axis;
+#[cfg(feature = "brine_chunk")]
+mod chunk;
+#[cfg(feature = "brine_chunk")]
+mod chunk_section;
 mod cuboid;
 mod direction;
 mod view;
 
 pub mod meshing;
+pub mod raycast;
 
 pub use axis::{Axis, AxisSign};
+#[cfg(feature = "brine_chunk")]
+pub use chunk::{ChunkView, RaisedQuads, RaisedQuadsIter};
+#[cfg(feature = "brine_chunk")]
+pub use chunk_section::{ChunkSectionView, SectionBorders, VoxelDescriptor};
 pub use cuboid::{AaCuboid, Cuboid, CuboidTransform};
 pub use direction::Direction;
-pub use meshing::{Mesh, Mesher, MeshingView, SimpleMesher};
+pub use meshing::{
+    FluidMesher, FluidView, GreedyMesher, Mesh, Mesher, MeshingView, OffsetView, PaddedView,
+    RotatedView, SimpleMesher,
+};
 pub use view::VoxelView;
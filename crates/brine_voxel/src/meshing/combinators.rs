@@ -0,0 +1,745 @@
+//! [`MeshingView`]s that present another view moved, rotated, or padded,
+//! without copying any of its voxels.
+
+use glam::{IVec3, Mat3, Vec3};
+
+use crate::{Axis, AxisSign, Direction, IndexTy, VoxelView};
+
+use super::{MeshingView, QuadPositions};
+
+#[inline]
+fn size_of(view: &impl VoxelView) -> [IndexTy; 3] {
+    [view.size_x(), view.size_y(), view.size_z()]
+}
+
+#[inline]
+fn to_ivec3(index: [IndexTy; 3]) -> IVec3 {
+    IVec3::new(index[0] as i32, index[1] as i32, index[2] as i32)
+}
+
+#[inline]
+fn from_ivec3(index: IVec3) -> [IndexTy; 3] {
+    [index.x as IndexTy, index.y as IndexTy, index.z as IndexTy]
+}
+
+/// A [`MeshingView`] of the part of another view that starts at `origin`.
+///
+/// Voxel `[x, y, z]` of this view is voxel `origin + [x, y, z]` of the inner
+/// view, and the quads of the inner view are moved back by `origin`. The inner
+/// view still decides which faces are occluded, so faces on the edges of this
+/// view are culled against the voxels just outside of it.
+#[derive(Debug, Clone, Copy)]
+pub struct OffsetView<V> {
+    view: V,
+    origin: [IndexTy; 3],
+    size: [IndexTy; 3],
+}
+
+impl<V: MeshingView> OffsetView<V> {
+    /// Returns a view of `view` from `origin` up to its far corner.
+    ///
+    /// # Panics
+    ///
+    /// If `origin` is outside of `view`.
+    #[inline]
+    pub fn new(view: V, origin: [IndexTy; 3]) -> Self {
+        let inner_size = size_of(&view);
+        assert!(
+            (0..3).all(|axis| origin[axis] <= inner_size[axis]),
+            "origin is outside of the view"
+        );
+
+        Self {
+            view,
+            origin,
+            size: [0, 1, 2].map(|axis| inner_size[axis] - origin[axis]),
+        }
+    }
+
+    /// Limits the view to `size` voxels along each axis.
+    ///
+    /// # Panics
+    ///
+    /// If the view would extend past the far corner of the inner view.
+    #[inline]
+    pub fn with_size(mut self, size: [IndexTy; 3]) -> Self {
+        assert!(
+            (0..3).all(|axis| size[axis] <= self.size[axis]),
+            "size extends past the view"
+        );
+
+        self.size = size;
+        self
+    }
+
+    #[inline]
+    fn inner(&self, [x, y, z]: [IndexTy; 3]) -> [IndexTy; 3] {
+        let [ox, oy, oz] = self.origin;
+        [x + ox, y + oy, z + oz]
+    }
+
+    #[inline]
+    fn transform(&self) -> QuadTransform {
+        QuadTransform::translation(-to_ivec3(self.origin).as_vec3())
+    }
+}
+
+impl<V: MeshingView> VoxelView for OffsetView<V> {
+    #[inline(always)]
+    fn size_x(&self) -> IndexTy {
+        self.size[0]
+    }
+
+    #[inline(always)]
+    fn size_y(&self) -> IndexTy {
+        self.size[1]
+    }
+
+    #[inline(always)]
+    fn size_z(&self) -> IndexTy {
+        self.size[2]
+    }
+}
+
+impl<V: MeshingView> MeshingView for OffsetView<V> {
+    type Quads = TransformedQuads<V::Quads>;
+
+    #[inline]
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        let [x, y, z] = self.inner([x, y, z]);
+        self.view.is_empty(x, y, z)
+    }
+
+    #[inline]
+    fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        let [x, y, z] = self.inner([x, y, z]);
+        self.view.is_full_cube(x, y, z)
+    }
+
+    #[inline]
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+        let [x, y, z] = self.inner([x, y, z]);
+        self.view.is_face_occluded(x, y, z, face)
+    }
+
+    #[inline]
+    fn face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Self::Quads {
+        let [x, y, z] = self.inner([x, y, z]);
+        TransformedQuads::new(self.view.face_quads(x, y, z, face), self.transform())
+    }
+
+    #[inline]
+    fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads {
+        let [x, y, z] = self.inner([x, y, z]);
+        TransformedQuads::new(self.view.non_face_quads(x, y, z), self.transform())
+    }
+
+    #[inline]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        self.view
+            .can_merge_faces(self.inner(a), self.inner(b), face)
+    }
+}
+
+/// A [`MeshingView`] of another view turned a whole number of quarter turns
+/// about an [`Axis`].
+///
+/// Rotations follow [`Direction::with_rotation`]: the voxel on the `face` side
+/// of the inner view ends up on the `face.with_rotation(axis, degrees)` side of
+/// this one. The rotated view is moved so that its minimum corner is at the
+/// origin, like the inner view's.
+#[derive(Debug, Clone, Copy)]
+pub struct RotatedView<V> {
+    view: V,
+    axis: Axis,
+    degrees: i32,
+
+    /// Where the rotation takes the unit vectors of the X, Y, and Z axes.
+    columns: [IVec3; 3],
+
+    size: [IndexTy; 3],
+}
+
+impl<V: MeshingView> RotatedView<V> {
+    /// Returns `view` rotated about `axis` by `degrees` degrees.
+    ///
+    /// # Panics
+    ///
+    /// If `degrees` is not a whole multiple of 90.
+    #[inline]
+    pub fn new(view: V, axis: Axis, degrees: i32) -> Self {
+        let columns = Axis::values().map(|column| {
+            let direction = column.with_sign(AxisSign::Pos).with_rotation(axis, degrees);
+            IVec3::from(direction.axis().unit_vec().map(|unit| unit as i32))
+                * match direction.sign() {
+                    AxisSign::Neg => -1,
+                    AxisSign::Pos => 1,
+                }
+        });
+
+        let mut rotated = Self {
+            view,
+            axis,
+            degrees,
+            columns,
+            size: [0; 3],
+        };
+        rotated.size = from_ivec3(rotated.rotate(to_ivec3(size_of(&rotated.view))).abs());
+        rotated
+    }
+
+    /// Applies the rotation to a vector.
+    #[inline]
+    fn rotate(&self, v: IVec3) -> IVec3 {
+        self.columns[0] * v.x + self.columns[1] * v.y + self.columns[2] * v.z
+    }
+
+    /// Applies the inverse of the rotation to a vector.
+    #[inline]
+    fn unrotate(&self, v: IVec3) -> IVec3 {
+        IVec3::new(
+            self.columns[0].dot(v),
+            self.columns[1].dot(v),
+            self.columns[2].dot(v),
+        )
+    }
+
+    /// Returns the index in the inner view of the voxel at `index` in this
+    /// view.
+    ///
+    /// Works with doubled coordinates relative to the center of each view, so
+    /// that voxel centers land on whole numbers.
+    #[inline]
+    fn inner(&self, index: [IndexTy; 3]) -> [IndexTy; 3] {
+        let size = to_ivec3(self.size);
+        let inner_size = to_ivec3(size_of(&self.view));
+
+        let centered = to_ivec3(index) * 2 + IVec3::ONE - size;
+        let inner_centered = self.unrotate(centered);
+
+        from_ivec3((inner_centered + inner_size - IVec3::ONE) / 2)
+    }
+
+    /// Returns the face in the inner view that `face` in this view is.
+    #[inline]
+    fn inner_face(&self, face: Direction) -> Direction {
+        face.with_rotation(self.axis, -self.degrees)
+    }
+
+    #[inline]
+    fn transform(&self) -> QuadTransform {
+        let half_size = to_ivec3(self.size).as_vec3() / 2.0;
+        let inner_half_size = to_ivec3(size_of(&self.view)).as_vec3() / 2.0;
+
+        let rotation = Mat3::from_cols(
+            self.columns[0].as_vec3(),
+            self.columns[1].as_vec3(),
+            self.columns[2].as_vec3(),
+        );
+
+        QuadTransform {
+            rotation,
+            translation: half_size - rotation * inner_half_size,
+        }
+    }
+}
+
+impl<V: MeshingView> VoxelView for RotatedView<V> {
+    #[inline(always)]
+    fn size_x(&self) -> IndexTy {
+        self.size[0]
+    }
+
+    #[inline(always)]
+    fn size_y(&self) -> IndexTy {
+        self.size[1]
+    }
+
+    #[inline(always)]
+    fn size_z(&self) -> IndexTy {
+        self.size[2]
+    }
+}
+
+impl<V: MeshingView> MeshingView for RotatedView<V> {
+    type Quads = TransformedQuads<V::Quads>;
+
+    #[inline]
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        let [x, y, z] = self.inner([x, y, z]);
+        self.view.is_empty(x, y, z)
+    }
+
+    #[inline]
+    fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        let [x, y, z] = self.inner([x, y, z]);
+        self.view.is_full_cube(x, y, z)
+    }
+
+    #[inline]
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+        let [x, y, z] = self.inner([x, y, z]);
+        self.view.is_face_occluded(x, y, z, self.inner_face(face))
+    }
+
+    #[inline]
+    fn face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Self::Quads {
+        let [x, y, z] = self.inner([x, y, z]);
+        let quads = self.view.face_quads(x, y, z, self.inner_face(face));
+
+        TransformedQuads::new(quads, self.transform())
+    }
+
+    #[inline]
+    fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads {
+        let [x, y, z] = self.inner([x, y, z]);
+
+        TransformedQuads::new(self.view.non_face_quads(x, y, z), self.transform())
+    }
+
+    #[inline]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        self.view
+            .can_merge_faces(self.inner(a), self.inner(b), self.inner_face(face))
+    }
+}
+
+/// A [`MeshingView`] of another view surrounded by a border one voxel thick.
+///
+/// The border is always empty, so it adds no geometry of its own. It only
+/// decides whether the faces on the edges of the inner view are occluded,
+/// overriding what the inner view would say about them: a [`solid`] border
+/// occludes all of them, and any other border occludes none of them.
+///
+/// Voxel `[x, y, z]` of the inner view is voxel `[x + 1, y + 1, z + 1]` of
+/// this view.
+///
+/// [`solid`]: PaddedView::solid
+#[derive(Debug, Clone, Copy)]
+pub struct PaddedView<V> {
+    view: V,
+    solid: bool,
+}
+
+impl<V: MeshingView> PaddedView<V> {
+    /// Returns `view` surrounded by a border that doesn't occlude anything.
+    #[inline]
+    pub fn new(view: V) -> Self {
+        Self { view, solid: false }
+    }
+
+    /// Returns `view` surrounded by a border that occludes every face next to
+    /// it.
+    #[inline]
+    pub fn solid(view: V) -> Self {
+        Self { view, solid: true }
+    }
+
+    /// Returns the index in the inner view of the voxel at `index` in this
+    /// view, or `None` if the voxel is part of the border.
+    #[inline]
+    fn inner(&self, index: [IndexTy; 3]) -> Option<[IndexTy; 3]> {
+        let size = size_of(&self.view);
+
+        let mut inner = [0; 3];
+        for axis in 0..3 {
+            inner[axis] = index[axis].checked_sub(1)?;
+            if inner[axis] >= size[axis] {
+                return None;
+            }
+        }
+
+        Some(inner)
+    }
+
+    #[inline]
+    fn transform(&self) -> QuadTransform {
+        QuadTransform::translation(Vec3::ONE)
+    }
+}
+
+impl<V: MeshingView> VoxelView for PaddedView<V> {
+    #[inline(always)]
+    fn size_x(&self) -> IndexTy {
+        self.view.size_x() + 2
+    }
+
+    #[inline(always)]
+    fn size_y(&self) -> IndexTy {
+        self.view.size_y() + 2
+    }
+
+    #[inline(always)]
+    fn size_z(&self) -> IndexTy {
+        self.view.size_z() + 2
+    }
+}
+
+impl<V: MeshingView> MeshingView for PaddedView<V> {
+    type Quads = TransformedQuads<V::Quads>;
+
+    #[inline]
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        self.inner([x, y, z])
+            .map_or(true, |[x, y, z]| self.view.is_empty(x, y, z))
+    }
+
+    #[inline]
+    fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+        self.inner([x, y, z])
+            .map_or(false, |[x, y, z]| self.view.is_full_cube(x, y, z))
+    }
+
+    #[inline]
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+        let [x, y, z] = match self.inner([x, y, z]) {
+            Some(inner) => inner,
+            None => return false,
+        };
+
+        let size = size_of(&self.view);
+        let faces_border = face.translate_pos([x, y, z], 1).map_or(true, |neighbor| {
+            (0..3).any(|axis| neighbor[axis] >= size[axis])
+        });
+
+        if faces_border {
+            self.solid
+        } else {
+            self.view.is_face_occluded(x, y, z, face)
+        }
+    }
+
+    #[inline]
+    fn face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Self::Quads {
+        let [x, y, z] = self.inner([x, y, z]).unwrap();
+        TransformedQuads::new(self.view.face_quads(x, y, z, face), self.transform())
+    }
+
+    #[inline]
+    fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads {
+        let [x, y, z] = self.inner([x, y, z]).unwrap();
+        TransformedQuads::new(self.view.non_face_quads(x, y, z), self.transform())
+    }
+
+    #[inline]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        match (self.inner(a), self.inner(b)) {
+            (Some(a), Some(b)) => self.view.can_merge_faces(a, b, face),
+            _ => false,
+        }
+    }
+}
+
+/// A rotation followed by a translation, applied to the quads of an inner
+/// view.
+#[derive(Debug, Clone, Copy)]
+struct QuadTransform {
+    rotation: Mat3,
+    translation: Vec3,
+}
+
+impl QuadTransform {
+    #[inline]
+    fn translation(translation: Vec3) -> Self {
+        Self {
+            rotation: Mat3::IDENTITY,
+            translation,
+        }
+    }
+
+    #[inline]
+    fn apply(&self, quad: QuadPositions) -> QuadPositions {
+        quad.map(|position| (self.rotation * Vec3::from(position) + self.translation).into())
+    }
+}
+
+/// The quads of an inner view, moved to where they are in an [`OffsetView`],
+/// [`RotatedView`], or [`PaddedView`].
+#[derive(Debug, Clone)]
+pub struct TransformedQuads<Q> {
+    quads: Q,
+    transform: QuadTransform,
+}
+
+impl<Q> TransformedQuads<Q> {
+    #[inline]
+    fn new(quads: Q, transform: QuadTransform) -> Self {
+        Self { quads, transform }
+    }
+}
+
+impl<Q: IntoIterator<Item = QuadPositions>> IntoIterator for TransformedQuads<Q> {
+    type Item = QuadPositions;
+    type IntoIter = TransformedQuadsIter<Q::IntoIter>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        TransformedQuadsIter {
+            quads: self.quads.into_iter(),
+            transform: self.transform,
+        }
+    }
+}
+
+/// Iterator over [`TransformedQuads`].
+#[derive(Debug, Clone)]
+pub struct TransformedQuadsIter<I> {
+    quads: I,
+    transform: QuadTransform,
+}
+
+impl<I: Iterator<Item = QuadPositions>> Iterator for TransformedQuadsIter<I> {
+    type Item = QuadPositions;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let transform = self.transform;
+        self.quads.next().map(|quad| transform.apply(quad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        meshing::{
+            test_util::{LayerVoxel, TestChunk},
+            Mesh, Mesher,
+        },
+        SimpleMesher,
+    };
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Voxel {
+        Air,
+        Cube,
+        Slab,
+    }
+
+    impl LayerVoxel for Voxel {
+        /// `0` is air, `1` is a cube, and `2` is a slab.
+        fn from_byte(byte: u8) -> Self {
+            match byte {
+                0 => Voxel::Air,
+                1 => Voxel::Cube,
+                _ => Voxel::Slab,
+            }
+        }
+    }
+
+    /// A chunk of air, full cubes, and bottom slabs.
+    type Fixture = TestChunk<Voxel>;
+
+    impl Fixture {
+        fn slab(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> (Vec3, Vec3) {
+            let min = Vec3::new(x as f32, y as f32, z as f32);
+            (min, min + Vec3::new(1.0, 0.5, 1.0))
+        }
+    }
+
+    /// Returns the quad on the `face` side of the box from `min` to `max`.
+    fn box_face(min: Vec3, max: Vec3, face: Direction) -> QuadPositions {
+        let axis = face.axis() as usize;
+        let [u, v] = [(axis + 1) % 3, (axis + 2) % 3];
+
+        let mut quad = [[0.0; 3]; 4];
+        for (i, corner) in quad.iter_mut().enumerate() {
+            corner[axis] = match face.sign() {
+                AxisSign::Neg => min[axis],
+                AxisSign::Pos => max[axis],
+            };
+            corner[u] = if i & 1 == 0 { min[u] } else { max[u] };
+            corner[v] = if i & 2 == 0 { min[v] } else { max[v] };
+        }
+        quad
+    }
+
+    impl MeshingView for &Fixture {
+        type Quads = Vec<QuadPositions>;
+
+        fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+            self.get(x, y, z) == Voxel::Air
+        }
+
+        fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+            self.get(x, y, z) == Voxel::Cube
+        }
+
+        fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+            self.neighbor(x, y, z, face) == Some(Voxel::Cube)
+        }
+
+        fn face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Self::Quads {
+            // The top of a slab doesn't touch the top of the voxel, so it's
+            // one of its non-face quads.
+            if face == Direction::YPos {
+                return vec![];
+            }
+
+            let (min, max) = self.slab(x, y, z);
+            vec![box_face(min, max, face)]
+        }
+
+        fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads {
+            let (min, max) = self.slab(x, y, z);
+            vec![box_face(min, max, Direction::YPos)]
+        }
+    }
+
+    /// A quad reduced to something that can be compared exactly and sorted:
+    /// its voxel, its face, and its sorted positions in half voxels.
+    type CanonicalQuad = ([IndexTy; 3], Option<u8>, [[i32; 3]; 4]);
+
+    fn canonical_quad(
+        voxel: [IndexTy; 3],
+        face: Option<Direction>,
+        positions: [Vec3; 4],
+    ) -> CanonicalQuad {
+        let mut positions =
+            positions.map(|position| (position * 2.0).round().as_ivec3().to_array());
+        positions.sort_unstable();
+
+        (voxel, face.map(|face| face as u8), positions)
+    }
+
+    fn canonical_mesh(mesh: &Mesh) -> Vec<CanonicalQuad> {
+        let mut quads: Vec<_> = mesh
+            .quads
+            .iter()
+            .map(|quad| canonical_quad(quad.voxel, quad.face, quad.positions.map(Vec3::from)))
+            .collect();
+        quads.sort_unstable();
+        quads
+    }
+
+    /// Rotates the quads of `mesh`, which was generated from a view of size
+    /// `size`, the same way that a [`RotatedView`] would.
+    fn rotate_mesh(
+        mesh: &Mesh,
+        size: [IndexTy; 3],
+        axis: Axis,
+        degrees: i32,
+    ) -> Vec<CanonicalQuad> {
+        // `Direction::with_rotation` turns clockwise when looking down the
+        // positive axis, which is a negative angle to glam.
+        let rotation =
+            Mat3::from_axis_angle(Vec3::from(axis.unit_vec()), -(degrees as f32).to_radians());
+
+        let inner_half_size = to_ivec3(size).as_vec3() / 2.0;
+        let half_size = (rotation * inner_half_size).abs();
+        let rotate = |position: Vec3| rotation * (position - inner_half_size) + half_size;
+
+        let mut quads: Vec<_> = mesh
+            .quads
+            .iter()
+            .map(|quad| {
+                let center = rotate(to_ivec3(quad.voxel).as_vec3() + Vec3::splat(0.5));
+                let voxel = from_ivec3((center - Vec3::splat(0.5)).round().as_ivec3());
+
+                canonical_quad(
+                    voxel,
+                    quad.face.map(|face| face.with_rotation(axis, degrees)),
+                    quad.positions.map(|position| rotate(Vec3::from(position))),
+                )
+            })
+            .collect();
+        quads.sort_unstable();
+        quads
+    }
+
+    fn fixture() -> Fixture {
+        #[rustfmt::skip]
+        let fixture = Fixture::from_layers(&[
+            [
+                [1, 0, 0],
+                [2, 1, 0],
+                [0, 0, 0],
+                [0, 0, 2],
+            ],
+            [
+                [1, 1, 0],
+                [1, 2, 1],
+                [0, 1, 0],
+                [1, 1, 1],
+            ],
+        ]);
+        fixture
+    }
+
+    #[test]
+    fn rotated_index_mapping() {
+        let fixture = Fixture::from_layers(&[[[1, 0, 2], [0, 0, 0]]]);
+        let rotated = RotatedView::new(&fixture, Axis::Y, 90);
+
+        assert_eq!(size_of(&rotated), [2, 1, 3]);
+        assert_eq!(rotated.inner([1, 0, 2]), [2, 0, 0]);
+        assert_eq!(rotated.inner([1, 0, 0]), [0, 0, 0]);
+        assert!(!rotated.is_empty(1, 0, 2));
+        assert!(!rotated.is_full_cube(1, 0, 2));
+        assert!(rotated.is_full_cube(1, 0, 0));
+    }
+
+    #[test]
+    fn rotated_mesh_is_rotated_inner_mesh() {
+        let fixture = fixture();
+        let inner_mesh = SimpleMesher.generate_mesh(&fixture);
+
+        for axis in Axis::values() {
+            for degrees in [0, 90, 180, 270, -90] {
+                let rotated = RotatedView::new(&fixture, axis, degrees);
+                let rotated_mesh = SimpleMesher.generate_mesh(rotated);
+
+                assert_eq!(
+                    canonical_mesh(&rotated_mesh),
+                    rotate_mesh(&inner_mesh, fixture.size, axis, degrees),
+                    "{:?} {}",
+                    axis,
+                    degrees
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn offset_view_culls_against_outside() {
+        let fixture = Fixture::from_layers(&[[[1, 1, 1], [1, 1, 1], [1, 1, 2]]; 3]);
+
+        let center = OffsetView::new(&fixture, [1, 1, 1]).with_size([1, 1, 1]);
+        assert!(SimpleMesher.generate_mesh(center).quads.is_empty());
+
+        let corner = OffsetView::new(&fixture, [2, 2, 2]);
+        assert_eq!(size_of(&corner), [1, 1, 1]);
+
+        let mesh = SimpleMesher.generate_mesh(corner);
+        let top = mesh.quads.iter().find(|quad| quad.face.is_none()).unwrap();
+        assert_eq!(top.voxel, [0, 0, 0]);
+        assert_eq!(
+            canonical_quad(top.voxel, top.face, top.positions.map(Vec3::from)).2,
+            [[0, 1, 0], [0, 1, 2], [2, 1, 0], [2, 1, 2]]
+        );
+    }
+
+    #[test]
+    fn padded_view_border() {
+        let fixture = Fixture::from_layers(&[[[1; 3]; 3]; 3]);
+
+        let open = PaddedView::new(&fixture);
+        assert_eq!(size_of(&open), [5, 5, 5]);
+        assert_eq!(SimpleMesher.generate_mesh(open).quads.len(), 6 * 9);
+
+        let solid = PaddedView::solid(&fixture);
+        assert!(SimpleMesher.generate_mesh(solid).quads.is_empty());
+
+        let slab = Fixture::from_layers(&[[[2]]]);
+        let mesh = SimpleMesher.generate_mesh(PaddedView::new(&slab));
+        assert_eq!(mesh.quads.len(), 6);
+
+        let top = mesh.quads.iter().find(|quad| quad.face.is_none()).unwrap();
+        assert_eq!(top.voxel, [1, 1, 1]);
+        assert_eq!(
+            canonical_quad(top.voxel, top.face, top.positions.map(Vec3::from)).2,
+            [[2, 3, 2], [2, 3, 4], [4, 3, 2], [4, 3, 4]]
+        );
+    }
+}
@@ -0,0 +1,457 @@
+use glam::Vec3;
+
+use crate::{Direction, IndexTy, VoxelView};
+
+use super::{simple::SimpleMesherContext, Mesh, Quad};
+
+/// The fluids that a [`FluidMesher`] knows how to mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FluidKind {
+    Water,
+    Lava,
+}
+
+impl FluidKind {
+    /// Returns the fluid whose block has the given name (e.g., `"water"`),
+    /// which may optionally include the `minecraft:` namespace.
+    #[inline]
+    pub fn from_block_name(name: &str) -> Option<Self> {
+        match name.strip_prefix("minecraft:").unwrap_or(name) {
+            "water" => Some(Self::Water),
+            "lava" => Some(Self::Lava),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the animated texture that the surface of the fluid
+    /// is drawn with.
+    #[inline]
+    pub const fn still_texture(self) -> &'static str {
+        match self {
+            Self::Water => "block/water_still",
+            Self::Lava => "block/lava_still",
+        }
+    }
+
+    /// Returns the tint index of the fluid's quads, like the `tint_index` of
+    /// `brine_asset`'s baked quads.
+    ///
+    /// Water is colored by the biome; lava is not tinted.
+    #[inline]
+    pub const fn tint_index(self) -> Option<u8> {
+        match self {
+            Self::Water => Some(0),
+            Self::Lava => None,
+        }
+    }
+
+    /// Returns true if the fluid should be drawn in the translucent render
+    /// pass.
+    #[inline]
+    pub const fn is_translucent(self) -> bool {
+        matches!(self, Self::Water)
+    }
+}
+
+/// The fluid in a single voxel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fluid {
+    pub kind: FluidKind,
+
+    /// The value of the block state's `level` property.
+    ///
+    /// `0` is a source block, `1` through `7` are flowing fluid that gets
+    /// lower the further it is from its source, and `8` and up are falling
+    /// fluid.
+    pub level: u8,
+}
+
+impl Fluid {
+    /// Returns a source block of the given fluid.
+    #[inline]
+    pub const fn source(kind: FluidKind) -> Self {
+        Self { kind, level: 0 }
+    }
+
+    #[inline]
+    pub const fn is_source(self) -> bool {
+        self.level == 0
+    }
+
+    #[inline]
+    pub const fn is_falling(self) -> bool {
+        self.level >= 8
+    }
+
+    /// Returns the height of the fluid's surface above the bottom of its
+    /// voxel, ignoring its neighbors.
+    ///
+    /// Falling fluid fills its whole voxel. Otherwise, a source block is 8/9
+    /// of a block tall and each level below it is 1/9 of a block shorter.
+    #[inline]
+    pub fn height(self) -> f32 {
+        if self.is_falling() {
+            1.0
+        } else {
+            (8 - self.level) as f32 / 9.0
+        }
+    }
+}
+
+/// A [`VoxelView`] that can be used with a [`FluidMesher`] to generate meshes
+/// for the fluids in a cuboid chunk of voxels.
+pub trait FluidView: VoxelView {
+    /// Returns the fluid in the voxel at index `[x, y, z]`, or `None` if there
+    /// is no fluid there.
+    fn fluid(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Option<Fluid>;
+
+    /// Returns true if the voxel at index `[x, y, z]` fully hides any fluid
+    /// face that touches it, e.g., if it is a full stone block.
+    fn is_opaque(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool;
+}
+
+/// The meshes generated by a [`FluidMesher`], one for each [`FluidKind`].
+///
+/// The meshes are kept separate so that each can be drawn with the
+/// [`still_texture`] and [`tint_index`] of its fluid, and in the translucent
+/// render pass if the fluid [`is_translucent`].
+///
+/// [`still_texture`]: FluidKind::still_texture
+/// [`tint_index`]: FluidKind::tint_index
+/// [`is_translucent`]: FluidKind::is_translucent
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FluidMesh {
+    pub water: Mesh,
+    pub lava: Mesh,
+}
+
+impl FluidMesh {
+    #[inline]
+    pub fn get(&self, kind: FluidKind) -> &Mesh {
+        match kind {
+            FluidKind::Water => &self.water,
+            FluidKind::Lava => &self.lava,
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, kind: FluidKind) -> &mut Mesh {
+        match kind {
+            FluidKind::Water => &mut self.water,
+            FluidKind::Lava => &mut self.lava,
+        }
+    }
+
+    /// Iterates over the mesh of every fluid.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (FluidKind, &Mesh)> {
+        [FluidKind::Water, FluidKind::Lava]
+            .into_iter()
+            .map(|kind| (kind, self.get(kind)))
+    }
+}
+
+/// Generates meshes for fluids, whose surfaces slope down toward where they
+/// are flowing.
+///
+/// Each corner of a fluid's top surface is as high as the average height of
+/// the (up to) four voxels of the same fluid that share that corner, with
+/// source blocks weighing ten times as much as flowing fluid, as in Minecraft.
+/// A corner is a full block high if any of those voxels has the same fluid
+/// above it. Falling fluid is always a full block high.
+///
+/// The top surface is skipped if the same fluid is above it. The other faces
+/// are skipped if the same fluid or an [opaque] voxel is next to them.
+/// Neighbors outside of the view are treated as empty.
+///
+/// [opaque]: FluidView::is_opaque
+#[derive(Debug, Default)]
+pub struct FluidMesher;
+
+impl FluidMesher {
+    pub fn generate_mesh<V: FluidView>(&mut self, view: V) -> FluidMesh {
+        let mut mesh = FluidMesh::default();
+
+        for y in 0..view.size_y() {
+            for z in 0..view.size_z() {
+                for x in 0..view.size_x() {
+                    if let Some(fluid) = view.fluid(x, y, z) {
+                        Self::mesh_fluid(&view, [x, y, z], fluid, mesh.get_mut(fluid.kind));
+                    }
+                }
+            }
+        }
+
+        mesh
+    }
+
+    fn mesh_fluid<V: FluidView>(view: &V, voxel: [IndexTy; 3], fluid: Fluid, mesh: &mut Mesh) {
+        let [x, y, z] = voxel;
+        let minimum = Vec3::new(x as f32, y as f32, z as f32);
+
+        let covered = Self::is_same_fluid(view, Direction::YPos.translate_pos(voxel, 1), fluid);
+
+        // Heights of the corners at the minimum and maximum X and Z.
+        let corner_heights = if covered || fluid.is_falling() {
+            [[1.0; 2]; 2]
+        } else {
+            [0, 1].map(|corner_x| {
+                [0, 1].map(|corner_z| Self::corner_height(view, voxel, [corner_x, corner_z], fluid))
+            })
+        };
+
+        for face in Direction::values() {
+            let neighbor = face.translate_pos(voxel, 1);
+
+            let visible = match face {
+                Direction::YPos => !covered,
+                _ => {
+                    !Self::is_same_fluid(view, neighbor, fluid)
+                        && !neighbor
+                            .filter(|&neighbor| Self::in_bounds(view, neighbor))
+                            .map_or(false, |[x, y, z]| view.is_opaque(x, y, z))
+                }
+            };
+            if !visible {
+                continue;
+            }
+
+            // Lower the top of each face to the surface of the fluid.
+            let unit_positions = SimpleMesherContext::<V>::full_face_quad(Vec3::ZERO, face);
+            let positions = unit_positions.map(|[unit_x, unit_y, unit_z]| {
+                let height = if unit_y > 0.0 {
+                    corner_heights[unit_x as usize][unit_z as usize]
+                } else {
+                    0.0
+                };
+
+                (minimum + Vec3::new(unit_x, height, unit_z)).into()
+            });
+
+            mesh.quads.push(Quad {
+                positions,
+                voxel,
+                face: Some(face),
+            });
+        }
+    }
+
+    /// Returns the height of the corner of the fluid at `voxel` that is at
+    /// the minimum (`0`) or maximum (`1`) X and Z of the voxel.
+    fn corner_height<V: FluidView>(
+        view: &V,
+        voxel: [IndexTy; 3],
+        [corner_x, corner_z]: [IndexTy; 2],
+        fluid: Fluid,
+    ) -> f32 {
+        let [x, y, z] = voxel;
+
+        let mut total_height = 0.0;
+        let mut total_weight = 0.0;
+
+        for (dx, dz) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            let column = (x + corner_x)
+                .checked_sub(dx)
+                .zip((z + corner_z).checked_sub(dz))
+                .map(|(x, z)| [x, y, z])
+                .filter(|&column| Self::in_bounds(view, column));
+
+            let neighbor = match column.and_then(|[x, y, z]| view.fluid(x, y, z)) {
+                Some(neighbor) if neighbor.kind == fluid.kind => neighbor,
+                _ => continue,
+            };
+
+            let above = column.and_then(|column| Direction::YPos.translate_pos(column, 1));
+            if Self::is_same_fluid(view, above, fluid) {
+                return 1.0;
+            }
+
+            let weight = if neighbor.is_source() { 10.0 } else { 1.0 };
+            total_height += neighbor.height() * weight;
+            total_weight += weight;
+        }
+
+        // The voxel itself is always one of the four.
+        total_height / total_weight
+    }
+
+    #[inline]
+    fn is_same_fluid<V: FluidView>(view: &V, voxel: Option<[IndexTy; 3]>, fluid: Fluid) -> bool {
+        voxel
+            .filter(|&voxel| Self::in_bounds(view, voxel))
+            .and_then(|[x, y, z]| view.fluid(x, y, z))
+            .map_or(false, |other| other.kind == fluid.kind)
+    }
+
+    #[inline]
+    fn in_bounds<V: FluidView>(view: &V, [x, y, z]: [IndexTy; 3]) -> bool {
+        x < view.size_x() && y < view.size_y() && z < view.size_z()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::meshing::test_util::{LayerVoxel, TestChunk};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    enum Voxel {
+        Air,
+        Stone,
+        Fluid(Fluid),
+    }
+
+    impl LayerVoxel for Voxel {
+        /// `.` is air, `#` is stone, `0`-`9` are water at that level, and `L`
+        /// is a lava source.
+        fn from_byte(byte: u8) -> Self {
+            match byte {
+                b'.' => Voxel::Air,
+                b'#' => Voxel::Stone,
+                b'L' => Voxel::Fluid(Fluid::source(FluidKind::Lava)),
+                level => Voxel::Fluid(Fluid {
+                    kind: FluidKind::Water,
+                    level: level - b'0',
+                }),
+            }
+        }
+    }
+
+    /// A chunk of air, stone, and fluids.
+    type FluidChunk = TestChunk<Voxel>;
+
+    impl FluidView for &FluidChunk {
+        fn fluid(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Option<Fluid> {
+            match self.get(x, y, z) {
+                Voxel::Fluid(fluid) => Some(fluid),
+                _ => None,
+            }
+        }
+
+        fn is_opaque(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+            matches!(self.get(x, y, z), Voxel::Stone)
+        }
+    }
+
+    fn count_faces(mesh: &Mesh, face: Direction) -> usize {
+        mesh.quads
+            .iter()
+            .filter(|quad| quad.face == Some(face))
+            .count()
+    }
+
+    fn assert_heights(mut heights: Vec<f32>, expected: [f32; 4]) {
+        heights.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+        for (height, expected) in heights.iter().zip(expected) {
+            assert!((height - expected).abs() < 1e-6, "{:?}", heights);
+        }
+    }
+
+    fn top_heights(mesh: &Mesh, voxel: [IndexTy; 3]) -> Vec<f32> {
+        let top = mesh
+            .quads
+            .iter()
+            .find(|quad| quad.voxel == voxel && quad.face == Some(Direction::YPos))
+            .unwrap();
+
+        top.positions
+            .iter()
+            .map(|[_, y, _]| y - voxel[1] as f32)
+            .collect()
+    }
+
+    #[test]
+    fn pool_is_flat() {
+        #[rustfmt::skip]
+        let chunk = FluidChunk::from_layers(&[
+            [*b"000", *b"000", *b"000"],
+            [*b"###", *b"###", *b"###"],
+        ]);
+
+        let mesh = FluidMesher.generate_mesh(&chunk);
+        assert!(mesh.lava.quads.is_empty());
+
+        let water = &mesh.water;
+        assert_eq!(count_faces(water, Direction::YPos), 9);
+        assert_eq!(count_faces(water, Direction::YNeg), 0);
+        for face in [
+            Direction::XNeg,
+            Direction::XPos,
+            Direction::ZNeg,
+            Direction::ZPos,
+        ] {
+            assert_eq!(count_faces(water, face), 3, "{:?}", face);
+        }
+        assert_eq!(water.quads.len(), 21);
+
+        assert_heights(top_heights(water, [1, 1, 1]), [8.0 / 9.0; 4]);
+    }
+
+    #[test]
+    fn stream_slopes_down() {
+        #[rustfmt::skip]
+        let chunk = FluidChunk::from_layers(&[
+            [*b"0123L"],
+            [*b"#####"],
+        ]);
+
+        let mesh = FluidMesher.generate_mesh(&chunk);
+
+        // The stream's top and sides, including where it meets the lava.
+        let water = &mesh.water;
+        assert_eq!(count_faces(water, Direction::YPos), 4);
+        assert_eq!(count_faces(water, Direction::ZNeg), 4);
+        assert_eq!(count_faces(water, Direction::ZPos), 4);
+        assert_eq!(count_faces(water, Direction::XNeg), 1);
+        assert_eq!(count_faces(water, Direction::XPos), 1);
+        assert_eq!(water.quads.len(), 14);
+
+        // Lava doesn't join the water.
+        assert_eq!(mesh.lava.quads.len(), 5);
+
+        // Each corner of the level 2 water is the average of the two levels
+        // on either side of it.
+        assert_heights(
+            top_heights(water, [2, 1, 0]),
+            [6.5 / 9.0, 6.5 / 9.0, 5.5 / 9.0, 5.5 / 9.0],
+        );
+
+        // The source pulls the corners it shares with the stream up.
+        let source_corner = (8.0 * 10.0 + 7.0) / 9.0 / 11.0;
+        assert_heights(
+            top_heights(water, [1, 1, 0]),
+            [source_corner, source_corner, 6.5 / 9.0, 6.5 / 9.0],
+        );
+    }
+
+    #[test]
+    fn falling_fluid_is_full_height() {
+        #[rustfmt::skip]
+        let chunk = FluidChunk::from_layers(&[
+            [*b"8"],
+            [*b"8"],
+            [*b"0"],
+        ]);
+
+        let water = FluidMesher.generate_mesh(&chunk).water;
+
+        // Only the top of the column has a top, and only the bottom has a
+        // bottom.
+        assert_eq!(count_faces(&water, Direction::YPos), 1);
+        assert_eq!(count_faces(&water, Direction::YNeg), 1);
+        assert_eq!(water.quads.len(), 3 * 4 + 2);
+
+        assert_heights(top_heights(&water, [0, 2, 0]), [1.0; 4]);
+
+        // The source under the column is covered, so its sides reach the top
+        // of the block.
+        let side = water
+            .quads
+            .iter()
+            .find(|quad| quad.voxel == [0, 0, 0] && quad.face == Some(Direction::XNeg))
+            .unwrap();
+        assert!(side.positions.iter().any(|[_, y, _]| *y == 1.0));
+    }
+}
@@ -0,0 +1,332 @@
+use glam::Vec3;
+
+use crate::{Axis, Direction, IndexTy};
+
+use super::{simple::SimpleMesherContext, Mesh, Mesher, MeshingView, Quad};
+
+/// A [`Mesher`] that merges the visible faces of neighboring full cubes into
+/// larger quads.
+///
+/// For each face direction, the chunk is sliced into layers perpendicular to
+/// that direction. In each layer, runs of visible faces are merged along the
+/// first of the two remaining axes (in `x`, `y`, `z` order), and then each run
+/// is expanded along the second axis for as long as the whole run can be
+/// merged. Two faces are only merged if [`MeshingView::can_merge_faces`]
+/// allows it.
+///
+/// Voxels that aren't full cubes are meshed the same way as with
+/// [`SimpleMesher`][super::SimpleMesher].
+///
+/// A merged quad's [`voxel`][Quad::voxel] is the voxel in the quad's minimum
+/// corner.
+#[derive(Debug, Default)]
+pub struct GreedyMesher;
+
+impl Mesher for GreedyMesher {
+    fn generate_mesh<V>(&mut self, view: V) -> Mesh
+    where
+        V: MeshingView,
+    {
+        let mut mesh = Mesh::default();
+
+        let mut context = SimpleMesherContext {
+            view,
+            mesh: &mut mesh,
+        };
+
+        for y in 0..context.view.size_y() {
+            for z in 0..context.view.size_z() {
+                for x in 0..context.view.size_x() {
+                    if !context.view.is_empty(x, y, z) && !context.view.is_full_cube(x, y, z) {
+                        context.mesh_voxel_using_view(x, y, z);
+                    }
+                }
+            }
+        }
+
+        for face in Direction::values() {
+            GreedyFaces::new(&context.view, face).generate_quads(context.mesh);
+        }
+
+        mesh
+    }
+}
+
+/// Merges the faces in one direction.
+struct GreedyFaces<'a, V> {
+    view: &'a V,
+    face: Direction,
+    size: [IndexTy; 3],
+
+    /// The axis that the faces point along, and the axes to merge along first
+    /// and second.
+    normal: Axis,
+    first: Axis,
+    second: Axis,
+
+    /// Faces in the current layer that are visible and haven't been merged
+    /// into a quad yet, indexed `[second][first]`.
+    remaining: Vec<bool>,
+}
+
+impl<'a, V: MeshingView> GreedyFaces<'a, V> {
+    fn new(view: &'a V, face: Direction) -> Self {
+        let normal = face.axis();
+        let [first, second] = match normal {
+            Axis::X => [Axis::Y, Axis::Z],
+            Axis::Y => [Axis::X, Axis::Z],
+            Axis::Z => [Axis::X, Axis::Y],
+        };
+        let size = [view.size_x(), view.size_y(), view.size_z()];
+
+        Self {
+            view,
+            face,
+            size,
+            normal,
+            first,
+            second,
+            remaining: Vec::new(),
+        }
+    }
+
+    fn generate_quads(&mut self, mesh: &mut Mesh) {
+        for layer in 0..self.size[self.normal as usize] {
+            self.find_visible_faces(layer);
+
+            for j in 0..self.size[self.second as usize] {
+                for i in 0..self.size[self.first as usize] {
+                    if self.is_remaining(i, j) {
+                        let quad = self.merge_quad(layer, i, j);
+                        mesh.quads.push(quad);
+                    }
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn voxel(&self, layer: IndexTy, i: IndexTy, j: IndexTy) -> [IndexTy; 3] {
+        let mut voxel = [0; 3];
+        voxel[self.normal as usize] = layer;
+        voxel[self.first as usize] = i;
+        voxel[self.second as usize] = j;
+        voxel
+    }
+
+    #[inline]
+    fn index(&self, i: IndexTy, j: IndexTy) -> usize {
+        j as usize * self.size[self.first as usize] as usize + i as usize
+    }
+
+    #[inline]
+    fn is_remaining(&self, i: IndexTy, j: IndexTy) -> bool {
+        self.remaining[self.index(i, j)]
+    }
+
+    fn find_visible_faces(&mut self, layer: IndexTy) {
+        let len_first = self.size[self.first as usize];
+        let len_second = self.size[self.second as usize];
+
+        self.remaining.clear();
+
+        for j in 0..len_second {
+            for i in 0..len_first {
+                let [x, y, z] = self.voxel(layer, i, j);
+
+                let visible = !self.view.is_empty(x, y, z)
+                    && self.view.is_full_cube(x, y, z)
+                    && !self.view.is_face_occluded(x, y, z, self.face);
+
+                self.remaining.push(visible);
+            }
+        }
+    }
+
+    #[inline]
+    fn can_merge(&self, start: [IndexTy; 3], layer: IndexTy, i: IndexTy, j: IndexTy) -> bool {
+        self.is_remaining(i, j)
+            && self
+                .view
+                .can_merge_faces(start, self.voxel(layer, i, j), self.face)
+    }
+
+    /// Merges the face at `[i, j]` with as many of its neighbors as possible,
+    /// removing the merged faces from the layer.
+    fn merge_quad(&mut self, layer: IndexTy, i: IndexTy, j: IndexTy) -> Quad {
+        let start = self.voxel(layer, i, j);
+
+        let mut width = 1;
+        while i + width < self.size[self.first as usize]
+            && self.can_merge(start, layer, i + width, j)
+        {
+            width += 1;
+        }
+
+        let mut height = 1;
+        while j + height < self.size[self.second as usize]
+            && (i..i + width).all(|i| self.can_merge(start, layer, i, j + height))
+        {
+            height += 1;
+        }
+
+        for j in j..j + height {
+            for i in i..i + width {
+                let index = self.index(i, j);
+                self.remaining[index] = false;
+            }
+        }
+
+        let mut extent = [1.0; 3];
+        extent[self.first as usize] = width as f32;
+        extent[self.second as usize] = height as f32;
+
+        let [x, y, z] = start;
+        let minimum = Vec3::new(x as f32, y as f32, z as f32);
+        let extent = Vec3::from(extent);
+
+        let unit_positions = SimpleMesherContext::<V>::full_face_quad(Vec3::ZERO, self.face);
+
+        Quad {
+            positions: unit_positions.map(|unit| (minimum + Vec3::from(unit) * extent).into()),
+            voxel: start,
+            face: Some(self.face),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        meshing::test_util::{LayerVoxel, TestChunk},
+        SimpleMesher,
+    };
+
+    use super::*;
+
+    /// A chunk of voxels that are either empty (`0`) or full cubes that can
+    /// all be merged with each other.
+    type BoolChunk = TestChunk<bool>;
+
+    impl LayerVoxel for bool {
+        fn from_byte(byte: u8) -> Self {
+            byte != 0
+        }
+    }
+
+    impl MeshingView for &BoolChunk {
+        type Quads = Option<[[f32; 3]; 4]>;
+
+        fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
+            !self.get(x, y, z)
+        }
+
+        fn is_full_cube(&self, _x: IndexTy, _y: IndexTy, _z: IndexTy) -> bool {
+            true
+        }
+
+        fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
+            self.neighbor(x, y, z, face).unwrap_or(false)
+        }
+
+        fn face_quads(&self, _: IndexTy, _: IndexTy, _: IndexTy, _: Direction) -> Self::Quads {
+            None
+        }
+
+        fn non_face_quads(&self, _x: IndexTy, _y: IndexTy, _z: IndexTy) -> Self::Quads {
+            None
+        }
+    }
+
+    fn count_faces(mesh: &Mesh, face: Direction) -> usize {
+        mesh.quads
+            .iter()
+            .filter(|quad| quad.face == Some(face))
+            .count()
+    }
+
+    #[test]
+    fn solid_chunk_is_six_quads() {
+        let chunk = BoolChunk::from_layers(&[[[1; 4]; 4]; 4]);
+
+        let mesh = GreedyMesher.generate_mesh(&chunk);
+
+        assert_eq!(mesh.quads.len(), 6);
+
+        let top = mesh
+            .quads
+            .iter()
+            .find(|quad| quad.face == Some(Direction::YPos))
+            .unwrap();
+        let mut corners = top.positions;
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            corners,
+            [
+                [0.0, 4.0, 0.0],
+                [0.0, 4.0, 4.0],
+                [4.0, 4.0, 0.0],
+                [4.0, 4.0, 4.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_quads_match_simple_quads() {
+        // A single row merges into one quad per side, and its ends stay unit
+        // quads.
+        let chunk = BoolChunk::from_layers(&[[[1, 1, 1, 0]]]);
+
+        let simple = SimpleMesher.generate_mesh(&chunk);
+        let greedy = GreedyMesher.generate_mesh(&chunk);
+
+        assert_eq!(simple.quads.len(), 14);
+        assert_eq!(greedy.quads.len(), 6);
+
+        let end = greedy
+            .quads
+            .iter()
+            .find(|quad| quad.face == Some(Direction::XPos))
+            .unwrap();
+        let simple_end = simple
+            .quads
+            .iter()
+            .find(|quad| quad.face == Some(Direction::XPos))
+            .unwrap();
+        assert_eq!(end, simple_end);
+    }
+
+    #[test]
+    fn example_chunk() {
+        // The two layers drawn in the comment on the examples' `IntChunk`.
+        #[rustfmt::skip]
+        let chunk = BoolChunk::from_layers(&[
+            [
+                [0, 1, 0, 1],
+                [0, 0, 1, 1],
+                [0, 0, 0, 0],
+                [1, 0, 0, 1],
+            ],
+            [
+                [1, 1, 1, 1],
+                [1, 0, 1, 0],
+                [0, 0, 1, 1],
+                [0, 1, 0, 0],
+            ],
+        ]);
+
+        let simple = SimpleMesher.generate_mesh(&chunk);
+        let greedy = GreedyMesher.generate_mesh(&chunk);
+
+        let expected_simple = [10, 10, 12, 12, 11, 11];
+        let expected_greedy = [8, 9, 8, 9, 8, 8];
+
+        for (i, face) in Direction::values().into_iter().enumerate() {
+            assert_eq!(count_faces(&simple, face), expected_simple[i], "{:?}", face);
+            assert_eq!(count_faces(&greedy, face), expected_greedy[i], "{:?}", face);
+        }
+
+        assert_eq!(simple.quads.len(), 66);
+        assert_eq!(greedy.quads.len(), 50);
+    }
+}
@@ -1,3 +1,5 @@
+use glam::Vec3;
+
 use crate::{Direction, IndexTy};
 
 /// Contains a list of [`Quads`] representing the geometry of a voxel chunk.
@@ -13,6 +15,48 @@ pub type QuadNormals = [[f32; 3]; 4];
 pub type QuadTexCoords = [[f32; 2]; 4];
 pub type QuadIndices = [u8; 6];
 
+impl Mesh {
+    /// Returns the number of vertices in the mesh, four for each quad.
+    #[inline]
+    pub fn num_vertices(&self) -> usize {
+        self.quads.len() * 4
+    }
+
+    /// Returns true if the mesh has too many vertices to be indexed with `u16`
+    /// indices once the quads' vertices are laid out one after another.
+    #[inline]
+    pub fn needs_u32_indices(&self) -> bool {
+        self.num_vertices() > u16::MAX as usize
+    }
+
+    /// Appends the quads of `other` to this mesh, with their positions
+    /// translated by `offset`.
+    ///
+    /// This can be used to combine the meshes of a chunk's sections into one,
+    /// so that they can be drawn together. Each quad's [`indices`] refer only to
+    /// its own four vertices, so they are re-based automatically when the
+    /// vertices are laid out. Use [`needs_u32_indices`] to pick an index width
+    /// for the combined mesh.
+    ///
+    /// The [`voxel`] of each merged quad is left as is, so it is relative to
+    /// the view that `other` was generated from.
+    ///
+    /// [`indices`]: Quad::get_indices
+    /// [`needs_u32_indices`]: Mesh::needs_u32_indices
+    /// [`voxel`]: Quad::voxel
+    pub fn merge(&mut self, other: &Mesh, offset: Vec3) {
+        self.quads.reserve(other.quads.len());
+
+        for quad in other.quads.iter() {
+            let mut quad = quad.clone();
+            quad.positions = quad
+                .positions
+                .map(|position| (Vec3::from(position) + offset).into());
+            self.quads.push(quad);
+        }
+    }
+}
+
 /// A single quad in a [`Mesh`].
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Quad {
@@ -68,3 +112,78 @@ impl Quad {
         [[0.0, 1.0], [1.0, 1.0], [0.0, 0.0], [1.0, 0.0]]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_quad(voxel: [IndexTy; 3]) -> Quad {
+        Quad {
+            positions: [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+            voxel,
+            face: Some(Direction::ZNeg),
+        }
+    }
+
+    #[test]
+    fn merge_translates_positions() {
+        let mut mesh = Mesh {
+            quads: vec![unit_quad([0, 0, 0])],
+        };
+        let other = Mesh {
+            quads: vec![unit_quad([1, 2, 3]), unit_quad([4, 5, 6])],
+        };
+
+        mesh.merge(&other, Vec3::new(0.0, 16.0, 0.0));
+
+        assert_eq!(mesh.quads.len(), 3);
+        assert_eq!(mesh.quads[0], unit_quad([0, 0, 0]));
+        assert_eq!(
+            mesh.quads[1].positions,
+            [
+                [0.0, 16.0, 0.0],
+                [1.0, 16.0, 0.0],
+                [0.0, 17.0, 0.0],
+                [1.0, 17.0, 0.0],
+            ]
+        );
+        assert_eq!(mesh.quads[2].voxel, [4, 5, 6]);
+        assert_eq!(mesh.quads[2].face, Some(Direction::ZNeg));
+        assert_eq!(mesh.num_vertices(), 12);
+    }
+
+    #[test]
+    fn merge_into_empty_mesh() {
+        let other = Mesh {
+            quads: vec![unit_quad([0, 0, 0])],
+        };
+
+        let mut mesh = Mesh::default();
+        mesh.merge(&other, Vec3::ZERO);
+
+        assert_eq!(mesh, other);
+    }
+
+    #[test]
+    fn index_width() {
+        let quad = unit_quad([0, 0, 0]);
+        let max_u16_quads = (u16::MAX as usize) / 4;
+
+        let mut mesh = Mesh {
+            quads: vec![quad.clone(); max_u16_quads],
+        };
+        assert!(!mesh.needs_u32_indices());
+
+        // One more quad pushes the total over the limit.
+        let other = Mesh {
+            quads: vec![quad; 1],
+        };
+        mesh.merge(&other, Vec3::ZERO);
+        assert!(mesh.needs_u32_indices());
+    }
+}
@@ -78,6 +78,23 @@ pub trait MeshingView: VoxelView {
     /// [`is_empty`]: MeshingView::is_empty
     /// [`is_face_occluded`]: MeshingView::is_face_occluded
     fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads;
+
+    /// Returns true if the given face of the voxels at indices `a` and `b` can
+    /// be merged into a single quad by a mesher that merges faces, like the
+    /// [`GreedyMesher`].
+    ///
+    /// This is only asked about neighboring full cubes whose faces are both
+    /// visible. It should return false if the faces look different from one
+    /// another (e.g., if they have different textures).
+    ///
+    /// By default, all such faces can be merged.
+    ///
+    /// [`GreedyMesher`]: super::GreedyMesher
+    #[inline]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        let _ = (a, b, face);
+        true
+    }
 }
 
 /// A trait that makes it possible to implement composable meshing views that
@@ -88,35 +105,45 @@ pub trait DelegatingMeshingView: VoxelView {
     fn delegate(&self) -> &Self::Delegate;
 
     #[inline(always)]
-    fn is_empty(&self, x: u8, y: u8, z: u8) -> bool {
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
         self.delegate().is_empty(x, y, z)
     }
 
     #[inline(always)]
-    fn is_full_cube(&self, x: u8, y: u8, z: u8) -> bool {
+    fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
         self.delegate().is_full_cube(x, y, z)
     }
 
     #[inline(always)]
-    fn is_face_occluded(&self, x: u8, y: u8, z: u8, face: Direction) -> bool {
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
         self.delegate().is_face_occluded(x, y, z, face)
     }
 
     #[inline(always)]
     fn face_quads(
         &self,
-        x: u8,
-        y: u8,
-        z: u8,
+        x: IndexTy,
+        y: IndexTy,
+        z: IndexTy,
         face: Direction,
     ) -> <Self::Delegate as MeshingView>::Quads {
         self.delegate().face_quads(x, y, z, face)
     }
 
     #[inline(always)]
-    fn non_face_quads(&self, x: u8, y: u8, z: u8) -> <Self::Delegate as MeshingView>::Quads {
+    fn non_face_quads(
+        &self,
+        x: IndexTy,
+        y: IndexTy,
+        z: IndexTy,
+    ) -> <Self::Delegate as MeshingView>::Quads {
         self.delegate().non_face_quads(x, y, z)
     }
+
+    #[inline(always)]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        self.delegate().can_merge_faces(a, b, face)
+    }
 }
 
 impl<T: DelegatingMeshingView> VoxelView for T {
@@ -140,27 +167,32 @@ impl<T: DelegatingMeshingView> MeshingView for T {
     type Quads = <T::Delegate as MeshingView>::Quads;
 
     #[inline(always)]
-    fn is_empty(&self, x: u8, y: u8, z: u8) -> bool {
+    fn is_empty(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
         DelegatingMeshingView::is_empty(self, x, y, z)
     }
 
     #[inline(always)]
-    fn is_full_cube(&self, x: u8, y: u8, z: u8) -> bool {
+    fn is_full_cube(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> bool {
         DelegatingMeshingView::is_full_cube(self, x, y, z)
     }
 
     #[inline(always)]
-    fn is_face_occluded(&self, x: u8, y: u8, z: u8, face: Direction) -> bool {
+    fn is_face_occluded(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> bool {
         DelegatingMeshingView::is_face_occluded(self, x, y, z, face)
     }
 
     #[inline(always)]
-    fn face_quads(&self, x: u8, y: u8, z: u8, face: Direction) -> Self::Quads {
+    fn face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Self::Quads {
         DelegatingMeshingView::face_quads(self, x, y, z, face)
     }
 
     #[inline(always)]
-    fn non_face_quads(&self, x: u8, y: u8, z: u8) -> Self::Quads {
+    fn non_face_quads(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> Self::Quads {
         DelegatingMeshingView::non_face_quads(self, x, y, z)
     }
+
+    #[inline(always)]
+    fn can_merge_faces(&self, a: [IndexTy; 3], b: [IndexTy; 3], face: Direction) -> bool {
+        DelegatingMeshingView::can_merge_faces(self, a, b, face)
+    }
 }
@@ -1,8 +1,18 @@
+mod combinators;
+mod fluid;
+mod greedy;
 mod mesh;
 mod mesher;
 mod meshing_view;
 mod simple;
+#[cfg(test)]
+mod test_util;
 
+pub use combinators::{
+    OffsetView, PaddedView, RotatedView, TransformedQuads, TransformedQuadsIter,
+};
+pub use fluid::{Fluid, FluidKind, FluidMesh, FluidMesher, FluidView};
+pub use greedy::GreedyMesher;
 pub use mesh::{Mesh, Quad, QuadIndices, QuadNormals, QuadPositions, QuadTexCoords};
 pub use mesher::Mesher;
 pub use meshing_view::{DelegatingMeshingView, MeshingView};
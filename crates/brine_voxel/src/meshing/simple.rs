@@ -26,8 +26,8 @@ impl Mesher for SimpleMesher {
 }
 
 pub struct SimpleMesherContext<'a, V> {
-    view: V,
-    mesh: &'a mut Mesh,
+    pub(super) view: V,
+    pub(super) mesh: &'a mut Mesh,
 }
 
 impl<'a, V: MeshingView> SimpleMesherContext<'a, V> {
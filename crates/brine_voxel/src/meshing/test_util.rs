@@ -0,0 +1,59 @@
+//! Fixtures shared by the meshing tests.
+
+use crate::{Direction, IndexTy, VoxelView};
+
+/// A voxel that can be written as a single byte in
+/// [`TestChunk::from_layers`].
+pub(crate) trait LayerVoxel: Copy {
+    fn from_byte(byte: u8) -> Self;
+}
+
+/// A chunk of voxels for tests to mesh.
+pub(crate) struct TestChunk<T> {
+    pub size: [IndexTy; 3],
+    voxels: Vec<T>,
+}
+
+impl<T: LayerVoxel> TestChunk<T> {
+    /// Builds a chunk from layers of rows of voxels, where the layers are
+    /// listed from the top (`+y`) down and each layer's rows are listed from
+    /// back (`-z`) to front (`+z`).
+    pub fn from_layers<const X: usize, const Z: usize>(layers: &[[[u8; X]; Z]]) -> Self {
+        let size = [X as IndexTy, layers.len() as IndexTy, Z as IndexTy];
+        let voxels = (0..X * layers.len() * Z)
+            .map(|index| {
+                let (x, z, y) = (index % X, index / X % Z, index / (X * Z));
+                T::from_byte(layers[layers.len() - 1 - y][z][x])
+            })
+            .collect();
+
+        Self { size, voxels }
+    }
+
+    pub fn get(&self, x: IndexTy, y: IndexTy, z: IndexTy) -> T {
+        let [size_x, _, size_z] = self.size.map(|size| size as usize);
+        self.voxels[(y as usize * size_z + z as usize) * size_x + x as usize]
+    }
+
+    /// Returns the voxel next to the one at `[x, y, z]` in the direction of
+    /// `face`, if it is in the chunk.
+    pub fn neighbor(&self, x: IndexTy, y: IndexTy, z: IndexTy, face: Direction) -> Option<T> {
+        face.translate_pos([x, y, z], 1)
+            .filter(|&[x, y, z]| x < self.size[0] && y < self.size[1] && z < self.size[2])
+            .map(|[x, y, z]| self.get(x, y, z))
+    }
+}
+
+impl<T> VoxelView for &TestChunk<T> {
+    fn size_x(&self) -> IndexTy {
+        self.size[0]
+    }
+
+    fn size_y(&self) -> IndexTy {
+        self.size[1]
+    }
+
+    fn size_z(&self) -> IndexTy {
+        self.size[2]
+    }
+}
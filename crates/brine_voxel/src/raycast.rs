@@ -0,0 +1,312 @@
+//! Casting rays against a voxel world.
+//!
+//! [`raycast`] walks through every voxel that a ray passes through, in order,
+//! using the algorithm from Amanatides & Woo's "A Fast Voxel Traversal
+//! Algorithm for Ray Tracing". It works on block data rather than meshes, so
+//! every block that [`BlockLookup::is_solid`] reports is treated as a full
+//! 1x1x1 cube.
+
+use glam::{IVec3, Vec3};
+
+use crate::{Axis, AxisSign, Direction};
+
+/// Provides the blocks that a ray can hit.
+///
+/// This is implemented by whatever stores the world's blocks. Closures of the
+/// form `Fn(IVec3) -> bool` implement it too.
+pub trait BlockLookup {
+    /// Returns whether the block at the given world block position stops rays.
+    ///
+    /// This should return `false` for blocks that aren't loaded and for blocks
+    /// that can be seen (and reached) through, such as air and water.
+    fn is_solid(&self, block_pos: IVec3) -> bool;
+}
+
+impl<F> BlockLookup for F
+where
+    F: Fn(IVec3) -> bool,
+{
+    #[inline]
+    fn is_solid(&self, block_pos: IVec3) -> bool {
+        self(block_pos)
+    }
+}
+
+/// The first block hit by a ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// World position of the block that was hit.
+    pub block_pos: IVec3,
+
+    /// The face of the block that the ray entered through.
+    pub face: Direction,
+
+    /// Distance from the ray's origin to the point where it hit the block.
+    pub distance: f32,
+}
+
+impl RayHit {
+    /// Returns the position of the block adjacent to the face that was hit,
+    /// i.e., where a block would be placed.
+    pub fn adjacent_pos(&self) -> IVec3 {
+        let offset = match self.face {
+            Direction::XNeg => IVec3::new(-1, 0, 0),
+            Direction::XPos => IVec3::new(1, 0, 0),
+            Direction::YNeg => IVec3::new(0, -1, 0),
+            Direction::YPos => IVec3::new(0, 1, 0),
+            Direction::ZNeg => IVec3::new(0, 0, -1),
+            Direction::ZPos => IVec3::new(0, 0, 1),
+        };
+
+        self.block_pos + offset
+    }
+}
+
+/// Finds the first solid block along a ray, up to `max_dist` away from
+/// `origin`.
+///
+/// `dir` does not need to be normalized. Returns `None` if the ray doesn't hit
+/// anything within `max_dist`, or if `dir` is zero.
+///
+/// If `origin` is inside a solid block, that block is returned with a distance
+/// of zero. Its face is the one facing back along the ray's dominant axis.
+///
+/// A ray that passes exactly through an edge or corner shared by several
+/// blocks visits them one axis at a time, in X, Y, Z order. So if more than one
+/// of them is solid, the first in that order is the one that's hit.
+pub fn raycast(
+    origin: Vec3,
+    dir: Vec3,
+    max_dist: f32,
+    blocks: &impl BlockLookup,
+) -> Option<RayHit> {
+    let dir = dir.normalize_or_zero();
+    if dir == Vec3::ZERO {
+        return None;
+    }
+
+    let mut block_pos = origin.floor().as_ivec3();
+
+    if blocks.is_solid(block_pos) {
+        let axis = dominant_axis(dir);
+
+        return Some(RayHit {
+            block_pos,
+            face: axis.with_sign(sign_of(dir, axis).opposite()),
+            distance: 0.0,
+        });
+    }
+
+    let step = IVec3::select(dir.cmpeq(Vec3::ZERO), IVec3::ZERO, dir.signum().as_ivec3());
+
+    // Distance along the ray between crossings of each axis's voxel
+    // boundaries.
+    let t_delta = dir.recip().abs();
+
+    // Distance along the ray to the first boundary crossing on each axis.
+    let mut t_max = Vec3::ZERO;
+    for axis in Axis::values() {
+        let i = axis as usize;
+        t_max[i] = match step[i] {
+            0 => f32::INFINITY,
+            s if s > 0 => (block_pos[i] as f32 + 1.0 - origin[i]) * t_delta[i],
+            _ => (origin[i] - block_pos[i] as f32) * t_delta[i],
+        };
+    }
+
+    loop {
+        // Ties go to the earliest axis, see above.
+        let axis = if t_max.x <= t_max.y && t_max.x <= t_max.z {
+            Axis::X
+        } else if t_max.y <= t_max.z {
+            Axis::Y
+        } else {
+            Axis::Z
+        };
+        let i = axis as usize;
+
+        let distance = t_max[i];
+        if distance > max_dist {
+            return None;
+        }
+
+        block_pos[i] += step[i];
+        t_max[i] += t_delta[i];
+
+        if blocks.is_solid(block_pos) {
+            // The ray enters through the face pointing back towards it.
+            let sign = if step[i] > 0 {
+                AxisSign::Neg
+            } else {
+                AxisSign::Pos
+            };
+
+            return Some(RayHit {
+                block_pos,
+                face: axis.with_sign(sign),
+                distance,
+            });
+        }
+    }
+}
+
+fn dominant_axis(dir: Vec3) -> Axis {
+    let abs = dir.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        Axis::X
+    } else if abs.y >= abs.z {
+        Axis::Y
+    } else {
+        Axis::Z
+    }
+}
+
+fn sign_of(dir: Vec3, axis: Axis) -> AxisSign {
+    if dir[axis as usize] < 0.0 {
+        AxisSign::Neg
+    } else {
+        AxisSign::Pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(solid: IVec3) -> impl Fn(IVec3) -> bool {
+        move |block_pos| block_pos == solid
+    }
+
+    fn assert_approx_eq(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn hits_block_straight_ahead() {
+        let hit = raycast(
+            Vec3::new(0.5, 0.5, 0.5),
+            Vec3::X,
+            10.0,
+            &only(IVec3::new(3, 0, 0)),
+        )
+        .unwrap();
+
+        assert_eq!(hit.block_pos, IVec3::new(3, 0, 0));
+        assert_eq!(hit.face, Direction::XNeg);
+        assert_approx_eq(hit.distance, 2.5);
+        assert_eq!(hit.adjacent_pos(), IVec3::new(2, 0, 0));
+    }
+
+    #[test]
+    fn misses_beyond_max_distance() {
+        let blocks = only(IVec3::new(3, 0, 0));
+
+        assert!(raycast(Vec3::new(0.5, 0.5, 0.5), Vec3::X, 2.4, &blocks).is_none());
+        assert!(raycast(Vec3::new(0.5, 0.5, 0.5), -Vec3::X, 100.0, &blocks).is_none());
+        assert!(raycast(Vec3::new(0.5, 0.5, 0.5), Vec3::ZERO, 100.0, &blocks).is_none());
+    }
+
+    #[test]
+    fn crosses_chunk_boundaries() {
+        // From chunk (-1, 0) through chunk (0, 0) into chunk (1, 0).
+        let hit = raycast(
+            Vec3::new(-0.5, 15.5, 3.5),
+            Vec3::X,
+            32.0,
+            &only(IVec3::new(16, 15, 3)),
+        )
+        .unwrap();
+        assert_eq!(hit.block_pos, IVec3::new(16, 15, 3));
+        assert_eq!(hit.face, Direction::XNeg);
+        assert_approx_eq(hit.distance, 16.5);
+
+        // Into negative coordinates, through chunk (-1, -1) into (-2, -1).
+        let hit = raycast(
+            Vec3::new(0.5, 70.0, -0.5),
+            -Vec3::X,
+            32.0,
+            &only(IVec3::new(-17, 70, -1)),
+        )
+        .unwrap();
+        assert_eq!(hit.block_pos, IVec3::new(-17, 70, -1));
+        assert_eq!(hit.face, Direction::XPos);
+        assert_approx_eq(hit.distance, 16.5);
+    }
+
+    #[test]
+    fn crosses_section_boundaries() {
+        // Looking down from section 4, through section 3, onto the floor at the
+        // top of section 2.
+        let hit = raycast(
+            Vec3::new(8.5, 70.0, 8.5),
+            -Vec3::Y,
+            64.0,
+            &|block_pos: IVec3| block_pos.y <= 47,
+        )
+        .unwrap();
+
+        assert_eq!(hit.block_pos, IVec3::new(8, 47, 8));
+        assert_eq!(hit.face, Direction::YPos);
+        assert_approx_eq(hit.distance, 22.0);
+    }
+
+    #[test]
+    fn diagonal_ray() {
+        let dir = Vec3::new(1.0, 0.0, 2.0);
+        let hit = raycast(
+            Vec3::new(0.5, 0.5, 0.5),
+            dir,
+            10.0,
+            &only(IVec3::new(1, 0, 2)),
+        )
+        .unwrap();
+
+        // Crosses z = 1 at x = 0.75, then x = 1 at z = 1.5, then z = 2 at
+        // x = 1.25.
+        assert_eq!(hit.block_pos, IVec3::new(1, 0, 2));
+        assert_eq!(hit.face, Direction::ZNeg);
+        assert_approx_eq(hit.distance, 1.5 * dir.length() / 2.0);
+    }
+
+    #[test]
+    fn exact_corner_hits() {
+        // Aimed exactly at the corner of the block at (1, 1, 1).
+        let dir = Vec3::ONE;
+        let hit = raycast(
+            Vec3::new(0.5, 0.5, 0.5),
+            dir,
+            10.0,
+            &only(IVec3::new(1, 1, 1)),
+        )
+        .unwrap();
+
+        assert_eq!(hit.block_pos, IVec3::new(1, 1, 1));
+        assert_eq!(hit.face, Direction::ZNeg);
+        assert_approx_eq(hit.distance, 0.5 * 3f32.sqrt());
+
+        // The blocks that only touch the corner are visited first, in X, Y, Z
+        // order.
+        let hit = raycast(Vec3::new(0.5, 0.5, 0.5), dir, 10.0, &|block_pos: IVec3| {
+            block_pos == IVec3::new(1, 1, 0) || block_pos == IVec3::new(1, 0, 0)
+        })
+        .unwrap();
+
+        assert_eq!(hit.block_pos, IVec3::new(1, 0, 0));
+        assert_eq!(hit.face, Direction::XNeg);
+    }
+
+    #[test]
+    fn origin_inside_block() {
+        let hit = raycast(
+            Vec3::new(2.5, 0.2, -4.5),
+            Vec3::new(0.1, -1.0, 0.0),
+            10.0,
+            &only(IVec3::new(2, 0, -5)),
+        )
+        .unwrap();
+
+        assert_eq!(hit.block_pos, IVec3::new(2, 0, -5));
+        assert_eq!(hit.face, Direction::YPos);
+        assert_eq!(hit.distance, 0.0);
+    }
+}
@@ -1,4 +1,4 @@
-//! Two implementations of chunk builders using algorithms from the `block-mesh` crate.
+//! Implementations of chunk builders using algorithms from the `block-mesh` crate.
 
 use bevy::prelude::*;
 use block_mesh::{
@@ -11,6 +11,7 @@ use brine_chunk::{Chunk, ChunkSection, SECTION_WIDTH};
 
 use crate::{
     chunk_builder::ChunkBuilderType,
+    light::{self, SectionNeighbors},
     mesh::{Axis, VoxelFace, VoxelMesh},
 };
 
@@ -28,12 +29,17 @@ impl VisibleFacesChunkBuilder {
         chunk
             .sections
             .iter()
-            .map(Self::build_chunk_section)
+            .map(|section| {
+                Self::build_chunk_section(section, SectionNeighbors::in_chunk(chunk, section))
+            })
             .collect()
     }
 
-    pub fn build_chunk_section(chunk_section: &ChunkSection) -> VoxelMesh {
-        BlockMeshBuilder::new().build_with(chunk_section, |builder| {
+    pub fn build_chunk_section(
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        BlockMeshBuilder::new().build_with(chunk_section, neighbors, |builder| {
             let mut buffer = UnitQuadBuffer::new();
             block_mesh::visible_block_faces(
                 &builder.voxels[..],
@@ -51,8 +57,12 @@ impl VisibleFacesChunkBuilder {
 impl ChunkBuilder for VisibleFacesChunkBuilder {
     const TYPE: ChunkBuilderType = ChunkBuilderType::VISIBLE_FACES;
 
-    fn build_chunk(&self, chunk: &Chunk) -> Vec<VoxelMesh> {
-        Self::build_chunk(chunk)
+    fn build_chunk_section(
+        &self,
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        Self::build_chunk_section(chunk_section, neighbors)
     }
 }
 
@@ -68,12 +78,17 @@ impl GreedyQuadsChunkBuilder {
         chunk
             .sections
             .iter()
-            .map(Self::build_chunk_section)
+            .map(|section| {
+                Self::build_chunk_section(section, SectionNeighbors::in_chunk(chunk, section))
+            })
             .collect()
     }
 
-    pub fn build_chunk_section(chunk_section: &ChunkSection) -> VoxelMesh {
-        BlockMeshBuilder::new().build_with(chunk_section, |builder| {
+    pub fn build_chunk_section(
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        BlockMeshBuilder::new().build_with(chunk_section, neighbors, |builder| {
             let mut buffer = GreedyQuadsBuffer::new(builder.voxels.len());
             block_mesh::greedy_quads(
                 &builder.voxels[..],
@@ -91,8 +106,197 @@ impl GreedyQuadsChunkBuilder {
 impl ChunkBuilder for GreedyQuadsChunkBuilder {
     const TYPE: ChunkBuilderType = ChunkBuilderType::GREEDY_QUADS;
 
-    fn build_chunk(&self, chunk: &Chunk) -> Vec<VoxelMesh> {
-        Self::build_chunk(chunk)
+    fn build_chunk_section(
+        &self,
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        Self::build_chunk_section(chunk_section, neighbors)
+    }
+}
+
+/// A [`ChunkBuilder`] that builds half-resolution meshes, for chunks that are
+/// far away from the camera.
+///
+/// Each 2x2x2 group of blocks is treated as a single voxel (see
+/// [`Downsampled`]) and the result is meshed with [`greedy_quads`]. Faces are
+/// textured with the group's dominant block.
+///
+/// [`greedy_quads`]: block_mesh::greedy_quads
+#[derive(Default)]
+pub struct Lod2ChunkBuilder;
+
+impl Lod2ChunkBuilder {
+    pub fn build_chunk(chunk: &Chunk) -> Vec<VoxelMesh> {
+        chunk
+            .sections
+            .iter()
+            .map(|section| {
+                Self::build_chunk_section(section, SectionNeighbors::in_chunk(chunk, section))
+            })
+            .collect()
+    }
+
+    pub fn build_chunk_section(
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        let downsampled = Downsampled::new(chunk_section);
+
+        let mut builder = BlockMeshBuilder::new();
+        builder.max = [LOD2_SIDE + 1; 3];
+
+        for (index, block_state) in downsampled.voxels.iter().enumerate() {
+            let [x, y, z] = Downsampled::index_to_xyz(index);
+            let index = builder
+                .shape
+                .linearize([x as u32 + 1, y as u32 + 1, z as u32 + 1]);
+            builder.voxels[index as usize] = BlockState(*block_state);
+        }
+
+        let mut buffer = GreedyQuadsBuffer::new(builder.voxels.len());
+        block_mesh::greedy_quads(
+            &builder.voxels[..],
+            &builder.shape,
+            builder.min,
+            builder.max,
+            &builder.faces,
+            &mut buffer,
+        );
+
+        let mut voxel_mesh = builder.generate_voxel_mesh(BlockMeshOutput::GreedyQuads(buffer));
+
+        for face in voxel_mesh.faces.iter_mut() {
+            for position in face.positions.iter_mut() {
+                *position = position.map(|elt| elt * 2.0);
+            }
+
+            // Light the face by whatever is in front of the group it belongs
+            // to. Then point it at a block with the group's texture.
+            let group = face.voxel;
+            face.voxel = Downsampled::outer_block(group, face.axis);
+            face.light = light::face_light_level(chunk_section, neighbors, face.voxel, face.axis);
+            face.voxel = downsampled.representative(group);
+        }
+
+        voxel_mesh
+    }
+}
+
+impl ChunkBuilder for Lod2ChunkBuilder {
+    const TYPE: ChunkBuilderType = ChunkBuilderType::LOD2;
+
+    fn build_chunk_section(
+        &self,
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        Self::build_chunk_section(chunk_section, neighbors)
+    }
+}
+
+const LOD2_SIDE: u32 = (SECTION_WIDTH as u32) / 2;
+const LOD2_VOLUME: usize = (LOD2_SIDE * LOD2_SIDE * LOD2_SIDE) as usize;
+
+/// A chunk section at half resolution.
+///
+/// Each voxel is the most common non-air block in its 2x2x2 group of blocks,
+/// or air if at least half of the group is air. Ties are broken in favor of
+/// the block that comes first in Y-Z-X order.
+struct Downsampled {
+    voxels: [brine_chunk::BlockState; LOD2_VOLUME],
+    /// For each voxel, the position of a block in the original section that
+    /// has the voxel's block state.
+    representatives: [[u8; 3]; LOD2_VOLUME],
+}
+
+impl Downsampled {
+    fn new(chunk_section: &ChunkSection) -> Self {
+        let mut voxels = [brine_chunk::BlockState::AIR; LOD2_VOLUME];
+        let mut representatives = [[0; 3]; LOD2_VOLUME];
+
+        for (index, (voxel, representative)) in voxels
+            .iter_mut()
+            .zip(representatives.iter_mut())
+            .enumerate()
+        {
+            let [x, y, z] = Self::index_to_xyz(index).map(|elt| elt * 2);
+
+            let group = (0..8u8).map(|i| {
+                let position = [x + (i & 1), y + (i >> 2), z + ((i >> 1) & 1)];
+                let [bx, by, bz] = position;
+                (chunk_section.block_states.get_block(bx, by, bz), position)
+            });
+
+            let mut counts: Vec<(brine_chunk::BlockState, [u8; 3], usize)> = Vec::with_capacity(8);
+            for (block_state, position) in group {
+                if BlockState(block_state).is_empty() {
+                    continue;
+                }
+
+                match counts
+                    .iter_mut()
+                    .find(|(state, _, _)| *state == block_state)
+                {
+                    Some((_, _, count)) => *count += 1,
+                    None => counts.push((block_state, position, 1)),
+                }
+            }
+
+            let non_air: usize = counts.iter().map(|(_, _, count)| count).sum();
+            if non_air <= 4 {
+                *representative = [x, y, z];
+                continue;
+            }
+
+            // `max_by_key` returns the last maximum, so search in reverse to
+            // prefer the first.
+            let (block_state, position, _) = *counts
+                .iter()
+                .rev()
+                .max_by_key(|(_, _, count)| *count)
+                .unwrap();
+
+            *voxel = block_state;
+            *representative = position;
+        }
+
+        Self {
+            voxels,
+            representatives,
+        }
+    }
+
+    #[inline]
+    fn index_to_xyz(index: usize) -> [u8; 3] {
+        let side = LOD2_SIDE as usize;
+        let x = index % side;
+        let z = (index / side) % side;
+        let y = index / (side * side);
+        [x as u8, y as u8, z as u8]
+    }
+
+    #[inline]
+    fn xyz_to_index([x, y, z]: [u8; 3]) -> usize {
+        let side = LOD2_SIDE as usize;
+        (y as usize * side + z as usize) * side + x as usize
+    }
+
+    #[inline]
+    fn representative(&self, voxel: [u8; 3]) -> [u8; 3] {
+        self.representatives[Self::xyz_to_index(voxel)]
+    }
+
+    /// Returns the block on the `axis` side of a voxel's group of blocks.
+    #[inline]
+    fn outer_block(voxel: [u8; 3], axis: Axis) -> [u8; 3] {
+        let mut block = voxel.map(|elt| elt * 2);
+        for (coord, offset) in block.iter_mut().zip(axis.normal()) {
+            if offset > 0 {
+                *coord += 1;
+            }
+        }
+        block
     }
 }
 
@@ -147,7 +351,12 @@ impl BlockMeshBuilder {
         }
     }
 
-    fn build_with<F>(&mut self, chunk_section: &ChunkSection, func: F) -> VoxelMesh
+    fn build_with<F>(
+        &mut self,
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+        func: F,
+    ) -> VoxelMesh
     where
         F: FnOnce(&BlockMeshBuilder) -> BlockMeshOutput,
     {
@@ -160,7 +369,8 @@ impl BlockMeshBuilder {
 
         let output = func(self);
 
-        let voxel_mesh = self.generate_voxel_mesh(output);
+        let mut voxel_mesh = self.generate_voxel_mesh(output);
+        light::light_mesh(&mut voxel_mesh, chunk_section, neighbors);
 
         debug!("built chunk");
 
@@ -189,6 +399,7 @@ impl BlockMeshBuilder {
                 positions,
                 tex_coords,
                 indices,
+                light: light::MAX_LIGHT_LEVEL,
             });
         });
 
@@ -246,3 +457,68 @@ impl BlockMeshOutput {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::{BlockState, BlockStates};
+
+    use super::*;
+
+    fn section_from_fn(block_at: impl Fn(u8, u8, u8) -> BlockState) -> ChunkSection {
+        let mut section = ChunkSection::empty(0);
+        for (i, block) in section.block_states.0.iter_mut().enumerate() {
+            let (x, y, z) = BlockStates::index_to_xyz(i);
+            *block = block_at(x, y, z);
+        }
+        section
+    }
+
+    #[test]
+    fn downsampled_voxels_use_dominant_block() {
+        let section = section_from_fn(|x, y, z| match (x, y, z) {
+            // Group (0, 0, 0): three of stone, two of dirt, three of air.
+            (0, 0, 0) | (1, 0, 0) | (0, 1, 0) => BlockState(1),
+            (0, 0, 1) | (1, 1, 1) => BlockState(2),
+            // Group (1, 0, 0): only four blocks of stone.
+            (2..=3, 0, 0..=1) => BlockState(1),
+            _ => BlockState::AIR,
+        });
+
+        let downsampled = Downsampled::new(&section);
+
+        let first = Downsampled::xyz_to_index([0, 0, 0]);
+        assert_eq!(downsampled.voxels[first], BlockState(1));
+        assert_eq!(downsampled.representative([0, 0, 0]), [0, 0, 0]);
+
+        let second = Downsampled::xyz_to_index([1, 0, 0]);
+        assert_eq!(downsampled.voxels[second], BlockState::AIR);
+    }
+
+    #[test]
+    fn lod2_scales_faces_to_section() {
+        let section = section_from_fn(|_, _, _| BlockState(1));
+
+        let mesh = Lod2ChunkBuilder::build_chunk_section(&section, Default::default());
+
+        assert_eq!(mesh.faces.len(), 6);
+        for face in mesh.faces.iter() {
+            for position in face.positions.iter() {
+                assert!(position.iter().all(|&elt| elt == 0.0 || elt == 16.0));
+            }
+        }
+    }
+
+    #[test]
+    fn lod2_reduces_face_count() {
+        // A bumpy terrain surface, which doesn't merge well at full detail.
+        let section = section_from_fn(|x, y, z| match y <= 4 + (x * 7 + z * 3) % 5 {
+            true => BlockState(1 + ((x + z) % 2) as u32),
+            false => BlockState::AIR,
+        });
+
+        let full = VisibleFacesChunkBuilder::build_chunk_section(&section, Default::default());
+        let lod = Lod2ChunkBuilder::build_chunk_section(&section, Default::default());
+
+        assert!(lod.faces.len() * 4 < full.faces.len());
+    }
+}
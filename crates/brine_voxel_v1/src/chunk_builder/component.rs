@@ -1,8 +1,10 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use bevy::prelude::*;
 
-use crate::mesh::VoxelMesh;
+use brine_asset::TextureKey;
+
+use crate::{mesh::VoxelMesh, occlusion::SectionConnectivity};
 
 use super::ChunkBuilderType;
 
@@ -22,7 +24,24 @@ pub struct PendingChunk {
     pub chunk_data: Option<brine_chunk::Chunk>,
     pub voxel_meshes: Option<Vec<VoxelMesh>>,
 
+    /// Connectivity of each section of the chunk, in the same order as
+    /// [`voxel_meshes`][Self::voxel_meshes]. Sections without one are
+    /// [`OPEN`][SectionConnectivity::OPEN].
+    pub connectivity: Vec<SectionConnectivity>,
+
+    /// How long the [`ChunkBuilder`][super::ChunkBuilder] took to build the
+    /// meshes.
+    pub build_time: Duration,
+
     pub texture_atlases: Option<Vec<PendingMeshAtlas>>,
+
+    /// Texture of each face of each mesh, for chunks that are textured from a
+    /// shared atlas instead of their own [`texture_atlases`][Self::texture_atlases].
+    pub face_texture_keys: Option<Vec<Vec<Option<TextureKey>>>>,
+
+    /// Built chunk entity to despawn once this chunk has been added to the
+    /// world.
+    pub replaces: Option<Entity>,
 }
 
 impl PendingChunk {
@@ -46,6 +65,10 @@ pub struct BuiltChunk {
     pub builder: ChunkBuilderType,
     pub chunk_x: i32,
     pub chunk_z: i32,
+
+    /// How long it took to build the meshes for this chunk, not including
+    /// texture atlas creation.
+    pub build_time: Duration,
 }
 
 impl fmt::Display for BuiltChunk {
@@ -59,6 +82,10 @@ impl fmt::Display for BuiltChunk {
 pub struct BuiltChunkSection {
     pub builder: ChunkBuilderType,
     pub section_y: u8,
+
+    /// Which of the section's faces can see each other, for occlusion culling
+    /// (see [`occlusion`][crate::occlusion]).
+    pub connectivity: SectionConnectivity,
 }
 
 impl fmt::Display for BuiltChunkSection {
@@ -81,6 +108,7 @@ impl BuiltChunkBundle {
             builder,
             chunk_x,
             chunk_z,
+            ..Default::default()
         };
 
         let name = Name::new(built_chunk.to_string());
@@ -108,7 +136,11 @@ pub struct BuiltChunkSectionBundle {
 
 impl BuiltChunkSectionBundle {
     pub fn new(builder: ChunkBuilderType, section_y: u8) -> Self {
-        let built_chunk_section = BuiltChunkSection { builder, section_y };
+        let built_chunk_section = BuiltChunkSection {
+            builder,
+            section_y,
+            ..Default::default()
+        };
 
         let name = Name::new(built_chunk_section.to_string());
 
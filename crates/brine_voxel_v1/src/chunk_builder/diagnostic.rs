@@ -0,0 +1,152 @@
+//! Diagnostics of the chunk building pipeline.
+//!
+//! [`SECTION_BUILD_TIME`] is measured by every [`ChunkBuilderPlugin`] as its
+//! tasks finish. The rest are measured by the [`ChunkBuilderDiagnosticsPlugin`].
+//! The number of built chunk sections is already measured by the
+//! [`ChunkVisibilityPlugin`] ([`TOTAL_SECTIONS`]).
+//!
+//! [`ChunkBuilderPlugin`]: super::ChunkBuilderPlugin
+//! [`ChunkVisibilityPlugin`]: crate::visibility::ChunkVisibilityPlugin
+//! [`TOTAL_SECTIONS`]: crate::visibility::TOTAL_SECTIONS
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+};
+
+use super::{
+    component::{BuiltChunk, PendingChunk},
+    ChunkBuildQueue,
+};
+
+/// Time (in milliseconds) that a chunk builder took to build the mesh of a
+/// single chunk section, measured on the builder's task.
+pub const SECTION_BUILD_TIME: DiagnosticId =
+    DiagnosticId::from_u128(0x4c1e_8b27_d05a_4f93_a6e2_7b38_91d4_0f5c);
+
+/// Number of chunks waiting in the [`ChunkBuildQueue`] for a builder task.
+pub const QUEUED_CHUNKS: DiagnosticId =
+    DiagnosticId::from_u128(0x5a09_e7c3_18d4_4b62_9f31_a8e0_6c7b_d253);
+
+/// Number of chunks whose builder task has been spawned but that haven't been
+/// added to the world yet.
+pub const PENDING_CHUNKS: DiagnosticId =
+    DiagnosticId::from_u128(0xb7d2_3f91_6e0c_4a58_8d14_c2a9_57e3_1b06);
+
+/// Number of built chunks in the world.
+pub const BUILT_CHUNKS: DiagnosticId =
+    DiagnosticId::from_u128(0x0e85_a4c6_2b7f_4d19_93a0_6f5d_e81c_24b7);
+
+/// Total number of vertices in the meshes of built chunks.
+pub const MESH_VERTICES: DiagnosticId =
+    DiagnosticId::from_u128(0x71f3_c90d_84b2_4e6a_b5c7_0d29_3a68_e4f1);
+
+/// Plugin that registers the chunk building diagnostics and measures the
+/// number of queued, pending and built chunks and their vertices.
+pub struct ChunkBuilderDiagnosticsPlugin;
+
+impl Plugin for ChunkBuilderDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_diagnostics);
+        app.add_system_to_stage(CoreStage::Last, measure_chunks);
+    }
+}
+
+fn setup_diagnostics(diagnostics: Option<ResMut<Diagnostics>>) {
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add(Diagnostic::new(
+            SECTION_BUILD_TIME,
+            "section_build_time",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(QUEUED_CHUNKS, "queued_chunks", 20));
+        diagnostics.add(Diagnostic::new(PENDING_CHUNKS, "pending_chunks", 20));
+        diagnostics.add(Diagnostic::new(BUILT_CHUNKS, "built_chunks", 20));
+        diagnostics.add(Diagnostic::new(MESH_VERTICES, "mesh_vertices", 20));
+    }
+}
+
+fn measure_chunks(
+    queue: Option<Res<ChunkBuildQueue>>,
+    pending_chunks: Query<(), With<PendingChunk>>,
+    built_chunks: Query<&Children, With<BuiltChunk>>,
+    mesh_handles: Query<&Handle<Mesh>>,
+    meshes: Res<Assets<Mesh>>,
+    diagnostics: Option<ResMut<Diagnostics>>,
+) {
+    if let Some(mut diagnostics) = diagnostics {
+        let vertices: usize = built_chunks
+            .iter()
+            .flat_map(|children| children.iter())
+            .filter_map(|child| mesh_handles.get(*child).ok())
+            .filter_map(|handle| meshes.get(handle))
+            .map(|mesh| mesh.count_vertices())
+            .sum();
+
+        let queued = queue.map_or(0, |queue| queue.len());
+
+        diagnostics.add_measurement(QUEUED_CHUNKS, queued as f64);
+        diagnostics.add_measurement(PENDING_CHUNKS, pending_chunks.iter().count() as f64);
+        diagnostics.add_measurement(BUILT_CHUNKS, built_chunks.iter().count() as f64);
+        diagnostics.add_measurement(MESH_VERTICES, vertices as f64);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{asset::AssetPlugin, core::CorePlugin};
+
+    use brine_chunk::Chunk;
+
+    use super::*;
+    use crate::chunk_builder::ChunkBuilderType;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .init_resource::<Diagnostics>()
+            .init_resource::<ChunkBuildQueue>()
+            .add_plugin(ChunkBuilderDiagnosticsPlugin);
+        app
+    }
+
+    fn value(app: &App, id: DiagnosticId) -> f64 {
+        app.world
+            .get_resource::<Diagnostics>()
+            .unwrap()
+            .get_measurement(id)
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn chunks_and_vertices_are_counted() {
+        let mut app = app();
+
+        let mesh = app
+            .world
+            .get_resource_mut::<Assets<Mesh>>()
+            .unwrap()
+            .add(Mesh::from(shape::Cube::default()));
+        let section = app.world.spawn().insert(mesh.clone()).id();
+        let other_section = app.world.spawn().insert(mesh).id();
+        app.world
+            .spawn()
+            .insert(BuiltChunk::default())
+            .push_children(&[section, other_section]);
+        app.world.spawn().insert(PendingChunk::default());
+        app.world
+            .get_resource_mut::<ChunkBuildQueue>()
+            .unwrap()
+            .push(ChunkBuilderType::UNKNOWN, Chunk::empty(0, 0), None);
+
+        app.update();
+
+        assert_eq!(value(&app, QUEUED_CHUNKS), 1.0);
+        assert_eq!(value(&app, PENDING_CHUNKS), 1.0);
+        assert_eq!(value(&app, BUILT_CHUNKS), 1.0);
+        assert_eq!(value(&app, MESH_VERTICES), 48.0);
+    }
+}
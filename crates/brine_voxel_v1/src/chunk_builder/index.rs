@@ -0,0 +1,381 @@
+//! Looking up the entities of built chunks by their coordinates.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use brine_proto::event::clientbound::{Dimension, JoinGame, Respawn};
+
+use crate::chunk_builder::{
+    component::{BuiltChunk, PendingChunk},
+    ChunkBuildQueue, ChunkBuilderType,
+};
+
+type ChunkKey = (ChunkBuilderType, i32, i32);
+
+/// Resource that maps chunk coordinates to the entities of built chunks and
+/// their sections.
+///
+/// The index is maintained by every [`ChunkBuilderPlugin`]. Since multiple
+/// builders can be active at once, chunks are keyed by the
+/// [`ChunkBuilderType`] that built them as well as their coordinates.
+///
+/// When a chunk is built again (e.g., because the server sent it again), the
+/// previous entity is despawned and replaced in the index. Chunks whose
+/// [`BuiltChunk`] entity is despawned are removed from the index at the end of
+/// the frame.
+///
+/// [`ChunkBuilderPlugin`]: super::ChunkBuilderPlugin
+#[derive(Debug, Default)]
+pub struct ChunkIndex {
+    chunks: HashMap<ChunkKey, IndexedChunk>,
+    entities: HashMap<Entity, ChunkKey>,
+}
+
+#[derive(Debug)]
+struct IndexedChunk {
+    entity: Entity,
+    sections: HashMap<u8, Entity>,
+}
+
+impl ChunkIndex {
+    /// Returns the entity of the chunk at the given chunk coordinates, built by
+    /// the given builder.
+    pub fn get_chunk(
+        &self,
+        builder: ChunkBuilderType,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Option<Entity> {
+        self.chunks
+            .get(&(builder, chunk_x, chunk_z))
+            .map(|chunk| chunk.entity)
+    }
+
+    /// Returns the entity of the chunk section at the given chunk coordinates,
+    /// built by the given builder.
+    pub fn get_section(
+        &self,
+        builder: ChunkBuilderType,
+        chunk_x: i32,
+        section_y: u8,
+        chunk_z: i32,
+    ) -> Option<Entity> {
+        self.chunks
+            .get(&(builder, chunk_x, chunk_z))?
+            .sections
+            .get(&section_y)
+            .copied()
+    }
+
+    /// Iterates over all chunks in the index, yielding the builder, chunk
+    /// coordinates, and entity of each.
+    pub fn iter_loaded(&self) -> impl Iterator<Item = (ChunkBuilderType, i32, i32, Entity)> + '_ {
+        self.chunks
+            .iter()
+            .map(|(&(builder, chunk_x, chunk_z), chunk)| (builder, chunk_x, chunk_z, chunk.entity))
+    }
+
+    /// Returns the number of chunks in the index.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Adds a chunk and its sections (given as `(section_y, entity)` pairs) to
+    /// the index.
+    ///
+    /// Returns the entity of the chunk that was previously at the same
+    /// coordinates, if any. The caller is responsible for despawning it.
+    pub fn insert(
+        &mut self,
+        builder: ChunkBuilderType,
+        chunk_x: i32,
+        chunk_z: i32,
+        entity: Entity,
+        sections: impl IntoIterator<Item = (u8, Entity)>,
+    ) -> Option<Entity> {
+        let key = (builder, chunk_x, chunk_z);
+        let chunk = IndexedChunk {
+            entity,
+            sections: sections.into_iter().collect(),
+        };
+
+        self.entities.insert(entity, key);
+        let replaced = self.chunks.insert(key, chunk)?.entity;
+        self.entities.remove(&replaced);
+
+        Some(replaced)
+    }
+
+    /// Removes every chunk from the index.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.entities.clear();
+    }
+
+    /// Removes the chunk with the given entity from the index.
+    ///
+    /// Returns `false` if the entity is not in the index, e.g., because it has
+    /// already been replaced.
+    pub fn remove_entity(&mut self, entity: Entity) -> bool {
+        match self.entities.remove(&entity) {
+            Some(key) => {
+                self.chunks.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// System that removes despawned chunks from the [`ChunkIndex`].
+pub(crate) fn remove_unloaded_chunks(
+    removed: RemovedComponents<BuiltChunk>,
+    mut chunk_index: ResMut<ChunkIndex>,
+) {
+    for entity in removed.iter() {
+        chunk_index.remove_entity(entity);
+    }
+}
+
+/// System that despawns every built and pending chunk, and empties the
+/// [`ChunkIndex`] and [`ChunkBuildQueue`], when the player moves to a
+/// different dimension.
+pub(crate) fn unload_chunks_on_dimension_change(
+    mut join_events: EventReader<JoinGame>,
+    mut respawn_events: EventReader<Respawn>,
+    mut dimension: Local<Option<Dimension>>,
+    built_chunks: Query<Entity, With<BuiltChunk>>,
+    pending_chunks: Query<Entity, With<PendingChunk>>,
+    mut chunk_index: ResMut<ChunkIndex>,
+    mut build_queue: ResMut<ChunkBuildQueue>,
+    mut commands: Commands,
+) {
+    let new_dimensions = join_events
+        .iter()
+        .map(|join| join.dimension)
+        .chain(respawn_events.iter().map(|respawn| respawn.dimension));
+
+    let mut changed = false;
+    for new_dimension in new_dimensions {
+        if dimension.map_or(false, |dimension| dimension != new_dimension) {
+            changed = true;
+        }
+        *dimension = Some(new_dimension);
+    }
+
+    if !changed {
+        return;
+    }
+
+    debug!(
+        "Dimension changed to {:?}, unloading all chunks",
+        dimension.unwrap()
+    );
+
+    for entity in built_chunks.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    // Dropping the builder tasks cancels them.
+    for entity in pending_chunks.iter() {
+        commands.entity(entity).despawn();
+    }
+    chunk_index.clear();
+    build_queue.clear();
+}
+
+#[cfg(test)]
+mod test {
+    use brine_proto::event::clientbound::GameMode;
+
+    use super::*;
+
+    const BUILDER: ChunkBuilderType = ChunkBuilderType::VISIBLE_FACES;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(brine_proto::ProtocolPlugin);
+        app.init_resource::<ChunkIndex>();
+        app.init_resource::<ChunkBuildQueue>();
+        app.add_system(unload_chunks_on_dimension_change);
+        app.add_system_to_stage(CoreStage::PostUpdate, remove_unloaded_chunks);
+        app
+    }
+
+    fn spawn_chunk(app: &mut App, builder: ChunkBuilderType, chunk_x: i32, chunk_z: i32) -> Entity {
+        let world = &mut app.world;
+
+        let sections: Vec<(u8, Entity)> = (0..2)
+            .map(|section_y| (section_y, world.spawn().id()))
+            .collect();
+        let entity = world
+            .spawn()
+            .insert(BuiltChunk {
+                builder,
+                chunk_x,
+                chunk_z,
+                ..Default::default()
+            })
+            .id();
+
+        let replaced = world
+            .get_resource_mut::<ChunkIndex>()
+            .unwrap()
+            .insert(builder, chunk_x, chunk_z, entity, sections);
+        if let Some(replaced) = replaced {
+            world.despawn(replaced);
+        }
+
+        entity
+    }
+
+    fn index(app: &App) -> &ChunkIndex {
+        app.world.get_resource::<ChunkIndex>().unwrap()
+    }
+
+    #[test]
+    fn insert() {
+        let mut app = app();
+        let chunk = spawn_chunk(&mut app, BUILDER, 1, -2);
+        app.update();
+
+        let index = index(&app);
+        assert_eq!(index.get_chunk(BUILDER, 1, -2), Some(chunk));
+        assert_eq!(index.get_chunk(BUILDER, -2, 1), None);
+        assert!(index.get_section(BUILDER, 1, 1, -2).is_some());
+        assert_eq!(index.get_section(BUILDER, 1, 2, -2), None);
+        assert_eq!(
+            index.iter_loaded().collect::<Vec<_>>(),
+            vec![(BUILDER, 1, -2, chunk)]
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let mut app = app();
+        let first = spawn_chunk(&mut app, BUILDER, 0, 0);
+        app.update();
+        let second = spawn_chunk(&mut app, BUILDER, 0, 0);
+        app.update();
+
+        let index = index(&app);
+        assert_ne!(first, second);
+        assert_eq!(index.get_chunk(BUILDER, 0, 0), Some(second));
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn unload() {
+        let mut app = app();
+        let chunk = spawn_chunk(&mut app, BUILDER, 0, 0);
+        let other = spawn_chunk(&mut app, BUILDER, 0, 1);
+        app.update();
+
+        app.world.despawn(chunk);
+        app.update();
+
+        let index = index(&app);
+        assert_eq!(index.get_chunk(BUILDER, 0, 0), None);
+        assert_eq!(index.get_section(BUILDER, 0, 0, 0), None);
+        assert_eq!(index.get_chunk(BUILDER, 0, 1), Some(other));
+    }
+
+    #[test]
+    fn builders_are_separate() {
+        let mut app = app();
+        let visible_faces = spawn_chunk(&mut app, BUILDER, 0, 0);
+        let greedy = spawn_chunk(&mut app, ChunkBuilderType::GREEDY_QUADS, 0, 0);
+        app.update();
+
+        let index = index(&app);
+        assert_eq!(index.get_chunk(BUILDER, 0, 0), Some(visible_faces));
+        assert_eq!(
+            index.get_chunk(ChunkBuilderType::GREEDY_QUADS, 0, 0),
+            Some(greedy)
+        );
+        assert_eq!(index.len(), 2);
+    }
+
+    fn send<T: Send + Sync + 'static>(app: &mut App, event: T) {
+        app.world
+            .get_resource_mut::<bevy::ecs::event::Events<T>>()
+            .unwrap()
+            .send(event);
+    }
+
+    fn join_game(dimension: Dimension) -> JoinGame {
+        JoinGame {
+            entity_id: 42,
+            gamemode: GameMode::Survival,
+            dimension,
+            max_players: 20,
+            level_type: String::from("default"),
+        }
+    }
+
+    fn respawn(dimension: Dimension) -> Respawn {
+        Respawn {
+            dimension,
+            gamemode: GameMode::Survival,
+        }
+    }
+
+    fn chunk_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), Or<(With<BuiltChunk>, With<PendingChunk>)>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn changing_dimension_unloads_chunks() {
+        let mut app = app();
+        send(&mut app, join_game(Dimension::Overworld));
+        app.update();
+
+        // Chunks sent by the server are either built or still being built.
+        spawn_chunk(&mut app, BUILDER, 0, 0);
+        spawn_chunk(&mut app, BUILDER, 0, 1);
+        app.world.spawn().insert(PendingChunk::new(BUILDER));
+        app.world
+            .get_resource_mut::<ChunkBuildQueue>()
+            .unwrap()
+            .push(BUILDER, brine_chunk::Chunk::empty(1, 1), None);
+        app.update();
+        assert_eq!(chunk_count(&mut app), 3);
+
+        send(&mut app, respawn(Dimension::Nether));
+        app.update();
+
+        assert_eq!(chunk_count(&mut app), 0);
+        assert!(index(&app).is_empty());
+        assert!(app
+            .world
+            .get_resource::<ChunkBuildQueue>()
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            app.world
+                .get_resource::<brine_proto::LocalPlayer>()
+                .unwrap()
+                .dimension,
+            Some(Dimension::Nether)
+        );
+    }
+
+    #[test]
+    fn respawning_in_the_same_dimension_keeps_chunks() {
+        let mut app = app();
+        send(&mut app, join_game(Dimension::Overworld));
+        spawn_chunk(&mut app, BUILDER, 0, 0);
+        app.update();
+
+        send(&mut app, respawn(Dimension::Overworld));
+        app.update();
+
+        assert_eq!(chunk_count(&mut app), 1);
+        assert_eq!(index(&app).len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Materials shared by every chunk that is textured from the atlas of all
+//! Minecraft textures (see [`ChunkBuilderPlugin::with_shared_atlas`]).
+//!
+//! [`ChunkBuilderPlugin::with_shared_atlas`]: super::ChunkBuilderPlugin::with_shared_atlas
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+    sprite::Rect,
+    utils::HashSet,
+};
+
+use brine_asset::{api::BlockFace, MinecraftAssets, TextureKey};
+use brine_chunk::ChunkSection;
+use brine_data::BlockStateId;
+use brine_render::texture::{MinecraftTextureAtlas, TextureAtlas};
+
+use crate::{material::VoxelMaterial, mesh::VoxelMesh};
+
+use super::component::BuiltChunk;
+
+/// Number of distinct materials used by built chunks last frame.
+pub const CHUNK_MATERIALS: DiagnosticId =
+    DiagnosticId::from_u128(0x93c1_7e52_0af4_4d2b_b86e_51d0_2c9f_7a36);
+
+/// Resource holding the materials that chunks built with a shared atlas are
+/// drawn with, one for each kind of shading.
+///
+/// The resource exists from the moment the plugin is added, but the materials
+/// are only created once the atlas has been stitched, i.e., on entering
+/// [`MinecraftTexturesState::Loaded`]. Until then, built chunks wait to be
+/// added to the world.
+///
+/// [`MinecraftTexturesState::Loaded`]: brine_render::texture::MinecraftTexturesState::Loaded
+#[derive(Debug, Default)]
+pub struct ChunkMaterials {
+    materials: Option<SharedMaterials>,
+}
+
+#[derive(Debug)]
+struct SharedMaterials {
+    atlas: Handle<TextureAtlas>,
+    lit: Handle<VoxelMaterial>,
+    unlit: Handle<VoxelMaterial>,
+}
+
+impl ChunkMaterials {
+    /// Creates the materials for the given atlas.
+    pub fn new(
+        atlas_handle: Handle<TextureAtlas>,
+        atlas: &TextureAtlas,
+        materials: &mut Assets<VoxelMaterial>,
+    ) -> Self {
+        let mut add_material = |lighting: bool| {
+            materials.add(VoxelMaterial {
+                texture: atlas.texture.clone(),
+                lit: lighting,
+            })
+        };
+
+        Self {
+            materials: Some(SharedMaterials {
+                atlas: atlas_handle,
+                lit: add_material(true),
+                unlit: add_material(false),
+            }),
+        }
+    }
+
+    /// Returns whether the materials have been created yet.
+    pub fn is_ready(&self) -> bool {
+        self.materials.is_some()
+    }
+
+    /// Returns the atlas that the materials draw from.
+    pub fn atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        self.materials.as_ref().map(|materials| &materials.atlas)
+    }
+
+    /// Returns the material for chunks that are (or aren't) shaded by the
+    /// lights in the world.
+    pub fn get(&self, lighting: bool) -> Option<Handle<VoxelMaterial>> {
+        self.materials.as_ref().map(|materials| {
+            if lighting {
+                materials.lit.clone()
+            } else {
+                materials.unlit.clone()
+            }
+        })
+    }
+}
+
+/// Returns the texture of each face in the mesh, which was built from the
+/// given chunk section, or `None` for faces whose block has no texture.
+pub(crate) fn face_texture_keys(
+    mesh: &VoxelMesh,
+    chunk_section: &ChunkSection,
+    mc_assets: &MinecraftAssets,
+) -> Vec<Option<TextureKey>> {
+    mesh.faces
+        .iter()
+        .map(|face| {
+            let [x, y, z] = face.voxel;
+            let block_state_id = chunk_section.get_block((x, y, z)).ok()?;
+            let block_state_id = BlockStateId(block_state_id.0 as u16);
+
+            mc_assets
+                .get_quads_for_face(block_state_id, BlockFace::from(face.axis))
+                .next()
+                .map(|quad| quad.texture)
+        })
+        .collect()
+}
+
+/// Returns the region of the atlas that each face's texture occupies.
+pub(crate) fn face_regions(
+    atlas: &TextureAtlas,
+    face_textures: &[Option<TextureKey>],
+) -> Vec<Rect> {
+    face_textures
+        .iter()
+        .map(|texture| match texture {
+            Some(texture) => atlas.get_uv(*texture),
+            None => atlas.placeholder_region,
+        })
+        .collect()
+}
+
+pub(crate) fn create_chunk_materials(
+    mc_atlas: Res<MinecraftTextureAtlas>,
+    atlases: Res<Assets<TextureAtlas>>,
+    mut materials: ResMut<Assets<VoxelMaterial>>,
+    mut chunk_materials: ResMut<ChunkMaterials>,
+) {
+    if chunk_materials.is_ready() {
+        return;
+    }
+
+    let atlas = atlases
+        .get(&mc_atlas.handle)
+        .expect("atlas is stitched once textures are loaded");
+
+    debug!("Creating shared chunk materials");
+
+    *chunk_materials = ChunkMaterials::new(mc_atlas.handle.clone(), atlas, &mut *materials);
+}
+
+/// Returns the number of distinct materials among the given handles.
+pub fn count_distinct_materials<'a>(
+    materials: impl IntoIterator<Item = &'a Handle<VoxelMaterial>>,
+) -> usize {
+    materials.into_iter().collect::<HashSet<_>>().len()
+}
+
+/// Plugin that reports the number of distinct materials that built chunks are
+/// drawn with as a diagnostic ([`CHUNK_MATERIALS`]).
+///
+/// With a shared atlas, the number should stay the same no matter how many
+/// chunks are loaded.
+pub struct ChunkMaterialDiagnosticsPlugin;
+
+impl Plugin for ChunkMaterialDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_diagnostics);
+        app.add_system(measure_chunk_materials);
+    }
+}
+
+fn setup_diagnostics(diagnostics: Option<ResMut<Diagnostics>>) {
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add(Diagnostic::new(CHUNK_MATERIALS, "chunk_materials", 20));
+    }
+}
+
+fn measure_chunk_materials(
+    chunks: Query<&Children, With<BuiltChunk>>,
+    materials: Query<&Handle<VoxelMaterial>>,
+    diagnostics: Option<ResMut<Diagnostics>>,
+) {
+    if let Some(mut diagnostics) = diagnostics {
+        let chunk_materials = chunks
+            .iter()
+            .flat_map(|children| children.iter())
+            .filter_map(|child| materials.get(*child).ok());
+
+        diagnostics.add_measurement(
+            CHUNK_MATERIALS,
+            count_distinct_materials(chunk_materials) as f64,
+        );
+    }
+}
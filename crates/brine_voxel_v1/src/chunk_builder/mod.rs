@@ -6,24 +6,53 @@
 
 use std::fmt;
 
-use brine_chunk::Chunk;
+use brine_chunk::{Chunk, ChunkSection};
 
 mod block_mesh;
 pub mod component;
+pub mod diagnostic;
+mod index;
+pub mod material;
 mod naive_blocks;
+mod origin;
 mod plugin;
+mod queue;
+mod relight;
+mod stats;
 
-use crate::mesh::VoxelMesh;
+use crate::{light::SectionNeighbors, mesh::VoxelMesh};
 
-pub use self::block_mesh::{GreedyQuadsChunkBuilder, VisibleFacesChunkBuilder};
+pub use self::block_mesh::{GreedyQuadsChunkBuilder, Lod2ChunkBuilder, VisibleFacesChunkBuilder};
+pub use index::ChunkIndex;
 pub use naive_blocks::NaiveBlocksChunkBuilder;
-pub use plugin::ChunkBuilderPlugin;
+pub use origin::{ChunkOrigin, ManualPlacement};
+pub use plugin::{ChunkBuilderPlugin, CombineSections, RebuildChunk};
+pub use queue::{ChunkBuildQueue, ChunkBuildSettings};
+pub use stats::{benchmark_builder, MeshStats};
 
 /// A trait for types that can turn a [`Chunk`] into [`VoxelMesh`]es.
 pub trait ChunkBuilder: Sized {
     const TYPE: ChunkBuilderType;
 
-    fn build_chunk(&self, chunk: &Chunk) -> Vec<VoxelMesh>;
+    /// Builds the mesh of a single chunk section, whose faces on the top and
+    /// bottom of the section are lit by its `neighbors`.
+    fn build_chunk_section(
+        &self,
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh;
+
+    /// Builds one mesh for each section of the chunk, in order.
+    fn build_chunk(&self, chunk: &Chunk) -> Vec<VoxelMesh> {
+        chunk
+            .sections
+            .iter()
+            .map(|chunk_section| {
+                let neighbors = SectionNeighbors::in_chunk(chunk, chunk_section);
+                self.build_chunk_section(chunk_section, neighbors)
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -34,6 +63,7 @@ impl ChunkBuilderType {
     pub const GREEDY_QUADS: Self = Self("GreedyQuadsChunkBuilder");
     pub const VISIBLE_FACES: Self = Self("VisibleFacesChunkBuilder");
     pub const NAIVE_BLOCKS: Self = Self("NaiveBlocksChunkBuilder");
+    pub const LOD2: Self = Self("Lod2ChunkBuilder");
 }
 
 impl Default for ChunkBuilderType {
@@ -7,7 +7,10 @@ use bevy::{
 
 use brine_chunk::{BlockState, Chunk, ChunkSection};
 
-use crate::mesh::{Axis, VoxelFace, VoxelMesh};
+use crate::{
+    light::{self, SectionNeighbors},
+    mesh::{Axis, VoxelFace, VoxelMesh},
+};
 
 use super::{ChunkBuilder, ChunkBuilderType};
 
@@ -20,11 +23,13 @@ impl NaiveBlocksChunkBuilder {
         chunk
             .sections
             .iter()
-            .map(Self::build_chunk_section)
+            .map(|section| {
+                Self::build_chunk_section(section, SectionNeighbors::in_chunk(chunk, section))
+            })
             .collect()
     }
 
-    pub fn build_chunk_section(section: &ChunkSection) -> VoxelMesh {
+    pub fn build_chunk_section(section: &ChunkSection, neighbors: SectionNeighbors) -> VoxelMesh {
         let num_blocks = section.block_count as usize;
         let num_faces = num_blocks * 6;
         let mut faces = Vec::with_capacity(num_faces);
@@ -35,7 +40,9 @@ impl NaiveBlocksChunkBuilder {
             }
         }
 
-        VoxelMesh { faces }
+        let mut mesh = VoxelMesh { faces };
+        light::light_mesh(&mut mesh, section, neighbors);
+        mesh
     }
 
     fn build_voxel(x: u8, y: u8, z: u8, faces: &mut Vec<VoxelFace>) {
@@ -73,6 +80,7 @@ impl NaiveBlocksChunkBuilder {
                     positions,
                     tex_coords,
                     indices: indices.map(|i| (i as usize - vertex_index) as u8),
+                    light: light::MAX_LIGHT_LEVEL,
                 });
             }
         } else {
@@ -112,7 +120,11 @@ impl NaiveBlocksChunkBuilder {
 impl ChunkBuilder for NaiveBlocksChunkBuilder {
     const TYPE: ChunkBuilderType = ChunkBuilderType::NAIVE_BLOCKS;
 
-    fn build_chunk(&self, chunk: &Chunk) -> Vec<VoxelMesh> {
-        Self::build_chunk(chunk)
+    fn build_chunk_section(
+        &self,
+        chunk_section: &ChunkSection,
+        neighbors: SectionNeighbors,
+    ) -> VoxelMesh {
+        Self::build_chunk_section(chunk_section, neighbors)
     }
 }
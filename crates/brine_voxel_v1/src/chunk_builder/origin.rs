@@ -0,0 +1,169 @@
+//! Placing built chunks relative to a moving origin.
+//!
+//! Chunk coordinates are absolute, so in a long session far away from the
+//! world's origin, the translations of built chunks grow large enough that
+//! `f32` loses precision and meshes start to jitter. Placing chunks relative
+//! to a [`ChunkOrigin`] that follows the player keeps them near zero in world
+//! space.
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use brine_chunk::{CHUNK_WIDTH, SECTION_HEIGHT};
+
+use super::component::{BuiltChunk, BuiltChunkSection};
+
+/// Resource that holds the coordinates of the chunk that is placed at the
+/// origin of world space.
+///
+/// Every built chunk is translated by its chunk coordinates minus the origin's
+/// (see [`translation`][Self::translation]). The subtraction happens on the
+/// integer coordinates, so there's no loss of precision no matter how far the
+/// origin is from the world's.
+///
+/// The origin defaults to chunk (0, 0). Whatever moves it (e.g., to the
+/// player's current chunk) is responsible for moving everything else in world
+/// space, like the camera, by the same amount.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOrigin {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+}
+
+impl ChunkOrigin {
+    pub fn new(chunk_x: i32, chunk_z: i32) -> Self {
+        Self { chunk_x, chunk_z }
+    }
+
+    /// Returns the translation of the chunk at the given chunk coordinates,
+    /// relative to the origin.
+    pub fn translation(&self, chunk_x: i32, chunk_z: i32) -> Vec3 {
+        Vec3::new(
+            ((chunk_x - self.chunk_x) * CHUNK_WIDTH as i32) as f32,
+            0.0,
+            ((chunk_z - self.chunk_z) * CHUNK_WIDTH as i32) as f32,
+        )
+    }
+}
+
+/// Component that opts a built chunk out of being placed relative to the
+/// [`ChunkOrigin`], for apps that position chunks themselves (e.g., to show
+/// the output of several builders side by side).
+///
+/// The chunk's sections are still kept at their heights in the chunk.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct ManualPlacement;
+
+/// System that keeps the translation of every built chunk relative to the
+/// [`ChunkOrigin`], and every built chunk section at its height in the chunk.
+///
+/// Chunks with the [`ManualPlacement`] component are left where they are.
+pub(crate) fn place_built_chunks(
+    origin: Res<ChunkOrigin>,
+    mut chunks: Query<(&BuiltChunk, &mut Transform), Without<ManualPlacement>>,
+    mut sections: Query<(&BuiltChunkSection, &mut Transform), Without<BuiltChunk>>,
+) {
+    for (built_chunk, mut transform) in chunks.iter_mut() {
+        let translation = origin.translation(built_chunk.chunk_x, built_chunk.chunk_z);
+
+        // Avoid triggering change detection when nothing changed.
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+
+    for (section, mut transform) in sections.iter_mut() {
+        let height = (section.section_y as usize * SECTION_HEIGHT) as f32;
+        if transform.translation.y != height {
+            transform.translation.y = height;
+        }
+    }
+}
+
+pub(crate) fn add_systems(app: &mut App) {
+    app.init_resource::<ChunkOrigin>();
+    app.add_system_to_stage(
+        CoreStage::PostUpdate,
+        place_built_chunks.before(TransformSystem::TransformPropagate),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chunk_builder::{
+        component::{BuiltChunkBundle, BuiltChunkSectionBundle},
+        ChunkBuilderType,
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        add_systems(&mut app);
+        app
+    }
+
+    fn spawn_chunk(app: &mut App, chunk_x: i32, chunk_z: i32) -> (Entity, Entity) {
+        let section = app
+            .world
+            .spawn()
+            .insert_bundle(BuiltChunkSectionBundle::new(ChunkBuilderType::UNKNOWN, 5))
+            .id();
+        let chunk = app
+            .world
+            .spawn()
+            .insert_bundle(BuiltChunkBundle::new(
+                ChunkBuilderType::UNKNOWN,
+                chunk_x,
+                chunk_z,
+            ))
+            .push_children(&[section])
+            .id();
+        (chunk, section)
+    }
+
+    fn translation(app: &App, entity: Entity) -> Vec3 {
+        app.world.get::<Transform>(entity).unwrap().translation
+    }
+
+    #[test]
+    fn chunk_at_origin_is_near_zero() {
+        let mut app = app();
+        app.insert_resource(ChunkOrigin::new(1000, 1000));
+        let (chunk, section) = spawn_chunk(&mut app, 1000, 1000);
+
+        app.update();
+
+        assert!(translation(&app, chunk).length() < 1.0);
+        assert_eq!(translation(&app, section), Vec3::new(0.0, 80.0, 0.0));
+    }
+
+    #[test]
+    fn chunks_follow_origin() {
+        let mut app = app();
+        let (chunk, _) = spawn_chunk(&mut app, 1001, 998);
+
+        app.update();
+        assert_eq!(translation(&app, chunk), Vec3::new(16016.0, 0.0, 15968.0));
+
+        app.insert_resource(ChunkOrigin::new(1000, 1000));
+        app.update();
+        assert_eq!(translation(&app, chunk), Vec3::new(16.0, 0.0, -32.0));
+    }
+
+    #[test]
+    fn manually_placed_chunks_stay_put() {
+        let mut app = app();
+        app.insert_resource(ChunkOrigin::new(1000, 1000));
+        let (chunk, section) = spawn_chunk(&mut app, 3, 4);
+        app.world
+            .entity_mut(chunk)
+            .insert(ManualPlacement)
+            .get_mut::<Transform>()
+            .unwrap()
+            .translation = Vec3::new(50.0, 0.0, 0.0);
+
+        app.update();
+
+        assert_eq!(translation(&app, chunk), Vec3::new(50.0, 0.0, 0.0));
+        assert_eq!(translation(&app, section), Vec3::new(0.0, 80.0, 0.0));
+    }
+}
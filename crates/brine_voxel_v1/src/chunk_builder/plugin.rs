@@ -1,29 +1,45 @@
 use std::collections::hash_map::Entry;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{any::Any, marker::PhantomData};
 
 use bevy::tasks::Task;
 use bevy::utils::{HashMap, HashSet};
-use bevy::{ecs::event::Events, prelude::*, tasks::AsyncComputeTaskPool};
+use bevy::{
+    diagnostic::Diagnostics, ecs::event::Events, pbr::MaterialMeshBundle, prelude::*,
+    render::camera::Camera, tasks::AsyncComputeTaskPool,
+};
 use futures_lite::future;
 
-use brine_asset::{api::BlockFace, MinecraftAssets};
-use brine_chunk::ChunkSection;
-use brine_data::BlockStateId;
+use brine_asset::{api::BlockFace, MinecraftAssets, TextureKey};
+use brine_chunk::{BlockState, ChunkSection};
+use brine_data::{BlockStateId, MinecraftData};
 use brine_proto::event;
+use brine_render::texture::{MinecraftTexturesState, TextureAtlas as SharedAtlas};
 
 use crate::chunk_builder::component::PendingChunk;
-use crate::mesh::VoxelMesh;
+use crate::light::{self, SectionNeighbors};
+use crate::material::{VoxelMaterial, VoxelMaterialPlugin};
+use crate::mesh::{RenderMeshOptions, VoxelMesh};
+use crate::occlusion::{self, SectionConnectivity};
 use crate::texture::BlockTextures;
 
 use super::component::{ChunkSection as ChunkSectionComponent, PendingMeshAtlas};
+use super::diagnostic::SECTION_BUILD_TIME;
+use super::index::{remove_unloaded_chunks, unload_chunks_on_dimension_change, ChunkIndex};
+use super::material::{self, create_chunk_materials, ChunkMaterials};
+use super::origin::{self, ChunkOrigin};
+use super::queue::{self, ChunkBuildQueue, ChunkBuildSettings};
+use super::relight::rebuild_relit_chunks;
 
 use super::{
     component::{BuiltChunkBundle, BuiltChunkSectionBundle},
-    ChunkBuilder,
+    ChunkBuilder, ChunkBuilderType,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
 pub enum System {
+    BuilderTaskQueue,
     BuilderTaskSpawn,
     BuilderResultAddToWorld,
 }
@@ -34,12 +50,100 @@ pub enum System {
 /// and spawns a task to run a particular [`ChunkBuilder`]. When the task
 /// completes, the plugin adds the result to the game world.
 ///
+/// Built chunks are recorded in the [`ChunkIndex`] resource. If a chunk is
+/// built again, the previously built chunk is despawned. When the player moves
+/// to a different dimension, every built and pending chunk is despawned.
+///
+/// Built chunks are placed relative to the [`ChunkOrigin`] resource, which
+/// can be moved to keep nearby chunks close to the origin of world space.
+/// Chunks with the [`ManualPlacement`][super::ManualPlacement] component are
+/// left wherever the app puts them.
+///
+/// The plugin also builds chunks requested through [`RebuildChunk`] events
+/// that name its builder, and rebuilds its chunks when the server sends
+/// [`LightUpdated`] events for them.
+///
+/// Chunks wait in the [`ChunkBuildQueue`] until there's room for another task,
+/// and the ones nearest to the camera are built first. See
+/// [`ChunkBuildSettings`] for the limits on tasks in flight and on the time
+/// spent adding built chunks to the world each frame.
+///
+/// By default, each section of a built chunk is its own entity with its own
+/// mesh and material. See [`with_combine_sections`] to draw each chunk with a
+/// single mesh instead.
+///
+/// By default, each mesh also gets its own texture atlas, made of only the
+/// textures that it needs, and thus its own material. See
+/// [`with_shared_atlas`] to texture every mesh from one atlas and share its
+/// materials instead.
+///
+/// Chunks are drawn with a [`VoxelMaterial`], which darkens each face by the
+/// light level of the block in front of it (see [`light`]). Chunk materials
+/// are otherwise unlit by default, so that chunks are visible without any
+/// lights in the world. See [`with_lighting`] to also shade them with the
+/// world's lights.
+///
 /// [`ChunkData`]: brine_proto::event::clientbound::ChunkData
+/// [`LightUpdated`]: brine_proto::event::clientbound::LightUpdated
+/// [`with_combine_sections`]: ChunkBuilderPlugin::with_combine_sections
+/// [`with_lighting`]: ChunkBuilderPlugin::with_lighting
+/// [`with_shared_atlas`]: ChunkBuilderPlugin::with_shared_atlas
 pub struct ChunkBuilderPlugin<T: ChunkBuilder> {
     shared: bool,
+    chunk_events: bool,
+    combine_sections: CombineSections,
+    render_mesh_options: RenderMeshOptions,
+    lighting: bool,
+    shared_atlas: bool,
+    _phantom: PhantomData<T>,
+}
+
+/// How a [`ChunkBuilderPlugin`] adds the meshes of a chunk's sections to the
+/// world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineSections {
+    /// All of the sections are merged into one mesh, with one texture atlas
+    /// and material, on a single child of the [`BuiltChunk`] entity. This
+    /// means one draw call per chunk instead of one per section.
+    ///
+    /// [`BuiltChunk`]: super::component::BuiltChunk
+    PerChunk,
+
+    /// Each section is its own [`BuiltChunkSection`] child of the
+    /// [`BuiltChunk`] entity, with its own mesh and material.
+    ///
+    /// [`BuiltChunk`]: super::component::BuiltChunk
+    /// [`BuiltChunkSection`]: super::component::BuiltChunkSection
+    PerSection,
+}
+
+impl Default for CombineSections {
+    fn default() -> Self {
+        Self::PerSection
+    }
+}
+
+/// Resource that holds the settings of the [`ChunkBuilderPlugin`] for `T`.
+struct ChunkBuilderSettings<T> {
+    combine_sections: CombineSections,
+    render_mesh_options: RenderMeshOptions,
+    lighting: bool,
+    shared_atlas: bool,
     _phantom: PhantomData<T>,
 }
 
+/// Event that requests a chunk to be built by a specific [`ChunkBuilder`],
+/// e.g., to build an already-loaded chunk with a different builder.
+#[derive(Debug, Clone)]
+pub struct RebuildChunk {
+    pub builder: ChunkBuilderType,
+    pub chunk: brine_chunk::Chunk,
+
+    /// Built chunk entity to despawn once the rebuilt chunk has been added to
+    /// the world.
+    pub replaces: Option<Entity>,
+}
+
 impl<T: ChunkBuilder> ChunkBuilderPlugin<T> {
     /// For (potentially premature) performance reasons, the default behavior of
     /// the [`ChunkBuilderPlugin`] is to consume `ChunkData` events (i.e.,
@@ -57,12 +161,59 @@ impl<T: ChunkBuilder> ChunkBuilderPlugin<T> {
             ..Default::default()
         }
     }
+
+    /// Creates a plugin that ignores `ChunkData` events entirely, and only
+    /// builds chunks requested through [`RebuildChunk`] events.
+    pub fn rebuilds_only() -> Self {
+        Self {
+            chunk_events: false,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how the meshes of each chunk's sections are added to the world.
+    pub fn with_combine_sections(mut self, combine_sections: CombineSections) -> Self {
+        self.combine_sections = combine_sections;
+        self
+    }
+
+    /// Sets how the built meshes are converted into render meshes, e.g., to
+    /// give them tangents for a normal-mapped material.
+    pub fn with_render_mesh_options(mut self, render_mesh_options: RenderMeshOptions) -> Self {
+        self.render_mesh_options = render_mesh_options;
+        self
+    }
+
+    /// Sets whether chunk materials are shaded by the lights in the world
+    /// (e.g., the sun), rather than only by the light levels of the chunk.
+    pub fn with_lighting(mut self, lighting: bool) -> Self {
+        self.lighting = lighting;
+        self
+    }
+
+    /// Sets whether chunks are textured from the atlas of every Minecraft
+    /// texture, rather than from an atlas of their own.
+    ///
+    /// Every chunk is then drawn with one of the same few materials (see
+    /// [`ChunkMaterials`]). This requires the [`MinecraftTexturesPlugin`], and
+    /// built chunks aren't added to the world until its atlas has loaded.
+    ///
+    /// [`MinecraftTexturesPlugin`]: brine_render::texture::MinecraftTexturesPlugin
+    pub fn with_shared_atlas(mut self, shared_atlas: bool) -> Self {
+        self.shared_atlas = shared_atlas;
+        self
+    }
 }
 
 impl<T: ChunkBuilder> Default for ChunkBuilderPlugin<T> {
     fn default() -> Self {
         Self {
             shared: false,
+            chunk_events: true,
+            combine_sections: CombineSections::default(),
+            render_mesh_options: RenderMeshOptions::default(),
+            lighting: false,
+            shared_atlas: false,
             _phantom: PhantomData,
         }
     }
@@ -75,56 +226,194 @@ where
     fn build(&self, app: &mut App) {
         let mut systems = SystemSet::new();
 
-        systems = if self.shared {
-            systems.with_system(Self::builder_task_spawn_shared.label(System::BuilderTaskSpawn))
-        } else {
-            systems.with_system(Self::builder_task_spawn_unique.label(System::BuilderTaskSpawn))
-        };
+        if self.chunk_events {
+            systems = if self.shared {
+                systems.with_system(Self::builder_task_queue_shared.label(System::BuilderTaskQueue))
+            } else {
+                systems.with_system(Self::builder_task_queue_unique.label(System::BuilderTaskQueue))
+            };
+        }
 
         systems = systems
+            .with_system(Self::rebuild_task_queue.label(System::BuilderTaskQueue))
+            .with_system(
+                Self::builder_task_spawn
+                    .label(System::BuilderTaskSpawn)
+                    .after(System::BuilderTaskQueue),
+            )
             .with_system(Self::receive_built_meshes)
             .with_system(Self::add_built_chunks_to_world.label(System::BuilderResultAddToWorld));
 
         app.add_system_set(systems);
+
+        app.insert_resource(ChunkBuilderSettings::<T> {
+            combine_sections: self.combine_sections,
+            render_mesh_options: self.render_mesh_options,
+            lighting: self.lighting,
+            shared_atlas: self.shared_atlas,
+            _phantom: PhantomData,
+        });
+
+        // Every builder plugin draws chunks with the same kind of material.
+        if !app.world.contains_resource::<Assets<VoxelMaterial>>() {
+            app.add_plugin(VoxelMaterialPlugin);
+        }
+
+        // Every builder plugin with a shared atlas shares the same materials.
+        if self.shared_atlas && !app.world.contains_resource::<ChunkMaterials>() {
+            app.init_resource::<ChunkMaterials>();
+            app.add_system_set(
+                SystemSet::on_enter(MinecraftTexturesState::Loaded)
+                    .with_system(create_chunk_materials),
+            );
+        }
+
+        // Every builder plugin reads the same events.
+        if !app.world.contains_resource::<Events<RebuildChunk>>() {
+            app.add_event::<RebuildChunk>();
+        }
+
+        // Every builder plugin places chunks relative to the same origin.
+        if !app.world.contains_resource::<ChunkOrigin>() {
+            origin::add_systems(app);
+        }
+
+        // Every builder plugin shares the same queue, so that the limit on
+        // tasks in flight applies to all of them together.
+        if !app.world.contains_resource::<ChunkBuildQueue>() {
+            app.init_resource::<ChunkBuildQueue>();
+            app.init_resource::<ChunkBuildSettings>();
+            app.add_system(count_builds_in_flight.before(System::BuilderTaskSpawn));
+        }
+
+        // Every builder plugin shares the same index.
+        if !app.world.contains_resource::<ChunkIndex>() {
+            app.init_resource::<ChunkIndex>();
+            app.add_system_to_stage(CoreStage::PostUpdate, remove_unloaded_chunks);
+            app.add_system(unload_chunks_on_dimension_change.before(System::BuilderTaskQueue));
+            app.add_system(rebuild_relit_chunks.before(System::BuilderTaskQueue));
+        }
     }
 }
 
-type MesherTask = Task<(brine_chunk::Chunk, Vec<VoxelMesh>)>;
+/// The result of a [`MesherTask`].
+struct BuiltMeshes {
+    chunk: brine_chunk::Chunk,
+
+    /// One mesh for each section of the chunk.
+    meshes: Vec<VoxelMesh>,
+
+    /// How long it took to build all of the meshes.
+    build_time: Duration,
+
+    /// How long it took to build each mesh.
+    section_build_times: Vec<Duration>,
+
+    /// Connectivity of each section of the chunk.
+    connectivity: Vec<SectionConnectivity>,
+}
+
+type MesherTask = Task<BuiltMeshes>;
+
+/// System that counts the builder tasks that are still running, before any
+/// more are spawned.
+fn count_builds_in_flight(tasks: Query<(), With<MesherTask>>, mut queue: ResMut<ChunkBuildQueue>) {
+    queue.set_in_flight(tasks.iter().count());
+}
 
 impl<T> ChunkBuilderPlugin<T>
 where
     T: ChunkBuilder + Default + Any + Send + Sync + 'static,
 {
-    fn builder_task_spawn(
-        chunk_event: event::clientbound::ChunkData,
-        commands: &mut Commands,
-        task_pool: &AsyncComputeTaskPool,
-    ) {
+    fn builder_task_queue(chunk_event: event::clientbound::ChunkData, queue: &mut ChunkBuildQueue) {
         let chunk = chunk_event.chunk_data;
         if !chunk.is_full() {
             return;
         }
 
+        debug!(
+            "Received chunk ({}, {}), queueing build",
+            chunk.chunk_x, chunk.chunk_z
+        );
+
+        queue.push(T::TYPE, chunk, None);
+    }
+
+    fn spawn_builder_task(
+        mut chunk: brine_chunk::Chunk,
+        replaces: Option<Entity>,
+        opacity: Option<Arc<Vec<bool>>>,
+        commands: &mut Commands,
+        task_pool: &AsyncComputeTaskPool,
+    ) {
         let chunk_x = chunk.chunk_x;
         let chunk_z = chunk.chunk_z;
 
-        debug!("Received chunk ({}, {}), spawning task", chunk_x, chunk_z);
+        debug!("Spawning task for chunk ({}, {})", chunk_x, chunk_z);
 
         let task: MesherTask = task_pool.spawn(async move {
-            let built = T::default().build_chunk(&chunk);
-            (chunk, built)
+            let builder = T::default();
+            let start = Instant::now();
+
+            // Sections with no blocks produce no geometry, so don't bother
+            // building (or spawning) them. They're only dropped once every
+            // section has been built, since their light still shines on the
+            // faces of their neighbors.
+            let (meshes, section_build_times) = chunk
+                .sections
+                .iter()
+                .filter(|chunk_section| chunk_section.block_count > 0)
+                .map(|chunk_section| {
+                    let section_start = Instant::now();
+                    let neighbors = SectionNeighbors::in_chunk(&chunk, chunk_section);
+                    let mesh = builder.build_chunk_section(chunk_section, neighbors);
+                    (mesh, section_start.elapsed())
+                })
+                .unzip();
+            chunk.sections.retain(|section| section.block_count > 0);
+
+            // Without knowing which blocks are opaque, assume that sections
+            // can be seen through.
+            let connectivity = chunk
+                .sections
+                .iter()
+                .map(|chunk_section| match &opacity {
+                    Some(opacity) => SectionConnectivity::compute(chunk_section, |block_state| {
+                        opacity
+                            .get(block_state.0 as usize)
+                            .copied()
+                            .unwrap_or(false)
+                    }),
+                    None => SectionConnectivity::OPEN,
+                })
+                .collect();
+
+            BuiltMeshes {
+                chunk,
+                meshes,
+                build_time: start.elapsed(),
+                section_build_times,
+                connectivity,
+            }
         });
 
         commands.spawn().insert_bundle((
             task,
-            PendingChunk::new(T::TYPE),
+            PendingChunk {
+                replaces,
+                ..PendingChunk::new(T::TYPE)
+            },
             Name::new(format!("Pending Chunk ({}, {})", chunk_x, chunk_z)),
         ));
     }
 
-    fn build_texture_atlas_for_mesh(
-        mesh: &VoxelMesh,
-        chunk_section: &ChunkSection,
+    /// Builds one texture atlas for all of the given meshes, each of which was
+    /// built from the chunk section it's paired with.
+    ///
+    /// The atlas's face textures are in the order of the meshes' faces, one
+    /// mesh after another.
+    fn build_texture_atlas_for_meshes<'a>(
+        meshes: impl IntoIterator<Item = (&'a VoxelMesh, &'a ChunkSection)>,
         asset_server: &AssetServer,
         mc_assets: &MinecraftAssets,
         texture_builder: &mut BlockTextures,
@@ -133,14 +422,18 @@ where
         // the atlas.
         let mut texture_handles: HashSet<Handle<Image>> = Default::default();
 
-        // Weak texture handles, one for each face in the mesh.
-        let mut face_textures: Vec<Handle<Image>> = Vec::with_capacity(mesh.faces.len());
+        // Weak texture handles, one for each face in the meshes.
+        let mut face_textures: Vec<Handle<Image>> = Vec::new();
 
         // Cached mapping from block state id to weak texture handle.
         let mut handle_cache: HashMap<(BlockStateId, BlockFace), Handle<Image>> =
             Default::default();
 
-        for face in mesh.faces.iter() {
+        let faces = meshes.into_iter().flat_map(|(mesh, chunk_section)| {
+            mesh.faces.iter().map(move |face| (face, chunk_section))
+        });
+
+        for (face, chunk_section) in faces {
             let [x, y, z] = face.voxel;
 
             let face = face.axis.into();
@@ -152,7 +445,7 @@ where
             let weak_handle = match handle_cache.entry(key) {
                 Entry::Vacant(entry) => {
                     let strong_handle = match mc_assets
-                        .get_texture_path_for_block_state_and_face(block_state_id, face)
+                        .get_texture_path_for_face(block_state_id, face)
                     {
                         Some(path) => asset_server.load(path),
                         None => {
@@ -186,56 +479,181 @@ where
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn add_built_chunk_to_world(
         chunk_data: brine_chunk::Chunk,
+        settings: &ChunkBuilderSettings<T>,
         voxel_meshes: Vec<VoxelMesh>,
-        atlases: Vec<&TextureAtlas>,
-        face_textures: Vec<Vec<Handle<Image>>>,
+        mesh_materials: Vec<Handle<VoxelMaterial>>,
+        connectivity: Vec<SectionConnectivity>,
+        build_time: Duration,
+        replaces: Option<Entity>,
         meshes: &mut Assets<Mesh>,
-        materials: &mut Assets<StandardMaterial>,
+        chunk_index: &mut ChunkIndex,
         commands: &mut Commands,
     ) -> Entity {
         debug!(
             "Adding chunk ({}, {}) to world",
             chunk_data.chunk_x, chunk_data.chunk_z
         );
-        commands
+        let mut built_chunk_bundle =
+            BuiltChunkBundle::new(T::TYPE, chunk_data.chunk_x, chunk_data.chunk_z);
+        built_chunk_bundle.built_chunk.build_time = build_time;
+
+        let (chunk_x, chunk_z) = (chunk_data.chunk_x, chunk_data.chunk_z);
+        let combine_sections = settings.combine_sections;
+        let render_mesh_options = settings.render_mesh_options;
+        let mut sections = Vec::with_capacity(chunk_data.sections.len());
+        let section_entities = &mut sections;
+
+        let entity = commands
             .spawn()
-            .insert_bundle(BuiltChunkBundle::new(
-                T::TYPE,
-                chunk_data.chunk_x,
-                chunk_data.chunk_z,
-            ))
+            .insert_bundle(built_chunk_bundle)
             .with_children(move |parent| {
-                for (((section, mut mesh), atlas), face_textures) in chunk_data
+                if combine_sections == CombineSections::PerChunk {
+                    // There's only one mesh, made of every section.
+                    for (mesh, material) in voxel_meshes.into_iter().zip(mesh_materials) {
+                        parent
+                            .spawn()
+                            .insert_bundle(MaterialMeshBundle {
+                                mesh: meshes.add(mesh.to_lit_render_mesh_with(render_mesh_options)),
+                                material,
+                                ..Default::default()
+                            })
+                            .insert(Name::new("Sections"));
+                    }
+                    return;
+                }
+
+                for (index, ((section, mesh), material)) in chunk_data
                     .sections
                     .into_iter()
                     .zip(voxel_meshes.into_iter())
-                    .zip(atlases.into_iter())
-                    .zip(face_textures.into_iter())
+                    .zip(mesh_materials)
+                    .enumerate()
                 {
-                    // debug!("atlas has texture handles: {:#?}", &atlas.texture_handles);
-                    // debug!("voxel mesh has face textures: {:#?}", &face_textures[..]);
+                    let section_y = section.chunk_y;
+                    let mut section_bundle = BuiltChunkSectionBundle::new(T::TYPE, section_y);
+                    section_bundle.built_chunk_section.connectivity =
+                        connectivity.get(index).copied().unwrap_or_default();
 
-                    mesh.adjust_tex_coords(atlas, &face_textures);
-
-                    parent
+                    let section_entity = parent
                         .spawn()
-                        .insert_bundle(BuiltChunkSectionBundle::new(T::TYPE, section.chunk_y))
-                        .insert_bundle(PbrBundle {
-                            mesh: meshes.add(mesh.to_render_mesh()),
-                            material: materials.add(StandardMaterial {
-                                base_color_texture: Some(atlas.texture.clone()),
-                                unlit: true,
-                                //alpha_mode: AlphaMode::Blend,
-                                ..Default::default()
-                            }),
+                        .insert_bundle(section_bundle)
+                        .insert_bundle(MaterialMeshBundle {
+                            mesh: meshes.add(mesh.to_lit_render_mesh_with(render_mesh_options)),
+                            material,
                             ..Default::default()
                         })
-                        .insert(ChunkSectionComponent(section));
+                        .insert(ChunkSectionComponent(section))
+                        .id();
+
+                    section_entities.push((section_y, section_entity));
                 }
             })
-            .id()
+            .id();
+
+        if let Some(replaced) = chunk_index.insert(T::TYPE, chunk_x, chunk_z, entity, sections) {
+            debug!("Replacing chunk ({}, {})", chunk_x, chunk_z);
+            commands.entity(replaced).despawn_recursive();
+        }
+
+        if let Some(replaced) = replaces {
+            if chunk_index.remove_entity(replaced) {
+                commands.entity(replaced).despawn_recursive();
+            }
+        }
+
+        entity
+    }
+
+    fn add_material(
+        materials: &mut Assets<VoxelMaterial>,
+        atlas: &TextureAtlas,
+        lighting: bool,
+    ) -> Handle<VoxelMaterial> {
+        materials.add(VoxelMaterial {
+            texture: atlas.texture.clone(),
+            lit: lighting,
+        })
+    }
+
+    /// Maps the pending chunk's meshes onto their own texture atlases and
+    /// creates a material for each, if the atlases have been built.
+    fn texture_with_own_atlases(
+        pending_chunk: &mut PendingChunk,
+        atlases: &Assets<TextureAtlas>,
+        materials: &mut Assets<VoxelMaterial>,
+        lighting: bool,
+    ) -> Option<(Vec<VoxelMesh>, Vec<Handle<VoxelMaterial>>)> {
+        let built_atlases: Vec<Option<&TextureAtlas>> = pending_chunk
+            .texture_atlases
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|pending_atlas| atlases.get(&pending_atlas.atlas))
+            .collect();
+
+        if built_atlases.iter().any(|atlas| atlas.is_none()) {
+            return None;
+        }
+
+        let face_textures: Vec<Vec<Handle<Image>>> = pending_chunk
+            .texture_atlases
+            .take()
+            .unwrap()
+            .into_iter()
+            .map(|atlas| atlas.face_textures)
+            .collect();
+
+        let mut voxel_meshes = pending_chunk.voxel_meshes.take().unwrap();
+        let mut mesh_materials = Vec::with_capacity(voxel_meshes.len());
+
+        for ((mesh, atlas), face_textures) in voxel_meshes
+            .iter_mut()
+            .zip(built_atlases.into_iter())
+            .zip(face_textures.iter())
+        {
+            let atlas = atlas.unwrap();
+            mesh.adjust_tex_coords(atlas, face_textures);
+            mesh_materials.push(Self::add_material(materials, atlas, lighting));
+        }
+
+        Some((voxel_meshes, mesh_materials))
+    }
+
+    /// Maps the pending chunk's meshes onto the shared atlas, if the shared
+    /// materials have been created.
+    fn texture_with_shared_atlas(
+        pending_chunk: &mut PendingChunk,
+        chunk_materials: Option<&ChunkMaterials>,
+        atlases: Option<&Assets<SharedAtlas>>,
+        lighting: bool,
+    ) -> Option<(Vec<VoxelMesh>, Vec<Handle<VoxelMaterial>>)> {
+        let chunk_materials = chunk_materials?;
+        let atlas = atlases?.get(chunk_materials.atlas()?)?;
+        let material = chunk_materials.get(lighting)?;
+
+        let face_textures = pending_chunk.face_texture_keys.take().unwrap();
+        let mut voxel_meshes = pending_chunk.voxel_meshes.take().unwrap();
+
+        for (mesh, face_textures) in voxel_meshes.iter_mut().zip(face_textures.iter()) {
+            mesh.adjust_tex_coords_to_regions(&material::face_regions(atlas, face_textures));
+        }
+
+        let mesh_materials = vec![material; voxel_meshes.len()];
+
+        Some((voxel_meshes, mesh_materials))
+    }
+
+    /// Merges the meshes of a chunk's sections into one mesh, in order.
+    fn combine_meshes(voxel_meshes: &[VoxelMesh], sections: &[ChunkSection]) -> VoxelMesh {
+        let mut combined = VoxelMesh::default();
+        for (mesh, section) in voxel_meshes.iter().zip(sections.iter()) {
+            let offset = Vec3::new(0.0, (section.chunk_y as f32) * 16.0, 0.0);
+            combined.merge(mesh, offset);
+        }
+        combined
     }
 
     /*
@@ -247,39 +665,82 @@ where
             |___/
     */
 
-    fn builder_task_spawn_unique(
+    fn builder_task_queue_unique(
         mut chunk_events: ResMut<Events<event::clientbound::ChunkData>>,
-        mut commands: Commands,
-        task_pool: Res<AsyncComputeTaskPool>,
+        mut queue: ResMut<ChunkBuildQueue>,
     ) {
         for chunk_event in chunk_events.drain() {
-            Self::builder_task_spawn(chunk_event, &mut commands, &task_pool);
+            Self::builder_task_queue(chunk_event, &mut queue);
         }
     }
 
-    fn builder_task_spawn_shared(
+    fn builder_task_queue_shared(
         mut chunk_events: EventReader<event::clientbound::ChunkData>,
+        mut queue: ResMut<ChunkBuildQueue>,
+    ) {
+        for chunk_event in chunk_events.iter() {
+            Self::builder_task_queue(chunk_event.clone(), &mut queue);
+        }
+    }
+
+    fn rebuild_task_queue(
+        mut rebuild_events: EventReader<RebuildChunk>,
+        mut queue: ResMut<ChunkBuildQueue>,
+    ) {
+        for rebuild in rebuild_events.iter() {
+            if rebuild.builder == T::TYPE {
+                queue.push(T::TYPE, rebuild.chunk.clone(), rebuild.replaces);
+            }
+        }
+    }
+
+    /// Spawns tasks for the queued chunks nearest to the camera, as long as
+    /// there's room for more tasks.
+    #[allow(clippy::too_many_arguments)]
+    fn builder_task_spawn(
+        settings: Res<ChunkBuildSettings>,
+        origin: Res<ChunkOrigin>,
+        cameras: Query<(&Camera, &GlobalTransform)>,
+        mc_data: Option<Res<MinecraftData>>,
+        mut queue: ResMut<ChunkBuildQueue>,
         mut commands: Commands,
         task_pool: Res<AsyncComputeTaskPool>,
+        mut opacity: Local<Option<Arc<Vec<bool>>>>,
     ) {
-        for chunk_event in chunk_events.iter() {
-            Self::builder_task_spawn(chunk_event.clone(), &mut commands, &task_pool);
+        let position = queue::camera_position(&cameras);
+
+        if opacity.is_none() {
+            *opacity = mc_data.map(|mc_data| Arc::new(occlusion::opacity_table(&mc_data)));
+        }
+
+        while let Some(build) = queue.pop_nearest(T::TYPE, position, &origin, &settings) {
+            Self::spawn_builder_task(
+                build.chunk,
+                build.replaces,
+                opacity.clone(),
+                &mut commands,
+                &task_pool,
+            );
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn receive_built_meshes(
+        settings: Res<ChunkBuilderSettings<T>>,
+        build_settings: Res<ChunkBuildSettings>,
         asset_server: Res<AssetServer>,
         mc_assets: Res<MinecraftAssets>,
         mut chunks_with_pending_meshes: Query<(Entity, &mut PendingChunk, &mut MesherTask)>,
         mut texture_builder: ResMut<BlockTextures>,
+        mut diagnostics: Option<ResMut<Diagnostics>>,
         mut commands: Commands,
     ) {
-        const MAX_PER_FRAME: usize = 1;
+        let start = Instant::now();
+        let mut received = 0;
 
-        for (i, (entity, mut pending_chunk, mut mesher_task)) in
-            chunks_with_pending_meshes.iter_mut().enumerate()
-        {
-            if i >= MAX_PER_FRAME {
+        for (entity, mut pending_chunk, mut mesher_task) in chunks_with_pending_meshes.iter_mut() {
+            // Always receive at least one chunk, however long it takes.
+            if received > 0 && start.elapsed() >= build_settings.frame_budget {
                 break;
             }
 
@@ -287,30 +748,95 @@ where
                 continue;
             }
 
-            if let Some((chunk, voxel_meshes)) =
-                future::block_on(future::poll_once(&mut *mesher_task))
-            {
+            if let Some(built) = future::block_on(future::poll_once(&mut *mesher_task)) {
+                received += 1;
+
+                let BuiltMeshes {
+                    chunk,
+                    meshes: mut voxel_meshes,
+                    build_time,
+                    section_build_times,
+                    connectivity,
+                } = built;
+
                 debug!(
-                    "Received meshes for Chunk ({}, {})",
-                    chunk.chunk_x, chunk.chunk_z
+                    "Received meshes for Chunk ({}, {}), built in {:?}",
+                    chunk.chunk_x, chunk.chunk_z, build_time
                 );
 
-                let texture_atlases = voxel_meshes
-                    .iter()
-                    .zip(chunk.sections.iter())
-                    .map(|(mesh, chunk_section)| {
-                        Self::build_texture_atlas_for_mesh(
-                            mesh,
-                            chunk_section,
+                if let Some(diagnostics) = diagnostics.as_mut() {
+                    for section_build_time in section_build_times {
+                        diagnostics.add_measurement(
+                            SECTION_BUILD_TIME,
+                            section_build_time.as_secs_f64() * 1000.0,
+                        );
+                    }
+                }
+
+                light_emissive_sections(&mut voxel_meshes, &chunk.sections, |block_state| {
+                    mc_assets.is_emissive(BlockStateId(block_state.0 as u16))
+                });
+
+                if settings.shared_atlas {
+                    let mut face_textures: Vec<Vec<Option<TextureKey>>> = voxel_meshes
+                        .iter()
+                        .zip(chunk.sections.iter())
+                        .map(|(mesh, section)| {
+                            material::face_texture_keys(mesh, section, &*mc_assets)
+                        })
+                        .collect();
+
+                    if settings.combine_sections == CombineSections::PerChunk {
+                        voxel_meshes = vec![Self::combine_meshes(&voxel_meshes, &chunk.sections)];
+                        face_textures = vec![face_textures.concat()];
+                    }
+
+                    pending_chunk.chunk_data = Some(chunk);
+                    pending_chunk.voxel_meshes = Some(voxel_meshes);
+                    pending_chunk.connectivity = connectivity;
+                    pending_chunk.build_time = build_time;
+                    pending_chunk.face_texture_keys = Some(face_textures);
+
+                    commands.entity(entity).remove::<MesherTask>();
+                    continue;
+                }
+
+                let (voxel_meshes, texture_atlases) = match settings.combine_sections {
+                    CombineSections::PerChunk => {
+                        let atlas = Self::build_texture_atlas_for_meshes(
+                            voxel_meshes.iter().zip(chunk.sections.iter()),
                             &*asset_server,
                             &*mc_assets,
                             &mut *texture_builder,
-                        )
-                    })
-                    .collect();
+                        );
+
+                        // Merged in the same order as the atlas's face textures.
+                        let combined = Self::combine_meshes(&voxel_meshes, &chunk.sections);
+
+                        (vec![combined], vec![atlas])
+                    }
+                    CombineSections::PerSection => {
+                        let texture_atlases = voxel_meshes
+                            .iter()
+                            .zip(chunk.sections.iter())
+                            .map(|(mesh, chunk_section)| {
+                                Self::build_texture_atlas_for_meshes(
+                                    [(mesh, chunk_section)],
+                                    &*asset_server,
+                                    &*mc_assets,
+                                    &mut *texture_builder,
+                                )
+                            })
+                            .collect();
+
+                        (voxel_meshes, texture_atlases)
+                    }
+                };
 
                 pending_chunk.chunk_data = Some(chunk);
                 pending_chunk.voxel_meshes = Some(voxel_meshes);
+                pending_chunk.connectivity = connectivity;
+                pending_chunk.build_time = build_time;
                 pending_chunk.texture_atlases = Some(texture_atlases);
 
                 commands.entity(entity).remove::<MesherTask>();
@@ -318,11 +844,16 @@ where
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn add_built_chunks_to_world(
+        settings: Res<ChunkBuilderSettings<T>>,
         atlases: Res<Assets<TextureAtlas>>,
+        shared_atlases: Option<Res<Assets<SharedAtlas>>>,
+        chunk_materials: Option<Res<ChunkMaterials>>,
         mut chunks_with_pending_atlases: Query<(Entity, &mut PendingChunk), Without<MesherTask>>,
         mut meshes: ResMut<Assets<Mesh>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
+        mut materials: ResMut<Assets<VoxelMaterial>>,
+        mut chunk_index: ResMut<ChunkIndex>,
         mut commands: Commands,
     ) {
         for (entity, mut pending_chunk) in chunks_with_pending_atlases.iter_mut() {
@@ -330,31 +861,28 @@ where
                 continue;
             }
 
-            let built_atlases: Vec<Option<&TextureAtlas>> = pending_chunk
-                .texture_atlases
-                .as_ref()
-                .unwrap()
-                .iter()
-                .map(|pending_atlas| atlases.get(&pending_atlas.atlas))
-                .collect();
-
-            if built_atlases.iter().any(|atlas| atlas.is_none()) {
-                continue;
-            }
-
-            let atlases: Vec<&TextureAtlas> =
-                built_atlases.iter().map(|atlas| atlas.unwrap()).collect();
+            let textured = if settings.shared_atlas {
+                Self::texture_with_shared_atlas(
+                    &mut *pending_chunk,
+                    chunk_materials.as_deref(),
+                    shared_atlases.as_deref(),
+                    settings.lighting,
+                )
+            } else {
+                Self::texture_with_own_atlases(
+                    &mut *pending_chunk,
+                    &*atlases,
+                    &mut *materials,
+                    settings.lighting,
+                )
+            };
 
-            let face_textures: Vec<Vec<Handle<Image>>> = pending_chunk
-                .texture_atlases
-                .take()
-                .unwrap()
-                .into_iter()
-                .map(|atlas| atlas.face_textures)
-                .collect();
+            let (voxel_meshes, mesh_materials) = match textured {
+                Some(textured) => textured,
+                None => continue,
+            };
 
             let chunk = pending_chunk.chunk_data.take().unwrap();
-            let voxel_meshes = pending_chunk.voxel_meshes.take().unwrap();
 
             debug!(
                 "Received all texture atlases for Chunk ({}, {})",
@@ -363,11 +891,14 @@ where
 
             Self::add_built_chunk_to_world(
                 chunk,
+                &*settings,
                 voxel_meshes,
-                atlases,
-                face_textures,
+                mesh_materials,
+                std::mem::take(&mut pending_chunk.connectivity),
+                pending_chunk.build_time,
+                pending_chunk.replaces,
                 &mut *meshes,
-                &mut *materials,
+                &mut *chunk_index,
                 &mut commands,
             );
 
@@ -375,3 +906,360 @@ where
         }
     }
 }
+
+/// Lights the emissive faces of each section's mesh, so that they're drawn at
+/// full brightness. See [`light::light_emissive_faces`].
+fn light_emissive_sections(
+    voxel_meshes: &mut [VoxelMesh],
+    sections: &[ChunkSection],
+    is_emissive: impl Fn(BlockState) -> bool,
+) {
+    for (mesh, section) in voxel_meshes.iter_mut().zip(sections.iter()) {
+        light::light_emissive_faces(mesh, section, &is_emissive);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        asset::AssetPlugin, core::CorePlugin, render::camera::CameraPlugin,
+        render::mesh::VertexAttributeValues, sprite::Rect,
+    };
+
+    use brine_chunk::{BlockStates, Chunk, LIGHT_ARRAY_LENGTH};
+
+    use super::*;
+    use crate::{
+        chunk_builder::{component::BuiltChunkSection, NaiveBlocksChunkBuilder},
+        mesh::{Axis, VoxelFace},
+    };
+
+    type Builder = NaiveBlocksChunkBuilder;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>()
+            .add_asset::<Mesh>()
+            .add_asset::<VoxelMaterial>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<SharedAtlas>()
+            .init_resource::<ChunkIndex>()
+            .init_resource::<ChunkMaterials>()
+            .insert_resource(ChunkBuilderSettings::<Builder> {
+                combine_sections: CombineSections::PerSection,
+                render_mesh_options: Default::default(),
+                lighting: false,
+                shared_atlas: true,
+                _phantom: PhantomData,
+            })
+            .add_system(ChunkBuilderPlugin::<Builder>::add_built_chunks_to_world);
+        app
+    }
+
+    /// Stitches the shared atlas and creates its materials, like entering
+    /// `MinecraftTexturesState::Loaded` would.
+    fn load_shared_atlas(app: &mut App) {
+        let mut regions = bevy::utils::HashMap::default();
+        regions.insert(
+            TextureKey(0),
+            Rect {
+                min: Vec2::new(0.0, 0.0),
+                max: Vec2::new(0.5, 0.5),
+            },
+        );
+        let atlas = SharedAtlas {
+            texture: Default::default(),
+            regions,
+            placeholder_region: Rect {
+                min: Vec2::new(0.5, 0.5),
+                max: Vec2::new(1.0, 1.0),
+            },
+        };
+
+        let world = &mut app.world;
+        let atlas_handle = world
+            .get_resource_mut::<Assets<SharedAtlas>>()
+            .unwrap()
+            .add(atlas.clone());
+        let chunk_materials = {
+            let mut materials = world.get_resource_mut::<Assets<VoxelMaterial>>().unwrap();
+            ChunkMaterials::new(atlas_handle, &atlas, &mut *materials)
+        };
+        world.insert_resource(chunk_materials);
+    }
+
+    /// Spawns a chunk whose meshes have been built, with one face per section.
+    fn spawn_built_chunk(app: &mut App, chunk_x: i32, num_sections: u8) {
+        let mut chunk = Chunk::empty(chunk_x, 0);
+        chunk.sections = (0..num_sections).map(ChunkSection::empty).collect();
+
+        let face = VoxelFace {
+            tex_coords: [[0.0, 1.0], [1.0, 1.0], [0.0, 0.0], [1.0, 0.0]],
+            ..Default::default()
+        };
+        let voxel_meshes = vec![VoxelMesh { faces: vec![face] }; num_sections as usize];
+        let face_texture_keys = vec![vec![Some(TextureKey(0))]; num_sections as usize];
+
+        app.world.spawn().insert(PendingChunk {
+            chunk_data: Some(chunk),
+            voxel_meshes: Some(voxel_meshes),
+            face_texture_keys: Some(face_texture_keys),
+            ..PendingChunk::new(Builder::TYPE)
+        });
+    }
+
+    fn distinct_chunk_materials(app: &mut App) -> usize {
+        let mut query = app.world.query::<&Handle<VoxelMaterial>>();
+        material::count_distinct_materials(query.iter(&app.world))
+    }
+
+    fn material_assets(app: &App) -> usize {
+        app.world
+            .get_resource::<Assets<VoxelMaterial>>()
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn shared_atlas_materials_stay_constant() {
+        let mut app = app();
+        load_shared_atlas(&mut app);
+        let materials_after_loading = material_assets(&app);
+
+        for chunk_x in 0..4 {
+            spawn_built_chunk(&mut app, chunk_x, 3);
+            app.update();
+
+            assert_eq!(distinct_chunk_materials(&mut app), 1);
+            assert_eq!(material_assets(&app), materials_after_loading);
+        }
+
+        let index = app.world.get_resource::<ChunkIndex>().unwrap();
+        assert_eq!(index.iter_loaded().count(), 4);
+    }
+
+    #[test]
+    fn chunks_wait_for_shared_materials() {
+        let mut app = app();
+
+        spawn_built_chunk(&mut app, 0, 1);
+        app.update();
+        app.update();
+        assert_eq!(distinct_chunk_materials(&mut app), 0);
+
+        load_shared_atlas(&mut app);
+        app.update();
+        assert_eq!(distinct_chunk_materials(&mut app), 1);
+    }
+
+    #[test]
+    fn section_meshes_are_colored_by_light() {
+        let mut app = app();
+        load_shared_atlas(&mut app);
+
+        // The face in each section has a light level of zero.
+        spawn_built_chunk(&mut app, 0, 2);
+        app.update();
+
+        let mut query = app
+            .world
+            .query_filtered::<&Handle<Mesh>, With<BuiltChunkSection>>();
+        let handles: Vec<Handle<Mesh>> = query.iter(&app.world).cloned().collect();
+        assert_eq!(handles.len(), 2);
+
+        let dark = [
+            light::MIN_BRIGHTNESS,
+            light::MIN_BRIGHTNESS,
+            light::MIN_BRIGHTNESS,
+            1.0,
+        ];
+        let meshes = app.world.get_resource::<Assets<Mesh>>().unwrap();
+        for handle in handles.iter() {
+            match meshes.get(handle).unwrap().attribute(Mesh::ATTRIBUTE_COLOR) {
+                Some(VertexAttributeValues::Float32x4(colors)) => {
+                    assert_eq!(colors, &vec![dark; 4]);
+                }
+                other => panic!("unexpected colors: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn emissive_faces_are_drawn_at_full_brightness() {
+        let mut app = app();
+        load_shared_atlas(&mut app);
+
+        let glowstone = BlockState(1);
+        let stone = BlockState(2);
+
+        // A pitch black section with a glowing block next to a stone block.
+        let mut section = ChunkSection {
+            sky_light: Some(Box::new([0; LIGHT_ARRAY_LENGTH])),
+            block_light: Some(Box::new([0; LIGHT_ARRAY_LENGTH])),
+            ..ChunkSection::empty(0)
+        };
+        section.block_states.0[BlockStates::xyz_to_index(0, 0, 0)] = glowstone;
+        section.block_states.0[BlockStates::xyz_to_index(1, 0, 0)] = stone;
+
+        let face = |voxel| VoxelFace {
+            voxel,
+            axis: Axis::YPos,
+            tex_coords: [[0.0, 1.0], [1.0, 1.0], [0.0, 0.0], [1.0, 0.0]],
+            ..Default::default()
+        };
+        let mut mesh = VoxelMesh {
+            faces: vec![face([0, 0, 0]), face([1, 0, 0])],
+        };
+        light::light_mesh(&mut mesh, &section, Default::default());
+
+        let mut voxel_meshes = vec![mesh];
+        let sections = vec![section];
+        light_emissive_sections(&mut voxel_meshes, &sections, |block_state| {
+            block_state == glowstone
+        });
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = sections;
+        app.world.spawn().insert(PendingChunk {
+            chunk_data: Some(chunk),
+            voxel_meshes: Some(voxel_meshes),
+            face_texture_keys: Some(vec![vec![Some(TextureKey(0)); 2]]),
+            ..PendingChunk::new(Builder::TYPE)
+        });
+        app.update();
+
+        let mut query = app
+            .world
+            .query_filtered::<&Handle<Mesh>, With<BuiltChunkSection>>();
+        let handle = query.iter(&app.world).next().unwrap().clone();
+
+        let bright = light::brightness(light::MAX_LIGHT_LEVEL);
+        let dark = light::brightness(0);
+        let meshes = app.world.get_resource::<Assets<Mesh>>().unwrap();
+        match meshes.get(handle).unwrap().attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => {
+                assert_eq!(colors[..4], [[bright, bright, bright, 1.0]; 4]);
+                assert_eq!(colors[4..], [[dark, dark, dark, 1.0]; 4]);
+            }
+            other => panic!("unexpected colors: {:?}", other),
+        }
+    }
+
+    /// The coordinates of chunks whose builder tasks have finished, in order.
+    #[derive(Default)]
+    struct FinishedBuilds(Vec<(i32, i32)>);
+
+    /// Stands in for [`ChunkBuilderPlugin::receive_built_meshes`], which needs
+    /// the Minecraft assets.
+    fn finish_builds(
+        mut tasks: Query<(Entity, &mut MesherTask)>,
+        mut finished: ResMut<FinishedBuilds>,
+        mut commands: Commands,
+    ) {
+        for (entity, mut task) in tasks.iter_mut() {
+            if let Some(built) = future::block_on(future::poll_once(&mut *task)) {
+                finished.0.push((built.chunk.chunk_x, built.chunk.chunk_z));
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    fn queue_app(max_in_flight: usize) -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_event::<event::clientbound::ChunkData>()
+            .init_resource::<ChunkBuildQueue>()
+            .insert_resource(ChunkBuildSettings {
+                max_in_flight,
+                ..Default::default()
+            })
+            .init_resource::<ChunkOrigin>()
+            .init_resource::<FinishedBuilds>()
+            .add_system(count_builds_in_flight.before(System::BuilderTaskSpawn))
+            .add_system(
+                ChunkBuilderPlugin::<Builder>::builder_task_queue_unique
+                    .label(System::BuilderTaskQueue),
+            )
+            .add_system(
+                ChunkBuilderPlugin::<Builder>::builder_task_spawn
+                    .label(System::BuilderTaskSpawn)
+                    .after(System::BuilderTaskQueue),
+            )
+            .add_system(finish_builds.after(System::BuilderTaskSpawn));
+
+        // Standing in the middle of chunk (0, 0).
+        app.world.spawn().insert_bundle((
+            Camera {
+                name: Some(CameraPlugin::CAMERA_3D.to_string()),
+                ..Default::default()
+            },
+            GlobalTransform::from_translation(Vec3::new(8.0, 64.0, 8.0)),
+        ));
+
+        app
+    }
+
+    #[test]
+    fn build_pipeline_under_load() {
+        const MAX_IN_FLIGHT: usize = 4;
+        const NUM_CHUNKS: usize = 500;
+
+        let mut app = queue_app(MAX_IN_FLIGHT);
+
+        // Row by row from one corner, so arrival order has nothing to do with
+        // distance.
+        let coords: Vec<(i32, i32)> = (-11..=11)
+            .flat_map(|chunk_z| (-11..=11).map(move |chunk_x| (chunk_x, chunk_z)))
+            .take(NUM_CHUNKS)
+            .collect();
+
+        let mut chunk_events = app
+            .world
+            .get_resource_mut::<Events<event::clientbound::ChunkData>>()
+            .unwrap();
+        for &(chunk_x, chunk_z) in coords.iter() {
+            chunk_events.send(event::clientbound::ChunkData {
+                chunk_data: Chunk::empty(chunk_x, chunk_z),
+            });
+        }
+
+        for _ in 0..10_000 {
+            app.update();
+
+            let mut tasks = app.world.query::<&MesherTask>();
+            assert!(tasks.iter(&app.world).count() <= MAX_IN_FLIGHT);
+
+            let finished = app.world.get_resource::<FinishedBuilds>().unwrap();
+            if finished.0.len() == NUM_CHUNKS {
+                break;
+            }
+        }
+
+        let finished = &app.world.get_resource::<FinishedBuilds>().unwrap().0;
+        assert_eq!(finished.len(), NUM_CHUNKS);
+        let queue = app.world.get_resource::<ChunkBuildQueue>().unwrap();
+        assert!(queue.is_empty());
+
+        let camera = Vec3::new(8.0, 64.0, 8.0);
+        let finished_at = |near: bool| {
+            finished
+                .iter()
+                .enumerate()
+                .filter(move |(_, &(chunk_x, chunk_z))| {
+                    let distance = crate::lod::chunk_distance(camera, chunk_x, chunk_z);
+                    if near {
+                        distance < 3.0
+                    } else {
+                        distance > 8.0
+                    }
+                })
+                .map(|(index, _)| index)
+        };
+
+        let last_near = finished_at(true).max().unwrap();
+        let first_far = finished_at(false).min().unwrap();
+        assert!(last_near < first_far);
+    }
+}
@@ -0,0 +1,229 @@
+//! Scheduling chunk builds, so that the task pool isn't flooded all at once.
+//!
+//! Chunks that are received (or rebuilt) wait in the [`ChunkBuildQueue`]
+//! until there's room for another builder task. The chunks closest to the
+//! camera are built first, so that the world fills in from the player
+//! outwards.
+
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    render::camera::{Camera, CameraPlugin},
+};
+
+use crate::lod::chunk_distance;
+
+use super::{ChunkBuilderType, ChunkOrigin};
+
+/// Resource that holds the settings of the [`ChunkBuildQueue`].
+///
+/// Insert it before adding a [`ChunkBuilderPlugin`] to change the defaults.
+///
+/// [`ChunkBuilderPlugin`]: super::ChunkBuilderPlugin
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkBuildSettings {
+    /// Maximum number of builder tasks running at once, across every builder.
+    pub max_in_flight: usize,
+
+    /// How long each builder may spend adding finished chunks to the world per
+    /// frame. At least one finished chunk is added each frame, even if it
+    /// takes longer.
+    pub frame_budget: Duration,
+}
+
+impl Default for ChunkBuildSettings {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 2 * num_cpus::get(),
+            frame_budget: Duration::from_millis(4),
+        }
+    }
+}
+
+/// A chunk waiting to be built.
+#[derive(Debug)]
+pub(crate) struct QueuedBuild {
+    pub builder: ChunkBuilderType,
+    pub chunk: brine_chunk::Chunk,
+    pub replaces: Option<Entity>,
+
+    /// Position in the queue, so that chunks at the same distance are built in
+    /// the order they arrived.
+    order: u64,
+}
+
+/// Resource that holds the chunks waiting to be built, and the number of
+/// builder tasks in flight.
+///
+/// Shared by every [`ChunkBuilderPlugin`], which takes the nearest chunks for
+/// its builder whenever fewer than [`ChunkBuildSettings::max_in_flight`] tasks
+/// are running.
+///
+/// [`ChunkBuilderPlugin`]: super::ChunkBuilderPlugin
+#[derive(Debug, Default)]
+pub struct ChunkBuildQueue {
+    queued: Vec<QueuedBuild>,
+    next_order: u64,
+    in_flight: usize,
+}
+
+impl ChunkBuildQueue {
+    /// Returns the number of chunks waiting to be built.
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// Returns the number of builder tasks in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        builder: ChunkBuilderType,
+        chunk: brine_chunk::Chunk,
+        replaces: Option<Entity>,
+    ) {
+        self.queued.push(QueuedBuild {
+            builder,
+            chunk,
+            replaces,
+            order: self.next_order,
+        });
+        self.next_order += 1;
+    }
+
+    /// Removes and returns the queued chunk for `builder` that is nearest to
+    /// `position`, if a task for it can be started.
+    pub(crate) fn pop_nearest(
+        &mut self,
+        builder: ChunkBuilderType,
+        position: Vec3,
+        origin: &ChunkOrigin,
+        settings: &ChunkBuildSettings,
+    ) -> Option<QueuedBuild> {
+        if self.in_flight >= settings.max_in_flight {
+            return None;
+        }
+
+        let distance = |build: &QueuedBuild| {
+            chunk_distance(
+                position,
+                build.chunk.chunk_x - origin.chunk_x,
+                build.chunk.chunk_z - origin.chunk_z,
+            )
+        };
+
+        let (index, _) = self
+            .queued
+            .iter()
+            .enumerate()
+            .filter(|(_, build)| build.builder == builder)
+            .map(|(index, build)| (index, (distance(build), build.order)))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))?;
+
+        self.in_flight += 1;
+        Some(self.queued.swap_remove(index))
+    }
+
+    pub(crate) fn set_in_flight(&mut self, in_flight: usize) {
+        self.in_flight = in_flight;
+    }
+
+    /// Drops every queued chunk.
+    pub fn clear(&mut self) {
+        self.queued.clear();
+    }
+}
+
+/// Returns the position of the 3D camera, or the origin if there isn't one.
+pub(crate) fn camera_position(cameras: &Query<(&Camera, &GlobalTransform)>) -> Vec3 {
+    cameras
+        .iter()
+        .find(|(camera, _)| camera.name.as_deref() == Some(CameraPlugin::CAMERA_3D))
+        .map(|(_, transform)| transform.translation)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::Chunk;
+
+    use super::*;
+
+    const BUILDER: ChunkBuilderType = ChunkBuilderType::VISIBLE_FACES;
+
+    fn settings(max_in_flight: usize) -> ChunkBuildSettings {
+        ChunkBuildSettings {
+            max_in_flight,
+            ..Default::default()
+        }
+    }
+
+    fn pop(queue: &mut ChunkBuildQueue, origin: ChunkOrigin) -> Option<(i32, i32)> {
+        queue
+            .pop_nearest(BUILDER, Vec3::ZERO, &origin, &settings(usize::MAX))
+            .map(|build| (build.chunk.chunk_x, build.chunk.chunk_z))
+    }
+
+    #[test]
+    fn nearest_chunks_first() {
+        let mut queue = ChunkBuildQueue::default();
+        for (chunk_x, chunk_z) in [(5, 5), (0, 0), (-2, 1), (0, 0), (3, 0)] {
+            queue.push(BUILDER, Chunk::empty(chunk_x, chunk_z), None);
+        }
+        queue.push(ChunkBuilderType::LOD2, Chunk::empty(1, 1), None);
+
+        let origin = ChunkOrigin::default();
+        assert_eq!(pop(&mut queue, origin), Some((0, 0)));
+        assert_eq!(pop(&mut queue, origin), Some((0, 0)));
+        assert_eq!(pop(&mut queue, origin), Some((-2, 1)));
+        assert_eq!(pop(&mut queue, origin), Some((3, 0)));
+        assert_eq!(pop(&mut queue, origin), Some((5, 5)));
+        assert_eq!(pop(&mut queue, origin), None);
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn distance_is_relative_to_origin() {
+        let mut queue = ChunkBuildQueue::default();
+        queue.push(BUILDER, Chunk::empty(0, 0), None);
+        queue.push(BUILDER, Chunk::empty(100, 100), None);
+
+        assert_eq!(
+            pop(&mut queue, ChunkOrigin::new(100, 100)),
+            Some((100, 100))
+        );
+    }
+
+    #[test]
+    fn in_flight_is_capped() {
+        let mut queue = ChunkBuildQueue::default();
+        for chunk_x in 0..3 {
+            queue.push(BUILDER, Chunk::empty(chunk_x, 0), None);
+        }
+
+        let origin = ChunkOrigin::default();
+        let settings = settings(2);
+        assert!(queue
+            .pop_nearest(BUILDER, Vec3::ZERO, &origin, &settings)
+            .is_some());
+        assert!(queue
+            .pop_nearest(BUILDER, Vec3::ZERO, &origin, &settings)
+            .is_some());
+        assert!(queue
+            .pop_nearest(BUILDER, Vec3::ZERO, &origin, &settings)
+            .is_none());
+        assert_eq!(queue.in_flight(), 2);
+
+        queue.set_in_flight(1);
+        assert!(queue
+            .pop_nearest(BUILDER, Vec3::ZERO, &origin, &settings)
+            .is_some());
+    }
+}
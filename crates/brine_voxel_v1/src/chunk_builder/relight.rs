@@ -0,0 +1,193 @@
+//! Rebuilding built chunks when the server changes their light.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use brine_chunk::Chunk;
+use brine_proto::event::clientbound::LightUpdated;
+
+use crate::chunk_builder::{
+    component::{BuiltChunk, ChunkSection},
+    ChunkBuilderType, ChunkIndex, RebuildChunk,
+};
+
+/// System that applies [`LightUpdated`] events to the [`ChunkSection`]
+/// components of built chunks, and rebuilds every chunk whose light changed.
+///
+/// Chunks that were built with
+/// [`CombineSections::PerChunk`][super::CombineSections::PerChunk] don't keep
+/// their sections' data, so they keep the light they were built with. So do
+/// chunks that are still being built when their light changes.
+pub(crate) fn rebuild_relit_chunks(
+    mut light_events: EventReader<LightUpdated>,
+    chunk_index: Res<ChunkIndex>,
+    chunks: Query<&Children, With<BuiltChunk>>,
+    mut sections: Query<&mut ChunkSection>,
+    mut rebuild_events: EventWriter<RebuildChunk>,
+) {
+    let mut relit: HashMap<(ChunkBuilderType, i32, i32), Entity> = HashMap::default();
+
+    for event in light_events.iter() {
+        let light = &event.light;
+
+        let built = chunk_index
+            .iter_loaded()
+            .filter(|&(_, chunk_x, chunk_z, _)| {
+                chunk_x == light.chunk_x && chunk_z == light.chunk_z
+            });
+
+        for (builder, chunk_x, chunk_z, entity) in built {
+            let children = match chunks.get(entity) {
+                Ok(children) => children,
+                Err(_) => continue,
+            };
+
+            let mut has_sections = false;
+            for child in children.iter() {
+                if let Ok(mut section) = sections.get_mut(*child) {
+                    has_sections = true;
+
+                    let chunk_y = section.0.chunk_y;
+                    if let Some(light_section) = light
+                        .sections
+                        .iter()
+                        .find(|light_section| light_section.chunk_y == chunk_y)
+                    {
+                        section.0.update_light(light_section);
+                    }
+                }
+            }
+
+            if has_sections {
+                relit.insert((builder, chunk_x, chunk_z), entity);
+            }
+        }
+    }
+
+    for ((builder, chunk_x, chunk_z), entity) in relit {
+        debug!("Rebuilding chunk ({}, {}) with new light", chunk_x, chunk_z);
+
+        let mut chunk = Chunk::empty(chunk_x, chunk_z);
+        chunk.sections = chunks
+            .get(entity)
+            .iter()
+            .flat_map(|children| children.iter())
+            .filter_map(|child| sections.get(*child).ok())
+            .map(|section| section.0.clone())
+            .collect();
+        chunk.sections.sort_by_key(|section| section.chunk_y);
+
+        rebuild_events.send(RebuildChunk {
+            builder,
+            chunk,
+            replaces: Some(entity),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::ecs::event::Events;
+    use brine_chunk::LIGHT_ARRAY_LENGTH;
+
+    use super::*;
+
+    const BUILDER: ChunkBuilderType = ChunkBuilderType::VISIBLE_FACES;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(brine_proto::ProtocolPlugin);
+        app.add_event::<RebuildChunk>();
+        app.init_resource::<ChunkIndex>();
+        app.add_system(rebuild_relit_chunks);
+        app
+    }
+
+    /// Spawns a built chunk with sections 0 and 1, without any light.
+    fn spawn_chunk(app: &mut App, chunk_x: i32) -> Entity {
+        let world = &mut app.world;
+
+        let sections: Vec<(u8, Entity)> = (0..2)
+            .map(|section_y| {
+                let section = brine_chunk::ChunkSection::empty(section_y);
+                (section_y, world.spawn().insert(ChunkSection(section)).id())
+            })
+            .collect();
+        let children: Vec<Entity> = sections.iter().map(|(_, entity)| *entity).collect();
+
+        let entity = world
+            .spawn()
+            .insert(BuiltChunk {
+                builder: BUILDER,
+                chunk_x,
+                chunk_z: 0,
+                ..Default::default()
+            })
+            .push_children(&children)
+            .id();
+
+        world
+            .get_resource_mut::<ChunkIndex>()
+            .unwrap()
+            .insert(BUILDER, chunk_x, 0, entity, sections);
+
+        entity
+    }
+
+    fn light_updated(chunk_x: i32, chunk_y: u8, sky_light: u8) -> LightUpdated {
+        let mut section = brine_chunk::ChunkSection::empty(chunk_y);
+        section.sky_light = Some(Box::new([(sky_light << 4) | sky_light; LIGHT_ARRAY_LENGTH]));
+
+        let mut light = Chunk::empty_delta(chunk_x, 0);
+        light.sections = vec![section];
+
+        LightUpdated { light }
+    }
+
+    fn rebuilds(app: &mut App) -> Vec<RebuildChunk> {
+        app.world
+            .get_resource_mut::<Events<RebuildChunk>>()
+            .unwrap()
+            .drain()
+            .collect()
+    }
+
+    #[test]
+    fn light_update_rebuilds_chunk_with_new_light() {
+        let mut app = app();
+        let chunk = spawn_chunk(&mut app, 0);
+        spawn_chunk(&mut app, 1);
+
+        let mut light_events = app
+            .world
+            .get_resource_mut::<Events<LightUpdated>>()
+            .unwrap();
+        light_events.send(light_updated(0, 1, 7));
+        light_events.send(light_updated(0, 0, 3));
+        app.update();
+
+        let rebuilds = rebuilds(&mut app);
+        assert_eq!(rebuilds.len(), 1);
+
+        let rebuild = &rebuilds[0];
+        assert_eq!(rebuild.builder, BUILDER);
+        assert_eq!(rebuild.replaces, Some(chunk));
+        assert_eq!((rebuild.chunk.chunk_x, rebuild.chunk.chunk_z), (0, 0));
+        assert_eq!(rebuild.chunk.sections.len(), 2);
+        assert_eq!(rebuild.chunk.sections[0].get_sky_light(0, 0, 0), 3);
+        assert_eq!(rebuild.chunk.sections[1].get_sky_light(0, 0, 0), 7);
+    }
+
+    #[test]
+    fn light_update_for_unbuilt_chunk_is_ignored() {
+        let mut app = app();
+        spawn_chunk(&mut app, 0);
+
+        app.world
+            .get_resource_mut::<Events<LightUpdated>>()
+            .unwrap()
+            .send(light_updated(5, 0, 15));
+        app.update();
+
+        assert!(rebuilds(&mut app).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Measuring the output of chunk builders without a Bevy app.
+
+use std::{
+    ops::{Add, AddAssign},
+    time::{Duration, Instant},
+};
+
+use brine_chunk::Chunk;
+
+use crate::mesh::VoxelMesh;
+
+use super::ChunkBuilder;
+
+/// Size and build time of the meshes generated for a chunk.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MeshStats {
+    /// Number of meshes generated (one per non-empty section).
+    pub sections: usize,
+
+    /// Number of vertices across all meshes.
+    pub vertices: usize,
+
+    /// Number of quads (faces) across all meshes.
+    pub quads: usize,
+
+    /// Number of triangles across all meshes.
+    pub triangles: usize,
+
+    /// Wall-clock time spent in [`ChunkBuilder::build_chunk`].
+    pub elapsed: Duration,
+}
+
+impl MeshStats {
+    /// Tallies the sizes of the given meshes. `elapsed` is left at zero.
+    pub fn from_meshes(meshes: &[VoxelMesh]) -> Self {
+        let quads = meshes.iter().map(|mesh| mesh.faces.len()).sum();
+
+        Self {
+            sections: meshes.len(),
+            vertices: quads * 4,
+            quads,
+            triangles: quads * 2,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Add for MeshStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            sections: self.sections + rhs.sections,
+            vertices: self.vertices + rhs.vertices,
+            quads: self.quads + rhs.quads,
+            triangles: self.triangles + rhs.triangles,
+            elapsed: self.elapsed + rhs.elapsed,
+        }
+    }
+}
+
+impl AddAssign for MeshStats {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+/// Builds `chunk` with a `B` and returns the size of the resulting meshes and
+/// how long it took to build them.
+///
+/// This doesn't touch any of Bevy's asset or render systems, so it can be used
+/// to compare builders in tests and benchmarks.
+pub fn benchmark_builder<B: ChunkBuilder + Default>(chunk: &Chunk) -> MeshStats {
+    let builder = B::default();
+
+    let start = Instant::now();
+    let meshes = builder.build_chunk(chunk);
+    let elapsed = start.elapsed();
+
+    MeshStats {
+        elapsed,
+        ..MeshStats::from_meshes(&meshes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::{BlockState, ChunkSection, BLOCKS_PER_SECTION, SECTIONS_PER_CHUNK};
+
+    use crate::chunk_builder::{
+        GreedyQuadsChunkBuilder, NaiveBlocksChunkBuilder, VisibleFacesChunkBuilder,
+    };
+
+    use super::*;
+
+    fn solid_chunk(num_sections: u8) -> Chunk {
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = (0..num_sections)
+            .map(|chunk_y| {
+                let mut section = ChunkSection::empty(chunk_y);
+                section.block_states.0.fill(BlockState(1));
+                section.block_count = BLOCKS_PER_SECTION as u16;
+                section
+            })
+            .collect();
+        chunk
+    }
+
+    #[test]
+    fn greedy_produces_fewer_quads_than_naive() {
+        let chunk = solid_chunk(2);
+
+        let naive = benchmark_builder::<NaiveBlocksChunkBuilder>(&chunk);
+        let visible = benchmark_builder::<VisibleFacesChunkBuilder>(&chunk);
+        let greedy = benchmark_builder::<GreedyQuadsChunkBuilder>(&chunk);
+
+        assert_eq!(naive.sections, 2);
+        assert_eq!(naive.quads, 2 * BLOCKS_PER_SECTION * 6);
+        assert_eq!(visible.quads, 2 * 16 * 16 * 6);
+        assert_eq!(greedy.quads, 2 * 6);
+
+        assert!(greedy.quads < visible.quads);
+        assert!(visible.quads < naive.quads);
+    }
+
+    #[test]
+    fn stats_add_up() {
+        let chunk = solid_chunk(SECTIONS_PER_CHUNK as u8);
+        let stats = benchmark_builder::<GreedyQuadsChunkBuilder>(&chunk);
+
+        assert_eq!(stats.sections, SECTIONS_PER_CHUNK);
+        assert_eq!(stats.vertices, stats.quads * 4);
+        assert_eq!(stats.triangles, stats.quads * 2);
+
+        let mut total = MeshStats::default();
+        total += stats;
+        total += stats;
+        assert_eq!(total.quads, stats.quads * 2);
+        assert_eq!(total.elapsed, stats.elapsed * 2);
+    }
+}
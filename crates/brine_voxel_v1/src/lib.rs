@@ -10,8 +10,13 @@
 //! [`block-mesh`]: <https://github.com/bonsairobo/block-mesh-rs>
 
 pub mod chunk_builder;
+pub mod light;
+pub mod lod;
+pub mod material;
 pub mod mesh;
+pub mod occlusion;
 pub mod texture;
+pub mod visibility;
 
 pub use chunk_builder::{
     ChunkBuilder, ChunkBuilderPlugin, NaiveBlocksChunkBuilder, VisibleFacesChunkBuilder,
@@ -0,0 +1,313 @@
+//! Shading voxel faces by the light levels stored in a chunk.
+//!
+//! Each face is lit by the light level of the block it faces, i.e., its
+//! neighbor in the direction of the face's [`Axis`]. Solid blocks have a light
+//! level of zero, so it's the air (or other transparent block) in front of the
+//! face that determines how bright it should be.
+//!
+//! Faces on the top and bottom of a section face into the sections above and
+//! below it, so those are passed along as [`SectionNeighbors`].
+
+use brine_chunk::{BlockState, Chunk, ChunkSection, SECTION_HEIGHT, SECTION_WIDTH};
+
+use crate::mesh::{Axis, VoxelMesh};
+
+/// The maximum light level for both sky light and block light.
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+/// The brightness of a face with a light level of zero.
+///
+/// Vanilla renders these completely black (modulo the brightness setting),
+/// but a little bit of ambient light makes caves easier to look at.
+pub const MIN_BRIGHTNESS: f32 = 0.05;
+
+/// Combines sky light and block light into a single light level, the same way
+/// vanilla does when lighting terrain.
+#[inline]
+pub fn combine(sky_light: u8, block_light: u8) -> u8 {
+    sky_light.max(block_light).min(MAX_LIGHT_LEVEL)
+}
+
+/// Maps a light level to a brightness in `[MIN_BRIGHTNESS, 1.0]`.
+///
+/// This uses the same curve as vanilla's light texture, `f / (4 - 3f)` where
+/// `f` is the light level divided by 15, which drops off roughly
+/// exponentially as the light level decreases.
+#[inline]
+pub fn brightness(light_level: u8) -> f32 {
+    let f = light_level.min(MAX_LIGHT_LEVEL) as f32 / MAX_LIGHT_LEVEL as f32;
+    let curved = f / (4.0 - 3.0 * f);
+
+    MIN_BRIGHTNESS + (1.0 - MIN_BRIGHTNESS) * curved
+}
+
+/// The sections above and below a chunk section, whose light the faces on
+/// the section's top and bottom are lit with.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SectionNeighbors<'a> {
+    pub below: Option<&'a ChunkSection>,
+    pub above: Option<&'a ChunkSection>,
+}
+
+impl<'a> SectionNeighbors<'a> {
+    /// Returns the neighbors of `chunk_section` in `chunk`.
+    pub fn in_chunk(chunk: &'a Chunk, chunk_section: &ChunkSection) -> Self {
+        let find = |chunk_y: Option<u8>| {
+            chunk_y.and_then(|chunk_y| {
+                chunk
+                    .sections
+                    .iter()
+                    .find(|section| section.chunk_y == chunk_y)
+            })
+        };
+
+        Self {
+            below: find(chunk_section.chunk_y.checked_sub(1)),
+            above: find(chunk_section.chunk_y.checked_add(1)),
+        }
+    }
+}
+
+/// Returns the light level that a face of the voxel at `[x, y, z]` pointing
+/// along `axis` should be lit with.
+///
+/// Faces on the top and bottom of the section are lit by the neighboring
+/// section in that direction. Faces on the sides of the chunk would be lit by
+/// the neighboring chunk, which isn't available, so they're lit by the voxel's
+/// own light instead.
+///
+/// Missing neighbor sections and sections without any light data are treated
+/// as fully lit, so that missing data never makes geometry disappear into
+/// darkness.
+pub fn face_light_level(
+    chunk_section: &ChunkSection,
+    neighbors: SectionNeighbors,
+    voxel: [u8; 3],
+    axis: Axis,
+) -> u8 {
+    if !has_light(chunk_section) {
+        return MAX_LIGHT_LEVEL;
+    }
+
+    let [x, _, z] = voxel;
+    match (neighbor(voxel, axis), axis) {
+        (Some([x, y, z]), _) => light_level(chunk_section, x, y, z),
+        (None, Axis::YPos) => match neighbors.above {
+            Some(above) if has_light(above) => light_level(above, x, 0, z),
+            _ => MAX_LIGHT_LEVEL,
+        },
+        (None, Axis::YNeg) => match neighbors.below {
+            Some(below) if has_light(below) => light_level(below, x, SECTION_HEIGHT as u8 - 1, z),
+            _ => MAX_LIGHT_LEVEL,
+        },
+        (None, _) => light_level(chunk_section, voxel[0], voxel[1], voxel[2]),
+    }
+}
+
+/// Sets the light level of every face in the mesh from the chunk section it
+/// was built from.
+///
+/// Faces that span several voxels (as produced by greedy meshing) are lit
+/// according to their [`voxel`][crate::mesh::VoxelFace::voxel] alone.
+pub fn light_mesh(mesh: &mut VoxelMesh, chunk_section: &ChunkSection, neighbors: SectionNeighbors) {
+    for face in mesh.faces.iter_mut() {
+        face.light = face_light_level(chunk_section, neighbors, face.voxel, face.axis);
+    }
+}
+
+/// Sets every face of an emissive block to [`MAX_LIGHT_LEVEL`], so that it
+/// glows no matter how dark it is around it.
+///
+/// This should be done after [`light_mesh`], which would otherwise overwrite
+/// the emissive faces' light levels.
+pub fn light_emissive_faces(
+    mesh: &mut VoxelMesh,
+    chunk_section: &ChunkSection,
+    is_emissive: impl Fn(BlockState) -> bool,
+) {
+    for face in mesh.faces.iter_mut() {
+        let [x, y, z] = face.voxel;
+        let block_state = chunk_section.get_block((x, y, z));
+
+        if block_state.map(&is_emissive).unwrap_or(false) {
+            face.light = MAX_LIGHT_LEVEL;
+        }
+    }
+}
+
+fn has_light(chunk_section: &ChunkSection) -> bool {
+    chunk_section.sky_light.is_some() || chunk_section.block_light.is_some()
+}
+
+fn light_level(chunk_section: &ChunkSection, x: u8, y: u8, z: u8) -> u8 {
+    combine(
+        chunk_section.get_sky_light(x, y, z),
+        chunk_section.get_block_light(x, y, z),
+    )
+}
+
+fn neighbor(voxel: [u8; 3], axis: Axis) -> Option<[u8; 3]> {
+    let limits = [SECTION_WIDTH, SECTION_HEIGHT, SECTION_WIDTH];
+
+    let mut neighbor = [0; 3];
+    for (i, (coord, offset)) in voxel.iter().zip(axis.normal().iter()).enumerate() {
+        let coord = *coord as i32 + *offset as i32;
+        if coord < 0 || coord >= limits[i] as i32 {
+            return None;
+        }
+        neighbor[i] = coord as u8;
+    }
+
+    Some(neighbor)
+}
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::{BlockStates, LIGHT_ARRAY_LENGTH};
+
+    use crate::mesh::VoxelFace;
+
+    use super::*;
+
+    fn section_with_sky_light(level: u8) -> ChunkSection {
+        let nibbles = level | (level << 4);
+
+        ChunkSection {
+            sky_light: Some(Box::new([nibbles; LIGHT_ARRAY_LENGTH])),
+            block_light: Some(Box::new([0; LIGHT_ARRAY_LENGTH])),
+            ..ChunkSection::empty(0)
+        }
+    }
+
+    #[test]
+    fn combine_takes_max() {
+        assert_eq!(combine(15, 0), 15);
+        assert_eq!(combine(3, 12), 12);
+        assert_eq!(combine(0, 0), 0);
+    }
+
+    #[test]
+    fn brightness_curve() {
+        assert!((brightness(MAX_LIGHT_LEVEL) - 1.0).abs() < f32::EPSILON);
+        assert_eq!(brightness(0), MIN_BRIGHTNESS);
+
+        for level in 0..MAX_LIGHT_LEVEL {
+            assert!(brightness(level) < brightness(level + 1));
+        }
+
+        // Vanilla's curve is much darker than linear at low light levels.
+        assert!(brightness(4) < 4.0 / 15.0);
+    }
+
+    #[test]
+    fn sky_lit_face_is_brighter_than_dim_face() {
+        let voxel = [8, 8, 8];
+
+        let no_neighbors = SectionNeighbors::default();
+        let sky_lit =
+            face_light_level(&section_with_sky_light(15), no_neighbors, voxel, Axis::YPos);
+        let dim = face_light_level(&section_with_sky_light(4), no_neighbors, voxel, Axis::YPos);
+
+        assert_eq!(sky_lit, 15);
+        assert_eq!(dim, 4);
+        assert!(brightness(sky_lit) > brightness(dim));
+    }
+
+    #[test]
+    fn missing_light_data_is_fully_lit() {
+        let section = ChunkSection::empty(0);
+
+        assert_eq!(
+            face_light_level(&section, Default::default(), [8, 8, 8], Axis::XNeg),
+            MAX_LIGHT_LEVEL
+        );
+    }
+
+    #[test]
+    fn emissive_faces_ignore_neighbor_light() {
+        let glowstone = BlockState(1);
+        let stone = BlockState(2);
+
+        let mut section = section_with_sky_light(0);
+        section.block_states.0[BlockStates::xyz_to_index(0, 0, 0)] = glowstone;
+        section.block_states.0[BlockStates::xyz_to_index(1, 0, 0)] = stone;
+
+        let face = |voxel| VoxelFace {
+            voxel,
+            axis: Axis::YPos,
+            ..Default::default()
+        };
+        let mut mesh = VoxelMesh {
+            faces: vec![face([0, 0, 0]), face([1, 0, 0])],
+        };
+
+        light_mesh(&mut mesh, &section, Default::default());
+        light_emissive_faces(&mut mesh, &section, |block_state| block_state == glowstone);
+
+        assert_eq!(mesh.faces[0].light, MAX_LIGHT_LEVEL);
+        assert_eq!(mesh.faces[1].light, 0);
+    }
+
+    #[test]
+    fn missing_neighbor_sections_are_fully_lit() {
+        let section = section_with_sky_light(0);
+        let light = |voxel, axis| face_light_level(&section, Default::default(), voxel, axis);
+
+        assert_eq!(light([8, 15, 8], Axis::YPos), MAX_LIGHT_LEVEL);
+        assert_eq!(light([8, 0, 8], Axis::YNeg), MAX_LIGHT_LEVEL);
+        assert_eq!(light([1, 8, 8], Axis::XNeg), 0);
+    }
+
+    #[test]
+    fn faces_on_chunk_sides_use_own_light() {
+        let mut section = section_with_sky_light(0);
+        section.sky_light.as_mut().unwrap()[0] = 9;
+        let light = |voxel, axis| face_light_level(&section, Default::default(), voxel, axis);
+
+        assert_eq!(light([0, 0, 0], Axis::XNeg), 9);
+        assert_eq!(light([0, 8, 8], Axis::XNeg), 0);
+        assert_eq!(light([15, 8, 8], Axis::XPos), 0);
+        assert_eq!(light([8, 8, 15], Axis::ZPos), 0);
+    }
+
+    #[test]
+    fn top_and_bottom_faces_use_neighbor_sections() {
+        // A pitch black cave section, between an unlit section above and a
+        // sky lit section below.
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![
+            ChunkSection {
+                chunk_y: 3,
+                ..section_with_sky_light(12)
+            },
+            ChunkSection {
+                chunk_y: 4,
+                ..section_with_sky_light(0)
+            },
+            ChunkSection {
+                chunk_y: 5,
+                ..section_with_sky_light(0)
+            },
+        ];
+        let section = &chunk.sections[1];
+        let neighbors = SectionNeighbors::in_chunk(&chunk, section);
+
+        let face = |voxel, axis| VoxelFace {
+            voxel,
+            axis,
+            ..Default::default()
+        };
+        let mut mesh = VoxelMesh {
+            faces: vec![
+                face([8, 15, 8], Axis::YPos),
+                face([3, 15, 0], Axis::YPos),
+                face([8, 0, 8], Axis::YNeg),
+            ],
+        };
+        light_mesh(&mut mesh, section, neighbors);
+
+        assert_eq!(mesh.faces[0].light, 0);
+        assert_eq!(mesh.faces[1].light, 0);
+        assert_eq!(mesh.faces[2].light, 12);
+    }
+}
@@ -0,0 +1,216 @@
+//! Swapping far away chunks for lower-detail versions of themselves.
+//!
+//! Chunks further from the camera than [`LodSettings::distance`] are rebuilt
+//! with the [`Lod2ChunkBuilder`], and rebuilt again with the regular builder
+//! once they come back into range. To keep chunks near the threshold from
+//! being rebuilt over and over, a chunk has to cross the threshold by
+//! [`LodSettings::hysteresis`] before it is swapped.
+//!
+//! Rebuilds use the block data stored in each section's
+//! [`ChunkSection`](crate::chunk_builder::component::ChunkSection) component,
+//! so nothing needs to be requested from the server.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    prelude::*,
+    render::camera::{Camera, CameraPlugin},
+};
+
+use brine_chunk::{Chunk, CHUNK_WIDTH};
+
+use crate::chunk_builder::{
+    component::{BuiltChunk, ChunkSection},
+    ChunkBuilder, ChunkBuilderPlugin, ChunkBuilderType, ChunkOrigin, Lod2ChunkBuilder,
+    RebuildChunk,
+};
+
+/// Plugin that swaps chunks built by `T` for [`Lod2ChunkBuilder`] chunks when
+/// they are far away from the camera, and back again when they are close.
+///
+/// This adds a [`ChunkBuilderPlugin`] for the [`Lod2ChunkBuilder`], but the
+/// [`ChunkBuilderPlugin`] for `T` must be added separately.
+pub struct ChunkLodPlugin<T: ChunkBuilder> {
+    settings: LodSettings,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ChunkBuilder> ChunkLodPlugin<T> {
+    pub fn new(settings: LodSettings) -> Self {
+        Self {
+            settings,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ChunkBuilder> Default for ChunkLodPlugin<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> Plugin for ChunkLodPlugin<T>
+where
+    T: ChunkBuilder + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings);
+        app.add_plugin(
+            ChunkBuilderPlugin::<Lod2ChunkBuilder>::rebuilds_only()
+                .with_lighting(self.settings.lighting),
+        );
+        app.add_system(swap_chunk_lods::<T>);
+    }
+}
+
+/// Resource that controls when chunks are swapped for lower-detail versions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodSettings {
+    /// Horizontal distance from the camera, in chunks, beyond which chunks are
+    /// built at a lower level of detail.
+    pub distance: f32,
+
+    /// How far past `distance`, in chunks, a chunk has to be before it is
+    /// swapped (in either direction).
+    pub hysteresis: f32,
+
+    /// Whether the lower-detail chunks are shaded by the world's lights (see
+    /// [`ChunkBuilderPlugin::with_lighting`]).
+    pub lighting: bool,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self {
+            distance: 8.0,
+            hysteresis: 1.0,
+            lighting: false,
+        }
+    }
+}
+
+impl LodSettings {
+    /// Returns whether a chunk at the given distance from the camera should be
+    /// swapped for its other level of detail.
+    pub fn should_swap(&self, is_lod: bool, distance: f32) -> bool {
+        if is_lod {
+            distance < self.distance - self.hysteresis
+        } else {
+            distance > self.distance + self.hysteresis
+        }
+    }
+}
+
+/// Returns the horizontal distance, in chunks, from `position` to the center
+/// of the chunk at the given chunk coordinates.
+pub fn chunk_distance(position: Vec3, chunk_x: i32, chunk_z: i32) -> f32 {
+    let chunk_width = CHUNK_WIDTH as f32;
+    let center = Vec2::new(chunk_x as f32 + 0.5, chunk_z as f32 + 0.5) * chunk_width;
+
+    Vec2::new(position.x, position.z).distance(center) / chunk_width
+}
+
+/// Marks a built chunk that is already being rebuilt at its other level of
+/// detail.
+#[derive(Component)]
+struct PendingLodSwap;
+
+fn swap_chunk_lods<T: ChunkBuilder + Send + Sync + 'static>(
+    settings: Res<LodSettings>,
+    origin: Res<ChunkOrigin>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    chunks: Query<(Entity, &BuiltChunk, Option<&Children>), Without<PendingLodSwap>>,
+    sections: Query<&ChunkSection>,
+    mut rebuild_events: EventWriter<RebuildChunk>,
+    mut commands: Commands,
+) {
+    // Rebuilding is expensive, so spread it out over multiple frames.
+    const MAX_SWAPS_PER_FRAME: usize = 4;
+
+    let camera_position = cameras
+        .iter()
+        .find(|(camera, _)| camera.name.as_deref() == Some(CameraPlugin::CAMERA_3D))
+        .map(|(_, transform)| transform.translation);
+
+    let camera_position = match camera_position {
+        Some(position) => position,
+        None => return,
+    };
+
+    let swaps = chunks.iter().filter_map(|(entity, built_chunk, children)| {
+        let is_lod = match built_chunk.builder {
+            builder if builder == T::TYPE => false,
+            ChunkBuilderType::LOD2 => true,
+            _ => return None,
+        };
+
+        // The camera is in the same space as the chunks, which is relative to
+        // the origin.
+        let distance = chunk_distance(
+            camera_position,
+            built_chunk.chunk_x - origin.chunk_x,
+            built_chunk.chunk_z - origin.chunk_z,
+        );
+
+        settings
+            .should_swap(is_lod, distance)
+            .then(|| (entity, built_chunk, children, is_lod))
+    });
+
+    for (entity, built_chunk, children, is_lod) in swaps.take(MAX_SWAPS_PER_FRAME) {
+        let mut chunk = Chunk::empty(built_chunk.chunk_x, built_chunk.chunk_z);
+        chunk.sections = children
+            .iter()
+            .flat_map(|children| children.iter())
+            .filter_map(|child| sections.get(*child).ok())
+            .map(|section| section.0.clone())
+            .collect();
+        chunk.sections.sort_by_key(|section| section.chunk_y);
+
+        let builder = if is_lod {
+            T::TYPE
+        } else {
+            ChunkBuilderType::LOD2
+        };
+
+        debug!("Rebuilding {} with {:?}", built_chunk, builder);
+
+        rebuild_events.send(RebuildChunk {
+            builder,
+            chunk,
+            replaces: Some(entity),
+        });
+
+        commands.entity(entity).insert(PendingLodSwap);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distance_is_measured_from_chunk_center() {
+        assert_eq!(chunk_distance(Vec3::new(8.0, 100.0, 8.0), 0, 0), 0.0);
+        assert_eq!(chunk_distance(Vec3::new(8.0, 0.0, 8.0), 3, 0), 3.0);
+        assert_eq!(chunk_distance(Vec3::new(-8.0, 0.0, -8.0), -1, -5), 4.0);
+    }
+
+    #[test]
+    fn swapping_has_hysteresis() {
+        let settings = LodSettings {
+            distance: 8.0,
+            hysteresis: 1.0,
+            ..Default::default()
+        };
+
+        // Full detail chunks only become LOD chunks past 9 chunks away.
+        assert!(!settings.should_swap(false, 8.5));
+        assert!(settings.should_swap(false, 9.5));
+
+        // LOD chunks only go back to full detail within 7 chunks.
+        assert!(!settings.should_swap(true, 7.5));
+        assert!(settings.should_swap(true, 6.5));
+    }
+}
@@ -0,0 +1,205 @@
+//! The material that built chunks are drawn with.
+//!
+//! Bevy's `StandardMaterial` ignores vertex colors, and its pipeline expects
+//! meshes to have only positions, normals, UVs, and (optionally) tangents. A
+//! [`VoxelMaterial`] instead multiplies its texture by the mesh's
+//! [`Mesh::ATTRIBUTE_COLOR`], which is where [`VoxelMesh::to_lit_render_mesh`]
+//! puts the brightness of each face (see [`light`]).
+//!
+//! [`VoxelMesh::to_lit_render_mesh`]: crate::mesh::VoxelMesh::to_lit_render_mesh
+//! [`light`]: crate::light
+
+use bevy::{
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    pbr::{MaterialPipeline, MaterialPlugin, SpecializedMaterial},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        render_asset::{PrepareAssetError, RenderAsset, RenderAssets},
+        render_resource::{
+            BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
+            RenderPipelineDescriptor, SamplerBindingType, ShaderStages, TextureSampleType,
+            TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexFormat,
+            VertexStepMode,
+        },
+        renderer::RenderDevice,
+    },
+};
+
+pub const VOXEL_MATERIAL_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 0x5c2e_91b4_7da0_4f63);
+
+/// Plugin that registers the [`VoxelMaterial`] asset and its pipeline.
+///
+/// Added by every [`ChunkBuilderPlugin`][crate::ChunkBuilderPlugin].
+pub struct VoxelMaterialPlugin;
+
+impl Plugin for VoxelMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        // Headless apps (e.g., in tests) have no shaders.
+        if let Some(mut shaders) = app.world.get_resource_mut::<Assets<Shader>>() {
+            shaders.set_untracked(
+                VOXEL_MATERIAL_SHADER_HANDLE,
+                Shader::from_wgsl(include_str!("material.wgsl")),
+            );
+        }
+
+        app.add_plugin(MaterialPlugin::<VoxelMaterial>::default());
+    }
+}
+
+/// A material that draws a texture, darkened by the mesh's vertex colors.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "b0a1d6f2-3c5e-4a8b-9e27-6f41c8d3a915"]
+pub struct VoxelMaterial {
+    pub texture: Handle<Image>,
+
+    /// Whether the material is also shaded by the ambient and directional
+    /// lights in the world. If not, only the vertex colors darken it.
+    pub lit: bool,
+}
+
+/// The GPU representation of a [`VoxelMaterial`].
+pub struct GpuVoxelMaterial {
+    bind_group: BindGroup,
+    lit: bool,
+}
+
+impl RenderAsset for VoxelMaterial {
+    type ExtractedAsset = VoxelMaterial;
+    type PreparedAsset = GpuVoxelMaterial;
+    type Param = (
+        SRes<RenderDevice>,
+        SRes<MaterialPipeline<Self>>,
+        SRes<RenderAssets<Image>>,
+    );
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline, gpu_images): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let gpu_image = match gpu_images.get(&material.texture) {
+            Some(gpu_image) => gpu_image,
+            None => return Err(PrepareAssetError::RetryNextUpdate(material)),
+        };
+
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&gpu_image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&gpu_image.sampler),
+                },
+            ],
+            label: Some("voxel_material_bind_group"),
+            layout: &pipeline.material_layout,
+        });
+
+        Ok(GpuVoxelMaterial {
+            bind_group,
+            lit: material.lit,
+        })
+    }
+}
+
+impl SpecializedMaterial for VoxelMaterial {
+    type Key = bool;
+
+    fn key(material: &<Self as RenderAsset>::PreparedAsset) -> Self::Key {
+        material.lit
+    }
+
+    fn specialize(lit: Self::Key, descriptor: &mut RenderPipelineDescriptor) {
+        let tangents = descriptor
+            .vertex
+            .shader_defs
+            .iter()
+            .any(|def| def == "VERTEX_TANGENTS");
+
+        // Mesh attributes are interleaved in order of their names, so the
+        // color comes first and pushes everything else back by 16 bytes.
+        let (array_stride, uv_offset) = if tangents { (64, 56) } else { (48, 40) };
+        descriptor.vertex.buffers = vec![VertexBufferLayout {
+            array_stride,
+            step_mode: VertexStepMode::Vertex,
+            attributes: vec![
+                // Position
+                VertexAttribute {
+                    format: VertexFormat::Float32x3,
+                    offset: 28,
+                    shader_location: 0,
+                },
+                // Normal
+                VertexAttribute {
+                    format: VertexFormat::Float32x3,
+                    offset: 16,
+                    shader_location: 1,
+                },
+                // UV
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: uv_offset,
+                    shader_location: 2,
+                },
+                // Color
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 3,
+                },
+            ],
+        }];
+
+        if lit {
+            if let Some(fragment) = descriptor.fragment.as_mut() {
+                fragment
+                    .shader_defs
+                    .push(String::from("VOXEL_MATERIAL_LIT"));
+            }
+        }
+    }
+
+    fn vertex_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(VOXEL_MATERIAL_SHADER_HANDLE.typed())
+    }
+
+    fn fragment_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(VOXEL_MATERIAL_SHADER_HANDLE.typed())
+    }
+
+    fn bind_group(material: &<Self as RenderAsset>::PreparedAsset) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("voxel_material_layout"),
+        })
+    }
+}
@@ -0,0 +1,66 @@
+#import bevy_pbr::mesh_view_bind_group
+#import bevy_pbr::mesh_struct
+
+[[group(1), binding(0)]]
+var atlas_texture: texture_2d<f32>;
+[[group(1), binding(1)]]
+var atlas_sampler: sampler;
+
+[[group(2), binding(0)]]
+var<uniform> mesh: Mesh;
+
+struct Vertex {
+    [[location(0)]] position: vec3<f32>;
+    [[location(1)]] normal: vec3<f32>;
+    [[location(2)]] uv: vec2<f32>;
+    [[location(3)]] color: vec4<f32>;
+};
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] world_normal: vec3<f32>;
+    [[location(1)]] uv: vec2<f32>;
+    [[location(2)]] color: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let world_position = mesh.model * vec4<f32>(vertex.position, 1.0);
+
+    var out: VertexOutput;
+    out.clip_position = view.view_proj * world_position;
+    out.world_normal = mat3x3<f32>(
+        mesh.inverse_transpose_model[0].xyz,
+        mesh.inverse_transpose_model[1].xyz,
+        mesh.inverse_transpose_model[2].xyz
+    ) * vertex.normal;
+    out.uv = vertex.uv;
+    out.color = vertex.color;
+    return out;
+}
+
+struct FragmentInput {
+    [[location(0)]] world_normal: vec3<f32>;
+    [[location(1)]] uv: vec2<f32>;
+    [[location(2)]] color: vec4<f32>;
+};
+
+[[stage(fragment)]]
+fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
+    var color = textureSample(atlas_texture, atlas_sampler, in.uv) * in.color;
+
+#ifdef VOXEL_MATERIAL_LIT
+    // A simple Lambertian term for each directional light, on top of the
+    // ambient light, capped so that the sun never washes out the texture.
+    let normal = normalize(in.world_normal);
+    var shade = lights.ambient_color.rgb;
+    for (var i: u32 = 0u; i < lights.n_directional_lights; i = i + 1u) {
+        let directional = lights.directional_lights[i];
+        let n_dot_l = max(dot(normal, directional.direction_to_light), 0.0);
+        shade = shade + directional.color.rgb * n_dot_l;
+    }
+    color = vec4<f32>(color.rgb * min(shade, vec3<f32>(1.0)), color.a);
+#endif
+
+    return color;
+}
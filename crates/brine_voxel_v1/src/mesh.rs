@@ -7,10 +7,12 @@ use bevy::{
         mesh::{Indices, Mesh},
         render_resource::PrimitiveTopology,
     },
-    sprite::TextureAtlas,
+    sprite::{Rect, TextureAtlas},
 };
 use brine_asset::BlockFace;
 
+use crate::light;
+
 /// The six sides of a voxel.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -30,6 +32,28 @@ impl Default for Axis {
 }
 
 impl Axis {
+    /// All six axes, in the order of their discriminants.
+    pub const ALL: [Axis; 6] = [
+        Axis::XPos,
+        Axis::XNeg,
+        Axis::YPos,
+        Axis::YNeg,
+        Axis::ZPos,
+        Axis::ZNeg,
+    ];
+
+    /// The axis pointing the other way.
+    pub const fn opposite(&self) -> Self {
+        match self {
+            Axis::XPos => Axis::XNeg,
+            Axis::XNeg => Axis::XPos,
+            Axis::YPos => Axis::YNeg,
+            Axis::YNeg => Axis::YPos,
+            Axis::ZPos => Axis::ZNeg,
+            Axis::ZNeg => Axis::ZPos,
+        }
+    }
+
     pub const fn normal(&self) -> [i8; 3] {
         match self {
             Axis::XPos => [1, 0, 0],
@@ -40,6 +64,14 @@ impl Axis {
             Axis::ZNeg => [0, 0, -1],
         }
     }
+
+    /// A tangent for faces whose tex coords don't give one.
+    const fn fallback_tangent(&self) -> [f32; 4] {
+        match self {
+            Axis::XPos | Axis::XNeg => [0.0, 0.0, 1.0, 1.0],
+            Axis::YPos | Axis::YNeg | Axis::ZPos | Axis::ZNeg => [1.0, 0.0, 0.0, 1.0],
+        }
+    }
 }
 
 impl From<Axis> for BlockFace {
@@ -55,6 +87,35 @@ impl From<Axis> for BlockFace {
     }
 }
 
+/// Options for converting a [`VoxelMesh`] into a render [`Mesh`].
+///
+/// The default options produce a mesh with only positions, normals, and UVs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RenderMeshOptions {
+    tangents: bool,
+    u32_indices: bool,
+}
+
+impl RenderMeshOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also writes each face's [tangent][VoxelFace::tangent] into
+    /// [`Mesh::ATTRIBUTE_TANGENT`], for materials with a normal map.
+    pub fn with_tangents(mut self, tangents: bool) -> Self {
+        self.tangents = tangents;
+        self
+    }
+
+    /// Always uses `u32` indices, even if the mesh is small enough for `u16`
+    /// indices. See [`VoxelMesh::needs_u32_indices`].
+    pub fn with_u32_indices(mut self, u32_indices: bool) -> Self {
+        self.u32_indices = u32_indices;
+        self
+    }
+}
+
 /// A mesh made up of one or more voxels.
 #[derive(Component, Debug, Default, Clone)]
 pub struct VoxelMesh {
@@ -83,11 +144,96 @@ pub struct VoxelFace {
     /// These describe how to draw the face using two triangles.
     /// Each entry is an index into the `positions` array.
     pub indices: [u8; 6],
+
+    /// The light level (0-15) that the face is lit with.
+    ///
+    /// See [`light::face_light_level`].
+    pub light: u8,
+}
+
+impl VoxelFace {
+    /// Returns the tangent of the face, in the `[x, y, z, w]` form expected by
+    /// [`Mesh::ATTRIBUTE_TANGENT`].
+    ///
+    /// `[x, y, z]` is the direction in which `u` increases across the face, and
+    /// `w` is the sign (1 or -1) such that `normal.cross(tangent) * w` is the
+    /// direction in which `v` increases.
+    ///
+    /// Faces are flat and axis-aligned, so every vertex of a face has the same
+    /// tangent, and it can be found from the face's first triangle alone.
+    pub fn tangent(&self) -> [f32; 4] {
+        let [i0, i1, i2] = [0, 1, 2].map(|i| self.indices[i] as usize);
+        let [p0, p1, p2] = [i0, i1, i2].map(|i| Vec3::from(self.positions[i]));
+        let [uv0, uv1, uv2] = [i0, i1, i2].map(|i| Vec2::from(self.tex_coords[i]));
+
+        let (edge1, edge2) = (p1 - p0, p2 - p0);
+        let (delta_uv1, delta_uv2) = (uv1 - uv0, uv2 - uv0);
+
+        let det = delta_uv1.x * delta_uv2.y - delta_uv2.x * delta_uv1.y;
+        if det == 0.0 {
+            // The texture isn't mapped onto the face, so any direction in the
+            // plane of the face will do.
+            return self.axis.fallback_tangent();
+        }
+
+        let tangent = ((edge1 * delta_uv2.y - edge2 * delta_uv1.y) / det).normalize();
+        let bitangent = (edge2 * delta_uv1.x - edge1 * delta_uv2.x) / det;
+
+        let normal = Vec3::from(self.axis.normal().map(|elt| elt as f32));
+        let w = if normal.cross(tangent).dot(bitangent) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+
+        tangent.extend(w).into()
+    }
 }
 
 impl VoxelMesh {
+    /// Returns the number of vertices in the mesh, four for each face.
+    #[inline]
+    pub fn num_vertices(&self) -> usize {
+        self.faces.len() * 4
+    }
+
+    /// Returns true if the [render mesh][Self::to_render_mesh] needs `u32`
+    /// indices, because it has more vertices than `u16` indices can address.
+    #[inline]
+    pub fn needs_u32_indices(&self) -> bool {
+        self.num_vertices() > u16::MAX as usize
+    }
+
+    /// Appends the faces of `other` to this mesh, with their positions
+    /// translated by `offset`.
+    ///
+    /// Each face's indices refer only to its own four vertices, so they are
+    /// re-based when the render mesh is built. The [`voxel`][VoxelFace::voxel]
+    /// of each merged face is left as is.
+    pub fn merge(&mut self, other: &VoxelMesh, offset: Vec3) {
+        self.faces.reserve(other.faces.len());
+
+        for face in other.faces.iter() {
+            let mut face = face.clone();
+            face.positions = face
+                .positions
+                .map(|position| (Vec3::from(position) + offset).into());
+            self.faces.push(face);
+        }
+    }
+
+    /// Converts the mesh into a render mesh with the default
+    /// [`RenderMeshOptions`].
     pub fn to_render_mesh(&self) -> Mesh {
-        let num_vertices = self.faces.len() * 4;
+        self.to_render_mesh_with(RenderMeshOptions::default())
+    }
+
+    /// Converts the mesh into a render mesh.
+    ///
+    /// The mesh has `u16` indices unless it [needs `u32`
+    /// indices][Self::needs_u32_indices] or `options` asks for them.
+    pub fn to_render_mesh_with(&self, options: RenderMeshOptions) -> Mesh {
+        let num_vertices = self.num_vertices();
         let mut positions = Vec::with_capacity(num_vertices);
         let mut tex_coords = Vec::with_capacity(num_vertices);
         let mut normals = Vec::with_capacity(num_vertices);
@@ -100,7 +246,7 @@ impl VoxelMesh {
             normals.extend_from_slice(&[normal; 4]);
         }
 
-        let indices = if num_vertices > u16::MAX as usize {
+        let indices = if options.u32_indices || self.needs_u32_indices() {
             Indices::U32(self.get_indices::<u32>())
         } else {
             Indices::U16(self.get_indices::<u16>())
@@ -113,6 +259,42 @@ impl VoxelMesh {
         mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
         mesh.set_indices(Some(indices));
 
+        if options.tangents {
+            let mut tangents = Vec::with_capacity(num_vertices);
+            for face in self.faces.iter() {
+                tangents.extend_from_slice(&[face.tangent(); 4]);
+            }
+
+            mesh.set_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+        }
+
+        mesh
+    }
+
+    /// Like [`to_render_mesh`][Self::to_render_mesh], but also writes each
+    /// face's [`light::brightness`] into the vertex color attribute.
+    ///
+    /// **Note:** `StandardMaterial` ignores vertex colors and expects meshes
+    /// to have only positions, normals, and UVs, so this mesh must be rendered
+    /// with a material whose pipeline reads [`Mesh::ATTRIBUTE_COLOR`], such as
+    /// [`VoxelMaterial`][crate::material::VoxelMaterial].
+    pub fn to_lit_render_mesh(&self) -> Mesh {
+        self.to_lit_render_mesh_with(RenderMeshOptions::default())
+    }
+
+    /// Like [`to_render_mesh_with`][Self::to_render_mesh_with], but also writes
+    /// each face's [`light::brightness`] into the vertex color attribute.
+    pub fn to_lit_render_mesh_with(&self, options: RenderMeshOptions) -> Mesh {
+        let mut mesh = self.to_render_mesh_with(options);
+
+        let mut colors = Vec::with_capacity(self.faces.len() * 4);
+        for face in self.faces.iter() {
+            let brightness = light::brightness(face.light);
+            colors.extend_from_slice(&[[brightness, brightness, brightness, 1.0]; 4]);
+        }
+
+        mesh.set_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
         mesh
     }
 
@@ -126,6 +308,18 @@ impl VoxelMesh {
         }
     }
 
+    /// Like [`adjust_tex_coords`][Self::adjust_tex_coords], but for an atlas
+    /// whose regions are already known: each face's texture coordinates are
+    /// mapped into the UV region (`0.0` to `1.0` scale) given for it.
+    pub fn adjust_tex_coords_to_regions(&mut self, face_regions: &[Rect]) {
+        for (face, region) in self.faces.iter_mut().zip(face_regions.iter()) {
+            let size = region.max - region.min;
+            face.tex_coords = face
+                .tex_coords
+                .map(|uv| (region.min + Vec2::from(uv) * size).into());
+        }
+    }
+
     fn get_tex_coords(
         face: &VoxelFace,
         texture_atlas: &TextureAtlas,
@@ -168,3 +362,204 @@ impl VoxelMesh {
         all_indices
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn face(voxel: [u8; 3]) -> VoxelFace {
+        VoxelFace {
+            voxel,
+            axis: Axis::ZNeg,
+            positions: [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+            tex_coords: [[0.0, 1.0], [1.0, 1.0], [0.0, 0.0], [1.0, 0.0]],
+            indices: [0, 1, 2, 1, 3, 2],
+            light: 15,
+        }
+    }
+
+    fn mesh(num_faces: usize) -> VoxelMesh {
+        VoxelMesh {
+            faces: vec![face([0, 0, 0]); num_faces],
+        }
+    }
+
+    #[test]
+    fn merge_translates_positions_and_rebases_indices() {
+        let mut combined = mesh(1);
+        combined.merge(&mesh(2), Vec3::new(0.0, 16.0, 0.0));
+
+        assert_eq!(combined.faces.len(), 3);
+        assert_eq!(combined.faces[0].positions[3], [1.0, 1.0, 0.0]);
+        assert_eq!(combined.faces[2].positions[3], [1.0, 17.0, 0.0]);
+        assert_eq!(combined.faces[2].tex_coords, face([0, 0, 0]).tex_coords);
+
+        let render_mesh = combined.to_render_mesh();
+        let indices: Vec<usize> = render_mesh.indices().unwrap().iter().collect();
+        assert_eq!(
+            indices,
+            vec![0, 1, 2, 1, 3, 2, 4, 5, 6, 5, 7, 6, 8, 9, 10, 9, 11, 10]
+        );
+    }
+
+    #[test]
+    fn tex_coords_to_regions() {
+        let mut mesh = mesh(2);
+        mesh.adjust_tex_coords_to_regions(&[
+            Rect {
+                min: Vec2::new(0.5, 0.0),
+                max: Vec2::new(1.0, 0.25),
+            },
+            Rect {
+                min: Vec2::new(0.0, 0.5),
+                max: Vec2::new(0.25, 1.0),
+            },
+        ]);
+
+        assert_eq!(
+            mesh.faces[0].tex_coords,
+            [[0.5, 0.25], [1.0, 0.25], [0.5, 0.0], [1.0, 0.0]]
+        );
+        assert_eq!(
+            mesh.faces[1].tex_coords,
+            [[0.0, 1.0], [0.25, 1.0], [0.0, 0.5], [0.25, 0.5]]
+        );
+    }
+
+    #[test]
+    fn index_width() {
+        let max_u16_faces = u16::MAX as usize / 4;
+
+        let mut combined = mesh(max_u16_faces);
+        assert!(matches!(
+            combined.to_render_mesh().indices(),
+            Some(Indices::U16(_))
+        ));
+
+        combined.merge(&mesh(1), Vec3::ZERO);
+        assert!(combined.needs_u32_indices());
+
+        let render_mesh = combined.to_render_mesh();
+        let indices = render_mesh.indices().unwrap();
+        assert!(matches!(indices, Indices::U32(_)));
+        assert_eq!(indices.iter().max(), Some(combined.num_vertices() - 1));
+    }
+
+    /// Builds each of the six faces of a voxel the same way the block-mesh
+    /// chunk builders do.
+    fn block_mesh_faces() -> Vec<VoxelFace> {
+        use block_mesh::{UnorientedQuad, RIGHT_HANDED_Y_UP_CONFIG};
+
+        let axes = [
+            Axis::XPos,
+            Axis::XNeg,
+            Axis::YPos,
+            Axis::YNeg,
+            Axis::ZPos,
+            Axis::ZNeg,
+        ];
+        let quad = UnorientedQuad {
+            minimum: [0, 0, 0],
+            width: 1,
+            height: 1,
+        };
+
+        RIGHT_HANDED_Y_UP_CONFIG
+            .faces
+            .iter()
+            .map(|face| {
+                let normal = face.signed_normal().to_array().map(|elt| elt as i8);
+                let axis = *axes.iter().find(|axis| axis.normal() == normal).unwrap();
+
+                VoxelFace {
+                    voxel: [0, 0, 0],
+                    axis,
+                    positions: face.quad_mesh_positions(&quad, 1.0),
+                    tex_coords: face.tex_coords(RIGHT_HANDED_Y_UP_CONFIG.u_flip_face, true, &quad),
+                    indices: face.quad_mesh_indices(0).map(|i| i as u8),
+                    light: 15,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the direction from one vertex of the face to another that
+    /// differs from it only in the given tex coord (0 for u, 1 for v).
+    fn direction_of_increasing(face: &VoxelFace, coord: usize) -> Vec3 {
+        let other = 1 - coord;
+        for a in 0..4 {
+            for b in 0..4 {
+                let (uv_a, uv_b) = (face.tex_coords[a], face.tex_coords[b]);
+                if uv_a[other] == uv_b[other] && uv_b[coord] > uv_a[coord] {
+                    let delta = Vec3::from(face.positions[b]) - Vec3::from(face.positions[a]);
+                    return delta.normalize();
+                }
+            }
+        }
+        unreachable!("face has no two vertices that differ in only one tex coord");
+    }
+
+    #[test]
+    fn tangent_handedness() {
+        let faces = block_mesh_faces();
+        assert_eq!(faces.len(), 6);
+
+        for face in faces.iter() {
+            let [x, y, z, w] = face.tangent();
+            let tangent = Vec3::new(x, y, z);
+            let normal = Vec3::from(face.axis.normal().map(|elt| elt as f32));
+
+            assert_eq!(w.abs(), 1.0, "{:?}", face.axis);
+            assert!(tangent.dot(normal).abs() < 1e-6, "{:?}", face.axis);
+
+            let u_direction = direction_of_increasing(face, 0);
+            assert!(tangent.abs_diff_eq(u_direction, 1e-6), "{:?}", face.axis);
+
+            let bitangent = normal.cross(tangent) * w;
+            let v_direction = direction_of_increasing(face, 1);
+            assert!(bitangent.abs_diff_eq(v_direction, 1e-6), "{:?}", face.axis);
+        }
+    }
+
+    #[test]
+    fn tangent_handedness_flips_with_mirrored_texture() {
+        for mut face in block_mesh_faces() {
+            let [.., w] = face.tangent();
+
+            for uv in face.tex_coords.iter_mut() {
+                uv[1] = 1.0 - uv[1];
+            }
+            let [.., mirrored_w] = face.tangent();
+
+            assert_eq!(mirrored_w, -w, "{:?}", face.axis);
+        }
+    }
+
+    #[test]
+    fn tangents_are_optional() {
+        let voxel_mesh = VoxelMesh {
+            faces: block_mesh_faces(),
+        };
+
+        let render_mesh = voxel_mesh.to_render_mesh();
+        assert!(render_mesh.attribute(Mesh::ATTRIBUTE_TANGENT).is_none());
+        assert!(matches!(render_mesh.indices(), Some(Indices::U16(_))));
+
+        let options = RenderMeshOptions::new()
+            .with_tangents(true)
+            .with_u32_indices(true);
+        let render_mesh = voxel_mesh.to_render_mesh_with(options);
+        assert_eq!(
+            render_mesh
+                .attribute(Mesh::ATTRIBUTE_TANGENT)
+                .map(|tangents| tangents.len()),
+            Some(voxel_mesh.num_vertices())
+        );
+        assert!(matches!(render_mesh.indices(), Some(Indices::U32(_))));
+    }
+}
@@ -0,0 +1,458 @@
+//! Finding chunk sections that are hidden behind opaque terrain.
+//!
+//! This is the same visibility graph that vanilla Minecraft uses. When a chunk
+//! is built, each of its sections is flood filled through its non-opaque
+//! blocks to find which of the section's six faces can see each other (see
+//! [`SectionConnectivity`]). Each frame, a breadth-first search over sections
+//! that starts at the camera's section finds every section that a line of
+//! sight could reach (see [`SectionGraph`]). Sections that are never reached,
+//! like the solid ground underneath the player and the caves within it, can be
+//! hidden without anyone noticing.
+//!
+//! The culling itself is done by the
+//! [`ChunkVisibilityPlugin`][crate::visibility::ChunkVisibilityPlugin].
+
+use std::collections::VecDeque;
+
+use bevy::{
+    math::{IVec3, Vec3},
+    utils::{HashMap, HashSet},
+};
+
+use brine_chunk::{
+    BlockState, BlockStates, ChunkSection, BLOCKS_PER_SECTION, SECTIONS_PER_CHUNK, SECTION_HEIGHT,
+    SECTION_WIDTH,
+};
+use brine_data::{BlockStateId, MinecraftData};
+
+use crate::mesh::Axis;
+
+/// Which faces of a chunk section can see each other through the section's
+/// non-opaque blocks.
+///
+/// This is a symmetric 6x6 matrix of booleans, one row and one column per
+/// [`Axis`], packed into the low 36 bits of a `u64`.
+///
+/// The default is [`OPEN`][Self::OPEN], which is right for sections without
+/// any blocks and safe for sections whose connectivity is unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectionConnectivity(u64);
+
+impl SectionConnectivity {
+    /// Every face can see every other face, e.g., in a section of air.
+    pub const OPEN: Self = Self((1 << 36) - 1);
+
+    /// No face can see any other face, e.g., in a section of stone.
+    pub const CLOSED: Self = Self(0);
+
+    /// Flood fills the section's non-opaque blocks to find which of its faces
+    /// can see each other.
+    ///
+    /// Every block is visited at most once, so this is cheap enough to do for
+    /// every section that gets built.
+    pub fn compute(section: &ChunkSection, is_opaque: impl Fn(BlockState) -> bool) -> Self {
+        if section.block_count == 0 {
+            return Self::OPEN;
+        }
+
+        // Opaque blocks start out visited so that the flood fill never enters
+        // them.
+        let mut visited = [0u64; BLOCKS_PER_SECTION / 64];
+        for (index, block_state) in section.block_states.0.iter().enumerate() {
+            if is_opaque(*block_state) {
+                visited[index / 64] |= 1 << (index % 64);
+            }
+        }
+
+        let mut connectivity = Self::CLOSED;
+        let mut queue = VecDeque::new();
+
+        for start in 0..BLOCKS_PER_SECTION {
+            if !visit(&mut visited, start) {
+                continue;
+            }
+
+            // Every face touched by the same region of non-opaque blocks can
+            // see every other.
+            let mut faces = 0u8;
+
+            queue.push_back(start);
+            while let Some(index) = queue.pop_front() {
+                let (x, y, z) = BlockStates::index_to_xyz(index);
+
+                for axis in Axis::ALL {
+                    match neighbor(x, y, z, axis) {
+                        Some(neighbor) => {
+                            if visit(&mut visited, neighbor) {
+                                queue.push_back(neighbor);
+                            }
+                        }
+                        None => faces |= 1 << axis as u8,
+                    }
+                }
+            }
+
+            connectivity.connect_all(faces);
+        }
+
+        connectivity
+    }
+
+    /// Returns whether the `from` face of the section can see the `to` face.
+    #[inline]
+    pub fn is_connected(&self, from: Axis, to: Axis) -> bool {
+        self.0 & Self::bit(from, to) != 0
+    }
+
+    /// Connects every pair of faces in the given mask, which has bit `axis`
+    /// set for every [`Axis`] in it.
+    fn connect_all(&mut self, faces: u8) {
+        let in_faces = |axis: &Axis| faces & (1 << *axis as u8) != 0;
+
+        for from in Axis::ALL.iter().filter(|axis| in_faces(axis)) {
+            for to in Axis::ALL.iter().filter(|axis| in_faces(axis)) {
+                self.0 |= Self::bit(*from, *to);
+            }
+        }
+    }
+
+    #[inline]
+    fn bit(from: Axis, to: Axis) -> u64 {
+        1 << (from as u8 * 6 + to as u8)
+    }
+}
+
+impl Default for SectionConnectivity {
+    fn default() -> Self {
+        Self::OPEN
+    }
+}
+
+/// Marks the block at `index` as visited, returning false if it already was.
+#[inline]
+fn visit(visited: &mut [u64; BLOCKS_PER_SECTION / 64], index: usize) -> bool {
+    let (word, bit) = (index / 64, 1 << (index % 64));
+    let unvisited = visited[word] & bit == 0;
+    visited[word] |= bit;
+    unvisited
+}
+
+/// Returns the index of the block next to `[x, y, z]` along `axis`, or `None`
+/// if it's in a different section.
+#[inline]
+fn neighbor(x: u8, y: u8, z: u8, axis: Axis) -> Option<usize> {
+    let [dx, dy, dz] = axis.normal();
+    let step = |coord: u8, delta: i8| {
+        let coord = coord as i8 + delta;
+        (0..SECTION_WIDTH as i8)
+            .contains(&coord)
+            .then(|| coord as u8)
+    };
+
+    Some(BlockStates::xyz_to_index(
+        step(x, dx)?,
+        step(y, dy)?,
+        step(z, dz)?,
+    ))
+}
+
+/// Returns whether each block state is opaque, indexed by block state id.
+///
+/// Block states that Minecraft considers transparent (air, fluids, glass,
+/// leaves, slabs, and the like) are not opaque, so sections are only ever
+/// hidden behind blocks that really can't be seen through.
+pub fn opacity_table(mc_data: &MinecraftData) -> Vec<bool> {
+    let blocks = mc_data.blocks();
+
+    (0..blocks.state_id_to_block.len())
+        .map(|id| {
+            blocks
+                .get_by_state_id(BlockStateId(id as _))
+                .map_or(false, |block| !block.transparent)
+        })
+        .collect()
+}
+
+/// Returns the position of the chunk section that contains the given point in
+/// world space, in units of sections.
+pub fn section_containing(point: Vec3) -> IVec3 {
+    let size = Vec3::new(
+        SECTION_WIDTH as f32,
+        SECTION_HEIGHT as f32,
+        SECTION_WIDTH as f32,
+    );
+
+    (point / size).floor().as_ivec3()
+}
+
+/// The [`SectionConnectivity`] of every loaded chunk section, keyed by the
+/// section's position (see [`section_containing`]).
+///
+/// Sections that aren't in the graph are never reached. That stops the search
+/// at the edge of the loaded world, and above and below it.
+#[derive(Debug, Default, Clone)]
+pub struct SectionGraph {
+    sections: HashMap<IVec3, SectionConnectivity>,
+}
+
+impl SectionGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every section of the chunk whose bottom section is at `chunk`.
+    ///
+    /// Sections that haven't been given a connectivity with
+    /// [`insert`][Self::insert] are [`OPEN`][SectionConnectivity::OPEN], since
+    /// chunks don't have a built section for sections without blocks.
+    pub fn insert_chunk(&mut self, chunk: IVec3) {
+        for section_y in 0..SECTIONS_PER_CHUNK as i32 {
+            self.sections
+                .entry(chunk + IVec3::Y * section_y)
+                .or_insert(SectionConnectivity::OPEN);
+        }
+    }
+
+    /// Sets the connectivity of the section at `section`.
+    pub fn insert(&mut self, section: IVec3, connectivity: SectionConnectivity) {
+        self.sections.insert(section, connectivity);
+    }
+
+    /// Returns the sections that can be seen from the section at `start`, or
+    /// `None` if `start` isn't in the graph (e.g., when the camera is above
+    /// the world).
+    ///
+    /// A section is entered through one of its faces, and left through the
+    /// faces that are connected to that one. Like in vanilla, the search never
+    /// heads back in a direction opposite to one it has already taken, since a
+    /// line of sight can't do that either.
+    pub fn reachable_from(&self, start: IVec3) -> Option<HashSet<IVec3>> {
+        if !self.sections.contains_key(&start) {
+            return None;
+        }
+
+        struct Step {
+            section: IVec3,
+            entered_through: Option<Axis>,
+            directions: u8,
+        }
+
+        let mut reachable = HashSet::default();
+        reachable.insert(start);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(Step {
+            section: start,
+            entered_through: None,
+            directions: 0,
+        });
+
+        while let Some(step) = queue.pop_front() {
+            let connectivity = self.sections[&step.section];
+
+            for exit in Axis::ALL {
+                if step.directions & (1 << exit.opposite() as u8) != 0 {
+                    continue;
+                }
+
+                if let Some(entry) = step.entered_through {
+                    if !connectivity.is_connected(entry, exit) {
+                        continue;
+                    }
+                }
+
+                let [dx, dy, dz] = exit.normal();
+                let next = step.section + IVec3::new(dx as i32, dy as i32, dz as i32);
+
+                if !self.sections.contains_key(&next) || !reachable.insert(next) {
+                    continue;
+                }
+
+                queue.push_back(Step {
+                    section: next,
+                    entered_through: Some(exit.opposite()),
+                    directions: step.directions | (1 << exit as u8),
+                });
+            }
+        }
+
+        Some(reachable)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const STONE: BlockState = BlockState(1);
+
+    fn is_opaque(block_state: BlockState) -> bool {
+        block_state != BlockState::AIR
+    }
+
+    fn solid_section() -> ChunkSection {
+        let mut section = ChunkSection::empty(0);
+        section.block_states = BlockStates([STONE; BLOCKS_PER_SECTION]);
+        section.block_count = BLOCKS_PER_SECTION as u16;
+        section
+    }
+
+    fn set_air(section: &mut ChunkSection, x: u8, y: u8, z: u8) {
+        section.block_states.0[BlockStates::xyz_to_index(x, y, z)] = BlockState::AIR;
+        section.block_count -= 1;
+    }
+
+    fn connected_pairs(connectivity: SectionConnectivity) -> Vec<(Axis, Axis)> {
+        Axis::ALL
+            .iter()
+            .flat_map(|from| Axis::ALL.iter().map(move |to| (*from, *to)))
+            .filter(|(from, to)| from != to && connectivity.is_connected(*from, *to))
+            .collect()
+    }
+
+    #[test]
+    fn empty_section_is_open() {
+        let section = ChunkSection::empty(0);
+
+        assert_eq!(
+            SectionConnectivity::compute(&section, is_opaque),
+            SectionConnectivity::OPEN
+        );
+    }
+
+    #[test]
+    fn solid_section_is_closed() {
+        assert_eq!(
+            SectionConnectivity::compute(&solid_section(), is_opaque),
+            SectionConnectivity::CLOSED
+        );
+    }
+
+    #[test]
+    fn hollow_box_is_closed() {
+        let mut section = solid_section();
+        for y in 1..15 {
+            for z in 1..15 {
+                for x in 1..15 {
+                    set_air(&mut section, x, y, z);
+                }
+            }
+        }
+
+        assert_eq!(
+            SectionConnectivity::compute(&section, is_opaque),
+            SectionConnectivity::CLOSED
+        );
+    }
+
+    #[test]
+    fn straight_tunnel_connects_its_ends() {
+        let mut section = solid_section();
+        for x in 0..16 {
+            set_air(&mut section, x, 7, 9);
+        }
+
+        let connectivity = SectionConnectivity::compute(&section, is_opaque);
+        assert_eq!(
+            connected_pairs(connectivity),
+            [(Axis::XPos, Axis::XNeg), (Axis::XNeg, Axis::XPos)]
+        );
+    }
+
+    #[test]
+    fn bent_tunnel_connects_its_ends() {
+        let mut section = solid_section();
+        for x in 0..8 {
+            set_air(&mut section, x, 3, 3);
+        }
+        for y in 4..16 {
+            set_air(&mut section, 7, y, 3);
+        }
+
+        let connectivity = SectionConnectivity::compute(&section, is_opaque);
+        assert!(connectivity.is_connected(Axis::XNeg, Axis::YPos));
+        assert!(connectivity.is_connected(Axis::YPos, Axis::XNeg));
+        assert_eq!(connected_pairs(connectivity).len(), 2);
+    }
+
+    #[test]
+    fn transparent_blocks_connect() {
+        const GLASS: BlockState = BlockState(2);
+
+        let mut section = solid_section();
+        for y in 0..16 {
+            section.block_states.0[BlockStates::xyz_to_index(0, y, 0)] = GLASS;
+        }
+
+        let connectivity =
+            SectionConnectivity::compute(&section, |block| is_opaque(block) && block != GLASS);
+        assert!(connectivity.is_connected(Axis::YNeg, Axis::YPos));
+        assert!(connectivity.is_connected(Axis::XNeg, Axis::ZNeg));
+    }
+
+    #[test]
+    fn section_containing_point() {
+        assert_eq!(section_containing(Vec3::new(0.0, 0.0, 0.0)), IVec3::ZERO);
+        assert_eq!(
+            section_containing(Vec3::new(31.9, 64.0, -0.5)),
+            IVec3::new(1, 4, -1)
+        );
+    }
+
+    #[test]
+    fn closed_section_hides_what_is_behind_it() {
+        let mut graph = SectionGraph::new();
+        graph.insert(IVec3::ZERO, SectionConnectivity::OPEN);
+        graph.insert(IVec3::X, SectionConnectivity::CLOSED);
+        graph.insert(IVec3::X * 2, SectionConnectivity::OPEN);
+
+        let reachable = graph.reachable_from(IVec3::ZERO).unwrap();
+        assert!(reachable.contains(&IVec3::ZERO));
+        assert!(reachable.contains(&IVec3::X));
+        assert!(!reachable.contains(&(IVec3::X * 2)));
+
+        let mut tunnel = SectionConnectivity::CLOSED;
+        tunnel.connect_all((1 << Axis::XNeg as u8) | (1 << Axis::XPos as u8));
+        graph.insert(IVec3::X, tunnel);
+
+        let reachable = graph.reachable_from(IVec3::ZERO).unwrap();
+        assert!(reachable.contains(&(IVec3::X * 2)));
+    }
+
+    #[test]
+    fn search_never_turns_back() {
+        // The only way from the start to the target is around a wall: over,
+        // down twice, and back again.
+        let start = IVec3::ZERO;
+        let wall = IVec3::new(0, -1, 0);
+        let target = IVec3::new(0, -2, 0);
+
+        let mut graph = SectionGraph::new();
+        for section in [
+            start,
+            target,
+            IVec3::new(1, 0, 0),
+            IVec3::new(1, -1, 0),
+            IVec3::new(1, -2, 0),
+        ] {
+            graph.insert(section, SectionConnectivity::OPEN);
+        }
+        graph.insert(wall, SectionConnectivity::CLOSED);
+
+        let reachable = graph.reachable_from(start).unwrap();
+        assert!(reachable.contains(&wall));
+        assert!(reachable.contains(&IVec3::new(1, -2, 0)));
+        assert!(!reachable.contains(&target));
+    }
+
+    #[test]
+    fn chunks_are_open_by_default() {
+        let mut graph = SectionGraph::new();
+        graph.insert(IVec3::new(0, 3, 0), SectionConnectivity::CLOSED);
+        graph.insert_chunk(IVec3::ZERO);
+
+        let reachable = graph.reachable_from(IVec3::new(0, 15, 0)).unwrap();
+        assert_eq!(reachable.len(), 13);
+        assert!(graph.reachable_from(IVec3::new(0, 16, 0)).is_none());
+    }
+}
@@ -1,15 +1,21 @@
-use std::collections::hash_map::Entry;
+use std::{collections::hash_map::Entry, time::Instant};
 
 use bevy::{
     asset::{AssetPath, HandleId, LoadState},
     prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
     utils::HashMap,
 };
+use futures_lite::future;
 
 use brine_data::blocks::BlockStateId;
+use brine_render::texture::{stitch_images, AtlasMainThreadTime, StitchedImage};
 
 const PLACEHOLDER_PATH: &str = "placeholder.png";
 
+/// The largest atlas that is stitched, in pixels on each side.
+const MAX_ATLAS_SIZE: u32 = 2048;
+
 struct PendingAtlas {
     /// Strong handle to each texture that will eventually be added to the atlas.
     textures: Vec<Handle<Image>>,
@@ -27,6 +33,18 @@ impl PendingAtlas {
     }
 }
 
+/// Texture atlas whose textures are being stitched together on the
+/// [`AsyncComputeTaskPool`].
+struct StitchingAtlas {
+    /// Weak handle to each texture that is being added to the atlas, in order.
+    textures: Vec<Handle<Image>>,
+
+    /// Strong handle that we will populate with the atlas once it's stitched.
+    handle: Handle<TextureAtlas>,
+
+    task: Task<Option<StitchedImage>>,
+}
+
 #[derive(Default)]
 pub struct BlockTextures {
     /// Strong handle to a placeholder texture.
@@ -38,6 +56,9 @@ pub struct BlockTextures {
     /// Texture atlases that have yet to be built because not all of the
     /// textures have loaded yet.
     pending_atlases: Vec<PendingAtlas>,
+
+    /// Texture atlases that are being stitched.
+    stitching_atlases: Vec<StitchingAtlas>,
 }
 
 impl BlockTextures {
@@ -135,18 +156,24 @@ impl BlockTextures {
         self.create_texture_atlas_with_textures(textures, asset_server)
     }
 
-    /// Builds a [`TextureAtlas`] out of each [`PendingAtlas`] that is ready to
-    /// be built, and inserts it into [`Assets<TextureAtlas>`].
-    pub(crate) fn finish_texture_atlases(
+    /// Starts stitching a [`TextureAtlas`] out of each [`PendingAtlas`] that is
+    /// ready to be built.
+    ///
+    /// The textures are copied so that they can be stitched on the task pool,
+    /// without holding on to [`Assets<Image>`].
+    pub(crate) fn stitch_texture_atlases(
         &mut self,
         asset_server: &AssetServer,
-        texture_atlases: &mut Assets<TextureAtlas>,
-        textures: &mut Assets<Image>,
+        textures: &Assets<Image>,
+        task_pool: &AsyncComputeTaskPool,
     ) {
         if asset_server.get_load_state(&self.placeholder_texture) != LoadState::Loaded {
             return;
         }
 
+        let placeholder_texture = &self.placeholder_texture;
+        let stitching_atlases = &mut self.stitching_atlases;
+
         self.pending_atlases.retain(|pending_atlas| {
             if pending_atlas.all_textures_loaded(asset_server) {
                 debug!(
@@ -154,33 +181,37 @@ impl BlockTextures {
                     pending_atlas.textures.len()
                 );
 
-                let mut builder = TextureAtlasBuilder::default();
-
-                for handle in pending_atlas.textures.iter() {
-                    let handle = if textures.contains(handle) {
-                        handle
-                    } else {
-                        debug!(
-                            "not loaded: {:?} - {:?}",
-                            asset_server.get_load_state(handle),
-                            asset_server
-                                .get_handle_path(handle)
-                                .map(|path| path.path().to_string_lossy().to_string())
-                        );
-                        &self.placeholder_texture
-                    };
-
-                    assert!(textures.get(handle).is_some());
-
-                    builder.add_texture(handle.clone_weak(), textures.get(handle).unwrap());
-                }
-
-                let atlas = builder.finish(textures).unwrap();
-
-                // It's okay to ignore the returned handle, we know that we
-                // already vended out at least one strong handle when
-                // `create_texture_atlas` was called.
-                let _ = texture_atlases.set(&pending_atlas.handle, atlas);
+                let handles: Vec<Handle<Image>> = pending_atlas
+                    .textures
+                    .iter()
+                    .map(|handle| {
+                        if textures.contains(handle) {
+                            handle.clone_weak()
+                        } else {
+                            debug!(
+                                "not loaded: {:?} - {:?}",
+                                asset_server.get_load_state(handle),
+                                asset_server
+                                    .get_handle_path(handle)
+                                    .map(|path| path.path().to_string_lossy().to_string())
+                            );
+                            placeholder_texture.clone_weak()
+                        }
+                    })
+                    .collect();
+
+                let images: Vec<Image> = handles
+                    .iter()
+                    .map(|handle| textures.get(handle).unwrap().clone())
+                    .collect();
+
+                let task = task_pool.spawn(async move { stitch_images(&images, MAX_ATLAS_SIZE) });
+
+                stitching_atlases.push(StitchingAtlas {
+                    textures: handles,
+                    handle: pending_atlas.handle.clone(),
+                    task,
+                });
 
                 false
             } else {
@@ -188,6 +219,47 @@ impl BlockTextures {
             }
         });
     }
+
+    /// Inserts each [`TextureAtlas`] that has finished stitching into
+    /// [`Assets<TextureAtlas>`].
+    pub(crate) fn finish_texture_atlases(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Image>,
+    ) {
+        let mut still_stitching = Vec::new();
+
+        for mut stitching_atlas in std::mem::take(&mut self.stitching_atlases) {
+            let stitched = match future::block_on(future::poll_once(&mut stitching_atlas.task)) {
+                Some(stitched) => stitched.expect("textures must fit in the atlas"),
+                None => {
+                    still_stitching.push(stitching_atlas);
+                    continue;
+                }
+            };
+
+            let texture_handles = stitching_atlas
+                .textures
+                .into_iter()
+                .enumerate()
+                .map(|(index, handle)| (handle, index))
+                .collect();
+
+            let atlas = TextureAtlas {
+                size: stitched.size(),
+                texture: textures.add(stitched.image),
+                textures: stitched.rects,
+                texture_handles: Some(texture_handles),
+            };
+
+            // It's okay to ignore the returned handle, we know that we
+            // already vended out at least one strong handle when
+            // `create_texture_atlas` was called.
+            let _ = texture_atlases.set(&stitching_atlas.handle, atlas);
+        }
+
+        self.stitching_atlases = still_stitching;
+    }
 }
 
 /// Plugin that assembles texture atlases for voxel meshes.
@@ -211,14 +283,23 @@ impl TextureBuilderPlugin {
         block_textures.placeholder_texture = asset_server.load(PLACEHOLDER_PATH);
     }
 
-    /// This system calls [`BlockTextures::finish_texture_atlases`] once per
-    /// frame to build texture atlases that are ready to be built.
+    /// This system adds the texture atlases that have been stitched, and starts
+    /// stitching the ones that are ready to be built, once per frame.
     fn finish_texture_atlases(
         asset_server: Res<AssetServer>,
         mut block_textures: ResMut<BlockTextures>,
         mut texture_atlases: ResMut<Assets<TextureAtlas>>,
         mut textures: ResMut<Assets<Image>>,
+        task_pool: Res<AsyncComputeTaskPool>,
+        main_thread_time: Option<ResMut<AtlasMainThreadTime>>,
     ) {
-        block_textures.finish_texture_atlases(&asset_server, &mut texture_atlases, &mut textures);
+        let start = Instant::now();
+
+        block_textures.finish_texture_atlases(&mut texture_atlases, &mut textures);
+        block_textures.stitch_texture_atlases(&asset_server, &textures, &task_pool);
+
+        if let Some(mut main_thread_time) = main_thread_time {
+            main_thread_time.0 += start.elapsed();
+        }
     }
 }
@@ -0,0 +1,288 @@
+//! Hiding built chunk sections that are outside of the camera's view.
+//!
+//! Each frame, every [`BuiltChunkSection`]'s bounding box is tested against the
+//! view frustum of the 3D camera, and the section's [`Visibility`] is updated
+//! accordingly. Sections that can't be seen from the camera's section through
+//! the sections in between are hidden too (see [`occlusion`] and
+//! [`OcclusionCulling`]). The number of visible, occluded, and total sections
+//! are reported as [`Diagnostics`] ([`VISIBLE_SECTIONS`],
+//! [`OCCLUDED_SECTIONS`], and [`TOTAL_SECTIONS`]).
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+    render::camera::{Camera, CameraPlugin},
+    transform::TransformSystem,
+};
+
+use brine_chunk::{SECTION_HEIGHT, SECTION_WIDTH};
+
+use crate::{
+    chunk_builder::component::{BuiltChunk, BuiltChunkSection},
+    occlusion::{self, SectionGraph},
+};
+
+/// Number of built chunk sections that were visible last frame.
+pub const VISIBLE_SECTIONS: DiagnosticId =
+    DiagnosticId::from_u128(0x6d3f_0c5e_41a2_4b8e_9f67_2a1c_8e04_d7b1);
+
+/// Number of built chunk sections that were hidden by occlusion culling last
+/// frame.
+pub const OCCLUDED_SECTIONS: DiagnosticId =
+    DiagnosticId::from_u128(0x3c71_9a0e_d254_4e83_b1f9_6e2d_48a5_0c37);
+
+/// Number of built chunk sections in the world last frame.
+pub const TOTAL_SECTIONS: DiagnosticId =
+    DiagnosticId::from_u128(0x1b94_e2a7_5c3d_4f60_8a2e_97d4_03c6_5f18);
+
+/// Plugin that frustum and occlusion culls [`BuiltChunkSection`]s.
+pub struct ChunkVisibilityPlugin;
+
+impl Plugin for ChunkVisibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OcclusionCulling>();
+        app.add_startup_system(setup_diagnostics);
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            cull_chunk_sections.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Resource that controls whether sections that can't be seen from the
+/// camera's section are hidden.
+///
+/// Enabled by default. Disabling it leaves only frustum culling, e.g., to
+/// compare the two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OcclusionCulling {
+    pub enabled: bool,
+}
+
+impl Default for OcclusionCulling {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Axis-aligned bounding box of a chunk section in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SectionBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl SectionBounds {
+    /// Size of a chunk section along each axis.
+    pub const SIZE: Vec3 = Vec3::new(
+        SECTION_WIDTH as f32,
+        SECTION_HEIGHT as f32,
+        SECTION_WIDTH as f32,
+    );
+
+    /// Returns the bounds of the section whose minimum corner is at `origin`.
+    pub fn from_origin(origin: Vec3) -> Self {
+        Self {
+            min: origin,
+            max: origin + Self::SIZE,
+        }
+    }
+
+    /// Returns the bounds of a section from its global transform.
+    ///
+    /// Section meshes are built in section-local coordinates, so the section is
+    /// the 16x16x16 box starting at the transform's translation. Rotation and
+    /// scale are ignored, since sections never have any.
+    pub fn from_transform(transform: &GlobalTransform) -> Self {
+        Self::from_origin(transform.translation)
+    }
+}
+
+/// The side planes of a camera's view frustum.
+///
+/// Only the left, right, bottom, and top planes are used. Together they already
+/// exclude everything behind the camera, and the default 3D projection has no
+/// far plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewFrustum {
+    /// Planes as `(normal, distance)` packed into a [`Vec4`], with normals
+    /// pointing into the frustum.
+    planes: [Vec4; 4],
+}
+
+impl ViewFrustum {
+    /// Extracts the frustum planes from a view-projection matrix (i.e., the
+    /// camera's projection matrix times the inverse of its world transform).
+    pub fn from_view_projection(view_projection: Mat4) -> Self {
+        let row = |i| view_projection.row(i);
+        let (x, y, w) = (row(0), row(1), row(3));
+
+        Self {
+            planes: [w + x, w - x, w + y, w - y],
+        }
+    }
+
+    /// Returns whether any part of the bounding box may be inside the frustum.
+    ///
+    /// This is conservative: boxes near the frustum's corners may be reported
+    /// as visible even if they are not.
+    pub fn intersects(&self, bounds: &SectionBounds) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+
+            // The corner of the box furthest along the plane's normal.
+            let corner = Vec3::select(normal.cmpge(Vec3::ZERO), bounds.max, bounds.min);
+
+            normal.dot(corner) + plane.w >= 0.0
+        })
+    }
+}
+
+fn setup_diagnostics(diagnostics: Option<ResMut<Diagnostics>>) {
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add(Diagnostic::new(
+            VISIBLE_SECTIONS,
+            "visible_chunk_sections",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            OCCLUDED_SECTIONS,
+            "occluded_chunk_sections",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(TOTAL_SECTIONS, "total_chunk_sections", 20));
+    }
+}
+
+fn cull_chunk_sections(
+    occlusion_culling: Res<OcclusionCulling>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    chunks: Query<&GlobalTransform, With<BuiltChunk>>,
+    mut sections: Query<(&BuiltChunkSection, &GlobalTransform, &mut Visibility)>,
+    diagnostics: Option<ResMut<Diagnostics>>,
+) {
+    let camera = cameras
+        .iter()
+        .find(|(camera, _)| camera.name.as_deref() == Some(CameraPlugin::CAMERA_3D));
+
+    let (camera, camera_transform) = match camera {
+        Some(camera) => camera,
+        None => return,
+    };
+
+    let frustum = ViewFrustum::from_view_projection(
+        camera.projection_matrix * camera_transform.compute_matrix().inverse(),
+    );
+
+    // If the camera isn't in a loaded section, there's nowhere to start the
+    // search from, so nothing is occluded.
+    let reachable = if occlusion_culling.enabled {
+        let mut graph = SectionGraph::new();
+        for transform in chunks.iter() {
+            graph.insert_chunk(occlusion::section_containing(transform.translation));
+        }
+        for (section, transform, _) in sections.iter() {
+            graph.insert(
+                occlusion::section_containing(transform.translation),
+                section.connectivity,
+            );
+        }
+
+        graph.reachable_from(occlusion::section_containing(camera_transform.translation))
+    } else {
+        None
+    };
+
+    let mut visible = 0;
+    let mut occluded = 0;
+    let mut total = 0;
+
+    for (_, transform, mut visibility) in sections.iter_mut() {
+        let is_occluded = reachable.as_ref().map_or(false, |reachable| {
+            !reachable.contains(&occlusion::section_containing(transform.translation))
+        });
+        let is_visible =
+            !is_occluded && frustum.intersects(&SectionBounds::from_transform(transform));
+
+        // Avoid triggering change detection when nothing changed.
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+
+        visible += is_visible as usize;
+        occluded += is_occluded as usize;
+        total += 1;
+    }
+
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add_measurement(VISIBLE_SECTIONS, visible as f64);
+        diagnostics.add_measurement(OCCLUDED_SECTIONS, occluded as f64);
+        diagnostics.add_measurement(TOTAL_SECTIONS, total as f64);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A camera at `eye` looking along -Z with a 90 degree field of view.
+    fn frustum(eye: Vec3) -> ViewFrustum {
+        let projection =
+            Mat4::perspective_infinite_reverse_rh(std::f32::consts::FRAC_PI_2, 1.0, 0.1);
+        let view = Mat4::from_translation(eye).inverse();
+
+        ViewFrustum::from_view_projection(projection * view)
+    }
+
+    #[test]
+    fn bounds_from_transform() {
+        let transform = GlobalTransform::from_translation(Vec3::new(32.0, 48.0, -16.0));
+
+        assert_eq!(
+            SectionBounds::from_transform(&transform),
+            SectionBounds {
+                min: Vec3::new(32.0, 48.0, -16.0),
+                max: Vec3::new(48.0, 64.0, 0.0),
+            }
+        );
+    }
+
+    #[test]
+    fn section_in_front_is_visible() {
+        let frustum = frustum(Vec3::ZERO);
+
+        assert!(frustum.intersects(&SectionBounds::from_origin(Vec3::new(-8.0, -8.0, -50.0))));
+    }
+
+    #[test]
+    fn section_behind_is_hidden() {
+        let frustum = frustum(Vec3::ZERO);
+
+        assert!(!frustum.intersects(&SectionBounds::from_origin(Vec3::new(-8.0, -8.0, 50.0))));
+    }
+
+    #[test]
+    fn section_to_the_side_is_hidden() {
+        let frustum = frustum(Vec3::ZERO);
+
+        // With a 90 degree field of view, the frustum at a distance `d` in
+        // front of the camera spans `[-d, d]` along X and Y.
+        assert!(!frustum.intersects(&SectionBounds::from_origin(Vec3::new(30.0, -8.0, -24.0))));
+        assert!(!frustum.intersects(&SectionBounds::from_origin(Vec3::new(-8.0, -60.0, -24.0))));
+    }
+
+    #[test]
+    fn partially_visible_section_is_visible() {
+        let frustum = frustum(Vec3::ZERO);
+
+        // Straddles the right plane.
+        assert!(frustum.intersects(&SectionBounds::from_origin(Vec3::new(15.0, -8.0, -24.0))));
+    }
+
+    #[test]
+    fn camera_inside_section() {
+        let frustum = frustum(Vec3::new(8.0, 8.0, 8.0));
+
+        assert!(frustum.intersects(&SectionBounds::from_origin(Vec3::ZERO)));
+    }
+}
@@ -0,0 +1,380 @@
+//! Playing the sounds sent by the server.
+//!
+//! Sound events are looked up in the asset pack's `sounds.json` to find the
+//! `.ogg` files to play. Actual audio output requires the `audio` feature;
+//! without it, sounds are only logged.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    render::camera::{Camera, CameraPlugin},
+};
+use serde::Deserialize;
+
+use brine_asset::MinecraftAssets;
+use brine_data::{MinecraftData, SoundId};
+use brine_proto::event::clientbound::{Sound, SoundNameOrId};
+
+/// Distance, in blocks, within which a sound of volume 1 can be heard. Louder
+/// sounds can be heard from proportionally further away.
+pub const HEARING_DISTANCE: f32 = 16.0;
+
+/// Returns how loud a sound of the given volume is when heard from `distance`
+/// blocks away, from 0 (inaudible) to 1.
+///
+/// Like in vanilla, the volume falls off linearly to 0 at the edge of the
+/// sound's range, and volumes above 1 only increase the range.
+pub fn attenuated_volume(volume: f32, distance: f32) -> f32 {
+    let range = HEARING_DISTANCE * volume.max(1.0);
+    let falloff = (1.0 - distance / range).clamp(0.0, 1.0);
+
+    volume.clamp(0.0, 1.0) * falloff
+}
+
+/// A plugin that plays the sounds from [`Sound`] events, quieter the further
+/// they are from the camera.
+///
+/// Requires the [`MinecraftData`] and [`MinecraftAssets`] resources.
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_non_send_resource(SoundOutput::new())
+            .init_resource::<MissingSounds>()
+            .add_startup_system(load_sound_index)
+            .add_system(play_sounds);
+    }
+}
+
+/// One of the files that a sound event can play.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawSoundFile")]
+pub struct SoundFile {
+    /// Path of the file relative to the `sounds` directory, without the
+    /// `.ogg` extension.
+    pub name: String,
+    pub volume: f32,
+    pub pitch: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSoundFile {
+    Name(String),
+    Full {
+        name: String,
+        #[serde(default = "one")]
+        volume: f32,
+        #[serde(default = "one")]
+        pitch: f32,
+    },
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+impl From<RawSoundFile> for SoundFile {
+    fn from(raw: RawSoundFile) -> Self {
+        match raw {
+            RawSoundFile::Name(name) => Self {
+                name,
+                volume: 1.0,
+                pitch: 1.0,
+            },
+            RawSoundFile::Full {
+                name,
+                volume,
+                pitch,
+            } => Self {
+                name,
+                volume,
+                pitch,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SoundEvent {
+    #[serde(default)]
+    sounds: Vec<SoundFile>,
+}
+
+/// Resource that maps sound event names to the files they can play, as listed
+/// in the asset pack's `sounds.json`.
+#[derive(Debug, Default)]
+pub struct SoundIndex {
+    sounds_dir: PathBuf,
+    events: HashMap<String, Vec<SoundFile>>,
+
+    /// Used to take turns between the files of each event.
+    plays: usize,
+}
+
+impl SoundIndex {
+    /// Parses a `sounds.json` file, with the sound files in `sounds_dir`.
+    pub fn from_json(json: &str, sounds_dir: impl Into<PathBuf>) -> serde_json::Result<Self> {
+        let events: HashMap<String, SoundEvent> = serde_json::from_str(json)?;
+
+        Ok(Self {
+            sounds_dir: sounds_dir.into(),
+            events: events
+                .into_iter()
+                .map(|(name, event)| (name, event.sounds))
+                .collect(),
+            plays: 0,
+        })
+    }
+
+    /// Loads the index from `assets/minecraft/sounds.json` in the asset pack
+    /// at `root`.
+    pub fn load(root: &Path) -> Result<Self, String> {
+        let minecraft_dir = root.join("assets/minecraft");
+        let path = minecraft_dir.join("sounds.json");
+
+        let json = fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+        Self::from_json(&json, minecraft_dir.join("sounds"))
+            .map_err(|e| format!("invalid {}: {}", path.display(), e))
+    }
+
+    /// Picks one of the files that the sound event plays, returning its path
+    /// along with its volume and pitch.
+    ///
+    /// The event name may optionally include the `minecraft:` namespace.
+    pub fn pick(&mut self, event_name: &str) -> Option<(PathBuf, &SoundFile)> {
+        let event_name = event_name.strip_prefix("minecraft:").unwrap_or(event_name);
+        let files = self
+            .events
+            .get(event_name)
+            .filter(|files| !files.is_empty())?;
+
+        self.plays = self.plays.wrapping_add(1);
+        let file = &files[self.plays % files.len()];
+
+        let name = file.name.strip_prefix("minecraft:").unwrap_or(&file.name);
+        let path = self.sounds_dir.join(format!("{}.ogg", name));
+
+        Some((path, file))
+    }
+}
+
+/// Names of the sounds that couldn't be played, so each is only warned about
+/// once.
+#[derive(Debug, Default)]
+struct MissingSounds(HashSet<String>);
+
+impl MissingSounds {
+    fn warn(&mut self, name: &str, reason: impl FnOnce() -> String) {
+        if self.0.insert(name.to_string()) {
+            warn!("Can't play sound {}: {}", name, reason());
+        }
+    }
+}
+
+fn load_sound_index(mc_assets: Res<MinecraftAssets>, mut commands: Commands) {
+    let index = match SoundIndex::load(mc_assets.root()) {
+        Ok(index) => index,
+        Err(e) => {
+            warn!("No sounds will be played: {}", e);
+            SoundIndex::default()
+        }
+    };
+
+    commands.insert_resource(index);
+}
+
+fn play_sounds(
+    mc_data: Res<MinecraftData>,
+    index: Option<ResMut<SoundIndex>>,
+    output: NonSend<SoundOutput>,
+    mut missing: ResMut<MissingSounds>,
+    mut sound_events: EventReader<Sound>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+) {
+    let mut index = match index {
+        Some(index) => index,
+        None => return,
+    };
+
+    let camera_position = cameras
+        .iter()
+        .find(|(camera, _)| camera.name.as_deref() == Some(CameraPlugin::CAMERA_3D))
+        .map(|(_, transform)| transform.translation);
+
+    let camera_position = match camera_position {
+        Some(position) => position,
+        None => return,
+    };
+
+    for sound in sound_events.iter() {
+        let name = match &sound.name_or_id {
+            SoundNameOrId::Name(name) => name.as_str(),
+            SoundNameOrId::Id(id) => {
+                let found = u16::try_from(*id)
+                    .ok()
+                    .and_then(|id| mc_data.sounds().get_by_id(SoundId(id)));
+                match found {
+                    Some(found) => found.name,
+                    None => {
+                        missing.warn(&format!("#{}", id), || String::from("unknown sound id"));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let volume = attenuated_volume(sound.volume, sound.position.distance(camera_position));
+        if volume <= 0.0 {
+            continue;
+        }
+
+        let (path, file) = match index.pick(name) {
+            Some(picked) => picked,
+            None => {
+                missing.warn(name, || String::from("not in sounds.json"));
+                continue;
+            }
+        };
+
+        if !path.exists() {
+            missing.warn(name, || format!("{} does not exist", path.display()));
+            continue;
+        }
+
+        let volume = volume * file.volume;
+        let pitch = sound.pitch * file.pitch;
+
+        trace!(
+            "Playing {} ({:?}) at volume {}",
+            name,
+            sound.category,
+            volume
+        );
+
+        if let Err(e) = output.play(&path, volume, pitch) {
+            missing.warn(name, || e);
+        }
+    }
+}
+
+/// Non-send resource that plays sound files on the default audio device.
+#[cfg(feature = "audio")]
+struct SoundOutput(Option<(rodio::OutputStream, rodio::OutputStreamHandle)>);
+
+#[cfg(feature = "audio")]
+impl SoundOutput {
+    fn new() -> Self {
+        match rodio::OutputStream::try_default() {
+            Ok(output) => Self(Some(output)),
+            Err(e) => {
+                warn!("No sounds will be played: {}", e);
+                Self(None)
+            }
+        }
+    }
+
+    fn play(&self, path: &Path, volume: f32, pitch: f32) -> Result<(), String> {
+        let handle = match &self.0 {
+            Some((_, handle)) => handle,
+            None => return Ok(()),
+        };
+
+        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let source =
+            rodio::Decoder::new(std::io::BufReader::new(file)).map_err(|e| e.to_string())?;
+
+        let sink = rodio::Sink::try_new(handle).map_err(|e| e.to_string())?;
+        sink.set_volume(volume);
+        sink.set_speed(pitch);
+        sink.append(source);
+        sink.detach();
+
+        Ok(())
+    }
+}
+
+/// Stand-in for the audio output when the `audio` feature is disabled, which
+/// only logs the sounds.
+#[cfg(not(feature = "audio"))]
+struct SoundOutput;
+
+#[cfg(not(feature = "audio"))]
+impl SoundOutput {
+    fn new() -> Self {
+        info!("Built without the `audio` feature, so sounds will only be logged");
+        Self
+    }
+
+    fn play(&self, path: &Path, volume: f32, pitch: f32) -> Result<(), String> {
+        debug!(
+            "Would play {} at volume {} and pitch {}",
+            path.display(),
+            volume,
+            pitch
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn volume_falls_off_with_distance() {
+        assert_eq!(attenuated_volume(1.0, 0.0), 1.0);
+        assert_eq!(attenuated_volume(1.0, 8.0), 0.5);
+        assert_eq!(attenuated_volume(1.0, 16.0), 0.0);
+        assert_eq!(attenuated_volume(1.0, 100.0), 0.0);
+
+        assert_eq!(attenuated_volume(0.5, 8.0), 0.25);
+    }
+
+    #[test]
+    fn loud_sounds_carry_further() {
+        // Louder than 1 doesn't make the sound louder up close...
+        assert_eq!(attenuated_volume(4.0, 0.0), 1.0);
+
+        // ...but it can be heard further away.
+        assert_eq!(attenuated_volume(4.0, 32.0), 0.5);
+        assert_eq!(attenuated_volume(4.0, 64.0), 0.0);
+    }
+
+    #[test]
+    fn sound_index() {
+        let json = r#"{
+            "block.stone.break": {
+                "sounds": ["dig/stone1", { "name": "dig/stone2", "volume": 0.5 }],
+                "subtitle": "subtitles.block.generic.break"
+            },
+            "music.game": { "replace": true, "sounds": [] }
+        }"#;
+        let mut index = SoundIndex::from_json(json, "sounds").unwrap();
+
+        let mut picked: Vec<_> = (0..2)
+            .map(|_| {
+                let (path, file) = index.pick("minecraft:block.stone.break").unwrap();
+                (path, file.volume)
+            })
+            .collect();
+        picked.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            picked,
+            vec![
+                (PathBuf::from("sounds/dig/stone1.ogg"), 1.0),
+                (PathBuf::from("sounds/dig/stone2.ogg"), 0.5),
+            ]
+        );
+
+        assert_eq!(index.pick("music.game"), None);
+        assert_eq!(index.pick("not.a.sound"), None);
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use brine_asset::bakery::{self, AssetPacks, BakeWarning};
+use brine_data::MinecraftData;
+
+/// Bakes all of the assets and summarizes what couldn't be baked.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Print every warning, not just how many there are of each kind.
+    #[clap(short, long)]
+    verbose: bool,
+}
+
+pub(crate) fn main(args: Args) {
+    let mc_data = MinecraftData::for_version("1.14.4");
+    let asset_pack = AssetPacks::at_path("assets/1.14.4");
+
+    println!("Baking Assets");
+    let (baked, warnings) = bakery::bake_all(&mc_data, &asset_pack).unwrap();
+
+    println!(
+        "Baked {} block states, {} models, {} textures",
+        baked.block_states.block_states.len(),
+        baked.models.models.len(),
+        baked.textures.iter().count()
+    );
+
+    let mut by_kind: BTreeMap<&str, Vec<&BakeWarning>> = BTreeMap::new();
+    for warning in warnings.iter() {
+        by_kind.entry(kind(warning)).or_default().push(warning);
+    }
+
+    println!("{} warnings", warnings.len());
+    for (kind, warnings) in by_kind {
+        println!("  {}: {}", kind, warnings.len());
+
+        if args.verbose {
+            for warning in warnings {
+                println!("    {}", warning);
+            }
+        }
+    }
+}
+
+fn kind(warning: &BakeWarning) -> &'static str {
+    match warning {
+        BakeWarning::MissingBlockStates(_) => "missing blockstates",
+        BakeWarning::MissingBlockData(_) => "missing block data",
+        BakeWarning::MissingModel(_) => "missing models",
+        BakeWarning::UnresolvedTexture { .. } => "unresolved textures",
+        BakeWarning::MissingTexture { .. } => "missing textures",
+        BakeWarning::InvalidFaceRotation { .. } => "invalid face rotations",
+        BakeWarning::MissingFluidTexture(_) => "missing fluid textures",
+    }
+}
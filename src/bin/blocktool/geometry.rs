@@ -0,0 +1,66 @@
+//! Flattening baked models into plain vertex and index buffers.
+
+use brine::mesh_export::MeshPrimitive;
+use brine_asset::{BakedModel, BakedQuad};
+
+/// The vertices and triangles of a [`BakedModel`], four vertices per quad.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ModelGeometry {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub tex_coords: Vec<[f32; 2]>,
+    /// Three indices per triangle, two triangles per quad.
+    pub indices: Vec<u16>,
+}
+
+impl ModelGeometry {
+    /// Collects the geometry of every quad in `baked_model` for which `include`
+    /// returns true.
+    ///
+    /// The texture coordinates of each quad are given by `tex_coords`, e.g., to
+    /// map them into a texture atlas.
+    pub fn from_baked_model(
+        baked_model: &BakedModel,
+        include: impl Fn(&BakedQuad) -> bool,
+        tex_coords: impl Fn(&BakedQuad) -> [[f32; 2]; 4],
+    ) -> Self {
+        let num_quads = baked_model.quads.len();
+        let num_vertices = num_quads * 4;
+        let num_indices = num_quads * 6;
+
+        let mut geometry = Self {
+            positions: Vec::with_capacity(num_vertices),
+            normals: Vec::with_capacity(num_vertices),
+            tex_coords: Vec::with_capacity(num_vertices),
+            indices: Vec::with_capacity(num_indices),
+        };
+
+        for quad in baked_model.quads.iter().filter(|quad| include(quad)) {
+            let first_vertex = geometry.positions.len();
+
+            geometry.indices.extend_from_slice(
+                &quad
+                    .indices()
+                    .map(|index| (first_vertex + index as usize) as u16),
+            );
+
+            geometry.positions.extend_from_slice(&quad.positions);
+            geometry.normals.extend_from_slice(&[quad.normal; 4]);
+            geometry.tex_coords.extend_from_slice(&tex_coords(quad));
+        }
+
+        geometry
+    }
+
+    /// Converts the geometry into a primitive that can be exported (see
+    /// [`brine::mesh_export`]).
+    pub fn into_primitive(self, material: Option<usize>) -> MeshPrimitive {
+        MeshPrimitive {
+            positions: self.positions,
+            normals: self.normals,
+            tex_coords: self.tex_coords,
+            indices: self.indices.into_iter().map(u32::from).collect(),
+            material,
+        }
+    }
+}
@@ -1,5 +1,8 @@
 #![allow(clippy::too_many_arguments)]
 
+mod coverage;
+mod geometry;
+mod mesh_dump;
 mod print;
 mod view;
 
@@ -16,6 +19,9 @@ struct Args {
 
 #[derive(clap::Subcommand)]
 enum Subcommand {
+    Coverage(coverage::Args),
+    #[clap(alias = "export")]
+    MeshDump(mesh_dump::Args),
     Print(print::Args),
     View(view::Args),
 }
@@ -32,10 +38,20 @@ pub fn parse_block_reference(block_reference: &str, mc_data: &MinecraftData) ->
             .expect("Invalid max state id");
 
         (min_state..max_state + 1).map(BlockStateId).collect()
+    } else if block_reference.contains('[') {
+        let block_state_id = mc_data
+            .blocks()
+            .parse_state(block_reference)
+            .unwrap_or_else(|e| panic!("Invalid block state: {}", e));
+
+        vec![block_state_id]
     } else {
+        let name = block_reference
+            .strip_prefix("minecraft:")
+            .unwrap_or(block_reference);
         let block = mc_data
             .blocks()
-            .get_by_name(block_reference)
+            .get_by_name(name)
             .expect("No block with the provided name");
         let block_id = BlockId(block.id);
 
@@ -52,6 +68,8 @@ fn main() {
     let args = Args::parse();
 
     match args.command {
+        Subcommand::Coverage(args) => coverage::main(args),
+        Subcommand::MeshDump(args) => mesh_dump::main(args),
         Subcommand::Print(args) => print::main(args),
         Subcommand::View(args) => view::main(args),
     }
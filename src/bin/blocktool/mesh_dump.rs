@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use brine::mesh_export::{MeshFormat, MeshMaterial, MeshObject, MeshPrimitive, MeshScene};
+use brine_asset::{BakedModel, MinecraftAssets, TextureKey};
+use brine_data::{BlockStateId, MinecraftData};
+
+use crate::{geometry::ModelGeometry, parse_block_reference};
+
+/// Writes the geometry of a block's baked models to a file, for inspecting in
+/// other tools such as Blender.
+///
+/// Each block state becomes its own named object, with one material per
+/// texture. OBJ files refer to the textures from an MTL file written next to
+/// them, while glTF files embed them. Texture coordinates are relative to each
+/// quad's own texture, not to a texture atlas.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Block reference, e.g., "stone", "42", "100:111",
+    /// "oak_stairs[facing=east,half=top]".
+    block_reference: String,
+
+    /// Output file.
+    #[clap(short, long, alias = "output", value_name = "FILE")]
+    out: PathBuf,
+
+    /// One of `obj`, `gltf`, or `glb` (binary glTF). If not given, the format
+    /// is chosen by the extension of the output file, defaulting to OBJ.
+    #[clap(long)]
+    format: Option<MeshFormat>,
+
+    /// Write each block state to its own numbered file, e.g., `out_0.obj`,
+    /// `out_1.obj`, and so on, instead of all of them to one file.
+    #[clap(long)]
+    split: bool,
+}
+
+pub(crate) fn main(args: Args) {
+    let mc_data = MinecraftData::for_version("1.14.4");
+
+    let block_state_ids = parse_block_reference(&args.block_reference, &mc_data);
+    println!("Requested to export block states: {:?}", block_state_ids);
+
+    println!("Loading Assets");
+    let mc_assets = MinecraftAssets::new("assets/1.14.4", &mc_data).unwrap();
+
+    let format = args
+        .format
+        .unwrap_or_else(|| MeshFormat::for_path(&args.out));
+
+    let scenes = if args.split {
+        block_state_ids
+            .iter()
+            .enumerate()
+            .map(|(i, block_state_id)| {
+                let scene = build_scene(&[*block_state_id], &mc_data, &mc_assets);
+                (numbered_path(&args.out, i), scene)
+            })
+            .collect()
+    } else {
+        let scene = build_scene(&block_state_ids, &mc_data, &mc_assets);
+        vec![(args.out.clone(), scene)]
+    };
+
+    for (path, scene) in scenes {
+        if scene.objects.is_empty() {
+            println!("ERROR: No models to export to {}", path.display());
+            continue;
+        }
+
+        match scene.save(&path, format) {
+            Ok(()) => {
+                for object in scene.objects.iter() {
+                    println!(
+                        "Wrote {} ({} vertices, {} triangles) to {}",
+                        object.name,
+                        object.num_vertices(),
+                        object.num_triangles(),
+                        path.display()
+                    );
+                }
+            }
+            Err(e) => println!("ERROR: {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Builds a scene with an object for each of the given block states that has
+/// any quads.
+fn build_scene(
+    block_state_ids: &[BlockStateId],
+    mc_data: &MinecraftData,
+    mc_assets: &MinecraftAssets,
+) -> MeshScene {
+    let mut scene = MeshScene::default();
+    let mut materials: HashMap<TextureKey, (usize, f32)> = HashMap::new();
+
+    for block_state_id in block_state_ids.iter().copied() {
+        let mut object = MeshObject {
+            name: block_state_name(block_state_id, mc_data),
+            primitives: Vec::new(),
+        };
+
+        for baked_model in get_models(block_state_id, mc_assets) {
+            object
+                .primitives
+                .extend(model_primitives(baked_model, |texture| {
+                    *materials
+                        .entry(texture)
+                        .or_insert_with(|| add_material(&mut scene, texture, mc_assets))
+                }));
+        }
+
+        if !object.primitives.is_empty() {
+            scene.objects.push(object);
+        }
+    }
+
+    scene
+}
+
+/// Returns every non-empty model of the given block state.
+fn get_models(block_state_id: BlockStateId, mc_assets: &MinecraftAssets) -> Vec<&BakedModel> {
+    let baked_block_state = match mc_assets.block_states().get_by_key(block_state_id) {
+        Some(baked_block_state) => baked_block_state,
+        None => return Vec::new(),
+    };
+
+    baked_block_state
+        .models
+        .iter()
+        .map(|grab_bag| {
+            let model_key = grab_bag.choices.first().unwrap();
+            mc_assets.models().get_by_key(*model_key).unwrap()
+        })
+        .filter(|baked_model| !baked_model.quads.is_empty())
+        .collect()
+}
+
+/// Splits a model into one primitive per texture, in the order that the
+/// textures first appear.
+///
+/// `material` returns the index of each texture's material, and how much to
+/// scale the V coordinates of the texture by.
+fn model_primitives(
+    baked_model: &BakedModel,
+    mut material: impl FnMut(TextureKey) -> (usize, f32),
+) -> Vec<MeshPrimitive> {
+    let mut textures: Vec<TextureKey> = Vec::new();
+    for quad in baked_model.quads.iter() {
+        if !textures.contains(&quad.texture) {
+            textures.push(quad.texture);
+        }
+    }
+
+    textures
+        .into_iter()
+        .map(|texture| {
+            let (material, v_scale) = material(texture);
+
+            let geometry = ModelGeometry::from_baked_model(
+                baked_model,
+                |quad| quad.texture == texture,
+                |quad| quad.tex_coords.map(|[u, v]| [u, v * v_scale]),
+            );
+
+            geometry.into_primitive(Some(material))
+        })
+        .collect()
+}
+
+/// Adds a material for the texture to the scene, returning its index and how
+/// much to scale the texture's V coordinates by.
+fn add_material(
+    scene: &mut MeshScene,
+    texture: TextureKey,
+    mc_assets: &MinecraftAssets,
+) -> (usize, f32) {
+    let name = mc_assets
+        .textures()
+        .get_by_key(texture)
+        .map(|texture_id| texture_id.as_str().to_string())
+        .unwrap_or_else(|| format!("texture_{}", texture.0));
+
+    // Texture paths are relative to the `assets` directory. Make them
+    // absolute so that the MTL file can be written anywhere.
+    let path = mc_assets
+        .get_texture_path(texture)
+        .and_then(|path| Path::new("assets").join(path).canonicalize().ok());
+
+    // Animated textures are a vertical strip of square frames. Only show the
+    // first frame.
+    let v_scale = path
+        .as_ref()
+        .and_then(|path| image::image_dimensions(path).ok())
+        .map(|(width, height)| width as f32 / height as f32)
+        .unwrap_or(1.0);
+
+    scene.materials.push(MeshMaterial {
+        name,
+        texture: path,
+    });
+
+    (scene.materials.len() - 1, v_scale)
+}
+
+/// Names a block state the same way as a block reference, e.g.,
+/// `oak_stairs[facing=east,half=top]`.
+fn block_state_name(block_state_id: BlockStateId, mc_data: &MinecraftData) -> String {
+    let block = match mc_data.blocks().get_by_state_id(block_state_id) {
+        Some(block) => block,
+        None => return format!("block_state_{}", block_state_id.0),
+    };
+
+    let mut state_values: Vec<String> = block
+        .state
+        .iter()
+        .map(|(property, value)| format!("{property}={value}"))
+        .collect();
+    state_values.sort();
+
+    if state_values.is_empty() {
+        block.name.to_string()
+    } else {
+        format!("{}[{}]", block.name, state_values.join(","))
+    }
+}
+
+/// Inserts `_{index}` before the extension of `path`.
+fn numbered_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}_{}.{}", stem, index, extension.to_string_lossy()),
+        None => format!("{}_{}", stem, index),
+    };
+
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod test {
+    use brine::mesh_export::write_obj;
+    use brine_asset::{BakedQuad, BlockFace};
+
+    use super::*;
+
+    /// A cube like `grass_block`'s, with a different texture on top.
+    fn cube(top: TextureKey, rest: TextureKey) -> BakedModel {
+        let faces = [
+            BlockFace::Down,
+            BlockFace::Up,
+            BlockFace::North,
+            BlockFace::South,
+            BlockFace::West,
+            BlockFace::East,
+        ];
+
+        BakedModel {
+            is_full_cube: true,
+            quads: faces
+                .into_iter()
+                .map(|face| BakedQuad {
+                    positions: [[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0; 3]],
+                    normal: [0.0, 1.0, 0.0],
+                    tex_coords: [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]],
+                    texture: if face == BlockFace::Up { top } else { rest },
+                    face,
+                    cull_face: Some(face),
+                    tint_index: None,
+                    shade: true,
+                })
+                .collect(),
+        }
+    }
+
+    fn count_lines(text: &str, prefix: &str) -> usize {
+        text.lines().filter(|line| line.starts_with(prefix)).count()
+    }
+
+    #[test]
+    fn one_primitive_per_texture() {
+        let model = cube(TextureKey(7), TextureKey(3));
+
+        // The top texture is animated, with two frames.
+        let primitives = model_primitives(&model, |texture| match texture {
+            TextureKey(3) => (0, 1.0),
+            _ => (1, 0.5),
+        });
+
+        assert_eq!(primitives.len(), 2);
+        assert_eq!(primitives[0].material, Some(0));
+        assert_eq!(primitives[0].num_vertices(), 5 * 4);
+        assert_eq!(primitives[0].num_triangles(), 5 * 2);
+        assert_eq!(primitives[1].material, Some(1));
+        assert_eq!(primitives[1].num_vertices(), 4);
+        assert_eq!(primitives[1].tex_coords[3], [1.0, 0.5]);
+
+        let scene = MeshScene {
+            objects: vec![MeshObject {
+                name: String::from("grass_block[snowy=false]"),
+                primitives,
+            }],
+            materials: Vec::new(),
+        };
+
+        let mut output = Vec::new();
+        write_obj(&scene, None, &mut output).unwrap();
+        let obj = String::from_utf8(output).unwrap();
+
+        assert_eq!(count_lines(&obj, "v "), 24);
+        assert_eq!(count_lines(&obj, "f "), 12);
+        assert!(obj.contains("o grass_block[snowy=false]"));
+    }
+
+    #[test]
+    fn numbered_paths() {
+        assert_eq!(
+            numbered_path(Path::new("out/stairs.obj"), 2),
+            Path::new("out/stairs_2.obj")
+        );
+        assert_eq!(numbered_path(Path::new("stairs"), 0), Path::new("stairs_0"));
+    }
+}
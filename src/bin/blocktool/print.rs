@@ -1,24 +1,33 @@
-use brine_data::{blocks::BlockStateId, MinecraftData};
+use brine_data::MinecraftData;
+
+use crate::parse_block_reference;
 
 /// Prints information about a given block.
 #[derive(clap::Args)]
 pub struct Args {
-    /// Block state id.
-    #[clap(short, long)]
-    state_id: u16,
+    /// Block reference, e.g., "stone", "42", "100:111",
+    /// "oak_stairs[facing=east,half=top]".
+    block_reference: String,
 }
 
 pub(crate) fn main(args: Args) {
-    print_block(BlockStateId(args.state_id));
+    print_blocks(&args.block_reference);
 }
 
-fn print_block(block_state_id: BlockStateId) {
+fn print_blocks(block_reference: &str) {
     let data = MinecraftData::for_version("1.14.4");
 
-    let block = data
-        .blocks()
-        .get_by_state_id(block_state_id)
-        .expect("no such block");
+    for block_state_id in parse_block_reference(block_reference, &data) {
+        let block = data
+            .blocks()
+            .get_by_state_id(block_state_id)
+            .expect("no such block");
 
-    println!("{:#?}", block);
+        println!(
+            "{:?}: {}",
+            block_state_id,
+            data.blocks().format_state(block_state_id).unwrap()
+        );
+        println!("{:#?}", block);
+    }
 }
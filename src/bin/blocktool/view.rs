@@ -13,12 +13,13 @@ use brine_render::texture::{
     TextureManagerPlugin,
 };
 
-use crate::parse_block_reference;
+use crate::{geometry::ModelGeometry, parse_block_reference};
 
 /// Displays a block.
 #[derive(clap::Args)]
 pub struct Args {
-    /// Block reference, e.g., "stone", "42", "100:111".
+    /// Block reference, e.g., "stone", "42", "100:111",
+    /// "oak_stairs[facing=east,half=top]".
     block_reference: String,
 
     /// Optionally show only a specific face.
@@ -366,39 +367,20 @@ fn baked_model_to_mesh(
     texture_atlas: &TextureAtlas,
     show_faces: &ShowFaces,
 ) -> Mesh {
-    let num_quads = baked_model.quads.len();
-    let num_vertices = num_quads * 4;
-    let num_indices = num_quads * 6;
-
-    let mut positions = Vec::with_capacity(num_vertices);
-    let mut normals = Vec::with_capacity(num_vertices);
-    let mut tex_coords = Vec::with_capacity(num_vertices);
-    let mut indices = Vec::with_capacity(num_indices);
-
-    for quad in baked_model.quads.iter() {
-        debug!("quad.face = {:?}", quad.face);
-        if !show_faces.show(quad.face) {
-            continue;
-        }
-
-        indices.extend_from_slice(
-            &quad
-                .indices()
-                .map(|index| (positions.len() + index as usize) as u16),
-        );
-
-        positions.extend_from_slice(&quad.positions);
-        normals.extend_from_slice(&[quad.normal; 4]);
-
-        let uvs_within_atlas = texture_atlas.get_uv(quad.texture);
-        tex_coords.extend_from_slice(&adjust_tex_coords(quad.tex_coords, uvs_within_atlas));
-    }
+    let geometry = ModelGeometry::from_baked_model(
+        baked_model,
+        |quad| show_faces.show(quad.face),
+        |quad| {
+            let uvs_within_atlas = texture_atlas.get_uv(quad.texture);
+            adjust_tex_coords(quad.tex_coords, uvs_within_atlas)
+        },
+    );
 
     let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
-    mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.set_attribute(Mesh::ATTRIBUTE_UV_0, tex_coords);
-    mesh.set_indices(Some(Indices::U16(indices)));
+    mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, geometry.positions);
+    mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, geometry.normals);
+    mesh.set_attribute(Mesh::ATTRIBUTE_UV_0, geometry.tex_coords);
+    mesh.set_indices(Some(Indices::U16(geometry.indices)));
 
     mesh
 }
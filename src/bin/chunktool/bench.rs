@@ -0,0 +1,295 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use brine_chunk::{
+    worldgen::{Preset, WorldGen},
+    Chunk,
+};
+use brine_voxel_v1::chunk_builder::{
+    benchmark_builder, ChunkBuilder, GreedyQuadsChunkBuilder, Lod2ChunkBuilder, MeshStats,
+    NaiveBlocksChunkBuilder, VisibleFacesChunkBuilder,
+};
+
+use brine::chunk::{is_chunk_file, load_chunk, Result};
+
+/// Measures the performance of every chunk builder on chunks loaded from disk,
+/// or on chunks generated from a preset.
+///
+/// Each builder is run directly (without a Bevy app) over every section of
+/// every chunk, one section at a time, so that the timings are not affected
+/// by the renderer.
+///
+/// Build with `--features bench-alloc` to also report peak heap allocation.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to a chunk data file, or to a directory of chunk data files.
+    #[clap(required_unless_present = "preset", conflicts_with = "preset")]
+    path: Option<PathBuf>,
+
+    /// Generate the chunks instead: one of flat, hills, checkerboard, or
+    /// swiss-cheese.
+    #[clap(long, value_name = "NAME")]
+    preset: Option<Preset>,
+
+    /// Seed for the generated chunks.
+    #[clap(long, default_value = "0")]
+    seed: u64,
+
+    /// Generate this many chunks along each side.
+    #[clap(long, default_value = "4")]
+    size: u32,
+
+    /// Number of times to build each section with each builder.
+    #[clap(short = 'n', long, default_value = "20")]
+    iterations: usize,
+
+    /// Print results as JSON instead of a table.
+    #[clap(long)]
+    json: bool,
+}
+
+pub(crate) fn main(args: Args) {
+    match bench(&args) {
+        Ok(()) => {}
+        Err(e) => println!("ERROR: {}", e),
+    }
+}
+
+fn bench(args: &Args) -> Result<()> {
+    let chunks = match (&args.path, args.preset) {
+        (Some(path), _) => load_chunks(path)?,
+        (None, Some(preset)) => WorldGen::new(preset, args.seed).chunks(args.size),
+        (None, None) => unreachable!("clap requires a path or a preset"),
+    };
+
+    // Each section is benchmarked on its own, so split them up ahead of time.
+    let sections: Vec<Chunk> = chunks
+        .iter()
+        .flat_map(|chunk| {
+            chunk.sections.iter().map(|section| Chunk {
+                sections: vec![section.clone()],
+                ..chunk.clone()
+            })
+        })
+        .collect();
+
+    let iterations = args.iterations.max(1);
+
+    let results = [
+        bench_builder::<NaiveBlocksChunkBuilder>(&sections, iterations),
+        bench_builder::<VisibleFacesChunkBuilder>(&sections, iterations),
+        bench_builder::<GreedyQuadsChunkBuilder>(&sections, iterations),
+        bench_builder::<Lod2ChunkBuilder>(&sections, iterations),
+    ];
+
+    if args.json {
+        print_json(&results, chunks.len(), iterations);
+    } else {
+        print_table(&results, chunks.len(), sections.len(), iterations);
+    }
+
+    Ok(())
+}
+
+/// Loads either a single chunk or every chunk in a directory.
+fn load_chunks(path: &Path) -> Result<Vec<Chunk>> {
+    if !path.is_dir() {
+        return Ok(vec![load_chunk(path)?]);
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let file = entry?.path();
+        if is_chunk_file(&file) {
+            files.push(file);
+        }
+    }
+    files.sort();
+
+    files.iter().map(load_chunk).collect()
+}
+
+struct BuilderStats {
+    name: &'static str,
+    sections: usize,
+    mean: Duration,
+    median: Duration,
+    p95: Duration,
+    vertices: usize,
+    quads: usize,
+    triangles: usize,
+    peak_alloc: Option<usize>,
+}
+
+fn bench_builder<T>(sections: &[Chunk], iterations: usize) -> BuilderStats
+where
+    T: ChunkBuilder + Default,
+{
+    let mut times = Vec::with_capacity(sections.len() * iterations);
+    let mut totals = MeshStats::default();
+    let mut peak_alloc = None;
+
+    for section in sections.iter() {
+        for iteration in 0..iterations {
+            let baseline = alloc::reset_peak();
+
+            let stats = benchmark_builder::<T>(section);
+            times.push(stats.elapsed);
+
+            if let Some(peak) = alloc::peak() {
+                let peak = peak.saturating_sub(baseline.unwrap_or_default());
+                peak_alloc = Some(peak_alloc.unwrap_or(0).max(peak));
+            }
+
+            if iteration == 0 {
+                totals += stats;
+            }
+        }
+    }
+
+    times.sort();
+
+    BuilderStats {
+        name: T::TYPE.0,
+        sections: sections.len(),
+        mean: mean(&times),
+        median: percentile(&times, 50),
+        p95: percentile(&times, 95),
+        vertices: totals.vertices,
+        quads: totals.quads,
+        triangles: totals.triangles,
+        peak_alloc,
+    }
+}
+
+fn mean(times: &[Duration]) -> Duration {
+    if times.is_empty() {
+        return Duration::ZERO;
+    }
+
+    times.iter().sum::<Duration>() / times.len() as u32
+}
+
+/// Returns the given percentile of an already-sorted list of times.
+fn percentile(sorted_times: &[Duration], percentile: usize) -> Duration {
+    if sorted_times.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let index = (sorted_times.len() - 1) * percentile / 100;
+    sorted_times[index]
+}
+
+fn print_table(results: &[BuilderStats], chunks: usize, sections: usize, iterations: usize) {
+    println!(
+        "{} chunks, {} sections, {} iterations per section",
+        chunks, sections, iterations
+    );
+    println!();
+    println!(
+        "{:<26} {:>12} {:>12} {:>12} {:>10} {:>10} {:>10} {:>12}",
+        "builder", "mean", "median", "p95", "vertices", "quads", "triangles", "peak alloc"
+    );
+
+    for stats in results.iter() {
+        let peak_alloc = stats
+            .peak_alloc
+            .map(|bytes| format!("{:.1} KiB", bytes as f64 / 1024.0))
+            .unwrap_or_else(|| String::from("-"));
+
+        println!(
+            "{:<26} {:>12} {:>12} {:>12} {:>10} {:>10} {:>10} {:>12}",
+            stats.name,
+            format!("{:.1?}", stats.mean),
+            format!("{:.1?}", stats.median),
+            format!("{:.1?}", stats.p95),
+            stats.vertices,
+            stats.quads,
+            stats.triangles,
+            peak_alloc,
+        );
+    }
+}
+
+fn print_json(results: &[BuilderStats], chunks: usize, iterations: usize) {
+    let builders: Vec<serde_json::Value> = results
+        .iter()
+        .map(|stats| {
+            serde_json::json!({
+                "builder": stats.name,
+                "sections": stats.sections,
+                "mean_ns": stats.mean.as_nanos() as u64,
+                "median_ns": stats.median.as_nanos() as u64,
+                "p95_ns": stats.p95.as_nanos() as u64,
+                "vertices": stats.vertices,
+                "quads": stats.quads,
+                "triangles": stats.triangles,
+                "peak_alloc_bytes": stats.peak_alloc,
+            })
+        })
+        .collect();
+
+    let output = serde_json::json!({
+        "chunks": chunks,
+        "iterations": iterations,
+        "builders": builders,
+    });
+
+    println!("{:#}", output);
+}
+
+/// A global allocator that keeps track of the peak number of bytes allocated.
+#[cfg(feature = "bench-alloc")]
+pub mod alloc {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+                PEAK.fetch_max(current, Ordering::Relaxed);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+    }
+
+    /// Resets the peak to the current number of bytes allocated, and returns it.
+    pub fn reset_peak() -> Option<usize> {
+        let current = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+        Some(current)
+    }
+
+    /// The peak number of bytes allocated since the last call to [`reset_peak`].
+    pub fn peak() -> Option<usize> {
+        Some(PEAK.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(not(feature = "bench-alloc"))]
+mod alloc {
+    pub fn reset_peak() -> Option<usize> {
+        None
+    }
+
+    pub fn peak() -> Option<usize> {
+        None
+    }
+}
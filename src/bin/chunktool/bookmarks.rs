@@ -0,0 +1,182 @@
+//! Named camera positions for `chunktool view`, saved to a TOML file next to
+//! the chunk that they were taken of.
+//!
+//! An example bookmarks file:
+//!
+//! ```toml
+//! [[bookmark]]
+//! name = "front"
+//! translation = [0.0, 8.0, 38.0]
+//! rotation = [-0.1045, 0.0, 0.0, 0.9945]
+//! ```
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Extension of a chunk's bookmarks file, which replaces the chunk file's own.
+pub const BOOKMARKS_EXTENSION: &str = "bookmarks.toml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum BookmarkError {
+    #[error("failed to access bookmarks file {}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error("invalid bookmarks file {}: {source}", .path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("no bookmark named {name:?} in {}", .path.display())]
+    NotFound { path: PathBuf, name: String },
+}
+
+/// A saved camera transform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+}
+
+impl Bookmark {
+    pub fn new(name: impl Into<String>, transform: &Transform) -> Self {
+        Self {
+            name: name.into(),
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(Vec3::from(self.translation))
+            .with_rotation(Quat::from_array(self.rotation).normalize())
+    }
+}
+
+/// The bookmarks of one chunk, in the order that they were saved.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmarks {
+    #[serde(default, rename = "bookmark")]
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Returns the path of the bookmarks file for the given chunk file, e.g.,
+    /// `chunk_0_0.bookmarks.toml` for `chunk_0_0.chunk`.
+    pub fn path_for_chunk(chunk_path: impl AsRef<Path>) -> PathBuf {
+        chunk_path.as_ref().with_extension(BOOKMARKS_EXTENSION)
+    }
+
+    /// Reads the bookmarks file at `path`, or returns no bookmarks if it
+    /// doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookmarkError> {
+        let path = path.as_ref();
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(BookmarkError::Io {
+                    path: path.to_owned(),
+                    source,
+                })
+            }
+        };
+
+        toml::from_str(&contents).map_err(|source| BookmarkError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BookmarkError> {
+        let path = path.as_ref();
+        let contents = toml::to_string_pretty(self)?;
+
+        fs::write(path, contents).map_err(|source| BookmarkError::Io {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.name == name)
+    }
+
+    /// Adds a bookmark for the transform, named after its position in the list
+    /// (i.e., the number key that recalls it), unless that name is taken.
+    pub fn add(&mut self, transform: &Transform) -> &Bookmark {
+        let name = (self.bookmarks.len() + 1..)
+            .map(|number| number.to_string())
+            .find(|name| self.get(name).is_none())
+            .unwrap();
+
+        self.bookmarks.push(Bookmark::new(name, transform));
+        self.bookmarks.last().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn path_for_chunk() {
+        assert_eq!(
+            Bookmarks::path_for_chunk("captures/chunk_0_-1.chunk"),
+            Path::new("captures/chunk_0_-1.bookmarks.toml")
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let transform =
+            Transform::from_translation(Vec3::new(0.0, 8.0, 38.0)).looking_at(Vec3::ZERO, Vec3::Y);
+
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.add(&transform);
+        bookmarks.add(&Transform::identity());
+
+        let toml = toml::to_string_pretty(&bookmarks).unwrap();
+        let loaded: Bookmarks = toml::from_str(&toml).unwrap();
+
+        assert_eq!(loaded, bookmarks);
+        assert_eq!(loaded.bookmarks[0].name, "1");
+
+        let recalled = loaded.get("1").unwrap().transform();
+        assert_eq!(recalled.translation, transform.translation);
+        assert!(recalled.rotation.abs_diff_eq(transform.rotation, 1e-6));
+    }
+
+    #[test]
+    fn added_names_are_unique() {
+        let mut bookmarks: Bookmarks = toml::from_str(
+            r#"
+            [[bookmark]]
+            name = "2"
+            translation = [1.0, 2.0, 3.0]
+            rotation = [0.0, 0.0, 0.0, 1.0]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(bookmarks.add(&Transform::identity()).name, "3");
+        assert_eq!(bookmarks.add(&Transform::identity()).name, "4");
+    }
+
+    #[test]
+    fn missing_file_has_no_bookmarks() {
+        let path = std::env::temp_dir().join("brine_no_such_chunk.bookmarks.toml");
+
+        assert_eq!(Bookmarks::load(path).unwrap(), Bookmarks::default());
+    }
+}
@@ -1,9 +1,16 @@
+mod bench;
+mod bookmarks;
 mod print;
 mod save;
+mod slice;
 mod view;
 
 use clap::Parser;
 
+#[cfg(feature = "bench-alloc")]
+#[global_allocator]
+static ALLOCATOR: bench::alloc::CountingAllocator = bench::alloc::CountingAllocator;
+
 /// Utility application for debugging chunk building / rendering.
 #[derive(Parser)]
 #[clap(name = "chunktool")]
@@ -14,8 +21,10 @@ struct Args {
 
 #[derive(clap::Subcommand)]
 enum Subcommand {
+    Bench(bench::Args),
     Print(print::Args),
     Save(save::Args),
+    Slice(slice::Args),
     View(view::Args),
 }
 
@@ -23,8 +32,10 @@ fn main() {
     let args = Args::parse();
 
     match args.command {
+        Subcommand::Bench(args) => bench::main(args),
         Subcommand::Print(args) => print::main(args),
         Subcommand::Save(args) => save::main(args),
+        Subcommand::Slice(args) => slice::main(args),
         Subcommand::View(args) => view::main(args),
     }
 }
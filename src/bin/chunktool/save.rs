@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use bevy::{app::AppExit, prelude::*};
 
@@ -10,10 +10,13 @@ use brine::{chunk::save_packet_if_has_chunk_data, login::LoginPlugin};
 
 /// Reads chunk packets from a server and saves them to files.
 ///
-/// Each ChunkData packet received will be saved to a pair of files in the
+/// Each ChunkData packet received will be saved to a chunk file in the
 /// specified output directory.
 ///
-/// Files will be named `chunk_{X}_{Z}.dump` and `chunk_{X}_{Z}.meta`.
+/// Files will be named `chunk_{X}_{Z}.chunk`.
+///
+/// Runs until the server disconnects, or until the `--limit` or `--timeout` is
+/// reached, whichever comes first.
 #[derive(clap::Args)]
 pub struct Args {
     /// Output directory.
@@ -35,6 +38,10 @@ pub struct Args {
     /// Exit after saving this many chunks.
     #[clap(short, long)]
     limit: Option<usize>,
+
+    /// Exit after this many seconds, no matter how many chunks were saved.
+    #[clap(short, long, value_name = "SECONDS")]
+    timeout: Option<u64>,
 }
 
 pub fn main(args: Args) {
@@ -48,6 +55,7 @@ pub fn main(args: Args) {
         .insert_resource(args)
         .add_system(receive_chunks)
         .add_system(handle_disconnect)
+        .add_system(handle_timeout)
         .run();
 }
 
@@ -61,6 +69,15 @@ fn handle_disconnect(
     }
 }
 
+fn handle_timeout(args: Res<Args>, time: Res<Time>, mut app_exit: EventWriter<AppExit>) {
+    if let Some(timeout) = args.timeout {
+        if time.time_since_startup() >= Duration::from_secs(timeout) {
+            println!("Timed out after {} seconds, terminating.", timeout);
+            app_exit.send(AppExit);
+        }
+    }
+}
+
 fn receive_chunks(
     args: Res<Args>,
     mut chunks_saved: Local<usize>,
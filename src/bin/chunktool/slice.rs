@@ -0,0 +1,263 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use brine::chunk::{load_chunk, Result};
+use brine_chunk::{BlockState, Chunk, CHUNK_HEIGHT, CHUNK_WIDTH};
+use brine_data::{BlockStateId, MinecraftData};
+
+/// Renders a horizontal 16x16 slice of a chunk loaded from disk.
+///
+/// By default, the slice is printed to stdout as a grid of colored letters,
+/// followed by a legend of the blocks in the slice. Each block is given the
+/// same color every time, so slices can be compared against each other.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to a chunk data file to load.
+    file: PathBuf,
+
+    /// Y coordinate of the slice within the chunk (0-255).
+    y: u16,
+
+    /// Save the slice as a PNG image to this path instead of printing it.
+    #[clap(long, value_name = "FILE")]
+    png: Option<PathBuf>,
+
+    /// Size of each block in the PNG image, in pixels.
+    #[clap(long, default_value = "16")]
+    scale: u32,
+}
+
+pub(crate) fn main(args: Args) {
+    match slice_chunk_from_file(&args) {
+        Ok(()) => {}
+        Err(e) => println!("ERROR: {}", e),
+    }
+}
+
+fn slice_chunk_from_file(args: &Args) -> Result<()> {
+    let data = MinecraftData::for_version("1.14.4");
+    let chunk = load_chunk(&args.file)?;
+
+    let slice = match Slice::from_chunk(&chunk, args.y) {
+        Some(slice) => slice,
+        None => {
+            println!("ERROR: y must be less than {}", CHUNK_HEIGHT);
+            return Ok(());
+        }
+    };
+
+    match args.png.as_ref() {
+        Some(path) => save_png(&slice, &data, path, args.scale.max(1)),
+        None => print_ascii(&slice, &data),
+    }
+
+    Ok(())
+}
+
+/// The block states in one horizontal layer of a chunk.
+pub struct Slice {
+    y: u16,
+    /// One block state per cell, in Z-X order.
+    cells: Vec<BlockState>,
+}
+
+impl Slice {
+    /// Returns the slice of `chunk` at height `y`, or `None` if `y` is outside
+    /// the chunk.
+    pub fn from_chunk(chunk: &Chunk, y: u16) -> Option<Self> {
+        let mut cells = Vec::with_capacity(CHUNK_WIDTH * CHUNK_WIDTH);
+
+        for z in 0..CHUNK_WIDTH as u8 {
+            for x in 0..CHUNK_WIDTH as u8 {
+                cells.push(chunk.get_block_at_local(x, y, z)?);
+            }
+        }
+
+        Some(Self { y, cells })
+    }
+
+    pub fn get(&self, x: u8, z: u8) -> BlockState {
+        self.cells[z as usize * CHUNK_WIDTH + x as usize]
+    }
+
+    /// Iterates over the rows of the slice, from `z = 0` to `z = 15`.
+    pub fn rows(&self) -> impl Iterator<Item = &[BlockState]> + '_ {
+        self.cells.chunks(CHUNK_WIDTH)
+    }
+}
+
+/// The name and color of a block state, as looked up in the block data.
+struct Label {
+    name: String,
+    color: [u8; 3],
+}
+
+fn label(state: BlockState, data: &MinecraftData) -> Label {
+    let block = data.blocks().get_by_state_id(BlockStateId(state.0 as u16));
+
+    match block {
+        Some(block) if block.is_air() => Label {
+            name: block.name.to_string(),
+            color: AIR_COLOR,
+        },
+        Some(block) => Label {
+            name: block.name.to_string(),
+            color: block_color(block.id as u32),
+        },
+        None => Label {
+            name: format!("unknown ({})", state.0),
+            color: block_color(u32::MAX - state.0),
+        },
+    }
+}
+
+const AIR_COLOR: [u8; 3] = [0, 0, 0];
+
+/// Returns a color for the block with the given id.
+///
+/// The color is derived from a hash of the id, so a block always gets the same
+/// color, and similar ids get very different colors.
+fn block_color(block_id: u32) -> [u8; 3] {
+    // Murmur3 finalizer.
+    let mut hash = block_id;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+
+    // Keep every channel away from black so that blocks stand out from air.
+    let [r, g, b, _] = hash.to_le_bytes();
+    [r | 0x40, g | 0x40, b | 0x40]
+}
+
+/// Characters used to label the distinct blocks in a slice.
+const SYMBOLS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+fn print_ascii(slice: &Slice, data: &MinecraftData) {
+    struct Entry {
+        symbol: char,
+        state: BlockState,
+        label: Label,
+        count: usize,
+    }
+
+    // Index into `legend` of each block state, in order of first appearance.
+    let mut indices: HashMap<BlockState, usize> = HashMap::new();
+    let mut legend: Vec<Entry> = Vec::new();
+
+    for &state in slice.cells.iter() {
+        let index = *indices.entry(state).or_insert_with(|| {
+            let label = label(state, data);
+            let symbol = if label.color == AIR_COLOR {
+                '.'
+            } else {
+                let non_air = legend.iter().filter(|entry| entry.symbol != '.').count();
+                SYMBOLS.get(non_air).map(|&c| c as char).unwrap_or('?')
+            };
+
+            legend.push(Entry {
+                symbol,
+                state,
+                label,
+                count: 0,
+            });
+            legend.len() - 1
+        });
+
+        legend[index].count += 1;
+    }
+
+    println!();
+    println!(
+        "Slice at y = {} (x increases to the right, z downwards)",
+        slice.y
+    );
+    println!();
+
+    for row in slice.rows() {
+        let line: String = row
+            .iter()
+            .map(|state| {
+                let entry = &legend[indices[state]];
+                colored(&format!("{} ", entry.symbol), entry.label.color)
+            })
+            .collect();
+
+        println!("{}", line);
+    }
+
+    println!();
+    for entry in legend.iter() {
+        println!(
+            "{} {:5} {:30} {}",
+            colored(&entry.symbol.to_string(), entry.label.color),
+            entry.state.0,
+            entry.label.name,
+            entry.count
+        );
+    }
+}
+
+/// Wraps `text` in 24-bit ANSI color escapes.
+fn colored(text: &str, [r, g, b]: [u8; 3]) -> String {
+    format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text)
+}
+
+fn save_png(slice: &Slice, data: &MinecraftData, path: &Path, scale: u32) {
+    let mut colors: HashMap<BlockState, [u8; 3]> = HashMap::new();
+
+    let size = CHUNK_WIDTH as u32 * scale;
+    let image = image::RgbImage::from_fn(size, size, |px, py| {
+        let state = slice.get((px / scale) as u8, (py / scale) as u8);
+        let color = *colors
+            .entry(state)
+            .or_insert_with(|| label(state, data).color);
+
+        image::Rgb(color)
+    });
+
+    match image.save(path) {
+        Ok(()) => println!("Saved slice at y = {} to {}", slice.y, path.display()),
+        Err(e) => println!("ERROR: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::{BlockStates, ChunkSection};
+
+    use super::*;
+
+    #[test]
+    fn slice_has_a_cell_for_every_column() {
+        let mut section = ChunkSection::empty(4);
+        section.block_states.0[BlockStates::xyz_to_index(3, 2, 9)] = BlockState(1);
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections = vec![section];
+
+        let slice = Slice::from_chunk(&chunk, 4 * 16 + 2).unwrap();
+
+        assert_eq!(slice.cells.len(), 256);
+        assert_eq!(slice.rows().count(), 16);
+        assert_eq!(slice.get(3, 9), BlockState(1));
+        assert_eq!(slice.get(9, 3), BlockState::AIR);
+
+        // Sections that are not present are all air.
+        let slice = Slice::from_chunk(&chunk, 0).unwrap();
+        assert_eq!(slice.cells.len(), 256);
+        assert!(slice.cells.iter().all(|&state| state == BlockState::AIR));
+
+        assert!(Slice::from_chunk(&chunk, CHUNK_HEIGHT as u16).is_none());
+    }
+
+    #[test]
+    fn block_colors_are_deterministic() {
+        assert_eq!(block_color(1), block_color(1));
+        assert_ne!(block_color(1), block_color(2));
+        assert_ne!(block_color(1), AIR_COLOR);
+    }
+}
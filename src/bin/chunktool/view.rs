@@ -1,14 +1,17 @@
 use std::{
     f32::consts::PI,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use bevy::{
+    app::AppExit,
     log::{Level, LogSettings},
     pbr::wireframe::{WireframeConfig, WireframePlugin},
     prelude::*,
     render::{options::WgpuOptions, render_resource::WgpuFeatures},
 };
+use bevy_fly_camera::{FlyCamera, FlyCameraPlugin};
 use bevy_inspector_egui::WorldInspectorPlugin;
 
 use brine_asset::MinecraftAssets;
@@ -17,42 +20,161 @@ use brine_data::MinecraftData;
 use brine_proto::{event, ProtocolPlugin};
 use brine_voxel_v1::{
     chunk_builder::{
-        component::{BuiltChunk, BuiltChunkSection},
-        ChunkBuilderPlugin, GreedyQuadsChunkBuilder, NaiveBlocksChunkBuilder,
-        VisibleFacesChunkBuilder,
+        component::{BuiltChunk, BuiltChunkSection, PendingChunk},
+        ChunkBuilderPlugin, ChunkBuilderType, GreedyQuadsChunkBuilder, Lod2ChunkBuilder,
+        ManualPlacement, NaiveBlocksChunkBuilder, VisibleFacesChunkBuilder,
     },
+    material::VoxelMaterial,
     texture::TextureBuilderPlugin,
 };
 
 use brine::{
     chunk::{load_chunk, Result},
-    error::log_error,
+    error::{exit_on_error, log_error},
+    hud::DEFAULT_FONT,
+    screenshot::{FrameCapture, FrameCapturePlugin},
     DEFAULT_LOG_FILTER,
 };
-use clap::ArgEnum;
+
+use crate::bookmarks::{Bookmark, BookmarkError, Bookmarks};
 
 /// Loads a chunk from a file and views it in 3D.
+///
+/// The output of each chunk builder is shown side by side, from left to right
+/// in the order given, each labeled with the builder's name (drawn with the
+/// HUD font, `assets/fonts/hud.ttf`). Press the function keys to show or hide
+/// each builder's output (`F1` for the first builder, `F2` for the second, and
+/// so on).
+///
+/// Fly around with `WASD`, `E`/`Q` and the mouse. Press `B` to bookmark the
+/// camera's position in a `.bookmarks.toml` file next to the chunk file, and
+/// the number keys to go back to a bookmark (`1` for the first one, and so on).
 #[derive(clap::Args)]
 pub struct Args {
     /// Paths to one or more chunk data files to load.
     files: Vec<PathBuf>,
 
-    /// Which chunk builder to test.
-    #[clap(arg_enum, short, long, default_value = "visible_faces")]
-    builder: ChunkBuilderType,
+    /// Comma-separated list of chunk builders to compare. Possible values:
+    /// naive, visible_faces, greedy, lod2.
+    #[clap(
+        short,
+        long,
+        default_value = "naive,visible_faces",
+        parse(try_from_str = Builders::parse)
+    )]
+    builders: Builders,
+
+    /// Start the camera at the bookmark with this name, from the first chunk
+    /// file's bookmarks.
+    #[clap(long)]
+    bookmark: Option<String>,
+
+    /// Once the chunk has been built and all of its textures have loaded, save
+    /// a screenshot to this file and exit.
+    #[clap(long, value_name = "FILE")]
+    screenshot: Option<PathBuf>,
 }
 
-#[derive(Clone, ArgEnum)]
-#[clap(rename_all = "snake_case")]
-enum ChunkBuilderType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Builder {
+    Naive,
     VisibleFaces,
-    GreedyQuads,
+    Greedy,
+    Lod2,
+}
+
+impl Builder {
+    fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.trim() {
+            "naive" | "naive_blocks" => Ok(Self::Naive),
+            "visible_faces" => Ok(Self::VisibleFaces),
+            "greedy" | "greedy_quads" => Ok(Self::Greedy),
+            "lod2" => Ok(Self::Lod2),
+            other => Err(format!("unknown chunk builder: {:?}", other)),
+        }
+    }
+
+    fn builder_type(self) -> ChunkBuilderType {
+        match self {
+            Self::Naive => ChunkBuilderType::NAIVE_BLOCKS,
+            Self::VisibleFaces => ChunkBuilderType::VISIBLE_FACES,
+            Self::Greedy => ChunkBuilderType::GREEDY_QUADS,
+            Self::Lod2 => ChunkBuilderType::LOD2,
+        }
+    }
+
+    fn add_plugin(self, app: &mut App) {
+        match self {
+            Self::Naive => {
+                app.add_plugin(ChunkBuilderPlugin::<NaiveBlocksChunkBuilder>::shared());
+            }
+            Self::VisibleFaces => {
+                app.add_plugin(ChunkBuilderPlugin::<VisibleFacesChunkBuilder>::shared());
+            }
+            Self::Greedy => {
+                app.add_plugin(ChunkBuilderPlugin::<GreedyQuadsChunkBuilder>::shared());
+            }
+            Self::Lod2 => {
+                app.add_plugin(ChunkBuilderPlugin::<Lod2ChunkBuilder>::shared());
+            }
+        }
+    }
+}
+
+/// The chunk builders being compared, in the order they are displayed.
+#[derive(Debug, Clone)]
+struct Builders {
+    builders: Vec<Builder>,
+    visible: Vec<bool>,
+}
+
+impl Builders {
+    fn parse(s: &str) -> std::result::Result<Self, String> {
+        let mut builders = Vec::new();
+        for builder in s.split(',').map(Builder::parse) {
+            let builder = builder?;
+            if !builders.contains(&builder) {
+                builders.push(builder);
+            }
+        }
+
+        if builders.is_empty() {
+            return Err(String::from("at least one chunk builder is required"));
+        }
+
+        let visible = vec![true; builders.len()];
+
+        Ok(Self { builders, visible })
+    }
+
+    fn index_of(&self, builder_type: ChunkBuilderType) -> Option<usize> {
+        self.builders
+            .iter()
+            .position(|builder| builder.builder_type() == builder_type)
+    }
+
+    /// Position of the given builder's output along the X axis, such that all
+    /// outputs are centered on the origin.
+    fn x_offset(&self, builder_type: ChunkBuilderType) -> f32 {
+        let index = self.index_of(builder_type).unwrap_or_default() as f32;
+        let center = (self.builders.len() - 1) as f32 / 2.0;
+
+        (index - center) * 2.0 * DISTANCE_FROM_ORIGIN
+    }
+
+    fn is_visible(&self, builder_type: ChunkBuilderType) -> bool {
+        self.index_of(builder_type)
+            .map_or(true, |index| self.visible[index])
+    }
 }
 
 struct Chunks {
     files: Vec<PathBuf>,
     next_file: usize,
 
+    /// The file that the current chunk was loaded from.
+    file: Option<PathBuf>,
+
     chunk: Option<Chunk>,
     next_section: usize,
 }
@@ -63,6 +185,8 @@ impl Chunks {
             files,
             next_file: 0,
 
+            file: None,
+
             chunk: None,
             next_section: 0,
         }
@@ -72,6 +196,10 @@ impl Chunks {
         self.chunk.as_ref().unwrap()
     }
 
+    fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
     fn next_file(&mut self) -> &Path {
         let path = &self.files[self.next_file];
         self.next_file = (self.next_file + 1) % self.files.len();
@@ -79,10 +207,11 @@ impl Chunks {
     }
 
     fn load_next_file(&mut self) -> Result<()> {
-        let path = self.next_file();
-        let chunk = load_chunk(path)?;
+        let path = self.next_file().to_owned();
+        let chunk = load_chunk(&path)?;
         self.next_section = chunk.sections.len() - 1;
         self.chunk = Some(chunk);
+        self.file = Some(path);
         Ok(())
     }
 
@@ -113,7 +242,51 @@ impl Chunks {
 
 const DISTANCE_FROM_ORIGIN: f32 = 13.0;
 
+/// Height of the builder labels above the origin, just above the top of the
+/// section being viewed.
+const LABEL_HEIGHT: f32 = 12.0;
+
+const LABEL_FONT_SIZE: f32 = 20.0;
+
+/// Where the camera starts.
+struct CameraStart(Transform);
+
+impl CameraStart {
+    /// Returns the bookmark given on the command line, or a view of the
+    /// origin if there isn't one.
+    fn from_args(args: &Args) -> std::result::Result<Self, BookmarkError> {
+        let (name, file) = match (&args.bookmark, args.files.first()) {
+            (Some(name), Some(file)) => (name, file),
+            _ => {
+                return Ok(Self(
+                    Transform::from_translation(Vec3::new(0.0, 8.0, 38.0))
+                        .looking_at(Vec3::ZERO, Vec3::Y),
+                ))
+            }
+        };
+
+        let path = Bookmarks::path_for_chunk(file);
+        let bookmarks = Bookmarks::load(&path)?;
+
+        match bookmarks.get(name) {
+            Some(bookmark) => Ok(Self(bookmark.transform())),
+            None => Err(BookmarkError::NotFound {
+                path,
+                name: name.clone(),
+            }),
+        }
+    }
+}
+
 pub fn main(args: Args) {
+    let camera_start = match CameraStart::from_args(&args) {
+        Ok(camera_start) => camera_start,
+        Err(e) => {
+            println!("ERROR: {}", e);
+            return;
+        }
+    };
+
     let mut app = App::new();
 
     app.insert_resource(LogSettings {
@@ -128,28 +301,34 @@ pub fn main(args: Args) {
     .insert_resource(Msaa { samples: 4 })
     .insert_resource(WireframeConfig { global: true })
     .add_plugin(WireframePlugin)
-    .add_plugin(WorldInspectorPlugin::new())
+    .add_plugin(FlyCameraPlugin)
     .add_plugin(ProtocolPlugin);
 
+    match args.screenshot {
+        Some(path) => {
+            app.add_plugin(FrameCapturePlugin)
+                .insert_resource(Screenshot { path })
+                .add_system(take_screenshot_when_ready.chain(exit_on_error));
+        }
+        None => {
+            app.add_plugin(WorldInspectorPlugin::new());
+        }
+    }
+
     let mc_data = MinecraftData::for_version("1.14.4");
     let mc_assets = MinecraftAssets::new("assets/1.14.4", &mc_data).unwrap();
     app.insert_resource(mc_data);
     app.insert_resource(mc_assets);
     app.add_plugin(TextureBuilderPlugin);
 
-    app.add_plugin(ChunkBuilderPlugin::<NaiveBlocksChunkBuilder>::shared());
-
-    match args.builder {
-        ChunkBuilderType::VisibleFaces => {
-            app.add_plugin(ChunkBuilderPlugin::<VisibleFacesChunkBuilder>::shared());
-        }
-        ChunkBuilderType::GreedyQuads => {
-            app.add_plugin(ChunkBuilderPlugin::<GreedyQuadsChunkBuilder>::shared());
-        }
+    for builder in args.builders.builders.iter() {
+        builder.add_plugin(&mut app);
     }
 
+    app.insert_resource(args.builders);
     app.add_plugin(ChunkViewerPlugin);
 
+    app.insert_resource(camera_start);
     app.add_startup_system(load_first_chunk.chain(log_error))
         .add_startup_system(set_up_camera)
         .add_system(load_next_chunk.chain(log_error));
@@ -193,38 +372,337 @@ fn load_next_chunk(
     Ok(())
 }
 
-fn set_up_camera(mut commands: Commands) {
-    commands.spawn_bundle(PerspectiveCameraBundle {
-        transform: Transform::from_translation(Vec3::new(0.0, 8.0, 38.0))
-            .looking_at(Vec3::ZERO, Vec3::Y),
+fn set_up_camera(
+    mut commands: Commands,
+    camera_start: Res<CameraStart>,
+    screenshot: Option<Res<Screenshot>>,
+) {
+    let mut fly_camera = FlyCamera {
+        key_up: KeyCode::E,
+        key_down: KeyCode::Q,
+        // Keep the mouse from moving the camera before the screenshot.
+        enabled: screenshot.is_none(),
         ..Default::default()
-    });
+    };
+    aim_fly_camera(&mut fly_camera, camera_start.0.rotation);
+
+    commands
+        .spawn_bundle(PerspectiveCameraBundle {
+            transform: camera_start.0,
+            ..Default::default()
+        })
+        .insert(fly_camera);
+}
+
+/// Points a fly camera in the direction of `rotation`.
+///
+/// The fly camera overwrites its transform's rotation with its own pitch and
+/// yaw whenever the mouse moves, so they have to be kept in sync with it.
+fn aim_fly_camera(fly_camera: &mut FlyCamera, rotation: Quat) {
+    let (yaw, pitch, _roll) = rotation.to_euler(EulerRot::YXZ);
+    fly_camera.yaw = yaw.to_degrees();
+    fly_camera.pitch = -pitch.to_degrees();
+}
+
+fn go_to_bookmark(bookmark: &Bookmark, transform: &mut Transform, fly_camera: &mut FlyCamera) {
+    *transform = bookmark.transform();
+    aim_fly_camera(fly_camera, transform.rotation);
+    info!("Moved camera to bookmark {:?}", bookmark.name);
+}
+
+/// Path that [`take_screenshot_when_ready`] saves the screenshot to.
+struct Screenshot {
+    path: PathBuf,
+}
+
+/// Number of frames in a row that everything has to be ready, with no assets
+/// changing, before the screenshot is taken. Gives the renderer time to
+/// prepare the last assets that changed.
+const SETTLE_FRAMES: u32 = 3;
+
+/// How long to wait for everything to be ready before giving up on the
+/// screenshot.
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+enum ScreenshotError {
+    #[error("failed to save screenshot: {0}")]
+    Save(#[from] image::ImageError),
+
+    #[error("chunk was still not ready for a screenshot after {0:?}")]
+    Timeout(Duration),
+}
+
+/// System that captures a frame once all chunks have been built and all of
+/// the textures that they use have loaded, then saves it and exits.
+#[allow(clippy::too_many_arguments)]
+fn take_screenshot_when_ready(
+    screenshot: Res<Screenshot>,
+    frame_capture: Res<FrameCapture>,
+    time: Res<Time>,
+    pending_chunks: Query<(), With<PendingChunk>>,
+    sections: Query<&Handle<VoxelMaterial>, With<BuiltChunkSection>>,
+    materials: Res<Assets<VoxelMaterial>>,
+    images: Res<Assets<Image>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut material_events: EventReader<AssetEvent<VoxelMaterial>>,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut settled_frames: Local<u32>,
+    mut app_exit: EventWriter<AppExit>,
+) -> std::result::Result<(), ScreenshotError> {
+    if let Some(image) = frame_capture.take() {
+        image.save(&screenshot.path)?;
+        info!("Saved screenshot to {}", screenshot.path.display());
+        app_exit.send(AppExit);
+        return Ok(());
+    }
+
+    if frame_capture.is_pending() {
+        return Ok(());
+    }
+
+    if time.time_since_startup() > SCREENSHOT_TIMEOUT {
+        return Err(ScreenshotError::Timeout(SCREENSHOT_TIMEOUT));
+    }
+
+    // Read all of the events, so that none are left over for the next frame.
+    let num_asset_events =
+        image_events.iter().count() + material_events.iter().count() + mesh_events.iter().count();
+
+    let is_ready = pending_chunks.iter().next().is_none()
+        && sections.iter().next().is_some()
+        && sections
+            .iter()
+            .all(|material| is_material_loaded(material, &materials, &images));
+
+    if is_ready && num_asset_events == 0 {
+        *settled_frames += 1;
+    } else {
+        *settled_frames = 0;
+    }
+
+    if *settled_frames >= SETTLE_FRAMES {
+        frame_capture.request();
+    }
+
+    Ok(())
+}
+
+fn is_material_loaded(
+    handle: &Handle<VoxelMaterial>,
+    materials: &Assets<VoxelMaterial>,
+    images: &Assets<Image>,
+) -> bool {
+    materials
+        .get(handle)
+        .map_or(false, |material| images.contains(&material.texture))
 }
 
 struct ChunkViewerPlugin;
 
+/// Component of the text that labels a builder's output.
+#[derive(Component)]
+struct BuilderLabel(ChunkBuilderType);
+
 impl Plugin for ChunkViewerPlugin {
     fn build(&self, app: &mut App) {
+        app.add_startup_system(Self::spawn_labels);
+        app.add_system(Self::place_labels);
         app.add_system(Self::center_section_at_bottom_of_chunk);
         app.add_system(Self::rename_chunks);
         app.add_system(Self::move_and_rotate);
         app.add_system(Self::rotate_chunk);
+        app.add_system(Self::log_build_stats);
+        app.add_system(Self::toggle_builders);
+        app.add_system(Self::save_and_recall_bookmarks.chain(log_error));
+        app.add_system(Self::update_visibility);
     }
 }
 
 impl ChunkViewerPlugin {
-    fn move_and_rotate(mut query: Query<(&mut Transform, &BuiltChunk), Added<BuiltChunk>>) {
-        for (mut transform, built_chunk) in query.iter_mut() {
+    fn spawn_labels(
+        builders: Res<Builders>,
+        asset_server: Res<AssetServer>,
+        mut commands: Commands,
+    ) {
+        commands.spawn_bundle(UiCameraBundle::default());
+
+        let font = asset_server.load(DEFAULT_FONT);
+
+        for (index, builder) in builders.builders.iter().enumerate() {
+            let builder_type = builder.builder_type();
+
+            commands
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        format!("F{}: {}", index + 1, builder_type.0),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: LABEL_FONT_SIZE,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert_bundle((
+                    Name::new(format!("Label ({})", builder_type.0)),
+                    BuilderLabel(builder_type),
+                ));
+        }
+    }
+
+    /// Keeps each label on the screen just above its builder's output, and
+    /// hides it along with the output.
+    fn place_labels(
+        builders: Res<Builders>,
+        windows: Res<Windows>,
+        cameras: Query<(&Camera, &GlobalTransform), With<FlyCamera>>,
+        mut labels: Query<(&BuilderLabel, &mut Style, &mut Visibility)>,
+    ) {
+        let (camera, camera_transform) = match cameras.get_single() {
+            Ok(camera) => camera,
+            Err(_) => return,
+        };
+
+        for (label, mut style, mut visibility) in labels.iter_mut() {
+            let position = Vec3::new(builders.x_offset(label.0), LABEL_HEIGHT, 0.0);
+            let screen_position = camera.world_to_screen(&windows, camera_transform, position);
+
+            let is_visible = screen_position.is_some() && builders.is_visible(label.0);
+            if visibility.is_visible != is_visible {
+                visibility.is_visible = is_visible;
+            }
+
+            if let Some(screen_position) = screen_position {
+                style.position = Rect {
+                    left: Val::Px(screen_position.x),
+                    bottom: Val::Px(screen_position.y),
+                    ..Default::default()
+                };
+            }
+        }
+    }
+
+    fn move_and_rotate(
+        builders: Res<Builders>,
+        mut query: Query<(Entity, &mut Transform, &BuiltChunk), Added<BuiltChunk>>,
+        mut commands: Commands,
+    ) {
+        for (entity, mut transform, built_chunk) in query.iter_mut() {
             transform.rotate(Quat::from_rotation_y(PI / 4.0));
+            transform.translation = Vec3::X * builders.x_offset(built_chunk.builder);
 
-            use brine_voxel_v1::chunk_builder::ChunkBuilderType;
-            match built_chunk.builder {
-                ChunkBuilderType::NAIVE_BLOCKS => {
-                    transform.translation = -Vec3::X * DISTANCE_FROM_ORIGIN;
-                }
-                _ => {
-                    transform.translation = Vec3::X * DISTANCE_FROM_ORIGIN;
-                }
+            // Keep the chunk in its builder's column instead of at its
+            // coordinates relative to the chunk origin.
+            commands.entity(entity).insert(ManualPlacement);
+        }
+    }
+
+    fn log_build_stats(
+        meshes: Res<Assets<Mesh>>,
+        chunks: Query<(&BuiltChunk, &Children), Added<BuiltChunk>>,
+        sections: Query<&Handle<Mesh>, With<BuiltChunkSection>>,
+    ) {
+        for (built_chunk, children) in chunks.iter() {
+            let mut num_vertices = 0;
+            let mut num_triangles = 0;
+
+            for mesh in children
+                .iter()
+                .filter_map(|child| sections.get(*child).ok())
+                .filter_map(|handle| meshes.get(handle))
+            {
+                num_vertices += mesh.count_vertices();
+                num_triangles += mesh.indices().map_or(0, |indices| indices.len() / 3);
+            }
+
+            info!(
+                "{:<24} {:>8} vertices {:>8} triangles {:>10.3?}",
+                built_chunk.builder.0, num_vertices, num_triangles, built_chunk.build_time
+            );
+        }
+    }
+
+    fn toggle_builders(input: Res<Input<KeyCode>>, mut builders: ResMut<Builders>) {
+        const KEYS: [KeyCode; 9] = [
+            KeyCode::F1,
+            KeyCode::F2,
+            KeyCode::F3,
+            KeyCode::F4,
+            KeyCode::F5,
+            KeyCode::F6,
+            KeyCode::F7,
+            KeyCode::F8,
+            KeyCode::F9,
+        ];
+
+        for (index, key) in KEYS.iter().enumerate().take(builders.builders.len()) {
+            if input.just_pressed(*key) {
+                builders.visible[index] = !builders.visible[index];
+            }
+        }
+    }
+
+    fn save_and_recall_bookmarks(
+        input: Res<Input<KeyCode>>,
+        chunks: Res<Chunks>,
+        mut cameras: Query<(&mut Transform, &mut FlyCamera)>,
+    ) -> std::result::Result<(), BookmarkError> {
+        const KEYS: [KeyCode; 9] = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+
+        let should_save = input.just_pressed(KeyCode::B);
+        let recalled = KEYS.iter().position(|key| input.just_pressed(*key));
+        if !should_save && recalled.is_none() {
+            return Ok(());
+        }
+
+        let (mut transform, mut fly_camera) = match cameras.get_single_mut() {
+            Ok(camera) => camera,
+            Err(_) => return Ok(()),
+        };
+
+        let path = match chunks.file() {
+            Some(file) => Bookmarks::path_for_chunk(file),
+            None => return Ok(()),
+        };
+
+        // Read the file every time, in case it has been edited by hand.
+        let mut bookmarks = Bookmarks::load(&path)?;
+
+        if should_save {
+            let name = bookmarks.add(&transform).name.clone();
+            bookmarks.save(&path)?;
+            info!("Saved bookmark {:?} to {}", name, path.display());
+        } else if let Some(bookmark) = recalled.and_then(|index| bookmarks.bookmarks.get(index)) {
+            go_to_bookmark(bookmark, &mut transform, &mut fly_camera);
+        }
+
+        Ok(())
+    }
+
+    fn update_visibility(
+        builders: Res<Builders>,
+        mut query: Query<(&BuiltChunkSection, &mut Visibility)>,
+    ) {
+        for (section, mut visibility) in query.iter_mut() {
+            let is_visible = builders.is_visible(section.builder);
+            if visibility.is_visible != is_visible {
+                visibility.is_visible = is_visible;
             }
         }
     }
@@ -0,0 +1,295 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{prelude::*, tasks::IoTaskPool};
+use serde::{Deserialize, Serialize};
+
+use brine_data::Version;
+use brine_proto::event::clientbound::ChunkData;
+use brine_proto_backend::version::VersionAdapter;
+
+use crate::{
+    chunk::{save_chunk, Result},
+    error::exit_on_error,
+};
+
+/// Name of the file in the capture directory that describes the capture.
+pub const CAPTURE_INDEX_FILE: &str = "index.json";
+
+/// A plugin that saves every full chunk received from the server to a
+/// directory, so that it can be served again later with
+/// [`ServeChunksFromDirectoryPlugin`][crate::server::ServeChunksFromDirectoryPlugin].
+///
+/// Chunks are saved to `chunk_{X}_{Z}.chunk`. A chunk that is received more
+/// than once is overwritten, unless [`keep_history`][Self::keep_history] is
+/// enabled, in which case each copy is saved to `chunk_{X}_{Z}_{N}.chunk` with
+/// increasing `N`.
+///
+/// The capture's [`CaptureIndex`] records the protocol version that the
+/// server speaks, and is rewritten whenever the [`VersionAdapter`] changes
+/// (i.e., once the server's protocol version has been discovered).
+///
+/// This plugin reads `ChunkData` events with a regular [`EventReader`], so any
+/// [`ChunkBuilderPlugin`]s must be created with
+/// [`ChunkBuilderPlugin::shared`] so that the events are not drained first.
+///
+/// [`ChunkBuilderPlugin`]: brine_voxel_v1::chunk_builder::ChunkBuilderPlugin
+/// [`ChunkBuilderPlugin::shared`]: brine_voxel_v1::chunk_builder::ChunkBuilderPlugin::shared
+pub struct ChunkCapturePlugin<P> {
+    path: P,
+    keep_history: bool,
+}
+
+impl<P> ChunkCapturePlugin<P> {
+    pub fn new(path: P) -> Self {
+        Self {
+            path,
+            keep_history: false,
+        }
+    }
+
+    /// Keep every copy of a chunk instead of overwriting previous ones.
+    pub fn keep_history(mut self, keep_history: bool) -> Self {
+        self.keep_history = keep_history;
+        self
+    }
+}
+
+impl<P> Plugin for ChunkCapturePlugin<P>
+where
+    P: AsRef<Path> + Any + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        let path = PathBuf::from(self.path.as_ref());
+        let captured_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        app.insert_resource(ChunkCapture {
+            path,
+            keep_history: self.keep_history,
+            captured_at,
+            history: Default::default(),
+        });
+        app.init_resource::<VersionAdapter>();
+        app.add_startup_system(create_capture_directory.chain(exit_on_error));
+        app.add_system(write_capture_index.chain(exit_on_error));
+        app.add_system(capture_chunks);
+    }
+}
+
+#[derive(Debug)]
+pub struct ChunkCapture {
+    path: PathBuf,
+    keep_history: bool,
+    captured_at: u64,
+    history: HashMap<(i32, i32), usize>,
+}
+
+impl ChunkCapture {
+    /// Returns the path (minus extension) that the next copy of the chunk at
+    /// the given coordinates should be saved to.
+    fn next_path(&mut self, chunk_x: i32, chunk_z: i32) -> PathBuf {
+        let file_name = if self.keep_history {
+            let sequence_number = self.history.entry((chunk_x, chunk_z)).or_default();
+            let file_name = format!("chunk_{}_{}_{}", chunk_x, chunk_z, sequence_number);
+            *sequence_number += 1;
+            file_name
+        } else {
+            format!("chunk_{}_{}", chunk_x, chunk_z)
+        };
+
+        self.path.join(file_name)
+    }
+}
+
+/// Contents of the [`CAPTURE_INDEX_FILE`].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CaptureIndex {
+    /// Minecraft version of the captured chunk data.
+    pub version: String,
+
+    /// Protocol version of the captured chunk data.
+    pub protocol_version: i32,
+
+    /// When the capture started, in seconds since the Unix epoch.
+    pub captured_at: u64,
+}
+
+fn create_capture_directory(capture: Res<ChunkCapture>) -> Result<()> {
+    fs::create_dir_all(&capture.path)?;
+
+    info!("Capturing chunks to {}", capture.path.to_string_lossy());
+
+    Ok(())
+}
+
+fn write_capture_index(capture: Res<ChunkCapture>, adapter: Res<VersionAdapter>) -> Result<()> {
+    if !adapter.is_changed() {
+        return Ok(());
+    }
+
+    let protocol_version = adapter.protocol_version();
+    let version = match Version::from_protocol_version(protocol_version) {
+        Some(version) => version.name().to_string(),
+        None => {
+            warn!(
+                "No Minecraft version known for protocol version {}",
+                protocol_version
+            );
+            String::new()
+        }
+    };
+
+    let index = CaptureIndex {
+        version,
+        protocol_version,
+        captured_at: capture.captured_at,
+    };
+
+    let index_file = fs::File::create(capture.path.join(CAPTURE_INDEX_FILE))?;
+    serde_json::to_writer_pretty(index_file, &index)?;
+
+    debug!(
+        "Wrote capture index for protocol version {}",
+        index.protocol_version
+    );
+
+    Ok(())
+}
+
+fn capture_chunks(
+    mut capture: ResMut<ChunkCapture>,
+    mut chunk_events: EventReader<ChunkData>,
+    task_pool: Res<IoTaskPool>,
+) {
+    for ChunkData { chunk_data } in chunk_events.iter() {
+        // Deltas can't be served back on their own.
+        if !chunk_data.is_full() {
+            continue;
+        }
+
+        let path = capture.next_path(chunk_data.chunk_x, chunk_data.chunk_z);
+        let chunk = chunk_data.clone();
+
+        task_pool
+            .spawn(async move {
+                match save_chunk(&chunk, &path) {
+                    Ok(path) => debug!("Captured chunk to {}", path.to_string_lossy()),
+                    Err(e) => error!("Failed to capture chunk: {}", e),
+                }
+            })
+            .detach();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::{BlockState, Chunk, ChunkSection};
+
+    use super::*;
+    use crate::chunk::load_chunk;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("brine_capture_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn chunk(chunk_x: i32, chunk_z: i32, block: u32) -> Chunk {
+        let mut section = ChunkSection::empty(4);
+        section.block_states.0[0] = BlockState(block);
+        section.block_count = 1;
+
+        let mut chunk = Chunk::empty(chunk_x, chunk_z);
+        chunk.sections = vec![section];
+        chunk
+    }
+
+    #[test]
+    fn captured_chunks_load_back() {
+        let dir = temp_dir("round_trip");
+        let mut capture = ChunkCapture {
+            path: dir.clone(),
+            keep_history: false,
+            captured_at: 0,
+            history: Default::default(),
+        };
+
+        let chunks = [chunk(0, 0, 1), chunk(-1, 2, 9), chunk(0, 0, 2)];
+        for chunk in chunks.iter() {
+            save_chunk(chunk, capture.next_path(chunk.chunk_x, chunk.chunk_z)).unwrap();
+        }
+
+        // The second copy of (0, 0) overwrote the first.
+        assert_eq!(load_chunk(dir.join("chunk_0_0.chunk")).unwrap(), chunks[2]);
+        assert_eq!(load_chunk(dir.join("chunk_-1_2.chunk")).unwrap(), chunks[1]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keep_history() {
+        let dir = temp_dir("keep_history");
+        let mut capture = ChunkCapture {
+            path: dir.clone(),
+            keep_history: true,
+            captured_at: 0,
+            history: Default::default(),
+        };
+
+        let chunks = [chunk(0, 0, 1), chunk(0, 0, 2)];
+        for chunk in chunks.iter() {
+            save_chunk(chunk, capture.next_path(chunk.chunk_x, chunk.chunk_z)).unwrap();
+        }
+
+        assert_eq!(
+            load_chunk(dir.join("chunk_0_0_0.chunk")).unwrap(),
+            chunks[0]
+        );
+        assert_eq!(
+            load_chunk(dir.join("chunk_0_0_1.chunk")).unwrap(),
+            chunks[1]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn index_records_negotiated_protocol_version() {
+        let dir = temp_dir("index");
+
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(bevy::tasks::TaskPool::new()))
+            .add_event::<ChunkData>()
+            .add_event::<bevy::app::AppExit>()
+            .add_plugin(ChunkCapturePlugin::new(dir.clone()));
+
+        let read_index = || -> CaptureIndex {
+            let index_file = fs::File::open(dir.join(CAPTURE_INDEX_FILE)).unwrap();
+            serde_json::from_reader(index_file).unwrap()
+        };
+
+        // Before the server's protocol version has been discovered.
+        app.update();
+        let index = read_index();
+        assert_eq!(index.version, "1.14.4");
+        assert_eq!(index.protocol_version, 498);
+
+        // Protocol discovery replaces the adapter.
+        app.insert_resource(VersionAdapter::for_protocol_version(578));
+        app.update();
+        let index = read_index();
+        assert_eq!(index.version, "1.15.2");
+        assert_eq!(index.protocol_version, 578);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,15 @@
 //! Saving and loading chunk data for testing.
 //!
-//! File format based on
+//! Chunks are saved to `{file}.chunk` files in the format defined by
+//! [`brine_chunk::file`].
+//!
+//! Older chunk files used a format based on
 //! <https://github.com/PrismarineJS/prismarine-chunk/tree/master/test>, i.e.
-//! binary blob stored in `{file}.dump` and extra information stored as JSON in
-//! `{file}.meta`.
+//! the undecoded packet data stored in `{file}.dump` and extra information
+//! stored as JSON in `{file}.meta`. These can still be loaded.
+//!
+//! Chunks can also be loaded from the region files of a vanilla world (see
+//! [`load_region`]).
 
 use std::{
     fs, io,
@@ -12,8 +18,17 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use brine_chunk::{decode::Error as ChunkError, Chunk};
-use brine_proto_backend::backend_stevenarella::{chunks::ChunkData, codec::Packet};
+use brine_chunk::{
+    anvil::{Error as AnvilError, RegionFile},
+    decode::Error as ChunkError,
+    file::Error as ChunkFileError,
+    BlockState, Chunk,
+};
+use brine_data::Blocks;
+use brine_proto_backend::backend_stevenarella::{
+    chunks::{get_chunk_from_packet, ChunkData},
+    codec::Packet,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -22,6 +37,12 @@ pub enum Error {
     #[error(transparent)]
     Chunk(#[from] ChunkError),
 
+    #[error(transparent)]
+    ChunkFile(#[from] ChunkFileError),
+
+    #[error(transparent)]
+    Anvil(#[from] AnvilError),
+
     #[error(transparent)]
     Io(#[from] io::Error),
 
@@ -42,7 +63,37 @@ pub struct ChunkMeta {
     pub bitmask: u16,
 }
 
-/// Loads **undecoded** chunk data from a pair of `.dump` and `.meta` files.
+/// The extension of chunk files.
+pub const CHUNK_FILE_EXTENSION: &str = "chunk";
+
+/// The extension of vanilla Anvil region files.
+pub const REGION_FILE_EXTENSION: &str = "mca";
+
+/// Returns whether `path` is a chunk file that [`load_chunk`] can load, either
+/// a `.chunk` file or the `.dump` half of a legacy pair of files.
+pub fn is_chunk_file(path: impl AsRef<Path>) -> bool {
+    let path = path.as_ref();
+
+    let is_light_data = path.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("chunk_light_")
+    });
+
+    match path.extension() {
+        Some(ext) if ext == CHUNK_FILE_EXTENSION => true,
+        Some(ext) if ext == "dump" => !is_light_data,
+        _ => false,
+    }
+}
+
+/// Returns whether `path` is a region file that [`load_region`] can load.
+pub fn is_region_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .map_or(false, |ext| ext == REGION_FILE_EXTENSION)
+}
+
+/// Loads **undecoded** chunk data from a pair of legacy `.dump` and `.meta`
+/// files.
 pub fn load_chunk_data(path: impl AsRef<Path>) -> Result<ChunkData<Vec<u8>>> {
     let path = path.as_ref();
     let dump_path = path.with_extension("dump");
@@ -61,48 +112,79 @@ pub fn load_chunk_data(path: impl AsRef<Path>) -> Result<ChunkData<Vec<u8>>> {
         chunk_z,
         bitmask,
         full_chunk: true,
+        heightmaps: None,
+        biomes_in_data: true,
         data,
     })
 }
 
-/// Loads a chunk from a pair of `.dump` and `.meta` files.
+/// Loads a chunk from a `.chunk` file, or from a pair of legacy `.dump` and
+/// `.meta` files.
+///
+/// `path` is the path of the `.chunk` file. Any other extension is assumed to
+/// belong to a legacy pair of files.
 pub fn load_chunk(path: impl AsRef<Path>) -> Result<Chunk> {
+    let path = path.as_ref();
+
+    if path
+        .extension()
+        .map_or(false, |ext| ext == CHUNK_FILE_EXTENSION)
+    {
+        let file = io::BufReader::new(fs::File::open(path)?);
+        return Ok(Chunk::read_from(file)?);
+    }
+
     let chunk = load_chunk_data(path)?.decode()?;
 
     Ok(chunk)
 }
 
-/// Saves a chunk packet to a pair of `chunk_{X}_{Z}.dump` and
-/// `chunk_{X}_{Z}.meta` files in the directory pointed to by `path`.
-pub fn save_packet_if_has_chunk_data(
-    packet: &Packet,
-    path: impl AsRef<Path>,
-) -> Result<Option<PathBuf>> {
-    if let Some(ChunkData {
-        chunk_x,
-        chunk_z,
-        bitmask,
-        full_chunk: true,
-        data,
-    }) = ChunkData::from_packet(packet)
-    {
-        let mut path = PathBuf::from(path.as_ref());
-        path.push(format!("chunk_{}_{}.dump", chunk_x, chunk_z));
+/// Loads every chunk stored in a vanilla Anvil region file (`r.{X}.{Z}.mca`).
+///
+/// Block states are looked up in `blocks` by name. Block states that don't
+/// exist in that version are replaced with air.
+pub fn load_region(path: impl AsRef<Path>, blocks: &Blocks) -> Result<Vec<Chunk>> {
+    let mut region = RegionFile::open(path)?;
+    let coords: Vec<_> = region.chunks().collect();
+
+    let mut chunks = Vec::with_capacity(coords.len());
+    for (local_x, local_z) in coords {
+        let chunk = region.read_chunk(local_x, local_z, |name, properties| {
+            blocks.block_state(name, properties)
+        })?;
+        chunks.extend(chunk);
+    }
+
+    Ok(chunks)
+}
 
-        let dump_path = path.with_extension("dump");
-        let meta_path = path.with_extension("meta");
+/// Saves a chunk to a `{path}.chunk` file.
+///
+/// Returns the path of the saved file.
+pub fn save_chunk(chunk: &Chunk, path: impl AsRef<Path>) -> Result<PathBuf> {
+    let path = path.as_ref().with_extension(CHUNK_FILE_EXTENSION);
 
-        let meta = ChunkMeta {
-            chunk_x,
-            chunk_z,
-            bitmask,
-        };
-        serde_json::to_writer(fs::File::create(meta_path)?, &meta)?;
+    let file = io::BufWriter::new(fs::File::create(&path)?);
+    chunk.write_to(file)?;
 
-        fs::write(&dump_path, data)?;
+    Ok(path)
+}
 
-        Ok(Some(dump_path))
-    } else {
-        Ok(None)
+/// Decodes a chunk packet and saves it to a `chunk_{X}_{Z}.chunk` file in the
+/// directory pointed to by `path`.
+///
+/// Only packets containing the full data of a chunk are saved.
+pub fn save_packet_if_has_chunk_data(
+    packet: &Packet,
+    path: impl AsRef<Path>,
+) -> Result<Option<PathBuf>> {
+    match get_chunk_from_packet(packet)? {
+        Some(chunk) if chunk.is_full() => {
+            let mut path = PathBuf::from(path.as_ref());
+            path.push(format!("chunk_{}_{}", chunk.chunk_x, chunk.chunk_z));
+
+            Ok(Some(save_chunk(&chunk, path)?))
+        }
+        _ => Ok(None),
     }
 }
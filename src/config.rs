@@ -0,0 +1,236 @@
+//! Settings for the main Brine binary.
+//!
+//! Each setting is taken from the first of these places that specifies it:
+//!
+//! 1. A command line flag.
+//! 2. A `brine.toml` config file, either in the working directory or in
+//!    `$XDG_CONFIG_HOME/brine/` (`~/.config/brine/` if that isn't set).
+//! 3. A built-in default.
+//!
+//! An example config file:
+//!
+//! ```toml
+//! server = "mc.example.com:25565"
+//! username = "steve"
+//! version = "1.14.4"
+//! assets = "assets/1.14.4"
+//! ```
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use brine_data::{UnsupportedVersion, Version};
+
+/// Name of the config file.
+pub const CONFIG_FILE_NAME: &str = "brine.toml";
+
+pub const DEFAULT_SERVER: &str = "localhost:25565";
+pub const DEFAULT_USERNAME: &str = "user";
+pub const DEFAULT_VERSION: &str = "1.14.4";
+
+/// Directory that holds the asset directories of each version, used when no
+/// asset path is specified.
+pub const DEFAULT_ASSETS_ROOT: &str = "assets";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {}: {source}", .path.display())]
+    Read { path: PathBuf, source: io::Error },
+
+    #[error("invalid config file {}: {source}", .path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error(transparent)]
+    UnsupportedVersion(#[from] UnsupportedVersion),
+}
+
+/// Settings that may or may not have been specified by one source.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PartialConfig {
+    pub server: Option<String>,
+    pub username: Option<String>,
+    pub version: Option<String>,
+    pub assets: Option<PathBuf>,
+}
+
+impl PartialConfig {
+    pub fn from_toml(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    /// Reads the config file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        Self::from_toml(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    /// Fills in the settings that `self` doesn't specify with those from
+    /// `fallback`.
+    pub fn or(self, fallback: PartialConfig) -> PartialConfig {
+        PartialConfig {
+            server: self.server.or(fallback.server),
+            username: self.username.or(fallback.username),
+            version: self.version.or(fallback.version),
+            assets: self.assets.or(fallback.assets),
+        }
+    }
+}
+
+/// Fully-resolved settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Address of the server to connect to, as `HOST:PORT`.
+    pub server: String,
+
+    pub username: String,
+
+    /// Name of the Minecraft version to load data and assets for.
+    pub version: String,
+
+    /// Path to the directory of the version's assets.
+    pub assets: PathBuf,
+}
+
+impl Config {
+    /// Resolves the settings from the command line `flags`, the config file (if
+    /// one can be found), and the defaults.
+    pub fn load(flags: PartialConfig) -> Result<Self, ConfigError> {
+        let file = match find_config_file() {
+            Some(path) => PartialConfig::from_file(path)?,
+            None => PartialConfig::default(),
+        };
+
+        Self::resolve(flags.or(file))
+    }
+
+    /// Fills in any settings missing from `partial` with the defaults, and
+    /// checks that the version is supported.
+    pub fn resolve(partial: PartialConfig) -> Result<Self, ConfigError> {
+        let version = partial
+            .version
+            .unwrap_or_else(|| DEFAULT_VERSION.to_string());
+
+        // Normalize the name, e.g., for when the version is given as a
+        // protocol version number.
+        let version = version.parse::<Version>()?.name().to_string();
+
+        let assets = partial
+            .assets
+            .unwrap_or_else(|| Path::new(DEFAULT_ASSETS_ROOT).join(&version));
+
+        Ok(Self {
+            server: partial.server.unwrap_or_else(|| DEFAULT_SERVER.to_string()),
+            username: partial
+                .username
+                .unwrap_or_else(|| DEFAULT_USERNAME.to_string()),
+            version,
+            assets,
+        })
+    }
+}
+
+/// Returns the path of the first config file that exists, if any.
+fn find_config_file() -> Option<PathBuf> {
+    let user_config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+
+    let candidates = [
+        Some(PathBuf::from(CONFIG_FILE_NAME)),
+        user_config_dir.map(|dir| dir.join("brine").join(CONFIG_FILE_NAME)),
+    ];
+
+    candidates.into_iter().flatten().find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn partial(server: Option<&str>, username: Option<&str>) -> PartialConfig {
+        PartialConfig {
+            server: server.map(String::from),
+            username: username.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_override_file() {
+        let flags = partial(Some("flag:1"), None);
+        let file = partial(Some("file:2"), Some("file_user"));
+
+        let config = Config::resolve(flags.or(file)).unwrap();
+
+        assert_eq!(config.server, "flag:1");
+        assert_eq!(config.username, "file_user");
+    }
+
+    #[test]
+    fn file_overrides_defaults() {
+        let file = PartialConfig::from_toml(
+            r#"
+            username = "steve"
+            assets = "/opt/assets"
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::resolve(PartialConfig::default().or(file)).unwrap();
+
+        assert_eq!(config.server, DEFAULT_SERVER);
+        assert_eq!(config.username, "steve");
+        assert_eq!(config.version, DEFAULT_VERSION);
+        assert_eq!(config.assets, PathBuf::from("/opt/assets"));
+    }
+
+    #[test]
+    fn defaults() {
+        let config = Config::resolve(PartialConfig::default()).unwrap();
+
+        assert_eq!(config.server, DEFAULT_SERVER);
+        assert_eq!(config.username, DEFAULT_USERNAME);
+        assert_eq!(config.version, DEFAULT_VERSION);
+        assert_eq!(config.assets, Path::new("assets").join(DEFAULT_VERSION));
+    }
+
+    #[test]
+    fn version_is_validated() {
+        let flags = PartialConfig {
+            version: Some("not a version".to_string()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            Config::resolve(flags),
+            Err(ConfigError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn bad_file_is_an_error() {
+        assert!(PartialConfig::from_toml("server = 25565").is_err());
+        assert!(PartialConfig::from_toml("sever = \"typo:25565\"").is_err());
+        assert_eq!(
+            PartialConfig::from_toml("").unwrap(),
+            PartialConfig::default()
+        );
+    }
+}
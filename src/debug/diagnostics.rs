@@ -0,0 +1,97 @@
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+};
+use bevy_inspector_egui::bevy_egui::{egui, EguiContext, EguiPlugin};
+
+use brine_proto_backend::backend_stevenarella::chunks::CHUNK_DECODE_TIME;
+use brine_voxel_v1::{
+    chunk_builder::diagnostic::{
+        ChunkBuilderDiagnosticsPlugin, BUILT_CHUNKS, MESH_VERTICES, PENDING_CHUNKS, QUEUED_CHUNKS,
+        SECTION_BUILD_TIME,
+    },
+    visibility::TOTAL_SECTIONS,
+};
+
+/// The diagnostics of the chunk pipeline, from decoding to meshing, in the
+/// order they're shown in the panel.
+const PIPELINE_DIAGNOSTICS: [DiagnosticId; 7] = [
+    CHUNK_DECODE_TIME,
+    SECTION_BUILD_TIME,
+    QUEUED_CHUNKS,
+    PENDING_CHUNKS,
+    BUILT_CHUNKS,
+    TOTAL_SECTIONS,
+    MESH_VERTICES,
+];
+
+/// Registers [`Diagnostics`] for each stage of the chunk pipeline: how long
+/// chunks take to decode and mesh, how many are queued or being built, and how
+/// many chunks, sections and vertices are in the world.
+///
+/// Like any other diagnostics, they're printed by the
+/// [`LogDiagnosticsPlugin`][bevy::diagnostic::LogDiagnosticsPlugin]. See
+/// [`with_panel`][Self::with_panel] to also show them in an egui window.
+///
+/// The number of sections is measured by the
+/// [`ChunkVisibilityPlugin`][brine_voxel_v1::visibility::ChunkVisibilityPlugin],
+/// so that plugin must be added too.
+#[derive(Default)]
+pub struct BrineDiagnosticsPlugin {
+    panel: bool,
+}
+
+impl BrineDiagnosticsPlugin {
+    /// Shows the latest and average value of each diagnostic in an egui window.
+    pub fn with_panel(mut self) -> Self {
+        self.panel = true;
+        self
+    }
+}
+
+impl Plugin for BrineDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(ChunkBuilderDiagnosticsPlugin);
+        app.add_startup_system(setup_diagnostics);
+
+        if self.panel {
+            if !app.world.contains_resource::<EguiContext>() {
+                app.add_plugin(EguiPlugin);
+            }
+            app.add_system(show_panel);
+        }
+    }
+}
+
+fn setup_diagnostics(diagnostics: Option<ResMut<Diagnostics>>) {
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.add(Diagnostic::new(CHUNK_DECODE_TIME, "chunk_decode_time", 20));
+    }
+}
+
+fn show_panel(mut egui_context: ResMut<EguiContext>, diagnostics: Res<Diagnostics>) {
+    egui::Window::new("Chunk Pipeline").show(egui_context.ctx_mut(), |ui| {
+        egui::Grid::new("chunk_pipeline_diagnostics").show(ui, |ui| {
+            ui.strong("Diagnostic");
+            ui.strong("Value");
+            ui.strong("Average");
+            ui.end_row();
+
+            for diagnostic in PIPELINE_DIAGNOSTICS
+                .iter()
+                .filter_map(|id| diagnostics.get(*id))
+            {
+                let format = |value: Option<f64>| {
+                    value
+                        .map(|value| format!("{:.2}", value))
+                        .unwrap_or_else(|| "-".to_string())
+                };
+
+                ui.label(diagnostic.name.as_str());
+                ui.label(format(diagnostic.value()));
+                ui.label(format(diagnostic.average()));
+                ui.end_row();
+            }
+        });
+    });
+}
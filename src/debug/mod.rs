@@ -1,3 +1,7 @@
+mod diagnostics;
+mod tab_list;
 mod wireframe;
 
+pub use diagnostics::BrineDiagnosticsPlugin;
+pub use tab_list::DebugTabListPlugin;
 pub use wireframe::{DebugWireframePlugin, EnableWireframe};
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use brine_proto::{TabList, TabListChanged};
+
+use crate::hud::{self, HudFont};
+
+const FONT_SIZE: f32 = 16.0;
+
+/// Lists the players in the [`TabList`] while the tab key is held.
+///
+/// The text is drawn with the [`HudPlugin`][crate::hud::HudPlugin]'s font, so
+/// that plugin must be added too.
+pub struct DebugTabListPlugin;
+
+impl Plugin for DebugTabListPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_overlay)
+            .add_system(update_overlay);
+    }
+}
+
+#[derive(Component)]
+struct TabListOverlay;
+
+fn spawn_overlay(mut commands: Commands) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(48.0),
+                    left: Val::Px(8.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert_bundle((Name::new("Debug Tab List"), TabListOverlay));
+}
+
+fn update_overlay(
+    keys: Res<Input<KeyCode>>,
+    font: Option<Res<HudFont>>,
+    tab_list: Res<TabList>,
+    mut changed_events: EventReader<TabListChanged>,
+    mut overlay: Query<&mut Text, With<TabListOverlay>>,
+) {
+    let font = match font {
+        Some(font) => font,
+        None => return,
+    };
+
+    let changed = changed_events.iter().count() > 0;
+    let shown = keys.pressed(KeyCode::Tab);
+    let toggled = keys.just_pressed(KeyCode::Tab) || keys.just_released(KeyCode::Tab);
+    if !toggled && !(changed && shown) {
+        return;
+    }
+
+    for mut text in overlay.iter_mut() {
+        text.sections = if shown {
+            tab_list_sections(&tab_list, &font.0)
+        } else {
+            Vec::new()
+        };
+    }
+}
+
+/// Lays out one line per player, sorted by name.
+fn tab_list_sections(tab_list: &TabList, font: &Handle<Font>) -> Vec<TextSection> {
+    let mut entries: Vec<_> = tab_list.iter().map(|(_, entry)| entry).collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let style = TextStyle {
+        font: font.clone(),
+        font_size: FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    let mut sections = vec![TextSection {
+        value: format!("Players ({})\n", entries.len()),
+        style: style.clone(),
+    }];
+
+    for entry in entries {
+        match &entry.display_name {
+            Some(display_name) => {
+                sections.extend(hud::text_sections(display_name, font, FONT_SIZE, 1.0))
+            }
+            None => sections.push(TextSection {
+                value: entry.name.clone(),
+                style: style.clone(),
+            }),
+        }
+
+        let gamemode = match entry.gamemode {
+            Some(gamemode) => format!("{:?}", gamemode),
+            None => String::from("?"),
+        };
+
+        sections.push(TextSection {
+            value: format!("  {}  {} ms\n", gamemode, entry.latency),
+            style: TextStyle {
+                color: Color::GRAY,
+                ..style.clone()
+            },
+        });
+    }
+
+    sections
+}
@@ -0,0 +1,516 @@
+//! Breaking blocks by holding the left mouse button.
+//!
+//! The client decides how long a block takes to break (see [`break_time`]),
+//! tells the server when digging starts, and tells it again once the time is
+//! up. The block is only removed from the local chunk data once the server
+//! acknowledges it (see [`BlockPredictionPlugin`]).
+//!
+//! The crack overlay isn't drawn yet, but [`Digging::progress`] says how far
+//! along the current block is.
+//!
+//! [`BlockPredictionPlugin`]: crate::prediction::BlockPredictionPlugin
+
+use std::{collections::HashMap, time::Duration};
+
+use bevy::prelude::*;
+
+use brine_chunk::BlockState;
+use brine_data::{BlockId, BlockStateId, Item, ItemId, MinecraftData};
+use brine_proto::event::{
+    clientbound::{GameMode, JoinGame, Respawn},
+    serverbound::{BlockFace, DiggingStatus, Hand, PlayerDigging, SwingArm},
+};
+use brine_voxel_v1::chunk_builder::{component::ChunkSection, ChunkIndex};
+
+use crate::{
+    interaction::{block_face, Hotbar},
+    picking::{self, BuiltChunkBlocks, PickedBlock},
+};
+
+/// How long to wait after breaking a block before digging the next one, in
+/// seconds (5 ticks, like vanilla).
+pub const DIG_COOLDOWN: f64 = 0.25;
+
+const MILLIS_PER_TICK: u64 = 50;
+
+/// Tools that break some materials faster, by the end of their item name.
+const TOOL_MATERIALS: &[(&str, &[&str])] = &[
+    ("_pickaxe", &["rock", "mineable/pickaxe"]),
+    ("_shovel", &["dirt", "mineable/shovel"]),
+    ("_axe", &["wood", "mineable/axe"]),
+];
+
+/// How much faster each tier of tool breaks the materials it's made for, by
+/// the start of its item name.
+const TOOL_TIERS: &[(&str, f32)] = &[
+    ("wooden_", 2.0),
+    ("stone_", 4.0),
+    ("iron_", 6.0),
+    ("diamond_", 8.0),
+    ("golden_", 12.0),
+];
+
+/// Plugin that digs the [`PickedBlock`] while the left mouse button is held,
+/// with the item held in the [`Hotbar`].
+///
+/// Requires the [`BlockPickingPlugin`][crate::picking::BlockPickingPlugin]
+/// and the [`BlockInteractionPlugin`][crate::interaction::BlockInteractionPlugin].
+pub struct BlockDiggingPlugin;
+
+impl Plugin for BlockDiggingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Digging>()
+            .add_system(update_game_mode.label(System::UpdateGameMode))
+            .add_system(
+                dig.after(System::UpdateGameMode)
+                    .after(picking::System::PickBlock),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+enum System {
+    UpdateGameMode,
+}
+
+/// The properties of a block that decide how long it takes to break.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigProperties {
+    /// `None` if the block can't be broken.
+    pub hardness: Option<f32>,
+
+    pub material: Option<String>,
+
+    /// The items that the block drops anything when broken with, or `None` if
+    /// it drops something whatever it's broken with.
+    pub harvest_tools: Option<Vec<ItemId>>,
+}
+
+/// Looks up the [`DigProperties`] of block states.
+pub trait BlockHardness {
+    /// Returns the properties of the block state, or `None` if it isn't
+    /// known.
+    fn dig_properties(&self, block: BlockState) -> Option<DigProperties>;
+}
+
+impl BlockHardness for MinecraftData {
+    fn dig_properties(&self, block: BlockState) -> Option<DigProperties> {
+        let state_id = BlockStateId(u16::try_from(block.0).ok()?);
+        let block = self.blocks().get_by_state_id(state_id)?;
+
+        Some(DigProperties {
+            hardness: block.hardness,
+            material: block.material.map(String::from),
+            harvest_tools: self.blocks().harvest_tools(BlockId(block.id)),
+        })
+    }
+}
+
+impl BlockHardness for HashMap<BlockState, DigProperties> {
+    fn dig_properties(&self, block: BlockState) -> Option<DigProperties> {
+        self.get(&block).cloned()
+    }
+}
+
+/// Returns how much faster `tool` breaks blocks of `material` than a bare
+/// hand does.
+///
+/// Only pickaxes, shovels, and axes are taken into account.
+fn tool_speed(tool: &Item, material: Option<&str>) -> f32 {
+    let is_made_for_material = TOOL_MATERIALS.iter().any(|(suffix, materials)| {
+        tool.name.ends_with(suffix) && material.map_or(false, |m| materials.contains(&m))
+    });
+
+    if !is_made_for_material {
+        return 1.0;
+    }
+
+    TOOL_TIERS
+        .iter()
+        .find(|(prefix, _)| tool.name.starts_with(prefix))
+        .map_or(1.0, |(_, speed)| *speed)
+}
+
+/// Returns how long it takes to break a block with the given properties while
+/// holding `tool`, or `None` if it can't be broken.
+///
+/// Blocks that break instantly, including every block in creative mode, take
+/// [`Duration::ZERO`]. Enchantments, potion effects, and being underwater or
+/// in the air are not taken into account.
+///
+/// See <https://minecraft.fandom.com/wiki/Breaking#Speed>.
+pub fn break_time(
+    block: &DigProperties,
+    tool: Option<&Item>,
+    gamemode: GameMode,
+) -> Option<Duration> {
+    match gamemode {
+        GameMode::Creative => return Some(Duration::ZERO),
+        GameMode::Adventure | GameMode::Spectator => return None,
+        GameMode::Survival => {}
+    }
+
+    let hardness = block.hardness?;
+    if hardness == 0.0 {
+        return Some(Duration::ZERO);
+    }
+
+    let speed = tool.map_or(1.0, |tool| tool_speed(tool, block.material.as_deref()));
+    let can_harvest = match &block.harvest_tools {
+        Some(tools) => tool.map_or(false, |tool| tools.contains(&tool.id)),
+        None => true,
+    };
+
+    // Each tick breaks `speed / hardness / 30` of a block that can be
+    // harvested, and `speed / hardness / 100` otherwise, as in vanilla.
+    let ticks = (hardness * if can_harvest { 30.0 } else { 100.0 } / speed).ceil();
+    if ticks <= 1.0 {
+        return Some(Duration::ZERO);
+    }
+
+    Some(Duration::from_millis(ticks as u64 * MILLIS_PER_TICK))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DigProgress {
+    block_pos: IVec3,
+    face: BlockFace,
+
+    /// Seconds since startup when digging started.
+    started_at: f64,
+
+    break_time: Duration,
+}
+
+/// Resource that holds the block that the player is digging, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Digging {
+    /// The player's game mode, which decides how long blocks take to break.
+    pub gamemode: GameMode,
+
+    current: Option<DigProgress>,
+
+    /// Seconds since startup before which the next block can't be dug.
+    cooldown_until: f64,
+}
+
+impl Default for Digging {
+    fn default() -> Self {
+        Self {
+            gamemode: GameMode::Survival,
+            current: None,
+            cooldown_until: 0.0,
+        }
+    }
+}
+
+impl Digging {
+    /// Returns the block being dug and how far along it is, from 0 to 1, at
+    /// `now` (in seconds since startup).
+    pub fn progress(&self, now: f64) -> Option<(IVec3, f32)> {
+        let current = self.current?;
+        let elapsed = (now - current.started_at) as f32;
+        let progress = elapsed / current.break_time.as_secs_f32();
+
+        Some((current.block_pos, progress.clamp(0.0, 1.0)))
+    }
+
+    /// Advances digging to `now` (in seconds since startup), and returns the
+    /// events to send to the server.
+    ///
+    /// `target` is the block and face that the player is holding the button
+    /// on, if any. `break_time` is called to find out how long a block takes
+    /// to break when digging it starts, and returns `None` if it can't be
+    /// broken.
+    pub fn update(
+        &mut self,
+        target: Option<(IVec3, BlockFace)>,
+        now: f64,
+        break_time: impl FnOnce() -> Option<Duration>,
+    ) -> Vec<PlayerDigging> {
+        let mut events = Vec::new();
+
+        // Stop digging a block that's no longer targeted.
+        if let Some(current) = self.current {
+            if target.map(|(block_pos, _)| block_pos) != Some(current.block_pos) {
+                events.push(PlayerDigging {
+                    status: DiggingStatus::Cancelled,
+                    block_pos: current.block_pos,
+                    face: current.face,
+                });
+                self.current = None;
+            }
+        }
+
+        let (block_pos, face) = match target {
+            Some(target) => target,
+            None => return events,
+        };
+
+        match self.current {
+            Some(current) => {
+                if now - current.started_at >= current.break_time.as_secs_f64() {
+                    events.push(PlayerDigging {
+                        status: DiggingStatus::Finished,
+                        block_pos,
+                        face,
+                    });
+                    self.current = None;
+                    self.cooldown_until = now + DIG_COOLDOWN;
+                }
+            }
+            None if now >= self.cooldown_until => {
+                let break_time = match break_time() {
+                    Some(break_time) => break_time,
+                    None => return events,
+                };
+
+                events.push(PlayerDigging {
+                    status: DiggingStatus::Started,
+                    block_pos,
+                    face,
+                });
+
+                // Blocks that break instantly don't need to be finished.
+                if break_time.is_zero() {
+                    self.cooldown_until = now + DIG_COOLDOWN;
+                } else {
+                    self.current = Some(DigProgress {
+                        block_pos,
+                        face,
+                        started_at: now,
+                        break_time,
+                    });
+                }
+            }
+            None => {}
+        }
+
+        events
+    }
+}
+
+/// System that keeps [`Digging::gamemode`] in sync with the server.
+fn update_game_mode(
+    mut join_events: EventReader<JoinGame>,
+    mut respawn_events: EventReader<Respawn>,
+    mut digging: ResMut<Digging>,
+) {
+    let gamemodes = join_events
+        .iter()
+        .map(|event| event.gamemode)
+        .chain(respawn_events.iter().map(|event| event.gamemode));
+
+    if let Some(gamemode) = gamemodes.last() {
+        digging.gamemode = gamemode;
+    }
+}
+
+/// System that digs the picked block while the left mouse button is held.
+#[allow(clippy::too_many_arguments)]
+fn dig(
+    time: Res<Time>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    picked_block: Res<PickedBlock>,
+    hotbar: Res<Hotbar>,
+    mc_data: Res<MinecraftData>,
+    chunk_index: Res<ChunkIndex>,
+    sections: Query<&ChunkSection>,
+    mut digging: ResMut<Digging>,
+    mut digging_events: EventWriter<PlayerDigging>,
+    mut swing_arm_events: EventWriter<SwingArm>,
+) {
+    let target = picked_block
+        .0
+        .filter(|_| mouse_buttons.pressed(MouseButton::Left))
+        .map(|hit| (hit.block_pos, block_face(hit.face)));
+
+    let gamemode = digging.gamemode;
+    let events = digging.update(target, time.seconds_since_startup(), || {
+        let blocks = BuiltChunkBlocks {
+            chunk_index: &*chunk_index,
+            sections: &sections,
+        };
+        let (block_pos, _) = target?;
+        let block = mc_data.dig_properties(blocks.get_block(block_pos)?)?;
+        let tool = hotbar.held_item().and_then(|item| {
+            let item_id = u16::try_from(item.item_id).ok()?;
+            mc_data.items().get_by_id(ItemId(item_id))
+        });
+
+        break_time(&block, tool.as_ref(), gamemode)
+    });
+
+    for event in events {
+        if event.status != DiggingStatus::Cancelled {
+            swing_arm_events.send(SwingArm { hand: Hand::Main });
+        }
+        digging_events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const STONE: BlockState = BlockState(1);
+    const DIRT: BlockState = BlockState(10);
+    const BEDROCK: BlockState = BlockState(33);
+    const GRASS: BlockState = BlockState(1341);
+
+    const WOODEN_PICKAXE: ItemId = ItemId(536);
+
+    /// A made-up hardness table with a few familiar blocks.
+    fn hardness_table() -> HashMap<BlockState, DigProperties> {
+        let block = |hardness, material: &str, harvest_tools: Option<Vec<ItemId>>| DigProperties {
+            hardness,
+            material: Some(material.to_string()),
+            harvest_tools,
+        };
+
+        HashMap::from([
+            (STONE, block(Some(1.5), "rock", Some(vec![WOODEN_PICKAXE]))),
+            (DIRT, block(Some(0.5), "dirt", None)),
+            (BEDROCK, block(None, "rock", None)),
+            (GRASS, block(Some(0.0), "plant", None)),
+        ])
+    }
+
+    fn item(id: ItemId, name: &str) -> Item<'_> {
+        Item {
+            id,
+            display_name: name,
+            name,
+            stack_size: 1,
+        }
+    }
+
+    fn break_time_of(
+        block: BlockState,
+        tool: Option<&Item>,
+        gamemode: GameMode,
+    ) -> Option<Duration> {
+        let block = hardness_table().dig_properties(block).unwrap();
+        break_time(&block, tool, gamemode)
+    }
+
+    #[test]
+    fn break_times() {
+        let pickaxe = item(WOODEN_PICKAXE, "wooden_pickaxe");
+        let survival = GameMode::Survival;
+        let millis = |millis| Some(Duration::from_millis(millis));
+
+        // Stone needs a pickaxe to be harvested, so it's slow by hand.
+        assert_eq!(break_time_of(STONE, None, survival), millis(7500));
+        assert_eq!(break_time_of(STONE, Some(&pickaxe), survival), millis(1150));
+
+        // A pickaxe isn't made for dirt.
+        assert_eq!(break_time_of(DIRT, None, survival), millis(750));
+        assert_eq!(break_time_of(DIRT, Some(&pickaxe), survival), millis(750));
+
+        assert_eq!(break_time_of(GRASS, None, survival), millis(0));
+        assert_eq!(break_time_of(BEDROCK, None, survival), None);
+
+        assert_eq!(break_time_of(STONE, None, GameMode::Creative), millis(0));
+        assert_eq!(break_time_of(DIRT, None, GameMode::Adventure), None);
+    }
+
+    #[test]
+    fn tool_tiers() {
+        let stone = hardness_table().dig_properties(STONE).unwrap();
+        let time = |name| {
+            let tool = item(WOODEN_PICKAXE, name);
+            break_time(&stone, Some(&tool), GameMode::Survival).unwrap()
+        };
+
+        assert!(time("golden_pickaxe") < time("diamond_pickaxe"));
+        assert!(time("diamond_pickaxe") < time("iron_pickaxe"));
+        assert!(time("iron_pickaxe") < time("stone_pickaxe"));
+        assert!(time("stone_pickaxe") < time("wooden_pickaxe"));
+        assert!(time("wooden_pickaxe") < time("wooden_shovel"));
+    }
+
+    fn statuses(events: Vec<PlayerDigging>) -> Vec<(DiggingStatus, IVec3)> {
+        events
+            .into_iter()
+            .map(|event| (event.status, event.block_pos))
+            .collect()
+    }
+
+    #[test]
+    fn dig_until_broken() {
+        let mut digging = Digging::default();
+        let block_pos = IVec3::new(1, 2, 3);
+        let target = Some((block_pos, BlockFace::Top));
+        let one_second = || Some(Duration::from_secs(1));
+
+        assert_eq!(
+            statuses(digging.update(target, 10.0, one_second)),
+            [(DiggingStatus::Started, block_pos)]
+        );
+        assert!(digging.update(target, 10.5, one_second).is_empty());
+        assert_eq!(digging.progress(10.5), Some((block_pos, 0.5)));
+
+        assert_eq!(
+            statuses(digging.update(target, 11.0, one_second)),
+            [(DiggingStatus::Finished, block_pos)]
+        );
+        assert_eq!(digging.progress(11.0), None);
+
+        // Still holding the button on the block while waiting for the server.
+        assert!(digging.update(target, 11.1, one_second).is_empty());
+    }
+
+    #[test]
+    fn dig_is_cancelled() {
+        let mut digging = Digging::default();
+        let first = IVec3::new(1, 2, 3);
+        let second = IVec3::new(1, 2, 4);
+        let one_second = || Some(Duration::from_secs(1));
+
+        digging.update(Some((first, BlockFace::Top)), 10.0, one_second);
+
+        // Looking at another block starts over.
+        assert_eq!(
+            statuses(digging.update(Some((second, BlockFace::Top)), 10.5, one_second)),
+            [
+                (DiggingStatus::Cancelled, first),
+                (DiggingStatus::Started, second)
+            ]
+        );
+
+        // Letting go of the button stops digging.
+        assert_eq!(
+            statuses(digging.update(None, 11.0, one_second)),
+            [(DiggingStatus::Cancelled, second)]
+        );
+        assert!(digging.update(None, 11.5, one_second).is_empty());
+    }
+
+    #[test]
+    fn instant_breaks_wait_for_cooldown() {
+        let mut digging = Digging::default();
+        let block_pos = IVec3::new(1, 2, 3);
+        let target = Some((block_pos, BlockFace::Top));
+        let instant = || Some(Duration::ZERO);
+
+        assert_eq!(
+            statuses(digging.update(target, 10.0, instant)),
+            [(DiggingStatus::Started, block_pos)]
+        );
+        assert!(digging.update(target, 10.1, instant).is_empty());
+        assert_eq!(
+            statuses(digging.update(target, 10.0 + DIG_COOLDOWN, instant)),
+            [(DiggingStatus::Started, block_pos)]
+        );
+    }
+
+    #[test]
+    fn unbreakable_blocks_are_not_dug() {
+        let mut digging = Digging::default();
+        let target = Some((IVec3::new(1, 2, 3), BlockFace::Top));
+
+        assert!(digging.update(target, 10.0, || None).is_empty());
+        assert_eq!(digging.progress(10.0), None);
+    }
+}
@@ -0,0 +1,215 @@
+//! Showing the entities that the server tells us about.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use brine_proto::event::clientbound::{
+    EntitiesDestroyed, EntityKind, EntityMoved, EntityMovement, EntitySpawned, EntityVelocity,
+};
+
+/// How long to hold on to moves for entities that haven't spawned yet before
+/// giving up on them, in seconds.
+const MAX_PENDING_MOVE_AGE: f64 = 1.0;
+
+/// Size of the placeholder boxes, roughly that of a player.
+const PLACEHOLDER_WIDTH: f32 = 0.6;
+const PLACEHOLDER_HEIGHT: f32 = 1.8;
+
+/// A plugin that spawns a placeholder box for each entity the server spawns,
+/// and keeps it at the entity's position until the entity is destroyed.
+///
+/// Moves for entities that haven't been spawned yet are held on to for a short
+/// while in case the spawn arrives late, and then dropped.
+pub struct EntityPlaceholderPlugin;
+
+impl Plugin for EntityPlaceholderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ServerEntities>()
+            .init_resource::<PendingMoves>()
+            .add_startup_system(set_up_placeholder_assets)
+            .add_system(spawn_entities.label(System::Spawn))
+            .add_system(move_entities.label(System::Move).after(System::Spawn))
+            .add_system(destroy_entities.after(System::Move));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+enum System {
+    Spawn,
+    Move,
+}
+
+/// Component for an entity that was spawned by the server.
+#[derive(Component, Debug, Clone)]
+pub struct ServerEntity {
+    /// Server-assigned id of the entity.
+    pub entity_id: i32,
+
+    pub kind: EntityKind,
+
+    /// Position of the entity's feet.
+    pub position: Vec3,
+
+    /// Velocity last reported by the server, in blocks per tick.
+    pub velocity: Vec3,
+}
+
+/// Resource that maps server entity ids to their Bevy entities.
+#[derive(Debug, Default)]
+pub struct ServerEntities(HashMap<i32, Entity>);
+
+impl ServerEntities {
+    pub fn get(&self, entity_id: i32) -> Option<Entity> {
+        self.0.get(&entity_id).copied()
+    }
+}
+
+/// Moves that couldn't be applied yet, along with the time they arrived.
+#[derive(Default)]
+struct PendingMoves(Vec<(f64, EntityMoved)>);
+
+struct PlaceholderAssets {
+    mesh: Handle<Mesh>,
+    player_material: Handle<StandardMaterial>,
+    mob_material: Handle<StandardMaterial>,
+    object_material: Handle<StandardMaterial>,
+}
+
+fn set_up_placeholder_assets(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let mesh = Mesh::from(shape::Box::new(
+        PLACEHOLDER_WIDTH,
+        PLACEHOLDER_HEIGHT,
+        PLACEHOLDER_WIDTH,
+    ));
+
+    commands.insert_resource(PlaceholderAssets {
+        mesh: meshes.add(mesh),
+        player_material: materials.add(Color::rgb(0.2, 0.4, 0.9).into()),
+        mob_material: materials.add(Color::rgb(0.9, 0.3, 0.2).into()),
+        object_material: materials.add(Color::rgb(0.6, 0.6, 0.6).into()),
+    });
+}
+
+/// The box is centered on its origin, but entity positions are at their feet.
+fn placeholder_translation(position: Vec3) -> Vec3 {
+    position + Vec3::Y * (PLACEHOLDER_HEIGHT / 2.0)
+}
+
+fn placeholder_rotation(yaw: f32) -> Quat {
+    Quat::from_rotation_y(-yaw.to_radians())
+}
+
+fn spawn_entities(
+    assets: Res<PlaceholderAssets>,
+    mut server_entities: ResMut<ServerEntities>,
+    mut spawned_events: EventReader<EntitySpawned>,
+    mut commands: Commands,
+) {
+    for event in spawned_events.iter() {
+        let material = match event.kind {
+            EntityKind::Player => assets.player_material.clone(),
+            EntityKind::Mob { .. } => assets.mob_material.clone(),
+            EntityKind::Object { .. } => assets.object_material.clone(),
+        };
+
+        let entity = commands
+            .spawn_bundle(PbrBundle {
+                mesh: assets.mesh.clone(),
+                material,
+                transform: Transform::from_translation(placeholder_translation(event.position))
+                    .with_rotation(placeholder_rotation(event.yaw)),
+                ..Default::default()
+            })
+            .insert_bundle((
+                Name::new(format!("Entity {}", event.entity_id)),
+                ServerEntity {
+                    entity_id: event.entity_id,
+                    kind: event.kind,
+                    position: event.position,
+                    velocity: event.velocity,
+                },
+            ))
+            .id();
+
+        if let Some(replaced) = server_entities.0.insert(event.entity_id, entity) {
+            debug!("Entity {} spawned again, replacing it", event.entity_id);
+            commands.entity(replaced).despawn();
+        }
+    }
+}
+
+fn move_entities(
+    time: Res<Time>,
+    mut pending_moves: ResMut<PendingMoves>,
+    server_entities: Res<ServerEntities>,
+    mut moved_events: EventReader<EntityMoved>,
+    mut velocity_events: EventReader<EntityVelocity>,
+    mut query: Query<(&mut ServerEntity, &mut Transform)>,
+) {
+    let now = time.seconds_since_startup();
+
+    // Moves that were held back are older, so they go first.
+    let moves = std::mem::take(&mut pending_moves.0)
+        .into_iter()
+        .chain(moved_events.iter().map(|event| (now, event.clone())));
+
+    for (received, event) in moves {
+        // An entity that was spawned this frame won't be in the query until
+        // next frame, so it's treated the same as one that hasn't spawned.
+        let found = server_entities
+            .get(event.entity_id)
+            .and_then(|entity| query.get_mut(entity).ok());
+
+        let (mut server_entity, mut transform) = match found {
+            Some(found) => found,
+            None if now - received < MAX_PENDING_MOVE_AGE => {
+                pending_moves.0.push((received, event));
+                continue;
+            }
+            None => {
+                debug!("Dropping move for unknown entity {}", event.entity_id);
+                continue;
+            }
+        };
+
+        server_entity.position = match event.movement {
+            EntityMovement::Relative(delta) => server_entity.position + delta,
+            EntityMovement::Absolute(position) => position,
+        };
+
+        transform.translation = placeholder_translation(server_entity.position);
+        if let Some((yaw, _pitch)) = event.rotation {
+            transform.rotation = placeholder_rotation(yaw);
+        }
+    }
+
+    for event in velocity_events.iter() {
+        let found = server_entities
+            .get(event.entity_id)
+            .and_then(|entity| query.get_mut(entity).ok());
+
+        if let Some((mut server_entity, _)) = found {
+            server_entity.velocity = event.velocity;
+        }
+    }
+}
+
+fn destroy_entities(
+    mut server_entities: ResMut<ServerEntities>,
+    mut destroyed_events: EventReader<EntitiesDestroyed>,
+    mut commands: Commands,
+) {
+    for event in destroyed_events.iter() {
+        for entity_id in event.entity_ids.iter() {
+            match server_entities.0.remove(entity_id) {
+                Some(entity) => commands.entity(entity).despawn(),
+                None => debug!("Destroying unknown entity {}", entity_id),
+            }
+        }
+    }
+}
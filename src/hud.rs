@@ -0,0 +1,651 @@
+//! Showing the boss bars, titles, and action bar text sent by the server.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use brine_proto::{
+    chat::ChatComponent,
+    event::{
+        clientbound::{BossBar, BossBarAction, BossBarColor, Title, TitleAction},
+        Uuid,
+    },
+};
+
+/// Font used for all HUD text, relative to the assets directory, unless
+/// another one is given to [`HudPlugin::new`].
+///
+/// No font is bundled with Brine, so one has to be put here (or passed to
+/// `--hud-font`) for the text to show up. Boss bars are drawn either way.
+pub const DEFAULT_FONT: &str = "fonts/hud.ttf";
+
+/// Number of ticks that pass every second.
+const TICKS_PER_SECOND: f32 = 20.0;
+
+/// How long the action bar text stays up, and how long it spends fading out at
+/// the end, in ticks.
+const ACTION_BAR_TICKS: f32 = 60.0;
+const ACTION_BAR_FADE_OUT_TICKS: f32 = 20.0;
+
+const TITLE_FONT_SIZE: f32 = 64.0;
+const SUBTITLE_FONT_SIZE: f32 = 32.0;
+const ACTION_BAR_FONT_SIZE: f32 = 20.0;
+const BOSS_BAR_FONT_SIZE: f32 = 16.0;
+
+const BOSS_BAR_WIDTH: f32 = 364.0;
+const BOSS_BAR_HEIGHT: f32 = 10.0;
+
+/// A plugin that shows the boss bars, titles, and action bar text that the
+/// server sends with [`BossBar`] and [`Title`] events, using Bevy UI.
+///
+/// # Resources
+///
+/// The plugin registers the following resources:
+///
+/// * [`BossBars`]
+/// * [`TitleDisplay`]
+/// * [`ActionBar`]
+/// * [`HudFont`]
+pub struct HudPlugin {
+    font: String,
+}
+
+impl HudPlugin {
+    /// Creates a plugin that draws text with the font at `font`, relative to
+    /// the assets directory.
+    pub fn new(font: impl Into<String>) -> Self {
+        Self { font: font.into() }
+    }
+}
+
+impl Default for HudPlugin {
+    fn default() -> Self {
+        Self::new(DEFAULT_FONT)
+    }
+}
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        let font = self.font.clone();
+
+        app.init_resource::<BossBars>()
+            .init_resource::<TitleDisplay>()
+            .init_resource::<ActionBar>()
+            .add_startup_system(move |asset_server: Res<AssetServer>, commands: Commands| {
+                set_up_hud(asset_server.load(font.as_str()), commands)
+            })
+            .add_system(update_boss_bars)
+            .add_system(update_title_display.label(System::UpdateTitle))
+            .add_system(update_action_bar.label(System::UpdateActionBar))
+            .add_system(draw_title.after(System::UpdateTitle))
+            .add_system(draw_subtitle.after(System::UpdateTitle))
+            .add_system(draw_action_bar.after(System::UpdateActionBar));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+enum System {
+    UpdateTitle,
+    UpdateActionBar,
+}
+
+/// How long a title fades in, stays, and fades out for, in ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TitleTimes {
+    pub fade_in: i32,
+    pub stay: i32,
+    pub fade_out: i32,
+}
+
+impl Default for TitleTimes {
+    fn default() -> Self {
+        Self {
+            fade_in: 10,
+            stay: 70,
+            fade_out: 20,
+        }
+    }
+}
+
+impl TitleTimes {
+    fn total(&self) -> f32 {
+        (self.fade_in + self.stay + self.fade_out) as f32
+    }
+}
+
+/// Resource that holds the title and subtitle that are being shown, if any.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TitleDisplay {
+    pub title: Option<ChatComponent>,
+    pub subtitle: Option<ChatComponent>,
+    pub times: TitleTimes,
+
+    /// Ticks since the title was shown, or `None` if no title is shown.
+    pub elapsed: Option<f32>,
+}
+
+impl TitleDisplay {
+    /// Applies a [`TitleAction`]. Action bar text is ignored.
+    pub fn apply(&mut self, action: &TitleAction) {
+        match action {
+            TitleAction::SetTitle(title) => {
+                self.title = Some(title.clone());
+                self.elapsed = Some(0.0);
+            }
+            TitleAction::SetSubtitle(subtitle) => self.subtitle = Some(subtitle.clone()),
+            TitleAction::SetActionBar(_) => {}
+            &TitleAction::SetTimes {
+                fade_in,
+                stay,
+                fade_out,
+            } => {
+                self.times = TitleTimes {
+                    fade_in,
+                    stay,
+                    fade_out,
+                };
+
+                // The new times apply to the title that's already up.
+                if self.elapsed.is_some() {
+                    self.elapsed = Some(0.0);
+                }
+            }
+            TitleAction::Hide => self.hide(),
+            TitleAction::Reset => {
+                self.hide();
+                self.times = TitleTimes::default();
+            }
+        }
+    }
+
+    /// Lets `ticks` ticks pass, hiding the title once it has faded out.
+    pub fn advance(&mut self, ticks: f32) {
+        if let Some(elapsed) = self.elapsed {
+            let elapsed = elapsed + ticks;
+            if elapsed >= self.times.total() {
+                self.hide();
+            } else {
+                self.elapsed = Some(elapsed);
+            }
+        }
+    }
+
+    /// Returns how opaque the title is, from 0 (hidden) to 1.
+    pub fn opacity(&self) -> f32 {
+        let elapsed = match self.elapsed {
+            Some(elapsed) => elapsed,
+            None => return 0.0,
+        };
+
+        let TitleTimes {
+            fade_in,
+            stay,
+            fade_out,
+        } = self.times;
+
+        let opacity = if elapsed < fade_in as f32 {
+            elapsed / fade_in as f32
+        } else if elapsed < (fade_in + stay) as f32 {
+            1.0
+        } else {
+            (self.times.total() - elapsed) / fade_out as f32
+        };
+
+        opacity.clamp(0.0, 1.0)
+    }
+
+    fn hide(&mut self) {
+        self.title = None;
+        self.subtitle = None;
+        self.elapsed = None;
+    }
+}
+
+/// Resource that holds the text shown above the hotbar, if any.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ActionBar {
+    pub text: Option<ChatComponent>,
+
+    /// Ticks left until the text disappears.
+    pub remaining: f32,
+}
+
+impl ActionBar {
+    pub fn set(&mut self, text: ChatComponent) {
+        self.text = Some(text);
+        self.remaining = ACTION_BAR_TICKS;
+    }
+
+    pub fn advance(&mut self, ticks: f32) {
+        self.remaining -= ticks;
+        if self.remaining <= 0.0 {
+            self.text = None;
+            self.remaining = 0.0;
+        }
+    }
+
+    /// Returns how opaque the text is, from 0 (hidden) to 1.
+    pub fn opacity(&self) -> f32 {
+        (self.remaining / ACTION_BAR_FADE_OUT_TICKS).clamp(0.0, 1.0)
+    }
+}
+
+/// Resource that maps the UUIDs of the boss bars being shown to their UI
+/// nodes.
+#[derive(Debug, Default)]
+pub struct BossBars(HashMap<Uuid, BossBarNodes>);
+
+impl BossBars {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BossBarNodes {
+    root: Entity,
+    title: Entity,
+    fill: Entity,
+}
+
+/// Resource that holds the font that HUD text is drawn with.
+pub struct HudFont(pub Handle<Font>);
+
+/// Marker component for the node that boss bars are added to.
+#[derive(Component)]
+struct BossBarContainer;
+
+#[derive(Component)]
+struct TitleText;
+
+#[derive(Component)]
+struct SubtitleText;
+
+#[derive(Component)]
+struct ActionBarText;
+
+fn set_up_hud(font: Handle<Font>, mut commands: Commands) {
+    commands.spawn_bundle(UiCameraBundle::default());
+
+    // Boss bars are stacked from the top of the screen down.
+    commands
+        .spawn_bundle(centered_column(Rect {
+            top: Val::Px(8.0),
+            ..Default::default()
+        }))
+        .insert_bundle((Name::new("Boss Bars"), BossBarContainer));
+
+    commands
+        .spawn_bundle(centered_column(Rect {
+            top: Val::Percent(30.0),
+            ..Default::default()
+        }))
+        .insert(Name::new("Title"))
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::default()).insert(TitleText);
+            parent
+                .spawn_bundle(TextBundle::default())
+                .insert(SubtitleText);
+        });
+
+    commands
+        .spawn_bundle(centered_column(Rect {
+            bottom: Val::Px(80.0),
+            ..Default::default()
+        }))
+        .insert(Name::new("Action Bar"))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::default())
+                .insert(ActionBarText);
+        });
+
+    commands.insert_resource(HudFont(font));
+}
+
+/// A transparent node that spans the width of the screen and centers its
+/// children horizontally, laying them out from top to bottom.
+fn centered_column(position: Rect<Val>) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position,
+            size: Size::new(Val::Percent(100.0), Val::Auto),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        color: UiColor(Color::NONE),
+        ..Default::default()
+    }
+}
+
+/// Converts a chat component to text sections with the component's colors.
+pub(crate) fn text_sections(
+    component: &ChatComponent,
+    font: &Handle<Font>,
+    font_size: f32,
+    opacity: f32,
+) -> Vec<TextSection> {
+    component
+        .to_sections()
+        .into_iter()
+        .map(|section| {
+            let mut color = section
+                .color
+                .and_then(|color| color.rgb())
+                .map(|[r, g, b]| Color::rgb_u8(r, g, b))
+                .unwrap_or(Color::WHITE);
+            color.set_a(opacity);
+
+            TextSection {
+                value: section.text,
+                style: TextStyle {
+                    font: font.clone(),
+                    font_size,
+                    color,
+                },
+            }
+        })
+        .collect()
+}
+
+fn boss_bar_color(color: BossBarColor) -> Color {
+    match color {
+        BossBarColor::Pink => Color::rgb_u8(0xec, 0x00, 0xb8),
+        BossBarColor::Blue => Color::rgb_u8(0x00, 0xb7, 0xec),
+        BossBarColor::Red => Color::rgb_u8(0xec, 0x35, 0x00),
+        BossBarColor::Green => Color::rgb_u8(0x1d, 0xec, 0x00),
+        BossBarColor::Yellow => Color::rgb_u8(0xe9, 0xec, 0x00),
+        BossBarColor::Purple => Color::rgb_u8(0x7b, 0x00, 0xec),
+        BossBarColor::White => Color::rgb_u8(0xec, 0xec, 0xec),
+    }
+}
+
+fn boss_bar_fill_size(health: f32) -> Size<Val> {
+    Size::new(
+        Val::Percent(health.clamp(0.0, 1.0) * 100.0),
+        Val::Percent(100.0),
+    )
+}
+
+fn update_boss_bars(
+    font: Res<HudFont>,
+    mut boss_bars: ResMut<BossBars>,
+    mut boss_bar_events: EventReader<BossBar>,
+    containers: Query<Entity, With<BossBarContainer>>,
+    mut texts: Query<&mut Text>,
+    mut fills: Query<(&mut Style, &mut UiColor)>,
+    mut commands: Commands,
+) {
+    let container = match containers.get_single() {
+        Ok(container) => container,
+        Err(_) => return,
+    };
+
+    for event in boss_bar_events.iter() {
+        if let BossBarAction::Add {
+            title,
+            health,
+            color,
+            ..
+        } = &event.action
+        {
+            let nodes = spawn_boss_bar(&mut commands, &font.0, title, *health, *color);
+            commands.entity(container).add_child(nodes.root);
+
+            if let Some(replaced) = boss_bars.0.insert(event.uuid, nodes) {
+                commands.entity(replaced.root).despawn_recursive();
+            }
+            continue;
+        }
+
+        // An update for a bar that was never added can't be shown.
+        let nodes = match boss_bars.0.get(&event.uuid) {
+            Some(nodes) => *nodes,
+            None => {
+                debug!("Ignoring update for unknown boss bar {}", event.uuid);
+                continue;
+            }
+        };
+
+        match &event.action {
+            BossBarAction::Remove => {
+                commands.entity(nodes.root).despawn_recursive();
+                boss_bars.0.remove(&event.uuid);
+            }
+            BossBarAction::UpdateHealth(health) => {
+                if let Ok((mut style, _)) = fills.get_mut(nodes.fill) {
+                    style.size = boss_bar_fill_size(*health);
+                }
+            }
+            BossBarAction::UpdateTitle(title) => {
+                if let Ok(mut text) = texts.get_mut(nodes.title) {
+                    text.sections = text_sections(title, &font.0, BOSS_BAR_FONT_SIZE, 1.0);
+                }
+            }
+            BossBarAction::UpdateStyle { color, .. } => {
+                if let Ok((_, mut fill_color)) = fills.get_mut(nodes.fill) {
+                    fill_color.0 = boss_bar_color(*color);
+                }
+            }
+            BossBarAction::Add { .. } | BossBarAction::UpdateFlags(_) => {}
+        }
+    }
+}
+
+fn spawn_boss_bar(
+    commands: &mut Commands,
+    font: &Handle<Font>,
+    title: &ChatComponent,
+    health: f32,
+    color: BossBarColor,
+) -> BossBarNodes {
+    let title = commands
+        .spawn_bundle(TextBundle {
+            text: Text {
+                sections: text_sections(title, font, BOSS_BAR_FONT_SIZE, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .id();
+
+    let fill = commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: boss_bar_fill_size(health),
+                ..Default::default()
+            },
+            color: UiColor(boss_bar_color(color)),
+            ..Default::default()
+        })
+        .id();
+
+    let background = commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(BOSS_BAR_WIDTH), Val::Px(BOSS_BAR_HEIGHT)),
+                margin: Rect {
+                    bottom: Val::Px(6.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            color: UiColor(Color::rgba(0.0, 0.0, 0.0, 0.5)),
+            ..Default::default()
+        })
+        .add_child(fill)
+        .id();
+
+    let root = commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(Name::new("Boss Bar"))
+        .push_children(&[title, background])
+        .id();
+
+    BossBarNodes { root, title, fill }
+}
+
+fn update_title_display(
+    time: Res<Time>,
+    mut title_events: EventReader<Title>,
+    mut title_display: ResMut<TitleDisplay>,
+) {
+    title_display.advance(time.delta_seconds() * TICKS_PER_SECOND);
+
+    for event in title_events.iter() {
+        title_display.apply(&event.action);
+    }
+}
+
+fn update_action_bar(
+    time: Res<Time>,
+    mut title_events: EventReader<Title>,
+    mut action_bar: ResMut<ActionBar>,
+) {
+    action_bar.advance(time.delta_seconds() * TICKS_PER_SECOND);
+
+    for event in title_events.iter() {
+        if let TitleAction::SetActionBar(text) = &event.action {
+            action_bar.set(text.clone());
+        }
+    }
+}
+
+fn draw_title(
+    font: Res<HudFont>,
+    title_display: Res<TitleDisplay>,
+    mut texts: Query<&mut Text, With<TitleText>>,
+) {
+    for mut text in texts.iter_mut() {
+        text.sections = match &title_display.title {
+            Some(title) => text_sections(title, &font.0, TITLE_FONT_SIZE, title_display.opacity()),
+            None => Vec::new(),
+        };
+    }
+}
+
+fn draw_subtitle(
+    font: Res<HudFont>,
+    title_display: Res<TitleDisplay>,
+    mut texts: Query<&mut Text, With<SubtitleText>>,
+) {
+    // The subtitle is only shown along with a title.
+    let subtitle = title_display
+        .title
+        .as_ref()
+        .and(title_display.subtitle.as_ref());
+
+    for mut text in texts.iter_mut() {
+        text.sections = match subtitle {
+            Some(subtitle) => text_sections(
+                subtitle,
+                &font.0,
+                SUBTITLE_FONT_SIZE,
+                title_display.opacity(),
+            ),
+            None => Vec::new(),
+        };
+    }
+}
+
+fn draw_action_bar(
+    font: Res<HudFont>,
+    action_bar: Res<ActionBar>,
+    mut texts: Query<&mut Text, With<ActionBarText>>,
+) {
+    for mut text in texts.iter_mut() {
+        text.sections = match &action_bar.text {
+            Some(action_bar_text) => text_sections(
+                action_bar_text,
+                &font.0,
+                ACTION_BAR_FONT_SIZE,
+                action_bar.opacity(),
+            ),
+            None => Vec::new(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set_times(fade_in: i32, stay: i32, fade_out: i32) -> TitleAction {
+        TitleAction::SetTimes {
+            fade_in,
+            stay,
+            fade_out,
+        }
+    }
+
+    #[test]
+    fn title_fades_in_and_out() {
+        let mut display = TitleDisplay::default();
+        display.apply(&set_times(10, 20, 10));
+        display.apply(&TitleAction::SetTitle(ChatComponent::text("Hi")));
+
+        assert_eq!(display.opacity(), 0.0);
+        display.advance(5.0);
+        assert_eq!(display.opacity(), 0.5);
+        display.advance(15.0);
+        assert_eq!(display.opacity(), 1.0);
+        display.advance(15.0);
+        assert_eq!(display.opacity(), 0.5);
+        display.advance(5.0);
+        assert_eq!(display.opacity(), 0.0);
+        assert_eq!(display.title, None);
+    }
+
+    #[test]
+    fn times_apply_to_the_shown_title() {
+        let mut display = TitleDisplay::default();
+        display.apply(&TitleAction::SetTitle(ChatComponent::text("Hi")));
+        display.advance(90.0);
+
+        // The default times would have the title almost faded out by now, but
+        // the new times restart it.
+        display.apply(&set_times(0, 100, 0));
+        display.advance(90.0);
+        assert_eq!(display.opacity(), 1.0);
+
+        display.advance(10.0);
+        assert_eq!(display.title, None);
+    }
+
+    #[test]
+    fn reset_clears_subtitle_and_times() {
+        let mut display = TitleDisplay::default();
+        display.apply(&set_times(1, 2, 3));
+        display.apply(&TitleAction::SetSubtitle(ChatComponent::text("sub")));
+        display.apply(&TitleAction::Reset);
+
+        assert_eq!(display, TitleDisplay::default());
+    }
+
+    #[test]
+    fn action_bar_fades_out() {
+        let mut action_bar = ActionBar::default();
+        action_bar.set(ChatComponent::text("Hello"));
+
+        action_bar.advance(ACTION_BAR_TICKS - ACTION_BAR_FADE_OUT_TICKS / 2.0);
+        assert_eq!(action_bar.opacity(), 0.5);
+
+        action_bar.advance(ACTION_BAR_FADE_OUT_TICKS);
+        assert_eq!(action_bar.text, None);
+        assert_eq!(action_bar.opacity(), 0.0);
+    }
+}
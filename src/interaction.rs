@@ -0,0 +1,286 @@
+//! Selecting hotbar slots and placing blocks.
+
+use bevy::prelude::*;
+
+use brine_chunk::{BlockClassifier, BlockState};
+use brine_data::{ItemId, MinecraftData};
+use brine_proto::event::{
+    clientbound::{HeldItemChanged, InventoryChanged, SlotChanged},
+    serverbound::{BlockFace, Hand, HeldItemChange, SwingArm, UseItemOn},
+    ItemStack,
+};
+use brine_voxel::{raycast::RayHit, Direction};
+use brine_voxel_v1::chunk_builder::{component::ChunkSection, ChunkIndex};
+
+use crate::{
+    picking::{self, BuiltChunkBlocks, PickedBlock},
+    prediction::PredictBlock,
+};
+
+/// Number of slots in the hotbar.
+pub const HOTBAR_SLOTS: usize = 9;
+
+/// Index of the first hotbar slot in the player's inventory window.
+const FIRST_HOTBAR_SLOT: i16 = 36;
+
+/// Keys that select each hotbar slot.
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Plugin that selects hotbar slots with the number keys, and uses the held
+/// item on the [`PickedBlock`] when the right mouse button is clicked.
+///
+/// If the held item is a block and there's room for it, the placed block is
+/// predicted with a [`PredictBlock`] event until the server answers.
+///
+/// Requires the [`BlockPickingPlugin`][crate::picking::BlockPickingPlugin]
+/// and the [`BlockPredictionPlugin`][crate::prediction::BlockPredictionPlugin].
+pub struct BlockInteractionPlugin;
+
+impl Plugin for BlockInteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hotbar>()
+            .add_system(update_hotbar.label(System::UpdateHotbar))
+            .add_system(select_hotbar_slot.after(System::UpdateHotbar))
+            .add_system(
+                use_held_item
+                    .after(System::UpdateHotbar)
+                    .after(picking::System::PickBlock),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+enum System {
+    UpdateHotbar,
+}
+
+/// Resource that holds the contents of the player's hotbar, as last sent by
+/// the server, and which slot is selected.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Hotbar {
+    /// Index of the selected slot (0-8).
+    pub selected: u8,
+
+    pub slots: [Option<ItemStack>; HOTBAR_SLOTS],
+}
+
+impl Hotbar {
+    /// Returns the item in the selected slot, if any.
+    pub fn held_item(&self) -> Option<&ItemStack> {
+        self.slots.get(self.selected as usize)?.as_ref()
+    }
+
+    /// Sets the contents of a slot of the player's inventory window, which is
+    /// ignored if it isn't a hotbar slot.
+    pub fn set_inventory_slot(&mut self, slot: i16, item: Option<ItemStack>) {
+        let index = slot - FIRST_HOTBAR_SLOT;
+        if (0..HOTBAR_SLOTS as i16).contains(&index) {
+            self.slots[index as usize] = item;
+        }
+    }
+}
+
+/// System that keeps the [`Hotbar`] in sync with the server.
+fn update_hotbar(
+    mut inventory_events: EventReader<InventoryChanged>,
+    mut slot_events: EventReader<SlotChanged>,
+    mut held_item_events: EventReader<HeldItemChanged>,
+    mut hotbar: ResMut<Hotbar>,
+) {
+    for event in inventory_events.iter().filter(|event| event.window_id == 0) {
+        for (slot, item) in event.slots.iter().enumerate() {
+            hotbar.set_inventory_slot(slot as i16, item.clone());
+        }
+    }
+
+    for event in slot_events.iter().filter(|event| event.window_id == 0) {
+        hotbar.set_inventory_slot(event.slot, event.item.clone());
+    }
+
+    for event in held_item_events.iter() {
+        hotbar.selected = event.slot.min(HOTBAR_SLOTS as u8 - 1);
+    }
+}
+
+/// System that selects a hotbar slot when its number key is pressed.
+fn select_hotbar_slot(
+    keys: Res<Input<KeyCode>>,
+    mut hotbar: ResMut<Hotbar>,
+    mut held_item_events: EventWriter<HeldItemChange>,
+) {
+    let slot = HOTBAR_KEYS
+        .iter()
+        .position(|key| keys.just_pressed(*key))
+        .map(|slot| slot as u8);
+
+    if let Some(slot) = slot {
+        if hotbar.selected != slot {
+            hotbar.selected = slot;
+            held_item_events.send(HeldItemChange { slot });
+        }
+    }
+}
+
+/// Returns the face of a block that the protocol calls `direction`.
+pub(crate) fn block_face(direction: Direction) -> BlockFace {
+    match direction {
+        Direction::XNeg => BlockFace::West,
+        Direction::XPos => BlockFace::East,
+        Direction::YNeg => BlockFace::Bottom,
+        Direction::YPos => BlockFace::Top,
+        Direction::ZNeg => BlockFace::North,
+        Direction::ZPos => BlockFace::South,
+    }
+}
+
+/// Returns the block that placing `item` would place, if the item has the same
+/// name as a block (e.g., `stone`, but not `wheat_seeds`).
+fn block_for_item(mc_data: &MinecraftData, item: &ItemStack) -> Option<BlockState> {
+    let item_id = u16::try_from(item.item_id).ok()?;
+    let item = mc_data.items().get_by_id(ItemId(item_id))?;
+
+    mc_data.blocks().block_state(item.name, &[])
+}
+
+/// Returns the event that uses an item on the face of the block that was hit.
+///
+/// The cursor is placed in the middle of the face.
+fn use_item_on(hit: &RayHit, hand: Hand) -> UseItemOn {
+    let normal = (hit.adjacent_pos() - hit.block_pos).as_vec3();
+
+    UseItemOn {
+        block_pos: hit.block_pos,
+        face: block_face(hit.face),
+        cursor_pos: Vec3::splat(0.5) + normal * 0.5,
+        hand,
+    }
+}
+
+/// System that uses the held item on the picked block when the right mouse
+/// button is clicked, predicting the placed block if there is one.
+#[allow(clippy::too_many_arguments)]
+fn use_held_item(
+    mouse_buttons: Res<Input<MouseButton>>,
+    picked_block: Res<PickedBlock>,
+    hotbar: Res<Hotbar>,
+    mc_data: Res<MinecraftData>,
+    chunk_index: Res<ChunkIndex>,
+    sections: Query<&ChunkSection>,
+    mut use_item_events: EventWriter<UseItemOn>,
+    mut swing_arm_events: EventWriter<SwingArm>,
+    mut predict_events: EventWriter<PredictBlock>,
+    mut classifier: Local<Option<BlockClassifier>>,
+) {
+    if !mouse_buttons.just_pressed(MouseButton::Right) {
+        return;
+    }
+
+    let hit = match picked_block.0 {
+        Some(hit) => hit,
+        None => return,
+    };
+
+    use_item_events.send(use_item_on(&hit, Hand::Main));
+    swing_arm_events.send(SwingArm { hand: Hand::Main });
+
+    let block = match hotbar
+        .held_item()
+        .and_then(|item| block_for_item(&mc_data, item))
+    {
+        Some(block) => block,
+        None => return,
+    };
+
+    // Only predict placing blocks into air. The server might still place it
+    // elsewhere (e.g., by replacing tall grass), which it will tell us about.
+    let classifier = classifier.get_or_insert_with(|| mc_data.blocks().classifier());
+    let blocks = BuiltChunkBlocks {
+        chunk_index: &*chunk_index,
+        sections: &sections,
+    };
+    let block_pos = hit.adjacent_pos();
+
+    if blocks
+        .get_block(block_pos)
+        .map_or(false, |current| classifier.is_air(current))
+    {
+        predict_events.send(PredictBlock {
+            block_pos,
+            block_state: block,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stone(count: u8) -> Option<ItemStack> {
+        Some(ItemStack {
+            item_id: 1,
+            count,
+            nbt: None,
+        })
+    }
+
+    #[test]
+    fn hotbar_tracks_inventory_slots() {
+        let mut hotbar = Hotbar::default();
+
+        hotbar.set_inventory_slot(35, stone(1));
+        hotbar.set_inventory_slot(36, stone(2));
+        hotbar.set_inventory_slot(44, stone(3));
+        hotbar.set_inventory_slot(45, stone(4));
+
+        assert_eq!(hotbar.slots[0], stone(2));
+        assert_eq!(hotbar.slots[8], stone(3));
+        assert_eq!(hotbar.slots.iter().flatten().count(), 2);
+
+        assert_eq!(hotbar.held_item(), stone(2).as_ref());
+        hotbar.selected = 1;
+        assert_eq!(hotbar.held_item(), None);
+    }
+
+    #[test]
+    fn block_items_place_their_block() {
+        let mc_data = MinecraftData::for_version("1.14.4");
+
+        assert_eq!(
+            block_for_item(&mc_data, &stone(1).unwrap()),
+            Some(BlockState(1))
+        );
+
+        let seeds = mc_data.items().get_by_name("wheat_seeds").unwrap();
+        let seeds = ItemStack {
+            item_id: seeds.id.0 as i32,
+            count: 1,
+            nbt: None,
+        };
+        assert_eq!(block_for_item(&mc_data, &seeds), None);
+    }
+
+    #[test]
+    fn cursor_is_in_the_middle_of_the_face() {
+        let hit = RayHit {
+            block_pos: IVec3::new(4, 64, -2),
+            face: Direction::YPos,
+            distance: 3.0,
+        };
+
+        let event = use_item_on(&hit, Hand::Main);
+
+        assert_eq!(event.block_pos, hit.block_pos);
+        assert_eq!(event.face, BlockFace::Top);
+        assert_eq!(event.cursor_pos, Vec3::new(0.5, 1.0, 0.5));
+    }
+}
@@ -3,10 +3,22 @@
 //! This library houses code that is common to the main Brine binary and other
 //! utility binaries in `src/bin/`.
 
+pub mod audio;
+pub mod capture;
 pub mod chunk;
+pub mod config;
 pub mod debug;
+pub mod digging;
+pub mod entity;
 pub mod error;
+pub mod hud;
+pub mod interaction;
 pub mod login;
+pub mod mesh_export;
+pub mod physics;
+pub mod picking;
+pub mod prediction;
+pub mod screenshot;
 pub mod server;
 
 pub const DEFAULT_LOG_FILTER: &str = "wgpu_core=warn,naga=warn";
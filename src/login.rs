@@ -1,8 +1,17 @@
-use bevy::{app::AppExit, prelude::*};
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
 
-use brine_proto::event::{
-    clientbound::{Disconnect, LoginSuccess},
-    serverbound::Login,
+use brine_data::{MinecraftData, MinecraftDataRegistry, Version};
+use brine_proto::{
+    event::{
+        clientbound::{Disconnect, LoginSuccess},
+        serverbound::Login,
+    },
+    Authenticator, LoginAuthenticator,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -20,8 +29,16 @@ struct LoginInfo {
 }
 
 /// Simple plugin that initiates login to a Minecraft server on app startup.
+///
+/// If a [`MinecraftDataRegistry`] resource exists, the plugin also replaces the
+/// [`MinecraftData`] resource with data for the server's version once login
+/// succeeds. The data is loaded on the [`AsyncComputeTaskPool`].
+///
+/// Login is offline unless an [`Authenticator`] is given with
+/// [`with_authenticator`][Self::with_authenticator].
 pub struct LoginPlugin {
     info: LoginInfo,
+    authenticator: LoginAuthenticator,
 }
 
 impl LoginPlugin {
@@ -32,9 +49,17 @@ impl LoginPlugin {
                 username,
                 exit_on_disconnect: false,
             },
+            authenticator: LoginAuthenticator::default(),
         }
     }
 
+    /// Uses `authenticator` to authenticate with servers that are in online
+    /// mode.
+    pub fn with_authenticator(mut self, authenticator: impl Authenticator) -> Self {
+        self.authenticator = LoginAuthenticator::new(authenticator);
+        self
+    }
+
     pub fn exit_on_disconnect(mut self) -> Self {
         self.info.exit_on_disconnect = true;
         self
@@ -44,6 +69,7 @@ impl LoginPlugin {
 impl Plugin for LoginPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(self.info.clone())
+            .insert_resource(self.authenticator.clone())
             .add_state(GameState::Idle)
             .add_startup_system(initiate_login)
             .add_system_set(
@@ -51,7 +77,9 @@ impl Plugin for LoginPlugin {
                     .with_system(await_success)
                     .with_system(handle_disconnect),
             )
-            .add_system_set(SystemSet::on_update(GameState::Play).with_system(handle_disconnect));
+            .add_system_set(SystemSet::on_update(GameState::Play).with_system(handle_disconnect))
+            .add_system(select_minecraft_data)
+            .add_system(insert_minecraft_data);
     }
 }
 
@@ -93,3 +121,66 @@ fn handle_disconnect(
         }
     }
 }
+
+type LoadMinecraftDataTask = Task<MinecraftData>;
+
+/// System that starts loading the [`MinecraftData`] matching the server's
+/// protocol version after a successful login.
+fn select_minecraft_data(
+    mut login_success_events: EventReader<LoginSuccess>,
+    registry: Option<Res<MinecraftDataRegistry>>,
+    current_data: Option<Res<MinecraftData>>,
+    task_pool: Res<AsyncComputeTaskPool>,
+    mut commands: Commands,
+) {
+    let (login_success, registry) = match (login_success_events.iter().last(), registry) {
+        (Some(login_success), Some(registry)) => (login_success, registry),
+        _ => return,
+    };
+
+    let protocol_version = login_success.protocol_version;
+
+    let version = match Version::from_protocol_version(protocol_version) {
+        Some(version) => version,
+        None => {
+            let fallback = current_data
+                .map(|data| data.version().to_string())
+                .unwrap_or_else(|| String::from("none"));
+            error!(
+                "No Minecraft data available for server protocol version {}, \
+                 continuing with data for version {}",
+                protocol_version, fallback
+            );
+            return;
+        }
+    };
+
+    if let Some(current_data) = current_data {
+        if *current_data.version() == version {
+            return;
+        }
+    }
+
+    info!("Loading Minecraft data for server version {}", version);
+
+    let registry = (*registry).clone();
+    let task: LoadMinecraftDataTask = task_pool.spawn(async move { registry.get_or_load(version) });
+
+    commands
+        .spawn()
+        .insert_bundle((task, Name::new("Loading Minecraft Data")));
+}
+
+fn insert_minecraft_data(
+    mut tasks: Query<(Entity, &mut LoadMinecraftDataTask)>,
+    mut commands: Commands,
+) {
+    for (task_entity, mut task) in tasks.iter_mut() {
+        if let Some(mc_data) = future::block_on(future::poll_once(&mut *task)) {
+            info!("Switched to Minecraft data for version {}", mc_data.version());
+            commands.insert_resource(mc_data);
+
+            commands.entity(task_entity).despawn();
+        }
+    }
+}
@@ -10,30 +10,63 @@ use bevy::{
 use bevy_fly_camera::{FlyCamera, FlyCameraPlugin};
 use bevy_inspector_egui::prelude::*;
 use brine_asset::MinecraftAssets;
-use brine_data::MinecraftData;
+use brine_data::MinecraftDataRegistry;
 use clap::Parser;
 
-use brine_proto::{AlwaysSuccessfulLoginPlugin, ProtocolPlugin};
-use brine_proto_backend::ProtocolBackendPlugin;
+use brine_chunk::CHUNK_WIDTH;
+use brine_net::{
+    record::{Recorder, Recording},
+    replay::{ReplayConnectionPlugin, ReplayTiming},
+    NetworkResource,
+};
+use brine_proto::{event::clientbound::ChunkData, AlwaysSuccessfulLoginPlugin, ProtocolPlugin};
+use brine_proto_backend::{backend_stevenarella::ProtocolCodec, ProtocolBackendPlugin};
+use brine_render::{
+    sky::SkyPlugin,
+    texture::{AtlasDiagnosticsPlugin, MinecraftTexturesPlugin, TextureManagerPlugin},
+};
 use brine_voxel_v1::{
     chunk_builder::{
-        component::BuiltChunkSection, ChunkBuilderPlugin, GreedyQuadsChunkBuilder,
+        material::ChunkMaterialDiagnosticsPlugin, ChunkBuilderPlugin, GreedyQuadsChunkBuilder,
         VisibleFacesChunkBuilder,
     },
+    lod::{ChunkLodPlugin, LodSettings},
     texture::TextureBuilderPlugin,
+    visibility::ChunkVisibilityPlugin,
 };
 
 use brine::{
-    debug::DebugWireframePlugin, login::LoginPlugin, server::ServeChunksFromDirectoryPlugin,
+    audio::AudioPlugin,
+    capture::ChunkCapturePlugin,
+    config::{Config, PartialConfig},
+    debug::{BrineDiagnosticsPlugin, DebugTabListPlugin, DebugWireframePlugin},
+    digging::BlockDiggingPlugin,
+    entity::EntityPlaceholderPlugin,
+    hud::HudPlugin,
+    interaction::BlockInteractionPlugin,
+    login::LoginPlugin,
+    physics::{Player, PlayerPhysicsPlugin},
+    picking::BlockPickingPlugin,
+    prediction::BlockPredictionPlugin,
+    server::{ServeChunksFromDirectoryPlugin, ServeChunksFromWorldPlugin},
     DEFAULT_LOG_FILTER,
 };
 
-const SERVER: &str = "localhost:25565";
-const USERNAME: &str = "user";
-
 /// Brine Minecraft Client
 #[derive(Parser)]
 struct Args {
+    /// Address of the server to connect to.
+    #[clap(long, value_name = "HOST:PORT")]
+    server: Option<String>,
+
+    /// Username to log in with.
+    #[clap(long, value_name = "NAME")]
+    username: Option<String>,
+
+    /// Minecraft version to load data and assets for (e.g., 1.14.4).
+    #[clap(long, value_name = "VERSION")]
+    version_string: Option<String>,
+
     /// Run with additional debug utilities (e.g., egui inspector).
     #[clap(short, long)]
     debug: bool,
@@ -41,11 +74,91 @@ struct Args {
     /// Run with a fake server that serves chunks from a directory of chunk files.
     #[clap(name = "chunks", long, value_name = "CHUNK_DIR")]
     chunk_dir: Option<PathBuf>,
+
+    /// When serving chunks from a directory, send the chunks listed in a
+    /// manifest file, in order, instead of every chunk file in the directory.
+    #[clap(long, value_name = "FILE", requires = "chunks")]
+    chunk_manifest: Option<PathBuf>,
+
+    /// Run with a fake server that serves chunks from a vanilla world's region
+    /// files.
+    #[clap(long, value_name = "WORLD_DIR", conflicts_with = "chunks")]
+    world: Option<PathBuf>,
+
+    /// Save every chunk received from the server to a directory of chunk files.
+    #[clap(long, value_name = "CAPTURE_DIR")]
+    capture: Option<PathBuf>,
+
+    /// Record every packet sent to and received from the server to a file.
+    #[clap(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Play back a recording made with `--record` instead of connecting to
+    /// the server.
+    #[clap(long, value_name = "FILE", conflicts_with_all = &["chunks", "world", "record"])]
+    replay: Option<PathBuf>,
+
+    /// When replaying, wait between packets as long as the server originally
+    /// did instead of replaying as fast as possible.
+    #[clap(long, requires = "replay")]
+    replay_original_timing: bool,
+
+    /// When capturing, keep every copy of a chunk instead of overwriting it.
+    #[clap(long, requires = "capture")]
+    keep_history: bool,
+
+    /// Render chunks further than this many chunks from the camera at a lower
+    /// level of detail.
+    #[clap(long, value_name = "CHUNKS")]
+    lod_distance: Option<f32>,
+
+    /// Walk around with gravity and collisions instead of flying.
+    #[clap(long)]
+    walk: bool,
+
+    /// Draw chunks at full brightness instead of lighting them by the time of
+    /// day.
+    #[clap(long)]
+    unlit: bool,
+
+    /// Texture every chunk from one atlas of all block textures, so that all
+    /// chunks share the same material.
+    #[clap(long)]
+    shared_atlas: bool,
+
+    /// Font to draw boss bars, titles, and the action bar with, relative to
+    /// the assets directory.
+    #[clap(long, value_name = "FONT")]
+    hud_font: Option<String>,
+
+    /// Play the sounds sent by the server. Without the `audio` feature, sounds
+    /// are only logged.
+    #[clap(long)]
+    audio: bool,
+}
+
+impl Args {
+    fn config_flags(&self) -> PartialConfig {
+        PartialConfig {
+            server: self.server.clone(),
+            username: self.username.clone(),
+            version: self.version_string.clone(),
+            assets: None,
+        }
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
+    let config = match Config::load(args.config_flags()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     let mut app = App::new();
 
     // Default plugins.
@@ -62,27 +175,100 @@ fn main() {
 
     if let Some(chunk_dir) = args.chunk_dir {
         app.add_plugin(AlwaysSuccessfulLoginPlugin);
-        app.add_plugin(ServeChunksFromDirectoryPlugin::new(chunk_dir));
+        let mut plugin = ServeChunksFromDirectoryPlugin::new(chunk_dir);
+        if let Some(manifest) = &args.chunk_manifest {
+            plugin = plugin.with_manifest(manifest);
+        }
+        app.add_plugin(plugin);
+    } else if let Some(world_dir) = args.world {
+        app.add_plugin(AlwaysSuccessfulLoginPlugin);
+        app.add_plugin(ServeChunksFromWorldPlugin::new(world_dir));
     } else {
         app.add_plugin(ProtocolBackendPlugin);
-        app.add_plugin(
-            LoginPlugin::new(SERVER.to_string(), USERNAME.to_string()).exit_on_disconnect(),
-        );
+        app.add_plugin(LoginPlugin::new(config.server, config.username).exit_on_disconnect());
+
+        if let Some(record_path) = &args.record {
+            let recorder = match Recorder::create(record_path) {
+                Ok(recorder) => recorder,
+                Err(e) => {
+                    eprintln!("error: failed to create {}: {}", record_path.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            app.world
+                .get_resource_mut::<NetworkResource<ProtocolCodec>>()
+                .unwrap()
+                .start_recording(recorder);
+        }
+
+        if let Some(replay_path) = &args.replay {
+            let recording = match Recording::from_file(replay_path) {
+                Ok(recording) => recording,
+                Err(e) => {
+                    eprintln!("error: failed to read {}: {}", replay_path.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            let timing = if args.replay_original_timing {
+                ReplayTiming::Original
+            } else {
+                ReplayTiming::FullSpeed
+            };
+            app.add_plugin(ReplayConnectionPlugin::<ProtocolCodec>::new(
+                recording, timing,
+            ));
+        }
     }
 
-    let mc_data = MinecraftData::for_version("1.14.4");
-    let mc_assets = MinecraftAssets::new("assets/1.14.4", &mc_data).unwrap();
+    let mc_data_registry = MinecraftDataRegistry::new();
+    let mc_data = mc_data_registry.get_or_load(config.version.as_str());
+    let mc_assets = match MinecraftAssets::new(&config.assets, &mc_data) {
+        Ok(mc_assets) => mc_assets,
+        Err(e) => {
+            eprintln!(
+                "error: failed to load assets from {}: {}",
+                config.assets.display(),
+                e
+            );
+            std::process::exit(1);
+        }
+    };
+    app.insert_resource(mc_data_registry);
     app.insert_resource(mc_data);
     app.insert_resource(mc_assets);
     app.add_plugin(TextureBuilderPlugin);
 
-    app.add_plugin(MinecraftWorldViewerPlugin);
+    // The capture plugin needs to see ChunkData events before they're drained.
+    let share_chunk_events = args.capture.is_some();
+    if let Some(capture_dir) = args.capture {
+        app.add_plugin(ChunkCapturePlugin::new(capture_dir).keep_history(args.keep_history));
+    }
+
+    app.add_plugin(MinecraftWorldViewerPlugin {
+        share_chunk_events,
+        lod_distance: args.lod_distance,
+        walk: args.walk,
+        unlit: args.unlit,
+        shared_atlas: args.shared_atlas,
+    });
+    app.add_plugin(args.hud_font.map(HudPlugin::new).unwrap_or_default());
+    app.add_plugin(BlockPickingPlugin)
+        .add_plugin(BlockInteractionPlugin)
+        .add_plugin(BlockDiggingPlugin);
+
+    if args.audio {
+        app.add_plugin(AudioPlugin);
+    }
 
     // Debugging, diagnostics, and utility plugins.
 
     if args.debug {
         app.add_plugin(WorldInspectorPlugin::new())
             .add_plugin(DebugWireframePlugin)
+            .add_plugin(DebugTabListPlugin)
+            .add_plugin(ChunkMaterialDiagnosticsPlugin)
+            .add_plugin(AtlasDiagnosticsPlugin)
+            .add_plugin(BrineDiagnosticsPlugin::default().with_panel())
             .add_plugin(FrameTimeDiagnosticsPlugin)
             .add_plugin(LogDiagnosticsPlugin::default());
     }
@@ -91,19 +277,77 @@ fn main() {
 }
 
 #[derive(Default)]
-pub struct MinecraftWorldViewerPlugin;
+pub struct MinecraftWorldViewerPlugin {
+    /// Whether other plugins also need to read ChunkData events.
+    share_chunk_events: bool,
+
+    /// Distance (in chunks) beyond which chunks are rendered at a lower level
+    /// of detail, if at all.
+    lod_distance: Option<f32>,
+
+    /// Whether the camera walks (see [`PlayerPhysicsPlugin`]) instead of
+    /// flying.
+    walk: bool,
+
+    /// Whether chunks are drawn at full brightness instead of being lit by the
+    /// [`SkyPlugin`].
+    unlit: bool,
+
+    /// Whether chunks are textured from one shared atlas (see
+    /// [`ChunkBuilderPlugin::with_shared_atlas`]).
+    shared_atlas: bool,
+}
 
 impl Plugin for MinecraftWorldViewerPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Msaa { samples: 4 })
-            .add_plugin(FlyCameraPlugin)
-            .add_plugin(ChunkBuilderPlugin::<VisibleFacesChunkBuilder>::default())
-            // .add_plugin(ChunkBuilderPlugin::<GreedyQuadsChunkBuilder>::default())
-            .add_startup_system(set_up_camera)
-            .add_system(give_chunk_sections_correct_y_height);
+        let chunk_builder = if self.share_chunk_events {
+            ChunkBuilderPlugin::<VisibleFacesChunkBuilder>::shared()
+        } else {
+            ChunkBuilderPlugin::<VisibleFacesChunkBuilder>::default()
+        };
+        app.add_plugin(
+            chunk_builder
+                .with_lighting(!self.unlit)
+                .with_shared_atlas(self.shared_atlas),
+        );
+
+        if self.shared_atlas {
+            app.add_plugin(TextureManagerPlugin)
+                .add_plugin(MinecraftTexturesPlugin);
+        }
+        // app.add_plugin(ChunkBuilderPlugin::<GreedyQuadsChunkBuilder>::default());
+
+        if let Some(distance) = self.lod_distance {
+            app.add_plugin(ChunkLodPlugin::<VisibleFacesChunkBuilder>::new(
+                LodSettings {
+                    distance,
+                    lighting: !self.unlit,
+                    ..Default::default()
+                },
+            ));
+        }
+
+        app.add_plugin(ChunkVisibilityPlugin);
+        app.add_plugin(BlockPredictionPlugin);
+        app.add_plugin(SkyPlugin);
+        app.add_plugin(EntityPlaceholderPlugin);
+
+        app.insert_resource(Msaa { samples: 4 });
+
+        if self.walk {
+            app.add_plugin(PlayerPhysicsPlugin)
+                .add_startup_system(set_up_walking_camera);
+        } else {
+            app.add_plugin(FlyCameraPlugin)
+                .add_startup_system(set_up_camera);
+        }
+        app.add_system(place_camera_above_first_chunk);
     }
 }
 
+/// How far above the ground the fly camera is placed.
+const FLY_CAMERA_HEIGHT: f32 = 2.0;
+
 fn set_up_camera(mut commands: Commands) {
     // Screenshot coords.
     let camera_start = Transform::from_translation(Vec3::new(-200.0, 87.8, 157.3))
@@ -120,11 +364,50 @@ fn set_up_camera(mut commands: Commands) {
         .insert(FlyCamera::default());
 }
 
-fn give_chunk_sections_correct_y_height(mut query: Query<(&mut Transform, &BuiltChunkSection)>) {
-    for (mut transform, chunk_section) in query.iter_mut() {
-        let height = (chunk_section.section_y as f32) * 16.0;
-        if transform.translation.y != height {
-            transform.translation.y = height;
+/// System that moves the camera above the middle of the first chunk that has
+/// heightmaps, standing on its world surface.
+fn place_camera_above_first_chunk(
+    mut chunk_events: EventReader<ChunkData>,
+    mut fly_cameras: Query<&mut Transform, With<FlyCamera>>,
+    mut players: Query<&mut Player>,
+    mut placed: Local<bool>,
+) {
+    if *placed {
+        return;
+    }
+
+    for ChunkData { chunk_data } in chunk_events.iter() {
+        let (x, z) = (CHUNK_WIDTH as u8 / 2, CHUNK_WIDTH as u8 / 2);
+        let height = match chunk_data.height_at(x, z) {
+            Some(height) => height as f32,
+            None => continue,
+        };
+
+        let ground = Vec3::new(
+            (chunk_data.chunk_x * CHUNK_WIDTH as i32 + x as i32) as f32 + 0.5,
+            height,
+            (chunk_data.chunk_z * CHUNK_WIDTH as i32 + z as i32) as f32 + 0.5,
+        );
+
+        for mut transform in fly_cameras.iter_mut() {
+            transform.translation = ground + Vec3::Y * FLY_CAMERA_HEIGHT;
+        }
+        for mut player in players.iter_mut() {
+            player.position = ground;
         }
+
+        *placed = true;
+        break;
     }
 }
+
+fn set_up_walking_camera(mut commands: Commands) {
+    let player = Player::at(Vec3::new(-200.0, 87.8, 157.3));
+
+    commands
+        .spawn_bundle(PerspectiveCameraBundle {
+            transform: Transform::from_translation(player.position),
+            ..Default::default()
+        })
+        .insert(player);
+}
@@ -0,0 +1,673 @@
+//! Writing meshes to standard 3D file formats, for inspecting them in other
+//! tools such as Blender.
+//!
+//! A [`MeshScene`] is a list of named objects, each made of primitives that
+//! share a material. Materials refer to PNG textures on disk. A scene can be
+//! written as:
+//!
+//! * Wavefront OBJ, with an MTL file next to it that refers to the textures.
+//! * glTF 2.0, as a single JSON file with the binary data and textures
+//!   embedded as a base64 data URI.
+//! * Binary glTF 2.0 (GLB), with the binary data and textures embedded.
+//!
+//! Texture coordinates follow Minecraft's (and glTF's) convention of having
+//! the origin at the top left of the texture.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A file format that a [`MeshScene`] can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshFormat {
+    Obj,
+    Gltf,
+    Glb,
+}
+
+impl MeshFormat {
+    /// Chooses the format from the extension of `path`: `.gltf` for glTF,
+    /// `.glb` for binary glTF, and OBJ otherwise.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("gltf") => Self::Gltf,
+            Some(extension) if extension.eq_ignore_ascii_case("glb") => Self::Glb,
+            _ => Self::Obj,
+        }
+    }
+}
+
+impl FromStr for MeshFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "obj" => Ok(Self::Obj),
+            "gltf" => Ok(Self::Gltf),
+            "glb" => Ok(Self::Glb),
+            _ => Err(format!("unknown mesh format `{}`", s)),
+        }
+    }
+}
+
+/// A material, which is just a texture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshMaterial {
+    pub name: String,
+
+    /// Path of a PNG file, if the material has a texture.
+    pub texture: Option<PathBuf>,
+}
+
+/// Triangles that share a material.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MeshPrimitive {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub tex_coords: Vec<[f32; 2]>,
+
+    /// Three indices per triangle.
+    pub indices: Vec<u32>,
+
+    /// Index of the primitive's material in [`MeshScene::materials`].
+    pub material: Option<usize>,
+}
+
+impl MeshPrimitive {
+    pub fn num_vertices(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+/// A named object, made of any number of primitives.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MeshObject {
+    pub name: String,
+    pub primitives: Vec<MeshPrimitive>,
+}
+
+impl MeshObject {
+    pub fn num_vertices(&self) -> usize {
+        self.primitives
+            .iter()
+            .map(MeshPrimitive::num_vertices)
+            .sum()
+    }
+
+    pub fn num_triangles(&self) -> usize {
+        self.primitives
+            .iter()
+            .map(MeshPrimitive::num_triangles)
+            .sum()
+    }
+}
+
+/// Objects and the materials that they use.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MeshScene {
+    pub objects: Vec<MeshObject>,
+    pub materials: Vec<MeshMaterial>,
+}
+
+impl MeshScene {
+    /// Writes the scene to `path` in the given format.
+    ///
+    /// For OBJ, the materials are written to an MTL file with the same name
+    /// as `path`, unless there are none.
+    pub fn save(&self, path: &Path, format: MeshFormat) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        match format {
+            MeshFormat::Obj if self.materials.is_empty() => write_obj(self, None, &mut writer)?,
+            MeshFormat::Obj => {
+                let mtl_path = path.with_extension("mtl");
+                let mut mtl_writer = BufWriter::new(File::create(&mtl_path)?);
+                write_mtl(self, &mut mtl_writer)?;
+                mtl_writer.flush()?;
+
+                let mtl_name = mtl_path.file_name().unwrap().to_string_lossy();
+                write_obj(self, Some(mtl_name.as_ref()), &mut writer)?;
+            }
+            MeshFormat::Gltf => write_gltf(self, &mut writer)?,
+            MeshFormat::Glb => write_glb(self, &mut writer)?,
+        }
+
+        writer.flush()
+    }
+}
+
+/// Writes the scene as a Wavefront OBJ file, one `o` per object and one
+/// `usemtl` per primitive with a material.
+///
+/// If `mtllib` is given, the materials are referred to by name from that MTL
+/// file (see [`write_mtl`]).
+pub fn write_obj(
+    scene: &MeshScene,
+    mtllib: Option<&str>,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(writer, "# Exported by brine")?;
+    if let Some(mtllib) = mtllib {
+        writeln!(writer, "mtllib {}", mtllib)?;
+    }
+
+    // OBJ indices are 1-based and count vertices across the whole file.
+    let mut first_vertex = 1;
+
+    for object in scene.objects.iter() {
+        writeln!(writer, "o {}", object.name)?;
+
+        for primitive in object.primitives.iter() {
+            if let (Some(_), Some(material)) = (mtllib, primitive.material) {
+                writeln!(writer, "usemtl {}", scene.materials[material].name)?;
+            }
+
+            for [x, y, z] in primitive.positions.iter() {
+                writeln!(writer, "v {} {} {}", x, y, z)?;
+            }
+
+            // OBJ puts the origin of texture space at the bottom left.
+            for [u, v] in primitive.tex_coords.iter() {
+                writeln!(writer, "vt {} {}", u, 1.0 - v)?;
+            }
+
+            for [x, y, z] in primitive.normals.iter() {
+                writeln!(writer, "vn {} {} {}", x, y, z)?;
+            }
+
+            // Every vertex has its own UV and normal.
+            for triangle in primitive.indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]]
+                    .map(|index| index as usize + first_vertex);
+                writeln!(writer, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+            }
+
+            first_vertex += primitive.num_vertices();
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the scene's materials as a Wavefront MTL file.
+pub fn write_mtl(scene: &MeshScene, writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, "# Exported by brine")?;
+
+    for material in scene.materials.iter() {
+        writeln!(writer)?;
+        writeln!(writer, "newmtl {}", material.name)?;
+        writeln!(writer, "Kd 1 1 1")?;
+        if let Some(texture) = material.texture.as_ref() {
+            writeln!(writer, "map_Kd {}", texture.display())?;
+            writeln!(writer, "map_d {}", texture.display())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the scene as a self-contained glTF 2.0 file, with the binary
+/// buffer embedded as a base64 data URI.
+pub fn write_gltf(scene: &MeshScene, writer: &mut impl Write) -> io::Result<()> {
+    let (mut gltf, buffer) = build_gltf(scene)?;
+
+    gltf["buffers"] = serde_json::json!([{
+        "byteLength": buffer.len(),
+        "uri": format!("data:application/octet-stream;base64,{}", base64(&buffer)),
+    }]);
+
+    writeln!(writer, "{:#}", gltf)
+}
+
+/// Writes the scene as a binary glTF 2.0 file.
+pub fn write_glb(scene: &MeshScene, writer: &mut impl Write) -> io::Result<()> {
+    const MAGIC: &[u8; 4] = b"glTF";
+    const VERSION: u32 = 2;
+    const JSON_CHUNK: &[u8; 4] = b"JSON";
+    const BIN_CHUNK: &[u8; 4] = b"BIN\0";
+
+    let (mut gltf, mut buffer) = build_gltf(scene)?;
+
+    gltf["buffers"] = serde_json::json!([{ "byteLength": buffer.len() }]);
+
+    // Both chunks have to be padded to 4 bytes, the JSON with spaces.
+    let mut json = serde_json::to_vec(&gltf)?;
+    pad(&mut json, b' ');
+    pad(&mut buffer, 0);
+
+    let total_length = 12 + 8 + json.len() + 8 + buffer.len();
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&(total_length as u32).to_le_bytes())?;
+
+    writer.write_all(&(json.len() as u32).to_le_bytes())?;
+    writer.write_all(JSON_CHUNK)?;
+    writer.write_all(&json)?;
+
+    writer.write_all(&(buffer.len() as u32).to_le_bytes())?;
+    writer.write_all(BIN_CHUNK)?;
+    writer.write_all(&buffer)?;
+
+    Ok(())
+}
+
+fn pad(bytes: &mut Vec<u8>, padding: u8) {
+    while bytes.len() % 4 != 0 {
+        bytes.push(padding);
+    }
+}
+
+/// Builds the glTF document for the scene, without its `buffers`, along with
+/// the contents of its one buffer.
+///
+/// Each object becomes a node with a mesh, and each material with a texture
+/// embeds the texture's PNG file in the buffer.
+fn build_gltf(scene: &MeshScene) -> io::Result<(serde_json::Value, Vec<u8>)> {
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const TRIANGLES: u32 = 4;
+    const NEAREST: u32 = 9728;
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+
+    let mut push_view = |bytes: &[u8], target: Option<u32>| {
+        // Every view has to be aligned to its component size.
+        pad(&mut buffer, 0);
+        let mut view = serde_json::json!({
+            "buffer": 0,
+            "byteOffset": buffer.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = target.into();
+        }
+        buffer.extend_from_slice(bytes);
+        buffer_views.push(view);
+        buffer_views.len() - 1
+    };
+
+    let floats = |values: &[f32]| -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    };
+
+    let mut images = Vec::new();
+    let mut textures = Vec::new();
+    let mut materials = Vec::new();
+
+    for material in scene.materials.iter() {
+        let mut pbr = serde_json::json!({ "metallicFactor": 0.0 });
+
+        if let Some(texture) = material.texture.as_ref() {
+            let png = fs::read(texture)?;
+            let view = push_view(&png, None);
+
+            images.push(serde_json::json!({ "bufferView": view, "mimeType": "image/png" }));
+            textures.push(serde_json::json!({ "sampler": 0, "source": images.len() - 1 }));
+            pbr["baseColorTexture"] = serde_json::json!({ "index": textures.len() - 1 });
+        }
+
+        materials.push(serde_json::json!({
+            "name": material.name,
+            "pbrMetallicRoughness": pbr,
+            "alphaMode": "MASK",
+        }));
+    }
+
+    let mut meshes = Vec::new();
+    let mut nodes = Vec::new();
+
+    for object in scene.objects.iter() {
+        let mut primitives = Vec::new();
+
+        // Accessors can't be empty.
+        for primitive in object.primitives.iter().filter(|p| !p.is_empty()) {
+            let positions = push_view(&floats(&primitive.positions.concat()), Some(ARRAY_BUFFER));
+            let normals = push_view(&floats(&primitive.normals.concat()), Some(ARRAY_BUFFER));
+            let tex_coords = push_view(&floats(&primitive.tex_coords.concat()), Some(ARRAY_BUFFER));
+            let indices: Vec<u8> = primitive
+                .indices
+                .iter()
+                .flat_map(|index| index.to_le_bytes())
+                .collect();
+            let indices = push_view(&indices, Some(ELEMENT_ARRAY_BUFFER));
+
+            // POSITION accessors are required to have bounds.
+            let mut min = [f32::MAX; 3];
+            let mut max = [f32::MIN; 3];
+            for position in primitive.positions.iter() {
+                for ((min, max), value) in min.iter_mut().zip(max.iter_mut()).zip(position) {
+                    *min = min.min(*value);
+                    *max = max.max(*value);
+                }
+            }
+
+            let num_vertices = primitive.num_vertices();
+            let first_accessor = accessors.len();
+            accessors.extend([
+                serde_json::json!({
+                    "bufferView": positions,
+                    "componentType": FLOAT,
+                    "count": num_vertices,
+                    "type": "VEC3",
+                    "min": min,
+                    "max": max,
+                }),
+                serde_json::json!({
+                    "bufferView": normals,
+                    "componentType": FLOAT,
+                    "count": num_vertices,
+                    "type": "VEC3",
+                }),
+                serde_json::json!({
+                    "bufferView": tex_coords,
+                    "componentType": FLOAT,
+                    "count": num_vertices,
+                    "type": "VEC2",
+                }),
+                serde_json::json!({
+                    "bufferView": indices,
+                    "componentType": UNSIGNED_INT,
+                    "count": primitive.indices.len(),
+                    "type": "SCALAR",
+                }),
+            ]);
+
+            let mut gltf_primitive = serde_json::json!({
+                "attributes": {
+                    "POSITION": first_accessor,
+                    "NORMAL": first_accessor + 1,
+                    "TEXCOORD_0": first_accessor + 2,
+                },
+                "indices": first_accessor + 3,
+                "mode": TRIANGLES,
+            });
+            if let Some(material) = primitive.material {
+                gltf_primitive["material"] = material.into();
+            }
+            primitives.push(gltf_primitive);
+        }
+
+        // Meshes can't be empty either, but nodes can.
+        let mut node = serde_json::json!({ "name": object.name });
+        if !primitives.is_empty() {
+            meshes.push(serde_json::json!({ "name": object.name, "primitives": primitives }));
+            node["mesh"] = (meshes.len() - 1).into();
+        }
+        nodes.push(node);
+    }
+
+    let mut gltf = serde_json::json!({
+        "asset": { "version": "2.0", "generator": "brine" },
+        "scene": 0,
+        "scenes": [{ "nodes": (0..nodes.len()).collect::<Vec<_>>() }],
+        "nodes": nodes,
+        "meshes": meshes,
+        "materials": materials,
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+    });
+
+    // Keep the textures pixelated.
+    if !textures.is_empty() {
+        gltf["images"] = images.into();
+        gltf["textures"] = textures.into();
+        gltf["samplers"] = serde_json::json!([{ "magFilter": NEAREST, "minFilter": NEAREST }]);
+    }
+
+    Ok((gltf, buffer))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A unit square in the XZ plane.
+    fn square(material: Option<usize>) -> MeshPrimitive {
+        MeshPrimitive {
+            positions: vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 1.0]],
+            normals: vec![[0.0, 1.0, 0.0]; 4],
+            tex_coords: vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]],
+            indices: vec![0, 2, 1, 1, 2, 3],
+            material,
+        }
+    }
+
+    /// Two objects, the first of which has one square per material.
+    fn scene(texture: PathBuf) -> MeshScene {
+        MeshScene {
+            objects: vec![
+                MeshObject {
+                    name: String::from("first"),
+                    primitives: vec![square(Some(0)), square(Some(1))],
+                },
+                MeshObject {
+                    name: String::from("second"),
+                    primitives: vec![square(None)],
+                },
+            ],
+            materials: vec![
+                MeshMaterial {
+                    name: String::from("textured"),
+                    texture: Some(texture),
+                },
+                MeshMaterial {
+                    name: String::from("plain"),
+                    texture: None,
+                },
+            ],
+        }
+    }
+
+    /// The name, number of vertices, and number of triangles of each object in
+    /// an OBJ file, counting only the vertices that its faces use.
+    fn parse_obj(obj: &str) -> Vec<(String, usize, usize)> {
+        let mut positions = Vec::new();
+        let mut objects: Vec<(String, Vec<[f32; 3]>, usize)> = Vec::new();
+
+        for line in obj.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("o") => objects.push((words.next().unwrap().to_string(), Vec::new(), 0)),
+                Some("v") => {
+                    let coords: Vec<f32> = words.map(|word| word.parse().unwrap()).collect();
+                    positions.push([coords[0], coords[1], coords[2]]);
+                }
+                Some("f") => {
+                    let (_, vertices, triangles) = objects.last_mut().unwrap();
+                    for vertex in words {
+                        let index: usize = vertex.split('/').next().unwrap().parse().unwrap();
+                        vertices.push(positions[index - 1]);
+                    }
+                    *triangles += 1;
+                }
+                _ => {}
+            }
+        }
+
+        objects
+            .into_iter()
+            .map(|(name, mut vertices, triangles)| {
+                vertices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                vertices.dedup();
+                (name, vertices.len(), triangles)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn obj_round_trip() {
+        let scene = scene(PathBuf::from("stone.png"));
+
+        let mut output = Vec::new();
+        write_obj(&scene, Some("scene.mtl"), &mut output).unwrap();
+        let obj = String::from_utf8(output).unwrap();
+
+        assert!(obj.contains("mtllib scene.mtl"));
+        assert_eq!(obj.matches("usemtl ").count(), 2);
+
+        let parsed = parse_obj(&obj);
+        assert_eq!(parsed.len(), scene.objects.len());
+        for ((name, vertices, triangles), object) in parsed.into_iter().zip(scene.objects.iter()) {
+            assert_eq!(name, object.name);
+            // The squares of the first object are on top of each other, so
+            // they share their positions.
+            assert_eq!(vertices, 4);
+            assert_eq!(triangles, object.num_triangles());
+        }
+
+        // Vertices are numbered across the whole file.
+        assert!(obj.contains("f 9/9/9 11/11/11 10/10/10"));
+    }
+
+    #[test]
+    fn mtl_refers_to_textures() {
+        let scene = scene(PathBuf::from("textures/stone.png"));
+
+        let mut output = Vec::new();
+        write_mtl(&scene, &mut output).unwrap();
+        let mtl = String::from_utf8(output).unwrap();
+
+        assert!(mtl.contains("newmtl textured\nKd 1 1 1\nmap_Kd textures/stone.png"));
+        assert!(mtl.contains("newmtl plain\nKd 1 1 1\n"));
+        assert_eq!(mtl.matches("map_Kd").count(), 1);
+    }
+
+    #[test]
+    fn glb_embeds_textures() {
+        let texture =
+            std::env::temp_dir().join(format!("brine_glb_test_{}.png", std::process::id()));
+        fs::write(&texture, b"not really a png").unwrap();
+
+        let mut output = Vec::new();
+        write_glb(&scene(texture.clone()), &mut output).unwrap();
+        fs::remove_file(&texture).unwrap();
+
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(output[offset..offset + 4].try_into().unwrap());
+
+        assert_eq!(&output[0..4], b"glTF");
+        assert_eq!(u32_at(4), 2);
+        assert_eq!(u32_at(8) as usize, output.len());
+        assert_eq!(output.len() % 4, 0);
+
+        let json_length = u32_at(12) as usize;
+        assert_eq!(&output[16..20], b"JSON");
+        let gltf: serde_json::Value =
+            serde_json::from_slice(&output[20..20 + json_length]).unwrap();
+
+        let bin_start = 20 + json_length;
+        assert_eq!(&output[bin_start + 4..bin_start + 8], b"BIN\0");
+        let bin = &output[bin_start + 8..];
+
+        assert_eq!(gltf["nodes"][0]["name"], "first");
+        assert_eq!(gltf["meshes"][0]["primitives"].as_array().unwrap().len(), 2);
+        assert_eq!(gltf["meshes"][1]["primitives"][0].get("material"), None);
+        assert_eq!(gltf["accessors"].as_array().unwrap().len(), 3 * 4);
+        assert_eq!(gltf["accessors"][0]["count"], 4);
+        assert_eq!(gltf["accessors"][3]["count"], 6);
+
+        assert_eq!(gltf["images"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            gltf["materials"][0]["pbrMetallicRoughness"]["baseColorTexture"]["index"],
+            0
+        );
+        assert_eq!(
+            gltf["materials"][1]["pbrMetallicRoughness"].get("baseColorTexture"),
+            None
+        );
+
+        let image_view =
+            &gltf["bufferViews"][gltf["images"][0]["bufferView"].as_u64().unwrap() as usize];
+        let offset = image_view["byteOffset"].as_u64().unwrap() as usize;
+        let length = image_view["byteLength"].as_u64().unwrap() as usize;
+        assert_eq!(&bin[offset..offset + length], b"not really a png");
+    }
+
+    #[test]
+    fn gltf_embeds_buffer() {
+        let mut scene = scene(PathBuf::new());
+        scene.materials.clear();
+        for primitive in scene.objects[0].primitives.iter_mut() {
+            primitive.material = None;
+        }
+
+        let mut output = Vec::new();
+        write_gltf(&scene, &mut output).unwrap();
+        let gltf: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        // 3 squares of 4 * (12 + 12 + 8) bytes of vertex data, plus 6 * 4 of
+        // indices.
+        assert_eq!(gltf["buffers"][0]["byteLength"], 3 * (4 * 32 + 24));
+        assert!(gltf["buffers"][0]["uri"]
+            .as_str()
+            .unwrap()
+            .starts_with("data:application/octet-stream;base64,"));
+        assert_eq!(gltf.get("images"), None);
+    }
+
+    #[test]
+    fn format_for_path() {
+        assert_eq!(MeshFormat::for_path(Path::new("out.obj")), MeshFormat::Obj);
+        assert_eq!(
+            MeshFormat::for_path(Path::new("out.GLTF")),
+            MeshFormat::Gltf
+        );
+        assert_eq!(MeshFormat::for_path(Path::new("out.glb")), MeshFormat::Glb);
+        assert_eq!(MeshFormat::for_path(Path::new("out")), MeshFormat::Obj);
+        assert_eq!("glTF".parse::<MeshFormat>(), Ok(MeshFormat::Gltf));
+        assert!("fbx".parse::<MeshFormat>().is_err());
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
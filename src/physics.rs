@@ -0,0 +1,515 @@
+//! Walking around the world with gravity and collisions.
+//!
+//! The player is an axis-aligned box that is moved one axis at a time (Y, then
+//! X, then Z), stopping at the first block it would run into along each axis.
+//! Blocks are treated as full cubes if they have a collision box at all.
+
+use bevy::{input::mouse::MouseMotion, math::Vec3A, prelude::*};
+
+use brine_data::{BlockStateId, MinecraftData};
+use brine_proto::event::serverbound::PlayerPosition;
+use brine_voxel::{raycast::BlockLookup, AaCuboid, Axis};
+use brine_voxel_v1::chunk_builder::{component::ChunkSection, ChunkIndex};
+
+use crate::picking::BuiltChunkBlocks;
+
+/// Size of the player's collision box, in blocks.
+pub const PLAYER_WIDTH: f32 = 0.6;
+pub const PLAYER_HEIGHT: f32 = 1.8;
+
+/// Height of the camera above the player's feet, in blocks.
+pub const EYE_HEIGHT: f32 = 1.62;
+
+/// Downwards acceleration, in blocks per second squared.
+pub const GRAVITY: f32 = 32.0;
+
+/// Fastest speed the player can fall at, in blocks per second.
+pub const TERMINAL_VELOCITY: f32 = 78.4;
+
+/// Upwards speed given by a jump, in blocks per second. Enough to jump up one
+/// block.
+pub const JUMP_VELOCITY: f32 = 9.0;
+
+/// Horizontal speed when walking, in blocks per second.
+pub const WALK_SPEED: f32 = 4.317;
+
+/// Radians turned per pixel of mouse movement.
+const MOUSE_SENSITIVITY: f32 = 0.003;
+
+/// Longest step to simulate at once, in seconds, so that a slow frame doesn't
+/// move the player through the floor.
+const MAX_TIME_STEP: f32 = 0.05;
+
+/// How often to tell the server where the player is even if they haven't
+/// moved, in seconds.
+const POSITION_KEEPALIVE_INTERVAL: f64 = 1.0;
+
+/// Plugin that moves the [`Player`] with WASD, space to jump, and the mouse to
+/// look around, applying gravity and colliding with the blocks of built
+/// chunks.
+///
+/// The player's position is sent to the server with
+/// [`PlayerPosition`] events.
+///
+/// The player doesn't move while the chunk under them isn't loaded, so that
+/// they don't fall out of the world while waiting for the server.
+pub struct PlayerPhysicsPlugin;
+
+impl Plugin for PlayerPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(look.label(System::Input))
+            .add_system(walk.label(System::Input))
+            .add_system(apply_physics.label(System::Physics).after(System::Input))
+            .add_system(update_camera.after(System::Physics))
+            .add_system(send_position.after(System::Physics));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+enum System {
+    Input,
+    Physics,
+}
+
+/// Component for the entity the player sees through.
+///
+/// The entity's [`Transform`] is kept at the player's eyes.
+#[derive(Component, Debug, Default, Clone)]
+pub struct Player {
+    /// Position of the player's feet, in blocks.
+    pub position: Vec3,
+
+    /// Velocity, in blocks per second.
+    pub velocity: Vec3,
+
+    /// Rotation around the Y axis, in radians.
+    pub yaw: f32,
+
+    /// Rotation around the X axis, in radians.
+    pub pitch: f32,
+
+    /// Whether the player is standing on a block.
+    pub on_ground: bool,
+}
+
+impl Player {
+    /// Returns a player standing (or falling) at the given position.
+    pub fn at(position: Vec3) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the player's collision box.
+    pub fn bounds(&self) -> AaCuboid {
+        player_bounds(self.position)
+    }
+
+    /// Returns the rotation of the player's view.
+    pub fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0)
+    }
+}
+
+/// Returns the collision box of a player whose feet are at `position`.
+pub fn player_bounds(position: Vec3) -> AaCuboid {
+    let half_width = PLAYER_WIDTH / 2.0;
+
+    AaCuboid::new(
+        position + Vec3::new(-half_width, 0.0, -half_width),
+        position + Vec3::new(half_width, PLAYER_HEIGHT, half_width),
+    )
+}
+
+/// The result of [`move_and_collide`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Collision {
+    /// How far the box actually moved.
+    pub motion: Vec3,
+
+    /// Whether the box ran into a block along each axis.
+    pub hit: [bool; 3],
+}
+
+impl Collision {
+    /// Returns whether the box landed on top of a block.
+    pub fn landed(&self, motion: Vec3) -> bool {
+        self.hit[Axis::Y as usize] && motion.y < 0.0
+    }
+}
+
+/// Moves `bounds` by `motion`, stopping short of any solid blocks.
+///
+/// The box is moved along the Y axis first, then X, then Z. Along each axis, it
+/// moves until it touches the first solid block in its way, and any remaining
+/// motion along that axis is dropped. Blocks that the box is already inside of
+/// are ignored, so that it can always move out of them.
+pub fn move_and_collide(bounds: AaCuboid, motion: Vec3, blocks: &impl BlockLookup) -> Collision {
+    let mut bounds = bounds;
+    let mut moved = Vec3::ZERO;
+    let mut hit = [false; 3];
+
+    for axis in [Axis::Y, Axis::X, Axis::Z] {
+        let i = axis as usize;
+        if motion[i] == 0.0 {
+            continue;
+        }
+
+        let mut step = Vec3A::ZERO;
+        step[i] = motion[i];
+
+        if let Some(time) = first_impact(&bounds, step, blocks) {
+            step[i] *= time;
+            hit[i] = true;
+        }
+
+        bounds.min += step;
+        bounds.max += step;
+        moved[i] = step[i];
+    }
+
+    Collision { motion: moved, hit }
+}
+
+/// Returns the fraction of `step` that `bounds` can move before running into
+/// a solid block, if it runs into one at all.
+fn first_impact(bounds: &AaCuboid, step: Vec3A, blocks: &impl BlockLookup) -> Option<f32> {
+    // Every block the box touches on the way.
+    let swept = AaCuboid {
+        min: bounds.min.min(bounds.min + step),
+        max: bounds.max.max(bounds.max + step),
+    };
+    let min = swept.min.floor().as_ivec3();
+    let max = swept.max.ceil().as_ivec3();
+
+    // Overlaps smaller than this don't count as being inside a block, so that
+    // rounding errors don't let the box fall through the floor.
+    let inside = bounds.expand(Vec3A::splat(-1e-4));
+
+    let mut first = None;
+
+    for x in min.x..max.x {
+        for y in min.y..max.y {
+            for z in min.z..max.z {
+                let block_pos = IVec3::new(x, y, z);
+                if !blocks.is_solid(block_pos) {
+                    continue;
+                }
+
+                let block = AaCuboid::new(block_pos.as_vec3(), block_pos.as_vec3() + Vec3::ONE);
+                if inside.intersects(&block) {
+                    continue;
+                }
+
+                let (time, face) = match bounds.sweep(step, &block) {
+                    Some(impact) => impact,
+                    None => continue,
+                };
+
+                // Only faces facing against the motion can stop it. Anything
+                // else is a block that the box is barely touching from the
+                // side.
+                if AaCuboid::get_normal(face).dot(step) >= 0.0 {
+                    continue;
+                }
+
+                if first.map_or(true, |first| time < first) {
+                    first = Some(time);
+                }
+            }
+        }
+    }
+
+    first
+}
+
+/// Advances the player by `dt` seconds: applies gravity, moves them, and
+/// updates their velocity and `on_ground` based on what they ran into.
+pub fn step_player(player: &mut Player, dt: f32, blocks: &impl BlockLookup) {
+    player.velocity.y = (player.velocity.y - GRAVITY * dt).max(-TERMINAL_VELOCITY);
+
+    let motion = player.velocity * dt;
+    let collision = move_and_collide(player.bounds(), motion, blocks);
+
+    player.position += collision.motion;
+    player.on_ground = collision.landed(motion);
+
+    for (i, hit) in collision.hit.into_iter().enumerate() {
+        if hit {
+            player.velocity[i] = 0.0;
+        }
+    }
+}
+
+/// Blocks that the player collides with, in built chunks.
+///
+/// Blocks in chunks that haven't been built are solid, so that the player
+/// can't walk out of the loaded part of the world.
+struct CollidableBlocks<'a, 'w, 's> {
+    blocks: BuiltChunkBlocks<'a, 'w, 's>,
+    collides: &'a [bool],
+}
+
+impl BlockLookup for CollidableBlocks<'_, '_, '_> {
+    fn is_solid(&self, block_pos: IVec3) -> bool {
+        if block_pos.y < 0 || block_pos.y >= brine_chunk::CHUNK_HEIGHT as i32 {
+            return false;
+        }
+
+        match self.blocks.get_block(block_pos) {
+            Some(block) => self.collides.get(block.0 as usize).copied().unwrap_or(true),
+            None => true,
+        }
+    }
+}
+
+/// Returns whether each block state has a collision box, indexed by block
+/// state id.
+fn collision_table(mc_data: &MinecraftData) -> Vec<bool> {
+    let blocks = mc_data.blocks();
+
+    (0..blocks.state_id_to_block.len())
+        .map(|id| {
+            blocks
+                .get_by_state_id(BlockStateId(id as _))
+                .map_or(false, |block| !block.empty)
+        })
+        .collect()
+}
+
+fn look(mut mouse_motion: EventReader<MouseMotion>, mut players: Query<&mut Player>) {
+    let delta = mouse_motion
+        .iter()
+        .fold(Vec2::ZERO, |delta, motion| delta + motion.delta);
+    if delta == Vec2::ZERO {
+        return;
+    }
+
+    for mut player in players.iter_mut() {
+        player.yaw -= delta.x * MOUSE_SENSITIVITY;
+        player.pitch = (player.pitch - delta.y * MOUSE_SENSITIVITY)
+            .clamp(-std::f32::consts::FRAC_PI_2, std::f32::consts::FRAC_PI_2);
+    }
+}
+
+fn walk(keys: Res<Input<KeyCode>>, mut players: Query<&mut Player>) {
+    let mut input = Vec3::ZERO;
+    if keys.pressed(KeyCode::W) {
+        input.z -= 1.0;
+    }
+    if keys.pressed(KeyCode::S) {
+        input.z += 1.0;
+    }
+    if keys.pressed(KeyCode::A) {
+        input.x -= 1.0;
+    }
+    if keys.pressed(KeyCode::D) {
+        input.x += 1.0;
+    }
+
+    for mut player in players.iter_mut() {
+        let walk = Quat::from_rotation_y(player.yaw) * input.normalize_or_zero() * WALK_SPEED;
+        player.velocity.x = walk.x;
+        player.velocity.z = walk.z;
+
+        if player.on_ground && keys.pressed(KeyCode::Space) {
+            player.velocity.y = JUMP_VELOCITY;
+            player.on_ground = false;
+        }
+    }
+}
+
+fn apply_physics(
+    time: Res<Time>,
+    mc_data: Res<MinecraftData>,
+    chunk_index: Res<ChunkIndex>,
+    sections: Query<&ChunkSection>,
+    mut players: Query<&mut Player>,
+    mut collides: Local<Option<Vec<bool>>>,
+) {
+    let collides = collides.get_or_insert_with(|| collision_table(&mc_data));
+    let dt = time.delta_seconds().min(MAX_TIME_STEP);
+
+    let blocks = CollidableBlocks {
+        blocks: BuiltChunkBlocks {
+            chunk_index: &*chunk_index,
+            sections: &sections,
+        },
+        collides,
+    };
+
+    for mut player in players.iter_mut() {
+        let chunk_pos = (player.position / brine_chunk::CHUNK_WIDTH as f32).floor();
+        if !blocks
+            .blocks
+            .is_chunk_loaded(chunk_pos.x as i32, chunk_pos.z as i32)
+        {
+            continue;
+        }
+
+        step_player(&mut player, dt, &blocks);
+    }
+}
+
+fn update_camera(mut players: Query<(&Player, &mut Transform), Changed<Player>>) {
+    for (player, mut transform) in players.iter_mut() {
+        transform.translation = player.position + Vec3::Y * EYE_HEIGHT;
+        transform.rotation = player.rotation();
+    }
+}
+
+fn send_position(
+    time: Res<Time>,
+    players: Query<&Player>,
+    mut position_events: EventWriter<PlayerPosition>,
+    mut last_sent: Local<Option<(f64, PlayerPosition)>>,
+) {
+    let player = match players.iter().next() {
+        Some(player) => player,
+        None => return,
+    };
+
+    // Minecraft's yaw is clockwise from +Z, and its pitch is positive looking
+    // down.
+    let position = PlayerPosition {
+        position: player.position,
+        yaw: (-player.yaw.to_degrees() + 180.0).rem_euclid(360.0),
+        pitch: -player.pitch.to_degrees(),
+        on_ground: player.on_ground,
+    };
+
+    let now = time.seconds_since_startup();
+    let due = match &*last_sent {
+        Some((sent_at, sent)) => *sent != position || now - sent_at >= POSITION_KEEPALIVE_INTERVAL,
+        None => true,
+    };
+
+    if due {
+        position_events.send(position.clone());
+        *last_sent = Some((now, position));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use brine_chunk::{BlockState, BlockStates, Chunk, ChunkSection};
+
+    use super::*;
+
+    const STONE: BlockState = BlockState(1);
+
+    /// A chunk at (0, 0) whose bottom four layers are stone, with a single
+    /// pillar of stone in the middle.
+    fn flat_chunk() -> Chunk {
+        let mut section = ChunkSection::empty(0);
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in 0..4 {
+                    section.block_states.0[BlockStates::xyz_to_index(x, y, z)] = STONE;
+                }
+            }
+        }
+        for y in 4..8 {
+            section.block_states.0[BlockStates::xyz_to_index(8, y, 8)] = STONE;
+        }
+
+        let mut chunk = Chunk::empty(0, 0);
+        chunk.sections.push(section);
+        chunk
+    }
+
+    fn solid_in(chunk: &Chunk) -> impl BlockLookup + '_ {
+        move |block_pos: IVec3| {
+            if block_pos.x < 0 || block_pos.z < 0 || block_pos.y < 0 {
+                return false;
+            }
+            chunk
+                .get_block_at_local(block_pos.x as u8, block_pos.y as u16, block_pos.z as u8)
+                .map_or(false, |block| block != BlockState::AIR)
+        }
+    }
+
+    #[test]
+    fn lands_on_the_ground() {
+        let chunk = flat_chunk();
+        let blocks = solid_in(&chunk);
+
+        let mut player = Player::at(Vec3::new(3.5, 10.0, 3.5));
+        for _ in 0..200 {
+            step_player(&mut player, 0.02, &blocks);
+        }
+
+        assert!(player.position.abs_diff_eq(Vec3::new(3.5, 4.0, 3.5), 1e-4));
+        assert_eq!(player.velocity, Vec3::ZERO);
+        assert!(player.on_ground);
+    }
+
+    #[test]
+    fn fast_fall_does_not_tunnel() {
+        let chunk = flat_chunk();
+        let blocks = solid_in(&chunk);
+
+        let collision = move_and_collide(
+            player_bounds(Vec3::new(3.5, 10.0, 3.5)),
+            Vec3::new(0.0, -100.0, 0.0),
+            &blocks,
+        );
+
+        assert!(collision
+            .motion
+            .abs_diff_eq(Vec3::new(0.0, -6.0, 0.0), 1e-4));
+        assert_eq!(collision.hit, [false, true, false]);
+    }
+
+    #[test]
+    fn walls_stop_movement_along_one_axis() {
+        let chunk = flat_chunk();
+        let blocks = solid_in(&chunk);
+
+        // Walking diagonally into the pillar at x = 8..9 slides along it.
+        let start = Vec3::new(7.0, 4.0, 8.5);
+        let collision = move_and_collide(player_bounds(start), Vec3::new(1.0, 0.0, 0.25), &blocks);
+
+        assert!((collision.motion.x - 0.7).abs() < 1e-5);
+        assert_eq!(collision.motion.z, 0.25);
+        assert_eq!(collision.hit, [true, false, false]);
+
+        // Standing against the pillar, walking along it doesn't get stuck.
+        let against = start + collision.motion;
+        let collision =
+            move_and_collide(player_bounds(against), Vec3::new(0.0, 0.0, -1.0), &blocks);
+
+        assert_eq!(collision.motion, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(collision.hit, [false, false, false]);
+    }
+
+    #[test]
+    fn walking_on_the_ground() {
+        let chunk = flat_chunk();
+        let blocks = solid_in(&chunk);
+
+        let mut player = Player::at(Vec3::new(3.5, 4.0, 3.5));
+        player.velocity = Vec3::new(WALK_SPEED, 0.0, 0.0);
+        step_player(&mut player, 0.05, &blocks);
+
+        assert!((player.position.y - 4.0).abs() < 1e-4);
+        assert!(player.position.x > 3.5);
+        assert!(player.on_ground);
+    }
+
+    #[test]
+    fn can_move_out_of_blocks() {
+        let chunk = flat_chunk();
+        let blocks = solid_in(&chunk);
+
+        // Stuck halfway into the ground.
+        let collision = move_and_collide(
+            player_bounds(Vec3::new(3.5, 3.5, 3.5)),
+            Vec3::new(0.0, 1.0, 0.0),
+            &blocks,
+        );
+
+        assert_eq!(collision.motion, Vec3::new(0.0, 1.0, 0.0));
+    }
+}
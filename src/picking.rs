@@ -0,0 +1,205 @@
+//! Picking the block that the camera is looking at.
+
+use bevy::{
+    pbr::wireframe::Wireframe,
+    prelude::*,
+    render::camera::{Camera, CameraPlugin},
+};
+
+use brine_chunk::{BlockClassifier, BlockState, CHUNK_HEIGHT, CHUNK_WIDTH, SECTION_HEIGHT};
+use brine_data::MinecraftData;
+use brine_voxel::raycast::{raycast, BlockLookup, RayHit};
+use brine_voxel_v1::chunk_builder::{component::ChunkSection, ChunkBuilderType, ChunkIndex};
+
+/// How far away from the camera blocks can be picked, in blocks.
+pub const PICK_DISTANCE: f32 = 32.0;
+
+/// Chunk builders whose chunks are searched for blocks, in order.
+pub(crate) const BUILDERS: [ChunkBuilderType; 4] = [
+    ChunkBuilderType::VISIBLE_FACES,
+    ChunkBuilderType::GREEDY_QUADS,
+    ChunkBuilderType::NAIVE_BLOCKS,
+    ChunkBuilderType::LOD2,
+];
+
+/// Plugin that casts a ray from the 3D camera every frame to find the block
+/// it's looking at, and draws a wireframe box around that block.
+///
+/// The result is available in the [`PickedBlock`] resource. The highlight is
+/// only visible if bevy's `WireframePlugin` has been added, e.g., by
+/// [`DebugWireframePlugin`](crate::debug::DebugWireframePlugin).
+pub struct BlockPickingPlugin;
+
+impl Plugin for BlockPickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PickedBlock>()
+            .add_startup_system(spawn_highlight)
+            .add_system(pick_block.label(System::PickBlock))
+            .add_system(update_highlight.after(System::PickBlock));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+pub enum System {
+    PickBlock,
+}
+
+/// Resource that holds the block the camera is looking at, if any.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PickedBlock(pub Option<RayHit>);
+
+/// Looks up blocks in the sections of built chunks.
+pub(crate) struct BuiltChunkBlocks<'a, 'w, 's> {
+    pub chunk_index: &'a ChunkIndex,
+    pub sections: &'a Query<'w, 's, &'static ChunkSection>,
+}
+
+impl BuiltChunkBlocks<'_, '_, '_> {
+    /// Returns whether any builder has built the chunk at the given chunk
+    /// coordinates.
+    pub fn is_chunk_loaded(&self, chunk_x: i32, chunk_z: i32) -> bool {
+        BUILDERS.iter().any(|builder| {
+            self.chunk_index
+                .get_chunk(*builder, chunk_x, chunk_z)
+                .is_some()
+        })
+    }
+
+    /// Returns the block at the given world block position, or `None` if it's
+    /// outside the world or in a chunk that hasn't been built.
+    pub fn get_block(&self, block_pos: IVec3) -> Option<BlockState> {
+        if block_pos.y < 0 || block_pos.y >= CHUNK_HEIGHT as i32 {
+            return None;
+        }
+
+        let width = CHUNK_WIDTH as i32;
+        let height = SECTION_HEIGHT as i32;
+        let chunk_x = block_pos.x.div_euclid(width);
+        let chunk_z = block_pos.z.div_euclid(width);
+        let section_y = (block_pos.y / height) as u8;
+
+        if !self.is_chunk_loaded(chunk_x, chunk_z) {
+            return None;
+        }
+
+        // Sections without any blocks don't get built, so they're all air.
+        let section = BUILDERS
+            .iter()
+            .filter_map(|builder| {
+                self.chunk_index
+                    .get_section(*builder, chunk_x, section_y, chunk_z)
+            })
+            .find_map(|entity| self.sections.get(entity).ok());
+
+        let section = match section {
+            Some(section) => &section.0,
+            None => return Some(BlockState::AIR),
+        };
+
+        Some(section.block_states.get_block(
+            block_pos.x.rem_euclid(width) as u8,
+            (block_pos.y % height) as u8,
+            block_pos.z.rem_euclid(width) as u8,
+        ))
+    }
+}
+
+/// Blocks that stop the picking ray: anything other than air and fluids.
+struct PickableBlocks<'a, 'w, 's> {
+    blocks: BuiltChunkBlocks<'a, 'w, 's>,
+    classifier: &'a BlockClassifier,
+}
+
+impl BlockLookup for PickableBlocks<'_, '_, '_> {
+    fn is_solid(&self, block_pos: IVec3) -> bool {
+        match self.blocks.get_block(block_pos) {
+            Some(block) => !self.classifier.is_air(block) && !self.classifier.is_fluid(block),
+            None => false,
+        }
+    }
+}
+
+fn pick_block(
+    mc_data: Res<MinecraftData>,
+    chunk_index: Res<ChunkIndex>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    sections: Query<&ChunkSection>,
+    mut picked_block: ResMut<PickedBlock>,
+    mut classifier: Local<Option<BlockClassifier>>,
+) {
+    let classifier = classifier.get_or_insert_with(|| mc_data.blocks().classifier());
+
+    let camera = cameras
+        .iter()
+        .find(|(camera, _)| camera.name.as_deref() == Some(CameraPlugin::CAMERA_3D));
+
+    let hit = camera.and_then(|(_, transform)| {
+        let blocks = PickableBlocks {
+            blocks: BuiltChunkBlocks {
+                chunk_index: &*chunk_index,
+                sections: &sections,
+            },
+            classifier,
+        };
+
+        raycast(
+            transform.translation,
+            transform.rotation * -Vec3::Z,
+            PICK_DISTANCE,
+            &blocks,
+        )
+    });
+
+    if picked_block.0 != hit {
+        picked_block.0 = hit;
+    }
+}
+
+#[derive(Component)]
+struct BlockHighlight;
+
+fn spawn_highlight(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    // Slightly bigger than a block so that the lines aren't hidden by its
+    // faces.
+    let cube = Mesh::from(shape::Cube::new(1.005));
+
+    // Only the wireframe should be visible.
+    let material = StandardMaterial {
+        base_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(cube),
+            material: materials.add(material),
+            visibility: Visibility { is_visible: false },
+            ..Default::default()
+        })
+        .insert_bundle((Name::new("Block Highlight"), BlockHighlight, Wireframe));
+}
+
+fn update_highlight(
+    picked_block: Res<PickedBlock>,
+    mut highlight: Query<(&mut Transform, &mut Visibility), With<BlockHighlight>>,
+) {
+    if !picked_block.is_changed() {
+        return;
+    }
+
+    for (mut transform, mut visibility) in highlight.iter_mut() {
+        match picked_block.0 {
+            Some(hit) => {
+                transform.translation = hit.block_pos.as_vec3() + Vec3::splat(0.5);
+                visibility.is_visible = true;
+            }
+            None => visibility.is_visible = false,
+        }
+    }
+}
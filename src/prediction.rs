@@ -0,0 +1,619 @@
+//! Predicting block changes before the server confirms them.
+//!
+//! Waiting for the server before showing a placed block would delay every
+//! placement by a round trip. Instead, the change is made to the local chunk
+//! data right away (see [`PredictBlock`]) and remembered in
+//! [`BlockPredictions`] until the server sends a [`BlockChanged`] event for the
+//! same position. If the server's block is different, or if the server doesn't
+//! answer in time, the prediction is rolled back.
+//!
+//! A [`DiggingAcknowledged`] event also says which block the server has at its
+//! position, so it's handled just like a [`BlockChanged`] event.
+
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use brine_chunk::{BlockClassifier, BlockState, Chunk, CHUNK_HEIGHT, CHUNK_WIDTH, SECTION_HEIGHT};
+use brine_data::MinecraftData;
+use brine_proto::event::clientbound::{BlockChanged, DiggingAcknowledged};
+use brine_voxel_v1::chunk_builder::{
+    component::{BuiltChunk, ChunkSection},
+    ChunkBuilderType, ChunkIndex, RebuildChunk,
+};
+
+use crate::picking::BUILDERS;
+
+/// How long a prediction waits for the server by default.
+pub const DEFAULT_PREDICTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Plugin that applies [`BlockChanged`] and [`DiggingAcknowledged`] events from
+/// the server and [`PredictBlock`] events from the client to the blocks of
+/// built chunks, and rebuilds the chunks that changed.
+///
+/// Requires the [`MinecraftData`] resource, and at least one
+/// [`ChunkBuilderPlugin`][brine_voxel_v1::chunk_builder::ChunkBuilderPlugin].
+pub struct BlockPredictionPlugin;
+
+impl Plugin for BlockPredictionPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<BlockPredictions>() {
+            app.init_resource::<BlockPredictions>();
+        }
+
+        app.add_event::<PredictBlock>()
+            .add_system(update_blocks.label(System::UpdateBlocks));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+pub enum System {
+    UpdateBlocks,
+}
+
+/// Event that predicts that a block is about to change, e.g., because the
+/// player placed a block there.
+///
+/// The block is changed immediately. Predictions of blocks in chunks that
+/// aren't loaded are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictBlock {
+    /// World position of the block.
+    pub block_pos: IVec3,
+
+    /// The block's predicted state.
+    pub block_state: BlockState,
+}
+
+/// What the server said about a prediction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredictionOutcome {
+    /// The server's block is the predicted one.
+    Confirmed,
+
+    /// The server's block is a different one.
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Prediction {
+    predicted: BlockState,
+
+    /// The block before the first prediction, which is restored if the
+    /// prediction times out.
+    previous: BlockState,
+
+    /// Seconds since startup after which the prediction times out.
+    expires_at: f64,
+}
+
+/// Resource that holds the block predictions that the server hasn't answered
+/// yet, by position.
+///
+/// Insert it before adding the [`BlockPredictionPlugin`] to change the
+/// timeout.
+#[derive(Debug)]
+pub struct BlockPredictions {
+    pending: HashMap<IVec3, Prediction>,
+    timeout: Duration,
+}
+
+impl Default for BlockPredictions {
+    fn default() -> Self {
+        Self::with_timeout(DEFAULT_PREDICTION_TIMEOUT)
+    }
+}
+
+impl BlockPredictions {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            pending: HashMap::new(),
+            timeout,
+        }
+    }
+
+    /// Returns the number of predictions waiting for the server.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the predicted block at the given position, if the server hasn't
+    /// answered that prediction yet.
+    pub fn get(&self, block_pos: IVec3) -> Option<BlockState> {
+        self.pending
+            .get(&block_pos)
+            .map(|prediction| prediction.predicted)
+    }
+
+    /// Remembers that the block at `block_pos` was changed from `previous` to
+    /// `predicted` at `now` (in seconds since startup).
+    ///
+    /// If the block was already predicted, the new prediction replaces it, but
+    /// the block from before the first prediction is kept for rolling back.
+    pub fn predict(
+        &mut self,
+        block_pos: IVec3,
+        predicted: BlockState,
+        previous: BlockState,
+        now: f64,
+    ) {
+        let expires_at = now + self.timeout.as_secs_f64();
+        let previous = self
+            .pending
+            .get(&block_pos)
+            .map_or(previous, |prediction| prediction.previous);
+
+        self.pending.insert(
+            block_pos,
+            Prediction {
+                predicted,
+                previous,
+                expires_at,
+            },
+        );
+    }
+
+    /// Forgets the prediction at `block_pos`, now that the server has said
+    /// that the block there is `server_block`.
+    ///
+    /// Returns `None` if there was no prediction at that position.
+    pub fn resolve(
+        &mut self,
+        block_pos: IVec3,
+        server_block: BlockState,
+    ) -> Option<PredictionOutcome> {
+        let prediction = self.pending.remove(&block_pos)?;
+
+        if prediction.predicted == server_block {
+            Some(PredictionOutcome::Confirmed)
+        } else {
+            Some(PredictionOutcome::Rejected)
+        }
+    }
+
+    /// Forgets the predictions that have timed out by `now` (in seconds since
+    /// startup), and returns the position of each with the block to roll back
+    /// to.
+    pub fn expire(&mut self, now: f64) -> Vec<(IVec3, BlockState)> {
+        let expired: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, prediction)| now > prediction.expires_at)
+            .map(|(block_pos, prediction)| (*block_pos, prediction.previous))
+            .collect();
+
+        for (block_pos, _) in expired.iter() {
+            self.pending.remove(block_pos);
+        }
+
+        expired
+    }
+}
+
+/// Changes blocks in the sections of built chunks.
+///
+/// Each change is made both to the chunk's [`ChunkSection`] components, so
+/// that the new block can be picked and collided with right away, and to a
+/// copy of the chunk's data that is rebuilt by
+/// [`rebuild_changed`][Self::rebuild_changed].
+#[derive(SystemParam)]
+struct BuiltChunkBlocksMut<'w, 's> {
+    chunk_index: Res<'w, ChunkIndex>,
+    chunks: Query<'w, 's, &'static Children, With<BuiltChunk>>,
+    sections: Query<'w, 's, &'static mut ChunkSection>,
+    rebuild_events: EventWriter<'w, 's, RebuildChunk>,
+    changed: Local<'s, HashMap<(ChunkBuilderType, i32, i32), (Entity, Chunk)>>,
+}
+
+impl BuiltChunkBlocksMut<'_, '_> {
+    /// Sets the block at the given world block position in the chunk of every
+    /// builder that has built it, and returns the block that was there.
+    ///
+    /// Returns `None` if the position is outside the world or in a chunk that
+    /// hasn't been built.
+    fn set_block(
+        &mut self,
+        block_pos: IVec3,
+        block: BlockState,
+        classifier: &BlockClassifier,
+    ) -> Option<BlockState> {
+        if block_pos.y < 0 || block_pos.y >= CHUNK_HEIGHT as i32 {
+            return None;
+        }
+
+        let width = CHUNK_WIDTH as i32;
+        let chunk_x = block_pos.x.div_euclid(width);
+        let chunk_z = block_pos.z.div_euclid(width);
+        let x = block_pos.x.rem_euclid(width) as u8;
+        let y = block_pos.y as u16;
+        let z = block_pos.z.rem_euclid(width) as u8;
+        let section_y = (y as usize / SECTION_HEIGHT) as u8;
+        let y_in_section = (y as usize % SECTION_HEIGHT) as u8;
+
+        let mut previous = None;
+
+        for builder in BUILDERS {
+            let entity = match self.chunk_index.get_chunk(builder, chunk_x, chunk_z) {
+                Some(entity) => entity,
+                None => continue,
+            };
+
+            let section = self
+                .chunk_index
+                .get_section(builder, chunk_x, section_y, chunk_z);
+
+            // Sections without any blocks don't get built, so they're all air.
+            let current = section
+                .and_then(|section| self.sections.get(section).ok())
+                .map_or(BlockState::AIR, |section| {
+                    section.0.block_states.get_block(x, y_in_section, z)
+                });
+            previous = previous.or(Some(current));
+
+            if current == block {
+                continue;
+            }
+
+            let (chunks, sections) = (&self.chunks, &self.sections);
+            let (_, chunk) = self
+                .changed
+                .entry((builder, chunk_x, chunk_z))
+                .or_insert_with(|| {
+                    (
+                        entity,
+                        chunk_data(entity, chunk_x, chunk_z, chunks, sections),
+                    )
+                });
+            chunk.set_block_at_local(x, y, z, block, classifier);
+
+            if let Some(mut section) =
+                section.and_then(|section| self.sections.get_mut(section).ok())
+            {
+                section.0.set_block(x, y_in_section, z, block, classifier);
+            }
+        }
+
+        previous
+    }
+
+    /// Rebuilds every chunk whose blocks were changed since the last call.
+    fn rebuild_changed(&mut self) {
+        for ((builder, _, _), (entity, chunk)) in self.changed.drain() {
+            debug!(
+                "Rebuilding chunk ({}, {}) with changed blocks",
+                chunk.chunk_x, chunk.chunk_z
+            );
+
+            self.rebuild_events.send(RebuildChunk {
+                builder,
+                chunk,
+                replaces: Some(entity),
+            });
+        }
+    }
+}
+
+/// Collects the data of a built chunk from its sections.
+fn chunk_data(
+    entity: Entity,
+    chunk_x: i32,
+    chunk_z: i32,
+    chunks: &Query<&Children, With<BuiltChunk>>,
+    sections: &Query<&mut ChunkSection>,
+) -> Chunk {
+    let mut chunk = Chunk::empty(chunk_x, chunk_z);
+    chunk.sections = chunks
+        .get(entity)
+        .iter()
+        .flat_map(|children| children.iter())
+        .filter_map(|child| sections.get(*child).ok())
+        .map(|section| section.0.clone())
+        .collect();
+    chunk.sections.sort_by_key(|section| section.chunk_y);
+    chunk
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_blocks(
+    time: Res<Time>,
+    mc_data: Res<MinecraftData>,
+    mut predictions: ResMut<BlockPredictions>,
+    mut predict_events: EventReader<PredictBlock>,
+    mut block_events: EventReader<BlockChanged>,
+    mut digging_events: EventReader<DiggingAcknowledged>,
+    mut blocks: BuiltChunkBlocksMut,
+    mut classifier: Local<Option<BlockClassifier>>,
+) {
+    let classifier = classifier.get_or_insert_with(|| mc_data.blocks().classifier());
+    let now = time.seconds_since_startup();
+
+    for event in predict_events.iter() {
+        match blocks.set_block(event.block_pos, event.block_state, classifier) {
+            Some(previous) => {
+                predictions.predict(event.block_pos, event.block_state, previous, now)
+            }
+            None => debug!("Ignoring prediction in unloaded chunk: {:?}", event),
+        }
+    }
+
+    let server_blocks = block_events
+        .iter()
+        .map(|event| (event.block_pos, event.block_state))
+        .chain(
+            digging_events
+                .iter()
+                .map(|event| (event.block_pos, event.block_state)),
+        );
+
+    for (block_pos, block_state) in server_blocks {
+        match predictions.resolve(block_pos, block_state) {
+            // The predicted block is already there.
+            Some(PredictionOutcome::Confirmed) => continue,
+            Some(PredictionOutcome::Rejected) => {
+                debug!(
+                    "Server rejected predicted block at {}: {:?}",
+                    block_pos, block_state
+                )
+            }
+            None => {}
+        }
+
+        blocks.set_block(block_pos, block_state, classifier);
+    }
+
+    for (block_pos, previous) in predictions.expire(now) {
+        debug!("Prediction at {} timed out, rolling back", block_pos);
+        blocks.set_block(block_pos, previous, classifier);
+    }
+
+    blocks.rebuild_changed();
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{core::CorePlugin, ecs::event::ManualEventReader};
+
+    use brine_proto::event::serverbound::DiggingStatus;
+    use brine_voxel_v1::chunk_builder::component::BuiltChunkBundle;
+
+    use super::*;
+
+    const STONE: BlockState = BlockState(1);
+    const DIRT: BlockState = BlockState(10);
+
+    /// The block that is predicted in every test, in section 0 of chunk
+    /// (-1, 0).
+    const BLOCK_POS: IVec3 = IVec3::new(-3, 4, 5);
+
+    struct TestApp {
+        app: App,
+        section: Entity,
+        rebuilds: ManualEventReader<RebuildChunk>,
+    }
+
+    impl TestApp {
+        /// An app with a single built chunk at (-1, 0), whose bottom layer is
+        /// stone.
+        fn new() -> Self {
+            let mut app = App::new();
+            app.add_plugin(CorePlugin)
+                .insert_resource(MinecraftData::for_version("1.14.4"))
+                .init_resource::<ChunkIndex>()
+                .add_event::<BlockChanged>()
+                .add_event::<DiggingAcknowledged>()
+                .add_event::<RebuildChunk>()
+                .add_plugin(BlockPredictionPlugin);
+
+            let classifier = BlockClassifier::new([BlockState::AIR], []);
+            let mut section = brine_chunk::ChunkSection::empty(0);
+            for x in 0..16 {
+                for z in 0..16 {
+                    section.set_block(x, 0, z, STONE, &classifier);
+                }
+            }
+
+            let section = app.world.spawn().insert(ChunkSection(section)).id();
+            let chunk = app
+                .world
+                .spawn()
+                .insert_bundle(BuiltChunkBundle::new(
+                    ChunkBuilderType::VISIBLE_FACES,
+                    -1,
+                    0,
+                ))
+                .push_children(&[section])
+                .id();
+            app.world.get_resource_mut::<ChunkIndex>().unwrap().insert(
+                ChunkBuilderType::VISIBLE_FACES,
+                -1,
+                0,
+                chunk,
+                [(0, section)],
+            );
+
+            let rebuilds = app
+                .world
+                .get_resource::<Events<RebuildChunk>>()
+                .unwrap()
+                .get_reader();
+
+            Self {
+                app,
+                section,
+                rebuilds,
+            }
+        }
+
+        fn send<T: Send + Sync + 'static>(&mut self, event: T) {
+            self.app
+                .world
+                .get_resource_mut::<Events<T>>()
+                .unwrap()
+                .send(event);
+            self.app.update();
+        }
+
+        fn block(&self) -> BlockState {
+            self.app
+                .world
+                .get::<ChunkSection>(self.section)
+                .unwrap()
+                .0
+                .block_states
+                .get_block(13, 4, 5)
+        }
+
+        fn predictions(&self) -> &BlockPredictions {
+            self.app.world.get_resource::<BlockPredictions>().unwrap()
+        }
+
+        /// Returns the block at [`BLOCK_POS`] in each chunk that has been
+        /// rebuilt since the last call.
+        fn rebuilt_blocks(&mut self) -> Vec<BlockState> {
+            let events = self
+                .app
+                .world
+                .get_resource::<Events<RebuildChunk>>()
+                .unwrap();
+
+            self.rebuilds
+                .iter(events)
+                .map(|rebuild| {
+                    assert_eq!((rebuild.chunk.chunk_x, rebuild.chunk.chunk_z), (-1, 0));
+                    rebuild.chunk.get_block_at_local(13, 4, 5).unwrap()
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn prediction_is_applied_immediately() {
+        let mut app = TestApp::new();
+
+        app.send(PredictBlock {
+            block_pos: BLOCK_POS,
+            block_state: DIRT,
+        });
+
+        assert_eq!(app.block(), DIRT);
+        assert_eq!(app.rebuilt_blocks(), [DIRT]);
+        assert_eq!(app.predictions().get(BLOCK_POS), Some(DIRT));
+    }
+
+    #[test]
+    fn server_confirms_prediction() {
+        let mut app = TestApp::new();
+        app.send(PredictBlock {
+            block_pos: BLOCK_POS,
+            block_state: DIRT,
+        });
+        app.rebuilt_blocks();
+
+        app.send(BlockChanged {
+            block_pos: BLOCK_POS,
+            block_state: DIRT,
+        });
+
+        assert_eq!(app.block(), DIRT);
+        assert!(app.predictions().is_empty());
+
+        // Nothing changed, so nothing needs to be rebuilt.
+        assert!(app.rebuilt_blocks().is_empty());
+    }
+
+    #[test]
+    fn server_rejects_prediction() {
+        let mut app = TestApp::new();
+        app.send(PredictBlock {
+            block_pos: BLOCK_POS,
+            block_state: DIRT,
+        });
+        app.rebuilt_blocks();
+
+        app.send(BlockChanged {
+            block_pos: BLOCK_POS,
+            block_state: BlockState::AIR,
+        });
+
+        assert_eq!(app.block(), BlockState::AIR);
+        assert!(app.predictions().is_empty());
+        assert_eq!(app.rebuilt_blocks(), [BlockState::AIR]);
+    }
+
+    #[test]
+    fn unpredicted_changes_are_applied() {
+        let mut app = TestApp::new();
+
+        app.send(BlockChanged {
+            block_pos: BLOCK_POS,
+            block_state: STONE,
+        });
+
+        assert_eq!(app.block(), STONE);
+        assert_eq!(app.rebuilt_blocks(), [STONE]);
+    }
+
+    #[test]
+    fn acknowledged_digging_is_applied() {
+        let mut app = TestApp::new();
+        let block_pos = BLOCK_POS - IVec3::new(0, 4, 0);
+
+        app.send(DiggingAcknowledged {
+            block_pos,
+            block_state: BlockState::AIR,
+            status: DiggingStatus::Finished,
+            successful: true,
+        });
+
+        let section = app.app.world.get::<ChunkSection>(app.section).unwrap();
+        assert_eq!(section.0.block_states.get_block(13, 0, 5), BlockState::AIR);
+        assert_eq!(app.rebuilt_blocks().len(), 1);
+    }
+
+    #[test]
+    fn predictions_in_unloaded_chunks_are_ignored() {
+        let mut app = TestApp::new();
+
+        app.send(PredictBlock {
+            block_pos: IVec3::new(3, 4, 5),
+            block_state: DIRT,
+        });
+
+        assert!(app.predictions().is_empty());
+        assert!(app.rebuilt_blocks().is_empty());
+    }
+
+    #[test]
+    fn predictions_time_out() {
+        let mut predictions = BlockPredictions::with_timeout(Duration::from_secs(1));
+        let other_pos = BLOCK_POS + IVec3::Y;
+
+        predictions.predict(BLOCK_POS, DIRT, BlockState::AIR, 10.0);
+        predictions.predict(other_pos, DIRT, BlockState::AIR, 10.5);
+
+        assert!(predictions.expire(11.0).is_empty());
+        assert_eq!(predictions.expire(11.25), [(BLOCK_POS, BlockState::AIR)]);
+        assert_eq!(predictions.len(), 1);
+        assert_eq!(predictions.resolve(BLOCK_POS, DIRT), None);
+        assert_eq!(
+            predictions.resolve(other_pos, DIRT),
+            Some(PredictionOutcome::Confirmed)
+        );
+    }
+
+    #[test]
+    fn repeated_predictions_roll_back_to_the_first_block() {
+        let mut predictions = BlockPredictions::with_timeout(Duration::from_secs(1));
+
+        predictions.predict(BLOCK_POS, DIRT, BlockState::AIR, 10.0);
+        predictions.predict(BLOCK_POS, STONE, DIRT, 10.5);
+
+        assert_eq!(predictions.get(BLOCK_POS), Some(STONE));
+        assert!(predictions.expire(11.25).is_empty());
+        assert_eq!(predictions.expire(12.0), [(BLOCK_POS, BlockState::AIR)]);
+    }
+}
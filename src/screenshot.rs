@@ -0,0 +1,319 @@
+//! Capturing rendered frames to images, e.g., for comparing the output of the
+//! renderer between runs.
+//!
+//! The window's swap chain texture can't be copied from, so for the frame that
+//! is captured, cameras on the primary window render into an offscreen texture
+//! instead. That texture is copied into a buffer after the main pass and read
+//! back once the frame has been submitted. The window shows nothing for that
+//! frame.
+
+use std::{
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    core_pipeline::node::MAIN_PASS_DRIVER,
+    prelude::*,
+    render::{
+        camera::ExtractedCamera,
+        render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext},
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageCopyTexture,
+            ImageDataLayout, MapMode, Origin3d, Texture, TextureAspect, TextureDescriptor,
+            TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::{ExtractedWindows, ViewTarget},
+        RenderApp, RenderStage,
+    },
+    window::WindowId,
+};
+use image::RgbaImage;
+
+/// Name of the render graph node that copies captured frames into a buffer.
+pub const FRAME_CAPTURE_NODE: &str = "frame_capture";
+
+/// Rows of a texture copied into a buffer must start at a multiple of this
+/// many bytes.
+const COPY_BYTES_PER_ROW_ALIGNMENT: u32 = 256;
+
+/// Plugin that captures a frame of the primary window whenever one is
+/// requested through the [`FrameCapture`] resource.
+///
+/// Must be added after the `DefaultPlugins`.
+pub struct FrameCapturePlugin;
+
+impl Plugin for FrameCapturePlugin {
+    fn build(&self, app: &mut App) {
+        let frame_capture = FrameCapture::default();
+        app.insert_resource(frame_capture.clone());
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .insert_resource(frame_capture)
+            .init_resource::<CaptureTarget>()
+            .add_system_to_stage(RenderStage::Queue, prepare_capture_target)
+            .add_system_to_stage(RenderStage::Cleanup, read_back_capture);
+
+        let mut graph = render_app.world.get_resource_mut::<RenderGraph>().unwrap();
+        graph.add_node(FRAME_CAPTURE_NODE, FrameCaptureNode);
+        graph
+            .add_node_edge(MAIN_PASS_DRIVER, FRAME_CAPTURE_NODE)
+            .unwrap();
+    }
+}
+
+/// Resource for requesting a frame capture and collecting the captured image.
+///
+/// The same state is shared by the main world and the render world.
+#[derive(Debug, Default, Clone)]
+pub struct FrameCapture {
+    state: Arc<Mutex<CaptureState>>,
+}
+
+#[derive(Debug)]
+enum CaptureState {
+    Idle,
+    Requested,
+    Captured(RgbaImage),
+}
+
+impl Default for CaptureState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+impl FrameCapture {
+    /// Captures the next frame that is rendered.
+    pub fn request(&self) {
+        *self.state.lock().unwrap() = CaptureState::Requested;
+    }
+
+    /// Returns whether a frame has been requested but not captured yet.
+    pub fn is_pending(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), CaptureState::Requested)
+    }
+
+    /// Takes the captured frame, if there is one.
+    pub fn take(&self) -> Option<RgbaImage> {
+        let mut state = self.state.lock().unwrap();
+        match std::mem::take(&mut *state) {
+            CaptureState::Captured(image) => Some(image),
+            other => {
+                *state = other;
+                None
+            }
+        }
+    }
+}
+
+/// Render world resource holding the texture that is being rendered into for
+/// a capture, and the buffer it is copied into.
+#[derive(Default)]
+struct CaptureTarget(Option<CaptureTextures>);
+
+struct CaptureTextures {
+    texture: Texture,
+    buffer: Buffer,
+    width: u32,
+    height: u32,
+}
+
+impl CaptureTextures {
+    fn new(render_device: &RenderDevice, width: u32, height: u32) -> Self {
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("frame_capture_texture"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            // Pipelines are specialized for the window's format, so the
+            // texture has to match it.
+            format: TextureFormat::bevy_default(),
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        });
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("frame_capture_buffer"),
+            size: (padded_bytes_per_row(width) * height) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            texture,
+            buffer,
+            width,
+            height,
+        }
+    }
+}
+
+/// Returns the length of each row of a captured frame in the buffer that it's
+/// copied into.
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let bytes_per_row = width * 4;
+    let alignment = COPY_BYTES_PER_ROW_ALIGNMENT;
+    (bytes_per_row + alignment - 1) / alignment * alignment
+}
+
+/// Converts a BGRA frame with padded rows into an image.
+fn frame_to_image(data: &[u8], width: u32, height: u32) -> RgbaImage {
+    let padded_bytes_per_row = padded_bytes_per_row(width) as usize;
+    let bytes_per_row = width as usize * 4;
+
+    let mut pixels = Vec::with_capacity(bytes_per_row * height as usize);
+    for row in data.chunks(padded_bytes_per_row).take(height as usize) {
+        for bgra in row[..bytes_per_row].chunks_exact(4) {
+            pixels.extend_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+        }
+    }
+
+    RgbaImage::from_raw(width, height, pixels).unwrap()
+}
+
+/// Points the cameras of the primary window at the capture texture, if a
+/// capture was requested.
+fn prepare_capture_target(
+    frame_capture: Res<FrameCapture>,
+    windows: Res<ExtractedWindows>,
+    render_device: Res<RenderDevice>,
+    mut capture_target: ResMut<CaptureTarget>,
+    mut views: Query<(&ExtractedCamera, &mut ViewTarget)>,
+) {
+    if !frame_capture.is_pending() {
+        return;
+    }
+
+    let window = match windows.get(&WindowId::primary()) {
+        Some(window) => window,
+        None => return,
+    };
+
+    let textures = CaptureTextures::new(
+        &render_device,
+        window.physical_width,
+        window.physical_height,
+    );
+    let view = textures
+        .texture
+        .create_view(&TextureViewDescriptor::default());
+
+    for (camera, mut target) in views.iter_mut() {
+        if camera.window_id == WindowId::primary() {
+            target.view = view.clone();
+        }
+    }
+
+    capture_target.0 = Some(textures);
+}
+
+/// Render graph node that copies the capture texture into its buffer after the
+/// main pass.
+struct FrameCaptureNode;
+
+impl Node for FrameCaptureNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let textures = match world.get_resource::<CaptureTarget>() {
+            Some(CaptureTarget(Some(textures))) => textures,
+            _ => return Ok(()),
+        };
+
+        render_context.command_encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &textures.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &textures.buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_bytes_per_row(textures.width)),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: textures.width,
+                height: textures.height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Ok(())
+    }
+}
+
+/// Reads back the frame that was copied into the capture buffer this frame.
+fn read_back_capture(
+    frame_capture: Res<FrameCapture>,
+    render_device: Res<RenderDevice>,
+    mut capture_target: ResMut<CaptureTarget>,
+) {
+    let textures = match capture_target.0.take() {
+        Some(textures) => textures,
+        None => return,
+    };
+
+    let slice = textures.buffer.slice(..);
+    render_device.map_buffer(&slice, MapMode::Read);
+    let image = frame_to_image(&slice.get_mapped_range(), textures.width, textures.height);
+    textures.buffer.unmap();
+
+    *frame_capture.state.lock().unwrap() = CaptureState::Captured(image);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rows_are_aligned() {
+        assert_eq!(padded_bytes_per_row(1), 256);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+    }
+
+    #[test]
+    fn frame_to_image_strips_padding() {
+        let (width, height) = (2, 2);
+        let mut data = vec![0; padded_bytes_per_row(width) as usize * height as usize];
+        data[..4].copy_from_slice(&[1, 2, 3, 4]);
+        data[256 + 4..256 + 8].copy_from_slice(&[5, 6, 7, 8]);
+
+        let image = frame_to_image(&data, width, height);
+
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [3, 2, 1, 4]);
+        assert_eq!(image.get_pixel(1, 1).0, [7, 6, 5, 8]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn take_only_returns_captured_frames() {
+        let frame_capture = FrameCapture::default();
+        assert!(frame_capture.take().is_none());
+
+        frame_capture.request();
+        assert!(frame_capture.take().is_none());
+        assert!(frame_capture.is_pending());
+
+        *frame_capture.state.lock().unwrap() = CaptureState::Captured(RgbaImage::new(1, 1));
+        assert!(!frame_capture.is_pending());
+        assert!(frame_capture.take().is_some());
+        assert!(frame_capture.take().is_none());
+    }
+}
@@ -1,7 +1,9 @@
 use std::{
     any::Any,
-    fs,
+    collections::VecDeque,
+    fs, io,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use bevy::{
@@ -10,23 +12,40 @@ use bevy::{
 };
 
 use brine_chunk::Chunk;
+use brine_data::MinecraftData;
 use brine_proto::event::clientbound::ChunkData;
 use futures_lite::future;
+use serde::Deserialize;
 
 use crate::{
-    chunk::{load_chunk, Result},
+    chunk::{is_chunk_file, is_region_file, load_chunk, load_region, Result},
     error::{exit_on_error, log_error},
 };
 
 /// A plugin that acts as a phony server, sending ChunkData events containing
 /// data read from a directory of chunk data files.
+///
+/// By default, every chunk file in the directory is sent as soon as it is
+/// loaded, in no particular order. With a [`ChunkManifest`] (see
+/// [`with_manifest`][Self::with_manifest]), only the files listed in the
+/// manifest are sent, in the order and at the times that it gives.
 pub struct ServeChunksFromDirectoryPlugin<P> {
     path: P,
+    manifest: Option<PathBuf>,
 }
 
 impl<P> ServeChunksFromDirectoryPlugin<P> {
     pub fn new(path: P) -> Self {
-        Self { path }
+        Self {
+            path,
+            manifest: None,
+        }
+    }
+
+    /// Serve chunks as described by the [`ChunkManifest`] at `manifest`.
+    pub fn with_manifest(mut self, manifest: impl Into<PathBuf>) -> Self {
+        self.manifest = Some(manifest.into());
+        self
     }
 }
 
@@ -36,15 +55,65 @@ where
 {
     fn build(&self, app: &mut App) {
         let path = PathBuf::from(self.path.as_ref());
-        app.insert_resource(ChunkDirectory { path });
-        app.add_startup_system(load_chunks.chain(exit_on_error));
-        app.add_system(send_chunks.chain(log_error));
+        app.insert_resource(ChunkDirectory {
+            path,
+            manifest: self.manifest.clone(),
+        });
+
+        if self.manifest.is_some() {
+            app.add_startup_system(load_manifest_chunks.chain(exit_on_error));
+            app.add_system(send_manifest_chunks.chain(log_error));
+        } else {
+            app.add_startup_system(load_chunks.chain(exit_on_error));
+            app.add_system(send_chunks.chain(log_error));
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct ChunkDirectory {
     path: PathBuf,
+    manifest: Option<PathBuf>,
+}
+
+/// A JSON file that lists the chunk files to serve with
+/// [`ServeChunksFromDirectoryPlugin`], e.g.:
+///
+/// ```json
+/// {
+///     "chunks": [
+///         { "file": "chunk_0_0.chunk", "chunk_x": 0, "chunk_z": 0 },
+///         { "file": "chunk_0_0.chunk", "chunk_x": 1, "chunk_z": 0, "delay_ms": 500 }
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChunkManifest {
+    /// The chunks to send, in order.
+    pub chunks: Vec<ChunkManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChunkManifestEntry {
+    /// Path of the chunk file, relative to the chunk directory.
+    pub file: PathBuf,
+
+    /// Coordinates to send the chunk at, regardless of the coordinates saved
+    /// in the file.
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+
+    /// How long to wait after sending the previous chunk (or after startup,
+    /// for the first chunk) before sending this one.
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+impl ChunkManifest {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let file = fs::File::open(path)?;
+        Ok(serde_json::from_reader(io::BufReader::new(file))?)
+    }
 }
 
 type LoadChunkTask = Task<Result<Chunk>>;
@@ -56,14 +125,12 @@ fn load_chunks(
 ) -> Result<()> {
     for entry in fs::read_dir(&chunk_directory.path)? {
         let entry = entry?;
+        let path = entry.path();
 
-        let path_string = entry.file_name().to_string_lossy().to_string();
-
-        if path_string.starts_with("chunk_light_") || !path_string.ends_with(".dump") {
+        if !is_chunk_file(&path) {
             continue;
         }
 
-        let path = entry.path();
         let task: LoadChunkTask = task_pool.spawn(async move { load_chunk(path) });
 
         commands.spawn().insert_bundle((
@@ -91,3 +158,258 @@ fn send_chunks(
 
     Ok(())
 }
+
+/// Resource holding the chunks of a [`ChunkManifest`] that have yet to be
+/// sent, in order.
+struct ManifestChunks {
+    pending: VecDeque<(ChunkManifestEntry, LoadChunkTask)>,
+
+    /// Time since startup at which the last chunk was sent.
+    last_sent: Duration,
+}
+
+fn load_manifest_chunks(
+    chunk_directory: Res<ChunkDirectory>,
+    task_pool: Res<IoTaskPool>,
+    mut commands: Commands,
+) -> Result<()> {
+    let manifest_path = chunk_directory.manifest.as_ref().unwrap();
+    let manifest = ChunkManifest::load(manifest_path)?;
+
+    info!(
+        "Serving {} chunks from {}",
+        manifest.chunks.len(),
+        manifest_path.to_string_lossy()
+    );
+
+    let pending = manifest
+        .chunks
+        .into_iter()
+        .map(|entry| {
+            let path = chunk_directory.path.join(&entry.file);
+            let task: LoadChunkTask = task_pool.spawn(async move { load_chunk(path) });
+            (entry, task)
+        })
+        .collect();
+
+    commands.insert_resource(ManifestChunks {
+        pending,
+        last_sent: Duration::ZERO,
+    });
+
+    Ok(())
+}
+
+fn send_manifest_chunks(
+    time: Res<Time>,
+    mut manifest_chunks: ResMut<ManifestChunks>,
+    mut chunk_events: EventWriter<ChunkData>,
+) -> Result<()> {
+    let now = time.time_since_startup();
+    let manifest_chunks = &mut *manifest_chunks;
+
+    while let Some((entry, task)) = manifest_chunks.pending.front_mut() {
+        let send_at = manifest_chunks.last_sent + Duration::from_millis(entry.delay_ms);
+        if now < send_at {
+            break;
+        }
+
+        let chunk = match future::block_on(future::poll_once(task)) {
+            Some(chunk) => chunk,
+            None => break,
+        };
+
+        let (entry, _) = manifest_chunks.pending.pop_front().unwrap();
+        manifest_chunks.last_sent = now;
+
+        let mut chunk_data = chunk?;
+        chunk_data.chunk_x = entry.chunk_x;
+        chunk_data.chunk_z = entry.chunk_z;
+
+        debug!(
+            "Sending {} as chunk ({}, {})",
+            entry.file.to_string_lossy(),
+            entry.chunk_x,
+            entry.chunk_z
+        );
+
+        chunk_events.send(ChunkData { chunk_data });
+    }
+
+    Ok(())
+}
+
+/// A plugin that acts as a phony server, sending ChunkData events containing
+/// the chunks of a vanilla Minecraft world.
+///
+/// The path may point to either the world's save directory or the `region/`
+/// directory inside it. Every region file in that directory is loaded.
+///
+/// Block states are mapped to the [`MinecraftData`] resource's version by
+/// name, so that resource must exist.
+pub struct ServeChunksFromWorldPlugin<P> {
+    path: P,
+}
+
+impl<P> ServeChunksFromWorldPlugin<P> {
+    pub fn new(path: P) -> Self {
+        Self { path }
+    }
+}
+
+impl<P> Plugin for ServeChunksFromWorldPlugin<P>
+where
+    P: AsRef<Path> + Any + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        let mut path = PathBuf::from(self.path.as_ref());
+        if path.join("region").is_dir() {
+            path.push("region");
+        }
+        app.insert_resource(RegionDirectory { path });
+        app.add_startup_system(load_regions.chain(exit_on_error));
+        app.add_system(send_region_chunks.chain(log_error));
+    }
+}
+
+#[derive(Debug)]
+pub struct RegionDirectory {
+    path: PathBuf,
+}
+
+type LoadRegionTask = Task<Result<Vec<Chunk>>>;
+
+fn load_regions(
+    region_directory: Res<RegionDirectory>,
+    mc_data: Res<MinecraftData>,
+    task_pool: Res<IoTaskPool>,
+    mut commands: Commands,
+) -> Result<()> {
+    for entry in fs::read_dir(&region_directory.path)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if !is_region_file(&path) {
+            continue;
+        }
+
+        let mc_data = mc_data.clone();
+        let task: LoadRegionTask =
+            task_pool.spawn(async move { load_region(path, mc_data.blocks()) });
+
+        commands.spawn().insert_bundle((
+            task,
+            Name::new(format!("Loading Region {}", entry.path().to_string_lossy())),
+        ));
+    }
+
+    Ok(())
+}
+
+fn send_region_chunks(
+    mut tasks: Query<(Entity, &mut LoadRegionTask)>,
+    mut chunk_events: EventWriter<ChunkData>,
+    mut commands: Commands,
+) -> Result<()> {
+    for (task_entity, mut task) in tasks.iter_mut() {
+        if let Some(chunks) = future::block_on(future::poll_once(&mut *task)) {
+            commands.entity(task_entity).despawn();
+
+            for chunk_data in chunks? {
+                chunk_events.send(ChunkData { chunk_data });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{app::Events, ecs::event::ManualEventReader, tasks::TaskPool};
+    use brine_chunk::{BlockState, ChunkSection};
+
+    use super::*;
+    use crate::chunk::save_chunk;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("brine_server_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn chunk(chunk_x: i32, chunk_z: i32, block: u32) -> Chunk {
+        let mut section = ChunkSection::empty(4);
+        section.block_states.0[0] = BlockState(block);
+        section.block_count = 1;
+
+        let mut chunk = Chunk::empty(chunk_x, chunk_z);
+        chunk.sections = vec![section];
+        chunk
+    }
+
+    #[test]
+    fn manifest_chooses_files_order_and_coordinates() {
+        let dir = temp_dir("manifest");
+        save_chunk(&chunk(0, 0, 1), dir.join("a")).unwrap();
+        save_chunk(&chunk(0, 0, 2), dir.join("b")).unwrap();
+        // Not in the manifest, so never sent.
+        save_chunk(&chunk(0, 0, 3), dir.join("c")).unwrap();
+
+        let manifest_path = dir.join("manifest.json");
+        fs::write(
+            &manifest_path,
+            r#"{
+                "chunks": [
+                    { "file": "b.chunk", "chunk_x": 5, "chunk_z": -3 },
+                    { "file": "a.chunk", "chunk_x": 0, "chunk_z": 7 },
+                    { "file": "b.chunk", "chunk_x": 1, "chunk_z": 1 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .init_resource::<Time>()
+            .add_event::<ChunkData>()
+            .add_event::<bevy::app::AppExit>()
+            .add_plugin(
+                ServeChunksFromDirectoryPlugin::new(dir.clone()).with_manifest(manifest_path),
+            );
+
+        let mut reader = ManualEventReader::<ChunkData>::default();
+        let mut sent = Vec::new();
+        for _ in 0..100 {
+            app.update();
+
+            let events = app.world.get_resource::<Events<ChunkData>>().unwrap();
+            sent.extend(reader.iter(events).map(|event| {
+                let chunk = &event.chunk_data;
+                (
+                    chunk.chunk_x,
+                    chunk.chunk_z,
+                    chunk.sections[0].block_states.0[0],
+                )
+            }));
+
+            if sent.len() == 3 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(
+            sent,
+            vec![
+                (5, -3, BlockState(2)),
+                (0, 7, BlockState(1)),
+                (1, 1, BlockState(2)),
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
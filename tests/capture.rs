@@ -0,0 +1,173 @@
+//! Captures the chunks sent by a scripted server, serves the capture back, and
+//! checks that the served chunks are the ones that were captured.
+
+#[path = "../crates/brine_proto_backend/tests/common/mod.rs"]
+mod common;
+
+use std::{
+    fs,
+    io::Write,
+    net::TcpListener,
+    path::PathBuf,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    app::AppExit,
+    ecs::event::{Events, ManualEventReader},
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+
+use brine::{
+    capture::{CaptureIndex, ChunkCapturePlugin, CAPTURE_INDEX_FILE},
+    chunk::load_chunk,
+    login::LoginPlugin,
+    server::ServeChunksFromDirectoryPlugin,
+};
+use brine_chunk::{BlockState, Chunk, ChunkSection};
+use brine_proto::{event::clientbound::ChunkData, ProtocolPlugin};
+use brine_proto_backend::ProtocolBackendPlugin;
+
+use common::{accept_login, chunk_data_body, serve_status, write_packet};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A full chunk with a different block in each of its two sections.
+fn chunk(chunk_x: i32, chunk_z: i32) -> Chunk {
+    let mut chunk = Chunk::empty(chunk_x, chunk_z);
+    for (chunk_y, block) in [(0, 1), (3, 9)] {
+        let mut section = ChunkSection::empty(chunk_y);
+        section.block_states.0.fill(BlockState(block));
+        section.block_count = section.block_states.0.len() as u16;
+        chunk.sections.push(section);
+    }
+    chunk
+}
+
+/// Plays the part of a 1.14.4 server that sends some chunks after login, then
+/// holds the connection open until told to hang up.
+fn scripted_server(listener: TcpListener, chunks: Vec<Chunk>, hang_up: mpsc::Receiver<()>) {
+    serve_status(&listener);
+
+    let mut login = accept_login(&listener);
+    write_packet(
+        &mut login,
+        0x02,
+        include_bytes!("../crates/brine_proto_backend/test/packet-data/login/login_success.dat"),
+    );
+    for chunk in chunks.iter() {
+        write_packet(&mut login, 0x21, &chunk_data_body(chunk));
+    }
+    login.flush().unwrap();
+
+    let _ = hang_up.recv();
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("brine_capture_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Sorts chunks by their coordinates, since neither the server nor the capture
+/// directory preserves their order.
+fn sorted(mut chunks: Vec<Chunk>) -> Vec<Chunk> {
+    chunks.sort_by_key(|chunk| (chunk.chunk_x, chunk.chunk_z));
+    chunks
+}
+
+/// Updates the app until `count` ChunkData events have been sent, and returns
+/// their chunks.
+fn receive_chunks(app: &mut App, count: usize) -> Vec<Chunk> {
+    let mut reader = ManualEventReader::<ChunkData>::default();
+    let mut received = Vec::new();
+
+    let start = Instant::now();
+    while received.len() < count {
+        assert!(start.elapsed() < TIMEOUT, "timed out waiting for chunks");
+
+        app.update();
+
+        let events = app.world.get_resource::<Events<ChunkData>>().unwrap();
+        received.extend(reader.iter(events).map(|event| event.chunk_data.clone()));
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    sorted(received)
+}
+
+#[test]
+fn served_capture_matches_captured_chunks() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let chunks = vec![chunk(3, -2), chunk(-1, 0)];
+    let (hang_up, hung_up) = mpsc::channel();
+    let server_thread = {
+        let chunks = chunks.clone();
+        thread::spawn(move || scripted_server(listener, chunks, hung_up))
+    };
+
+    let dir = temp_dir("served_capture");
+
+    let mut capture_app = App::new();
+    capture_app
+        .add_plugins(MinimalPlugins)
+        .add_plugin(ProtocolPlugin)
+        .add_plugin(ProtocolBackendPlugin)
+        .add_plugin(LoginPlugin::new(
+            format!("127.0.0.1:{}", port),
+            String::from("Herobrine"),
+        ))
+        .add_plugin(ChunkCapturePlugin::new(dir.clone()));
+
+    let captured = receive_chunks(&mut capture_app, chunks.len());
+
+    // Chunks are saved in the background, so wait for every file to be
+    // readable.
+    let start = Instant::now();
+    while !captured.iter().all(|chunk| {
+        let path = dir.join(format!("chunk_{}_{}.chunk", chunk.chunk_x, chunk.chunk_z));
+        load_chunk(path).is_ok()
+    }) {
+        assert!(start.elapsed() < TIMEOUT, "timed out waiting for capture");
+
+        capture_app.update();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    hang_up.send(()).unwrap();
+    drop(capture_app);
+    server_thread.join().unwrap();
+
+    let index_file = fs::File::open(dir.join(CAPTURE_INDEX_FILE)).unwrap();
+    let index: CaptureIndex = serde_json::from_reader(index_file).unwrap();
+    assert_eq!(index.version, "1.14.4");
+    assert_eq!(index.protocol_version, 498);
+
+    let mut serve_app = App::new();
+    serve_app
+        .insert_resource(IoTaskPool(TaskPool::new()))
+        .init_resource::<Time>()
+        .add_event::<ChunkData>()
+        .add_event::<AppExit>()
+        .add_plugin(ServeChunksFromDirectoryPlugin::new(dir.clone()));
+
+    let served = receive_chunks(&mut serve_app, chunks.len());
+
+    assert_eq!(served, captured);
+    for (served, sent) in served.iter().zip(sorted(chunks).iter()) {
+        assert_eq!(
+            (served.chunk_x, served.chunk_z),
+            (sent.chunk_x, sent.chunk_z)
+        );
+        assert_eq!(served.get_block_at_local(0, 0, 0), Some(BlockState(1)));
+        assert_eq!(served.get_block_at_local(0, 48, 0), Some(BlockState(9)));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
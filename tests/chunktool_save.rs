@@ -0,0 +1,85 @@
+//! Runs `chunktool save` against a scripted server and checks that the chunk
+//! it's sent ends up in a chunk file.
+
+#[path = "../crates/brine_proto_backend/tests/common/mod.rs"]
+mod common;
+
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    process::Command,
+    thread,
+};
+
+use brine::chunk::load_chunk;
+use brine_chunk::{BlockState, Chunk, ChunkSection};
+
+use common::{accept_login, chunk_data_body, serve_status, write_packet};
+
+/// A full chunk whose bottom section is solid stone.
+fn stone_chunk() -> Chunk {
+    let mut section = ChunkSection::empty(0);
+    section.block_states.0.fill(BlockState(1));
+    section.block_count = section.block_states.0.len() as u16;
+
+    let mut chunk = Chunk::empty(3, -2);
+    chunk.sections.push(section);
+    chunk
+}
+
+/// Plays the part of a 1.14.4 server that sends a single chunk after login,
+/// then waits for the client to hang up.
+fn scripted_server(listener: TcpListener, chunk: Chunk) {
+    serve_status(&listener);
+
+    let mut login = accept_login(&listener);
+    write_packet(
+        &mut login,
+        0x02,
+        include_bytes!("../crates/brine_proto_backend/test/packet-data/login/login_success.dat"),
+    );
+    write_packet(&mut login, 0x21, &chunk_data_body(&chunk));
+    login.flush().unwrap();
+
+    let _ = login.read_to_end(&mut Vec::new());
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "brine_chunktool_save_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn saves_chunks_sent_by_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let chunk = stone_chunk();
+    let server_thread = thread::spawn(move || scripted_server(listener, chunk));
+
+    let output = temp_dir("live");
+    let status = Command::new(env!("CARGO_BIN_EXE_chunktool"))
+        .arg("save")
+        .arg("--output")
+        .arg(&output)
+        .args(["--server", "127.0.0.1", "--port", &port.to_string()])
+        .args(["--limit", "1", "--timeout", "30"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    server_thread.join().unwrap();
+
+    let saved = load_chunk(output.join("chunk_3_-2.chunk")).unwrap();
+    assert_eq!((saved.chunk_x, saved.chunk_z), (3, -2));
+    assert!(saved.is_full());
+    assert_eq!(saved.get_block_at_local(15, 15, 15), Some(BlockState(1)));
+    assert_eq!(saved.get_block_at_local(0, 16, 0), Some(BlockState::AIR));
+
+    std::fs::remove_dir_all(&output).unwrap();
+}